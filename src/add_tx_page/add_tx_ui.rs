@@ -6,12 +6,31 @@ use ratatui::backend::Backend;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::Paragraph;
+use ratatui::widgets::{Block, Paragraph};
 use ratatui::Frame;
+use rusqlite::Connection;
+
+/// Creates the block of an input field. Fields that failed the last submit get a red border
+/// and fields changed while editing get a blue border
+#[cfg(not(tarpaulin_include))]
+fn field_block<'a>(title: &'a str, field: TxTab, add_tx_data: &TxData) -> Block<'a> {
+    if add_tx_data.is_invalid_field(&field) {
+        styled_block(title).border_style(Style::default().fg(RED))
+    } else if add_tx_data.get_original_value(&field).is_some() {
+        styled_block(title).border_style(Style::default().fg(BLUE))
+    } else {
+        styled_block(title)
+    }
+}
 
 /// The function draws the Add Transaction page of the interface.
 #[cfg(not(tarpaulin_include))]
-pub fn add_tx_ui<B: Backend>(f: &mut Frame<B>, add_tx_data: &TxData, add_tx_tab: &TxTab) {
+pub fn add_tx_ui<B: Backend>(
+    f: &mut Frame<B>,
+    add_tx_data: &TxData,
+    add_tx_tab: &TxTab,
+    conn: &Connection,
+) {
     // get the data to insert into the Status widget of this page
 
     let status_data = add_tx_data.get_tx_status();
@@ -81,6 +100,25 @@ pub fn add_tx_ui<B: Backend>(f: &mut Frame<B>, add_tx_data: &TxData, add_tx_tab:
         }
     };
 
+    // while editing, changed fields show the original value in the title
+    let field_title = |title: &str, field: TxTab| match add_tx_data.get_original_value(&field) {
+        Some(old) if old.is_empty() => format!("{title} (was empty)"),
+        Some(old) => format!("{title} (was {old})"),
+        None => title.to_string(),
+    };
+
+    let date_title = field_title("Date", TxTab::Date);
+    let details_title = field_title("Details", TxTab::Details);
+    let from_method_title = field_title(from_method_name, TxTab::FromMethod);
+    let to_method_title = field_title("To Method", TxTab::ToMethod);
+    // a calculation shows its result in the title while it is being typed
+    let amount_title = match add_tx_data.get_amount_preview(conn) {
+        Some(preview) => format!("{} {preview}", field_title("Amount", TxTab::Amount)),
+        None => field_title("Amount", TxTab::Amount),
+    };
+    let tx_type_title = field_title("TX Type", TxTab::TxType);
+    let tags_title = field_title("Tags", TxTab::Tags);
+
     // creates border around the entire terminal
     f.render_widget(main_block(), size);
 
@@ -159,37 +197,41 @@ pub fn add_tx_ui<B: Backend>(f: &mut Frame<B>, add_tx_data: &TxData, add_tx_tab:
 
     let date_sec = Paragraph::new(date_text)
         .style(Style::default().bg(BACKGROUND).fg(TEXT))
-        .block(styled_block("Date"))
+        .block(field_block(&date_title, TxTab::Date, add_tx_data))
         .alignment(Alignment::Left);
 
     let from_method_sec = Paragraph::new(from_method_text)
         .style(Style::default().bg(BACKGROUND).fg(TEXT))
-        .block(styled_block(from_method_name))
+        .block(field_block(
+            &from_method_title,
+            TxTab::FromMethod,
+            add_tx_data,
+        ))
         .alignment(Alignment::Left);
 
     let to_method_sec = Paragraph::new(to_method_text)
         .style(Style::default().bg(BACKGROUND).fg(TEXT))
-        .block(styled_block("To Method"))
+        .block(field_block(&to_method_title, TxTab::ToMethod, add_tx_data))
         .alignment(Alignment::Left);
 
     let amount_sec = Paragraph::new(amount_text)
         .style(Style::default().bg(BACKGROUND).fg(TEXT))
-        .block(styled_block("Amount"))
+        .block(field_block(&amount_title, TxTab::Amount, add_tx_data))
         .alignment(Alignment::Left);
 
     let tx_type_sec = Paragraph::new(tx_type_text)
         .style(Style::default().bg(BACKGROUND).fg(TEXT))
-        .block(styled_block("TX Type"))
+        .block(field_block(&tx_type_title, TxTab::TxType, add_tx_data))
         .alignment(Alignment::Left);
 
     let details_sec = Paragraph::new(details_text)
         .style(Style::default().bg(BACKGROUND).fg(TEXT))
-        .block(styled_block("Details"))
+        .block(field_block(&details_title, TxTab::Details, add_tx_data))
         .alignment(Alignment::Left);
 
     let tags_sec = Paragraph::new(tags_text)
         .style(Style::default().bg(BACKGROUND).fg(TEXT))
-        .block(styled_block("Tags"))
+        .block(field_block(&tags_title, TxTab::Tags, add_tx_data))
         .alignment(Alignment::Left);
 
     // We will be adding a cursor based on which tab is selected + the selected index.
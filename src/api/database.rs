@@ -0,0 +1,252 @@
+use crate::db::insert_refund_link;
+use crate::outputs::ApiError;
+use crate::page_handler::NameCache;
+use crate::tx_handler::{insert_tx, TxData};
+use crate::utility::{check_table_exists, get_all_tags, get_all_tx_methods, Money};
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// The id_num of a transaction in the database
+pub type TxId = i32;
+
+/// A transaction to add to the database. Dates are in YYYY-MM-DD format and
+/// to_method is only used for Transfer transactions. refund_of is the id of the expense a
+/// Refund transaction refunds. external_id is the id the transaction has in the program it
+/// was imported from, a transaction with an external id that is already saved is not added again.
+/// New fields will always have a default value so build it with `..Default::default()`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NewTx {
+    pub date: String,
+    pub details: String,
+    pub from_method: String,
+    pub to_method: String,
+    pub amount: String,
+    pub tx_type: String,
+    pub tags: String,
+    pub refund_of: Option<TxId>,
+    pub external_id: Option<String>,
+}
+
+/// A transaction saved in the database. Transfers have the tx method in `From to To` format
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Tx {
+    pub id: TxId,
+    pub date: NaiveDate,
+    pub details: String,
+    pub tx_method: String,
+    pub amount: String,
+    pub tx_type: String,
+    pub tags: Vec<String>,
+}
+
+/// Income and expense of a single tag
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct TagTotal {
+    pub tag: String,
+    pub income: f64,
+    pub expense: f64,
+}
+
+/// Totals of the transactions within a date range. Transfers are not counted as income or expense
+/// and refunds reduce the expense
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Summary {
+    pub total_income: f64,
+    pub total_expense: f64,
+    pub net: f64,
+    pub tx_count: usize,
+    pub tags: Vec<TagTotal>,
+}
+
+/// An open Rex database
+pub struct Database {
+    conn: Connection,
+}
+
+impl Database {
+    /// Opens an existing Rex database. The database must have been created by Rex
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ApiError> {
+        let conn = Connection::open(path)?;
+
+        if !check_table_exists("tx_all", &conn) {
+            return Err(ApiError::NotRexDatabase);
+        }
+
+        conn.execute("PRAGMA foreign_keys = ON", [])?;
+        Ok(Database { conn })
+    }
+
+    /// Validates and adds a new transaction. Returns the id of the saved transaction
+    pub fn add_transaction(&mut self, new_tx: NewTx) -> Result<TxId, ApiError> {
+        add_transaction(&new_tx, &mut self.conn)
+    }
+
+    /// Returns all transactions within the date range, ordered by date
+    pub fn transactions_in(&self, range: RangeInclusive<NaiveDate>) -> Result<Vec<Tx>, ApiError> {
+        transactions_in(range, &self.conn)
+    }
+
+    /// Returns the income and expense totals of the transactions within the date range
+    pub fn summary_for(&self, range: RangeInclusive<NaiveDate>) -> Result<Summary, ApiError> {
+        let txs = self.transactions_in(range)?;
+
+        let mut total_income = Money::ZERO;
+        let mut total_expense = Money::ZERO;
+        let mut tag_totals: HashMap<String, (Money, Money)> = HashMap::new();
+
+        for tx in &txs {
+            let amount = Money::parse(&tx.amount).unwrap_or_default();
+
+            match tx.tx_type.as_str() {
+                "Income" => {
+                    total_income += amount;
+                    for tag in &tx.tags {
+                        tag_totals.entry(tag.to_string()).or_default().0 += amount;
+                    }
+                }
+                "Expense" => {
+                    total_expense += amount;
+                    for tag in &tx.tags {
+                        tag_totals.entry(tag.to_string()).or_default().1 += amount;
+                    }
+                }
+                "Refund" => {
+                    total_expense -= amount;
+                    for tag in &tx.tags {
+                        tag_totals.entry(tag.to_string()).or_default().1 -= amount;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut tags = tag_totals
+            .into_iter()
+            .map(|(tag, (income, expense))| TagTotal {
+                tag,
+                income: income.to_f64(),
+                expense: expense.to_f64(),
+            })
+            .collect::<Vec<TagTotal>>();
+        tags.sort_by(|a, b| a.tag.cmp(&b.tag));
+
+        Ok(Summary {
+            total_income: total_income.to_f64(),
+            total_expense: total_expense.to_f64(),
+            net: (total_income - total_expense).to_f64(),
+            tx_count: txs.len(),
+            tags,
+        })
+    }
+
+    /// Returns all tx methods in the order they are shown in the app
+    pub fn methods(&self) -> Vec<String> {
+        get_all_tx_methods(&self.conn)
+    }
+
+    /// Returns all tags used by the transactions, sorted
+    pub fn tags(&self) -> Vec<String> {
+        get_all_tags(&self.conn)
+    }
+}
+
+/// Validates the transaction with the same checks as the Add Transaction page and adds it
+/// to the database. This is the path the app uses for adding new transactions as well
+pub(crate) fn add_transaction(new_tx: &NewTx, conn: &mut Connection) -> Result<TxId, ApiError> {
+    let mut tx_data = TxData::new_with_data(
+        &new_tx.date,
+        &new_tx.details,
+        &new_tx.from_method,
+        &new_tx.to_method,
+        &new_tx.amount,
+        &new_tx.tx_type,
+        &new_tx.tags,
+    );
+
+    tx_data.set_refund_of(new_tx.refund_of);
+
+    if let Some(external_id) = &new_tx.external_id {
+        let saved = conn
+            .query_row(
+                "SELECT id_num FROM external_ids WHERE external_id = ?",
+                [external_id],
+                |row| row.get::<_, i32>(0),
+            )
+            .optional()?;
+
+        if saved.is_some() {
+            return Err(ApiError::AlreadyImported(external_id.to_string()));
+        }
+    }
+
+    let errors = tx_data.check_all_fields(&NameCache::new(), conn);
+
+    if !errors.is_empty() {
+        return Err(ApiError::InvalidTx(errors));
+    }
+
+    let tx_method = tx_data.get_tx_method();
+    let date = tx_data.get_iso_date(conn);
+    let data = tx_data.get_all_texts();
+
+    // the transaction and its refund link are added together so the link can not end up
+    // on a transaction another process added in the meantime
+    let sp = conn.savepoint()?;
+    let id = insert_tx(
+        &date, data[1], &tx_method, data[4], data[5], data[6], None, &sp,
+    )?;
+
+    if data[5] == "Refund" {
+        insert_refund_link(id, tx_data.get_refund_of(), &sp)?;
+    }
+
+    if let Some(external_id) = &new_tx.external_id {
+        sp.execute(
+            "INSERT INTO external_ids (external_id, id_num) VALUES (?, ?)",
+            (external_id, id),
+        )?;
+    }
+    sp.commit()?;
+
+    Ok(id)
+}
+
+fn transactions_in(
+    range: RangeInclusive<NaiveDate>,
+    conn: &Connection,
+) -> Result<Vec<Tx>, ApiError> {
+    let mut statement = conn.prepare(
+        "SELECT id_num, date, details, tx_method, amount, tx_type, tags FROM tx_all
+        WHERE date BETWEEN ? AND ? ORDER BY date, id_num",
+    )?;
+
+    let start = range.start().format("%Y-%m-%d").to_string();
+    let end = range.end().format("%Y-%m-%d").to_string();
+
+    let rows = statement.query_map([start, end], |row| {
+        let date: String = row.get(1)?;
+        let tags: String = row.get(6)?;
+
+        Ok(Tx {
+            id: row.get(0)?,
+            date: NaiveDate::parse_from_str(&date, "%Y-%m-%d").unwrap_or_default(),
+            details: row.get(2)?,
+            tx_method: row.get(3)?,
+            amount: row.get(4)?,
+            tx_type: row.get(5)?,
+            tags: tags.split(',').map(|tag| tag.trim().to_string()).collect(),
+        })
+    })?;
+
+    let mut txs = Vec::new();
+    for tx in rows {
+        txs.push(tx?);
+    }
+    Ok(txs)
+}
@@ -0,0 +1,32 @@
+//! A small, stable API for reading and writing a Rex database from other programs.
+//!
+//! Every transaction added through this API goes through the same checks as the
+//! Add Transaction page so the balances and changes of the database stay correct.
+//! The types here follow semver: fields are only added in minor versions and the
+//! output types are `#[non_exhaustive]` so that adding a field is not a breaking change.
+//!
+//! ```no_run
+//! use rex_tui::api::{Database, NewTx};
+//! use chrono::NaiveDate;
+//!
+//! let mut db = Database::open("data.sqlite").unwrap();
+//! let id = db
+//!     .add_transaction(NewTx {
+//!         date: "2023-05-12".to_string(),
+//!         details: "Groceries".to_string(),
+//!         from_method: "Cash".to_string(),
+//!         amount: "12.50".to_string(),
+//!         tx_type: "Expense".to_string(),
+//!         tags: "Food".to_string(),
+//!         ..Default::default()
+//!     })
+//!     .unwrap();
+//!
+//! let start = NaiveDate::from_ymd_opt(2023, 5, 1).unwrap();
+//! let end = NaiveDate::from_ymd_opt(2023, 5, 31).unwrap();
+//! let summary = db.summary_for(start..=end).unwrap();
+//! ```
+
+mod database;
+
+pub use database::*;
@@ -1,9 +1,10 @@
-use crate::db::{MONTHS, YEARS};
+use crate::db::{get_date_range, MONTHS};
 use crate::page_handler::IndexedData;
-use crate::utility::get_all_txs;
+use crate::utility::{get_all_tx_methods, get_all_txs, get_tag_groups, Money, WeekRange};
 use chrono::naive::NaiveDate;
+use chrono::{Datelike, Weekday};
 use rusqlite::Connection;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Stores every transaction in the database and along with
 /// all balance amount after each transaction was committed
@@ -11,6 +12,8 @@ use std::collections::HashMap;
 pub struct ChartData {
     all_txs: HashMap<i32, Vec<Vec<String>>>,
     all_balance: HashMap<i32, Vec<Vec<String>>>,
+    total_years: usize,
+    start_year: i32,
 }
 
 impl ChartData {
@@ -18,7 +21,10 @@ impl ChartData {
     pub fn new(conn: &Connection) -> Self {
         let mut all_txs = HashMap::new();
         let mut all_balance = HashMap::new();
-        for x in 0..YEARS.len() {
+        let date_range = get_date_range(conn);
+        let total_years = date_range.total_years();
+
+        for x in 0..total_years {
             for i in 0..MONTHS.len() {
                 let target_id = i as i32 + (x as i32 * 12);
                 let (t, b, _) = get_all_txs(conn, i, x);
@@ -30,6 +36,8 @@ impl ChartData {
         ChartData {
             all_txs,
             all_balance,
+            total_years,
+            start_year: date_range.start_year,
         }
     }
 
@@ -56,7 +64,7 @@ impl ChartData {
             }
             // 2 = all time mode. Select every single data
             2 => {
-                for x in 0..YEARS.len() {
+                for x in 0..self.total_years {
                     for i in 0..MONTHS.len() {
                         let target_id = i as i32 + (x as i32 * 12);
                         for i in &self.all_txs[&target_id] {
@@ -105,7 +113,7 @@ impl ChartData {
             }
             // 2 = all time mode. Select every single data
             2 => {
-                for x in 0..YEARS.len() {
+                for x in 0..self.total_years {
                     for i in 0..MONTHS.len() {
                         let target_id = i as i32 + (x as i32 * 12);
                         for i in &self.all_txs[&target_id] {
@@ -122,4 +130,332 @@ impl ChartData {
         }
         (to_return_tx, to_return_balance)
     }
+
+    /// Returns the transactions and the balances after each of them between the two dates.
+    /// Both dates are inclusive and dates outside of the date range are ignored
+    pub fn get_range_data(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> (Vec<&Vec<String>>, Vec<&Vec<String>>) {
+        let mut to_return_tx = vec![];
+        let mut to_return_balance = vec![];
+
+        for target_id in self.get_range_ids(start_date, end_date) {
+            let (txs, balances) = match (
+                self.all_txs.get(&target_id),
+                self.all_balance.get(&target_id),
+            ) {
+                (Some(txs), Some(balances)) => (txs, balances),
+                _ => continue,
+            };
+
+            for (tx, balance) in txs.iter().zip(balances) {
+                let date = NaiveDate::parse_from_str(&tx[0], "%d-%m-%Y").unwrap();
+                if date >= start_date && date <= end_date {
+                    to_return_tx.push(tx);
+                    to_return_balance.push(balance);
+                }
+            }
+        }
+
+        (to_return_tx, to_return_balance)
+    }
+
+    /// Returns all dates of the transactions between the two dates. Both dates are inclusive
+    pub fn get_range_dates(&self, start_date: NaiveDate, end_date: NaiveDate) -> Vec<NaiveDate> {
+        self.get_range_data(start_date, end_date)
+            .0
+            .iter()
+            .map(|tx| NaiveDate::parse_from_str(&tx[0], "%d-%m-%Y").unwrap())
+            .collect()
+    }
+
+    /// Returns the ids of every month that the two dates cover in order
+    fn get_range_ids(&self, start_date: NaiveDate, end_date: NaiveDate) -> Vec<i32> {
+        let month_id =
+            |date: NaiveDate| (date.year() - self.start_year) * 12 + date.month0() as i32;
+
+        (month_id(start_date)..=month_id(end_date)).collect()
+    }
+}
+
+/// Returns how a date is shown on the x axis of the balance chart. Charts that cover more than
+/// 3 months show the month and the year, shorter ones show the full date
+pub fn format_axis_date(date: NaiveDate, total_days: i64) -> String {
+    if total_days > 92 {
+        date.format("%b %Y").to_string()
+    } else {
+        date.to_string()
+    }
+}
+
+/// Sums the visible tx method lines point by point into the Total line of the balance chart.
+/// Every method line has a point at the same x positions, so same date transactions that
+/// were merged into one point stay merged in the total. Also returns the lowest and the
+/// highest point of the total so the y axis can fit it
+///
+/// return example: `([(0.0, 150.0), (1.0, 90.0)], 90.0, 150.0)`
+pub fn get_total_points(
+    datasets: &[Vec<(f64, f64)>],
+    tx_methods: &[String],
+    hidden_methods: &HashSet<String>,
+) -> (Vec<(f64, f64)>, f64, f64) {
+    let mut total_points: Vec<(f64, f64)> = match datasets.first() {
+        Some(points) => points.iter().map(|(x, _)| (*x, 0.0)).collect(),
+        None => Vec::new(),
+    };
+
+    for (method, points) in tx_methods.iter().zip(datasets) {
+        if hidden_methods.contains(method) {
+            continue;
+        }
+        for (total, (_, balance)) in total_points.iter_mut().zip(points) {
+            total.1 += balance;
+        }
+    }
+
+    let mut lowest = 0.0;
+    let mut highest = 0.0;
+
+    for (_, total) in &total_points {
+        if *total > highest {
+            highest = *total
+        } else if *total < lowest {
+            lowest = *total
+        }
+    }
+
+    (total_points, lowest, highest)
+}
+
+/// Returns the total income and expense of every month of the given year.
+/// Months without any transactions return 0 for both. Transfers are not counted
+///
+/// return example: `[("January", 500.0, 120.5), ("February", 0.0, 0.0),]`
+pub fn get_monthly_income_expense(year: i32, conn: &Connection) -> Vec<(String, f64, f64)> {
+    get_monthly_totals(year, None, conn)
+}
+
+/// Returns the expense of every month of the year as chart points where x is the index of the month.
+/// The points stop at the last month that has any income or expense
+///
+/// return example: `[(0.0, 120.5), (1.0, 0.0), (2.0, 40.0)]`
+pub fn get_monthly_expense_points(year: i32, conn: &Connection) -> Vec<(f64, f64)> {
+    let monthly_data = get_monthly_income_expense(year, conn);
+
+    let last_month = monthly_data
+        .iter()
+        .rposition(|(_, income, expense)| *income != 0.0 || *expense != 0.0);
+
+    match last_month {
+        Some(last_month) => monthly_data[..=last_month]
+            .iter()
+            .enumerate()
+            .map(|(index, (_, _, expense))| (index as f64, *expense))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Same as `get_monthly_income_expense` but only the transactions that have the given tag are counted
+pub fn get_monthly_tag_income_expense(
+    year: i32,
+    tag: &str,
+    conn: &Connection,
+) -> Vec<(String, f64, f64)> {
+    get_monthly_totals(year, Some(tag), conn)
+}
+
+/// Sums the income and expense of every month of the year, optionally only of a single tag
+fn get_monthly_totals(year: i32, tag: Option<&str>, conn: &Connection) -> Vec<(String, f64, f64)> {
+    let mut query = conn
+        .prepare(
+            "SELECT CAST(strftime('%m', date) AS INTEGER), amount, tx_type, tags FROM tx_all
+            WHERE date BETWEEN ? AND ?",
+        )
+        .expect("could not prepare statement");
+
+    let mut totals = vec![(Money::ZERO, Money::ZERO); MONTHS.len()];
+
+    if let Ok(rows) = query.query_map([format!("{year}-01-01"), format!("{year}-12-31")], |row| {
+        Ok((
+            row.get::<_, usize>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+        ))
+    }) {
+        for (month, amount, tx_type, tags) in rows.flatten() {
+            add_to_totals(&mut totals[month - 1], &amount, &tx_type, &tags, tag);
+        }
+    }
+
+    MONTHS
+        .iter()
+        .zip(totals)
+        .map(|(month, (income, expense))| (month.to_string(), income.to_f64(), expense.to_f64()))
+        .collect()
+}
+
+/// Returns the total income and expense of every week that has a day in the month of the year,
+/// optionally only of a single tag. The weeks are counted whole even if they reach into
+/// another month. `month` is the 0 based month index
+///
+/// return example: `[("May 27 – Jun 2", 500.0, 120.5), ("Jun 3 – Jun 9", 0.0, 0.0),]`
+pub fn get_weekly_income_expense(
+    month: usize,
+    year: i32,
+    week_start: Weekday,
+    tag: Option<&str>,
+    conn: &Connection,
+) -> Vec<(String, f64, f64)> {
+    let weeks = WeekRange::in_month(month, year, week_start);
+
+    let mut query = conn
+        .prepare("SELECT date, amount, tx_type, tags FROM tx_all WHERE date BETWEEN ? AND ?")
+        .expect("could not prepare statement");
+
+    let mut totals = vec![(Money::ZERO, Money::ZERO); weeks.len()];
+
+    let (first_day, last_day) = match (weeks.first(), weeks.last()) {
+        (Some(first_week), Some(last_week)) => (first_week.get_start(), last_week.get_end()),
+        _ => return Vec::new(),
+    };
+
+    if let Ok(rows) = query.query_map([first_day.to_string(), last_day.to_string()], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+        ))
+    }) {
+        for (date, amount, tx_type, tags) in rows.flatten() {
+            let week_index = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| weeks.iter().position(|week| week.contains(date)));
+
+            if let Some(week_index) = week_index {
+                add_to_totals(&mut totals[week_index], &amount, &tx_type, &tags, tag);
+            }
+        }
+    }
+
+    weeks
+        .iter()
+        .zip(totals)
+        .map(|(week, (income, expense))| (week.get_label(), income.to_f64(), expense.to_f64()))
+        .collect()
+}
+
+/// Adds the amount of a transaction to the income or expense of the totals.
+/// Nothing is added if a tag is given and the transaction does not have it
+fn add_to_totals(
+    totals: &mut (Money, Money),
+    amount: &str,
+    tx_type: &str,
+    tags: &str,
+    tag: Option<&str>,
+) {
+    if let Some(tag) = tag {
+        if !tags.split(',').any(|tx_tag| tx_tag.trim() == tag) {
+            return;
+        }
+    }
+
+    let amount = Money::parse(amount).unwrap_or_default();
+    match tx_type {
+        "Income" => totals.0 += amount,
+        "Expense" => totals.1 += amount,
+        "Refund" => totals.1 -= amount,
+        _ => {}
+    }
+}
+
+/// Returns the expense of every month of the year split by tx method, or by tag group if
+/// `by_tag_group` is true. Transactions of the hidden methods are not counted.
+/// Methods are in their db order and groups are sorted by name. A transaction belongs to the group
+/// of its first tag and tags without a group are their own group. Segments without any expense
+/// in the year are not returned
+///
+/// return example: `[("Cash", [120.5, 0.0, ...12 months]), ("Bank", [0.0, 40.0, ...])]`
+pub fn get_monthly_stacked_expense(
+    year: i32,
+    by_tag_group: bool,
+    hidden_methods: &HashSet<String>,
+    conn: &Connection,
+) -> Vec<(String, Vec<f64>)> {
+    let mut query = conn
+        .prepare(
+            "SELECT CAST(strftime('%m', date) AS INTEGER), amount, tx_type, tx_method, tags
+            FROM tx_all WHERE date BETWEEN ? AND ?",
+        )
+        .expect("could not prepare statement");
+
+    let tag_groups = get_tag_groups(conn);
+    let mut totals: HashMap<String, Vec<Money>> = HashMap::new();
+
+    if let Ok(rows) = query.query_map([format!("{year}-01-01"), format!("{year}-12-31")], |row| {
+        Ok((
+            row.get::<_, usize>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+        ))
+    }) {
+        for (month, amount, tx_type, tx_method, tags) in rows.flatten() {
+            if hidden_methods.contains(&tx_method) {
+                continue;
+            }
+
+            let amount = Money::parse(&amount).unwrap_or_default();
+            let amount = match tx_type.as_str() {
+                "Expense" => amount,
+                "Refund" => -amount,
+                _ => continue,
+            };
+
+            let segment = if by_tag_group {
+                let first_tag = tags.split(',').next().unwrap_or_default().trim();
+                match tag_groups.get(first_tag) {
+                    Some(group_name) => group_name.to_string(),
+                    None => first_tag.to_string(),
+                }
+            } else {
+                tx_method
+            };
+
+            totals
+                .entry(segment)
+                .or_insert_with(|| vec![Money::ZERO; MONTHS.len()])[month - 1] += amount;
+        }
+    }
+
+    let segments = if by_tag_group {
+        let mut group_names = totals.keys().cloned().collect::<Vec<String>>();
+        group_names.sort();
+        group_names
+    } else {
+        get_all_tx_methods(conn)
+    };
+
+    segments
+        .into_iter()
+        .filter_map(|segment| {
+            let monthly_expense = totals.remove(&segment)?;
+            if monthly_expense
+                .iter()
+                .all(|expense| *expense == Money::ZERO)
+            {
+                return None;
+            }
+            let monthly_expense = monthly_expense
+                .iter()
+                .map(|expense| expense.to_f64())
+                .collect();
+            Some((segment, monthly_expense))
+        })
+        .collect()
 }
@@ -1,30 +1,98 @@
-use crate::chart_page::ChartData;
-use crate::page_handler::{ChartTab, IndexedData, BACKGROUND, BOX, SELECTED};
-use crate::utility::{create_tab, get_all_tx_methods, main_block};
-use chrono::{naive::NaiveDate, Duration};
+use crate::chart_page::{
+    format_axis_date, get_monthly_expense_points, get_monthly_income_expense,
+    get_monthly_stacked_expense, get_monthly_tag_income_expense, get_total_points,
+    get_weekly_income_expense, ChartData,
+};
+use crate::db::{get_date_range, get_week_start, MONTHS};
+use crate::page_handler::{
+    ChartTab, ChartView, IndexedData, NameCache, BACKGROUND, BOX, GRAY, GREEN, HIGHLIGHTED, RED,
+    SELECTED, TEXT,
+};
+use crate::summary_page::{SummaryRangeData, RANGE_MODE};
+use crate::utility::{create_tab, main_block, styled_block, AmountFormat, Clock, SystemClock};
+use chrono::{naive::NaiveDate, Datelike, Duration};
 use ratatui::backend::Backend;
-use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
-use ratatui::text::Span;
-use ratatui::widgets::{Axis, Block, Chart, Dataset, GraphType};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Axis, BarChart, Block, Chart, Dataset, GraphType, Paragraph};
 use ratatui::{symbols, Frame};
 use rusqlite::Connection;
+use std::collections::HashSet;
+
+/// The line colors of the tx methods in the order of the methods. Methods after these are cyan
+const METHOD_COLORS: [Color; 10] = [
+    Color::Magenta,
+    Color::Blue,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Black,
+    Color::Gray,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightRed,
+];
 
 /// Creates the balance chart from the transactions
 #[cfg(not(tarpaulin_include))]
+#[allow(clippy::too_many_arguments)]
 pub fn chart_ui<B: Backend>(
     f: &mut Frame<B>,
     months: &IndexedData,
     years: &IndexedData,
     mode_selection: &IndexedData,
+    tags: &IndexedData,
+    compare_years: &IndexedData,
+    chart_range: &SummaryRangeData,
     chart_data: &ChartData,
     current_page: &ChartTab,
     chart_hidden_mode: bool,
+    chart_view: ChartView,
+    hidden_methods: &HashSet<String>,
+    show_total: bool,
+    group_stack: bool,
+    weekly: bool,
     loop_remaining: &mut Option<f64>,
+    amount_format: &AmountFormat,
+    names: &NameCache,
     conn: &Connection,
 ) {
     let size = f.size();
-    let (all_txs, all_balance) = chart_data.get_data(mode_selection, months.index, years.index);
+
+    if let ChartView::YearComparison = chart_view {
+        // the comparison is not animated
+        *loop_remaining = None;
+        year_comparison_ui(
+            f,
+            years,
+            compare_years,
+            current_page,
+            chart_hidden_mode,
+            amount_format,
+            conn,
+        );
+        return;
+    }
+
+    // the Range mode plots the dates of the boxes or the current month if they can not be used
+    let mut range_error = None;
+    let selected_range = if mode_selection.index == RANGE_MODE {
+        match chart_range.get_range(conn) {
+            Ok(range) => Some(range),
+            Err(error) => {
+                range_error = Some(error);
+                Some(get_current_month_range())
+            }
+        }
+    } else {
+        None
+    };
+
+    let (all_txs, all_balance) = match selected_range {
+        Some((start_date, end_date)) => chart_data.get_range_data(start_date, end_date),
+        None => chart_data.get_data(mode_selection, months.index, years.index),
+    };
 
     // divide the terminal into various chunks to draw the interface. This is a vertical chunk
     let mut main_layout = Layout::default().direction(Direction::Vertical).margin(2);
@@ -40,6 +108,7 @@ pub fn chart_ui<B: Backend>(
                         Constraint::Length(3),
                         Constraint::Length(3),
                         Constraint::Length(3),
+                        Constraint::Length(3),
                         Constraint::Min(0),
                     ]
                     .as_ref(),
@@ -48,6 +117,7 @@ pub fn chart_ui<B: Backend>(
             1 => {
                 main_layout = main_layout.constraints(
                     [
+                        Constraint::Length(3),
                         Constraint::Length(3),
                         Constraint::Length(3),
                         Constraint::Min(0),
@@ -56,8 +126,27 @@ pub fn chart_ui<B: Backend>(
                 )
             }
             2 => {
-                main_layout =
-                    main_layout.constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+                main_layout = main_layout.constraints(
+                    [
+                        Constraint::Length(3),
+                        Constraint::Length(3),
+                        Constraint::Min(0),
+                    ]
+                    .as_ref(),
+                )
+            }
+            3 => {
+                main_layout = main_layout.constraints(
+                    [
+                        Constraint::Length(3),
+                        Constraint::Length(3),
+                        Constraint::Length(3),
+                        Constraint::Length(3),
+                        Constraint::Length(3),
+                        Constraint::Min(0),
+                    ]
+                    .as_ref(),
+                )
             }
             _ => {}
         };
@@ -68,13 +157,142 @@ pub fn chart_ui<B: Backend>(
     // creates border around the entire terminal
     f.render_widget(main_block(), size);
 
-    let mut month_tab = create_tab(months, "Months");
+    let mut month_tab = create_tab(months, "Months", chunks[2].width);
 
-    let mut year_tab = create_tab(years, "Years");
+    let mut year_tab = create_tab(years, "Years", chunks[1].width);
 
-    let mut mode_selection_tab = create_tab(mode_selection, "Modes");
+    let mut mode_selection_tab = create_tab(mode_selection, "Modes", chunks[0].width);
 
-    let all_tx_methods = get_all_tx_methods(conn);
+    let mut tag_tab = create_tab(tags, "Tags", chunks[0].width);
+
+    match current_page {
+        // previously added a black block to year and month widget if a value is not selected
+        // Now we will turn that black block into green if a value is selected
+        ChartTab::Months => {
+            month_tab = month_tab
+                .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(SELECTED));
+        }
+
+        ChartTab::Years => {
+            year_tab = year_tab
+                .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(SELECTED));
+        }
+        ChartTab::ModeSelection => {
+            mode_selection_tab = mode_selection_tab
+                .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(SELECTED));
+        }
+        ChartTab::Tags => {
+            tag_tab =
+                tag_tab.highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(SELECTED));
+        }
+        ChartTab::RangeStart | ChartTab::RangeEnd | ChartTab::CompareYears => {}
+    }
+
+    // the area of the chart itself. Hidden mode leaves nothing else on the page
+    let chart_area = if chart_hidden_mode {
+        chunks[0]
+    } else {
+        f.render_widget(mode_selection_tab, chunks[0]);
+
+        match mode_selection.index {
+            0 => {
+                f.render_widget(year_tab, chunks[1]);
+                f.render_widget(month_tab, chunks[2]);
+                f.render_widget(tag_tab, chunks[3]);
+                chunks[4]
+            }
+            1 => {
+                f.render_widget(year_tab, chunks[1]);
+                f.render_widget(tag_tab, chunks[2]);
+                chunks[3]
+            }
+            3 => {
+                f.render_widget(year_tab, chunks[1]);
+                f.render_widget(month_tab, chunks[2]);
+                range_boxes_ui(f, chunks[3], chart_range, current_page);
+                f.render_widget(tag_tab, chunks[4]);
+                chunks[5]
+            }
+            _ => {
+                f.render_widget(tag_tab, chunks[1]);
+                chunks[2]
+            }
+        }
+    };
+
+    if let ChartView::StackedExpense = chart_view {
+        // the stacked bars are not animated
+        *loop_remaining = None;
+        let year = get_date_range(conn).year_at(years.index);
+        stacked_expense_chart_ui(
+            f,
+            chart_area,
+            year,
+            hidden_methods,
+            group_stack,
+            amount_format,
+            names,
+            conn,
+        );
+        return;
+    }
+
+    // a selected tag is always shown as the income and expense bars
+    let tag = tags.get_chart_tag();
+
+    if tag.is_some() || matches!(chart_view, ChartView::IncomeExpense) {
+        // the bar chart is not animated
+        *loop_remaining = None;
+        let year = get_date_range(conn).year_at(years.index);
+
+        // the bars are labeled with the first 3 letters of the month or the first day of the week
+        let (title, bar_data) = if weekly {
+            let weekly_data =
+                get_weekly_income_expense(months.index, year, get_week_start(conn), tag, conn);
+            let title = format!("Weekly Income vs Expense - {} {year}", MONTHS[months.index]);
+            let bar_data = weekly_data
+                .into_iter()
+                .map(|(week, income, expense)| {
+                    let first_day = week.split(" – ").next().unwrap_or_default().to_string();
+                    (first_day, income, expense)
+                })
+                .collect();
+            (title, bar_data)
+        } else {
+            let monthly_data = match tag {
+                Some(tag) => get_monthly_tag_income_expense(year, tag, conn),
+                None => get_monthly_income_expense(year, conn),
+            };
+            let title = format!("{} - {year}", ChartView::IncomeExpense.title());
+            let bar_data = monthly_data
+                .into_iter()
+                .map(|(month, income, expense)| (month[..3].to_string(), income, expense))
+                .collect();
+            (title, bar_data)
+        };
+
+        let title = match tag {
+            Some(tag) => format!("{tag} - {title}"),
+            None => title,
+        };
+        income_expense_chart_ui(f, chart_area, &title, bar_data, amount_format);
+        return;
+    }
+
+    let all_tx_methods = names.get_tx_methods(conn);
+
+    // the legend takes the top line of the chart area
+    let legend_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(chart_area);
+    let (legend_area, chart_area) = (legend_chunks[0], legend_chunks[1]);
+
+    f.render_widget(
+        Paragraph::new(method_legend(&all_tx_methods, hidden_methods, show_total))
+            .style(Style::default().bg(BACKGROUND).fg(TEXT)),
+        legend_area,
+    );
 
     // a vector containing another vector with vec![X, Y] coordinate of where to render chart points
     let mut datasets: Vec<Vec<(f64, f64)>> = Vec::new();
@@ -98,7 +316,10 @@ pub fn chart_ui<B: Backend>(
     // if there are no transactions, we will create an empty chart
     if !all_txs.is_empty() {
         // contains all dates of the transactions
-        let all_dates = chart_data.get_all_dates(mode_selection, months.index, years.index);
+        let all_dates = match selected_range {
+            Some((start_date, end_date)) => chart_data.get_range_dates(start_date, end_date),
+            None => chart_data.get_all_dates(mode_selection, months.index, years.index),
+        };
 
         let mut checking_date = NaiveDate::parse_from_str(&all_txs[0][0], "%d-%m-%Y").unwrap();
 
@@ -150,8 +371,8 @@ pub fn chart_ui<B: Backend>(
         let mut to_loop = loop_remaining.as_mut().map(|val| total_loop - *val);
 
         // labels of the x axis
-        date_labels.push(checking_date.to_string());
-        date_labels.push(final_date.to_string());
+        date_labels.push(format_axis_date(checking_date, total_loop as i64));
+        date_labels.push(format_axis_date(final_date, total_loop as i64));
 
         // data_num represents which index to check out from all the txs and balances data.
         // to_add_again will become true in cases where two or more transactions shares the same date simultaneously.
@@ -175,13 +396,15 @@ pub fn chart_ui<B: Backend>(
                 last_balances = Vec::new();
 
                 for method_index in 0..all_tx_methods.len() {
-                    // keep track of the highest and the lowest point of the balance
+                    // keep track of the highest and the lowest point of the visible balances
                     let current_balance = current_balances[method_index].parse::<f64>().unwrap();
 
-                    if current_balance > highest_balance {
-                        highest_balance = current_balance
-                    } else if current_balance < lowest_balance {
-                        lowest_balance = current_balance
+                    if !hidden_methods.contains(&all_tx_methods[method_index]) {
+                        if current_balance > highest_balance {
+                            highest_balance = current_balance
+                        } else if current_balance < lowest_balance {
+                            lowest_balance = current_balance
+                        }
                     }
 
                     if to_add_again {
@@ -233,7 +456,7 @@ pub fn chart_ui<B: Backend>(
                     // break the loop if total day amount is reached
                     if val - 1.0 <= 0.0 {
                         date_labels.pop().unwrap();
-                        date_labels.push(checking_date.to_string());
+                        date_labels.push(format_axis_date(checking_date, total_loop as i64));
                         break;
                     }
                     to_loop = Some(val - 1.0);
@@ -247,6 +470,16 @@ pub fn chart_ui<B: Backend>(
     } else {
         *loop_remaining = None;
     }
+
+    // the sum of the visible balances at every point of the chart
+    let (total_dataset, lowest_total, highest_total) =
+        get_total_points(&datasets, &all_tx_methods, hidden_methods);
+
+    if show_total {
+        highest_balance = f64::max(highest_balance, highest_total);
+        lowest_balance = f64::min(lowest_balance, lowest_total);
+    }
+
     // add a 10% extra value to the highest and the lowest balance
     // so the chart can properly render
     highest_balance += highest_balance * 10.0 / 100.0;
@@ -257,50 +490,63 @@ pub fn chart_ui<B: Backend>(
     let mut to_add = lowest_balance;
 
     // go through the lowest balance and keep adding the difference until the highest point
-    let mut labels = vec![lowest_balance.to_string()];
+    let mut labels = vec![amount_format.localize_decimal(&lowest_balance.to_string())];
     // 10 labels, so loop 10 times
     for _i in 0..10 {
         to_add += diff;
-        labels.push(format!("{:.2}", to_add));
+        labels.push(amount_format.localize_decimal(&format!("{:.2}", to_add)));
     }
 
-    let mut color_list = vec![
-        Color::LightRed,
-        Color::LightBlue,
-        Color::LightYellow,
-        Color::Gray,
-        Color::Black,
-        Color::Yellow,
-        Color::Green,
-        Color::Red,
-        Color::Blue,
-        Color::Magenta,
-    ];
-
     let mut final_dataset = vec![];
 
-    // loop through the data that was added for each tx_method  and turn them into chart data
+    // loop through the data that was added for each visible tx_method and turn them into chart data
     for i in 0..all_tx_methods.len() {
-        // run out of colors = cyan default
-        if color_list.is_empty() {
-            color_list.push(Color::Cyan)
+        if hidden_methods.contains(&all_tx_methods[i]) {
+            continue;
         }
         final_dataset.push(
             Dataset::default()
                 .name(&all_tx_methods[i])
                 .marker(symbols::Marker::Braille)
                 .graph_type(GraphType::Line)
+                .style(Style::default().fg(method_color(i)).bg(BACKGROUND))
+                .data(&datasets[i]),
+        )
+    }
+
+    if show_total {
+        final_dataset.push(
+            Dataset::default()
+                .name("Total")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
                 .style(
                     Style::default()
-                        .fg(color_list.pop().unwrap())
-                        .bg(BACKGROUND),
+                        .fg(TOTAL_COLOR)
+                        .bg(BACKGROUND)
+                        .add_modifier(Modifier::BOLD),
                 )
-                .data(&datasets[i]),
+                .data(&total_dataset),
         )
     }
 
+    let chart_title = match range_error {
+        Some(error) => format!(
+            "{} - {error}. Showing the current month",
+            chart_view.title()
+        ),
+        None => chart_view.title().to_string(),
+    };
+
     let chart = Chart::new(final_dataset)
-        .block(Block::default().style(Style::default().bg(BACKGROUND).fg(BOX)))
+        .block(
+            Block::default()
+                .style(Style::default().bg(BACKGROUND).fg(BOX))
+                .title(Span::styled(
+                    chart_title,
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+        )
         .style(Style::default().bg(BACKGROUND).fg(BOX))
         .x_axis(
             Axis::default()
@@ -317,43 +563,510 @@ pub fn chart_ui<B: Backend>(
                 .labels(labels.iter().cloned().map(Span::from).collect()),
         );
 
-    match current_page {
-        // previously added a black block to year and month widget if a value is not selected
-        // Now we will turn that black block into green if a value is selected
-        ChartTab::Months => {
-            month_tab = month_tab
-                .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(SELECTED));
-        }
+    f.render_widget(chart, chart_area);
+}
 
-        ChartTab::Years => {
-            year_tab = year_tab
-                .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(SELECTED));
-        }
-        ChartTab::ModeSelection => {
-            mode_selection_tab = mode_selection_tab
-                .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(SELECTED));
+/// Draws the monthly expense of two years on the same January to December axis
+#[cfg(not(tarpaulin_include))]
+fn year_comparison_ui<B: Backend>(
+    f: &mut Frame<B>,
+    years: &IndexedData,
+    compare_years: &IndexedData,
+    current_page: &ChartTab,
+    chart_hidden_mode: bool,
+    amount_format: &AmountFormat,
+    conn: &Connection,
+) {
+    let size = f.size();
+
+    let constraints = if chart_hidden_mode {
+        vec![Constraint::Min(0)]
+    } else {
+        vec![
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(0),
+        ]
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(constraints)
+        .split(size);
+
+    f.render_widget(main_block(), size);
+
+    let chart_area = if chart_hidden_mode {
+        chunks[0]
+    } else {
+        let mut year_tab = create_tab(years, "Years", chunks[0].width);
+        let mut compare_year_tab = create_tab(compare_years, "Compare With", chunks[1].width);
+
+        match current_page {
+            ChartTab::CompareYears => {
+                compare_year_tab = compare_year_tab
+                    .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(SELECTED));
+            }
+            _ => {
+                year_tab = year_tab
+                    .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(SELECTED));
+            }
         }
+
+        f.render_widget(year_tab, chunks[0]);
+        f.render_widget(compare_year_tab, chunks[1]);
+        chunks[2]
+    };
+
+    let date_range = get_date_range(conn);
+    let year = date_range.year_at(years.index);
+    let compare_year = date_range.year_at(compare_years.index);
+
+    let year_points = get_monthly_expense_points(year, conn);
+    let compare_points = get_monthly_expense_points(compare_year, conn);
+
+    let highest_expense = year_points
+        .iter()
+        .chain(&compare_points)
+        .map(|(_, expense)| *expense)
+        .fold(0.0, f64::max);
+    // 10% extra so the highest point is not drawn on the border
+    let highest_bound = (highest_expense * 1.1).max(1.0);
+
+    let y_labels = [0.0, highest_bound / 2.0, highest_bound]
+        .iter()
+        .map(|amount| Span::from(amount_format.localize(&format!("{amount:.2}"))))
+        .collect();
+
+    let x_labels = MONTHS.iter().map(|month| Span::from(&month[..3])).collect();
+
+    let year_name = year.to_string();
+    let compare_year_name = compare_year.to_string();
+
+    // the legend takes the top line of the chart area
+    let legend_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(chart_area);
+    let (legend_area, chart_area) = (legend_chunks[0], legend_chunks[1]);
+
+    let legend = Line::from(vec![
+        Span::styled(format!("━ {year_name}"), Style::default().fg(RED)),
+        Span::raw("  "),
+        Span::styled(format!("• {compare_year_name}"), Style::default().fg(GRAY)),
+    ]);
+    f.render_widget(
+        Paragraph::new(legend).style(Style::default().bg(BACKGROUND).fg(TEXT)),
+        legend_area,
+    );
+
+    // the compared year is drawn first so the selected year stays on top of it
+    let datasets = vec![
+        Dataset::default()
+            .name(compare_year_name.as_str())
+            .marker(symbols::Marker::Dot)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(GRAY).bg(BACKGROUND))
+            .data(&compare_points),
+        Dataset::default()
+            .name(year_name.as_str())
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(RED).bg(BACKGROUND))
+            .data(&year_points),
+    ];
+
+    let chart = Chart::new(datasets)
+        .block(
+            Block::default()
+                .style(Style::default().bg(BACKGROUND).fg(BOX))
+                .title(Span::styled(
+                    format!(
+                        "{} - {year} vs {compare_year}",
+                        ChartView::YearComparison.title()
+                    ),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+        )
+        .style(Style::default().bg(BACKGROUND).fg(BOX))
+        .x_axis(
+            Axis::default()
+                .style(Style::default().bg(BACKGROUND).fg(BOX))
+                .bounds([0.0, (MONTHS.len() - 1) as f64])
+                .labels(x_labels),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().bg(BACKGROUND).fg(BOX))
+                .bounds([0.0, highest_bound])
+                .labels(y_labels),
+        );
+
+    f.render_widget(chart, chart_area);
+}
+
+/// Returns the first and the last day of the current month
+fn get_current_month_range() -> (NaiveDate, NaiveDate) {
+    let start_date = SystemClock.today().with_day(1).unwrap();
+    let end_date = (start_date + Duration::days(31))
+        .with_day(1)
+        .unwrap()
+        .pred_opt()
+        .unwrap();
+
+    (start_date, end_date)
+}
+
+/// Draws the start and end date boxes of the Range mode
+#[cfg(not(tarpaulin_include))]
+fn range_boxes_ui<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    chart_range: &SummaryRangeData,
+    current_page: &ChartTab,
+) {
+    let range_chunk = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let start_sec = Paragraph::new(chart_range.get_start_date())
+        .style(Style::default().bg(BACKGROUND).fg(TEXT))
+        .block(styled_block("Start Date"))
+        .alignment(Alignment::Left);
+
+    let end_sec = Paragraph::new(chart_range.get_end_date())
+        .style(Style::default().bg(BACKGROUND).fg(TEXT))
+        .block(styled_block("End Date"))
+        .alignment(Alignment::Left);
+
+    f.render_widget(start_sec, range_chunk[0]);
+    f.render_widget(end_sec, range_chunk[1]);
+
+    match current_page {
+        ChartTab::RangeStart => f.set_cursor(
+            range_chunk[0].x + chart_range.get_start_index() as u16 + 1,
+            range_chunk[0].y + 1,
+        ),
+        ChartTab::RangeEnd => f.set_cursor(
+            range_chunk[1].x + chart_range.get_end_index() as u16 + 1,
+            range_chunk[1].y + 1,
+        ),
+        _ => {}
     }
+}
 
-    if chart_hidden_mode {
-        f.render_widget(chart, chunks[0]);
+/// The line color of the sum of the visible tx method balances
+const TOTAL_COLOR: Color = HIGHLIGHTED;
+
+/// Returns the line color of the tx method at the given index
+fn method_color(index: usize) -> Color {
+    METHOD_COLORS.get(index).copied().unwrap_or(Color::Cyan)
+}
+
+/// Creates the line that lists every tx method with its number key in the color of its line
+/// followed by the Total line. Hidden lines are shown with an empty checkbox
+fn method_legend<'a>(
+    tx_methods: &'a [String],
+    hidden_methods: &HashSet<String>,
+    show_total: bool,
+) -> Line<'a> {
+    let mut spans = Vec::new();
+
+    for (index, method) in tx_methods.iter().enumerate() {
+        let (marker, style) = if hidden_methods.contains(method) {
+            ("[ ]", Style::default().fg(BOX).add_modifier(Modifier::DIM))
+        } else {
+            ("[x]", Style::default().fg(method_color(index)))
+        };
+
+        spans.push(Span::styled(format!("{}:{marker} ", index + 1), style));
+        spans.push(Span::styled(method.as_str(), style));
+        spans.push(Span::raw("  "));
+    }
+
+    let (marker, style) = if show_total {
+        (
+            "[x]",
+            Style::default()
+                .fg(TOTAL_COLOR)
+                .add_modifier(Modifier::BOLD),
+        )
     } else {
-        f.render_widget(mode_selection_tab, chunks[0]);
+        ("[ ]", Style::default().fg(BOX).add_modifier(Modifier::DIM))
+    };
+    spans.push(Span::styled(format!("T:{marker} Total"), style));
 
-        match mode_selection.index {
-            0 => {
-                f.render_widget(year_tab, chunks[1]);
-                f.render_widget(month_tab, chunks[2]);
-                f.render_widget(chart, chunks[3]);
+    Line::from(spans)
+}
+
+/// Draws a pair of income and expense bars for every month or week with the label under them
+#[cfg(not(tarpaulin_include))]
+fn income_expense_chart_ui<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    title: &str,
+    bar_data: Vec<(String, f64, f64)>,
+    amount_format: &AmountFormat,
+) {
+    let highest_amount = bar_data
+        .iter()
+        .map(|(_, income, expense)| income.max(*expense))
+        .fold(0.0, f64::max);
+
+    let block = Block::default()
+        .style(Style::default().bg(BACKGROUND).fg(BOX))
+        .title(Span::styled(
+            title.to_string(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    if inner_area.height < 3 {
+        return;
+    }
+
+    // the y axis shows the highest amount, the half of it and 0. The last row is for the bar labels
+    let y_labels = [highest_amount, highest_amount / 2.0, 0.0]
+        .map(|amount| amount_format.localize(&format!("{amount:.2}")));
+    let label_width = y_labels.iter().map(|label| label.len()).max().unwrap_or(0) as u16 + 1;
+
+    let chart_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(label_width), Constraint::Min(0)])
+        .split(inner_area);
+
+    let bar_rows = inner_area.height as usize - 1;
+    let mut y_axis = vec![Line::from(""); bar_rows];
+    y_axis[0] = Line::from(y_labels[0].as_str());
+    y_axis[bar_rows / 2] = Line::from(y_labels[1].as_str());
+    y_axis[bar_rows - 1] = Line::from(y_labels[2].as_str());
+
+    f.render_widget(
+        Paragraph::new(y_axis).style(Style::default().bg(BACKGROUND).fg(TEXT)),
+        chart_chunks[0],
+    );
+
+    // every month or week gets an income and an expense bar next to each other with a gap after the pair
+    let bars_area = chart_chunks[1];
+    let pairs = (bar_data.len() as u16).max(1);
+    let bar_width = ((bars_area.width.saturating_sub(pairs)) / (pairs * 2)).max(1);
+
+    let income_data = bar_data
+        .iter()
+        .map(|(label, income, _)| (label.as_str(), income.round() as u64))
+        .collect::<Vec<(&str, u64)>>();
+    let expense_data = bar_data
+        .iter()
+        .map(|(_, _, expense)| ("", expense.round() as u64))
+        .collect::<Vec<(&str, u64)>>();
+
+    // both charts share the same scale so the bars can be compared
+    let max = (highest_amount.ceil() as u64).max(1);
+
+    let income_chart = BarChart::default()
+        .data(&income_data)
+        .bar_width(bar_width)
+        .bar_gap(bar_width + 1)
+        .max(max)
+        .bar_style(Style::default().fg(GREEN).bg(BACKGROUND))
+        .value_style(Style::default().fg(BACKGROUND).bg(GREEN))
+        .label_style(Style::default().fg(TEXT).bg(BACKGROUND));
+
+    let expense_chart = BarChart::default()
+        .data(&expense_data)
+        .bar_width(bar_width)
+        .bar_gap(bar_width + 1)
+        .max(max)
+        .bar_style(Style::default().fg(RED).bg(BACKGROUND))
+        .value_style(Style::default().fg(BACKGROUND).bg(RED));
+
+    let expense_area = Rect {
+        x: bars_area.x + bar_width,
+        width: bars_area.width.saturating_sub(bar_width),
+        ..bars_area
+    };
+
+    f.render_widget(income_chart, bars_area);
+    f.render_widget(expense_chart, expense_area);
+}
+
+/// Draws the expense of every month of the year as a bar stacked by tx method or tag group
+/// with a legend of the segments above it
+#[cfg(not(tarpaulin_include))]
+#[allow(clippy::too_many_arguments)]
+fn stacked_expense_chart_ui<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    year: i32,
+    hidden_methods: &HashSet<String>,
+    group_stack: bool,
+    amount_format: &AmountFormat,
+    names: &NameCache,
+    conn: &Connection,
+) {
+    let all_tx_methods = names.get_tx_methods(conn);
+    let segments = get_monthly_stacked_expense(year, group_stack, hidden_methods, conn);
+
+    // methods keep the color and the number key of their balance line
+    let segment_colors = segments
+        .iter()
+        .enumerate()
+        .map(|(index, (segment, _))| {
+            if group_stack {
+                method_color(index)
+            } else {
+                let method_index = all_tx_methods
+                    .iter()
+                    .position(|method| method == segment)
+                    .unwrap_or(index);
+                method_color(method_index)
             }
-            1 => {
-                f.render_widget(year_tab, chunks[1]);
-                f.render_widget(chart, chunks[2]);
+        })
+        .collect::<Vec<Color>>();
+
+    let title = if group_stack {
+        "Monthly Expense by Tag Group"
+    } else {
+        ChartView::StackedExpense.title()
+    };
+
+    let block = Block::default()
+        .style(Style::default().bg(BACKGROUND).fg(BOX))
+        .title(Span::styled(
+            format!("{title} - {year}"),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    // the legend, at least 1 bar row and the month labels
+    if inner_area.height < 3 {
+        return;
+    }
+
+    let mut legend = Vec::new();
+    if group_stack {
+        for ((segment, _), color) in segments.iter().zip(&segment_colors) {
+            legend.push(Span::styled(
+                format!("■ {segment}"),
+                Style::default().fg(*color),
+            ));
+            legend.push(Span::raw("  "));
+        }
+    } else {
+        // hidden methods stay on the legend so they can be found and shown again
+        for (index, method) in all_tx_methods.iter().enumerate() {
+            if hidden_methods.contains(method) {
+                legend.push(Span::styled(
+                    format!("{}:[ ] {method}", index + 1),
+                    Style::default().fg(BOX).add_modifier(Modifier::DIM),
+                ));
+            } else if segments.iter().any(|(segment, _)| segment == method) {
+                legend.push(Span::styled(
+                    format!("{}:[x] {method}", index + 1),
+                    Style::default().fg(method_color(index)),
+                ));
+            } else {
+                continue;
             }
-            2 => {
-                f.render_widget(chart, chunks[1]);
+            legend.push(Span::raw("  "));
+        }
+    }
+
+    let monthly_totals = (0..MONTHS.len())
+        .map(|month| segments.iter().map(|(_, expense)| expense[month]).sum())
+        .collect::<Vec<f64>>();
+    let highest_amount = monthly_totals.iter().copied().fold(0.0, f64::max);
+
+    let y_labels = [highest_amount, highest_amount / 2.0, 0.0]
+        .map(|amount| amount_format.localize(&format!("{amount:.2}")));
+    let label_width = y_labels.iter().map(|label| label.len()).max().unwrap_or(0) as u16 + 1;
+
+    let layout_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner_area);
+
+    f.render_widget(
+        Paragraph::new(Line::from(legend)).style(Style::default().bg(BACKGROUND).fg(TEXT)),
+        layout_chunks[0],
+    );
+
+    let chart_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(label_width), Constraint::Min(0)])
+        .split(layout_chunks[1]);
+
+    let bar_rows = layout_chunks[1].height as usize - 1;
+    let mut y_axis = vec![Line::from(""); bar_rows];
+    y_axis[0] = Line::from(y_labels[0].as_str());
+    y_axis[bar_rows / 2] = Line::from(y_labels[1].as_str());
+    y_axis[bar_rows - 1] = Line::from(y_labels[2].as_str());
+
+    f.render_widget(
+        Paragraph::new(y_axis).style(Style::default().bg(BACKGROUND).fg(TEXT)),
+        chart_chunks[0],
+    );
+
+    // every month gets a bar with a gap after it
+    let bar_width = (chart_chunks[1].width / MONTHS.len() as u16)
+        .saturating_sub(1)
+        .max(1) as usize;
+
+    // the color of every row of every bar, from the bottom row to the top
+    let bar_cells = (0..MONTHS.len())
+        .map(|month| {
+            let mut cells = Vec::new();
+            let mut stacked = 0.0;
+            for ((_, expense), color) in segments.iter().zip(&segment_colors) {
+                stacked += expense[month];
+                let stacked_rows = stacked_row_count(stacked, highest_amount, bar_rows);
+                while cells.len() < stacked_rows {
+                    cells.push(*color);
+                }
             }
-            _ => {}
+            cells
+        })
+        .collect::<Vec<Vec<Color>>>();
+
+    let mut lines = Vec::new();
+    for row in (0..bar_rows).rev() {
+        let mut spans = Vec::new();
+        for cells in &bar_cells {
+            match cells.get(row) {
+                Some(color) => spans.push(Span::styled(
+                    "█".repeat(bar_width),
+                    Style::default().fg(*color).bg(BACKGROUND),
+                )),
+                None => spans.push(Span::raw(" ".repeat(bar_width))),
+            }
+            spans.push(Span::raw(" "));
         }
+        lines.push(Line::from(spans));
+    }
+
+    let month_labels = MONTHS
+        .iter()
+        .map(|month| {
+            let label = &month[..3.min(bar_width)];
+            Span::raw(format!("{label:<bar_width$} "))
+        })
+        .collect::<Vec<Span>>();
+    lines.push(Line::from(month_labels));
+
+    f.render_widget(
+        Paragraph::new(lines).style(Style::default().bg(BACKGROUND).fg(TEXT)),
+        chart_chunks[1],
+    );
+}
+
+/// Returns how many rows of a bar with the given rows are filled by the amount
+fn stacked_row_count(amount: f64, highest_amount: f64, bar_rows: usize) -> usize {
+    if highest_amount <= 0.0 {
+        return 0;
     }
+    ((amount / highest_amount) * bar_rows as f64).round() as usize
 }
@@ -1,5 +1,9 @@
 mod chart_data;
 mod chart_ui;
 
-pub use chart_data::ChartData;
+pub use chart_data::{
+    format_axis_date, get_monthly_expense_points, get_monthly_income_expense,
+    get_monthly_stacked_expense, get_monthly_tag_income_expense, get_total_points,
+    get_weekly_income_expense, ChartData,
+};
 pub use chart_ui::chart_ui;
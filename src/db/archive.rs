@@ -0,0 +1,207 @@
+use crate::utility::get_all_tx_methods;
+use rusqlite::{Connection, Result};
+use std::path::Path;
+
+/// The file name of the archive database that is created next to the main database
+pub const ARCHIVE_DB: &str = "rex-archive.db";
+
+/// Attaches the archive database to the connection under the name `archive`.
+/// Creates the tx_all and changes_all tables inside the archive if they don't exist and
+/// adds any tx method column that was added to the main db after the archive was created.
+pub fn attach_archive(archive_path: &str, conn: &Connection) -> Result<()> {
+    if is_archive_attached(conn) {
+        return Ok(());
+    }
+
+    conn.execute("ATTACH DATABASE ? AS archive", [archive_path])?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS archive.tx_all (
+        date TEXT,
+        details TEXT,
+        tx_method TEXT,
+        amount TEXT,
+        tx_type TEXT,
+        id_num INTEGER NOT NULL PRIMARY KEY,
+        tags TEXT
+    );",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS archive.changes_all (
+            date TEXT,
+            id_num INTEGER NOT NULL PRIMARY KEY
+        );",
+        [],
+    )?;
+
+    let archive_columns = get_archive_changes_columns(conn)?;
+
+    for method in get_all_tx_methods(conn) {
+        if !archive_columns.contains(&method) {
+            let query = format!(
+                r#"ALTER TABLE archive.changes_all ADD COLUMN "{method}" TEXT DEFAULT 0.00"#
+            );
+            conn.execute(&query, [])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Detaches the archive database from the connection if it is attached
+pub fn detach_archive(conn: &Connection) -> Result<()> {
+    if is_archive_attached(conn) {
+        conn.execute("DETACH DATABASE archive", [])?;
+    }
+    Ok(())
+}
+
+/// Checks whether the archive database is currently attached to the connection
+pub fn is_archive_attached(conn: &Connection) -> bool {
+    let mut statement = conn.prepare("PRAGMA database_list").unwrap();
+    let databases = statement
+        .query_map([], |row| row.get::<_, String>(1))
+        .unwrap()
+        .flatten()
+        .collect::<Vec<String>>();
+
+    databases.contains(&"archive".to_string())
+}
+
+/// Moves every transaction before the month of the given YYYY-MM-DD date into the archive database.
+/// Only whole months are moved so the balance_all rows of the earlier months keep working as the
+/// carried forward opening balance of all following months. Returns the amount of moved transactions.
+///
+/// The transactions are copied first, the copied amount is verified and only then
+/// deleted from the main db. Everything happens inside a single transaction.
+pub fn archive_txs(cutoff: &str, archive_path: &str, conn: &mut Connection) -> Result<usize> {
+    let cutoff = format!("{}-01", &cutoff[0..7]);
+    let keep_attached = is_archive_attached(conn);
+
+    attach_archive(archive_path, conn)?;
+
+    let result = move_txs(&cutoff, "main", "archive", conn);
+
+    if !keep_attached {
+        detach_archive(conn)?;
+    }
+
+    result
+}
+
+/// Moves every archived transaction back to the main database. Returns the amount of moved transactions.
+pub fn unarchive_txs(archive_path: &str, conn: &mut Connection) -> Result<usize> {
+    let keep_attached = is_archive_attached(conn);
+
+    attach_archive(archive_path, conn)?;
+
+    let result = move_txs("9999-12-31", "archive", "main", conn);
+
+    if !keep_attached {
+        detach_archive(conn)?;
+    }
+
+    result
+}
+
+/// Copies all transactions and their changes before the given date from one
+/// attached database to the other, verifies the copied amount and deletes them from the source
+fn move_txs(cutoff: &str, from: &str, to: &str, conn: &mut Connection) -> Result<usize> {
+    let sp = conn.savepoint()?;
+
+    // archive may not know about the latest tx methods and the main db may not have the
+    // methods that were removed. Only move the columns both of them have.
+    let archive_columns = get_archive_changes_columns(&sp)?;
+    let columns = get_all_tx_methods(&sp)
+        .into_iter()
+        .filter(|method| archive_columns.contains(method))
+        .map(|method| format!(r#""{method}""#))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let to_move: usize = sp.query_row(
+        &format!("SELECT COUNT(*) FROM {from}.tx_all WHERE date < ?"),
+        [cutoff],
+        |row| row.get(0),
+    )?;
+
+    let moved_txs = sp.execute(
+        &format!(
+            "INSERT INTO {to}.tx_all (date, details, tx_method, amount, tx_type, id_num, tags)
+            SELECT date, details, tx_method, amount, tx_type, id_num, tags FROM {from}.tx_all WHERE date < ?"
+        ),
+        [cutoff],
+    )?;
+
+    let moved_changes = sp.execute(
+        &format!(
+            "INSERT INTO {to}.changes_all (date, id_num, {columns})
+            SELECT date, id_num, {columns} FROM {from}.changes_all WHERE id_num IN
+            (SELECT id_num FROM {from}.tx_all WHERE date < ?)"
+        ),
+        [cutoff],
+    )?;
+
+    // any mismatch means something went wrong while copying. Dropping the savepoint
+    // without committing rolls back everything on both databases
+    if moved_txs != to_move || moved_changes != to_move {
+        return Err(rusqlite::Error::StatementChangedRows(moved_txs));
+    }
+
+    sp.execute(
+        &format!(
+            "DELETE FROM {from}.changes_all WHERE id_num IN
+            (SELECT id_num FROM {from}.tx_all WHERE date < ?)"
+        ),
+        [cutoff],
+    )?;
+    sp.execute(
+        &format!("DELETE FROM {from}.tx_all WHERE date < ?"),
+        [cutoff],
+    )?;
+
+    sp.commit()?;
+    Ok(moved_txs)
+}
+
+/// Returns the first and the last year of the archived transactions. None if the archive
+/// does not exist or has no transactions
+pub fn get_archive_year_bounds(archive_path: &str, conn: &Connection) -> Option<(i32, i32)> {
+    let keep_attached = is_archive_attached(conn);
+
+    if !keep_attached && !Path::new(archive_path).exists() {
+        return None;
+    }
+
+    attach_archive(archive_path, conn).ok()?;
+
+    let bounds = conn
+        .query_row(
+            "SELECT CAST(strftime('%Y', MIN(date)) AS INTEGER), CAST(strftime('%Y', MAX(date)) AS INTEGER) FROM archive.tx_all",
+            [],
+            |row| Ok((row.get::<_, Option<i32>>(0)?, row.get::<_, Option<i32>>(1)?)),
+        )
+        .ok();
+
+    if !keep_attached {
+        detach_archive(conn).ok();
+    }
+
+    match bounds {
+        Some((Some(first), Some(last))) => Some((first, last)),
+        _ => None,
+    }
+}
+
+/// Returns all the tx method columns of the archived changes_all table
+pub fn get_archive_changes_columns(conn: &Connection) -> Result<Vec<String>> {
+    let mut statement = conn.prepare("PRAGMA archive.table_info(changes_all)")?;
+    let columns = statement
+        .query_map([], |row| row.get::<_, String>(1))?
+        .flatten()
+        .filter(|column| column != "date" && column != "id_num")
+        .collect();
+    Ok(columns)
+}
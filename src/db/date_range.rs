@@ -0,0 +1,176 @@
+use crate::db::{create_balances_table, insert_zero_balances, YEARS};
+use crate::utility::get_all_tx_methods;
+use rusqlite::{Connection, Result, Savepoint};
+
+/// The years transactions can be added to. balance_all has a row for every month
+/// of these years along with the final balance row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DateRange {
+    pub start_year: i32,
+    pub end_year: i32,
+}
+
+impl Default for DateRange {
+    /// The range every database used before the year range was configurable
+    fn default() -> Self {
+        DateRange {
+            start_year: YEARS[0].parse().unwrap(),
+            end_year: YEARS[YEARS.len() - 1].parse().unwrap(),
+        }
+    }
+}
+
+impl DateRange {
+    pub fn new(start_year: i32, end_year: i32) -> Self {
+        DateRange {
+            start_year,
+            end_year,
+        }
+    }
+
+    /// Returns whether the year is inside the range
+    pub fn contains(&self, year: i32) -> bool {
+        (self.start_year..=self.end_year).contains(&year)
+    }
+
+    pub fn total_years(&self) -> usize {
+        (self.end_year - self.start_year + 1) as usize
+    }
+
+    /// Returns all years of the range.
+    /// return example: `["2022", "2023"]`
+    pub fn years(&self) -> Vec<String> {
+        (self.start_year..=self.end_year)
+            .map(|year| year.to_string())
+            .collect()
+    }
+
+    /// Returns the index of the year inside the range. Years outside the range
+    /// return the closest index
+    pub fn year_index(&self, year: i32) -> usize {
+        (year.clamp(self.start_year, self.end_year) - self.start_year) as usize
+    }
+
+    /// Returns the year at the given index of the range
+    pub fn year_at(&self, index: usize) -> i32 {
+        self.start_year + index as i32
+    }
+
+    /// Returns the range extended to include the given year
+    pub fn including(&self, year: i32) -> Self {
+        DateRange {
+            start_year: self.start_year.min(year),
+            end_year: self.end_year.max(year),
+        }
+    }
+
+    /// Returns the id_num of the balance_all row that holds the final balance after all transactions
+    pub fn final_balance_id(&self) -> i32 {
+        self.total_years() as i32 * 12 + 1
+    }
+}
+
+/// creates the date_range table of the DB
+pub fn create_date_range_table(date_range: &DateRange, sp: &Savepoint) -> Result<()> {
+    // date_range table. Contains a single row with the first and the last year of balance_all
+    sp.execute(
+        "CREATE TABLE date_range (
+            start_year INTEGER NOT NULL,
+            end_year INTEGER NOT NULL
+        );",
+        [],
+    )?;
+
+    sp.execute(
+        "INSERT INTO date_range (start_year, end_year) VALUES (?, ?)",
+        [date_range.start_year, date_range.end_year],
+    )?;
+
+    Ok(())
+}
+
+/// Adds the date_range table inside the database with the default range. Used when an old database without the table is detected
+pub fn add_date_range_table(conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    create_date_range_table(&DateRange::default(), &sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Returns the year range of the db. Falls back to the default range if it was never saved
+pub fn get_date_range(conn: &Connection) -> DateRange {
+    conn.query_row("SELECT start_year, end_year FROM date_range", [], |row| {
+        Ok(DateRange::new(row.get(0)?, row.get(1)?))
+    })
+    .unwrap_or_default()
+}
+
+/// Changes the year range of the db. balance_all is rebuilt with a row for every month of
+/// the new range and the balances of the years both ranges have are kept. Transactions
+/// outside the new range must be removed before shrinking it.
+pub fn set_date_range(date_range: &DateRange, conn: &mut Connection) -> Result<()> {
+    let old_range = get_date_range(conn);
+    let tx_methods = get_all_tx_methods(conn);
+
+    let sp = conn.savepoint()?;
+
+    sp.execute("ALTER TABLE balance_all RENAME TO balance_all_old", [])?;
+    sp.execute("DROP INDEX IF EXISTS balance_all_id_num_IDX", [])?;
+
+    create_balances_table(&tx_methods, &sp)?;
+    insert_zero_balances(&tx_methods, date_range.final_balance_id(), &sp)?;
+
+    // the same month is offset rows away on the new table. Moving the start year back by 1 year
+    // moves every month 12 rows forward
+    let offset = (old_range.start_year - date_range.start_year) * 12;
+
+    let month_values = tx_methods
+        .iter()
+        .map(|method| {
+            format!(
+                r#""{method}" = (SELECT "{method}" FROM balance_all_old WHERE balance_all_old.id_num = balance_all.id_num - {offset})"#
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+
+    let query = format!(
+        "UPDATE balance_all SET {month_values} WHERE id_num - ? BETWEEN 1 AND ? AND id_num < ?"
+    );
+    sp.execute(
+        &query,
+        [
+            offset,
+            old_range.final_balance_id() - 1,
+            date_range.final_balance_id(),
+        ],
+    )?;
+
+    let final_values = tx_methods
+        .iter()
+        .map(|method| {
+            format!(
+                r#""{method}" = (SELECT "{method}" FROM balance_all_old WHERE id_num = {})"#,
+                old_range.final_balance_id()
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+
+    let query = format!("UPDATE balance_all SET {final_values} WHERE id_num = ?");
+    sp.execute(&query, [date_range.final_balance_id()])?;
+
+    sp.execute("DROP TABLE balance_all_old", [])?;
+    sp.execute(
+        "CREATE UNIQUE INDEX balance_all_id_num_IDX ON balance_all (id_num);",
+        [],
+    )?;
+
+    sp.execute(
+        "UPDATE date_range SET start_year = ?, end_year = ?",
+        [date_range.start_year, date_range.end_year],
+    )?;
+
+    sp.commit()?;
+    Ok(())
+}
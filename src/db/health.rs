@@ -0,0 +1,201 @@
+use chrono::NaiveDateTime;
+use rusqlite::Connection;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Backups of the database are files in the data directory that start with this and end with
+/// `.sqlite`. The rest of the name is the time it was taken so the newest one sorts last
+pub const BACKUP_PREFIX: &str = "data_backup";
+
+/// The most integrity_check problems shown when the database is corrupted
+const MAX_SHOWN_PROBLEMS: usize = 10;
+
+/// The tables and the columns every database of the app has had since the first version.
+/// Tables added later are created by the migrations instead
+const REQUIRED_COLUMNS: [(&str, &[&str]); 3] = [
+    (
+        "tx_all",
+        &[
+            "date",
+            "details",
+            "tx_method",
+            "amount",
+            "tx_type",
+            "id_num",
+        ],
+    ),
+    ("balance_all", &["id_num"]),
+    ("changes_all", &["date", "id_num"]),
+];
+
+/// The state of the database file found on startup
+#[derive(Debug, PartialEq)]
+pub enum DbHealth {
+    Healthy,
+    /// The problems reported by integrity_check
+    Corrupt(Vec<String>),
+    /// The tables and columns that are missing. The file is likely not a database of the app
+    Foreign(Vec<String>),
+    /// The file could not be read as a database at all
+    Unreadable(String),
+}
+
+impl DbHealth {
+    pub fn is_healthy(&self) -> bool {
+        *self == DbHealth::Healthy
+    }
+
+    /// Returns the text that explains what is wrong with the database
+    pub fn get_message(&self) -> String {
+        match self {
+            DbHealth::Healthy => "The database is healthy.".to_string(),
+            DbHealth::Corrupt(problems) => {
+                let mut message = "The database is corrupted. Problems found:".to_string();
+                for problem in problems.iter().take(MAX_SHOWN_PROBLEMS) {
+                    message.push_str(&format!("\n  {problem}"));
+                }
+                if problems.len() > MAX_SHOWN_PROBLEMS {
+                    message.push_str(&format!(
+                        "\n  and {} more",
+                        problems.len() - MAX_SHOWN_PROBLEMS
+                    ));
+                }
+                message
+            }
+            DbHealth::Foreign(missing) => format!(
+                "The file does not look like a Rex database. Missing: {}",
+                missing.join(", ")
+            ),
+            DbHealth::Unreadable(error) => {
+                format!("The file could not be read as a database. Error: {error}")
+            }
+        }
+    }
+}
+
+/// What can be done on startup about a database that is not healthy
+#[derive(Debug, PartialEq)]
+pub enum RecoveryAction {
+    /// Moves the bad file aside and copies the backup in its place
+    RestoreBackup(PathBuf),
+    /// Moves the bad file aside and creates a new database
+    StartFresh,
+    Exit,
+}
+
+impl RecoveryAction {
+    /// Returns the text of the action on the startup menu
+    pub fn get_text(&self) -> String {
+        match self {
+            RecoveryAction::RestoreBackup(path) => {
+                format!("Restore from the newest backup {}", path.display())
+            }
+            RecoveryAction::StartFresh => {
+                "Move the file aside and start with a new database".to_string()
+            }
+            RecoveryAction::Exit => "Exit".to_string(),
+        }
+    }
+}
+
+/// Checks whether the database is intact and has the tables and columns of the app
+pub fn check_db_health(conn: &Connection) -> DbHealth {
+    let problems = conn
+        .prepare("PRAGMA integrity_check")
+        .and_then(|mut query| {
+            query
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<String>, _>>()
+        });
+
+    match problems {
+        Ok(problems) if problems != ["ok"] => return DbHealth::Corrupt(problems),
+        Ok(_) => {}
+        Err(e) => return DbHealth::Unreadable(e.to_string()),
+    }
+
+    let mut missing = Vec::new();
+
+    for (table, columns) in REQUIRED_COLUMNS {
+        let table_columns = match get_table_columns(table, conn) {
+            Ok(table_columns) => table_columns,
+            Err(e) => return DbHealth::Unreadable(e.to_string()),
+        };
+
+        if table_columns.is_empty() {
+            missing.push(format!("table {table}"));
+            continue;
+        }
+
+        for column in columns {
+            if !table_columns.iter().any(|name| name == column) {
+                missing.push(format!("column {table}.{column}"));
+            }
+        }
+    }
+
+    if missing.is_empty() {
+        DbHealth::Healthy
+    } else {
+        DbHealth::Foreign(missing)
+    }
+}
+
+/// Returns the column names of the table. Empty if the table does not exist
+fn get_table_columns(table: &str, conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    let mut query = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    let columns = query
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(columns)
+}
+
+/// Returns the actions offered for the database. Nothing is offered for a healthy one.
+/// Restoring is only offered if there is a backup and nothing but exiting is offered in read-only mode
+pub fn get_recovery_actions(
+    health: &DbHealth,
+    newest_backup: Option<&Path>,
+    read_only: bool,
+) -> Vec<RecoveryAction> {
+    if health.is_healthy() {
+        return Vec::new();
+    }
+
+    let mut actions = Vec::new();
+
+    if !read_only {
+        if let Some(backup) = newest_backup {
+            actions.push(RecoveryAction::RestoreBackup(backup.to_path_buf()));
+        }
+        actions.push(RecoveryAction::StartFresh);
+    }
+
+    actions.push(RecoveryAction::Exit);
+    actions
+}
+
+/// Returns the newest backup in the directory
+pub fn find_newest_backup(dir: &Path) -> Option<PathBuf> {
+    let mut backups = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| {
+                    name.starts_with(BACKUP_PREFIX) && name.ends_with(".sqlite")
+                })
+        })
+        .collect::<Vec<PathBuf>>();
+
+    backups.sort();
+    backups.pop()
+}
+
+/// Returns the path the bad database file is moved to so it is not lost
+///
+/// return example: `data.sqlite.corrupt-20230714-093000`
+pub fn get_moved_aside_path(path: &str, now: NaiveDateTime) -> String {
+    format!("{path}.corrupt-{}", now.format("%Y%m%d-%H%M%S"))
+}
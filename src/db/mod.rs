@@ -1,5 +1,15 @@
+mod archive;
+mod date_range;
+mod health;
+mod settings;
 mod setup;
+mod templates;
 mod update;
 
+pub use archive::*;
+pub use date_range::*;
+pub use health::*;
+pub use settings::*;
 pub use setup::*;
+pub use templates::*;
 pub use update::*;
@@ -0,0 +1,337 @@
+use crate::utility::Money;
+use chrono::Weekday;
+use rusqlite::{Connection, Result, Savepoint};
+
+/// All the settings that can be changed by the user.
+/// Format: `(key, default value, description)`
+pub const SETTINGS: [(&str, &str, &str); 22] = [
+    (
+        "gauge_warning_ratio",
+        "0.75",
+        "Expense to income ratio where the Home gauge turns yellow",
+    ),
+    (
+        "gauge_danger_ratio",
+        "1.00",
+        "Expense to income ratio where the Home gauge turns red",
+    ),
+    ("gauge_hidden", "false", "Hides the Home gauge"),
+    (
+        "tx_confirmation",
+        "never",
+        "Shows a summary of the transaction before saving it. Values: always/threshold/never",
+    ),
+    (
+        "tx_confirmation_threshold",
+        "1000.00",
+        "Amount above which the transaction summary is shown when tx_confirmation is threshold",
+    ),
+    (
+        "autofill_recency_weight",
+        "0.50",
+        "Boost given to the 5 most recently used tags and tx methods when ranking autofill matches",
+    ),
+    (
+        "summary_include_transfers",
+        "false",
+        "Counts transfers as income and expense of the tx methods on the Summary method table",
+    ),
+    (
+        "date_format",
+        "ymd",
+        "The order dates are typed and shown in. Values: ymd/dmy/mdy",
+    ),
+    (
+        "show_dashboard",
+        "false",
+        "Shows a summary of the current month when the app opens",
+    ),
+    (
+        "decimal_mark",
+        ".",
+        "The character that separates the decimals of typed and shown amounts. Values: ./,",
+    ),
+    (
+        "currency_symbols",
+        "$,€,£,¥,₹",
+        "Comma separated currency symbols that are removed from typed amounts",
+    ),
+    (
+        "details_max_length",
+        "100",
+        "The maximum number of characters of transaction details. Values: 1 to 500",
+    ),
+    (
+        "max_tags",
+        "10",
+        "The maximum number of tags a transaction can have",
+    ),
+    (
+        "max_tag_length",
+        "40",
+        "The maximum number of characters of a single tag",
+    ),
+    (
+        "amount_step",
+        "1.00",
+        "The amount Arrow Up/Down steps the amount field by. Shift steps 10 and Ctrl 100 times of it",
+    ),
+    (
+        "show_hidden_methods",
+        "false",
+        "Shows the hidden tx methods on the Home balance table",
+    ),
+    (
+        "home_day_groups",
+        "false",
+        "Groups the Home table by day with a separator row showing the expense of each day",
+    ),
+    (
+        "amount_rounding",
+        "half_up",
+        "How the decimals of an amount past the ones the tx method keeps are dropped. Values: half_up/half_even/truncate",
+    ),
+    (
+        "week_start",
+        "monday",
+        "The day weeks start on in the date calendar and the weekly views. Values: monday/sunday",
+    ),
+    (
+        "skip_empty_periods",
+        "false",
+        "Arrow Left/Right on the month and year tabs skip the months and years without transactions",
+    ),
+    (
+        "require_tags",
+        "false",
+        "Transactions cannot be saved without at least one tag",
+    ),
+    (
+        "deletion_typing_threshold",
+        "0.00",
+        "Amount at or above which deleting a transaction needs yes or the amount typed. 0 turns it off",
+    ),
+];
+
+/// The accepted values of the tx_confirmation setting
+pub const CONFIRMATION_MODES: [&str; 3] = ["always", "threshold", "never"];
+
+/// The accepted values of the date_format setting
+pub const DATE_FORMATS: [&str; 3] = ["ymd", "dmy", "mdy"];
+
+/// The accepted values of the decimal_mark setting
+pub const DECIMAL_MARKS: [&str; 2] = [".", ","];
+
+/// The accepted values of the amount_rounding setting
+pub const ROUNDING_MODES: [&str; 3] = ["half_up", "half_even", "truncate"];
+
+/// The accepted values of the week_start setting
+pub const WEEK_STARTS: [&str; 2] = ["monday", "sunday"];
+
+/// The highest value of the details_max_length setting. Tables never show more characters of
+/// the details than this even if older transactions saved longer ones
+pub const DETAILS_LENGTH_LIMIT: usize = 500;
+
+/// creates the settings table of the DB
+pub fn create_settings_table(sp: &Savepoint) -> Result<()> {
+    // settings table. Each row is a key with the value set by the user.
+    // Settings that were never changed do not have a row and use the default value
+    sp.execute(
+        "CREATE TABLE settings (
+            key TEXT NOT NULL PRIMARY KEY,
+            value TEXT NOT NULL
+        );",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Adds the settings table inside the database. Used when an old database without the table is detected
+pub fn add_settings_table(conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    create_settings_table(&sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Returns the value of the given setting key. Falls back to the default value
+/// if the value was never changed
+pub fn get_setting(key: &str, conn: &Connection) -> String {
+    let saved_value = conn.query_row("SELECT value FROM settings WHERE key = ?", [key], |row| {
+        row.get::<_, String>(0)
+    });
+
+    match saved_value {
+        Ok(value) => value,
+        Err(_) => get_default_setting(key),
+    }
+}
+
+/// Returns the default value of the given setting key
+pub fn get_default_setting(key: &str) -> String {
+    SETTINGS
+        .iter()
+        .find(|(setting_key, ..)| *setting_key == key)
+        .map(|(_, default, _)| default.to_string())
+        .unwrap_or_default()
+}
+
+/// Returns the value of the given setting key parsed as a bool
+pub fn get_bool_setting(key: &str, conn: &Connection) -> bool {
+    get_setting(key, conn) == "true"
+}
+
+/// Returns the value of the given setting key parsed as f64. Falls back to the default value on invalid values
+pub fn get_f64_setting(key: &str, conn: &Connection) -> f64 {
+    get_setting(key, conn)
+        .parse()
+        .unwrap_or_else(|_| get_default_setting(key).parse().unwrap_or_default())
+}
+
+/// Returns the value of the given setting key parsed as usize. None if the value was never
+/// saved or is not a valid number
+pub fn get_usize_setting(key: &str, conn: &Connection) -> Option<usize> {
+    get_setting(key, conn).parse().ok()
+}
+
+/// Returns the comma separated values of the given setting key. Empty values are skipped
+pub fn get_list_setting(key: &str, conn: &Connection) -> Vec<String> {
+    get_setting(key, conn)
+        .split(',')
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// Returns the value of the details_max_length setting
+pub fn get_details_max_length(conn: &Connection) -> usize {
+    get_setting("details_max_length", conn)
+        .parse()
+        .unwrap_or_else(|_| get_default_setting("details_max_length").parse().unwrap())
+}
+
+/// Returns the day of the week_start setting. Unknown values fall back to Monday
+pub fn get_week_start(conn: &Connection) -> Weekday {
+    match get_setting("week_start", conn).as_str() {
+        "sunday" => Weekday::Sun,
+        _ => Weekday::Mon,
+    }
+}
+
+/// The number of tags a transaction can have and the number of characters each tag can have
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TagLimits {
+    pub max_tags: usize,
+    pub max_tag_length: usize,
+}
+
+impl Default for TagLimits {
+    fn default() -> Self {
+        TagLimits {
+            max_tags: get_default_setting("max_tags").parse().unwrap(),
+            max_tag_length: get_default_setting("max_tag_length").parse().unwrap(),
+        }
+    }
+}
+
+/// Returns the tag limits of the max_tags and max_tag_length settings
+pub fn get_tag_limits(conn: &Connection) -> TagLimits {
+    let default = TagLimits::default();
+
+    TagLimits {
+        max_tags: get_setting("max_tags", conn)
+            .parse()
+            .unwrap_or(default.max_tags),
+        max_tag_length: get_setting("max_tag_length", conn)
+            .parse()
+            .unwrap_or(default.max_tag_length),
+    }
+}
+
+/// Returns the value of the amount_step setting. Falls back to the default value on invalid values
+pub fn get_amount_step(conn: &Connection) -> Money {
+    Money::parse(&get_setting("amount_step", conn))
+        .filter(|step| *step > Money::ZERO)
+        .unwrap_or_else(|| Money::parse(&get_default_setting("amount_step")).unwrap())
+}
+
+/// Saves the value of the given setting key
+pub fn set_setting(key: &str, value: &str, conn: &Connection) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
+        [key, value],
+    )?;
+    Ok(())
+}
+
+/// Saves the bool value of the given setting key. Nothing is written if the value did not change
+pub fn set_bool_setting(key: &str, value: bool, conn: &Connection) -> Result<()> {
+    if get_setting(key, conn) != value.to_string() {
+        set_setting(key, &value.to_string(), conn)?;
+    }
+    Ok(())
+}
+
+/// Saves the usize value of the given setting key. Nothing is written if the value did not change
+pub fn set_usize_setting(key: &str, value: usize, conn: &Connection) -> Result<()> {
+    if get_usize_setting(key, conn) != Some(value) {
+        set_setting(key, &value.to_string(), conn)?;
+    }
+    Ok(())
+}
+
+/// Saves the values of the given setting key as a comma separated list
+pub fn set_list_setting(key: &str, values: &[String], conn: &Connection) -> Result<()> {
+    set_setting(key, &values.join(","), conn)
+}
+
+/// Returns the highest transaction id_num the month had when it was last viewed on the Home page.
+/// None if the month was never viewed. month is 1 to 12
+pub fn get_month_viewed(month: usize, year: i32, conn: &Connection) -> Option<i64> {
+    get_setting(&format!("last_viewed_{year}_{month:02}"), conn)
+        .parse()
+        .ok()
+}
+
+/// Saves the highest transaction id_num the month had when it was viewed on the Home page.
+/// Transactions have an increasing id_num so any row with a bigger one was added afterwards
+pub fn set_month_viewed(month: usize, year: i32, last_id: i64, conn: &Connection) -> Result<()> {
+    set_setting(
+        &format!("last_viewed_{year}_{month:02}"),
+        &last_id.to_string(),
+        conn,
+    )
+}
+
+/// Checks whether the value can be saved for the given setting key. The value must be of the
+/// same type as the default value and settings with a fixed set of values only accept those.
+pub fn is_valid_setting(key: &str, value: &str) -> bool {
+    let default = get_default_setting(key);
+
+    if key == "tx_confirmation" {
+        CONFIRMATION_MODES.contains(&value)
+    } else if key == "date_format" {
+        DATE_FORMATS.contains(&value)
+    } else if key == "decimal_mark" {
+        DECIMAL_MARKS.contains(&value)
+    } else if key == "amount_rounding" {
+        ROUNDING_MODES.contains(&value)
+    } else if key == "week_start" {
+        WEEK_STARTS.contains(&value)
+    } else if key == "details_max_length" {
+        value
+            .parse::<usize>()
+            .map_or(false, |length| (1..=DETAILS_LENGTH_LIMIT).contains(&length))
+    } else if key == "amount_step" {
+        Money::parse(value).map_or(false, |step| step > Money::ZERO)
+    } else if key == "max_tags" || key == "max_tag_length" {
+        value.parse::<usize>().map_or(false, |limit| limit > 0)
+    } else if default == "true" || default == "false" {
+        value == "true" || value == "false"
+    } else if default.parse::<f64>().is_ok() {
+        value.parse::<f64>().map_or(false, f64::is_finite)
+    } else {
+        true
+    }
+}
@@ -1,3 +1,9 @@
+use crate::db::{
+    create_date_range_table, create_details_templates_table, create_settings_table,
+    create_templates_table, DateRange,
+};
+use crate::utility::normalize_name;
+use chrono::{Datelike, Local};
 use rusqlite::{Connection, Result, Savepoint};
 
 pub const MONTHS: [&str; 12] = [
@@ -15,15 +21,31 @@ pub const MONTHS: [&str; 12] = [
     "December",
 ];
 
+/// The default year range of the db
 pub const YEARS: [&str; 16] = [
     "2022", "2023", "2024", "2025", "2026", "2027", "2028", "2029", "2030", "2031", "2032", "2033",
     "2034", "2035", "2036", "2037",
 ];
 
-pub const MODES: [&str; 3] = ["Monthly", "Yearly", "All Time"];
+/// The Chart page modes. Range plots a custom start and end date
+pub const MODES: [&str; 4] = ["Monthly", "Yearly", "All Time", "Range"];
+
+/// The Summary page modes. Range aggregates a custom start and end date
+pub const SUMMARY_MODES: [&str; 4] = ["Monthly", "Yearly", "All Time", "Range"];
+
+/// The link type of the links added between transactions from the details popup
+pub const RELATED_LINK: &str = "Related";
 
 /// Creates the db that is used by this app
 pub fn create_db(tx_methods: Vec<String>, conn: &mut Connection) -> Result<()> {
+    let tx_methods = tx_methods
+        .iter()
+        .map(|method| normalize_name(method))
+        .collect::<Vec<String>>();
+
+    // the default range is extended to the current year so a new db is usable past the default years
+    let date_range = DateRange::default().including(Local::now().year());
+
     // add a save point to reverse commits if failed
     let sp = conn.savepoint()?;
 
@@ -45,6 +67,32 @@ pub fn create_db(tx_methods: Vec<String>, conn: &mut Connection) -> Result<()> {
 
     create_changes_table(&tx_methods, &sp)?;
 
+    create_tag_groups_table(&sp)?;
+
+    create_tag_bounds_table(&sp)?;
+
+    create_reviewed_txs_table(&sp)?;
+
+    create_minor_units_table(&sp)?;
+
+    create_hidden_methods_table(&sp)?;
+
+    create_excluded_tags_table(&sp)?;
+
+    create_refund_links_table(&sp)?;
+
+    create_tx_links_table(&sp)?;
+
+    create_external_ids_table(&sp)?;
+
+    create_settings_table(&sp)?;
+
+    create_templates_table(&sp)?;
+
+    create_details_templates_table(&sp)?;
+
+    create_date_range_table(&date_range, &sp)?;
+
     sp.execute("CREATE UNIQUE INDEX all_tx_id_IDX ON tx_all (id_num);", [])?;
 
     sp.execute(
@@ -57,6 +105,14 @@ pub fn create_db(tx_methods: Vec<String>, conn: &mut Connection) -> Result<()> {
     )?;
 
     // fill up balance_all table with total year * 12 + 1 rows with 0 balance
+    insert_zero_balances(&tx_methods, date_range.final_balance_id(), &sp)?;
+
+    sp.commit()?;
+    Ok(())
+}
+
+/// Adds the given amount of rows with 0 balance for all tx methods to the balance_all table
+pub fn insert_zero_balances(tx_methods: &[String], total_rows: i32, sp: &Savepoint) -> Result<()> {
     let zero_values = vec!["0.00"; tx_methods.len()];
 
     let highlighted_tx_methods = tx_methods
@@ -71,14 +127,10 @@ pub fn create_db(tx_methods: Vec<String>, conn: &mut Connection) -> Result<()> {
         zero_values.join(",")
     );
 
-    for _i in YEARS {
-        for _a in 0..MONTHS.len() {
-            sp.execute(&query, [])?;
-        }
+    for _ in 0..total_rows {
+        sp.execute(&query, [])?;
     }
 
-    sp.execute(&query, [])?;
-    sp.commit()?;
     Ok(())
 }
 
@@ -105,6 +157,139 @@ pub fn create_balances_table(tx_methods: &[String], sp: &Savepoint) -> Result<()
     Ok(())
 }
 
+/// creates the tag_groups table of the DB
+pub fn create_tag_groups_table(sp: &Savepoint) -> Result<()> {
+    // tag_groups table. Each row assigns a tag to a group that the summary can roll up to
+    sp.execute(
+        "CREATE TABLE tag_groups (
+            tag TEXT NOT NULL PRIMARY KEY,
+            group_name TEXT NOT NULL
+        );",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// creates the tag_bounds table of the DB
+pub fn create_tag_bounds_table(sp: &Savepoint) -> Result<()> {
+    // tag_bounds table. Each row sets the amount range a single transaction of the tag is expected to be in.
+    // NULL means the tag has no bound on that side
+    sp.execute(
+        "CREATE TABLE tag_bounds (
+            tag TEXT NOT NULL PRIMARY KEY,
+            min_amount REAL,
+            max_amount REAL
+        );",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// creates the reviewed_txs table of the DB
+pub fn create_reviewed_txs_table(sp: &Savepoint) -> Result<()> {
+    // reviewed_txs table. Each row is the id_num of a transaction that was marked as reviewed on the Home page
+    sp.execute(
+        "CREATE TABLE reviewed_txs (
+            id_num INTEGER NOT NULL PRIMARY KEY
+        );",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// create the minor_units table of the DB
+pub fn create_minor_units_table(sp: &Savepoint) -> Result<()> {
+    // minor_units table. Each row sets how many numbers after the dot the amounts of a tx method use.
+    // Tx methods without a row use 2
+    sp.execute(
+        "CREATE TABLE minor_units (
+            tx_method TEXT NOT NULL PRIMARY KEY,
+            units INTEGER NOT NULL
+        );",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// create the hidden_methods table of the DB
+pub fn create_hidden_methods_table(sp: &Savepoint) -> Result<()> {
+    // hidden_methods table. Each row is a tx method that is no longer used. Hidden methods
+    // keep their transactions but are skipped when stepping and correcting tx methods
+    sp.execute(
+        "CREATE TABLE hidden_methods (
+            tx_method TEXT NOT NULL PRIMARY KEY
+        );",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// create the excluded_tags table of the DB
+pub fn create_excluded_tags_table(sp: &Savepoint) -> Result<()> {
+    // excluded_tags table. Each row is a tag whose transactions are left out of the
+    // Summary page calculations unless they also have a tag that is not excluded
+    sp.execute(
+        "CREATE TABLE excluded_tags (
+            tag TEXT NOT NULL PRIMARY KEY
+        );",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// create the refund_links table of the DB
+pub fn create_refund_links_table(sp: &Savepoint) -> Result<()> {
+    // refund_links table. Each row links a Refund transaction to the id_num of the expense it refunds.
+    // The original_id is kept after the expense is deleted so the refund can be told apart
+    sp.execute(
+        "CREATE TABLE refund_links (
+            id_num INTEGER NOT NULL PRIMARY KEY,
+            original_id INTEGER NOT NULL
+        );",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// create the external_ids table of the DB
+pub fn create_external_ids_table(sp: &Savepoint) -> Result<()> {
+    // external_ids table. Each row is the id an imported transaction has in the program it was
+    // exported from. Importing the same export again skips the ids that are already saved
+    sp.execute(
+        "CREATE TABLE external_ids (
+            external_id TEXT NOT NULL PRIMARY KEY,
+            id_num INTEGER NOT NULL
+        );",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// create the tx_links table of the DB
+pub fn create_tx_links_table(sp: &Savepoint) -> Result<()> {
+    // tx_links table. Each link between two transactions is saved once from each side
+    // so the links of a transaction can be found from its own id_num
+    sp.execute(
+        "CREATE TABLE tx_links (
+            id_num INTEGER NOT NULL,
+            linked_id INTEGER NOT NULL,
+            link_type TEXT NOT NULL,
+            PRIMARY KEY (id_num, linked_id, link_type)
+        );",
+        [],
+    )?;
+
+    Ok(())
+}
+
 /// create the changes_all table of the DB
 pub fn create_changes_table(tx_methods: &[String], sp: &Savepoint) -> Result<()> {
     // changes_all column. Will contain all balance changes with up and down arrows
@@ -0,0 +1,225 @@
+use crate::utility::check_similar_tx;
+use chrono::{Datelike, NaiveDate};
+use rusqlite::{Connection, Result, Savepoint};
+
+/// A saved transaction that can be added again later. With a remind day, a reminder
+/// is shown from that day of the month onward until a similar transaction is added that month.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TxTemplate {
+    pub id_num: i32,
+    pub details: String,
+    pub tx_method: String,
+    pub amount: String,
+    pub tx_type: String,
+    pub tags: String,
+    pub remind_day: Option<u32>,
+}
+
+/// A saved text for the details field. `{placeholder}` markers are asked for when it is
+/// inserted and `{{` or `}}` insert a single brace
+#[derive(Debug, PartialEq, Clone)]
+pub struct DetailsTemplate {
+    pub id_num: i32,
+    pub text: String,
+}
+
+/// creates the tx_templates and reminder_dismissals tables of the DB
+pub fn create_templates_table(sp: &Savepoint) -> Result<()> {
+    // tx_templates table. Each row is a transaction without a date.
+    // remind_day is NULL for templates without a reminder
+    sp.execute(
+        "CREATE TABLE tx_templates (
+            id_num INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+            details TEXT,
+            tx_method TEXT,
+            amount TEXT,
+            tx_type TEXT,
+            tags TEXT,
+            remind_day INTEGER
+        );",
+        [],
+    )?;
+
+    // reminder_dismissals table. Each row stops the reminder of a template for a YYYY-MM month
+    sp.execute(
+        "CREATE TABLE reminder_dismissals (
+            template_id INTEGER NOT NULL,
+            month TEXT NOT NULL,
+            PRIMARY KEY (template_id, month),
+            FOREIGN KEY (template_id) REFERENCES tx_templates(id_num) ON DELETE CASCADE
+        );",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Adds the template tables inside the database. Used when an old database without the tables is detected
+pub fn add_templates_table(conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    create_templates_table(&sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// creates the details_templates table of the DB
+pub fn create_details_templates_table(sp: &Savepoint) -> Result<()> {
+    // details_templates table. Each row is a text that can be inserted into the details field
+    sp.execute(
+        "CREATE TABLE details_templates (
+            id_num INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+            text TEXT NOT NULL
+        );",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Adds the details_templates table inside the database. Used when an old database without the table is detected
+pub fn add_details_templates_table(conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    create_details_templates_table(&sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Saves a new template
+pub fn add_template(
+    details: &str,
+    tx_method: &str,
+    amount: &str,
+    tx_type: &str,
+    tags: &str,
+    remind_day: Option<u32>,
+    conn: &Connection,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO tx_templates (details, tx_method, amount, tx_type, tags, remind_day)
+        VALUES (?, ?, ?, ?, ?, ?)",
+        (details, tx_method, amount, tx_type, tags, remind_day),
+    )?;
+    Ok(())
+}
+
+/// Deletes a template along with its dismissed reminders
+pub fn delete_template(id_num: i32, conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    sp.execute(
+        "DELETE FROM reminder_dismissals WHERE template_id = ?",
+        [id_num],
+    )?;
+    sp.execute("DELETE FROM tx_templates WHERE id_num = ?", [id_num])?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Returns all saved templates in the order they were added
+pub fn get_templates(conn: &Connection) -> Vec<TxTemplate> {
+    let mut query = conn
+        .prepare(
+            "SELECT id_num, details, tx_method, amount, tx_type, tags, remind_day FROM tx_templates ORDER BY id_num",
+        )
+        .expect("could not prepare statement");
+
+    let mut templates = Vec::new();
+
+    if let Ok(rows) = query.query_map([], |row| {
+        Ok(TxTemplate {
+            id_num: row.get(0)?,
+            details: row.get(1)?,
+            tx_method: row.get(2)?,
+            amount: row.get(3)?,
+            tx_type: row.get(4)?,
+            tags: row.get(5)?,
+            remind_day: row.get(6)?,
+        })
+    }) {
+        templates.extend(rows.flatten());
+    }
+
+    templates
+}
+
+/// Saves a new details template
+pub fn add_details_template(text: &str, conn: &Connection) -> Result<()> {
+    conn.execute("INSERT INTO details_templates (text) VALUES (?)", [text])?;
+    Ok(())
+}
+
+pub fn delete_details_template(id_num: i32, conn: &Connection) -> Result<()> {
+    conn.execute("DELETE FROM details_templates WHERE id_num = ?", [id_num])?;
+    Ok(())
+}
+
+/// Returns all saved details templates in the order they were added
+pub fn get_details_templates(conn: &Connection) -> Vec<DetailsTemplate> {
+    let mut query = conn
+        .prepare("SELECT id_num, text FROM details_templates ORDER BY id_num")
+        .expect("could not prepare statement");
+
+    let mut templates = Vec::new();
+
+    if let Ok(rows) = query.query_map([], |row| {
+        Ok(DetailsTemplate {
+            id_num: row.get(0)?,
+            text: row.get(1)?,
+        })
+    }) {
+        templates.extend(rows.flatten());
+    }
+
+    templates
+}
+
+/// Stops the reminder of the template for the given YYYY-MM month
+pub fn dismiss_reminder(template_id: i32, month: &str, conn: &Connection) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO reminder_dismissals (template_id, month) VALUES (?, ?)",
+        (template_id, month),
+    )?;
+    Ok(())
+}
+
+/// Returns the templates whose remind day has been reached in the month of the given date,
+/// that were not dismissed for that month and that do not have a similar transaction in that month yet
+pub fn get_pending_reminders(today: NaiveDate, conn: &Connection) -> Vec<TxTemplate> {
+    let month = today.format("%Y-%m").to_string();
+    let month_start = format!("{month}-01");
+    let month_end = format!("{month}-31");
+
+    // a remind day past the end of a shorter month fires on its last day instead
+    let next_month = match today.month() {
+        12 => NaiveDate::from_ymd_opt(today.year() + 1, 1, 1),
+        month => NaiveDate::from_ymd_opt(today.year(), month + 1, 1),
+    };
+    let last_day = next_month
+        .and_then(|date| date.pred_opt())
+        .map_or(31, |date| date.day());
+
+    get_templates(conn)
+        .into_iter()
+        .filter(|template| match template.remind_day {
+            Some(day) => day.min(last_day) <= today.day(),
+            None => false,
+        })
+        .filter(|template| {
+            let dismissed = conn.query_row(
+                "SELECT COUNT(*) FROM reminder_dismissals WHERE template_id = ? AND month = ?",
+                (template.id_num, &month),
+                |row| row.get::<_, i32>(0),
+            );
+            !matches!(dismissed, Ok(count) if count > 0)
+        })
+        .filter(|template| {
+            !check_similar_tx(
+                &month_start,
+                &month_end,
+                &template.tx_method,
+                &template.amount,
+                &template.tags,
+                conn,
+            )
+        })
+        .collect()
+}
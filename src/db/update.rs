@@ -1,10 +1,25 @@
-use crate::db::{create_balances_table, create_changes_table};
-use crate::utility::get_all_tx_methods;
+use crate::db::{
+    attach_archive, create_balances_table, create_changes_table, create_excluded_tags_table,
+    create_external_ids_table, create_hidden_methods_table, create_minor_units_table,
+    create_refund_links_table, create_reviewed_txs_table, create_tag_bounds_table,
+    create_tag_groups_table, create_tx_links_table, detach_archive, get_archive_changes_columns,
+    is_archive_attached, set_setting,
+};
+use crate::utility::{
+    get_all_tags, get_all_tx_methods, get_minor_units, get_name_collisions, normalize_name, Money,
+};
 use rusqlite::{Connection, Result, Savepoint};
+use std::collections::HashMap;
+use std::path::Path;
 
 /// adds new tx methods as columns on balance_all and changes_all tables. Gets called after
 /// successful handling of 'J' from the app
 pub fn add_new_tx_methods(tx_methods: Vec<String>, conn: &mut Connection) -> Result<()> {
+    let tx_methods = tx_methods
+        .iter()
+        .map(|method| normalize_name(method))
+        .collect::<Vec<String>>();
+
     // add a save point to reverse commits if failed
     let sp = conn.savepoint()?;
 
@@ -29,6 +44,257 @@ pub fn add_tags_column(conn: &mut Connection) -> Result<()> {
     Ok(())
 }
 
+/// Adds the tag_groups table inside the database. Used when an old database without the table is detected
+pub fn add_tag_groups_table(conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    create_tag_groups_table(&sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Assigns the tag to the given group. Empty group name removes the tag from its group
+pub fn set_tag_group(tag: &str, group_name: &str, conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    if group_name.is_empty() {
+        sp.execute("DELETE FROM tag_groups WHERE tag = ?", [tag])?;
+    } else {
+        sp.execute(
+            "INSERT OR REPLACE INTO tag_groups (tag, group_name) VALUES (?, ?)",
+            [tag, group_name],
+        )?;
+    }
+    sp.commit()?;
+    Ok(())
+}
+
+/// Adds the tag_bounds table inside the database. Used when an old database without the table is detected
+pub fn add_tag_bounds_table(conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    create_tag_bounds_table(&sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Sets the expected amount range of a single transaction of the tag. No bound on both sides removes the row
+pub fn set_tag_bounds(
+    tag: &str,
+    min_amount: Option<f64>,
+    max_amount: Option<f64>,
+    conn: &mut Connection,
+) -> Result<()> {
+    let sp = conn.savepoint()?;
+    if min_amount.is_none() && max_amount.is_none() {
+        sp.execute("DELETE FROM tag_bounds WHERE tag = ?", [tag])?;
+    } else {
+        sp.execute(
+            "INSERT OR REPLACE INTO tag_bounds (tag, min_amount, max_amount) VALUES (?, ?, ?)",
+            (tag, min_amount, max_amount),
+        )?;
+    }
+    sp.commit()?;
+    Ok(())
+}
+
+/// Adds the reviewed_txs table inside the database. Used when an old database without the table is detected
+pub fn add_reviewed_txs_table(conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    create_reviewed_txs_table(&sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Marks the transaction as reviewed or removes the mark
+pub fn set_tx_reviewed(id_num: i32, reviewed: bool, conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    if reviewed {
+        sp.execute(
+            "INSERT OR IGNORE INTO reviewed_txs (id_num) VALUES (?)",
+            [id_num],
+        )?;
+    } else {
+        sp.execute("DELETE FROM reviewed_txs WHERE id_num = ?", [id_num])?;
+    }
+    sp.commit()?;
+    Ok(())
+}
+
+/// Replaces the tags of the transaction. The balances are not affected by tags
+pub fn update_tx_tags(id_num: i32, tags: &str, conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    sp.execute(
+        "UPDATE tx_all SET tags = ? WHERE id_num = ?",
+        (tags, id_num),
+    )?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Adds the minor_units table inside the database. Used when an old database without the table is detected
+pub fn add_minor_units_table(conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    create_minor_units_table(&sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Adds the hidden_methods table inside the database. Used when an old database without the table is detected
+pub fn add_hidden_methods_table(conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    create_hidden_methods_table(&sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Hides or shows the tx method. Hidden methods are skipped when stepping and correcting tx methods
+pub fn set_method_hidden(tx_method: &str, hidden: bool, conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    if hidden {
+        sp.execute(
+            "INSERT OR REPLACE INTO hidden_methods (tx_method) VALUES (?)",
+            [tx_method],
+        )?;
+    } else {
+        sp.execute(
+            "DELETE FROM hidden_methods WHERE tx_method = ?",
+            [tx_method],
+        )?;
+    }
+    sp.commit()?;
+    Ok(())
+}
+
+/// Adds the excluded_tags table inside the database. Used when an old database without the table is detected
+pub fn add_excluded_tags_table(conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    create_excluded_tags_table(&sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Excludes or includes the tag. Transactions with only excluded tags are left out of the Summary page calculations
+pub fn set_tag_excluded(tag: &str, excluded: bool, conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    if excluded {
+        sp.execute(
+            "INSERT OR REPLACE INTO excluded_tags (tag) VALUES (?)",
+            [tag],
+        )?;
+    } else {
+        sp.execute("DELETE FROM excluded_tags WHERE tag = ?", [tag])?;
+    }
+    sp.commit()?;
+    Ok(())
+}
+
+/// Adds the refund_links table inside the database. Used when an old database without the table is detected
+pub fn add_refund_links_table(conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    create_refund_links_table(&sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Links the refund to the expense it refunds. None removes the link of the transaction
+pub fn set_refund_link(id_num: i32, original_id: Option<i32>, conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    insert_refund_link(id_num, original_id, &sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Does the work of `set_refund_link` on the given savepoint without committing it
+pub fn insert_refund_link(id_num: i32, original_id: Option<i32>, sp: &Savepoint) -> Result<()> {
+    match original_id {
+        Some(original_id) => {
+            sp.execute(
+                "INSERT OR REPLACE INTO refund_links (id_num, original_id) VALUES (?, ?)",
+                [id_num, original_id],
+            )?;
+        }
+        None => {
+            sp.execute("DELETE FROM refund_links WHERE id_num = ?", [id_num])?;
+        }
+    }
+    Ok(())
+}
+
+/// Adds the external_ids table inside the database. Used when an old database without the table is detected
+pub fn add_external_ids_table(conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    create_external_ids_table(&sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Adds the tx_links table inside the database. Used when an old database without the table is detected
+pub fn add_tx_links_table(conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    create_tx_links_table(&sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Links the two transactions with each other. Linking an already linked pair does nothing
+pub fn add_tx_link(
+    id_num: i32,
+    linked_id: i32,
+    link_type: &str,
+    conn: &mut Connection,
+) -> Result<()> {
+    let sp = conn.savepoint()?;
+    for (from, to) in [(id_num, linked_id), (linked_id, id_num)] {
+        sp.execute(
+            "INSERT OR IGNORE INTO tx_links (id_num, linked_id, link_type) VALUES (?, ?, ?)",
+            (from, to, link_type),
+        )?;
+    }
+    sp.commit()?;
+    Ok(())
+}
+
+/// Removes the link between the two transactions from both sides
+pub fn remove_tx_link(
+    id_num: i32,
+    linked_id: i32,
+    link_type: &str,
+    conn: &mut Connection,
+) -> Result<()> {
+    let sp = conn.savepoint()?;
+    sp.execute(
+        "DELETE FROM tx_links WHERE link_type = ?1 AND
+        ((id_num = ?2 AND linked_id = ?3) OR (id_num = ?3 AND linked_id = ?2))",
+        (link_type, id_num, linked_id),
+    )?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Removes every link of the transaction of any type
+pub fn remove_all_tx_links(id_num: i32, conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    sp.execute(
+        "DELETE FROM tx_links WHERE id_num = ?1 OR linked_id = ?1",
+        [id_num],
+    )?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Sets the number of minor units the amounts of the tx method use. 2 removes the row as it is the default
+pub fn set_minor_units(tx_method: &str, units: usize, conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    if units == 2 {
+        sp.execute("DELETE FROM minor_units WHERE tx_method = ?", [tx_method])?;
+    } else {
+        sp.execute(
+            "INSERT OR REPLACE INTO minor_units (tx_method, units) VALUES (?, ?)",
+            (tx_method, units),
+        )?;
+    }
+    sp.commit()?;
+    Ok(())
+}
+
 /// Migrates existing database's balance_all column's data type from TEXT to REAL
 pub fn update_balance_type(conn: &mut Connection) -> Result<()> {
     let all_methods = get_all_tx_methods(conn);
@@ -112,6 +378,87 @@ pub fn update_balance_type(conn: &mut Connection) -> Result<()> {
     Ok(())
 }
 
+/// Rewrites the amounts and balances that were saved from f64 calculations. Tx amounts get
+/// exactly the decimal places of their tx method and balances are rounded to the nearest
+/// thousandth which removes the error f64 added over time. Only runs once per database
+pub fn normalize_stored_amounts(conn: &mut Connection) -> Result<()> {
+    let all_methods = get_all_tx_methods(conn);
+    let sp = conn.savepoint()?;
+
+    let mut statement = sp.prepare("SELECT id_num, tx_method, amount, tx_type FROM tx_all")?;
+    let txs = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i32>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    drop(statement);
+
+    for (id_num, tx_method, amount, tx_type) in txs {
+        // transfers use the decimal places of the method the amount is sent from
+        let method = if tx_type == "Transfer" {
+            tx_method.split(" to ").next().unwrap_or(&tx_method)
+        } else {
+            &tx_method
+        };
+
+        let normalized = match Money::parse(&amount) {
+            Some(money) => money.format(get_minor_units(method, &sp)),
+            None => continue,
+        };
+
+        if normalized != amount {
+            sp.execute(
+                "UPDATE tx_all SET amount = ? WHERE id_num = ?",
+                (normalized, id_num),
+            )?;
+        }
+    }
+
+    let query = format!(
+        "SELECT id_num, {} FROM balance_all",
+        all_methods
+            .iter()
+            .map(|method| format!(r#""{method}""#))
+            .collect::<Vec<String>>()
+            .join(", ")
+    );
+
+    let mut statement = sp.prepare(&query)?;
+    let balances = statement
+        .query_map([], |row| {
+            let mut balance = Vec::new();
+            for i in 0..all_methods.len() {
+                balance.push(Money::from_f64(row.get(i + 1)?));
+            }
+            Ok((row.get::<_, i32>(0)?, balance))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    drop(statement);
+
+    for (id_num, balance) in balances {
+        let set_values = all_methods
+            .iter()
+            .zip(balance.iter())
+            .map(|(method, value)| format!(r#""{}" = "{}""#, method, value.to_stored_string()))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        sp.execute(
+            &format!("UPDATE balance_all SET {set_values} WHERE id_num = {id_num}"),
+            [],
+        )?;
+    }
+
+    set_setting("amounts_normalized", "true", &sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
 /// return the last balance from the db
 fn get_last_balance(sp: &Savepoint, all_methods: &Vec<String>) -> Vec<String> {
     let mut query = format!(
@@ -132,8 +479,39 @@ fn get_last_balance(sp: &Savepoint, all_methods: &Vec<String>) -> Vec<String> {
     final_balance.unwrap()
 }
 
-/// Updates the DB with the new tx method name
-pub fn rename_column(old_name: &str, new_name: &str, conn: &mut Connection) -> Result<()> {
+/// Updates the DB with the new tx method name. The archived transactions and their changes
+/// are renamed too if the archive exists, so they can be moved back under the new name
+pub fn rename_column(
+    old_name: &str,
+    new_name: &str,
+    archive_path: &str,
+    conn: &mut Connection,
+) -> Result<()> {
+    // attaching is not possible inside a savepoint
+    let keep_attached = is_archive_attached(conn);
+    let with_archive = keep_attached || Path::new(archive_path).exists();
+
+    if with_archive {
+        attach_archive(archive_path, conn)?;
+    }
+
+    let result = rename_method_columns(old_name, new_name, with_archive, conn);
+
+    if with_archive && !keep_attached {
+        detach_archive(conn)?;
+    }
+
+    result
+}
+
+/// Does the work of `rename_column` inside a single savepoint so the main db and the archive
+/// are either both renamed or neither of them
+fn rename_method_columns(
+    old_name: &str,
+    new_name: &str,
+    with_archive: bool,
+    conn: &mut Connection,
+) -> Result<()> {
     let sp = conn.savepoint()?;
     let query = format!(r#"ALTER TABLE balance_all RENAME COLUMN "{old_name}" TO "{new_name}""#);
     sp.execute(&query, [])?;
@@ -141,13 +519,42 @@ pub fn rename_column(old_name: &str, new_name: &str, conn: &mut Connection) -> R
     let query = format!(r#"ALTER TABLE changes_all RENAME COLUMN "{old_name}" TO "{new_name}""#);
     sp.execute(&query, [])?;
 
+    replace_tx_method(old_name, new_name, "main", &sp)?;
+
+    if with_archive {
+        if get_archive_changes_columns(&sp)?.contains(&old_name.to_string()) {
+            let query = format!(
+                r#"ALTER TABLE archive.changes_all RENAME COLUMN "{old_name}" TO "{new_name}""#
+            );
+            sp.execute(&query, [])?;
+        }
+        replace_tx_method(old_name, new_name, "archive", &sp)?;
+    }
+
+    sp.execute(
+        "UPDATE minor_units SET tx_method = ? WHERE tx_method = ?",
+        [new_name, old_name],
+    )?;
+
+    sp.execute(
+        "UPDATE hidden_methods SET tx_method = ? WHERE tx_method = ?",
+        [new_name, old_name],
+    )?;
+
+    sp.commit()?;
+    Ok(())
+}
+
+/// Replaces the old tx method name with the new one on every transaction of the given
+/// attached database that uses it
+fn replace_tx_method(old_name: &str, new_name: &str, schema: &str, sp: &Savepoint) -> Result<()> {
     // Follows 3 cases
     // 1. old_name == new_name. Replace old name with the new name
     // 2. If the tx method = old_name to tx_method. Replace the old name part but keep to tx_method
     // 3. If the tx method = tx_method to old_name. Replace the old name part but keep tx_method to
     // last 2 are used for transfer tx
     let query = format!(
-        r#"UPDATE tx_all SET tx_method =
+        r#"UPDATE {schema}.tx_all SET tx_method =
             CASE
                 WHEN tx_method = "{old_name}" THEN "{new_name}"
                 WHEN tx_method LIKE "{old_name} %" THEN REPLACE(tx_method, "{old_name}", "{new_name}")
@@ -157,7 +564,143 @@ pub fn rename_column(old_name: &str, new_name: &str, conn: &mut Connection) -> R
         WHERE tx_method LIKE "%{old_name}%""#
     );
     sp.execute(&query, [])?;
+    Ok(())
+}
+
+/// Merges a tx method into another one. Transactions of the merged method are moved over,
+/// the balances are added together and the merged method is removed.
+/// Used when two tx methods only differ by case or spacing
+pub fn merge_tx_methods(from_method: &str, into_method: &str, conn: &mut Connection) -> Result<()> {
+    let remaining_methods = get_all_tx_methods(conn)
+        .into_iter()
+        .filter(|method| method != from_method)
+        .collect::<Vec<String>>();
+
+    let sp = conn.savepoint()?;
+
+    let query =
+        format!(r#"UPDATE balance_all SET "{into_method}" = "{into_method}" + "{from_method}""#);
+    sp.execute(&query, [])?;
 
+    // only one of the 2 methods can have a change on a transaction, unless it was a transfer between them
+    let query = format!(
+        r#"UPDATE changes_all SET "{into_method}" = "{from_method}" WHERE "{into_method}" = '0.00'"#
+    );
+    sp.execute(&query, [])?;
+
+    replace_tx_method(from_method, into_method, "main", &sp)?;
+
+    sp.execute(
+        "UPDATE tx_templates SET tx_method = ? WHERE tx_method = ?",
+        [into_method, from_method],
+    )?;
+    sp.execute("DELETE FROM minor_units WHERE tx_method = ?", [from_method])?;
+    sp.execute(
+        "DELETE FROM hidden_methods WHERE tx_method = ?",
+        [from_method],
+    )?;
+
+    rebuild_tx_method_tables(&remaining_methods, &sp)?;
+
+    sp.commit()?;
+    Ok(())
+}
+
+/// Merges a tag into another one on every transaction and template that uses it.
+/// The group, the amount bounds and the exclusion of the merged tag are only kept if the other tag has none.
+/// Used when two tags only differ by case or spacing
+pub fn merge_tags(from_tag: &str, into_tag: &str, conn: &mut Connection) -> Result<()> {
+    let sp = conn.savepoint()?;
+    replace_tag(from_tag, into_tag, &sp)?;
+    sp.commit()?;
+    Ok(())
+}
+
+/// Replaces the tag in every table that uses it without committing the savepoint
+fn replace_tag(from_tag: &str, into_tag: &str, sp: &Savepoint) -> Result<()> {
+    for table in ["tx_all", "tx_templates"] {
+        let mut updated_rows = Vec::new();
+
+        {
+            let mut query = sp.prepare(&format!("SELECT id_num, tags FROM {table}"))?;
+            let rows = query.query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?;
+
+            for (id_num, tags) in rows.flatten() {
+                let splitted = tags.split(',').map(|tag| tag.trim()).collect::<Vec<&str>>();
+
+                if !splitted.contains(&from_tag) {
+                    continue;
+                }
+
+                let mut merged_tags: Vec<&str> = Vec::new();
+
+                for tag in splitted {
+                    let tag = if tag == from_tag { into_tag } else { tag };
+
+                    if !merged_tags.contains(&tag) {
+                        merged_tags.push(tag);
+                    }
+                }
+
+                updated_rows.push((id_num, merged_tags.join(", ")));
+            }
+        }
+
+        for (id_num, tags) in updated_rows {
+            sp.execute(
+                &format!("UPDATE {table} SET tags = ? WHERE id_num = ?"),
+                (tags, id_num),
+            )?;
+        }
+    }
+
+    for table in ["tag_groups", "tag_bounds", "excluded_tags"] {
+        sp.execute(
+            &format!("UPDATE OR IGNORE {table} SET tag = ? WHERE tag = ?"),
+            [into_tag, from_tag],
+        )?;
+        sp.execute(&format!("DELETE FROM {table} WHERE tag = ?"), [from_tag])?;
+    }
+
+    Ok(())
+}
+
+/// Merges the tags that only differ by case or spacing into the spelling used by the most
+/// transactions so the summary shows them as a single tag. Only runs once per database
+pub fn merge_tag_case_duplicates(conn: &mut Connection) -> Result<()> {
+    let mut tag_count: HashMap<String, usize> = HashMap::new();
+
+    {
+        let mut query = conn.prepare("SELECT tags FROM tx_all")?;
+        let rows = query.query_map([], |row| row.get::<_, String>(0))?;
+
+        for tags in rows.flatten() {
+            for tag in tags.split(',').map(|tag| tag.trim()) {
+                *tag_count.entry(tag.to_string()).or_default() += 1;
+            }
+        }
+    }
+
+    let collisions = get_name_collisions(&get_all_tags(conn));
+
+    let sp = conn.savepoint()?;
+
+    for group in collisions {
+        // ties go to the spelling that comes first
+        let into_tag = group
+            .iter()
+            .max_by(|a, b| tag_count.get(*a).cmp(&tag_count.get(*b)).then(b.cmp(a)))
+            .unwrap()
+            .to_string();
+
+        for from_tag in group.iter().filter(|tag| **tag != into_tag) {
+            replace_tag(from_tag, &into_tag, &sp)?;
+        }
+    }
+
+    set_setting("tags_merged", "true", &sp)?;
     sp.commit()?;
     Ok(())
 }
@@ -166,14 +709,23 @@ pub fn rename_column(old_name: &str, new_name: &str, conn: &mut Connection) -> R
 pub fn reposition_column(tx_methods: Vec<String>, conn: &mut Connection) -> Result<()> {
     let sp = conn.savepoint()?;
 
+    rebuild_tx_method_tables(&tx_methods, &sp)?;
+
+    sp.commit()?;
+
+    Ok(())
+}
+
+/// Recreates balance_all and changes_all with only the given tx method columns in the given order
+fn rebuild_tx_method_tables(tx_methods: &[String], sp: &Savepoint) -> Result<()> {
     let query = "ALTER TABLE balance_all RENAME TO balance_all_old";
     sp.execute(query, [])?;
 
     let query = "ALTER TABLE changes_all RENAME TO changes_all_old";
     sp.execute(query, [])?;
 
-    create_balances_table(&tx_methods, &sp)?;
-    create_changes_table(&tx_methods, &sp)?;
+    create_balances_table(tx_methods, sp)?;
+    create_changes_table(tx_methods, sp)?;
 
     let columns = tx_methods
         .iter()
@@ -196,7 +748,5 @@ pub fn reposition_column(tx_methods: Vec<String>, conn: &mut Connection) -> Resu
     sp.execute("DROP TABLE balance_all_old", [])?;
     sp.execute("DROP TABLE changes_all_old", [])?;
 
-    sp.commit()?;
-
     Ok(())
 }
@@ -0,0 +1,159 @@
+use crate::home_page::TransactionData;
+use crate::tx_handler::ReminderData;
+use crate::utility::{
+    format_amount, get_last_balances, get_minor_units, get_tag_bounds, Clock, Money,
+};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Contains all data for the popup that summarizes the current month. Built from the
+/// transactions the Home page already loaded for the month so it does not need extra queries
+/// other than the balance, the tag bounds and the reminders
+///
+/// top_tags: `[("Food", 120.0), ("Rent", 100.0)]` up to 3 tags with the highest expense
+///
+/// warnings: `["Lunch: Food expected 5.00 to 50.00"]`
+///
+/// reminders: `["Day 5: Rent"]`
+pub struct DashboardData {
+    month: String,
+    total_balance: f64,
+    income: f64,
+    expense: f64,
+    top_tags: Vec<(String, f64)>,
+    warnings: Vec<String>,
+    reminders: Vec<String>,
+    auto_close_at: Option<Instant>,
+}
+
+impl DashboardData {
+    /// Creates an instance of the struct. The transaction data must be of the clock's month
+    pub fn new(tx_data: &TransactionData, clock: &impl Clock, conn: &Connection) -> Self {
+        let total_balance = get_last_balances(conn)
+            .iter()
+            .map(|balance| Money::parse(balance).unwrap_or_default())
+            .sum::<Money>()
+            .to_f64();
+
+        let (income, expense) = tx_data.get_month_totals();
+
+        let mut tag_expense: HashMap<String, f64> = HashMap::new();
+        let mut warnings = Vec::new();
+        let tag_bounds = get_tag_bounds(conn);
+
+        for tx in tx_data.all_tx.iter() {
+            let amount = tx[3].parse::<f64>().unwrap_or(0.0);
+
+            // refunds only take back from the expense of their tags
+            if tx[4] == "Refund" {
+                for tag in tx[5].split(',').map(|tag| tag.trim()) {
+                    *tag_expense.entry(tag.to_string()).or_insert(0.0) -= amount;
+                }
+                continue;
+            }
+
+            if tx[4] != "Expense" {
+                continue;
+            }
+
+            for tag in tx[5].split(',').map(|tag| tag.trim()) {
+                *tag_expense.entry(tag.to_string()).or_insert(0.0) += amount;
+
+                let (min_amount, max_amount) = match tag_bounds.get(tag) {
+                    Some(bounds) => bounds,
+                    None => continue,
+                };
+
+                let below_min = min_amount.map_or(false, |min| amount < min);
+                let above_max = max_amount.map_or(false, |max| amount > max);
+
+                if below_min || above_max {
+                    let minor_units = get_minor_units(&tx[2], conn);
+                    let min =
+                        min_amount.map_or("-".to_string(), |min| format_amount(min, minor_units));
+                    let max =
+                        max_amount.map_or("-".to_string(), |max| format_amount(max, minor_units));
+                    warnings.push(format!("{}: {tag} expected {min} to {max}", tx[1]));
+                }
+            }
+        }
+
+        let mut top_tags = tag_expense.into_iter().collect::<Vec<(String, f64)>>();
+        top_tags.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top_tags.truncate(3);
+
+        let reminders = ReminderData::new_with_clock(clock, conn)
+            .get_reminders()
+            .iter()
+            .map(|template| {
+                format!(
+                    "Day {}: {}",
+                    template.remind_day.unwrap_or_default(),
+                    template.details
+                )
+            })
+            .collect();
+
+        DashboardData {
+            month: clock.today().format("%B %Y").to_string(),
+            total_balance,
+            income,
+            expense,
+            top_tags,
+            warnings,
+            reminders,
+            auto_close_at: None,
+        }
+    }
+
+    /// Closes the popup on its own once the duration passes
+    pub fn with_auto_close(mut self, duration: Duration) -> Self {
+        self.auto_close_at = Some(Instant::now() + duration);
+        self
+    }
+
+    /// Returns whether the popup was opened with an auto close that has passed
+    pub fn is_expired(&self) -> bool {
+        self.auto_close_at
+            .map_or(false, |close_at| Instant::now() >= close_at)
+    }
+
+    /// Returns whether the popup was opened with an auto close. Only the popup shown
+    /// when the app opens has it
+    pub fn is_auto_closing(&self) -> bool {
+        self.auto_close_at.is_some()
+    }
+
+    pub fn get_month(&self) -> &str {
+        &self.month
+    }
+
+    pub fn get_total_balance(&self) -> f64 {
+        self.total_balance
+    }
+
+    pub fn get_income(&self) -> f64 {
+        self.income
+    }
+
+    pub fn get_expense(&self) -> f64 {
+        self.expense
+    }
+
+    pub fn get_net(&self) -> f64 {
+        self.income - self.expense
+    }
+
+    pub fn get_top_tags(&self) -> &Vec<(String, f64)> {
+        &self.top_tags
+    }
+
+    pub fn get_warnings(&self) -> &Vec<String> {
+        &self.warnings
+    }
+
+    pub fn get_reminders(&self) -> &Vec<String> {
+        &self.reminders
+    }
+}
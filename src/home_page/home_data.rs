@@ -1,7 +1,22 @@
+use crate::db::{get_date_range, remove_all_tx_links};
+use crate::page_handler::HomeFilter;
 use crate::tx_handler::delete_tx;
-use crate::utility::{get_all_changes, get_all_tx_methods, get_all_txs, get_last_balances};
+use crate::utility::{
+    get_all_changes, get_all_tx_methods, get_all_txs, get_last_balances, get_reviewed_txs, Clock,
+    Money, WeekRange,
+};
+use chrono::NaiveDate;
 use rusqlite::{Connection, Result as sqlResult};
 
+/// A row of the Home table when it is grouped by day
+#[derive(Debug, PartialEq)]
+pub enum HomeRow {
+    /// The date of the day and the expense of its transactions after refunds
+    Separator(String, Money),
+    /// The index of the transaction
+    Tx(usize),
+}
+
 /// This struct stores the transaction data, balance, changes and the id num
 /// Data storing format is:
 ///
@@ -12,24 +27,93 @@ use rusqlite::{Connection, Result as sqlResult};
 /// all_changes: `[["↓123.00", "↑123.00"],]`
 ///
 /// all_id_num : `["1", "2", "3",]`
+///
+/// grouped_rows: `[Separator("14-06-2024", 56.20), Tx(0), Tx(1), Separator("15-06-2024", 0.00), Tx(2)]`
+///
+/// row_indexes: `[1, 2, 4]` the grouped row of each transaction
 pub struct TransactionData {
     pub all_tx: Vec<Vec<String>>,
     all_balance: Vec<Vec<String>>,
     all_changes: Vec<Vec<String>>,
     all_id_num: Vec<String>,
+    all_reviewed: Vec<bool>,
+    all_archived: Vec<bool>,
+    grouped_rows: Vec<HomeRow>,
+    row_indexes: Vec<usize>,
 }
 
 impl TransactionData {
     /// Calls the db to fetch transaction data, transaction changes, balances and id numbers
-    /// from the given month and year index
-    pub fn new(month: usize, year: usize, conn: &Connection) -> Self {
+    /// from the given month and year index. Only the transactions that pass the filter are kept
+    pub fn new(month: usize, year: usize, filter: &HomeFilter, conn: &Connection) -> Self {
+        let mut tx_data = TransactionData::new_search(Vec::new(), Vec::new());
+        tx_data.add_month(month, year, filter, None, conn);
+        tx_data.group_by_day();
+        tx_data
+    }
+
+    /// Same as `new` but keeps the transactions of the given week. The months the week
+    /// has days in are loaded so the balances still start from the previous month.
+    /// Days outside of the date range are skipped
+    pub fn new_weekly(week: &WeekRange, filter: &HomeFilter, conn: &Connection) -> Self {
+        let date_range = get_date_range(conn);
+        let mut tx_data = TransactionData::new_search(Vec::new(), Vec::new());
+
+        for (month, year) in week.get_months() {
+            if date_range.contains(year) {
+                let year = date_range.year_index(year);
+                tx_data.add_month(month, year, filter, Some(week), conn);
+            }
+        }
+
+        tx_data.group_by_day();
+        tx_data
+    }
+
+    /// Loads the transactions of the week if the weekly view is on, otherwise of the month and year index
+    pub fn new_period(
+        month: usize,
+        year: usize,
+        week: &Option<WeekRange>,
+        filter: &HomeFilter,
+        conn: &Connection,
+    ) -> Self {
+        match week {
+            Some(week) => TransactionData::new_weekly(week, filter, conn),
+            None => TransactionData::new(month, year, filter, conn),
+        }
+    }
+
+    /// Adds the transactions of the month that pass the filter and are within the week if there is one
+    fn add_month(
+        &mut self,
+        month: usize,
+        year: usize,
+        filter: &HomeFilter,
+        week: Option<&WeekRange>,
+        conn: &Connection,
+    ) {
         let (all_tx, all_balance, all_id_num) = get_all_txs(conn, month, year);
         let all_changes = get_all_changes(month, year, conn);
-        TransactionData {
-            all_tx,
-            all_balance,
-            all_changes,
-            all_id_num,
+        let reviewed_txs = get_reviewed_txs(conn);
+
+        for (index, tx) in all_tx.into_iter().enumerate() {
+            if let Some(week) = week {
+                match NaiveDate::parse_from_str(&tx[0], "%d-%m-%Y") {
+                    Ok(date) if week.contains(date) => {}
+                    _ => continue,
+                }
+            }
+
+            let reviewed = reviewed_txs.contains(&all_id_num[index]);
+
+            if filter.includes(&tx[5], reviewed) {
+                self.all_tx.push(tx);
+                self.all_balance.push(all_balance[index].clone());
+                self.all_changes.push(all_changes[index].clone());
+                self.all_id_num.push(all_id_num[index].clone());
+                self.all_reviewed.push(reviewed);
+            }
         }
     }
 
@@ -39,9 +123,64 @@ impl TransactionData {
             all_balance: Vec::new(),
             all_changes: Vec::new(),
             all_id_num,
+            all_reviewed: Vec::new(),
+            all_archived: Vec::new(),
+            grouped_rows: Vec::new(),
+            row_indexes: Vec::new(),
+        }
+    }
+
+    /// Tags the search results that come from the attached archive
+    pub fn set_archived(&mut self, all_archived: Vec<bool>) {
+        self.all_archived = all_archived;
+    }
+
+    /// Returns whether the search result comes from the attached archive
+    pub fn is_archived(&self, index: usize) -> bool {
+        self.all_archived.get(index).copied().unwrap_or(false)
+    }
+
+    /// Builds the rows of the day grouped Home table. Every day starts with a separator row
+    /// and the grouped row of each transaction is saved so the table selection can stay on the transactions
+    fn group_by_day(&mut self) {
+        let mut separator_index = 0;
+
+        for (index, tx) in self.all_tx.iter().enumerate() {
+            let new_day = match self.grouped_rows.get(separator_index) {
+                Some(HomeRow::Separator(date, _)) => date != &tx[0],
+                _ => true,
+            };
+
+            if new_day {
+                separator_index = self.grouped_rows.len();
+                self.grouped_rows
+                    .push(HomeRow::Separator(tx[0].clone(), Money::ZERO));
+            }
+
+            if let HomeRow::Separator(_, spent) = &mut self.grouped_rows[separator_index] {
+                let amount = Money::parse(&tx[3]).unwrap_or_default();
+                match tx[4].as_str() {
+                    "Expense" => *spent += amount,
+                    "Refund" => *spent -= amount,
+                    _ => {}
+                }
+            }
+
+            self.row_indexes.push(self.grouped_rows.len());
+            self.grouped_rows.push(HomeRow::Tx(index));
         }
     }
 
+    /// Returns the rows of the Home table grouped by day
+    pub fn get_grouped_rows(&self) -> &Vec<HomeRow> {
+        &self.grouped_rows
+    }
+
+    /// Returns the row of the transaction on the Home table grouped by day
+    pub fn get_grouped_index(&self, index: usize) -> usize {
+        self.row_indexes[index]
+    }
+
     /// returns all the Transaction data for the given index. Index is of the
     /// Home Table's selected index
     pub fn get_txs(&self) -> Vec<Vec<String>> {
@@ -56,17 +195,14 @@ impl TransactionData {
     /// Home Table's selected index
     pub fn get_balance(&self, index: usize) -> Vec<String> {
         let mut balance_data = vec!["Balance".to_string()];
+        let mut total_balance = Money::ZERO;
         for i in self.all_balance[index].iter() {
-            balance_data.push(format!("{:.2}", i.parse::<f64>().unwrap()));
-        }
-
-        let mut total_balance: f64 = 0.0;
-        for i in balance_data.iter().skip(1) {
-            let int_bal = i.parse::<f64>().unwrap();
+            let int_bal = Money::parse(i).unwrap();
             total_balance += int_bal;
+            balance_data.push(int_bal.to_string());
         }
-        let formatted_total_balance = format!("{:.2}", total_balance);
-        balance_data.push(formatted_total_balance);
+
+        balance_data.push(total_balance.to_string());
         balance_data
     }
 
@@ -75,17 +211,14 @@ impl TransactionData {
     pub fn get_last_balance(&self, conn: &Connection) -> Vec<String> {
         let mut balance_data = vec!["Balance".to_string()];
         let db_data = get_last_balances(conn);
+        let mut total_balance = Money::ZERO;
         for i in db_data.iter() {
-            balance_data.push(format!("{:.2}", i.parse::<f64>().unwrap()));
-        }
-
-        let mut total_balance: f64 = 0.0;
-        for i in balance_data.iter().skip(1) {
-            let int_bal = i.parse::<f64>().unwrap();
+            let int_bal = Money::parse(i).unwrap();
             total_balance += int_bal;
+            balance_data.push(int_bal.to_string());
         }
-        let formatted_total_balance = format!("{:.2}", total_balance);
-        balance_data.push(formatted_total_balance);
+
+        balance_data.push(total_balance.to_string());
         balance_data
     }
 
@@ -112,15 +245,23 @@ impl TransactionData {
         changes_data
     }
 
+    /// Returns whether each transaction was marked as reviewed
+    pub fn get_reviewed(&self) -> &Vec<bool> {
+        &self.all_reviewed
+    }
+
     /// Returns the id_num of the tx of the given index
     pub fn get_id_num(&self, index: usize) -> i32 {
         self.all_id_num[index].parse::<i32>().unwrap().to_owned()
     }
 
-    /// gets the ID Number of the selected table row and calls the function to delete a transaction from the database
+    /// gets the ID Number of the selected table row and calls the function to delete a transaction from the database.
+    /// The link of a deleted refund is removed while refunds of a deleted expense keep theirs.
+    /// Every link to other transactions is removed
     pub fn del_tx(&self, index: usize, conn: &mut Connection) -> sqlResult<()> {
         let target_id = self.get_id_num(index);
-        delete_tx(target_id as usize, conn)
+        delete_tx(target_id as usize, conn)?;
+        remove_all_tx_links(target_id, conn)
     }
 
     /// returns total incomes for the selected month by going through all the tx saved in the struct
@@ -142,13 +283,13 @@ impl TransactionData {
         }
 
         // Iterate over all transactions and accumulate the total income.
-        let mut total_income = 0.0_f64;
+        let mut total_income = Money::ZERO;
         for tx in self.all_tx.iter() {
             let amount = &tx[3];
             let tx_type = &tx[4];
 
             if tx_type == "Income" {
-                total_income += amount.parse::<f64>().unwrap();
+                total_income += Money::parse(amount).unwrap();
             }
 
             if stopping_index == 0 {
@@ -161,7 +302,7 @@ impl TransactionData {
         }
 
         // Add the computed total income to the output vector.
-        final_income.push(format!("{:.2}", total_income));
+        final_income.push(total_income.to_string());
         final_income
     }
 
@@ -188,13 +329,15 @@ impl TransactionData {
         }
 
         // Iterate over all transactions and accumulate the total expense.
-        let mut total_expense = 0.0_f64;
+        let mut total_expense = Money::ZERO;
         for tx in self.all_tx.iter() {
             let amount = &tx[3];
             let tx_type = &tx[4];
 
             if tx_type == "Expense" {
-                total_expense += amount.parse::<f64>().unwrap();
+                total_expense += Money::parse(amount).unwrap();
+            } else if tx_type == "Refund" {
+                total_expense -= Money::parse(amount).unwrap();
             }
 
             if stopping_index == 0 {
@@ -207,7 +350,53 @@ impl TransactionData {
         }
 
         // Add the computed total expense to the output vector.
-        final_expense.push(format!("{:.2}", total_expense));
+        final_expense.push(total_expense.to_string());
         final_expense
     }
+
+    /// Returns the total income and total expense of all the transactions saved in the struct
+    pub fn get_month_totals(&self) -> (f64, f64) {
+        let mut total_income = Money::ZERO;
+        let mut total_expense = Money::ZERO;
+
+        for tx in self.all_tx.iter() {
+            let amount = Money::parse(&tx[3]).unwrap();
+            match tx[4].as_str() {
+                "Income" => total_income += amount,
+                "Expense" => total_expense += amount,
+                "Refund" => total_expense -= amount,
+                _ => {}
+            }
+        }
+        (total_income.to_f64(), total_expense.to_f64())
+    }
+
+    /// Returns the amount of days covered by the given week, the net of all incomes and
+    /// expenses within it and the net per day. If the week includes the current date,
+    /// only the elapsed days are counted for the daily net.
+    pub fn get_weekly_net(&self, week: &WeekRange, clock: &impl Clock) -> (i64, f64, f64) {
+        self.get_period_net(week.get_start(), week.get_end(), clock)
+    }
+
+    /// Returns the days between the dates, the net of the saved transactions and the net per day.
+    /// Only the elapsed days are counted if the current date is within the dates
+    fn get_period_net(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        clock: &impl Clock,
+    ) -> (i64, f64, f64) {
+        let today = clock.today();
+
+        let days = if today >= start_date && today <= end_date {
+            today.signed_duration_since(start_date).num_days() + 1
+        } else {
+            end_date.signed_duration_since(start_date).num_days() + 1
+        };
+
+        let (total_income, total_expense) = self.get_month_totals();
+        let net = total_income - total_expense;
+
+        (days, net, net / days as f64)
+    }
 }
@@ -1,17 +1,23 @@
+use crate::home_page::{HomeRow, TransactionData};
 use crate::page_handler::{
-    HomeTab, IndexedData, TableData, BACKGROUND, BLUE, BOX, HEADER, RED, SELECTED, TEXT,
+    DisplaySettings, GaugeSettings, HomeFilter, HomeTab, IndexedData, NameCache, TableData,
+    BACKGROUND, BLUE, BOX, GREEN, HEADER, RED, SELECTED, TEXT, YELLOW,
+};
+use crate::utility::{
+    create_tab, get_shown_details, get_table_position, main_block, styled_block, WeekRange,
+    TX_TABLE_HEADER,
 };
-use crate::utility::{create_tab, get_all_tx_methods, main_block, styled_block};
 use ratatui::backend::Backend;
 use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::style::{Modifier, Style};
-use ratatui::widgets::{Cell, Row, Table};
+use ratatui::text::Span;
+use ratatui::widgets::{Cell, Gauge, Paragraph, Row, Table};
 use ratatui::Frame;
 use rusqlite::Connection;
-use thousands::Separable;
 
 /// The function draws the Home page of the interface.
 #[cfg(not(tarpaulin_include))]
+#[allow(clippy::too_many_arguments)]
 pub fn home_ui<B: Backend>(
     f: &mut Frame<B>,
     months: &IndexedData,
@@ -20,23 +26,28 @@ pub fn home_ui<B: Backend>(
     balance: &mut [Vec<String>],
     current_tab: &HomeTab,
     width_data: &mut [Constraint],
+    daily_net: Option<(i64, f64, f64)>,
+    month_totals: (f64, f64),
+    tx_data: &TransactionData,
+    home_filter: &HomeFilter,
+    week: Option<&WeekRange>,
+    gauge_settings: &GaugeSettings,
+    display_settings: &DisplaySettings,
+    highlighted: bool,
+    names: &NameCache,
     conn: &Connection,
 ) {
-    let all_methods = get_all_tx_methods(conn);
+    let all_methods = names.get_tx_methods(conn);
+    let date_format = display_settings.get_date_format();
+    let amount_format = display_settings.get_amount_format();
     let size = f.size();
 
     // Used to highlight Changes on Balance section of Home Page
     let selected_style_income = Style::default().fg(BLUE).add_modifier(Modifier::REVERSED);
     let selected_style_expense = Style::default().fg(RED).add_modifier(Modifier::REVERSED);
 
-    let mut table_name = "Transactions".to_string();
-
-    if !table.items.is_empty() {
-        table_name = format!("Transactions: {}", table.items.len());
-    }
-
     // Transaction widget's top row/header to highlight what each data will mean
-    let header_cells = ["Date", "Details", "TX Method", "Amount", "Type", "Tags"]
+    let header_cells = TX_TABLE_HEADER
         .iter()
         .map(|h| Cell::from(*h).style(Style::default().fg(BACKGROUND)));
 
@@ -45,15 +56,66 @@ pub fn home_ui<B: Backend>(
         .height(1)
         .bottom_margin(0);
 
+    let grouped = display_settings.is_day_grouped();
+    let reviewed = tx_data.get_reviewed();
+
     // iter through table data and turn them into rows and columns
-    let rows = table.items.iter().map(|item| {
+    let tx_row = |row_index: usize, item: &Vec<String>| {
         let height = 1;
-        let cells = item.iter().map(|c| Cell::from(c.separate_with_commas()));
+        let row_reviewed = reviewed.get(row_index).copied().unwrap_or(false);
+        let amount_format = &amount_format;
+        let date_format = &date_format;
+        let cells = item.iter().enumerate().map(move |(index, c)| {
+            if index == 0 {
+                Cell::from(date_format.format_table_date(c))
+            } else if index == 1 && row_reviewed {
+                // reviewed transactions are marked before the details
+                Cell::from(format!(
+                    "✓ {}",
+                    amount_format.localize(&get_shown_details(c))
+                ))
+            } else if index == 1 {
+                Cell::from(amount_format.localize(&get_shown_details(c)))
+            } else {
+                Cell::from(amount_format.localize(c))
+            }
+        });
         Row::new(cells)
             .height(height as u16)
             .bottom_margin(0)
             .style(Style::default().bg(BACKGROUND).fg(TEXT))
-    });
+    };
+
+    // when grouped every day starts with a separator row that can not be selected
+    let rows = if grouped {
+        tx_data
+            .get_grouped_rows()
+            .iter()
+            .map(|row| match row {
+                HomeRow::Separator(date, spent) => {
+                    let text = format!(
+                        "── {} · spent {} ──",
+                        date_format.format_table_date(date),
+                        amount_format.localize(&spent.to_string())
+                    );
+                    Row::new(vec![Cell::from("──"), Cell::from(text)]).style(
+                        Style::default()
+                            .bg(BACKGROUND)
+                            .fg(BLUE)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                }
+                HomeRow::Tx(index) => tx_row(*index, &table.items[*index]),
+            })
+            .collect::<Vec<Row>>()
+    } else {
+        table
+            .items
+            .iter()
+            .enumerate()
+            .map(|(row_index, item)| tx_row(row_index, item))
+            .collect()
+    };
 
     // Decides how many chunks of spaces in the terminal will be.
     // Each constraint creates an empty space in the terminal with the given
@@ -65,6 +127,7 @@ pub fn home_ui<B: Backend>(
     // - The year tab
     // - The month tab
     // - The transaction list/Table
+    // - The footer with the period info
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -75,6 +138,7 @@ pub fn home_ui<B: Backend>(
                 Constraint::Length(3),
                 Constraint::Length(3),
                 Constraint::Min(0),
+                Constraint::Length(3),
             ]
             .as_ref(),
         )
@@ -82,9 +146,34 @@ pub fn home_ui<B: Backend>(
 
     f.render_widget(main_block(), size);
 
-    let mut month_tab = create_tab(months, "Months");
+    let selected_row = table.state.selected().map(|index| {
+        if grouped {
+            tx_data.get_grouped_index(index)
+        } else {
+            index
+        }
+    });
+
+    // long tables show where the selection is instead of only the transaction count
+    let table_name = match get_table_position(
+        selected_row,
+        table.state.offset(),
+        rows.len(),
+        chunks[3].height,
+    ) {
+        Some(position) => format!("Transactions: {position}"),
+        None if table.items.is_empty() => "Transactions".to_string(),
+        None => format!("Transactions: {}", table.items.len()),
+    };
 
-    let mut year_tab = create_tab(years, "Years");
+    // the weekly view shows the days of the week on the month tab
+    let month_title = match week {
+        Some(week) => format!("Months · Week {}", week.get_label()),
+        None => "Months".to_string(),
+    };
+    let mut month_tab = create_tab(months, &month_title, chunks[2].width);
+
+    let mut year_tab = create_tab(years, "Years", chunks[1].width);
 
     // set up the table columns and their size
     // resizing the table headers to match a % of the
@@ -106,7 +195,7 @@ pub fn home_ui<B: Backend>(
     let bal_data = balance.iter().map(|item| {
         let height = 1;
         let cells = item.iter().map(|c| {
-            let c = c.separate_with_commas();
+            let c = amount_format.localize(c);
             if c.contains('↑') {
                 Cell::from(c).style(Style::default().fg(BLUE))
             } else if c.contains('↓') {
@@ -136,6 +225,82 @@ pub fn home_ui<B: Backend>(
         .widths(width_data)
         .style(Style::default().fg(BOX));
 
+    // the covered days, the net and the net per day are only shown while a non-monthly
+    // range such as a week is active
+    let mut footer_info = Vec::new();
+    if let Some((days, net, per_day)) = daily_net {
+        footer_info.push(format!(
+            "{days} days · net {} · {}/day",
+            amount_format.format_amount(net, 2),
+            amount_format.format_amount(per_day, 2)
+        ));
+    }
+
+    // the filtered table only holds the transactions that still need attention
+    match home_filter {
+        HomeFilter::All => {}
+        HomeFilter::Untagged => {
+            footer_info.push(format!("Untagged: {} remaining", table.items.len()))
+        }
+        HomeFilter::Cleanup => {
+            footer_info.push(format!("Cleanup: {} remaining", table.items.len()))
+        }
+    }
+    let footer_area = Paragraph::new(footer_info.join(" · "))
+        .style(Style::default().bg(BACKGROUND).fg(TEXT))
+        .block(styled_block(""));
+
+    // the footer gets divided to make space for the expense/income gauge if it's not hidden
+    let gauge_hidden = gauge_settings.is_hidden();
+    let footer_chunks = if gauge_hidden {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(100)].as_ref())
+            .split(chunks[4])
+    } else {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(chunks[4])
+    };
+
+    // expense shown as a fraction of the income. If there is no income,
+    // the absolute expense is shown instead
+    let (total_income, total_expense) = month_totals;
+    let (warning_ratio, danger_ratio) = gauge_settings.get_ratios();
+
+    let (ratio, gauge_label) = if total_income == 0.0 {
+        let ratio = if total_expense > 0.0 { 1.0 } else { 0.0 };
+        (
+            ratio,
+            format!(
+                "Expense: {} (no income)",
+                amount_format.format_amount(total_expense, 2)
+            ),
+        )
+    } else {
+        let ratio = total_expense / total_income;
+        let spent = amount_format.localize_decimal(&format!("{:.2}", ratio * 100.0));
+        (ratio, format!("{spent}% of income spent"))
+    };
+
+    let gauge_color = if total_income == 0.0 && total_expense > 0.0 || ratio >= danger_ratio {
+        RED
+    } else if ratio >= warning_ratio {
+        YELLOW
+    } else {
+        GREEN
+    };
+
+    let gauge_area = Gauge::default()
+        .block(styled_block("Expense / Income"))
+        .gauge_style(Style::default().fg(gauge_color).bg(BACKGROUND))
+        .ratio(ratio.clamp(0.0, 1.0))
+        .label(Span::styled(
+            gauge_label,
+            Style::default().fg(TEXT).add_modifier(Modifier::BOLD),
+        ));
+
     match current_tab {
         // previously added a black block to year and month widget if a value is not selected
         // Now we will turn that black block into green if a value is selected
@@ -152,9 +317,17 @@ pub fn home_ui<B: Backend>(
         HomeTab::Table => {
             if let Some(a) = table.state.selected() {
                 table_area = table_area.highlight_symbol(">> ");
-                if table.items[a][4] == "Expense" {
+                if highlighted {
+                    // a transaction opened from another page stands out for a moment
+                    table_area = table_area.highlight_style(
+                        Style::default()
+                            .bg(YELLOW)
+                            .fg(BACKGROUND)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else if table.items[a][4] == "Expense" {
                     table_area = table_area.highlight_style(selected_style_expense)
-                } else if table.items[a][4] == "Income" {
+                } else if table.items[a][4] == "Income" || table.items[a][4] == "Refund" {
                     table_area = table_area.highlight_style(selected_style_income)
                 } else if table.items[a][4] == "Transfer" {
                     table_area = table_area.highlight_style(Style::default().bg(SELECTED))
@@ -168,7 +341,24 @@ pub fn home_ui<B: Backend>(
     f.render_widget(balance_area, chunks[0]);
     f.render_widget(month_tab, chunks[2]);
     f.render_widget(year_tab, chunks[1]);
+    f.render_widget(footer_area, footer_chunks[0]);
+    if !gauge_hidden {
+        f.render_widget(gauge_area, footer_chunks[1]);
+    }
 
-    // this one is different because the Transaction widget interface works differently
-    f.render_stateful_widget(table_area, chunks[3], &mut table.state)
+    // this one is different because the Transaction widget interface works differently.
+    // The selection is kept on the transactions so it is moved to the grouped row before rendering
+    if grouped {
+        let mut grouped_state = table.state.clone();
+        grouped_state.select(
+            table
+                .state
+                .selected()
+                .map(|index| tx_data.get_grouped_index(index)),
+        );
+        f.render_stateful_widget(table_area, chunks[3], &mut grouped_state);
+        *table.state.offset_mut() = grouped_state.offset();
+    } else {
+        f.render_stateful_widget(table_area, chunks[3], &mut table.state)
+    }
 }
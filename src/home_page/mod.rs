@@ -1,5 +1,9 @@
+mod dashboard;
 mod home_data;
 mod home_ui;
+mod month_badges;
 
-pub use home_data::TransactionData;
+pub use dashboard::DashboardData;
+pub use home_data::{HomeRow, TransactionData};
 pub use home_ui::home_ui;
+pub use month_badges::MonthBadges;
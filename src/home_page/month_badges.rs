@@ -0,0 +1,88 @@
+use crate::db::{get_date_range, get_month_viewed, set_month_viewed};
+use crate::utility::{get_db_modified_time, get_month_tx_counts};
+use rusqlite::Connection;
+use std::time::SystemTime;
+
+/// Stores the transaction count of every month of the selected Home year and whether the
+/// month got new transactions since it was last viewed. The counts are only reloaded
+/// once the year changes or the db gets modified.
+///
+/// counts: `[(3, 25), (0, 0),]` transaction count and the highest id_num of the month
+///
+/// changed: `[false, true,]`
+pub struct MonthBadges {
+    year_index: Option<usize>,
+    modified_time: Option<SystemTime>,
+    counts: Vec<(usize, i64)>,
+    changed: Vec<bool>,
+}
+
+impl Default for MonthBadges {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MonthBadges {
+    pub fn new() -> Self {
+        MonthBadges {
+            year_index: None,
+            modified_time: None,
+            counts: vec![(0, 0); 12],
+            changed: vec![false; 12],
+        }
+    }
+
+    /// Reloads the counts if the cache is outdated and marks the selected month as viewed
+    pub fn update(&mut self, month_index: usize, year_index: usize, conn: &Connection) {
+        let modified_time = get_db_modified_time(conn);
+
+        if self.year_index != Some(year_index) || self.modified_time != modified_time {
+            self.reload(year_index, conn);
+        }
+
+        if self.changed[month_index] {
+            let year = get_date_range(conn).year_at(year_index);
+            let last_id = self.counts[month_index].1;
+
+            if set_month_viewed(month_index + 1, year, last_id, conn).is_ok() {
+                self.changed[month_index] = false;
+            }
+        }
+    }
+
+    /// Fetches the counts of the year and compares them to the last viewed state of each month.
+    /// Months that were never viewed start from their current state so only transactions
+    /// added later get marked
+    fn reload(&mut self, year_index: usize, conn: &Connection) {
+        let year = get_date_range(conn).year_at(year_index);
+        self.counts = get_month_tx_counts(year, conn);
+
+        for (index, (_, last_id)) in self.counts.iter().enumerate() {
+            self.changed[index] = match get_month_viewed(index + 1, year, conn) {
+                Some(viewed_id) => *last_id > viewed_id,
+                None => {
+                    set_month_viewed(index + 1, year, *last_id, conn).ok();
+                    false
+                }
+            };
+        }
+
+        self.year_index = Some(year_index);
+        self.modified_time = get_db_modified_time(conn);
+    }
+
+    /// Returns the text to show after each month title. Months without any transactions have none,
+    /// months with new transactions since the last view end with a *
+    pub fn get_suffixes(&self) -> Vec<String> {
+        self.counts
+            .iter()
+            .zip(self.changed.iter())
+            .map(|((count, _), changed)| match (count, changed) {
+                (0, _) => String::new(),
+                (_, true) => format!(" ({count})*"),
+                (_, false) => format!(" ({count})"),
+            })
+            .collect()
+    }
+}
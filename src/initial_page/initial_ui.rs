@@ -83,7 +83,7 @@ Q: Quit
 
 Home Page:
 D: Delete selected Transaction
-J: Add/Rename/Reposition Transaction Methods
+J: Manage Transaction Methods, Tag Groups and the archive
 E: Edit Selected Transaction
 
 Transaction Field Selection:
@@ -10,7 +10,7 @@ pub fn add_tx_keys(handler: &mut InputKeyHandler) -> Option<HandlingOutput> {
         // we don't want to move this interface while the popup is on
         PopupState::Nothing => match handler.add_tx_tab {
             TxTab::Nothing => match handler.key.code {
-                KeyCode::Char('q') => return Some(HandlingOutput::QuitUi),
+                KeyCode::Char('q') => return handler.quit_app(),
                 KeyCode::Char('f') => handler.go_home(),
                 KeyCode::Char('r') => handler.go_chart(),
                 KeyCode::Char('z') => handler.go_summary(),
@@ -18,6 +18,8 @@ pub fn add_tx_keys(handler: &mut InputKeyHandler) -> Option<HandlingOutput> {
                 KeyCode::Char('s') => handler.add_tx(),
                 KeyCode::Char('w') => handler.go_search(),
                 KeyCode::Char('c') => handler.clear_input(),
+                KeyCode::Char('l') => handler.do_refund_picker_popup(),
+                KeyCode::Esc | KeyCode::Backspace => handler.go_back(),
                 KeyCode::Enter => handler.select_date_field(),
                 KeyCode::Char(c) => {
                     if c.is_numeric() {
@@ -44,6 +46,12 @@ pub fn add_tx_keys(handler: &mut InputKeyHandler) -> Option<HandlingOutput> {
                 },
             },
         },
+        PopupState::TxConfirmation(..) => handler.handle_confirmation_popup(),
+        PopupState::RefundPicker(_) => handler.handle_refund_picker_popup(),
+        PopupState::MethodPicker(_) => handler.handle_method_picker_popup(),
+        PopupState::DetailsTemplates(_) => handler.handle_details_template_popup(),
+        PopupState::Calendar(_) => handler.handle_calendar_popup(),
+        PopupState::SubmitErrors(_) => handler.handle_submit_errors_popup(),
         _ => handler.do_empty_popup(),
     }
 
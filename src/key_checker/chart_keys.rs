@@ -1,3 +1,4 @@
+use crate::key_checker::summary_keys::is_date_char;
 use crate::key_checker::InputKeyHandler;
 use crate::outputs::HandlingOutput;
 use crate::page_handler::PopupState;
@@ -8,19 +9,40 @@ use crossterm::event::KeyCode;
 pub fn chart_keys(handler: &mut InputKeyHandler) -> Option<HandlingOutput> {
     match handler.popup {
         PopupState::Nothing => match handler.key.code {
+            // the date boxes of the Range mode take the date characters and Backspace
+            KeyCode::Char(c) if handler.is_chart_range_selected() && is_date_char(c) => {
+                handler.edit_chart_range(Some(c))
+            }
+            KeyCode::Backspace if handler.is_chart_range_selected() => {
+                handler.edit_chart_range(None)
+            }
+            KeyCode::Enter if handler.is_chart_range_selected() => handler.verify_chart_range(),
+            // the numbers jump to a month on the Months tab and toggle the tx methods elsewhere
+            KeyCode::Char(c) if handler.is_months_tab_selected() && c.is_ascii_digit() => {
+                handler.do_month_jump(c)
+            }
+            KeyCode::Char('.') => handler.do_current_month_jump(),
             KeyCode::Char('a') => handler.go_add_tx(),
             KeyCode::Char('z') => handler.go_summary(),
-            KeyCode::Char('q') => return Some(HandlingOutput::QuitUi),
+            KeyCode::Char('q') => return handler.quit_app(),
             KeyCode::Char('f') => handler.go_home(),
             KeyCode::Char('h') => handler.do_help_popup(),
             KeyCode::Char('r') => handler.do_chart_hidden_mode(),
             KeyCode::Char('w') => handler.go_search(),
+            KeyCode::Char('b') => handler.do_balance_date_popup(),
+            KeyCode::Char('v') => handler.do_chart_view_toggle(),
+            KeyCode::Char('1'..='9') => handler.do_chart_method_toggle(),
+            KeyCode::Char('t') => handler.do_chart_total_toggle(),
+            KeyCode::Char('g') => handler.do_chart_group_stack_toggle(),
+            KeyCode::Char('c') => handler.do_chart_weekly_toggle(),
+            KeyCode::Esc | KeyCode::Backspace => handler.go_back(),
             KeyCode::Right => handler.handle_right_arrow(),
             KeyCode::Left => handler.handle_left_arrow(),
             KeyCode::Up => handler.handle_up_arrow(),
             KeyCode::Down => handler.handle_down_arrow(),
             _ => {}
         },
+        PopupState::BalanceDate(_) => handler.handle_balance_date_popup(),
         _ => handler.do_empty_popup(),
     }
     None
@@ -1,32 +1,60 @@
 use crate::key_checker::InputKeyHandler;
 use crate::outputs::HandlingOutput;
 use crate::page_handler::PopupState;
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyModifiers};
 
 /// Tracks the keys of the Home page and calls relevant function based on it
 #[cfg(not(tarpaulin_include))]
 pub fn home_keys(handler: &mut InputKeyHandler) -> Option<HandlingOutput> {
     match handler.popup {
         PopupState::Nothing => match handler.key.code {
-            KeyCode::Char('q') => return Some(HandlingOutput::QuitUi),
+            KeyCode::Char(c) if handler.is_months_tab_selected() && c.is_ascii_digit() => {
+                handler.do_month_jump(c)
+            }
+            KeyCode::Char('.') => handler.do_current_month_jump(),
+            KeyCode::Char('q') => return handler.quit_app(),
             KeyCode::Char('a') => handler.go_add_tx(),
             KeyCode::Char('r') => handler.go_chart(),
-            KeyCode::Char('j') => return Some(HandlingOutput::TakeUserInput),
+            KeyCode::Char('j') => return handler.take_user_input(),
             KeyCode::Char('h') => handler.do_help_popup(),
             KeyCode::Char('z') => handler.go_summary(),
             KeyCode::Char('w') => handler.go_search(),
             KeyCode::Char('e') => handler.edit_tx(),
             KeyCode::Char('d') => handler.do_deletion_popup(),
+            KeyCode::Char('g') => handler.do_home_gauge_toggle(),
+            KeyCode::Char('y') if handler.key.modifiers.contains(KeyModifiers::CONTROL) => {
+                handler.copy_selected_tx()
+            }
+            KeyCode::Char('Y') => handler.copy_table(),
+            KeyCode::Char('y') => handler.do_repeat_popup(),
+            KeyCode::Char('u') => handler.do_home_filter(),
+            KeyCode::Char('c') => handler.do_home_week_toggle(),
+            KeyCode::Char('v') => handler.do_toggle_reviewed(),
+            KeyCode::Char('t') => handler.do_tag_edit_popup(),
+            KeyCode::Char('b') => handler.do_balance_date_popup(),
+            KeyCode::Char('o') => handler.do_dashboard_popup(),
+            KeyCode::Char('m') => handler.do_hidden_methods_toggle(),
+            KeyCode::Char('i') => handler.do_tx_details_popup(),
+            KeyCode::Char('n') => handler.refund_tx(),
+            KeyCode::Char('p') => handler.do_day_groups_toggle(),
+            KeyCode::Char('k') => handler.do_read_only_popup(),
+            KeyCode::Esc | KeyCode::Backspace => handler.go_back(),
             KeyCode::Right => handler.handle_right_arrow(),
             KeyCode::Left => handler.handle_left_arrow(),
             KeyCode::Up => handler.handle_up_arrow(),
             KeyCode::Down => handler.handle_down_arrow(),
             _ => {}
         },
-        PopupState::TxDeletion => match handler.key.code {
-            KeyCode::Left | KeyCode::Right | KeyCode::Enter => handler.handle_deletion_popup(),
-            _ => {}
-        },
+        PopupState::TxDeletion(_) => handler.handle_deletion_popup(),
+        PopupState::RepeatTx(_) => handler.handle_repeat_popup(),
+        PopupState::Reminders(_) => handler.handle_reminder_popup(),
+        PopupState::TagEdit(_) => handler.handle_tag_edit_popup(),
+        PopupState::NewTags(_) => handler.handle_new_tags_popup(),
+        PopupState::BalanceDate(_) => handler.handle_balance_date_popup(),
+        PopupState::Dashboard(_) => handler.handle_dashboard_popup(),
+        PopupState::TxDetails(_) => handler.handle_tx_details_popup(),
+        PopupState::LinkPicker(..) => handler.handle_link_picker_popup(),
+        PopupState::ReadOnlyToggle(..) => return handler.handle_read_only_popup(),
         _ => handler.do_empty_popup(),
     }
     None
@@ -8,8 +8,11 @@ use crossterm::event::KeyCode;
 pub fn initial_keys(handler: &mut InputKeyHandler) -> Option<HandlingOutput> {
     match handler.popup {
         PopupState::Nothing => match handler.key.code {
-            KeyCode::Char('q') => return Some(HandlingOutput::QuitUi),
-            _ => handler.go_home(),
+            KeyCode::Char('q') => return handler.quit_app(),
+            _ => {
+                handler.go_home();
+                handler.do_startup_popup();
+            }
         },
         PopupState::NewUpdate(_) => match handler.key.code {
             KeyCode::Enter => {
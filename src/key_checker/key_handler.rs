@@ -1,16 +1,42 @@
 use crate::chart_page::ChartData;
-use crate::home_page::TransactionData;
+use crate::db::{
+    add_tx_link, attach_archive, detach_archive, get_bool_setting, get_date_range,
+    get_details_templates, get_f64_setting, get_setting, get_week_start, is_archive_attached,
+    remove_tx_link, set_bool_setting, set_list_setting, set_tx_reviewed, set_usize_setting,
+    ARCHIVE_DB, RELATED_LINK,
+};
+use crate::home_page::{DashboardData, TransactionData};
 use crate::outputs::TxType;
-use crate::outputs::{HandlingOutput, TxUpdateError, VerifyingOutput};
+use crate::outputs::{
+    AmountStepSize, DateStepSize, HandlingOutput, StepType, TxUpdateError, VerifyingOutput,
+};
 use crate::page_handler::{
-    ChartTab, CurrentUi, DeletionStatus, HomeTab, IndexedData, PopupState, SortingType, SummaryTab,
-    TableData, TxTab,
+    BackgroundTask, ChartTab, ChartView, ConfirmStatus, CurrentUi, DeletionStatus, DisplaySettings,
+    GaugeSettings, HomeFilter, HomeHighlight, HomeTab, IndexedData, MonthJump, NameCache,
+    NavigationStack, PageState, PopupState, QuitStatus, SearchGrouping, SortOrder, SortingType,
+    StatusMessage, SummaryTab, TableData, Toasts, TxTab, TypeAhead, READ_ONLY_MESSAGE,
+};
+use crate::summary_page::{
+    get_method_table_header, get_tag_table_header, ExcludedTagsData, SummaryData, SummaryRangeData,
+    RANGE_MODE,
+};
+use crate::tx_handler::{
+    BalanceDateData, CalendarData, DetailsTemplateData, MethodPickerData, ReminderData,
+    RepeatTxData, TagEditData, TxData, TxDeletionData, TxDetailsData, TxPickerData,
 };
-use crate::summary_page::SummaryData;
-use crate::tx_handler::TxData;
-use crate::utility::sort_table_data;
-use crossterm::event::{KeyCode, KeyEvent};
-use rusqlite::Connection;
+use crate::utility::{
+    align_week, copy_to_clipboard, get_amount_format, get_close_matches, get_date_format,
+    get_minor_units, get_refund_ids, get_refundable_amount, get_searched_id, get_single_line,
+    get_summary_table_tsv, get_tag_groups, get_tx_by_id, get_tx_table_tsv, group_search_txs,
+    select_month_of, sort_table_data_by, step_week, Clock, InstanceLock, LockStatus, Money,
+    SystemClock, WeekRange, LOCK_FILE,
+};
+use chrono::{Datelike, NaiveDate};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use rusqlite::{Connection, OpenFlags};
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::time::Duration;
 
 /// Stores all the data that is required to handle
 /// every single possible key press event from the
@@ -27,6 +53,7 @@ pub struct InputKeyHandler<'a> {
     all_tx_data: &'a mut TransactionData,
     chart_data: &'a mut ChartData,
     summary_data: &'a mut SummaryData,
+    summary_range: &'a mut SummaryRangeData,
     table: &'a mut TableData,
     summary_table: &'a mut TableData,
     home_months: &'a mut IndexedData,
@@ -34,24 +61,51 @@ pub struct InputKeyHandler<'a> {
     chart_months: &'a mut IndexedData,
     chart_years: &'a mut IndexedData,
     chart_modes: &'a mut IndexedData,
+    chart_tags: &'a mut IndexedData,
+    chart_compare_years: &'a mut IndexedData,
+    chart_range: &'a mut SummaryRangeData,
     summary_months: &'a mut IndexedData,
     summary_years: &'a mut IndexedData,
     summary_modes: &'a mut IndexedData,
+    summary_methods: &'a mut IndexedData,
     summary_sort: &'a mut SortingType,
+    summary_sort_order: &'a mut SortOrder,
     search_data: &'a mut TxData,
     pub search_tab: &'a mut TxTab,
     search_table: &'a mut TableData,
     search_txs: &'a mut TransactionData,
+    search_grouping: &'a mut SearchGrouping,
     total_tags: usize,
     chart_index: &'a mut Option<f64>,
     chart_hidden_mode: &'a mut bool,
+    chart_view: &'a mut ChartView,
+    chart_hidden_methods: &'a mut HashSet<String>,
+    chart_show_total: &'a mut bool,
+    chart_group_stack: &'a mut bool,
+    chart_weekly: &'a mut bool,
     summary_hidden_mode: &'a mut bool,
+    summary_grouped: &'a mut bool,
+    summary_compare: &'a mut bool,
+    summary_type_ahead: &'a mut TypeAhead,
+    month_jump: &'a mut MonthJump,
+    home_highlight: &'a mut HomeHighlight,
+    home_filter: &'a mut HomeFilter,
+    home_week: &'a mut Option<WeekRange>,
+    gauge_settings: &'a mut GaugeSettings,
+    display_settings: &'a mut DisplaySettings,
+    navigation: &'a mut NavigationStack,
     deletion_status: &'a mut DeletionStatus,
+    background_task: &'a mut Option<BackgroundTask>,
+    toasts: &'a Toasts,
+    name_cache: &'a NameCache,
+    read_only: &'a mut bool,
+    instance_lock: &'a mut Option<InstanceLock>,
     conn: &'a mut Connection,
 }
 
 impl<'a> InputKeyHandler<'a> {
     #[cfg(not(tarpaulin_include))]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         key: KeyEvent,
         page: &'a mut CurrentUi,
@@ -64,6 +118,7 @@ impl<'a> InputKeyHandler<'a> {
         all_tx_data: &'a mut TransactionData,
         chart_data: &'a mut ChartData,
         summary_data: &'a mut SummaryData,
+        summary_range: &'a mut SummaryRangeData,
         table: &'a mut TableData,
         summary_table: &'a mut TableData,
         home_months: &'a mut IndexedData,
@@ -71,23 +126,47 @@ impl<'a> InputKeyHandler<'a> {
         chart_months: &'a mut IndexedData,
         chart_years: &'a mut IndexedData,
         chart_modes: &'a mut IndexedData,
+        chart_tags: &'a mut IndexedData,
+        chart_compare_years: &'a mut IndexedData,
+        chart_range: &'a mut SummaryRangeData,
         summary_months: &'a mut IndexedData,
         summary_years: &'a mut IndexedData,
         summary_modes: &'a mut IndexedData,
+        summary_methods: &'a mut IndexedData,
         summary_sort: &'a mut SortingType,
+        summary_sort_order: &'a mut SortOrder,
         search_data: &'a mut TxData,
         search_tab: &'a mut TxTab,
         search_table: &'a mut TableData,
         search_txs: &'a mut TransactionData,
+        search_grouping: &'a mut SearchGrouping,
         chart_index: &'a mut Option<f64>,
         chart_hidden_mode: &'a mut bool,
+        chart_view: &'a mut ChartView,
+        chart_hidden_methods: &'a mut HashSet<String>,
+        chart_show_total: &'a mut bool,
+        chart_group_stack: &'a mut bool,
+        chart_weekly: &'a mut bool,
         summary_hidden_mode: &'a mut bool,
+        summary_grouped: &'a mut bool,
+        summary_compare: &'a mut bool,
+        summary_type_ahead: &'a mut TypeAhead,
+        month_jump: &'a mut MonthJump,
+        home_highlight: &'a mut HomeHighlight,
+        home_filter: &'a mut HomeFilter,
+        home_week: &'a mut Option<WeekRange>,
+        gauge_settings: &'a mut GaugeSettings,
+        display_settings: &'a mut DisplaySettings,
+        navigation: &'a mut NavigationStack,
         deletion_status: &'a mut DeletionStatus,
+        background_task: &'a mut Option<BackgroundTask>,
+        toasts: &'a Toasts,
+        name_cache: &'a NameCache,
+        read_only: &'a mut bool,
+        instance_lock: &'a mut Option<InstanceLock>,
         conn: &'a mut Connection,
     ) -> InputKeyHandler<'a> {
-        let total_tags = summary_data
-            .get_table_data(summary_modes, summary_months.index, summary_years.index)
-            .len();
+        let total_tags = summary_table.items.len();
         InputKeyHandler {
             key,
             page,
@@ -100,6 +179,7 @@ impl<'a> InputKeyHandler<'a> {
             all_tx_data,
             chart_data,
             summary_data,
+            summary_range,
             table,
             summary_table,
             home_months,
@@ -107,19 +187,45 @@ impl<'a> InputKeyHandler<'a> {
             chart_months,
             chart_years,
             chart_modes,
+            chart_tags,
+            chart_compare_years,
+            chart_range,
             summary_months,
             summary_years,
             summary_modes,
+            summary_methods,
             summary_sort,
+            summary_sort_order,
             search_data,
             search_tab,
             search_table,
             search_txs,
+            search_grouping,
             total_tags,
             chart_index,
             summary_hidden_mode,
             chart_hidden_mode,
+            chart_view,
+            chart_hidden_methods,
+            chart_show_total,
+            chart_group_stack,
+            chart_weekly,
+            summary_grouped,
+            summary_compare,
+            summary_type_ahead,
+            month_jump,
+            home_highlight,
+            home_filter,
+            home_week,
+            gauge_settings,
+            display_settings,
+            navigation,
             deletion_status,
+            background_task,
+            toasts,
+            name_cache,
+            read_only,
+            instance_lock,
             conn,
         }
     }
@@ -132,40 +238,52 @@ impl<'a> InputKeyHandler<'a> {
         match self.page {
             CurrentUi::AddTx => {
                 *self.add_tx_data = TxData::new();
+                self.add_tx_data.localize_date(&get_date_format(self.conn));
                 *self.add_tx_tab = TxTab::Nothing;
             }
             CurrentUi::Search => {
                 *self.search_data = TxData::new();
+                self.search_data.localize_date(&get_date_format(self.conn));
                 *self.search_tab = TxTab::Nothing;
             }
             _ => {}
         }
         *self.page = CurrentUi::Home;
+        self.navigation.clear();
     }
 
     /// Moves the interface to Home page
     #[cfg(not(tarpaulin_include))]
     pub fn go_home(&mut self) {
         *self.page = CurrentUi::Home;
+        self.navigation.clear();
     }
 
     /// Moves the interface to Add Tx page
     #[cfg(not(tarpaulin_include))]
     pub fn go_add_tx(&mut self) {
-        *self.page = CurrentUi::AddTx
+        if self.is_read_only() {
+            return;
+        }
+
+        *self.page = CurrentUi::AddTx;
+        self.navigation.clear();
     }
 
     /// Moves the interface to Search page
     #[cfg(not(tarpaulin_include))]
     pub fn go_search(&mut self) {
-        *self.page = CurrentUi::Search
+        *self.page = CurrentUi::Search;
+        self.navigation.clear();
     }
 
     /// Moves the interface to Summary page
     #[cfg(not(tarpaulin_include))]
     pub fn go_summary(&mut self) {
         *self.page = CurrentUi::Summary;
+        self.navigation.clear();
         self.summary_modes.set_index_zero();
+        self.summary_methods.set_index_zero();
         self.summary_months.set_index_zero();
         self.summary_years.set_index_zero();
         *self.summary_tab = SummaryTab::ModeSelection;
@@ -177,14 +295,31 @@ impl<'a> InputKeyHandler<'a> {
     #[cfg(not(tarpaulin_include))]
     pub fn go_chart(&mut self) {
         *self.page = CurrentUi::Chart;
+        self.navigation.clear();
         self.chart_modes.set_index_zero();
         self.chart_years.set_index_zero();
         self.chart_months.set_index_zero();
+        // tags may have been added or removed since the page was last opened
+        *self.chart_tags = IndexedData::new_chart_tags(self.conn);
         *self.chart_tab = ChartTab::ModeSelection;
         *self.chart_hidden_mode = false;
         self.reload_chart();
     }
 
+    /// Goes back to the page that was left through the last cross-page jump and restores
+    /// its tabs and selection. Goes to the Home page if there is nowhere to go back
+    #[cfg(not(tarpaulin_include))]
+    pub fn go_back(&mut self) {
+        match self.navigation.pop() {
+            Some(state) => self.restore_page_state(state),
+            None => {
+                if *self.page != CurrentUi::Home {
+                    self.go_home();
+                }
+            }
+        }
+    }
+
     /// Turns on help popup
     #[cfg(not(tarpaulin_include))]
     pub fn do_help_popup(&mut self) {
@@ -198,39 +333,1162 @@ impl<'a> InputKeyHandler<'a> {
         }
     }
 
-    /// Turns on deletion confirmation popup
+    /// Turns on deletion confirmation popup with the selected transaction.
+    /// Warns if refunds are linked to the transaction
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_deletion_popup(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
+
+        if let Some(index) = self.table.state.selected() {
+            let id_num = self.all_tx_data.get_id_num(index);
+            let refund_count = get_refund_ids(id_num, self.conn).len();
+
+            let warning = if refund_count == 0 {
+                String::new()
+            } else {
+                format!(
+                    "Warning: {refund_count} refund(s) of this expense will be left without the expense they refund"
+                )
+            };
+
+            let deletion_data = TxDeletionData::new(
+                &self.all_tx_data.all_tx[index],
+                warning,
+                get_f64_setting("deletion_typing_threshold", self.conn),
+                &get_date_format(self.conn),
+                &get_amount_format(self.conn),
+            );
+
+            *self.popup = PopupState::TxDeletion(deletion_data)
+        }
+    }
+
+    /// Turns on the popup with the selected transaction, its refund links and the transactions linked to it
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_tx_details_popup(&mut self) {
+        if self.is_archived_search_tx_selected() {
+            return;
+        }
+
+        if let Some((id_num, _)) = self.get_selected_tx() {
+            if let Some(details_data) = TxDetailsData::new(id_num, self.conn) {
+                *self.popup = PopupState::TxDetails(details_data);
+            }
+        }
+    }
+
+    /// Copies the transaction selected on the Home or the Search table to the clipboard
+    /// as a tab separated line
+    #[cfg(not(tarpaulin_include))]
+    pub fn copy_selected_tx(&mut self) {
+        let row = match self.page {
+            CurrentUi::Home => self
+                .table
+                .state
+                .selected()
+                .and_then(|index| self.table.items.get(index)),
+            CurrentUi::Search if !self.is_search_subtotal_selected() => self
+                .search_table
+                .state
+                .selected()
+                .and_then(|index| self.search_table.items.get(index)),
+            _ => None,
+        };
+
+        let row = match row {
+            Some(row) => row.clone(),
+            None => return,
+        };
+
+        let date_format = get_date_format(self.conn);
+        let text = get_tx_table_tsv(&[row], &date_format, false);
+        self.copy_text(&text, "Copied the transaction to the clipboard".to_string());
+    }
+
+    /// Copies every row of the Home or the Search table with the column names to the clipboard
+    /// as tab separated lines
+    #[cfg(not(tarpaulin_include))]
+    pub fn copy_table(&mut self) {
+        let rows = match self.page {
+            CurrentUi::Home => &self.table.items,
+            CurrentUi::Search => &self.search_table.items,
+            _ => return,
+        };
+
+        if rows.is_empty() {
+            return;
+        }
+
+        let date_format = get_date_format(self.conn);
+        let text = get_tx_table_tsv(rows, &date_format, true);
+        let message = format!("Copied {} rows to the clipboard", rows.len());
+        self.copy_text(&text, message);
+    }
+
+    /// Copies the tag table or the method table of the Summary page with the column names to
+    /// the clipboard as tab separated lines. The rows are the ones the page shows
+    #[cfg(not(tarpaulin_include))]
+    pub fn copy_summary_table(&mut self, method_table: bool) {
+        // an unusable range shows the reason instead of the tables
+        if self.summary_modes.index == RANGE_MODE
+            && self.summary_range.get_range(self.conn).is_err()
+        {
+            return;
+        }
+
+        let comparing = *self.summary_compare && self.summary_modes.index == 0;
+        let amount_format = get_amount_format(self.conn);
+
+        let (header, rows, excluded_tags) = if method_table {
+            let custom_range = self
+                .summary_range
+                .get_active_range(self.summary_modes, self.conn);
+
+            let method_data = self.summary_data.get_page_method_data(
+                self.summary_modes,
+                self.summary_months.index,
+                self.summary_years.index,
+                custom_range,
+                self.summary_methods.get_summary_method(),
+                *self.summary_compare,
+                self.conn,
+            );
+            (
+                get_method_table_header(comparing),
+                method_data,
+                HashSet::new(),
+            )
+        } else {
+            (
+                get_tag_table_header(*self.summary_grouped, comparing),
+                self.summary_table.items.clone(),
+                self.summary_data.get_excluded_tags().clone(),
+            )
+        };
+
+        if rows.is_empty() {
+            return;
+        }
+
+        let text = get_summary_table_tsv(&header, &rows, &amount_format, &excluded_tags);
+        let message = format!("Copied {} rows to the clipboard", rows.len());
+        self.copy_text(&text, message);
+    }
+
+    /// Puts the text on the clipboard and shows the message. A warning is shown instead
+    /// if there is no clipboard to copy to
+    #[cfg(not(tarpaulin_include))]
+    fn copy_text(&mut self, text: &str, message: String) {
+        match copy_to_clipboard(text) {
+            Ok(()) => self.toasts.push(StatusMessage::info(message)),
+            Err(e) => self
+                .toasts
+                .push(StatusMessage::warn(format!("Could not copy: {e}"))),
+        }
+    }
+
+    /// Puts the pasted text into the focused input in one go. The field of the Add Tx or
+    /// the Search page is checked once after the text is added. Nothing is pasted if no input is focused
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_paste(&mut self, text: &str) {
+        let text = get_single_line(text);
+
+        if text.is_empty() {
+            return;
+        }
+
+        match self.popup {
+            PopupState::RepeatTx(repeat_data) if repeat_data.is_editing_date() => {
+                text.chars().for_each(|c| repeat_data.edit_date(Some(c)));
+                return;
+            }
+            PopupState::TagEdit(tag_data) => {
+                text.chars().for_each(|c| tag_data.edit_tags(Some(c)));
+                return;
+            }
+            PopupState::BalanceDate(balance_data) => {
+                text.chars().for_each(|c| balance_data.edit_date(Some(c)));
+                return;
+            }
+            PopupState::LinkPicker(picker_data, _) | PopupState::RefundPicker(picker_data) => {
+                text.chars().for_each(|c| picker_data.edit_query(Some(c)));
+                return;
+            }
+            PopupState::Nothing => {}
+            _ => {
+                self.push_paste_hint();
+                return;
+            }
+        }
+
+        if self.is_summary_range_selected() {
+            text.chars().for_each(|c| self.edit_summary_range(Some(c)));
+            return;
+        }
+
+        if self.is_chart_range_selected() && matches!(self.page, CurrentUi::Chart) {
+            text.chars().for_each(|c| self.edit_chart_range(Some(c)));
+            return;
+        }
+
+        let (tx_data, current_tab, is_search) = match self.page {
+            CurrentUi::AddTx => (&mut *self.add_tx_data, *self.add_tx_tab, false),
+            CurrentUi::Search => (&mut *self.search_data, *self.search_tab, true),
+            _ => {
+                self.push_paste_hint();
+                return;
+            }
+        };
+
+        tx_data.paste_text(&current_tab, &text);
+
+        let status = match current_tab {
+            TxTab::Date => tx_data.check_date(self.conn),
+            TxTab::Details => tx_data.check_details(self.conn),
+            TxTab::FromMethod => tx_data.check_from_method(self.name_cache, self.conn),
+            TxTab::ToMethod => tx_data.check_to_method(self.name_cache, self.conn),
+            TxTab::Amount => tx_data.check_amount(is_search, self.conn),
+            TxTab::TxType => tx_data.check_tx_type(),
+            TxTab::Tags => tx_data.check_tags(self.name_cache, self.conn),
+            TxTab::Nothing => {
+                self.push_paste_hint();
+                return;
+            }
+        };
+
+        tx_data.add_tx_status(status.to_string());
+        self.check_autofill();
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    fn push_paste_hint(&self) {
+        self.toasts
+            .push(StatusMessage::info("Select an input field to paste into"));
+    }
+
+    /// Turns on the popup to insert a details template into the details field of the Add Tx page
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_details_template_popup(&mut self) {
+        let templates = get_details_templates(self.conn);
+
+        if templates.is_empty() {
+            self.toasts.push(StatusMessage::warn(
+                "No details templates yet. Add them from the J menu",
+            ));
+            return;
+        }
+
+        *self.popup = PopupState::DetailsTemplates(DetailsTemplateData::new(templates));
+    }
+
+    /// Turns on the popup to pick the expense the refund on the Add Tx page is linked to
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_refund_picker_popup(&mut self) {
+        if self.add_tx_data.is_refund() {
+            *self.popup = PopupState::RefundPicker(TxPickerData::new_expenses(self.conn));
+        }
+    }
+
+    /// Quits the app unless an operation is still running. In that case
+    /// a popup is shown to confirm quitting with wait being the default option
+    #[cfg(not(tarpaulin_include))]
+    pub fn quit_app(&mut self) -> Option<HandlingOutput> {
+        match self.background_task {
+            Some(task) => {
+                *self.popup =
+                    PopupState::QuitWarning(task.get_name().to_string(), QuitStatus::default());
+                None
+            }
+            None => Some(HandlingOutput::QuitUi),
+        }
+    }
+
+    /// Checks whether the pressed key is Ctrl+C
+    #[cfg(not(tarpaulin_include))]
+    pub fn is_ctrl_c(&self) -> bool {
+        self.key.code == KeyCode::Char('c') && self.key.modifiers.contains(KeyModifiers::CONTROL)
+    }
+
+    /// Checks whether the pressed key is Ctrl+X
+    #[cfg(not(tarpaulin_include))]
+    pub fn is_ctrl_x(&self) -> bool {
+        self.key.code == KeyCode::Char('x') && self.key.modifiers.contains(KeyModifiers::CONTROL)
+    }
+
+    /// Returns whether the database was opened in read-only mode.
+    /// If it was, the reason of the action not being done is shown
+    #[cfg(not(tarpaulin_include))]
+    fn is_read_only(&self) -> bool {
+        if *self.read_only {
+            self.toasts.push(StatusMessage::warn(READ_ONLY_MESSAGE));
+        }
+        *self.read_only
+    }
+
+    /// Turns on the popup to confirm switching read-only mode on or off
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_read_only_popup(&mut self) {
+        *self.popup = PopupState::ReadOnlyToggle(*self.read_only, ConfirmStatus::Confirm);
+    }
+
+    /// Handles the key presses of the popup that confirms switching read-only mode on or off
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_read_only_popup(&mut self) -> Option<HandlingOutput> {
+        if let PopupState::ReadOnlyToggle(read_only, confirm_status) = self.popup {
+            match self.key.code {
+                KeyCode::Left | KeyCode::Right => {
+                    *self.popup = PopupState::ReadOnlyToggle(*read_only, confirm_status.toggle())
+                }
+                KeyCode::Enter => {
+                    let output = match confirm_status {
+                        ConfirmStatus::Confirm => self.toggle_read_only(),
+                        _ => None,
+                    };
+                    *self.popup = PopupState::Nothing;
+                    return output;
+                }
+                KeyCode::Esc => *self.popup = PopupState::Nothing,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Opens the database again in read-only mode and reloads the pages. Leaving read-only mode
+    /// needs the instance lock so it is not possible while another instance is using the database.
+    /// Once the lock is taken, the database is opened for writing outside of the interface
+    /// so the updates skipped in read-only mode can be done first
+    #[cfg(not(tarpaulin_include))]
+    fn toggle_read_only(&mut self) -> Option<HandlingOutput> {
+        if *self.read_only {
+            if self.instance_lock.is_none() {
+                match InstanceLock::acquire(LOCK_FILE) {
+                    Ok(LockStatus::Acquired(lock)) => {
+                        lock.set_panic_hook();
+                        *self.instance_lock = Some(lock);
+                    }
+                    Ok(LockStatus::HeldBy(pid)) => {
+                        let holder = match pid {
+                            Some(pid) => format!("Rex (PID {pid})"),
+                            None => "Another instance".to_string(),
+                        };
+                        self.toasts.push(StatusMessage::warn(format!(
+                            "{holder} is using the database"
+                        )));
+                        return None;
+                    }
+                    Err(e) => {
+                        self.toasts.push(StatusMessage::error(format!(
+                            "Failed to take the lock: {e}"
+                        )));
+                        return None;
+                    }
+                }
+            }
+            return Some(HandlingOutput::LeaveReadOnly);
+        }
+
+        let path = self.conn.path().unwrap_or_default().to_string();
+
+        let conn =
+            Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).and_then(|conn| {
+                conn.execute("PRAGMA foreign_keys = ON", [])?;
+                Ok(conn)
+            });
+
+        match conn {
+            Ok(conn) => *self.conn = conn,
+            Err(e) => {
+                self.toasts.push(StatusMessage::error(format!(
+                    "Failed to open the database: {e}"
+                )));
+                return None;
+            }
+        }
+
+        *self.read_only = true;
+
+        self.name_cache.invalidate();
+        self.reload_home_table();
+        self.reload_chart_data();
+        self.reload_summary_data();
+        self.reload_search_data();
+
+        self.toasts
+            .push(StatusMessage::info("Read-only mode turned on"));
+        None
+    }
+
+    /// Opens the J menu that changes the database outside of the interface
+    #[cfg(not(tarpaulin_include))]
+    pub fn take_user_input(&mut self) -> Option<HandlingOutput> {
+        if self.is_read_only() {
+            return None;
+        }
+        Some(HandlingOutput::TakeUserInput)
+    }
+
+    /// Removes the newest toast
+    #[cfg(not(tarpaulin_include))]
+    pub fn dismiss_toast(&mut self) {
+        self.toasts.dismiss_newest();
+    }
+
+    /// Returns whether a background task is running. Its progress popup takes every key
+    #[cfg(not(tarpaulin_include))]
+    pub fn is_task_running(&self) -> bool {
+        self.background_task.is_some()
+    }
+
+    /// Handles the key presses of the background task progress popup. Esc asks the task to stop
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_task_keys(&mut self) {
+        if let Some(task) = self.background_task {
+            if self.key.code == KeyCode::Esc {
+                task.cancel();
+            }
+        }
+    }
+
+    /// Handles the key presses of the quit warning popup
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_quit_popup(&mut self) -> Option<HandlingOutput> {
+        if let PopupState::QuitWarning(operation, quit_status) = self.popup {
+            match self.key.code {
+                KeyCode::Left | KeyCode::Right => {
+                    *self.popup =
+                        PopupState::QuitWarning(operation.to_string(), quit_status.toggle())
+                }
+                KeyCode::Enter => match quit_status {
+                    QuitStatus::Wait => *self.popup = PopupState::Nothing,
+                    QuitStatus::QuitAnyway => return Some(HandlingOutput::QuitUi),
+                },
+                KeyCode::Esc => *self.popup = PopupState::Nothing,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Handles the key presses of the transaction confirmation popup. Edit closes the popup
+    /// and returns to the Add Transaction page with all the fields intact
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_confirmation_popup(&mut self) {
+        if let PopupState::TxConfirmation(summary, confirm_status) = self.popup {
+            match self.key.code {
+                KeyCode::Left | KeyCode::Right => {
+                    *self.popup =
+                        PopupState::TxConfirmation(summary.to_string(), confirm_status.toggle())
+                }
+                KeyCode::Enter => match confirm_status {
+                    ConfirmStatus::Confirm => {
+                        *self.popup = PopupState::Nothing;
+                        self.save_tx();
+                    }
+                    ConfirmStatus::Edit => *self.popup = PopupState::Nothing,
+                },
+                KeyCode::Esc => *self.popup = PopupState::Nothing,
+                _ => {}
+            }
+        }
+    }
+
+    /// Turns on the popup shown after the initial page. The dashboard is shown if it is enabled,
+    /// otherwise the pending reminders if there are any
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_startup_popup(&mut self) {
+        if get_bool_setting("show_dashboard", self.conn) {
+            let dashboard_data = self
+                .get_dashboard_data()
+                .with_auto_close(Duration::from_secs(5));
+            *self.popup = PopupState::Dashboard(dashboard_data);
+        } else {
+            self.do_reminder_popup();
+        }
+    }
+
+    /// Turns on the popup that summarizes the current month
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_dashboard_popup(&mut self) {
+        *self.popup = PopupState::Dashboard(self.get_dashboard_data());
+    }
+
+    /// Closes the dashboard popup on any key press. The dashboard shown
+    /// after the initial page is followed by the pending reminders
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_dashboard_popup(&mut self) {
+        let startup = match self.popup {
+            PopupState::Dashboard(dashboard_data) => dashboard_data.is_auto_closing(),
+            _ => false,
+        };
+
+        *self.popup = PopupState::Nothing;
+
+        if startup {
+            self.do_reminder_popup();
+        }
+    }
+
+    /// Turns on the popup of pending template reminders if there are any
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_reminder_popup(&mut self) {
+        let reminder_data = ReminderData::new(self.conn);
+
+        if !reminder_data.is_empty() {
+            *self.popup = PopupState::Reminders(reminder_data);
+        }
+    }
+
+    /// Handles the key presses of the reminder popup. Adding a reminder opens
+    /// the Add Transaction page with the data of the template
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_reminder_popup(&mut self) {
+        let mut close_popup = self.key.code == KeyCode::Esc;
+        let state = self.get_page_state();
+
+        if let PopupState::Reminders(reminder_data) = self.popup {
+            match self.key.code {
+                KeyCode::Up => reminder_data.previous(),
+                KeyCode::Down => reminder_data.next(),
+                KeyCode::Char('a') if *self.read_only => {
+                    self.toasts.push(StatusMessage::warn(READ_ONLY_MESSAGE));
+                }
+                KeyCode::Char('a') => {
+                    if let Some(template) = reminder_data.get_selected() {
+                        *self.add_tx_data = TxData::from_template(template);
+                        self.add_tx_data.localize_date(&get_date_format(self.conn));
+                        *self.add_tx_tab = TxTab::Nothing;
+                        *self.page = CurrentUi::AddTx;
+                        self.navigation.push(state);
+                        close_popup = true;
+                    }
+                }
+                KeyCode::Char('d') if *self.read_only => {
+                    self.toasts.push(StatusMessage::warn(READ_ONLY_MESSAGE));
+                }
+                KeyCode::Char('d') => {
+                    reminder_data.dismiss_selected(self.conn);
+                    close_popup = reminder_data.is_empty();
+                }
+                _ => {}
+            }
+        }
+
+        if close_popup {
+            *self.popup = PopupState::Nothing;
+        }
+    }
+
+    /// Turns on the popup for repeating the transactions of a past day
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_repeat_popup(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
+
+        *self.popup = PopupState::RepeatTx(RepeatTxData::new());
+    }
+
+    /// Handles the key presses of the repeat transactions popup. Once the selected
+    /// transactions are added, the popup is closed and the pages are reloaded
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_repeat_popup(&mut self) {
+        let mut added = false;
+
+        if let PopupState::RepeatTx(repeat_data) = self.popup {
+            if repeat_data.is_editing_date() {
+                match self.key.code {
+                    KeyCode::Enter => repeat_data.load_txs(self.conn),
+                    KeyCode::Backspace => repeat_data.edit_date(None),
+                    KeyCode::Char(a) => repeat_data.edit_date(Some(a)),
+                    _ => {}
+                }
+            } else {
+                match self.key.code {
+                    KeyCode::Up => repeat_data.previous(),
+                    KeyCode::Down => repeat_data.next(),
+                    KeyCode::Char(' ') => repeat_data.toggle_selected(),
+                    KeyCode::Tab => repeat_data.start_editing_date(),
+                    KeyCode::Enter => added = repeat_data.add_selected_txs(self.conn).is_ok(),
+                    _ => {}
+                }
+            }
+        }
+
+        if added || self.key.code == KeyCode::Esc {
+            *self.popup = PopupState::Nothing;
+        }
+
+        if added {
+            self.name_cache.invalidate();
+            self.reload_home_table();
+            self.reload_chart_data();
+            self.reload_summary_data();
+            self.reload_search_data();
+        }
+    }
+
+    /// Cycles the Home table filter and selects the first row if the table was selected
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_home_filter(&mut self) {
+        *self.home_filter = self.home_filter.next_type();
+        self.reload_home_table();
+        if let HomeTab::Table = self.home_tab {
+            if !self.all_tx_data.all_tx.is_empty() {
+                self.table.state.select(Some(0));
+            }
+        }
+    }
+
+    /// Switches the Home table between the selected month and a single week of it.
+    /// The weekly view starts at the current week if it is in the selected month
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_home_week_toggle(&mut self) {
+        *self.home_week = match self.home_week {
+            Some(_) => None,
+            None => Some(WeekRange::containing(
+                SystemClock.today(),
+                get_week_start(self.conn),
+            )),
+        };
+        self.reload_home_table();
+        if let HomeTab::Table = self.home_tab {
+            if !self.all_tx_data.all_tx.is_empty() {
+                self.table.state.select(Some(0));
+            }
+        }
+    }
+
+    /// Marks the selected Home transaction as reviewed or removes the mark.
+    /// The cleanup filter drops the row once it is reviewed and tagged
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_toggle_reviewed(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
+
+        if let Some(index) = self.table.state.selected() {
+            let id_num = self.all_tx_data.get_id_num(index);
+            let reviewed = self.all_tx_data.get_reviewed()[index];
+
+            if set_tx_reviewed(id_num, !reviewed, self.conn).is_ok() {
+                self.reload_home_keeping_selection(index);
+            }
+        }
+    }
+
+    /// Turns on the popup for editing the tags of the selected Home transaction
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_tag_edit_popup(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
+
+        if let Some(index) = self.table.state.selected() {
+            let tx = &self.all_tx_data.get_txs()[index];
+            let id_num = self.all_tx_data.get_id_num(index);
+            *self.popup = PopupState::TagEdit(TagEditData::new(id_num, &tx[1], &tx[5]));
+        }
+    }
+
+    /// Handles the key presses of the tag edit popup. Once the tags are saved,
+    /// the popup is closed and the pages are reloaded. If any typed tag does not exist,
+    /// the popup asking to create them is opened
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_tag_edit_popup(&mut self) {
+        let mut saved = false;
+        let mut unknown_tags = false;
+
+        if let PopupState::TagEdit(tag_data) = self.popup {
+            match self.key.code {
+                KeyCode::Enter => {
+                    saved = tag_data.save_tags(self.conn).is_ok();
+                    unknown_tags = tag_data.has_unknown_tags();
+                }
+                KeyCode::Backspace => tag_data.edit_tags(None),
+                KeyCode::Char(a) => tag_data.edit_tags(Some(a)),
+                _ => {}
+            }
+        }
+
+        if unknown_tags {
+            if let PopupState::TagEdit(tag_data) = mem::replace(self.popup, PopupState::Nothing) {
+                *self.popup = PopupState::NewTags(tag_data);
+            }
+            return;
+        }
+
+        if saved || self.key.code == KeyCode::Esc {
+            *self.popup = PopupState::Nothing;
+        }
+
+        if saved {
+            self.reload_after_tag_edit();
+        }
+    }
+
+    /// Handles the key presses of the popup asking to create the unknown tags.
+    /// Y creates them and saves the tags, N goes back to the tag edit popup with the
+    /// previously typed tags
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_new_tags_popup(&mut self) {
+        let mut saved = false;
+        let mut go_back = false;
+
+        if let PopupState::NewTags(tag_data) = self.popup {
+            match self.key.code {
+                KeyCode::Char('y' | 'Y') => {
+                    saved = tag_data.create_unknown_tags(self.conn).is_ok();
+                    go_back = !saved;
+                }
+                KeyCode::Char('n' | 'N') | KeyCode::Esc => {
+                    tag_data.discard_unknown_tags();
+                    go_back = true;
+                }
+                _ => {}
+            }
+        }
+
+        if go_back {
+            if let PopupState::NewTags(tag_data) = mem::replace(self.popup, PopupState::Nothing) {
+                *self.popup = PopupState::TagEdit(tag_data);
+            }
+        }
+
+        if saved {
+            *self.popup = PopupState::Nothing;
+            self.reload_after_tag_edit();
+        }
+    }
+
+    /// Reloads the pages that show tags after the tags of a transaction were edited
+    #[cfg(not(tarpaulin_include))]
+    fn reload_after_tag_edit(&mut self) {
+        self.name_cache.invalidate();
+        if let Some(index) = self.table.state.selected() {
+            self.reload_home_keeping_selection(index);
+        }
+        self.reload_chart_data();
+        self.reload_summary_data();
+        self.reload_search_data();
+    }
+
+    /// Turns on the popup for checking the balances at the end of a date
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_balance_date_popup(&mut self) {
+        *self.popup = PopupState::BalanceDate(BalanceDateData::new());
+    }
+
+    /// Handles the key presses of the balance on date popup. The balances stay
+    /// on the popup until it is closed
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_balance_date_popup(&mut self) {
+        if let PopupState::BalanceDate(balance_data) = self.popup {
+            match self.key.code {
+                KeyCode::Enter => balance_data.load_balances(self.conn),
+                KeyCode::Backspace => balance_data.edit_date(None),
+                KeyCode::Char(a) => balance_data.edit_date(Some(a)),
+                _ => {}
+            }
+        }
+
+        if self.key.code == KeyCode::Esc {
+            *self.popup = PopupState::Nothing;
+        }
+    }
+
+    /// Removes popup status
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_empty_popup(&mut self) {
+        *self.popup = PopupState::Nothing
+    }
+
+    /// Shows or hides the expense/income gauge of the Home page
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_home_gauge_toggle(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
+
+        self.gauge_settings.toggle_hidden(self.conn);
+    }
+
+    /// Groups the Home table by day or shows it without the separator rows
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_day_groups_toggle(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
+
+        self.display_settings.toggle_day_groups(self.conn);
+    }
+
+    /// Shows or hides the hidden tx methods on the Home balance table
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_hidden_methods_toggle(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
+
+        self.display_settings.toggle_hidden_methods(self.conn);
+    }
+
+    /// Hides chart top widgets
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_chart_hidden_mode(&mut self) {
+        *self.chart_hidden_mode = !*self.chart_hidden_mode;
+    }
+
+    /// Switches the chart between the balance and the monthly income and expense
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_chart_view_toggle(&mut self) {
+        *self.chart_view = self.chart_view.next_type();
+
+        match self.chart_view {
+            // the balance chart is drawn with the animation again. The mode, year and month
+            // selections were not touched by the other views
+            ChartView::Balance => {
+                *self.chart_tab = ChartTab::ModeSelection;
+                self.reload_chart();
+            }
+            ChartView::YearComparison => *self.chart_tab = ChartTab::Years,
+            ChartView::IncomeExpense | ChartView::StackedExpense => {}
+        }
+    }
+
+    /// Shows or hides the balance line of the tx method of the pressed number key
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_chart_method_toggle(&mut self) {
+        let method_index = match self.key.code {
+            KeyCode::Char(c) => match c.to_digit(10) {
+                Some(num) if num > 0 => num as usize - 1,
+                _ => return,
+            },
+            _ => return,
+        };
+
+        let all_tx_methods = self.name_cache.get_tx_methods(self.conn);
+
+        if let Some(method) = all_tx_methods.get(method_index) {
+            if !self.chart_hidden_methods.remove(method) {
+                self.chart_hidden_methods.insert(method.to_string());
+            }
+
+            // the lines still change in read-only mode but are not saved
+            if *self.read_only {
+                return;
+            }
+
+            // saved in the order of the methods so the value does not change between toggles
+            let hidden_methods = all_tx_methods
+                .into_iter()
+                .filter(|method| self.chart_hidden_methods.contains(method))
+                .collect::<Vec<String>>();
+            set_list_setting("chart_hidden_methods", &hidden_methods, self.conn).ok();
+        }
+    }
+
+    /// Shows or hides the line of the sum of the visible balances
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_chart_total_toggle(&mut self) {
+        *self.chart_show_total = !*self.chart_show_total;
+        if !*self.read_only {
+            set_bool_setting("chart_show_total", *self.chart_show_total, self.conn).ok();
+        }
+    }
+
+    /// Switches the segments of the stacked monthly expense bars between tx methods and tag groups
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_chart_group_stack_toggle(&mut self) {
+        *self.chart_group_stack = !*self.chart_group_stack;
+    }
+
+    /// Switches the income and expense bars between the months of the selected year and
+    /// the weeks of the selected month
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_chart_weekly_toggle(&mut self) {
+        *self.chart_weekly = !*self.chart_weekly;
+    }
+
+    /// Switches the summary tag table between individual tags and tag groups
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_summary_grouped_mode(&mut self) {
+        *self.summary_grouped = !*self.summary_grouped;
+        self.reload_summary();
+        if let SummaryTab::Table = self.summary_tab {
+            if self.total_tags > 0 {
+                self.summary_table.state.select(Some(0));
+            }
+        }
+    }
+
+    /// Switches the summary tables of the Monthly mode between the selected month only
+    /// and the comparison with the month before it
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_summary_compare_mode(&mut self) {
+        *self.summary_compare = !*self.summary_compare;
+        self.reload_summary();
+        if let SummaryTab::Table = self.summary_tab {
+            if self.total_tags > 0 {
+                self.summary_table.state.select(Some(0));
+            }
+        }
+    }
+
+    /// Saves the Summary page selections that are restored when the app is started again
+    #[cfg(not(tarpaulin_include))]
+    pub fn save_summary_preferences(&mut self) {
+        if *self.read_only {
+            return;
+        }
+
+        set_bool_setting("summary_hidden_mode", *self.summary_hidden_mode, self.conn).ok();
+        self.summary_modes.save_index("summary_mode", self.conn);
+        self.summary_months.save_index("summary_month", self.conn);
+        self.summary_years.save_index("summary_year", self.conn);
+        set_usize_setting("summary_sort", self.summary_sort.column(), self.conn).ok();
+        set_bool_setting(
+            "summary_sort_descending",
+            *self.summary_sort_order == SortOrder::Descending,
+            self.conn,
+        )
+        .ok();
+    }
+
+    /// Turns on the popup for selecting the tags left out of the Summary calculations
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_excluded_tags_popup(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
+
+        *self.popup = PopupState::ExcludedTags(ExcludedTagsData::new(self.conn));
+    }
+
+    /// Handles the key presses of the excluded tags popup. Once the selection is saved,
+    /// the popup is closed and the Summary is reloaded
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_excluded_tags_popup(&mut self) {
+        let mut saved = false;
+
+        if let PopupState::ExcludedTags(excluded_data) = self.popup {
+            match self.key.code {
+                KeyCode::Up => excluded_data.previous(),
+                KeyCode::Down => excluded_data.next(),
+                KeyCode::Char(' ') => excluded_data.toggle_selected(),
+                KeyCode::Enter => saved = excluded_data.save(self.conn).is_ok(),
+                _ => {}
+            }
+        }
+
+        if saved || self.key.code == KeyCode::Esc {
+            *self.popup = PopupState::Nothing;
+        }
+
+        if saved {
+            self.reload_summary_after_exclusions();
+        }
+    }
+
+    /// Switches the Summary between leaving out the excluded tags and the raw view with every transaction
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_summary_raw_view_toggle(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
+
+        self.display_settings.toggle_summary_raw_view(self.conn);
+        self.reload_summary_after_exclusions();
+    }
+
+    /// Switches the Summary method table between leaving out the transfers and counting them
+    /// as income and expense of the tx methods
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_summary_transfers_toggle(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
+
+        self.display_settings.toggle_summary_transfers(self.conn);
+    }
+
+    /// Hides summary top widgets
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_summary_hidden_mode(&mut self) {
+        *self.summary_hidden_mode = !*self.summary_hidden_mode;
+
+        if *self.summary_hidden_mode {
+            *self.summary_tab = SummaryTab::Table;
+            if self.total_tags > 0 {
+                self.summary_table.state.select(Some(0));
+            }
+        } else {
+            *self.summary_tab = SummaryTab::ModeSelection
+        }
+    }
+
+    /// Returns whether one of the date boxes of the Summary page Range mode is selected
+    #[cfg(not(tarpaulin_include))]
+    pub fn is_summary_range_selected(&self) -> bool {
+        matches!(
+            self.summary_tab,
+            SummaryTab::RangeStart | SummaryTab::RangeEnd
+        )
+    }
+
+    /// Returns whether one of the date boxes of the Chart page Range mode is selected
+    #[cfg(not(tarpaulin_include))]
+    pub fn is_chart_range_selected(&self) -> bool {
+        matches!(self.chart_tab, ChartTab::RangeStart | ChartTab::RangeEnd)
+    }
+
+    /// Checks whether the pressed key is typed into the type-ahead of the Summary table.
+    /// Keys pressed with Ctrl or Alt are left to the page shortcuts
+    #[cfg(not(tarpaulin_include))]
+    pub fn is_summary_type_ahead_key(&self) -> bool {
+        matches!(self.summary_tab, SummaryTab::Table)
+            && !self
+                .key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    pub fn is_summary_type_ahead_active(&self) -> bool {
+        !self.summary_type_ahead.is_empty()
+    }
+
+    /// Adds the character to the type-ahead of the Summary table and selects the first tag
+    /// that starts with it. The selection does not move if no tag matches
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_summary_type_ahead(&mut self, c: char) {
+        self.summary_type_ahead.push(c);
+        self.select_summary_type_ahead_match();
+    }
+
+    /// Removes the last character of the Summary table type-ahead
+    #[cfg(not(tarpaulin_include))]
+    pub fn shrink_summary_type_ahead(&mut self) {
+        self.summary_type_ahead.pop();
+        self.select_summary_type_ahead_match();
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    pub fn clear_summary_type_ahead(&mut self) {
+        self.summary_type_ahead.clear();
+    }
+
+    /// Checks whether the Months tab of the current page is selected
+    #[cfg(not(tarpaulin_include))]
+    pub fn is_months_tab_selected(&self) -> bool {
+        match self.page {
+            CurrentUi::Home => matches!(self.home_tab, HomeTab::Months),
+            CurrentUi::Chart => {
+                !*self.chart_hidden_mode && matches!(self.chart_tab, ChartTab::Months)
+            }
+            CurrentUi::Summary => matches!(self.summary_tab, SummaryTab::Months),
+            _ => false,
+        }
+    }
+
+    /// Selects the month of the typed number on the Months tab and reloads the page
+    /// the same way moving the month with the arrows does
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_month_jump(&mut self, c: char) {
+        let month_index = match self.month_jump.push(c) {
+            Some(index) => index,
+            None => return,
+        };
+
+        match self.page {
+            CurrentUi::Home => {
+                self.home_months.index = month_index;
+                self.reload_home_table();
+            }
+            CurrentUi::Chart => {
+                self.chart_months.index = month_index;
+                self.fill_chart_range();
+                self.reload_chart();
+            }
+            CurrentUi::Summary => {
+                self.summary_months.index = month_index;
+                self.reload_summary();
+            }
+            _ => {}
+        }
+    }
+
+    /// Selects the current month and year on the month and year tabs of the page
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_current_month_jump(&mut self) {
+        let today = SystemClock.today();
+
+        let changed = match self.page {
+            CurrentUi::Home => select_month_of(self.home_months, self.home_years, today),
+            CurrentUi::Chart => select_month_of(self.chart_months, self.chart_years, today),
+            CurrentUi::Summary => select_month_of(self.summary_months, self.summary_years, today),
+            _ => false,
+        };
+
+        if !changed {
+            self.toasts.push(StatusMessage::warn(format!(
+                "{} is outside of the year range",
+                today.year()
+            )));
+            return;
+        }
+
+        match self.page {
+            CurrentUi::Home => {
+                // the weekly view moves to the current week instead of the first week of the month
+                if self.home_week.is_some() {
+                    *self.home_week = Some(WeekRange::containing(today, get_week_start(self.conn)));
+                }
+                self.reload_home_table();
+            }
+            CurrentUi::Chart => {
+                self.fill_chart_range();
+                self.reload_chart();
+            }
+            CurrentUi::Summary => self.reload_summary(),
+            _ => {}
+        }
+    }
+
+    /// Insert or remove from the selected date box of the Summary page Range mode
     #[cfg(not(tarpaulin_include))]
-    pub fn do_deletion_popup(&mut self) {
-        if let Some(_) = self.table.state.selected() {
-            *self.popup = PopupState::TxDeletion
+    pub fn edit_summary_range(&mut self, to_add: Option<char>) {
+        match self.summary_tab {
+            SummaryTab::RangeStart => self.summary_range.edit_start_date(to_add),
+            SummaryTab::RangeEnd => self.summary_range.edit_end_date(to_add),
+            _ => {}
         }
     }
 
-    /// Removes popup status
+    /// Verifies the date boxes of the Summary page Range mode and reloads the summary
     #[cfg(not(tarpaulin_include))]
-    pub fn do_empty_popup(&mut self) {
-        *self.popup = PopupState::Nothing
+    pub fn verify_summary_range(&mut self) {
+        self.summary_range.verify_dates(self.conn);
+        self.reload_summary();
     }
 
-    /// Hides chart top widgets
+    /// Insert or remove from the selected date box of the Chart page Range mode
     #[cfg(not(tarpaulin_include))]
-    pub fn do_chart_hidden_mode(&mut self) {
-        *self.chart_hidden_mode = !*self.chart_hidden_mode;
+    pub fn edit_chart_range(&mut self, to_add: Option<char>) {
+        match self.chart_tab {
+            ChartTab::RangeStart => self.chart_range.edit_start_date(to_add),
+            ChartTab::RangeEnd => self.chart_range.edit_end_date(to_add),
+            _ => {}
+        }
     }
 
-    /// Hides summary top widgets
+    /// Verifies the date boxes of the Chart page Range mode and reloads the chart
     #[cfg(not(tarpaulin_include))]
-    pub fn do_summary_hidden_mode(&mut self) {
-        *self.summary_hidden_mode = !*self.summary_hidden_mode;
-
-        if *self.summary_hidden_mode {
-            *self.summary_tab = SummaryTab::Table;
-            if self.total_tags > 0 {
-                self.summary_table.state.select(Some(0));
-            }
-        } else {
-            *self.summary_tab = SummaryTab::ModeSelection
-        }
+    pub fn verify_chart_range(&mut self) {
+        self.chart_range.verify_dates(self.conn);
+        self.reload_chart();
     }
 
     /// Handles Enter key press if there is a new update and the update popup is on
@@ -260,28 +1518,201 @@ impl<'a> InputKeyHandler<'a> {
             let search_txs = self.search_data.get_search_tx(self.conn);
 
             if search_txs.0.is_empty() {
-                self.search_data.add_tx_status(
-                    "Search: No transactions found with the provided input".to_string(),
-                )
+                let status = match get_searched_id(self.search_data.get_all_texts()[1]) {
+                    Some(id_num) => format!("Search: No transaction found with the id #{id_num}"),
+                    None => "Search: No transactions found with the provided input".to_string(),
+                };
+                self.search_data.add_tx_status(status)
             } else {
                 *self.search_txs =
                     TransactionData::new_search(search_txs.0.to_owned(), search_txs.1);
-                *self.search_table = TableData::new(search_txs.0);
+                self.search_txs.set_archived(search_txs.2);
+                *self.search_table =
+                    TableData::new(group_search_txs(&search_txs.0, *self.search_grouping));
                 self.search_table.state.select(Some(0));
             }
         }
     }
 
+    /// Opens the Home page on the month of the selected search result with the transaction selected.
+    /// The Home filter is turned off if it hides the transaction
+    #[cfg(not(tarpaulin_include))]
+    pub fn show_search_tx(&mut self) {
+        if self.is_archived_search_tx_selected() {
+            return;
+        }
+
+        let tx_index = match self.get_search_tx_index() {
+            Some(tx_index) => tx_index,
+            None => return,
+        };
+
+        let tx = self.search_txs.all_tx[tx_index].clone();
+        let id_num = self.search_txs.get_id_num(tx_index);
+
+        let date = match NaiveDate::parse_from_str(&tx[0], "%d-%m-%Y") {
+            Ok(date) => date,
+            Err(_) => return,
+        };
+
+        if let Err(e) = self.select_home_tx(id_num, &tx[1], date) {
+            self.search_data.add_tx_status(format!("Search: {e}"));
+        }
+    }
+
+    /// Switches the Home page to the month of the transaction and selects it on the table.
+    /// Returns the reason if the transaction can not be shown on the Home page
+    #[cfg(not(tarpaulin_include))]
+    fn select_home_tx(
+        &mut self,
+        id_num: i32,
+        details: &str,
+        date: NaiveDate,
+    ) -> Result<(), &'static str> {
+        let date_range = get_date_range(self.conn);
+
+        if !date_range.contains(date.year()) {
+            return Err("The transaction is outside the year range");
+        }
+
+        let state = self.get_page_state();
+        let previous_filter = *self.home_filter;
+        let (previous_month, previous_year) = (self.home_months.index, self.home_years.index);
+
+        self.home_months.index = date.month0() as usize;
+        self.home_years.index = date_range.year_index(date.year());
+
+        // archived transactions can share the id_num of an existing one so the details are checked too
+        let mut row_index = None;
+        for filter in [previous_filter, HomeFilter::All] {
+            *self.home_filter = filter;
+            self.reload_home_table();
+            row_index = (0..self.all_tx_data.all_tx.len()).find(|row| {
+                self.all_tx_data.get_id_num(*row) == id_num
+                    && self.all_tx_data.all_tx[*row][1] == details
+            });
+
+            if row_index.is_some() {
+                break;
+            }
+        }
+
+        match row_index {
+            Some(row) => {
+                *self.page = CurrentUi::Home;
+                *self.home_tab = HomeTab::Table;
+                self.table.state.select(Some(row));
+                self.navigation.push(state);
+                self.home_highlight.start(id_num);
+                Ok(())
+            }
+            None => {
+                *self.home_filter = previous_filter;
+                self.home_months.index = previous_month;
+                self.home_years.index = previous_year;
+                self.reload_home_table();
+                Err("The transaction is not on the Home page")
+            }
+        }
+    }
+
+    /// Cycles the search result grouping between None, Month and Year
+    #[cfg(not(tarpaulin_include))]
+    pub fn change_search_grouping(&mut self) {
+        *self.search_grouping = self.search_grouping.next_type();
+
+        if !self.search_txs.all_tx.is_empty() {
+            *self.search_table = TableData::new(group_search_txs(
+                &self.search_txs.all_tx,
+                *self.search_grouping,
+            ));
+            self.search_table.state.select(Some(0));
+        }
+
+        let status = match self.search_grouping {
+            SearchGrouping::None => "Grouping: Not grouping the transactions",
+            SearchGrouping::Month => "Grouping: Grouping the transactions by month",
+            SearchGrouping::Year => "Grouping: Grouping the transactions by year",
+        };
+        self.search_data.add_tx_status(status.to_string());
+    }
+
+    /// Attaches or detaches the archive database to include or exclude the
+    /// archived transactions from the search results
+    #[cfg(not(tarpaulin_include))]
+    pub fn toggle_archived_search(&mut self) {
+        let status = if is_archive_attached(self.conn) {
+            detach_archive(self.conn).map(|_| "Archive: Excluding archived transactions")
+        } else {
+            attach_archive(ARCHIVE_DB, self.conn)
+                .map(|_| "Archive: Including archived transactions")
+        };
+
+        match status {
+            Ok(text) => self.search_data.add_tx_status(text.to_string()),
+            Err(e) => self
+                .search_data
+                .add_tx_status(format!("Archive: Failed to toggle the archive. Error: {e}")),
+        }
+    }
+
     /// Adds new tx and reloads home and chart data
     #[cfg(not(tarpaulin_include))]
     pub fn add_tx(&mut self) {
+        let check_status = self
+            .add_tx_data
+            .check_all_fields(self.name_cache, self.conn);
+
+        if !check_status.is_empty() {
+            // show every failed check at once. The cursor goes to the first invalid field once it is dismissed
+            *self.popup = PopupState::SubmitErrors(check_status);
+            return;
+        }
+
+        // amounts outside the bounds of a tag are likely typos. Saving them needs an explicit confirm
+        let bound_warnings = self.add_tx_data.get_bound_warnings(self.conn);
+
+        if !bound_warnings.is_empty() {
+            let summary = format!(
+                "{}\n\nAmount outside the expected range:\n{}",
+                self.add_tx_data.get_summary(),
+                bound_warnings.join("\n")
+            );
+            *self.popup = PopupState::TxConfirmation(summary, ConfirmStatus::Edit);
+            return;
+        }
+
+        let mode = get_setting("tx_confirmation", self.conn);
+        let threshold = get_f64_setting("tx_confirmation_threshold", self.conn);
+
+        if self.add_tx_data.needs_confirmation(&mode, threshold) {
+            *self.popup =
+                PopupState::TxConfirmation(self.add_tx_data.get_summary(), ConfirmStatus::Confirm);
+            return;
+        }
+
+        self.save_tx();
+    }
+
+    /// Saves the checked tx and reloads home and chart data
+    #[cfg(not(tarpaulin_include))]
+    fn save_tx(&mut self) {
+        let editing = self.add_tx_data.is_editing();
         let status = self.add_tx_data.add_tx(self.conn);
 
         match status {
             Ok(_) => {
+                let message = if editing {
+                    "Transaction updated"
+                } else {
+                    "Transaction added"
+                };
+                self.toasts.push(StatusMessage::info(message));
                 self.go_home_reset();
                 // we just added a new tx, select the month tab again + reload the data of balance and table widgets to get updated data
                 *self.home_tab = HomeTab::Months;
+                // the transaction may have created new tags
+                self.name_cache.invalidate();
                 self.reload_home_table();
                 self.reload_chart_data();
                 self.reload_summary_data();
@@ -295,6 +1726,10 @@ impl<'a> InputKeyHandler<'a> {
     /// allocates the data of the tx to the input boxes
     #[cfg(not(tarpaulin_include))]
     pub fn edit_tx(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
+
         if let Some(a) = self.table.state.selected() {
             let target_data = &self.all_tx_data.get_txs()[a];
             let target_id_num = self.all_tx_data.get_id_num(a);
@@ -313,6 +1748,9 @@ impl<'a> InputKeyHandler<'a> {
                     &target_data[5],
                     target_id_num,
                 );
+                self.add_tx_data.load_refund_link(self.conn);
+                self.add_tx_data.localize_date(&get_date_format(self.conn));
+                self.navigation.push(self.get_page_state());
                 *self.page = CurrentUi::AddTx;
             } else {
                 let splitted_method = target_data[2].split(" to ").collect::<Vec<&str>>();
@@ -329,6 +1767,8 @@ impl<'a> InputKeyHandler<'a> {
                     &target_data[5],
                     target_id_num,
                 );
+                self.add_tx_data.localize_date(&get_date_format(self.conn));
+                self.navigation.push(self.get_page_state());
                 *self.page = CurrentUi::AddTx;
             }
         }
@@ -341,7 +1781,9 @@ impl<'a> InputKeyHandler<'a> {
             let status = self.all_tx_data.del_tx(index, self.conn);
             match status {
                 Ok(_) => {
+                    self.toasts.push(StatusMessage::info("Transaction deleted"));
                     // transaction deleted so reload the data again
+                    self.name_cache.invalidate();
                     self.reload_home_table();
                     self.reload_chart_data();
                     self.reload_summary_data();
@@ -362,6 +1804,43 @@ impl<'a> InputKeyHandler<'a> {
         }
     }
 
+    /// Opens the Add Tx page with a refund of the selected expense. The amount is set to
+    /// what is left to refund and can be lowered for a partial refund
+    #[cfg(not(tarpaulin_include))]
+    pub fn refund_tx(&mut self) {
+        if self.is_read_only() || self.is_archived_search_tx_selected() {
+            return;
+        }
+
+        let (id_num, tx) = match self.get_selected_tx() {
+            Some(selected) => selected,
+            None => return,
+        };
+
+        if tx[4] != "Expense" {
+            if let CurrentUi::Search = self.page {
+                self.search_data
+                    .add_tx_status("Refund: Only expenses can be refunded".to_string());
+            }
+            return;
+        }
+
+        let refundable = get_refundable_amount(id_num, None, self.conn).unwrap_or_default();
+        let amount = refundable.format(get_minor_units(&tx[2], self.conn));
+        let amount = get_amount_format(self.conn).localize_decimal(&amount);
+
+        *self.add_tx_data = TxData::new_refund(id_num, &tx, &amount);
+        self.add_tx_data.localize_date(&get_date_format(self.conn));
+
+        if refundable == Money::ZERO {
+            self.add_tx_data
+                .add_tx_status("Refund: Nothing is left to refund of the expense".to_string());
+        }
+
+        self.navigation.push(self.get_page_state());
+        *self.page = CurrentUi::AddTx;
+    }
+
     /// Handles all number key presses and selects relevant input field
     #[cfg(not(tarpaulin_include))]
     pub fn handle_number_press(&mut self) {
@@ -417,14 +1896,21 @@ impl<'a> InputKeyHandler<'a> {
     /// Handles left arrow key press for multiple pages
     #[cfg(not(tarpaulin_include))]
     pub fn handle_left_arrow(&mut self) {
+        let skip_empty = get_bool_setting("skip_empty_periods", self.conn);
+
         match self.page {
             CurrentUi::Home => match self.home_tab {
                 HomeTab::Months => {
-                    self.home_months.previous();
+                    match self.home_week.as_mut() {
+                        Some(week) => {
+                            step_week(week, false, self.home_months, self.home_years);
+                        }
+                        None => self.home_months.previous_period(skip_empty),
+                    }
                     self.reload_home_table();
                 }
                 HomeTab::Years => {
-                    self.home_years.previous();
+                    self.home_years.previous_period(skip_empty);
                     self.home_months.set_index_zero();
                     self.reload_home_table();
                 }
@@ -440,14 +1926,24 @@ impl<'a> InputKeyHandler<'a> {
                             self.reload_chart();
                         }
                         ChartTab::Years => {
-                            self.chart_years.previous();
+                            self.chart_years.previous_period(skip_empty);
                             self.chart_months.set_index_zero();
+                            self.fill_chart_range();
                             self.reload_chart();
                         }
                         ChartTab::Months => {
-                            self.chart_months.previous();
+                            self.chart_months.previous_period(skip_empty);
+                            self.fill_chart_range();
+                            self.reload_chart();
+                        }
+                        ChartTab::RangeStart | ChartTab::RangeEnd => {
+                            self.step_chart_range(StepType::StepDown);
+                        }
+                        ChartTab::Tags => {
+                            self.chart_tags.previous();
                             self.reload_chart();
                         }
+                        ChartTab::CompareYears => self.chart_compare_years.previous(),
                     }
                 }
             }
@@ -458,15 +1954,22 @@ impl<'a> InputKeyHandler<'a> {
                             self.summary_modes.previous();
                             self.reload_summary();
                         }
+                        SummaryTab::Methods => {
+                            self.summary_methods.previous();
+                            self.reload_summary();
+                        }
                         SummaryTab::Years => {
                             self.summary_months.set_index_zero();
-                            self.summary_years.previous();
+                            self.summary_years.previous_period(skip_empty);
                             self.reload_summary();
                         }
                         SummaryTab::Months => {
-                            self.summary_months.previous();
+                            self.summary_months.previous_period(skip_empty);
                             self.reload_summary();
                         }
+                        SummaryTab::RangeStart | SummaryTab::RangeEnd => {
+                            self.step_summary_range(StepType::StepDown);
+                        }
                         _ => {}
                     }
                 }
@@ -478,14 +1981,21 @@ impl<'a> InputKeyHandler<'a> {
     /// Handles right arrow key press for multiple pages
     #[cfg(not(tarpaulin_include))]
     pub fn handle_right_arrow(&mut self) {
+        let skip_empty = get_bool_setting("skip_empty_periods", self.conn);
+
         match self.page {
             CurrentUi::Home => match self.home_tab {
                 HomeTab::Months => {
-                    self.home_months.next();
+                    match self.home_week.as_mut() {
+                        Some(week) => {
+                            step_week(week, true, self.home_months, self.home_years);
+                        }
+                        None => self.home_months.next_period(skip_empty),
+                    }
                     self.reload_home_table();
                 }
                 HomeTab::Years => {
-                    self.home_years.next();
+                    self.home_years.next_period(skip_empty);
                     self.home_months.set_index_zero();
                     self.reload_home_table();
                 }
@@ -501,14 +2011,24 @@ impl<'a> InputKeyHandler<'a> {
                             self.reload_chart();
                         }
                         ChartTab::Years => {
-                            self.chart_years.next();
+                            self.chart_years.next_period(skip_empty);
                             self.chart_months.set_index_zero();
+                            self.fill_chart_range();
                             self.reload_chart();
                         }
                         ChartTab::Months => {
-                            self.chart_months.next();
+                            self.chart_months.next_period(skip_empty);
+                            self.fill_chart_range();
                             self.reload_chart();
                         }
+                        ChartTab::RangeStart | ChartTab::RangeEnd => {
+                            self.step_chart_range(StepType::StepUp);
+                        }
+                        ChartTab::Tags => {
+                            self.chart_tags.next();
+                            self.reload_chart();
+                        }
+                        ChartTab::CompareYears => self.chart_compare_years.next(),
                     }
                 }
             }
@@ -517,15 +2037,22 @@ impl<'a> InputKeyHandler<'a> {
                     self.summary_modes.next();
                     self.reload_summary();
                 }
+                SummaryTab::Methods => {
+                    self.summary_methods.next();
+                    self.reload_summary();
+                }
                 SummaryTab::Years => {
                     self.summary_months.set_index_zero();
-                    self.summary_years.next();
+                    self.summary_years.next_period(skip_empty);
                     self.reload_summary();
                 }
                 SummaryTab::Months => {
-                    self.summary_months.next();
+                    self.summary_months.next_period(skip_empty);
                     self.reload_summary();
                 }
+                SummaryTab::RangeStart | SummaryTab::RangeEnd => {
+                    self.step_summary_range(StepType::StepUp);
+                }
                 _ => {}
             },
             _ => {}
@@ -635,9 +2162,13 @@ impl<'a> InputKeyHandler<'a> {
     #[cfg(not(tarpaulin_include))]
     pub fn clear_input(&mut self) {
         match self.page {
-            CurrentUi::AddTx => *self.add_tx_data = TxData::new(),
+            CurrentUi::AddTx => {
+                *self.add_tx_data = TxData::new();
+                self.add_tx_data.localize_date(&get_date_format(self.conn));
+            }
             CurrentUi::Search => {
                 *self.search_data = TxData::new();
+                self.search_data.localize_date(&get_date_format(self.conn));
                 self.reload_search_data()
             }
             _ => {}
@@ -654,6 +2185,16 @@ impl<'a> InputKeyHandler<'a> {
         }
     }
 
+    /// Opens the selected search result on the Home page. The date field is selected if no result is selected
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_search_enter(&mut self) {
+        if self.search_table.state.selected().is_some() && !self.is_search_subtotal_selected() {
+            self.show_search_tx();
+        } else {
+            self.select_date_field();
+        }
+    }
+
     #[cfg(not(tarpaulin_include))]
     pub fn select_date_field(&mut self) {
         match self.page {
@@ -667,35 +2208,339 @@ impl<'a> InputKeyHandler<'a> {
     #[cfg(not(tarpaulin_include))]
     pub fn change_summary_sort(&mut self) {
         *self.summary_sort = self.summary_sort.next_type();
-        let summary_data = self.summary_table.items.to_owned();
-        let sorted_data = sort_table_data(summary_data, self.summary_sort);
-        *self.summary_table = TableData::new(sorted_data);
+        *self.summary_sort_order = self.summary_sort.default_order();
+        self.sort_summary_table();
+    }
+
+    /// Flips the direction the summary tag table is sorted in
+    #[cfg(not(tarpaulin_include))]
+    pub fn flip_summary_sort_order(&mut self) {
+        *self.summary_sort_order = self.summary_sort_order.flip();
+        self.sort_summary_table();
     }
 
     #[cfg(not(tarpaulin_include))]
     pub fn search_tag(&mut self) {
         if let SummaryTab::Table = self.summary_tab {
             if let Some(index) = self.summary_table.state.selected() {
-                let tag_name = &self.summary_table.items[index][0];
-                let search_param = TxData::custom("", "", "", "", "", "", tag_name, 0);
+                let mut tag_name = self.summary_table.items[index][0].to_string();
+
+                // a group is searched with all the tags that are rolled up into it
+                if *self.summary_grouped {
+                    for (tag, group_name) in get_tag_groups(self.conn) {
+                        if group_name == tag_name {
+                            tag_name.push_str(&format!(", {tag}"));
+                        }
+                    }
+                }
+                // the search keeps the tx method the Summary was filtered by
+                let tx_method = self
+                    .summary_methods
+                    .get_summary_method()
+                    .unwrap_or_default();
+                let search_param = TxData::custom("", "", tx_method, "", "", "", &tag_name, 0);
                 *self.search_data = search_param;
+                let state = self.get_page_state();
                 self.go_search();
                 self.search_tx();
+                self.navigation.push(state);
+            }
+        }
+    }
+
+    /// Turns on the popup that shows the biggest transaction of the selected tag of the Summary table
+    #[cfg(not(tarpaulin_include))]
+    pub fn do_biggest_tx_popup(&mut self) {
+        if let SummaryTab::Table = self.summary_tab {
+            if let Some(index) = self.summary_table.state.selected() {
+                let tag = self.summary_table.items[index][0].to_string();
+
+                let tag_groups = if *self.summary_grouped {
+                    get_tag_groups(self.conn)
+                } else {
+                    HashMap::new()
+                };
+
+                let biggest_tx = self.summary_data.get_tag_biggest_tx(
+                    self.summary_modes,
+                    self.summary_months.index,
+                    self.summary_years.index,
+                    self.summary_range
+                        .get_active_range(self.summary_modes, self.conn),
+                    self.summary_methods.get_summary_method(),
+                    &tag_groups,
+                    &tag,
+                );
+
+                if let Some(tx) = biggest_tx {
+                    *self.popup = PopupState::BiggestTx(tag, tx);
+                }
+            }
+        }
+    }
+
+    /// Handles the key presses of the transaction details popup. Enter shows the selected
+    /// linked transaction on the Home page, L opens the popup to add a link and D removes the selected link
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_tx_details_popup(&mut self) {
+        let mut go_to = None;
+
+        if let PopupState::TxDetails(details_data) = self.popup {
+            match self.key.code {
+                KeyCode::Up => details_data.previous(),
+                KeyCode::Down => details_data.next(),
+                KeyCode::Enter => go_to = details_data.get_selected_link(),
+                KeyCode::Char('d') | KeyCode::Char('l') if *self.read_only => {
+                    details_data.set_status(READ_ONLY_MESSAGE.to_string());
+                    return;
+                }
+                KeyCode::Char('d') => {
+                    if let Some(linked_id) = details_data.get_selected_link() {
+                        let status = match remove_tx_link(
+                            details_data.get_id_num(),
+                            linked_id,
+                            RELATED_LINK,
+                            self.conn,
+                        ) {
+                            Ok(()) => "Link: Removed the selected link".to_string(),
+                            Err(e) => format!("Link: Failed to remove the link. Error: {e}"),
+                        };
+                        details_data.reload_links(self.conn);
+                        details_data.set_status(status);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        match self.key.code {
+            KeyCode::Char('l') => {
+                if let PopupState::TxDetails(details_data) =
+                    mem::replace(self.popup, PopupState::Nothing)
+                {
+                    let picker_data = TxPickerData::new_link_targets(
+                        details_data.get_id_num(),
+                        RELATED_LINK,
+                        self.conn,
+                    );
+                    *self.popup = PopupState::LinkPicker(picker_data, details_data);
+                }
+            }
+            KeyCode::Esc => *self.popup = PopupState::Nothing,
+            _ => {}
+        }
+
+        if let Some(linked_id) = go_to {
+            self.go_linked_tx(linked_id);
+        }
+    }
+
+    /// Handles the key presses of the popup that picks the transaction to link.
+    /// The details popup is shown again after a link is added or the popup is closed
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_link_picker_popup(&mut self) {
+        let mut selected = None;
+
+        if let PopupState::LinkPicker(picker_data, _) = self.popup {
+            match self.key.code {
+                KeyCode::Up => picker_data.previous(),
+                KeyCode::Down => picker_data.next(),
+                KeyCode::Backspace => picker_data.edit_query(None),
+                KeyCode::Char(c) => picker_data.edit_query(Some(c)),
+                KeyCode::Enter => selected = picker_data.get_selected(),
+                _ => {}
+            }
+        }
+
+        if selected.is_none() && self.key.code != KeyCode::Esc {
+            return;
+        }
+
+        if let PopupState::LinkPicker(_, mut details_data) =
+            mem::replace(self.popup, PopupState::Nothing)
+        {
+            if let Some(linked_id) = selected {
+                let status = match add_tx_link(
+                    details_data.get_id_num(),
+                    linked_id,
+                    RELATED_LINK,
+                    self.conn,
+                ) {
+                    Ok(()) => "Link: Linked to the selected transaction".to_string(),
+                    Err(e) => format!("Link: Failed to add the link. Error: {e}"),
+                };
+                details_data.reload_links(self.conn);
+                details_data.set_status(status);
+            }
+
+            *self.popup = PopupState::TxDetails(details_data);
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_refund_picker_popup(&mut self) {
+        let mut selected = None;
+
+        if let PopupState::RefundPicker(picker_data) = self.popup {
+            match self.key.code {
+                KeyCode::Up => picker_data.previous(),
+                KeyCode::Down => picker_data.next(),
+                KeyCode::Backspace => picker_data.edit_query(None),
+                KeyCode::Char(c) => picker_data.edit_query(Some(c)),
+                KeyCode::Enter => selected = picker_data.get_selected(),
+                _ => {}
+            }
+        }
+
+        if let Some(original_id) = selected {
+            self.add_tx_data.set_refund_original(original_id, self.conn);
+            self.add_tx_data
+                .add_tx_status("Refund: Linked to the selected expense".to_string());
+        }
+
+        if selected.is_some() || self.key.code == KeyCode::Esc {
+            *self.popup = PopupState::Nothing;
+        }
+    }
+
+    /// Picks a details template and takes the values of its placeholders. The finished
+    /// details are inserted at the cursor of the details field
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_details_template_popup(&mut self) {
+        let mut details = None;
+
+        if let PopupState::DetailsTemplates(template_data) = self.popup {
+            if template_data.is_filling() {
+                match self.key.code {
+                    KeyCode::Enter => details = template_data.confirm_value(),
+                    KeyCode::Backspace => template_data.edit_input(None),
+                    KeyCode::Char(c) => template_data.edit_input(Some(c)),
+                    _ => {}
+                }
+            } else {
+                match self.key.code {
+                    KeyCode::Up => template_data.previous(),
+                    KeyCode::Down => template_data.next(),
+                    KeyCode::Enter => details = template_data.select(),
+                    _ => {}
+                }
+            }
+        }
+
+        if let Some(details) = &details {
+            for c in details.chars() {
+                self.add_tx_data.edit_details(Some(c));
+            }
+            self.check_autofill();
+        }
+
+        if details.is_some() || self.key.code == KeyCode::Esc {
+            *self.popup = PopupState::Nothing;
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_method_picker_popup(&mut self) {
+        let mut selected = None;
+
+        if let PopupState::MethodPicker(picker_data) = self.popup {
+            match self.key.code {
+                KeyCode::Up => picker_data.previous(),
+                KeyCode::Down => picker_data.next(),
+                KeyCode::Enter => {
+                    selected = picker_data
+                        .get_selected()
+                        .map(|method| (picker_data.get_tab(), method.to_string()))
+                }
+                _ => {}
+            }
+        }
+
+        if let Some((tab, method)) = &selected {
+            self.add_tx_data.set_method(tab, method);
+            let status = match tab {
+                TxTab::ToMethod => self.add_tx_data.check_to_method(self.name_cache, self.conn),
+                _ => self
+                    .add_tx_data
+                    .check_from_method(self.name_cache, self.conn),
+            };
+            self.add_tx_data.add_tx_status(status.to_string());
+        }
+
+        if selected.is_some() || self.key.code == KeyCode::Esc {
+            *self.popup = PopupState::Nothing;
+        }
+    }
+
+    /// Closes the popup of the failed checks and selects the first invalid field
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_submit_errors_popup(&mut self) {
+        if let PopupState::SubmitErrors(errors) = mem::replace(self.popup, PopupState::Nothing) {
+            if let Some(field) = errors.first_field() {
+                *self.add_tx_tab = field;
+                self.go_correct_index();
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    pub fn handle_calendar_popup(&mut self) {
+        let mut selected = None;
+
+        if let PopupState::Calendar(calendar_data) = self.popup {
+            match self.key.code {
+                KeyCode::Left => calendar_data.move_days(-1),
+                KeyCode::Right => calendar_data.move_days(1),
+                KeyCode::Up => calendar_data.move_days(-7),
+                KeyCode::Down => calendar_data.move_days(7),
+                KeyCode::PageUp => calendar_data.previous_month(),
+                KeyCode::PageDown => calendar_data.next_month(),
+                KeyCode::Enter => selected = Some(calendar_data.get_iso_date()),
+                _ => {}
             }
         }
+
+        if let Some(date) = &selected {
+            let tx_data = match self.page {
+                CurrentUi::Search => &mut *self.search_data,
+                _ => &mut *self.add_tx_data,
+            };
+
+            // the date is checked right away so it is shown in the date format setting
+            tx_data.set_date(date);
+            let status = tx_data.check_date(self.conn);
+            tx_data.add_tx_status(status.to_string());
+        }
+
+        if selected.is_some() || self.key.code == KeyCode::Esc {
+            *self.popup = PopupState::Nothing;
+        }
     }
 
     #[cfg(not(tarpaulin_include))]
     pub fn handle_deletion_popup(&mut self) {
+        let deletion_data = match self.popup {
+            PopupState::TxDeletion(deletion_data) => deletion_data,
+            _ => return,
+        };
+
         match self.key.code {
             KeyCode::Left | KeyCode::Right => *self.deletion_status = self.deletion_status.next(),
             KeyCode::Enter => match self.deletion_status {
                 DeletionStatus::Yes => {
+                    if !deletion_data.is_confirmed(&get_amount_format(self.conn)) {
+                        self.toasts.push(StatusMessage::warn(
+                            "Type yes or the amount of the transaction to delete it",
+                        ));
+                        return;
+                    }
                     self.delete_tx();
                     *self.popup = PopupState::Nothing
                 }
                 DeletionStatus::No => *self.popup = PopupState::Nothing,
             },
+            KeyCode::Char(c) => deletion_data.push(c),
+            KeyCode::Backspace => deletion_data.pop(),
             _ => {}
         }
     }
@@ -811,12 +2656,32 @@ impl<'a> InputKeyHandler<'a> {
                             self.summary_table.state.select(None)
                         }
                     }
-                    _ => *self.summary_tab = self.summary_tab.change_tab_up_yearly(),
+                    _ => *self.summary_tab = self.summary_tab.change_tab_up_yearly(),
+                },
+                2 => match self.summary_tab {
+                    SummaryTab::Table => {
+                        if self.summary_table.state.selected() == Some(0) {
+                            *self.summary_tab = self.summary_tab.change_tab_up_all_time();
+                        } else {
+                            self.summary_table.previous()
+                        }
+                    }
+                    SummaryTab::ModeSelection => {
+                        if self.total_tags > 0 {
+                            self.summary_table.state.select(Some(self.total_tags - 1));
+                            *self.summary_tab = self.summary_tab.change_tab_up_all_time();
+                        } else {
+                            *self.summary_tab = self.summary_tab.change_tab_up_all_time();
+                            *self.summary_tab = self.summary_tab.change_tab_up_all_time();
+                            self.summary_table.state.select(None)
+                        }
+                    }
+                    _ => {}
                 },
-                2 => match self.summary_tab {
+                3 => match self.summary_tab {
                     SummaryTab::Table => {
                         if self.summary_table.state.selected() == Some(0) {
-                            *self.summary_tab = self.summary_tab.change_tab_up_all_time();
+                            *self.summary_tab = self.summary_tab.change_tab_up_range();
                         } else {
                             self.summary_table.previous()
                         }
@@ -824,14 +2689,14 @@ impl<'a> InputKeyHandler<'a> {
                     SummaryTab::ModeSelection => {
                         if self.total_tags > 0 {
                             self.summary_table.state.select(Some(self.total_tags - 1));
-                            *self.summary_tab = self.summary_tab.change_tab_up_all_time();
+                            *self.summary_tab = self.summary_tab.change_tab_up_range();
                         } else {
-                            *self.summary_tab = self.summary_tab.change_tab_up_all_time();
-                            *self.summary_tab = self.summary_tab.change_tab_up_all_time();
+                            *self.summary_tab = self.summary_tab.change_tab_up_range();
+                            *self.summary_tab = self.summary_tab.change_tab_up_range();
                             self.summary_table.state.select(None)
                         }
                     }
-                    _ => {}
+                    _ => *self.summary_tab = self.summary_tab.change_tab_up_range(),
                 },
                 _ => {}
             }
@@ -896,7 +2761,7 @@ impl<'a> InputKeyHandler<'a> {
                             self.summary_table.next()
                         }
                     }
-                    SummaryTab::ModeSelection => {
+                    SummaryTab::Methods => {
                         if self.total_tags > 0 {
                             self.summary_table.state.select(Some(0));
                             *self.summary_tab = self.summary_tab.change_tab_down_all_time();
@@ -908,6 +2773,26 @@ impl<'a> InputKeyHandler<'a> {
                     }
                     _ => {}
                 },
+                3 => match self.summary_tab {
+                    SummaryTab::Table => {
+                        if self.summary_table.state.selected() == Some(self.total_tags - 1) {
+                            *self.summary_tab = self.summary_tab.change_tab_down_range();
+                        } else {
+                            self.summary_table.next()
+                        }
+                    }
+                    SummaryTab::RangeEnd => {
+                        if self.total_tags > 0 {
+                            self.summary_table.state.select(Some(0));
+                            *self.summary_tab = self.summary_tab.change_tab_down_range();
+                        } else {
+                            *self.summary_tab = self.summary_tab.change_tab_down_range();
+                            *self.summary_tab = self.summary_tab.change_tab_down_range();
+                            self.summary_table.state.select(None)
+                        }
+                    }
+                    _ => *self.summary_tab = self.summary_tab.change_tab_down_range(),
+                },
                 _ => {}
             }
         } else if self.total_tags > 0 {
@@ -921,22 +2806,38 @@ impl<'a> InputKeyHandler<'a> {
 
     #[cfg(not(tarpaulin_include))]
     fn do_chart_up(&mut self) {
+        if let ChartView::YearComparison = self.chart_view {
+            if !*self.chart_hidden_mode {
+                *self.chart_tab = self.chart_tab.change_tab_comparison();
+            }
+            return;
+        }
+
         if !*self.chart_hidden_mode {
             match self.chart_modes.index {
                 0 => *self.chart_tab = self.chart_tab.change_tab_up_monthly(),
                 1 => *self.chart_tab = self.chart_tab.change_tab_up_yearly(),
-                _ => {}
+                RANGE_MODE => *self.chart_tab = self.chart_tab.change_tab_up_range(),
+                _ => *self.chart_tab = self.chart_tab.change_tab_all_time(),
             }
         }
     }
 
     #[cfg(not(tarpaulin_include))]
     fn do_chart_down(&mut self) {
+        if let ChartView::YearComparison = self.chart_view {
+            if !*self.chart_hidden_mode {
+                *self.chart_tab = self.chart_tab.change_tab_comparison();
+            }
+            return;
+        }
+
         if !*self.chart_hidden_mode {
             match self.chart_modes.index {
                 0 => *self.chart_tab = self.chart_tab.change_tab_down_monthly(),
                 1 => *self.chart_tab = self.chart_tab.change_tab_down_yearly(),
-                _ => {}
+                RANGE_MODE => *self.chart_tab = self.chart_tab.change_tab_down_range(),
+                _ => *self.chart_tab = self.chart_tab.change_tab_all_time(),
             }
         }
     }
@@ -945,7 +2846,7 @@ impl<'a> InputKeyHandler<'a> {
     fn check_add_tx_date(&mut self) {
         match self.key.code {
             KeyCode::Enter => {
-                let status = self.add_tx_data.check_date();
+                let status = self.add_tx_data.check_date(self.conn);
                 self.add_tx_data.add_tx_status(status.to_string());
                 match status {
                     VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
@@ -956,7 +2857,7 @@ impl<'a> InputKeyHandler<'a> {
                 }
             }
             KeyCode::Esc => {
-                let status = self.add_tx_data.check_date();
+                let status = self.add_tx_data.check_date(self.conn);
                 self.add_tx_data.add_tx_status(status.to_string());
                 match status {
                     VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
@@ -966,6 +2867,13 @@ impl<'a> InputKeyHandler<'a> {
                 }
             }
             KeyCode::Backspace => self.add_tx_data.edit_date(None),
+            KeyCode::Char('t') if self.key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let status = self.add_tx_data.do_date_today(self.conn);
+                self.add_tx_data.add_tx_status(status);
+            }
+            KeyCode::Char('d') if self.key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.do_calendar_popup()
+            }
             KeyCode::Char(a) => self.add_tx_data.edit_date(Some(a)),
             _ => {}
         }
@@ -975,11 +2883,30 @@ impl<'a> InputKeyHandler<'a> {
     fn check_add_tx_details(&mut self) {
         match self.key.code {
             KeyCode::Enter => {
-                *self.add_tx_tab = TxTab::TxType;
-                self.go_correct_index();
+                let status = self.add_tx_data.check_details(self.conn);
+                self.add_tx_data.add_tx_status(status.to_string());
+                match status {
+                    VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
+                        *self.add_tx_tab = TxTab::TxType;
+                        self.go_correct_index();
+                    }
+                    VerifyingOutput::NotAccepted(_) => {}
+                }
+            }
+            KeyCode::Esc => {
+                let status = self.add_tx_data.check_details(self.conn);
+                self.add_tx_data.add_tx_status(status.to_string());
+                match status {
+                    VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
+                        *self.add_tx_tab = TxTab::Nothing
+                    }
+                    VerifyingOutput::NotAccepted(_) => {}
+                }
             }
-            KeyCode::Esc => *self.add_tx_tab = TxTab::Nothing,
             KeyCode::Backspace => self.add_tx_data.edit_details(None),
+            KeyCode::Char('t') if self.key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.do_details_template_popup()
+            }
             KeyCode::Char(a) => self.add_tx_data.edit_details(Some(a)),
             _ => {}
         }
@@ -998,6 +2925,11 @@ impl<'a> InputKeyHandler<'a> {
                     }
                     VerifyingOutput::NotAccepted(_) => {}
                 }
+
+                // a refund picks the expense it refunds before anything else
+                if self.add_tx_data.needs_refund_original() {
+                    self.do_refund_picker_popup();
+                }
             }
             KeyCode::Esc => {
                 let status = self.add_tx_data.check_tx_type();
@@ -1008,6 +2940,10 @@ impl<'a> InputKeyHandler<'a> {
                     }
                     VerifyingOutput::NotAccepted(_) => {}
                 }
+
+                if self.add_tx_data.needs_refund_original() {
+                    self.do_refund_picker_popup();
+                }
             }
             KeyCode::Backspace => self.add_tx_data.edit_tx_type(None),
             KeyCode::Char(a) => self.add_tx_data.edit_tx_type(Some(a)),
@@ -1019,7 +2955,10 @@ impl<'a> InputKeyHandler<'a> {
     fn check_add_tx_from(&mut self) {
         match self.key.code {
             KeyCode::Enter => {
-                let status = self.add_tx_data.check_from_method(self.conn);
+                let typed = self.add_tx_data.get_method(&TxTab::FromMethod).to_string();
+                let status = self
+                    .add_tx_data
+                    .check_from_method(self.name_cache, self.conn);
                 self.add_tx_data.add_tx_status(status.to_string());
                 match status {
                     VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
@@ -1029,17 +2968,24 @@ impl<'a> InputKeyHandler<'a> {
                         }
                         self.go_correct_index();
                     }
-                    VerifyingOutput::NotAccepted(_) => {}
+                    VerifyingOutput::NotAccepted(_) => {
+                        self.check_method_match(TxTab::FromMethod, &typed)
+                    }
                 }
             }
             KeyCode::Esc => {
-                let status = self.add_tx_data.check_from_method(self.conn);
+                let typed = self.add_tx_data.get_method(&TxTab::FromMethod).to_string();
+                let status = self
+                    .add_tx_data
+                    .check_from_method(self.name_cache, self.conn);
                 self.add_tx_data.add_tx_status(status.to_string());
                 match status {
                     VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
                         *self.add_tx_tab = TxTab::Nothing
                     }
-                    VerifyingOutput::NotAccepted(_) => {}
+                    VerifyingOutput::NotAccepted(_) => {
+                        self.check_method_match(TxTab::FromMethod, &typed)
+                    }
                 }
             }
             KeyCode::Backspace => self.add_tx_data.edit_from_method(None),
@@ -1048,28 +2994,73 @@ impl<'a> InputKeyHandler<'a> {
         }
     }
 
+    /// Opens the calendar on the date of the date field of the current page
+    #[cfg(not(tarpaulin_include))]
+    fn do_calendar_popup(&mut self) {
+        let field_date = match self.page {
+            CurrentUi::Search => self.search_data.get_all_texts()[0].to_string(),
+            _ => self.add_tx_data.get_all_texts()[0].to_string(),
+        };
+
+        *self.popup = PopupState::Calendar(CalendarData::new(
+            &field_date,
+            &get_date_format(self.conn),
+            &get_date_range(self.conn),
+            get_week_start(self.conn),
+            SystemClock.today(),
+        ));
+    }
+
+    /// Shows the correction if the typed tx method was replaced by the closest one or opens
+    /// a popup to pick one if several tx methods are about as close to it
+    #[cfg(not(tarpaulin_include))]
+    fn check_method_match(&mut self, tab: TxTab, typed: &str) {
+        let typed = typed.trim();
+        let current = self.add_tx_data.get_method(&tab);
+
+        if !typed.is_empty() && current != typed {
+            self.toasts.push(StatusMessage::info(format!(
+                "TX Method: Corrected {typed} to {current}"
+            )));
+            return;
+        }
+
+        let candidates =
+            get_close_matches(typed, &self.name_cache.get_active_tx_methods(self.conn));
+
+        if candidates.len() > 1 {
+            *self.popup = PopupState::MethodPicker(MethodPickerData::new(tab, typed, candidates));
+        }
+    }
+
     #[cfg(not(tarpaulin_include))]
     fn check_add_tx_to(&mut self) {
         match self.key.code {
             KeyCode::Enter => {
-                let status = self.add_tx_data.check_to_method(self.conn);
+                let typed = self.add_tx_data.get_method(&TxTab::ToMethod).to_string();
+                let status = self.add_tx_data.check_to_method(self.name_cache, self.conn);
                 self.add_tx_data.add_tx_status(status.to_string());
                 match status {
                     VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
                         *self.add_tx_tab = TxTab::Amount;
                         self.go_correct_index();
                     }
-                    VerifyingOutput::NotAccepted(_) => {}
+                    VerifyingOutput::NotAccepted(_) => {
+                        self.check_method_match(TxTab::ToMethod, &typed)
+                    }
                 }
             }
             KeyCode::Esc => {
-                let status = self.add_tx_data.check_to_method(self.conn);
+                let typed = self.add_tx_data.get_method(&TxTab::ToMethod).to_string();
+                let status = self.add_tx_data.check_to_method(self.name_cache, self.conn);
                 self.add_tx_data.add_tx_status(status.to_string());
                 match status {
                     VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
                         *self.add_tx_tab = TxTab::Nothing
                     }
-                    VerifyingOutput::NotAccepted(_) => {}
+                    VerifyingOutput::NotAccepted(_) => {
+                        self.check_method_match(TxTab::ToMethod, &typed)
+                    }
                 }
             }
             KeyCode::Backspace => self.add_tx_data.edit_to_method(None),
@@ -1111,13 +3102,15 @@ impl<'a> InputKeyHandler<'a> {
     #[cfg(not(tarpaulin_include))]
     fn check_add_tx_tags(&mut self) {
         match self.key.code {
-            KeyCode::Enter => {
-                *self.add_tx_tab = TxTab::Nothing;
-                self.add_tx_data.check_tags();
-            }
-            KeyCode::Esc => {
-                *self.add_tx_tab = TxTab::Nothing;
-                self.add_tx_data.check_tags();
+            KeyCode::Enter | KeyCode::Esc => {
+                let status = self.add_tx_data.check_tags(self.name_cache, self.conn);
+                self.add_tx_data.add_tx_status(status.to_string());
+                match status {
+                    VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
+                        *self.add_tx_tab = TxTab::Nothing;
+                    }
+                    VerifyingOutput::NotAccepted(_) => {}
+                }
             }
             KeyCode::Backspace => self.add_tx_data.edit_tags(None),
             KeyCode::Char(a) => self.add_tx_data.edit_tags(Some(a)),
@@ -1129,7 +3122,7 @@ impl<'a> InputKeyHandler<'a> {
     fn check_search_date(&mut self) {
         match self.key.code {
             KeyCode::Enter => {
-                let status = self.search_data.check_date();
+                let status = self.search_data.check_date(self.conn);
                 self.search_data.add_tx_status(status.to_string());
                 match status {
                     VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
@@ -1140,7 +3133,7 @@ impl<'a> InputKeyHandler<'a> {
                 }
             }
             KeyCode::Esc => {
-                let status = self.search_data.check_date();
+                let status = self.search_data.check_date(self.conn);
                 self.search_data.add_tx_status(status.to_string());
                 match status {
                     VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
@@ -1150,6 +3143,13 @@ impl<'a> InputKeyHandler<'a> {
                 }
             }
             KeyCode::Backspace => self.search_data.edit_date(None),
+            KeyCode::Char('t') if self.key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let status = self.search_data.do_date_today(self.conn);
+                self.search_data.add_tx_status(status);
+            }
+            KeyCode::Char('d') if self.key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.do_calendar_popup()
+            }
             KeyCode::Char(a) => self.search_data.edit_date(Some(a)),
             _ => {}
         }
@@ -1203,7 +3203,9 @@ impl<'a> InputKeyHandler<'a> {
     fn check_search_from(&mut self) {
         match self.key.code {
             KeyCode::Enter => {
-                let status = self.search_data.check_from_method(self.conn);
+                let status = self
+                    .search_data
+                    .check_from_method(self.name_cache, self.conn);
                 self.search_data.add_tx_status(status.to_string());
                 match status {
                     VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
@@ -1217,7 +3219,9 @@ impl<'a> InputKeyHandler<'a> {
                 }
             }
             KeyCode::Esc => {
-                let status = self.search_data.check_from_method(self.conn);
+                let status = self
+                    .search_data
+                    .check_from_method(self.name_cache, self.conn);
                 self.search_data.add_tx_status(status.to_string());
                 match status {
                     VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
@@ -1236,7 +3240,7 @@ impl<'a> InputKeyHandler<'a> {
     fn check_search_to(&mut self) {
         match self.key.code {
             KeyCode::Enter => {
-                let status = self.search_data.check_to_method(self.conn);
+                let status = self.search_data.check_to_method(self.name_cache, self.conn);
                 self.search_data.add_tx_status(status.to_string());
                 match status {
                     VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
@@ -1247,7 +3251,7 @@ impl<'a> InputKeyHandler<'a> {
                 }
             }
             KeyCode::Esc => {
-                let status = self.search_data.check_to_method(self.conn);
+                let status = self.search_data.check_to_method(self.name_cache, self.conn);
                 self.search_data.add_tx_status(status.to_string());
                 match status {
                     VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
@@ -1296,7 +3300,9 @@ impl<'a> InputKeyHandler<'a> {
     fn check_search_tags(&mut self) {
         match self.key.code {
             KeyCode::Enter | KeyCode::Esc => {
-                let status = self.search_data.check_tags_forced(self.conn);
+                let status = self
+                    .search_data
+                    .check_tags_forced(self.name_cache, self.conn);
                 self.search_data.add_tx_status(status.to_string());
                 match status {
                     VerifyingOutput::Accepted(_) | VerifyingOutput::Nothing(_) => {
@@ -1313,21 +3319,267 @@ impl<'a> InputKeyHandler<'a> {
 
     #[cfg(not(tarpaulin_include))]
     fn reload_home_table(&mut self) {
-        *self.all_tx_data =
-            TransactionData::new(self.home_months.index, self.home_years.index, self.conn);
+        if let Some(week) = self.home_week.as_mut() {
+            align_week(
+                week,
+                self.home_months,
+                self.home_years,
+                get_week_start(self.conn),
+            );
+        }
+
+        *self.all_tx_data = TransactionData::new_period(
+            self.home_months.index,
+            self.home_years.index,
+            self.home_week,
+            self.home_filter,
+            self.conn,
+        );
         *self.table = TableData::new(self.all_tx_data.get_txs());
     }
 
+    /// Returns the dashboard data of the current month. The loaded Home transactions are
+    /// reused if they are of the current month without a filter
+    #[cfg(not(tarpaulin_include))]
+    fn get_dashboard_data(&self) -> DashboardData {
+        let today = SystemClock.today();
+        let date_range = get_date_range(self.conn);
+        let month = today.month0() as usize;
+        let year = date_range.year_index(today.year());
+
+        if date_range.contains(today.year())
+            && self.home_months.index == month
+            && self.home_years.index == year
+            && *self.home_filter == HomeFilter::All
+            && self.home_week.is_none()
+        {
+            return DashboardData::new(self.all_tx_data, &SystemClock, self.conn);
+        }
+
+        let tx_data = if date_range.contains(today.year()) {
+            TransactionData::new(month, year, &HomeFilter::All, self.conn)
+        } else {
+            TransactionData::new_search(Vec::new(), Vec::new())
+        };
+
+        DashboardData::new(&tx_data, &SystemClock, self.conn)
+    }
+
+    /// Returns the state of the current page to save before a cross-page jump
+    #[cfg(not(tarpaulin_include))]
+    fn get_page_state(&self) -> PageState {
+        let mut state = PageState::new(*self.page);
+
+        match self.page {
+            CurrentUi::Home => {
+                state.month_index = self.home_months.index;
+                state.year_index = self.home_years.index;
+                state.selected = self.table.state.selected();
+            }
+            CurrentUi::Chart => {
+                state.mode_index = self.chart_modes.index;
+                state.month_index = self.chart_months.index;
+                state.year_index = self.chart_years.index;
+            }
+            CurrentUi::Summary => {
+                state.mode_index = self.summary_modes.index;
+                state.month_index = self.summary_months.index;
+                state.year_index = self.summary_years.index;
+                state.selected = self.summary_table.state.selected();
+            }
+            CurrentUi::Search => state.selected = self.search_table.state.selected(),
+            _ => {}
+        }
+
+        state
+    }
+
+    /// Opens the page of the state with the tabs and the selection it had when it was left
+    #[cfg(not(tarpaulin_include))]
+    fn restore_page_state(&mut self, state: PageState) {
+        *self.page = state.page;
+
+        match state.page {
+            CurrentUi::Home => {
+                self.home_months.index = state.month_index;
+                self.home_years.index = state.year_index;
+                self.reload_home_table();
+                *self.home_tab = HomeTab::Months;
+
+                if let Some(index) = state.selected {
+                    if index < self.table.items.len() {
+                        self.table.state.select(Some(index));
+                        *self.home_tab = HomeTab::Table;
+                    }
+                }
+            }
+            CurrentUi::Chart => {
+                self.chart_modes.index = state.mode_index;
+                self.chart_months.index = state.month_index;
+                self.chart_years.index = state.year_index;
+                *self.chart_tab = ChartTab::ModeSelection;
+                self.reload_chart();
+            }
+            CurrentUi::Summary => {
+                self.summary_modes.index = state.mode_index;
+                self.summary_months.index = state.month_index;
+                self.summary_years.index = state.year_index;
+                self.reload_summary();
+                *self.summary_tab = SummaryTab::ModeSelection;
+
+                if let Some(index) = state.selected {
+                    if index < self.summary_table.items.len() {
+                        self.summary_table.state.select(Some(index));
+                        *self.summary_tab = SummaryTab::Table;
+                    }
+                }
+            }
+            CurrentUi::Search => {
+                if let Some(index) = state.selected {
+                    if index < self.search_table.items.len() {
+                        self.search_table.state.select(Some(index));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Reloads the Home table and keeps the selection on the same position
+    /// or the last row if the table got shorter
+    #[cfg(not(tarpaulin_include))]
+    fn reload_home_keeping_selection(&mut self, index: usize) {
+        self.reload_home_table();
+        let total_rows = self.all_tx_data.all_tx.len();
+        if total_rows > 0 {
+            self.table.state.select(Some(index.min(total_rows - 1)));
+        }
+    }
+
     #[cfg(not(tarpaulin_include))]
     fn reload_summary(&mut self) {
-        let summary_table = self.summary_data.get_table_data(
+        let custom_range = self
+            .summary_range
+            .get_active_range(self.summary_modes, self.conn);
+
+        let tag_groups = if *self.summary_grouped {
+            get_tag_groups(self.conn)
+        } else {
+            HashMap::new()
+        };
+
+        let summary_table = self.summary_data.get_page_table_data(
             self.summary_modes,
             self.summary_months.index,
             self.summary_years.index,
+            custom_range,
+            self.summary_methods.get_summary_method(),
+            &tag_groups,
+            *self.summary_compare,
         );
         self.total_tags = summary_table.len();
-        *self.summary_table = TableData::new(summary_table);
-        *self.summary_sort = SortingType::ByTags;
+        *self.summary_table = TableData::new(sort_table_data_by(
+            summary_table,
+            self.summary_sort,
+            *self.summary_sort_order,
+        ));
+    }
+
+    /// Selects the first row of the Summary table that matches the type-ahead, if any
+    #[cfg(not(tarpaulin_include))]
+    fn select_summary_type_ahead_match(&mut self) {
+        if let Some(index) = self
+            .summary_type_ahead
+            .find_match(&self.summary_table.items)
+        {
+            self.summary_table.state.select(Some(index));
+        }
+    }
+
+    /// Sorts the summary tag table with the current sort. The selected row stays on its tag
+    #[cfg(not(tarpaulin_include))]
+    fn sort_summary_table(&mut self) {
+        let selected_tag = self
+            .summary_table
+            .state
+            .selected()
+            .map(|index| self.summary_table.items[index][0].to_string());
+
+        let summary_data = self.summary_table.items.to_owned();
+        let sorted_data =
+            sort_table_data_by(summary_data, self.summary_sort, *self.summary_sort_order);
+        *self.summary_table = TableData::new(sorted_data);
+
+        if let Some(tag) = selected_tag {
+            let index = self
+                .summary_table
+                .items
+                .iter()
+                .position(|row| row[0] == tag);
+            self.summary_table.state.select(index);
+        }
+    }
+
+    /// Steps the selected date box of the Summary page Range mode and reloads the summary
+    #[cfg(not(tarpaulin_include))]
+    fn step_summary_range(&mut self, step_type: StepType) {
+        let step_size = self.get_date_step_size();
+
+        match self.summary_tab {
+            SummaryTab::RangeStart => self
+                .summary_range
+                .step_start_date(step_type, step_size, self.conn),
+            SummaryTab::RangeEnd => self
+                .summary_range
+                .step_end_date(step_type, step_size, self.conn),
+            _ => {}
+        }
+        self.reload_summary();
+    }
+
+    /// Steps the selected date box of the Chart page Range mode and reloads the chart
+    #[cfg(not(tarpaulin_include))]
+    fn step_chart_range(&mut self, step_type: StepType) {
+        let step_size = self.get_date_step_size();
+
+        match self.chart_tab {
+            ChartTab::RangeStart => self
+                .chart_range
+                .step_start_date(step_type, step_size, self.conn),
+            ChartTab::RangeEnd => self
+                .chart_range
+                .step_end_date(step_type, step_size, self.conn),
+            _ => {}
+        }
+        self.reload_chart();
+    }
+
+    /// Fills the date boxes of the Chart page Range mode with the selected year in the Yearly mode
+    /// or from the Years tab of the Range mode, and with the selected month otherwise
+    #[cfg(not(tarpaulin_include))]
+    fn fill_chart_range(&mut self) {
+        let year = get_date_range(self.conn).year_at(self.chart_years.index);
+
+        match (self.chart_modes.index, &self.chart_tab) {
+            (1, _) | (RANGE_MODE, ChartTab::Years) => self.chart_range.set_year(year, self.conn),
+            (0, _) | (RANGE_MODE, _) => {
+                self.chart_range
+                    .set_month(year, self.chart_months.index, self.conn)
+            }
+            _ => {}
+        }
+    }
+
+    /// Reloads the Summary after the excluded tags or the view changed. The table keeps the sort
+    #[cfg(not(tarpaulin_include))]
+    fn reload_summary_after_exclusions(&mut self) {
+        self.reload_summary_data();
+        self.reload_summary();
+        if let SummaryTab::Table = self.summary_tab {
+            if self.total_tags > 0 {
+                self.summary_table.state.select(Some(0));
+            }
+        }
     }
 
     #[cfg(not(tarpaulin_include))]
@@ -1360,15 +3612,54 @@ impl<'a> InputKeyHandler<'a> {
         }
     }
 
+    /// Returns how far the date field should step based on the held modifier key.
+    /// Ctrl steps by months, Shift by years and Alt by weeks
+    #[cfg(not(tarpaulin_include))]
+    fn get_date_step_size(&self) -> DateStepSize {
+        let modifiers = self.key.modifiers;
+
+        if modifiers.contains(KeyModifiers::CONTROL) {
+            DateStepSize::Month
+        } else if modifiers.contains(KeyModifiers::SHIFT) {
+            DateStepSize::Year
+        } else if modifiers.contains(KeyModifiers::ALT) {
+            DateStepSize::Week
+        } else {
+            DateStepSize::Day
+        }
+    }
+
+    /// Returns how many times of the amount step the amount field should step based on
+    /// the held modifier key. Shift steps 10 times and Ctrl 100 times
+    #[cfg(not(tarpaulin_include))]
+    fn get_amount_step_size(&self) -> AmountStepSize {
+        let modifiers = self.key.modifiers;
+
+        if modifiers.contains(KeyModifiers::CONTROL) {
+            AmountStepSize::Hundred
+        } else if modifiers.contains(KeyModifiers::SHIFT) {
+            AmountStepSize::Ten
+        } else {
+            AmountStepSize::Single
+        }
+    }
+
     #[cfg(not(tarpaulin_include))]
     fn do_add_tx_up(&mut self) {
         let status = match self.add_tx_tab {
-            TxTab::Date => self.add_tx_data.do_date_up(),
-            TxTab::FromMethod => self.add_tx_data.do_from_method_up(self.conn),
-            TxTab::ToMethod => self.add_tx_data.do_to_method_up(self.conn),
-            TxTab::Amount => self.add_tx_data.do_amount_up(false, self.conn),
+            TxTab::Date => self
+                .add_tx_data
+                .do_date_up(self.get_date_step_size(), self.conn),
+            TxTab::FromMethod => self
+                .add_tx_data
+                .do_from_method_up(self.name_cache, self.conn),
+            TxTab::ToMethod => self.add_tx_data.do_to_method_up(self.name_cache, self.conn),
+            TxTab::Amount => {
+                self.add_tx_data
+                    .do_amount_up(false, self.get_amount_step_size(), self.conn)
+            }
             TxTab::TxType => self.add_tx_data.do_tx_type_up(),
-            TxTab::Tags => self.add_tx_data.do_tags_up(self.conn),
+            TxTab::Tags => self.add_tx_data.do_tags_up(self.name_cache, self.conn),
             _ => Ok(()),
         };
 
@@ -1380,12 +3671,21 @@ impl<'a> InputKeyHandler<'a> {
     #[cfg(not(tarpaulin_include))]
     fn do_add_tx_down(&mut self) {
         let status = match self.add_tx_tab {
-            TxTab::Date => self.add_tx_data.do_date_down(),
-            TxTab::FromMethod => self.add_tx_data.do_from_method_down(self.conn),
-            TxTab::ToMethod => self.add_tx_data.do_to_method_down(self.conn),
-            TxTab::Amount => self.add_tx_data.do_amount_down(false, self.conn),
+            TxTab::Date => self
+                .add_tx_data
+                .do_date_down(self.get_date_step_size(), self.conn),
+            TxTab::FromMethod => self
+                .add_tx_data
+                .do_from_method_down(self.name_cache, self.conn),
+            TxTab::ToMethod => self
+                .add_tx_data
+                .do_to_method_down(self.name_cache, self.conn),
+            TxTab::Amount => {
+                self.add_tx_data
+                    .do_amount_down(false, self.get_amount_step_size(), self.conn)
+            }
             TxTab::TxType => self.add_tx_data.do_tx_type_down(),
-            TxTab::Tags => self.add_tx_data.do_tags_down(self.conn),
+            TxTab::Tags => self.add_tx_data.do_tags_down(self.name_cache, self.conn),
             _ => Ok(()),
         };
 
@@ -1397,19 +3697,35 @@ impl<'a> InputKeyHandler<'a> {
     #[cfg(not(tarpaulin_include))]
     fn do_search_up(&mut self) {
         let status = match self.search_tab {
-            TxTab::Date => self.search_data.do_date_up(),
-            TxTab::FromMethod => self.search_data.do_from_method_up(self.conn),
-            TxTab::ToMethod => self.search_data.do_to_method_up(self.conn),
-            TxTab::Amount => self.search_data.do_amount_up(true, self.conn),
+            TxTab::Date => self
+                .search_data
+                .do_date_up(self.get_date_step_size(), self.conn),
+            TxTab::FromMethod => self
+                .search_data
+                .do_from_method_up(self.name_cache, self.conn),
+            TxTab::ToMethod => self.search_data.do_to_method_up(self.name_cache, self.conn),
+            TxTab::Amount => {
+                self.search_data
+                    .do_amount_up(true, self.get_amount_step_size(), self.conn)
+            }
             TxTab::TxType => self.search_data.do_tx_type_up(),
-            TxTab::Tags => self.search_data.do_tags_up(self.conn),
+            TxTab::Tags => self.search_data.do_tags_up(self.name_cache, self.conn),
             TxTab::Nothing => {
-                if self.search_table.state.selected() == Some(0) {
-                    self.search_table
-                        .state
-                        .select(Some(self.search_table.items.len() - 1));
-                } else if !self.search_txs.all_tx.is_empty() {
-                    self.search_table.previous();
+                if !self.search_txs.all_tx.is_empty() {
+                    // subtotal rows cannot be selected
+                    loop {
+                        if self.search_table.state.selected() == Some(0) {
+                            self.search_table
+                                .state
+                                .select(Some(self.search_table.items.len() - 1));
+                        } else {
+                            self.search_table.previous();
+                        }
+
+                        if !self.is_search_subtotal_selected() {
+                            break;
+                        }
+                    }
                 }
                 Ok(())
             }
@@ -1424,17 +3740,37 @@ impl<'a> InputKeyHandler<'a> {
     #[cfg(not(tarpaulin_include))]
     fn do_search_down(&mut self) {
         let status = match self.search_tab {
-            TxTab::Date => self.search_data.do_date_down(),
-            TxTab::FromMethod => self.search_data.do_from_method_down(self.conn),
-            TxTab::ToMethod => self.search_data.do_to_method_down(self.conn),
-            TxTab::Amount => self.search_data.do_amount_down(true, self.conn),
+            TxTab::Date => self
+                .search_data
+                .do_date_down(self.get_date_step_size(), self.conn),
+            TxTab::FromMethod => self
+                .search_data
+                .do_from_method_down(self.name_cache, self.conn),
+            TxTab::ToMethod => self
+                .search_data
+                .do_to_method_down(self.name_cache, self.conn),
+            TxTab::Amount => {
+                self.search_data
+                    .do_amount_down(true, self.get_amount_step_size(), self.conn)
+            }
             TxTab::TxType => self.search_data.do_tx_type_down(),
-            TxTab::Tags => self.search_data.do_tags_down(self.conn),
+            TxTab::Tags => self.search_data.do_tags_down(self.name_cache, self.conn),
             TxTab::Nothing => {
-                if self.search_table.state.selected() == Some(self.search_table.items.len() - 1) {
-                    self.search_table.state.select(Some(0));
-                } else if !self.search_txs.all_tx.is_empty() {
-                    self.search_table.next();
+                if !self.search_txs.all_tx.is_empty() {
+                    // subtotal rows cannot be selected
+                    loop {
+                        if self.search_table.state.selected()
+                            == Some(self.search_table.items.len() - 1)
+                        {
+                            self.search_table.state.select(Some(0));
+                        } else {
+                            self.search_table.next();
+                        }
+
+                        if !self.is_search_subtotal_selected() {
+                            break;
+                        }
+                    }
                 }
                 Ok(())
             }
@@ -1446,11 +3782,111 @@ impl<'a> InputKeyHandler<'a> {
         }
     }
 
+    #[cfg(not(tarpaulin_include))]
+    fn is_search_subtotal_selected(&self) -> bool {
+        match self.search_table.state.selected() {
+            Some(index) => self.search_table.items[index][4] == "Subtotal",
+            None => false,
+        }
+    }
+
+    /// Returns the index of the selected search result among the search transactions.
+    /// Subtotal rows are not a part of them
+    #[cfg(not(tarpaulin_include))]
+    fn get_search_tx_index(&self) -> Option<usize> {
+        let index = match self.search_table.state.selected() {
+            Some(index) if !self.is_search_subtotal_selected() => index,
+            _ => return None,
+        };
+
+        Some(
+            self.search_table.items[..index]
+                .iter()
+                .filter(|row| row[4] != "Subtotal")
+                .count(),
+        )
+    }
+
+    /// Checks whether the selected search result comes from the attached archive and warns
+    /// that it has to be un-archived first. Its id_num can belong to a transaction of the main db
+    #[cfg(not(tarpaulin_include))]
+    fn is_archived_search_tx_selected(&mut self) -> bool {
+        if !matches!(self.page, CurrentUi::Search) {
+            return false;
+        }
+
+        let archived = self
+            .get_search_tx_index()
+            .map_or(false, |tx_index| self.search_txs.is_archived(tx_index));
+
+        if archived {
+            self.toasts.push(StatusMessage::warn(
+                "Archived transactions can not be changed. Un-archive them first",
+            ));
+        }
+        archived
+    }
+
+    /// Closes the details popup and shows the linked transaction on the Home page. The popup
+    /// stays open with the reason if it can not be shown
+    #[cfg(not(tarpaulin_include))]
+    fn go_linked_tx(&mut self, linked_id: i32) {
+        let tx = match get_tx_by_id(linked_id, self.conn) {
+            Some(tx) => tx,
+            None => return,
+        };
+
+        let date = match NaiveDate::parse_from_str(&tx[0], "%Y-%m-%d") {
+            Ok(date) => date,
+            Err(_) => return,
+        };
+
+        match self.select_home_tx(linked_id, &tx[1], date) {
+            Ok(()) => *self.popup = PopupState::Nothing,
+            Err(e) => {
+                if let PopupState::TxDetails(details_data) = self.popup {
+                    details_data.set_status(format!("Link: {e}"));
+                }
+            }
+        }
+    }
+
+    /// Returns the id_num and the saved data of the transaction selected on the Home or the
+    /// Search page. Archived search results are not returned
+    #[cfg(not(tarpaulin_include))]
+    fn get_selected_tx(&self) -> Option<(i32, Vec<String>)> {
+        match self.page {
+            CurrentUi::Home => {
+                let index = self.table.state.selected()?;
+                let id_num = self.all_tx_data.get_id_num(index);
+                get_tx_by_id(id_num, self.conn).map(|tx| (id_num, tx))
+            }
+            CurrentUi::Search => {
+                let tx_index = self.get_search_tx_index()?;
+
+                // archived transactions can share the id_num of an existing one
+                if self.search_txs.is_archived(tx_index) {
+                    return None;
+                }
+
+                let id_num = self.search_txs.get_id_num(tx_index);
+                get_tx_by_id(id_num, self.conn).map(|tx| (id_num, tx))
+            }
+            _ => None,
+        }
+    }
+
     #[cfg(not(tarpaulin_include))]
     fn check_autofill(&mut self) {
         match self.page {
-            CurrentUi::AddTx => self.add_tx_data.check_autofill(self.add_tx_tab, self.conn),
-            CurrentUi::Search => self.search_data.check_autofill(self.search_tab, self.conn),
+            CurrentUi::AddTx => {
+                self.add_tx_data
+                    .check_autofill(self.add_tx_tab, self.name_cache, self.conn)
+            }
+            CurrentUi::Search => {
+                self.search_data
+                    .check_autofill(self.search_tab, self.name_cache, self.conn)
+            }
             _ => {}
         }
     }
@@ -13,14 +13,22 @@ pub fn search_keys(handler: &mut InputKeyHandler) -> Option<HandlingOutput> {
                 KeyCode::Char('a') => handler.go_add_tx(),
                 KeyCode::Char('r') => handler.go_chart(),
                 KeyCode::Char('z') => handler.go_summary(),
-                KeyCode::Char('q') => return Some(HandlingOutput::QuitUi),
+                KeyCode::Char('q') => return handler.quit_app(),
                 KeyCode::Char('f') => handler.go_home(),
                 KeyCode::Char('h') => handler.do_help_popup(),
                 KeyCode::Char('s') => handler.search_tx(),
                 KeyCode::Char('c') => handler.clear_input(),
+                KeyCode::Char('v') => handler.toggle_archived_search(),
+                KeyCode::Char('g') => handler.change_search_grouping(),
                 KeyCode::Up => handler.handle_up_arrow(),
                 KeyCode::Down => handler.handle_down_arrow(),
-                KeyCode::Enter => handler.select_date_field(),
+                KeyCode::Char('e') | KeyCode::Char('o') => handler.show_search_tx(),
+                KeyCode::Char('y') => handler.copy_selected_tx(),
+                KeyCode::Char('Y') => handler.copy_table(),
+                KeyCode::Char('i') => handler.do_tx_details_popup(),
+                KeyCode::Char('n') => handler.refund_tx(),
+                KeyCode::Esc | KeyCode::Backspace => handler.go_back(),
+                KeyCode::Enter => handler.handle_search_enter(),
                 KeyCode::Char(c) => {
                     if c.is_numeric() {
                         handler.handle_number_press()
@@ -46,6 +54,9 @@ pub fn search_keys(handler: &mut InputKeyHandler) -> Option<HandlingOutput> {
                 },
             },
         },
+        PopupState::TxDetails(_) => handler.handle_tx_details_popup(),
+        PopupState::LinkPicker(..) => handler.handle_link_picker_popup(),
+        PopupState::Calendar(_) => handler.handle_calendar_popup(),
         _ => handler.do_empty_popup(),
     }
 
@@ -8,14 +8,49 @@ use crossterm::event::KeyCode;
 pub fn summary_keys(handler: &mut InputKeyHandler) -> Option<HandlingOutput> {
     match handler.popup {
         PopupState::Nothing => match handler.key.code {
+            // the date boxes of the Range mode take the date characters and Backspace
+            KeyCode::Char(c) if handler.is_summary_range_selected() && is_date_char(c) => {
+                handler.edit_summary_range(Some(c))
+            }
+            KeyCode::Backspace if handler.is_summary_range_selected() => {
+                handler.edit_summary_range(None)
+            }
+            KeyCode::Enter if handler.is_summary_range_selected() => handler.verify_summary_range(),
+            KeyCode::Char(c) if handler.is_months_tab_selected() && c.is_ascii_digit() => {
+                handler.do_month_jump(c)
+            }
+            // a typed prefix on the tag table may contain a dot
+            KeyCode::Char('.') if !handler.is_summary_type_ahead_active() => {
+                handler.do_current_month_jump()
+            }
+            // typing on the tag table jumps to the first tag that starts with the typed text
+            KeyCode::Char(c) if handler.is_summary_type_ahead_key() => {
+                handler.do_summary_type_ahead(c)
+            }
+            KeyCode::Backspace if handler.is_summary_type_ahead_active() => {
+                handler.shrink_summary_type_ahead()
+            }
+            KeyCode::Esc if handler.is_summary_type_ahead_active() => {
+                handler.clear_summary_type_ahead()
+            }
             KeyCode::Char('a') => handler.go_add_tx(),
             KeyCode::Char('r') => handler.go_chart(),
-            KeyCode::Char('q') => return Some(HandlingOutput::QuitUi),
+            KeyCode::Char('q') => return handler.quit_app(),
             KeyCode::Char('f') => handler.go_home(),
             KeyCode::Char('w') => handler.go_search(),
             KeyCode::Char('h') => handler.do_help_popup(),
             KeyCode::Char('z') => handler.do_summary_hidden_mode(),
             KeyCode::Char('s') => handler.change_summary_sort(),
+            KeyCode::Char('S') => handler.flip_summary_sort_order(),
+            KeyCode::Char('g') => handler.do_summary_grouped_mode(),
+            KeyCode::Char('c') => handler.do_summary_compare_mode(),
+            KeyCode::Char('x') => handler.do_excluded_tags_popup(),
+            KeyCode::Char('v') => handler.do_summary_raw_view_toggle(),
+            KeyCode::Char('t') => handler.do_summary_transfers_toggle(),
+            KeyCode::Char('b') => handler.do_biggest_tx_popup(),
+            KeyCode::Char('y') => handler.copy_summary_table(false),
+            KeyCode::Char('Y') => handler.copy_summary_table(true),
+            KeyCode::Esc | KeyCode::Backspace => handler.go_back(),
             KeyCode::Right => handler.handle_right_arrow(),
             KeyCode::Left => handler.handle_left_arrow(),
             KeyCode::Up => handler.handle_up_arrow(),
@@ -23,8 +58,17 @@ pub fn summary_keys(handler: &mut InputKeyHandler) -> Option<HandlingOutput> {
             KeyCode::Enter => handler.search_tag(),
             _ => {}
         },
+        PopupState::ExcludedTags(_) => handler.handle_excluded_tags_popup(),
         _ => handler.do_empty_popup(),
     }
 
+    handler.save_summary_preferences();
+
     None
 }
+
+/// Checks whether the character can be a part of a typed date
+#[cfg(not(tarpaulin_include))]
+pub(super) fn is_date_char(c: char) -> bool {
+    c.is_ascii_digit() || c == '-' || c == '/' || c == '.'
+}
@@ -1,7 +1,8 @@
 mod add_tx_page;
-mod chart_page;
+pub mod api;
+pub mod chart_page;
 pub mod db;
-mod home_page;
+pub mod home_page;
 mod initial_page;
 mod key_checker;
 pub mod outputs;
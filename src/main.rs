@@ -1,11 +1,13 @@
 use dirs::data_local_dir;
 use rex_tui::page_handler::initialize_app;
-use std::env::{current_dir, set_current_dir};
+use std::env::{args, current_dir, set_current_dir};
 use std::fs;
 
 fn main() {
     if let Some(dir) = data_local_dir() {
         let current_dir = current_dir().unwrap().display().to_string();
+        // opens the database without allowing any changes
+        let read_only = args().any(|arg| arg == "--read-only");
         let mut verifying_path = "./data.sqlite";
 
         // OS based path where data will be stored
@@ -18,7 +20,7 @@ fn main() {
         // to the OS data directory
         fs::create_dir_all(&working_path).unwrap();
         set_current_dir(working_path).unwrap();
-        if initialize_app(verifying_path, &current_dir).is_err() {
+        if initialize_app(verifying_path, &current_dir, read_only).is_err() {
             std::process::exit(1);
         }
     } else {
@@ -1,3 +1,5 @@
+use crate::outputs::NAType;
+use crate::page_handler::TxTab;
 use rusqlite::Error as sqlError;
 use std::fmt;
 use std::io::Error;
@@ -25,6 +27,7 @@ impl std::error::Error for TerminalExecutionError {}
 pub enum UiHandlingError {
     DrawingError(Error),
     PollingError(Error),
+    SuspendingError(Error),
 }
 
 impl fmt::Display for UiHandlingError {
@@ -37,19 +40,27 @@ impl fmt::Display for UiHandlingError {
             UiHandlingError::PollingError(err) => {
                 write!(f, "Error while polling for keyboard input. {err}")
             }
+            UiHandlingError::SuspendingError(err) => {
+                write!(f, "Error while suspending or resuming the app. {err}")
+            }
         }
     }
 }
 
 impl std::error::Error for UiHandlingError {}
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum CheckingError {
     EmptyDate,
     EmptyMethod,
     EmptyAmount,
     EmptyTxType,
+    EmptyTags,
     SameTxMethod,
+    NoRefundOriginal,
+    InvalidRefundOriginal,
+    RefundTooBig(String),
+    NotAccepted(NAType),
 }
 
 impl fmt::Display for CheckingError {
@@ -60,16 +71,102 @@ impl fmt::Display for CheckingError {
             CheckingError::EmptyMethod => write!(f, "Tx Method: TX Method cannot be empty"),
             CheckingError::EmptyAmount => write!(f, "Amount: Amount cannot be empty"),
             CheckingError::EmptyTxType => write!(f, "Tx Type: Transaction Type cannot be empty"),
+            CheckingError::EmptyTags => write!(f, "Tags: At least one tag is required"),
             CheckingError::SameTxMethod => write!(
                 f,
                 "Tx Method: From and To methods cannot be the same for Transfer"
             ),
+            CheckingError::NoRefundOriginal => write!(
+                f,
+                "Tx Type: A refund must be linked to the expense it refunds"
+            ),
+            CheckingError::InvalidRefundOriginal => write!(
+                f,
+                "Tx Type: The linked transaction is not an expense that can be refunded"
+            ),
+            CheckingError::RefundTooBig(refundable) => write!(
+                f,
+                "Amount: The refund cannot be more than the {refundable} left of the expense"
+            ),
+            CheckingError::NotAccepted(value) => write!(f, "{value}"),
         }
     }
 }
 
 impl std::error::Error for CheckingError {}
 
+/// Contains every check that failed while submitting a transaction along with
+/// the field it belongs to, in the order the fields appear on the page
+#[derive(Debug, Default)]
+pub struct CheckingErrors {
+    errors: Vec<(TxTab, CheckingError)>,
+}
+
+impl CheckingErrors {
+    pub fn new() -> Self {
+        CheckingErrors { errors: Vec::new() }
+    }
+
+    /// Adds a failed check of the given field
+    pub fn add(&mut self, field: TxTab, error: CheckingError) {
+        self.errors.push((field, error));
+    }
+
+    pub fn get_errors(&self) -> &Vec<(TxTab, CheckingError)> {
+        &self.errors
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Returns the first field that failed any check
+    pub fn first_field(&self) -> Option<TxTab> {
+        self.errors.first().map(|(field, _)| *field)
+    }
+
+    /// Returns all the fields that failed any check without any repetition
+    pub fn get_fields(&self) -> Vec<TxTab> {
+        let mut fields = Vec::new();
+        for (field, _) in &self.errors {
+            if !fields.contains(field) {
+                fields.push(*field);
+            }
+        }
+        fields
+    }
+
+    /// Returns the report shown when a transaction can not be saved. Every failed check
+    /// gets its own line in the order the fields appear on the page
+    pub fn get_report(&self) -> String {
+        let field_count = self.get_fields().len();
+        let problem = if field_count == 1 {
+            "1 field needs".to_string()
+        } else {
+            format!("{field_count} fields need")
+        };
+        format!("{problem} fixing before the transaction can be saved\n\n{self}")
+    }
+}
+
+impl fmt::Display for CheckingErrors {
+    #[cfg(not(tarpaulin_include))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines = self
+            .errors
+            .iter()
+            .map(|(_, error)| error.to_string())
+            .collect::<Vec<String>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl std::error::Error for CheckingErrors {}
+
 pub enum SteppingError {
     InvalidDate,
     InvalidTxMethod,
@@ -107,6 +204,40 @@ impl fmt::Display for SteppingError {
     }
 }
 
+/// Errors of the public database API
+#[derive(Debug)]
+pub enum ApiError {
+    /// The file is not a database created by Rex
+    NotRexDatabase,
+    /// The transaction did not pass the checks. Contains every invalid field
+    InvalidTx(CheckingErrors),
+    /// A transaction with the same external id was already added
+    AlreadyImported(String),
+    Sql(sqlError),
+}
+
+impl From<sqlError> for ApiError {
+    fn from(e: sqlError) -> Self {
+        ApiError::Sql(e)
+    }
+}
+
+impl fmt::Display for ApiError {
+    #[cfg(not(tarpaulin_include))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::NotRexDatabase => write!(f, "The database was not created by Rex"),
+            ApiError::InvalidTx(errors) => write!(f, "Invalid transaction:\n{errors}"),
+            ApiError::AlreadyImported(external_id) => {
+                write!(f, "Transaction {external_id} was already imported")
+            }
+            ApiError::Sql(e) => write!(f, "Database error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
 pub enum TxUpdateError {
     FailedAddTx(sqlError),
     FailedEditTx(sqlError),
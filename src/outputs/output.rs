@@ -1,9 +1,12 @@
+use crate::utility::RoundingMode;
 use std::fmt;
 
 pub enum HandlingOutput {
     QuitUi,
     TakeUserInput,
     PrintNewUpdate,
+    /// The instance lock was taken and the database has to be opened for writing again
+    LeaveReadOnly,
 }
 
 #[derive(PartialEq, Debug)]
@@ -18,6 +21,9 @@ impl fmt::Display for VerifyingOutput {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             VerifyingOutput::Nothing(value) => write!(f, "{value}: Nothing to check"),
+            VerifyingOutput::Accepted(AType::RoundedAmount(mode)) => {
+                write!(f, "Amount: Accepted. Extra decimals were {mode}")
+            }
             VerifyingOutput::Accepted(value) => write!(f, "{value}: Accepted"),
             VerifyingOutput::NotAccepted(value) => write!(f, "{value}"),
         }
@@ -28,8 +34,11 @@ impl fmt::Display for VerifyingOutput {
 #[derive(PartialEq, Debug)]
 pub enum AType {
     Date,
+    Details,
     TxMethod,
     Amount,
+    /// An amount with more decimals than the tx method keeps. Contains how they were dropped
+    RoundedAmount(RoundingMode),
     TxType,
     Tags,
 }
@@ -39,8 +48,9 @@ impl fmt::Display for AType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             AType::Date => write!(f, "Date"),
+            AType::Details => write!(f, "Details"),
             AType::TxMethod => write!(f, "Tx Method"),
-            AType::Amount => write!(f, "Amount"),
+            AType::Amount | AType::RoundedAmount(_) => write!(f, "Amount"),
             AType::TxType => write!(f, "Tx Type"),
             AType::Tags => write!(f, "Tags"),
         }
@@ -54,7 +64,7 @@ pub enum NAType {
     InvalidYear,
     InvalidMonth,
     InvalidDay,
-    YearTooBig,
+    YearTooBig(i32, i32),
     MonthTooBig,
     DayTooBig,
     NonExistingDate,
@@ -63,7 +73,10 @@ pub enum NAType {
     InvalidTxType,
     ParsingError(AType),
     InvalidBValue,
-    NonExistingTag,
+    UnknownTags(Vec<String>),
+    DetailsTooLong(usize),
+    TooManyTags(usize),
+    TagTooLong(usize),
 }
 
 impl fmt::Display for NAType {
@@ -83,7 +96,7 @@ impl fmt::Display for NAType {
                 f,
                 "Date: Day length not acceptable. Example Date: 2022-05-01"
             ),
-            NAType::YearTooBig => write!(f, "Date: Year must be between 2022-2037"),
+            NAType::YearTooBig(start, end) => write!(f, "Date: Year must be between {start}-{end}"),
             NAType::MonthTooBig => write!(f, "Date: Month must be between 01-12"),
             NAType::DayTooBig => write!(f, "Date: Day must be between 01-31"),
             NAType::NonExistingDate => {
@@ -93,7 +106,7 @@ impl fmt::Display for NAType {
             NAType::InvalidTxMethod => write!(f, "TX Method: Transaction Method not found"),
             NAType::InvalidTxType => write!(
                 f,
-                "TX Type: Transaction Type not acceptable. Values: Expense/Income/E/I"
+                "TX Type: Transaction Type not acceptable. Values: Expense/Income/Transfer/Refund/E/I/T/R"
             ),
             NAType::ParsingError(error) => {
                 write!(f, "{error}: Error acquired while validating input")
@@ -102,7 +115,22 @@ impl fmt::Display for NAType {
                 f,
                 "Amount: TX Method cannot be empty. Value of B cannot be determined"
             ),
-            NAType::NonExistingTag => write!(f, "Tags: Non-existing tags cannot be accepted"),
+            NAType::UnknownTags(tags) => write!(
+                f,
+                "Tags: Non-existing tags cannot be accepted: {}",
+                tags.join(", ")
+            ),
+            NAType::DetailsTooLong(max_length) => write!(
+                f,
+                "Details: Text was cut to the maximum of {max_length} characters"
+            ),
+            NAType::TooManyTags(max_tags) => {
+                write!(f, "Tags: Only the first {max_tags} tags were kept")
+            }
+            NAType::TagTooLong(max_length) => write!(
+                f,
+                "Tags: Tags were cut to the maximum of {max_length} characters"
+            ),
         }
     }
 }
@@ -112,6 +140,34 @@ pub enum StepType {
     StepDown,
 }
 
+/// How many times of the amount step a single step moves the amount field
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AmountStepSize {
+    Single,
+    Ten,
+    Hundred,
+}
+
+impl AmountStepSize {
+    /// Returns the number the amount step is multiplied with
+    pub fn multiplier(&self) -> i64 {
+        match self {
+            AmountStepSize::Single => 1,
+            AmountStepSize::Ten => 10,
+            AmountStepSize::Hundred => 100,
+        }
+    }
+}
+
+/// How far a single step moves the date field
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateStepSize {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
 pub enum TxType {
     IncomeExpense,
     Transfer,
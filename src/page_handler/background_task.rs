@@ -0,0 +1,146 @@
+use rusqlite::Connection;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The frames of the spinner shown while the total of a task is not known yet
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+
+/// How long a single spinner frame is shown
+const SPINNER_FRAME_TIME: Duration = Duration::from_millis(200);
+
+/// A message sent from the thread of a background task to the UI
+pub enum TaskMessage {
+    /// The number of done and total steps
+    Progress(usize, usize),
+    /// The report of the finished task or the reason it failed
+    Finished(Result<String, String>),
+}
+
+/// The side of a background task the operation runs with. Sends the progress
+/// to the UI and tells whether the task was asked to stop
+pub struct TaskProgress {
+    sender: Sender<TaskMessage>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TaskProgress {
+    /// Sends the number of done and total steps. Nothing happens if the UI is gone
+    pub fn update(&self, done: usize, total: usize) {
+        self.sender.send(TaskMessage::Progress(done, total)).ok();
+    }
+
+    /// Returns whether the task should stop at the next point it safely can
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// A long database operation running on its own thread with its own connection so the UI keeps
+/// drawing while it works. The UI polls it on every tick and reloads its data once it is done
+pub struct BackgroundTask {
+    name: String,
+    receiver: Receiver<TaskMessage>,
+    cancelled: Arc<AtomicBool>,
+    done: usize,
+    total: usize,
+    started: Instant,
+}
+
+impl BackgroundTask {
+    /// Starts the operation on a new thread with a new connection to the database at the path.
+    /// The operation returns the report shown once it is done or the reason it failed
+    pub fn start<F>(name: &str, db_path: &str, operation: F) -> Self
+    where
+        F: FnOnce(&mut Connection, &TaskProgress) -> Result<String, String> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let progress = TaskProgress {
+            sender,
+            cancelled: cancelled.clone(),
+        };
+        let db_path = db_path.to_string();
+
+        thread::spawn(move || {
+            let result = match Connection::open(&db_path) {
+                Ok(mut conn) => conn
+                    .execute("PRAGMA foreign_keys = ON", [])
+                    .map_err(|e| e.to_string())
+                    .and_then(|_| operation(&mut conn, &progress)),
+                Err(e) => Err(e.to_string()),
+            };
+            progress.sender.send(TaskMessage::Finished(result)).ok();
+        });
+
+        BackgroundTask {
+            name: name.to_string(),
+            receiver,
+            cancelled,
+            done: 0,
+            total: 0,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Reads every message sent since the last poll. Returns the result of the
+    /// operation once it is done
+    pub fn poll(&mut self) -> Option<Result<String, String>> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(TaskMessage::Progress(done, total)) => {
+                    self.done = done;
+                    self.total = total;
+                }
+                Ok(TaskMessage::Finished(result)) => return Some(result),
+                Err(TryRecvError::Empty) => return None,
+                // the thread ended without sending the result, it can only happen on a panic
+                Err(TryRecvError::Disconnected) => {
+                    return Some(Err("The operation stopped unexpectedly".to_string()))
+                }
+            }
+        }
+    }
+
+    /// Asks the operation to stop at the next point it safely can
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of done and total steps
+    pub fn get_progress(&self) -> (usize, usize) {
+        (self.done, self.total)
+    }
+
+    /// Returns the percentage of the done steps. None until the operation sends its total
+    pub fn get_percentage(&self) -> Option<u16> {
+        if self.total == 0 {
+            None
+        } else {
+            Some((self.done.min(self.total) * 100 / self.total) as u16)
+        }
+    }
+
+    /// Returns the spinner frame for the time that passed since the task started
+    pub fn get_spinner(&self) -> char {
+        self.get_spinner_at(Instant::now())
+    }
+
+    /// Returns the spinner frame for the time that passed since the task started at the given time
+    pub fn get_spinner_at(&self, now: Instant) -> char {
+        let frame = now.saturating_duration_since(self.started).as_millis()
+            / SPINNER_FRAME_TIME.as_millis();
+        SPINNER_FRAMES[frame as usize % SPINNER_FRAMES.len()]
+    }
+}
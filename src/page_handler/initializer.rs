@@ -1,13 +1,20 @@
-use crate::db::{add_new_tx_methods, rename_column, reposition_column};
+use crate::db::{
+    add_details_template, add_new_tx_methods, add_template, archive_txs, delete_details_template,
+    delete_template, rename_column, reposition_column, set_date_range, set_method_hidden,
+    set_minor_units, set_setting, set_tag_bounds, set_tag_group, unarchive_txs, ARCHIVE_DB,
+};
 use crate::initial_page::check_version;
 use crate::outputs::HandlingOutput;
-use crate::page_handler::start_app;
+use crate::page_handler::{start_app, BackgroundTask};
+use crate::tx_handler::{import_csv_task, import_journal_task};
 use crate::utility::{
-    check_n_create_db, check_old_sql, enter_tui_interface, exit_tui_interface, start_taking_input,
-    start_terminal, start_timer,
+    ask_read_only_mode, check_db_on_startup, check_n_create_db, check_name_collisions,
+    check_old_sql, enter_tui_interface, exit_tui_interface, get_hidden_tx_methods, needs_migration,
+    register_suspend_signals, start_taking_input, start_terminal, start_timer, InstanceLock,
+    LockStatus, LOCK_FILE,
 };
 use atty::Stream;
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags};
 use std::fs::File;
 use std::io::prelude::*;
 use std::{error::Error, process};
@@ -15,7 +22,11 @@ use std::{error::Error, process};
 use super::UserInputType;
 
 #[cfg(not(tarpaulin_include))]
-pub fn initialize_app(verifying_path: &str, current_dir: &str) -> Result<(), Box<dyn Error>> {
+pub fn initialize_app(
+    verifying_path: &str,
+    current_dir: &str,
+    read_only_flag: bool,
+) -> Result<(), Box<dyn Error>> {
     let new_version_available = check_version()?;
     if !atty::is(Stream::Stdout) {
         if let Err(err) = start_terminal(current_dir) {
@@ -25,17 +36,69 @@ pub fn initialize_app(verifying_path: &str, current_dir: &str) -> Result<(), Box
         }
     }
 
+    // only one instance can write to the database. Others can only open it in read-only mode.
+    // Starting in read-only mode does not take the lock so another instance can still write
+    let mut lock = if read_only_flag {
+        None
+    } else {
+        match InstanceLock::acquire(LOCK_FILE)? {
+            LockStatus::Acquired(lock) => {
+                lock.set_panic_hook();
+                Some(lock)
+            }
+            LockStatus::HeldBy(pid) => {
+                if !ask_read_only_mode(pid) {
+                    return Ok(());
+                }
+                None
+            }
+        }
+    };
+    let mut read_only = lock.is_none();
+
     // create a new db if not found. If there is an error, delete the failed data.sqlite file and exit
-    check_n_create_db(verifying_path)?;
+    if !read_only {
+        check_n_create_db(verifying_path)?;
+    }
 
-    let mut conn = Connection::open(verifying_path)?;
+    // a corrupted or unknown file is dealt with before anything reads it
+    if !check_db_on_startup(verifying_path, read_only)? {
+        return Ok(());
+    }
 
-    // initiates migration if old database is detected.
-    check_old_sql(&mut conn);
+    let mut conn = if read_only {
+        // nothing is created or migrated, the database is only opened as it is
+        let conn = Connection::open_with_flags(verifying_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+        // the pages expect the tables that older databases do not have yet
+        if needs_migration(&conn) {
+            println!("\nThe database was created by an older version of Rex and has to be updated first.");
+            println!("Start Rex once without read-only mode while no other instance is running.");
+            return Ok(());
+        }
+        conn
+    } else {
+        open_writable_db(verifying_path)?
+    };
+
+    // the long database operation running on its own thread, if any. It outlives
+    // the TUI restarts so it can be started from outside of it
+    let mut background_task: Option<BackgroundTask> = None;
+
+    // registered once here as every restart of the TUI would otherwise add another handler
+    let suspend_flags = register_suspend_signals();
 
     loop {
         let mut terminal = enter_tui_interface()?;
-        let result = start_app(&mut terminal, &new_version_available, &mut conn);
+        let result = start_app(
+            &mut terminal,
+            &new_version_available,
+            &mut read_only,
+            &mut background_task,
+            &mut lock,
+            &suspend_flags,
+            &mut conn,
+        );
         exit_tui_interface()?;
 
         match result {
@@ -54,7 +117,7 @@ pub fn initialize_app(verifying_path: &str, current_dir: &str) -> Result<(), Box
                         let old_name = &rename_data[0];
                         let new_name = &rename_data[1];
 
-                        let status = rename_column(old_name, new_name, &mut conn);
+                        let status = rename_column(old_name, new_name, ARCHIVE_DB, &mut conn);
 
                         match status {
                             Ok(_) => start_timer("Tx Method renamed successfully."),
@@ -75,16 +138,187 @@ pub fn initialize_app(verifying_path: &str, current_dir: &str) -> Result<(), Box
                             }
                         }
                     }
+                    UserInputType::ArchiveTxs(cutoff) => {
+                        let status = archive_txs(&cutoff, ARCHIVE_DB, &mut conn);
+
+                        match status {
+                            Ok(total) => start_timer(format!("Archived {total} transactions successfully.")),
+                            Err(e) => {
+                                println!("Error while archiving transactions. Error: {e:?}");
+                                start_timer("");
+                            }
+                        }
+                    }
+                    UserInputType::UnarchiveTxs => {
+                        let status = unarchive_txs(ARCHIVE_DB, &mut conn);
+
+                        match status {
+                            Ok(total) => start_timer(format!("Restored {total} archived transactions successfully.")),
+                            Err(e) => {
+                                println!("Error while restoring archived transactions. Error: {e:?}");
+                                start_timer("");
+                            }
+                        }
+                    }
+                    UserInputType::AssignTagGroup(group_data) => {
+                        let tag = &group_data[0];
+                        let group_name = &group_data[1];
+
+                        let status = set_tag_group(tag, group_name, &mut conn);
+
+                        match status {
+                            Ok(_) => start_timer("Tag group updated successfully."),
+                            Err(e) => {
+                                println!("Error while updating tag group. Error: {e:?}");
+                                start_timer("");
+                            }
+                        }
+                    }
+                    UserInputType::SetMinorUnits(tx_method, units) => {
+                        let status = set_minor_units(&tx_method, units, &mut conn);
+
+                        match status {
+                            Ok(_) => start_timer("Decimal places updated successfully."),
+                            Err(e) => {
+                                println!("Error while updating decimal places. Error: {e:?}");
+                                start_timer("");
+                            }
+                        }
+                    }
+                    UserInputType::SetTagBounds(tag, min_amount, max_amount) => {
+                        let status = set_tag_bounds(&tag, min_amount, max_amount, &mut conn);
+
+                        match status {
+                            Ok(_) => start_timer("Tag bounds updated successfully."),
+                            Err(e) => {
+                                println!("Error while updating tag bounds. Error: {e:?}");
+                                start_timer("");
+                            }
+                        }
+                    }
+                    UserInputType::SetDateRange(date_range) => {
+                        let status = set_date_range(&date_range, &mut conn);
+
+                        match status {
+                            Ok(_) => start_timer("Year range changed successfully."),
+                            Err(e) => {
+                                println!("Error while changing the year range. Error: {e:?}");
+                                start_timer("");
+                            }
+                        }
+                    }
+                    UserInputType::ToggleHiddenMethod(tx_method) => {
+                        let hidden = get_hidden_tx_methods(&conn).contains(&tx_method);
+                        let status = set_method_hidden(&tx_method, !hidden, &mut conn);
+
+                        match status {
+                            Ok(_) if hidden => start_timer("Transaction Method is shown again."),
+                            Ok(_) => start_timer("Transaction Method hidden successfully."),
+                            Err(e) => {
+                                println!("Error while hiding the tx method. Error: {e:?}");
+                                start_timer("");
+                            }
+                        }
+                    }
+                    UserInputType::ChangeSetting(setting_data) => {
+                        let key = &setting_data[0];
+                        let value = &setting_data[1];
+
+                        match set_setting(key, value, &conn) {
+                            Ok(_) => start_timer("Setting changed successfully."),
+                            Err(e) => {
+                                println!("Error while changing setting. Error: {e:?}");
+                                start_timer("");
+                            }
+                        }
+                    }
+                    UserInputType::AddTemplate(template_data) => {
+                        let remind_day = template_data[5].parse::<u32>().ok();
+
+                        let status = add_template(
+                            &template_data[0],
+                            &template_data[1],
+                            &template_data[2],
+                            &template_data[3],
+                            &template_data[4],
+                            remind_day,
+                            &conn,
+                        );
+
+                        match status {
+                            Ok(_) => start_timer("Template added successfully."),
+                            Err(e) => {
+                                println!("Error while adding template. Error: {e:?}");
+                                start_timer("");
+                            }
+                        }
+                    }
+                    UserInputType::DeleteTemplate(id_num) => {
+                        match delete_template(id_num, &mut conn) {
+                            Ok(_) => start_timer("Template deleted successfully."),
+                            Err(e) => {
+                                println!("Error while deleting template. Error: {e:?}");
+                                start_timer("");
+                            }
+                        }
+                    }
+                    UserInputType::AddDetailsTemplate(text) => {
+                        match add_details_template(&text, &conn) {
+                            Ok(_) => start_timer("Details template added successfully."),
+                            Err(e) => {
+                                println!("Error while adding details template. Error: {e:?}");
+                                start_timer("");
+                            }
+                        }
+                    }
+                    UserInputType::DeleteDetailsTemplate(id_num) => {
+                        match delete_details_template(id_num, &conn) {
+                            Ok(_) => start_timer("Details template deleted successfully."),
+                            Err(e) => {
+                                println!("Error while deleting details template. Error: {e:?}");
+                                start_timer("");
+                            }
+                        }
+                    }
+                    UserInputType::ImportJournal(txs, problems) => {
+                        // the TUI shows the progress and the report once it is done
+                        background_task = Some(BackgroundTask::start(
+                            "Journal import",
+                            verifying_path,
+                            move |conn, progress| {
+                                Ok(import_journal_task(&txs, problems, progress, conn))
+                            },
+                        ));
+                    }
+                    UserInputType::ImportCsv(profile, new_methods, txs, problems) => {
+                        // the report lists the created tx methods and the skipped duplicates
+                        background_task = Some(BackgroundTask::start(
+                            "CSV import",
+                            verifying_path,
+                            move |conn, progress| {
+                                import_csv_task(profile, new_methods, &txs, problems, progress, conn)
+                            },
+                        ));
+                    }
                     UserInputType::CancelledOperation => {
                         start_timer("Operation Cancelled.")
                     }
                     _ => {}
                 },
+                // the lock is already taken. The updates skipped in read-only mode are done now
+                HandlingOutput::LeaveReadOnly => {
+                    conn = open_writable_db(verifying_path)?;
+                    read_only = false;
+                }
                 HandlingOutput::QuitUi => break,
                 HandlingOutput::PrintNewUpdate => println!("Could not open browser.\n\nLatest Version Link: https://github.com/TheRustyPickle/Rex/releases/latest")
             },
             Err(error) => {
                 println!("{error}");
+                // exiting does not drop the lock
+                if let Some(lock) = &lock {
+                    lock.release();
+                }
                 process::exit(1);
             }
         }
@@ -92,3 +326,16 @@ pub fn initialize_app(verifying_path: &str, current_dir: &str) -> Result<(), Box
 
     Ok(())
 }
+
+/// Opens the database for writing and brings it up to date with the app
+#[cfg(not(tarpaulin_include))]
+fn open_writable_db(verifying_path: &str) -> Result<Connection, Box<dyn Error>> {
+    let mut conn = Connection::open(verifying_path)?;
+
+    // initiates migration if old database is detected.
+    check_old_sql(&mut conn);
+
+    // tx methods and tags that only differ by case or spacing can be merged before starting
+    check_name_collisions(&mut conn);
+    Ok(conn)
+}
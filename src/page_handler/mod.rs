@@ -1,7 +1,15 @@
+mod background_task;
 mod initializer;
+mod name_cache;
+mod toast;
+mod tx_periods;
 mod ui_handler;
 mod ui_state;
 
+pub use background_task::*;
 pub use initializer::initialize_app;
+pub use name_cache::*;
+pub use toast::*;
+pub use tx_periods::*;
 pub use ui_handler::*;
 pub use ui_state::*;
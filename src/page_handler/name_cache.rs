@@ -0,0 +1,152 @@
+use crate::db::{get_f64_setting, get_tag_limits, TagLimits};
+use crate::utility::{
+    filter_active_tx_methods, get_all_tags, get_all_tx_methods, get_hidden_tx_methods,
+    get_usage_data, rank_by_usage, SuggestionIndex,
+};
+use rusqlite::Connection;
+use std::cell::{Cell, RefCell};
+
+/// Keeps the tx methods and tags in memory so drawing the pages and every key press
+/// read them without querying the db. Owned by the page handler and invalidated
+/// whenever a tx method or a tag gets added, renamed or removed. The autofill indexes
+/// are built from them on the first suggestion after an invalidation. The hidden tx methods
+/// and the tag limits that the fields are checked with are kept the same way.
+///
+/// tx_methods: `["Cash", "Bank"]`
+///
+/// tags: `["Food", "Rent"]`
+pub struct NameCache {
+    tx_methods: RefCell<Option<Vec<String>>>,
+    tags: RefCell<Option<Vec<String>>>,
+    hidden_tx_methods: RefCell<Option<Vec<String>>>,
+    tag_limits: Cell<Option<TagLimits>>,
+    method_index: RefCell<Option<SuggestionIndex>>,
+    tag_index: RefCell<Option<SuggestionIndex>>,
+    generation: Cell<usize>,
+    loads: Cell<usize>,
+}
+
+impl Default for NameCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NameCache {
+    pub fn new() -> Self {
+        NameCache {
+            tx_methods: RefCell::new(None),
+            tags: RefCell::new(None),
+            hidden_tx_methods: RefCell::new(None),
+            tag_limits: Cell::new(None),
+            method_index: RefCell::new(None),
+            tag_index: RefCell::new(None),
+            generation: Cell::new(0),
+            loads: Cell::new(0),
+        }
+    }
+
+    /// Returns all tx methods. They are only queried on the first call after an invalidation
+    pub fn get_tx_methods(&self, conn: &Connection) -> Vec<String> {
+        self.tx_methods
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                self.loads.set(self.loads.get() + 1);
+                get_all_tx_methods(conn)
+            })
+            .clone()
+    }
+
+    /// Returns the hidden tx methods. They are only queried on the first call after an invalidation
+    pub fn get_hidden_tx_methods(&self, conn: &Connection) -> Vec<String> {
+        self.hidden_tx_methods
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                self.loads.set(self.loads.get() + 1);
+                get_hidden_tx_methods(conn)
+            })
+            .clone()
+    }
+
+    /// Returns the tx methods that are not hidden. Every tx method if all of them are hidden
+    pub fn get_active_tx_methods(&self, conn: &Connection) -> Vec<String> {
+        filter_active_tx_methods(self.get_tx_methods(conn), &self.get_hidden_tx_methods(conn))
+    }
+
+    /// Returns the tag limits. They are only queried on the first call after an invalidation
+    pub fn get_tag_limits(&self, conn: &Connection) -> TagLimits {
+        match self.tag_limits.get() {
+            Some(limits) => limits,
+            None => {
+                self.loads.set(self.loads.get() + 1);
+                let limits = get_tag_limits(conn);
+                self.tag_limits.set(Some(limits));
+                limits
+            }
+        }
+    }
+
+    /// Returns all unique tags. They are only queried on the first call after an invalidation
+    pub fn get_tags(&self, conn: &Connection) -> Vec<String> {
+        self.tags
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                self.loads.set(self.loads.get() + 1);
+                get_all_tags(conn)
+            })
+            .clone()
+    }
+
+    /// Returns the tx method that is not hidden the typed text autofills to
+    pub fn get_method_suggestion(&self, typed: &str, conn: &Connection) -> Option<String> {
+        self.method_index
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                let (_, method_usage) = get_usage_data(conn);
+                let recency_weight = get_f64_setting("autofill_recency_weight", conn);
+                SuggestionIndex::new(rank_by_usage(
+                    self.get_active_tx_methods(conn),
+                    &method_usage,
+                    recency_weight,
+                ))
+            })
+            .get_suggestion(typed)
+    }
+
+    /// Returns the tag the typed text autofills to
+    pub fn get_tag_suggestion(&self, typed: &str, conn: &Connection) -> Option<String> {
+        self.tag_index
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                let (tag_usage, _) = get_usage_data(conn);
+                let recency_weight = get_f64_setting("autofill_recency_weight", conn);
+                SuggestionIndex::new(rank_by_usage(
+                    self.get_tags(conn),
+                    &tag_usage,
+                    recency_weight,
+                ))
+            })
+            .get_suggestion(typed)
+    }
+
+    /// Drops the cached names so the next read gets them from the db again
+    pub fn invalidate(&self) {
+        *self.tx_methods.borrow_mut() = None;
+        *self.tags.borrow_mut() = None;
+        *self.hidden_tx_methods.borrow_mut() = None;
+        self.tag_limits.set(None);
+        *self.method_index.borrow_mut() = None;
+        *self.tag_index.borrow_mut() = None;
+        self.generation.set(self.generation.get() + 1);
+    }
+
+    /// Returns how many times the names were invalidated
+    pub fn get_generation(&self) -> usize {
+        self.generation.get()
+    }
+
+    /// Returns how many times any of the cached data was queried
+    pub fn get_load_count(&self) -> usize {
+        self.loads.get()
+    }
+}
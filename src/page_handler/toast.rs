@@ -0,0 +1,133 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a toast stays on the screen before it goes away on its own
+pub const TOAST_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// The most toasts shown at once. Pushing more drops the oldest one
+pub const MAX_TOASTS: usize = 3;
+
+/// Shown when something that changes the database is done while another instance holds the lock
+pub const READ_ONLY_MESSAGE: &str = "Read-only mode, changes are disabled";
+
+/// How important a message is. Decides the color it is shown with
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Severity {
+    Info,
+    Warn,
+    Error,
+}
+
+/// A short message about something that happened. Shared by everything that shows transient
+/// messages so the same message can be put on a status line or in a toast
+#[derive(Debug, PartialEq, Clone)]
+pub struct StatusMessage {
+    text: String,
+    severity: Severity,
+}
+
+impl StatusMessage {
+    pub fn new(text: impl Into<String>, severity: Severity) -> Self {
+        StatusMessage {
+            text: text.into(),
+            severity,
+        }
+    }
+
+    pub fn info(text: impl Into<String>) -> Self {
+        Self::new(text, Severity::Info)
+    }
+
+    pub fn warn(text: impl Into<String>) -> Self {
+        Self::new(text, Severity::Warn)
+    }
+
+    pub fn error(text: impl Into<String>) -> Self {
+        Self::new(text, Severity::Error)
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn get_severity(&self) -> Severity {
+        self.severity
+    }
+}
+
+/// The queue of toasts shown in the corner of the screen. Cloning it returns a handle to
+/// the same queue so the key handler and operations running outside of it can push to it.
+/// Toasts never take the key presses, those always go to the page under them
+#[derive(Default, Clone)]
+pub struct Toasts {
+    queue: Arc<Mutex<VecDeque<(StatusMessage, Instant)>>>,
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Toasts {
+            queue: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// Shows the message from now on
+    pub fn push(&self, message: StatusMessage) {
+        self.push_at(message, Instant::now());
+    }
+
+    /// Shows the message from the given time. The oldest toast is dropped if the queue is full
+    pub fn push_at(&self, message: StatusMessage, now: Instant) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.push_back((message, now));
+        while queue.len() > MAX_TOASTS {
+            queue.pop_front();
+        }
+    }
+
+    /// Removes the newest toast. Returns false if there was nothing to remove
+    pub fn dismiss_newest(&self) -> bool {
+        self.queue.lock().unwrap().pop_back().is_some()
+    }
+
+    /// Returns the messages of the toasts, the oldest one first
+    pub fn get_messages(&self) -> Vec<StatusMessage> {
+        self.queue
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(message, _)| message.clone())
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.lock().unwrap().is_empty()
+    }
+
+    /// Returns whether any toast was shown for longer than the timeout
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(Instant::now())
+    }
+
+    /// Returns whether any toast was shown for longer than the timeout at the given time
+    pub fn is_expired_at(&self, now: Instant) -> bool {
+        self.queue
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(_, pushed)| now >= *pushed + TOAST_TIMEOUT)
+    }
+
+    /// Removes every toast that was shown for longer than the timeout
+    pub fn remove_expired(&self) {
+        self.remove_expired_at(Instant::now());
+    }
+
+    /// Removes every toast that was shown for longer than the timeout at the given time
+    pub fn remove_expired_at(&self, now: Instant) {
+        self.queue
+            .lock()
+            .unwrap()
+            .retain(|(_, pushed)| now < *pushed + TOAST_TIMEOUT);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::page_handler::IndexedData;
+use crate::utility::{get_db_modified_time, get_tx_periods};
+use rusqlite::Connection;
+use std::collections::HashSet;
+use std::time::SystemTime;
+
+/// Stores the months that have at least one transaction. Shared by the month and year tabs of
+/// every page. The months are only reloaded once the db gets modified.
+///
+/// periods: `{"2022-05", "2023-01"}`
+#[derive(Default)]
+pub struct TxPeriods {
+    loaded: bool,
+    modified_time: Option<SystemTime>,
+    periods: HashSet<String>,
+}
+
+impl TxPeriods {
+    pub fn new() -> Self {
+        TxPeriods {
+            loaded: false,
+            modified_time: None,
+            periods: HashSet::new(),
+        }
+    }
+
+    /// Reloads the months if the cache is outdated
+    pub fn update(&mut self, conn: &Connection) {
+        let modified_time = get_db_modified_time(conn);
+
+        if !self.loaded || self.modified_time != modified_time {
+            self.periods = get_tx_periods(conn);
+            self.modified_time = modified_time;
+            self.loaded = true;
+        }
+    }
+
+    /// Returns whether the month of the year has any transactions. The month starts from 0
+    pub fn has_month(&self, year: &str, month_index: usize) -> bool {
+        self.periods
+            .contains(&format!("{year}-{:02}", month_index + 1))
+    }
+
+    /// Returns whether any month of the year has any transactions
+    pub fn has_year(&self, year: &str) -> bool {
+        self.periods
+            .iter()
+            .any(|period| period.starts_with(&format!("{year}-")))
+    }
+
+    /// Marks the months of the selected year and the years that have no transactions
+    pub fn mark_tabs(&self, months: &mut IndexedData, years: &mut IndexedData) {
+        let year = years.titles[years.index].clone();
+
+        let empty_months = (0..months.titles.len())
+            .map(|index| !self.has_month(&year, index))
+            .collect();
+        let empty_years = years
+            .titles
+            .iter()
+            .map(|year| !self.has_year(year))
+            .collect();
+
+        months.set_empty(empty_months);
+        years.set_empty(empty_years);
+    }
+}
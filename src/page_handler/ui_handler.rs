@@ -1,28 +1,41 @@
 use crate::add_tx_page::add_tx_ui;
 use crate::chart_page::{chart_ui, ChartData};
+use crate::db::{
+    get_bool_setting, get_date_range, get_list_setting, get_setting, get_usize_setting,
+    get_week_start,
+};
 use crate::home_page::home_ui;
-use crate::home_page::TransactionData;
+use crate::home_page::{MonthBadges, TransactionData};
 use crate::initial_page::initial_ui;
 use crate::key_checker::{
     add_tx_keys, chart_keys, home_keys, initial_keys, search_keys, summary_keys, InputKeyHandler,
 };
 use crate::outputs::{HandlingOutput, UiHandlingError};
 use crate::page_handler::{
-    ChartTab, CurrentUi, DeletionStatus, HomeTab, IndexedData, PopupState, SortingType, SummaryTab,
-    TableData, TxTab,
+    BackgroundTask, ChartTab, ChartView, CurrentUi, DeletionStatus, DisplaySettings, GaugeSettings,
+    HomeFilter, HomeHighlight, HomeTab, IndexedData, MonthJump, NameCache, NavigationStack,
+    PopupState, SearchGrouping, SortOrder, SortingType, StatusMessage, SummaryTab, TableData,
+    Toasts, TxPeriods, TxTab, TypeAhead, READ_ONLY_MESSAGE,
 };
-use crate::popup_page::PopupData;
+use crate::popup_page::{create_read_only_indicator, create_task_popup, create_toasts, PopupData};
 use crate::search_page::search_ui;
-use crate::summary_page::{summary_ui, SummaryData};
-use crate::tx_handler::TxData;
-use crate::utility::{get_all_tx_methods, get_empty_changes};
+use crate::summary_page::{summary_ui, SummaryData, SummaryRangeData};
+use crate::tx_handler::{ReminderData, TxData};
+use crate::utility::{
+    align_week, get_db_modified_time, get_empty_changes, get_tag_groups,
+    remove_hidden_method_columns, roll_over_month, sort_table_data_by, suspend_tui_interface,
+    Clock, InstanceLock, SystemClock, WeekRange,
+};
 use crossterm::event::poll;
-use crossterm::event::{self, Event};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
 use ratatui::backend::Backend;
 use ratatui::layout::Constraint;
 use ratatui::style::Color;
 use ratatui::Terminal;
 use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 pub const BACKGROUND: Color = Color::Rgb(245, 245, 255);
@@ -34,44 +47,88 @@ pub const HEADER: Color = Color::Rgb(0, 150, 255);
 pub const RED: Color = Color::Rgb(255, 51, 51);
 pub const BLUE: Color = Color::Rgb(51, 51, 255);
 pub const GRAY: Color = Color::Rgb(128, 128, 128);
+pub const GREEN: Color = Color::Rgb(34, 177, 76);
+pub const YELLOW: Color = Color::Rgb(230, 170, 0);
 
 /// Starts the interface and run the app
 #[cfg(not(tarpaulin_include))]
 pub fn start_app<B: Backend>(
     terminal: &mut Terminal<B>,
     new_version_data: &Option<Vec<String>>,
+    read_only: &mut bool,
+    background_task: &mut Option<BackgroundTask>,
+    instance_lock: &mut Option<InstanceLock>,
+    suspend_flags: &(Arc<AtomicBool>, Arc<AtomicBool>),
     conn: &mut Connection,
 ) -> Result<HandlingOutput, UiHandlingError> {
     // Setting up some default values. Let's go through all of them
 
+    // the years transactions can be added to. Gets reloaded every time the app is started
+    let date_range = get_date_range(conn);
+    // the settings the pages are drawn with. Gets reloaded every time the app is started
+    let mut display_settings = DisplaySettings::new(conn);
+    let date_format = display_settings.get_date_format();
+
     // contains the home page month list that is indexed
     let mut home_months = IndexedData::new_monthly();
     // contains the home page year list that is indexed
-    let mut home_years = IndexedData::new_yearly();
+    let mut home_years = IndexedData::new_yearly(&date_range);
     // contains the chart page month list that is indexed
     let mut chart_months = IndexedData::new_monthly();
     // contains the chart page year list that is indexed
-    let mut chart_years = IndexedData::new_yearly();
+    let mut chart_years = IndexedData::new_yearly(&date_range);
     // contains the chart page mode selection list that is indexed
     let mut chart_modes = IndexedData::new_modes();
+    // contains the chart page tag list that is indexed
+    let mut chart_tags = IndexedData::new_chart_tags(conn);
+    // the year the selected chart year is compared with. Starts at the year before it
+    let mut chart_compare_years = IndexedData::new_yearly(&date_range);
+    chart_compare_years.index = chart_years.index.saturating_sub(1);
+    // the start and end date boxes of the chart page Range mode
+    let mut chart_range = SummaryRangeData::new(conn);
     // contains the summary page month list that is indexed
     let mut summary_months = IndexedData::new_monthly();
     // contains the summary page year list that is indexed
-    let mut summary_years = IndexedData::new_yearly();
+    let mut summary_years = IndexedData::new_yearly(&date_range);
     // contains the summary page mode selection list that is indexed
-    let mut summary_modes = IndexedData::new_modes();
+    let mut summary_modes = IndexedData::new_summary_modes();
+
+    // the summary page selections of the last session are kept
+    summary_months.restore_index("summary_month", conn);
+    summary_years.restore_index("summary_year", conn);
+    summary_modes.restore_index("summary_mode", conn);
+    // contains the summary page tx method filter list that is indexed
+    let mut summary_methods = IndexedData::new_summary_methods(conn);
+
+    // today's date is checked on every loop to catch the day changing while the app is running
+    let clock = SystemClock;
+    let mut current_day = clock.today();
 
     // the selected widget on the Home Page. Default set to the month selection
     let mut home_tab = HomeTab::Months;
 
     // How summary table will be sorted
-    let mut summary_sort = SortingType::ByTags;
+    let mut summary_sort = get_usize_setting("summary_sort", conn)
+        .and_then(SortingType::from_column)
+        .unwrap_or(SortingType::ByTags);
+    // Which direction the summary table is sorted in
+    let mut summary_sort_order = match get_setting("summary_sort_descending", conn).as_str() {
+        "true" => SortOrder::Descending,
+        "false" => SortOrder::Ascending,
+        _ => summary_sort.default_order(),
+    };
 
     conn.execute("PRAGMA foreign_keys = ON", [])
         .expect("Could not enable foreign keys");
 
+    // Which transactions the Home table shows
+    let mut home_filter = HomeFilter::All;
+    // the week the Home table shows. None shows the whole selected month
+    let mut home_week: Option<WeekRange> = None;
+
     // Stores all data relevant for home page such as balance, changes and txs
-    let mut all_tx_data = TransactionData::new(home_months.index, home_years.index, conn);
+    let mut all_tx_data =
+        TransactionData::new(home_months.index, home_years.index, &home_filter, conn);
 
     let mut search_txs = TransactionData::new_search(Vec::new(), Vec::new());
     // data for the Home Page's tx table
@@ -79,6 +136,9 @@ pub fn start_app<B: Backend>(
 
     // The page which is currently selected. Default is the initial page
     let mut page = CurrentUi::Initial;
+
+    // The pages left through cross-page jumps that Esc goes back to
+    let mut navigation = NavigationStack::new();
     // stores current popup status
     let mut popup_state = if let Some(data) = new_version_data {
         PopupState::NewUpdate(data.to_owned())
@@ -97,24 +157,58 @@ pub fn start_app<B: Backend>(
 
     // Holds the data that will be/are inserted into the Add Tx page's input fields
     let mut add_tx_data = TxData::new();
+    add_tx_data.localize_date(&date_format);
     // Holds the data that will be/are inserted into the Summary Page
     let mut summary_data = SummaryData::new(conn);
+    // Holds the start and end date of the Summary Page's Range mode
+    let mut summary_range = SummaryRangeData::new(conn);
     // Holds the data that will be/are inserted into the Search page's input fields
     let mut search_data = TxData::new();
+    search_data.localize_date(&date_format);
     // Holds the data that will be/are inserted into the Chart Page
     let mut chart_data = ChartData::new(conn);
     // Holds the popup data that will be/are inserted into the Popup page
     let mut popup_data = PopupData::new();
+    // Holds the transaction counts shown on the Home page month tab
+    let mut month_badges = MonthBadges::new();
+    // Holds the months with transactions for the month and year tabs of every page
+    let mut tx_periods = TxPeriods::new();
+
+    // whether the summary tag table shows tag groups instead of individual tags
+    let mut summary_grouped = false;
+
+    // whether the summary tables of the Monthly mode are compared with the previous month
+    let mut summary_compare = false;
+
+    // the typed prefix that jumps to a tag on the Summary Page's table
+    let mut summary_type_ahead = TypeAhead::new();
+
+    // the numbers typed on a Months tab to jump to a month
+    let mut month_jump = MonthJump::new();
+
+    // the transaction opened on the Home Page's table from another page
+    let mut home_highlight = HomeHighlight::new();
 
     // data for the Summary Page's table
-    let mut summary_table = TableData::new(summary_data.get_table_data(
-        &summary_modes,
-        summary_months.index,
-        summary_years.index,
+    let mut summary_table = TableData::new(sort_table_data_by(
+        summary_data.get_page_table_data(
+            &summary_modes,
+            summary_months.index,
+            summary_years.index,
+            summary_range.get_active_range(&summary_modes, conn),
+            summary_methods.get_summary_method(),
+            &HashMap::new(),
+            summary_compare,
+        ),
+        &summary_sort,
+        summary_sort_order,
     ));
 
     let mut search_table = TableData::new(Vec::new());
 
+    // whether the search result table is split into monthly or yearly groups with subtotals
+    let mut search_grouping = SearchGrouping::None;
+
     // the initial page REX loading index
     let mut starter_index = 0;
 
@@ -122,10 +216,44 @@ pub fn start_app<B: Backend>(
 
     let mut chart_hidden_mode = false;
 
-    let mut summary_hidden_mode = false;
+    // what the chart page plots
+    let mut chart_view = ChartView::Balance;
+    // the tx methods whose balance line is not drawn on the chart
+    let mut chart_hidden_methods = get_list_setting("chart_hidden_methods", conn)
+        .into_iter()
+        .collect::<HashSet<String>>();
+    // the gauge settings only change through the gauge toggle or the settings menu,
+    // which starts the pages again
+    let mut gauge_settings = GaugeSettings::new(conn);
+    // whether the chart draws the sum of the visible balances
+    let mut chart_show_total = get_bool_setting("chart_show_total", conn);
+    // whether the stacked monthly expense bars are split by tag groups instead of tx methods
+    let mut chart_group_stack = false;
+    // whether the income and expense bars show the weeks of the selected month instead of the months
+    let mut chart_weekly = false;
+
+    let mut summary_hidden_mode = get_bool_setting("summary_hidden_mode", conn);
 
     let mut deletion_status: DeletionStatus = DeletionStatus::Yes;
 
+    // the short messages shown in the corner. Operations outside of the key handler
+    // can push to it through a clone of the handle
+    let toasts = Toasts::new();
+
+    // the tx methods and tags read while drawing and on every key press. Dropped whenever
+    // something may have changed them
+    let name_cache = NameCache::new();
+
+    if *read_only {
+        toasts.push(StatusMessage::warn(READ_ONLY_MESSAGE));
+    }
+
+    // set when the app gets suspended or resumed from outside with SIGTSTP/SIGCONT.
+    // Signals received while outside of the TUI are not needed anymore
+    let (suspend_flag, resume_flag) = suspend_flags;
+    suspend_flag.store(false, Ordering::Relaxed);
+    resume_flag.store(false, Ordering::Relaxed);
+
     // how it work:
     // Default value from above -> Goes to an interface page and render -> Wait for an event key press.
     //
@@ -140,17 +268,9 @@ pub fn start_app<B: Backend>(
         // balance variable contains all the 'rows' of the Balance widget in the home page.
         // So each line is inside a vector. "" represents empty placeholder.
         let mut balance: Vec<Vec<String>> = vec![vec!["".to_string()]];
-        balance[0].extend(get_all_tx_methods(conn));
+        balance[0].extend(name_cache.get_tx_methods(conn));
         balance[0].extend(vec!["Total".to_string()]);
 
-        // save the % of space each column should take in the Balance section based on the total
-        // transaction methods/columns available
-        let width_percent = 100 / balance[0].len() as u16;
-        let mut width_data = vec![];
-        for _i in 0..balance[0].len() {
-            width_data.push(Constraint::Percentage(width_percent));
-        }
-
         // current_table_index is the Home Page table widget index. If a row is selected,
         // get the balance there was once that transaction happened + the changes it did
         // otherwise, get the absolute final balance after all transaction happened + no changes.
@@ -168,10 +288,41 @@ pub fn start_app<B: Backend>(
             }
         }
 
+        if let CurrentUi::Home = page {
+            month_badges.update(home_months.index, home_years.index, conn);
+            home_months.set_suffixes(month_badges.get_suffixes());
+        }
+
+        // the month and year tabs grey out the periods without transactions
+        tx_periods.update(conn);
+        match page {
+            CurrentUi::Home => tx_periods.mark_tabs(&mut home_months, &mut home_years),
+            CurrentUi::Chart => tx_periods.mark_tabs(&mut chart_months, &mut chart_years),
+            CurrentUi::Summary => tx_periods.mark_tabs(&mut summary_months, &mut summary_years),
+            _ => {}
+        }
+
         // total_income & total_expense data changes on each month/year index change.
         balance.push(all_tx_data.get_total_income(current_table_index, conn));
         balance.push(all_tx_data.get_total_expense(current_table_index, conn));
 
+        if !display_settings.is_showing_hidden_methods() {
+            remove_hidden_method_columns(&mut balance, conn);
+        }
+
+        // save the % of space each column should take in the Balance section based on the total
+        // transaction methods/columns available
+        let width_percent = 100 / balance[0].len() as u16;
+        let mut width_data = vec![];
+        for _i in 0..balance[0].len() {
+            width_data.push(Constraint::Percentage(width_percent));
+        }
+
+        // the selected transaction stands out for a moment if it was opened from another page
+        let home_highlighted = table.state.selected().map_or(false, |index| {
+            home_highlight.is_highlighted(all_tx_data.get_id_num(index))
+        });
+
         // passing out relevant data to the ui function
         terminal
             .draw(|f| {
@@ -184,10 +335,21 @@ pub fn start_app<B: Backend>(
                         &mut balance,
                         &home_tab,
                         &mut width_data,
+                        home_week
+                            .as_ref()
+                            .map(|week| all_tx_data.get_weekly_net(week, &clock)),
+                        all_tx_data.get_month_totals(),
+                        &all_tx_data,
+                        &home_filter,
+                        home_week.as_ref(),
+                        &gauge_settings,
+                        &display_settings,
+                        home_highlighted,
+                        &name_cache,
                         conn,
                     ),
 
-                    CurrentUi::AddTx => add_tx_ui(f, &add_tx_data, &add_tx_tab),
+                    CurrentUi::AddTx => add_tx_ui(f, &add_tx_data, &add_tx_tab, conn),
 
                     CurrentUi::Initial => initial_ui(f, starter_index),
 
@@ -196,10 +358,20 @@ pub fn start_app<B: Backend>(
                         &chart_months,
                         &chart_years,
                         &chart_modes,
+                        &chart_tags,
+                        &chart_compare_years,
+                        &chart_range,
                         &chart_data,
                         &chart_tab,
                         chart_hidden_mode,
+                        chart_view,
+                        &chart_hidden_methods,
+                        chart_show_total,
+                        chart_group_stack,
+                        chart_weekly,
                         &mut chart_index,
+                        display_settings.get_amount_format(),
+                        &name_cache,
                         conn,
                     ),
 
@@ -208,29 +380,76 @@ pub fn start_app<B: Backend>(
                         &summary_months,
                         &summary_years,
                         &summary_modes,
+                        &summary_methods,
                         &summary_data,
+                        &summary_range,
                         &mut summary_table,
                         &summary_tab,
                         summary_hidden_mode,
+                        summary_grouped,
+                        summary_compare,
+                        &summary_type_ahead,
                         &summary_sort,
+                        summary_sort_order,
+                        &display_settings,
+                        &name_cache,
                         conn,
                     ),
-                    CurrentUi::Search => search_ui(f, &search_data, &search_tab, &mut search_table),
+                    CurrentUi::Search => search_ui(
+                        f,
+                        &search_data,
+                        &search_tab,
+                        &mut search_table,
+                        search_grouping,
+                        &date_format,
+                        display_settings.get_amount_format(),
+                    ),
+                }
+                popup_data.create_popup(
+                    f,
+                    &popup_state,
+                    &deletion_status,
+                    display_settings.get_amount_format(),
+                );
+                if let Some(task) = background_task.as_ref() {
+                    create_task_popup(f, task);
                 }
-                popup_data.create_popup(f, &popup_state, &deletion_status)
+                if *read_only {
+                    create_read_only_indicator(f);
+                }
+                create_toasts(f, &toasts.get_messages());
             })
             .map_err(UiHandlingError::DrawingError)?;
 
+        // once the task is done its report is shown and the data is reloaded the same way it is on resume
+        let mut task_finished = false;
+
+        if let Some(result) = background_task.as_mut().and_then(|task| task.poll()) {
+            if let Some(task) = background_task.take() {
+                match result {
+                    Ok(report) => popup_state = PopupState::TaskReport(report),
+                    Err(e) => toasts.push(StatusMessage::error(format!(
+                        "{} failed. Error: {e}",
+                        task.get_name()
+                    ))),
+                }
+            }
+            task_finished = true;
+        }
+
         // poll for key press on two page for a duration. If not found, start next loop
         match page {
             CurrentUi::Initial => {
-                if !poll(Duration::from_millis(40)).map_err(UiHandlingError::PollingError)? {
+                if !task_finished
+                    && !poll(Duration::from_millis(40)).map_err(UiHandlingError::PollingError)?
+                {
                     starter_index = (starter_index + 1) % 28;
                     continue;
                 }
             }
             CurrentUi::Chart => {
                 if chart_index.is_some()
+                    && !task_finished
                     && !poll(Duration::from_millis(2)).map_err(UiHandlingError::PollingError)?
                 {
                     continue;
@@ -240,8 +459,226 @@ pub fn start_app<B: Backend>(
             _ => {}
         }
 
-        // if not inside one of the duration polling, wait for keypress
-        if let Event::Key(key) = event::read().map_err(UiHandlingError::PollingError)? {
+        // if not inside one of the duration polling, wait for keypress.
+        // While waiting, check whether the app was suspended or resumed from outside
+        let mut suspend_requested = false;
+        let mut resumed = false;
+        let mut day_changed = clock.today() != current_day;
+        let mut dashboard_expired = false;
+        let mut type_ahead_expired = false;
+        let mut highlight_expired = false;
+        let mut toast_expired = false;
+        let mut task_ticked = false;
+
+        while !day_changed
+            && !task_finished
+            && !poll(Duration::from_millis(200)).map_err(UiHandlingError::PollingError)?
+        {
+            if let PopupState::Dashboard(dashboard_data) = &popup_state {
+                if dashboard_data.is_expired() {
+                    dashboard_expired = true;
+                    break;
+                }
+            }
+
+            // the type-ahead prefix of the Summary table goes away once nothing is typed for a while
+            if summary_type_ahead.is_expired() {
+                type_ahead_expired = true;
+                break;
+            }
+
+            if home_highlight.is_expired() {
+                highlight_expired = true;
+                break;
+            }
+
+            if toasts.is_expired() {
+                toast_expired = true;
+                break;
+            }
+
+            // the progress popup is redrawn on every tick while a background task runs
+            if background_task.is_some() {
+                task_ticked = true;
+                break;
+            }
+
+            if suspend_flag.swap(false, Ordering::Relaxed) {
+                suspend_requested = true;
+                break;
+            } else if resume_flag.swap(false, Ordering::Relaxed) {
+                resumed = true;
+                break;
+            } else if clock.today() != current_day {
+                day_changed = true;
+            }
+        }
+
+        // the dashboard shown after the initial page closes on its own and the reminders follow it
+        if dashboard_expired {
+            let reminder_data = ReminderData::new_with_clock(&clock, conn);
+            popup_state = if reminder_data.is_empty() {
+                PopupState::Nothing
+            } else {
+                PopupState::Reminders(reminder_data)
+            };
+            continue;
+        }
+
+        if type_ahead_expired {
+            summary_type_ahead.clear();
+            continue;
+        }
+
+        if highlight_expired {
+            home_highlight.clear();
+            continue;
+        }
+
+        if toast_expired {
+            toasts.remove_expired();
+            continue;
+        }
+
+        if task_ticked {
+            continue;
+        }
+
+        if day_changed {
+            let previous_day = current_day;
+            current_day = clock.today();
+
+            // only move the month and year selections that were following the current month
+            roll_over_month(&mut home_months, &mut home_years, previous_day, &clock);
+            roll_over_month(&mut chart_months, &mut chart_years, previous_day, &clock);
+            roll_over_month(
+                &mut summary_months,
+                &mut summary_years,
+                previous_day,
+                &clock,
+            );
+
+            add_tx_data.roll_date(previous_day, &clock, &date_format);
+
+            if let Some(week) = home_week.as_mut() {
+                align_week(week, &home_months, &home_years, get_week_start(conn));
+            }
+
+            all_tx_data = TransactionData::new_period(
+                home_months.index,
+                home_years.index,
+                &home_week,
+                &home_filter,
+                conn,
+            );
+            table = TableData::new(all_tx_data.get_txs());
+            chart_data = ChartData::new(conn);
+            summary_data = SummaryData::new(conn);
+            let tag_groups = if summary_grouped {
+                get_tag_groups(conn)
+            } else {
+                HashMap::new()
+            };
+            summary_table = TableData::new(sort_table_data_by(
+                summary_data.get_page_table_data(
+                    &summary_modes,
+                    summary_months.index,
+                    summary_years.index,
+                    summary_range.get_active_range(&summary_modes, conn),
+                    summary_methods.get_summary_method(),
+                    &tag_groups,
+                    summary_compare,
+                ),
+                &summary_sort,
+                summary_sort_order,
+            ));
+
+            // reminders of the new day are shown the same way they are after the initial page
+            if let (CurrentUi::Home, PopupState::Nothing) = (&page, &popup_state) {
+                let reminder_data = ReminderData::new_with_clock(&clock, conn);
+                if !reminder_data.is_empty() {
+                    popup_state = PopupState::Reminders(reminder_data);
+                }
+            }
+            continue;
+        }
+
+        let event = if suspend_requested || resumed || task_finished {
+            None
+        } else {
+            Some(event::read().map_err(UiHandlingError::PollingError)?)
+        };
+
+        // a paste arrives as a single event with the whole text. It goes through the handler
+        // with an empty key so the focused input gets the text at once
+        let (key, pasted) = match event {
+            Some(Event::Key(key)) => (Some(key), None),
+            Some(Event::Paste(text)) => (
+                Some(KeyEvent::new(KeyCode::Null, KeyModifiers::NONE)),
+                Some(text),
+            ),
+            _ => (None, None),
+        };
+
+        // Ctrl+Z does not send SIGTSTP while in raw mode so it is handled as a key press
+        if let Some(key) = key {
+            if cfg!(unix)
+                && key.code == KeyCode::Char('z')
+                && key.modifiers.contains(KeyModifiers::CONTROL)
+            {
+                suspend_requested = true;
+            }
+        }
+
+        if suspend_requested || resumed || task_finished {
+            let modified_time = get_db_modified_time(conn);
+
+            if suspend_requested {
+                suspend_tui_interface(terminal).map_err(UiHandlingError::SuspendingError)?;
+                resume_flag.store(false, Ordering::Relaxed);
+            } else if resumed {
+                terminal.clear().map_err(UiHandlingError::DrawingError)?;
+            }
+
+            // the db may have been changed by something else while the app was stopped
+            if resumed || task_finished || get_db_modified_time(conn) != modified_time {
+                name_cache.invalidate();
+                all_tx_data = TransactionData::new_period(
+                    home_months.index,
+                    home_years.index,
+                    &home_week,
+                    &home_filter,
+                    conn,
+                );
+                table = TableData::new(all_tx_data.get_txs());
+                chart_data = ChartData::new(conn);
+                summary_data = SummaryData::new(conn);
+                // the tx methods may have changed as well
+                summary_methods = IndexedData::new_summary_methods(conn);
+                chart_tags = IndexedData::new_chart_tags(conn);
+                let tag_groups = if summary_grouped {
+                    get_tag_groups(conn)
+                } else {
+                    HashMap::new()
+                };
+                summary_table = TableData::new(sort_table_data_by(
+                    summary_data.get_page_table_data(
+                        &summary_modes,
+                        summary_months.index,
+                        summary_years.index,
+                        summary_range.get_active_range(&summary_modes, conn),
+                        summary_methods.get_summary_method(),
+                        &tag_groups,
+                        summary_compare,
+                    ),
+                    &summary_sort,
+                    summary_sort_order,
+                ));
+            }
+            continue;
+        }
+
+        if let Some(key) = key {
             let mut handler = InputKeyHandler::new(
                 key,
                 &mut page,
@@ -254,6 +691,7 @@ pub fn start_app<B: Backend>(
                 &mut all_tx_data,
                 &mut chart_data,
                 &mut summary_data,
+                &mut summary_range,
                 &mut table,
                 &mut summary_table,
                 &mut home_months,
@@ -261,28 +699,74 @@ pub fn start_app<B: Backend>(
                 &mut chart_months,
                 &mut chart_years,
                 &mut chart_modes,
+                &mut chart_tags,
+                &mut chart_compare_years,
+                &mut chart_range,
                 &mut summary_months,
                 &mut summary_years,
                 &mut summary_modes,
+                &mut summary_methods,
                 &mut summary_sort,
+                &mut summary_sort_order,
                 &mut search_data,
                 &mut search_tab,
                 &mut search_table,
                 &mut search_txs,
+                &mut search_grouping,
                 &mut chart_index,
                 &mut chart_hidden_mode,
+                &mut chart_view,
+                &mut chart_hidden_methods,
+                &mut chart_show_total,
+                &mut chart_group_stack,
+                &mut chart_weekly,
                 &mut summary_hidden_mode,
+                &mut summary_grouped,
+                &mut summary_compare,
+                &mut summary_type_ahead,
+                &mut month_jump,
+                &mut home_highlight,
+                &mut home_filter,
+                &mut home_week,
+                &mut gauge_settings,
+                &mut display_settings,
+                &mut navigation,
                 &mut deletion_status,
+                background_task,
+                &toasts,
+                &name_cache,
+                read_only,
+                instance_lock,
                 conn,
             );
 
-            let status = match handler.page {
-                CurrentUi::Initial => initial_keys(&mut handler),
-                CurrentUi::Home => home_keys(&mut handler),
-                CurrentUi::AddTx => add_tx_keys(&mut handler),
-                CurrentUi::Chart => chart_keys(&mut handler),
-                CurrentUi::Summary => summary_keys(&mut handler),
-                CurrentUi::Search => search_keys(&mut handler),
+            // the quit warning and Ctrl+C are handled the same way on every page
+            let status = if let Some(text) = pasted {
+                // nothing takes the text while a background task runs
+                if !handler.is_task_running() {
+                    handler.handle_paste(&text);
+                }
+                None
+            } else if let PopupState::QuitWarning(..) = handler.popup {
+                handler.handle_quit_popup()
+            } else if handler.is_ctrl_c() {
+                handler.quit_app()
+            } else if handler.is_ctrl_x() {
+                // the toasts never take any other key so the page under them keeps working
+                handler.dismiss_toast();
+                None
+            } else if handler.is_task_running() {
+                handler.handle_task_keys();
+                None
+            } else {
+                match handler.page {
+                    CurrentUi::Initial => initial_keys(&mut handler),
+                    CurrentUi::Home => home_keys(&mut handler),
+                    CurrentUi::AddTx => add_tx_keys(&mut handler),
+                    CurrentUi::Chart => chart_keys(&mut handler),
+                    CurrentUi::Summary => summary_keys(&mut handler),
+                    CurrentUi::Search => search_keys(&mut handler),
+                }
             };
 
             if let Some(output) = status {
@@ -1,7 +1,24 @@
-use crate::db::{MODES, MONTHS, YEARS};
-use chrono::prelude::Local;
+use crate::api::NewTx;
+use crate::db::{
+    get_bool_setting, get_f64_setting, get_usize_setting, set_setting, set_usize_setting,
+    DateRange, MODES, MONTHS, SUMMARY_MODES,
+};
+use crate::home_page::DashboardData;
+use crate::outputs::CheckingErrors;
+use crate::summary_page::ExcludedTagsData;
+use crate::tx_handler::{
+    BalanceDateData, CalendarData, CsvProblem, DetailsTemplateData, ImportProfile, JournalProblem,
+    MethodPickerData, ReminderData, RepeatTxData, TagEditData, TxDeletionData, TxDetailsData,
+    TxPickerData,
+};
+use crate::utility::{
+    get_all_tags, get_all_tx_methods, get_amount_format, get_date_format, AmountFormat, Clock,
+    DateFormat, SystemClock,
+};
 use chrono::Datelike;
 use ratatui::widgets::TableState;
+use rusqlite::Connection;
+use std::time::{Duration, Instant};
 
 /// The struct stores all transaction data for the Transaction widget
 /// and creates an index to keep track of which transactions row is selected
@@ -64,22 +81,41 @@ impl TableData {
 pub struct IndexedData {
     pub titles: Vec<String>,
     pub index: usize,
+    /// Text shown after each title on the tab. Empty if the titles have no suffix
+    pub suffixes: Vec<String>,
+    /// Whether each title is a month or year without any transactions.
+    /// Empty if the titles are not periods
+    pub empty: Vec<bool>,
 }
 
 impl IndexedData {
     pub fn new_monthly() -> Self {
-        let month_index = Local::now().month() as usize - 1;
+        Self::new_monthly_with_clock(&SystemClock)
+    }
+
+    /// Creates the month list with the month of the clock's date selected
+    pub fn new_monthly_with_clock(clock: &impl Clock) -> Self {
+        let month_index = clock.today().month() as usize - 1;
         IndexedData {
             titles: MONTHS.into_iter().map(|s| s.to_string()).collect(),
             index: month_index,
+            suffixes: Vec::new(),
+            empty: Vec::new(),
         }
     }
 
-    pub fn new_yearly() -> Self {
-        let year_index = Local::now().year() as usize - 2022;
+    pub fn new_yearly(date_range: &DateRange) -> Self {
+        Self::new_yearly_with_clock(&SystemClock, date_range)
+    }
+
+    /// Creates the year list of the date range with the year of the clock's date selected.
+    /// The closest year is selected if the clock's year is outside the range
+    pub fn new_yearly_with_clock(clock: &impl Clock, date_range: &DateRange) -> Self {
         IndexedData {
-            titles: YEARS.into_iter().map(|s| s.to_string()).collect(),
-            index: year_index,
+            titles: date_range.years(),
+            index: date_range.year_index(clock.today().year()),
+            suffixes: Vec::new(),
+            empty: Vec::new(),
         }
     }
 
@@ -87,6 +123,61 @@ impl IndexedData {
         IndexedData {
             titles: MODES.into_iter().map(|s| s.to_string()).collect(),
             index: 0,
+            suffixes: Vec::new(),
+            empty: Vec::new(),
+        }
+    }
+
+    pub fn new_summary_modes() -> Self {
+        IndexedData {
+            titles: SUMMARY_MODES.into_iter().map(|s| s.to_string()).collect(),
+            index: 0,
+            suffixes: Vec::new(),
+            empty: Vec::new(),
+        }
+    }
+
+    /// Creates the tx method filter of the Summary page. The 1st value selects every tx method
+    pub fn new_summary_methods(conn: &Connection) -> Self {
+        let mut titles = vec![String::from("All")];
+        titles.extend(get_all_tx_methods(conn));
+
+        IndexedData {
+            titles,
+            index: 0,
+            suffixes: Vec::new(),
+            empty: Vec::new(),
+        }
+    }
+
+    /// Creates the tag list of the Chart page with an All value in front
+    pub fn new_chart_tags(conn: &Connection) -> Self {
+        let mut titles = vec![String::from("All")];
+        titles.extend(get_all_tags(conn));
+
+        IndexedData {
+            titles,
+            index: 0,
+            suffixes: Vec::new(),
+            empty: Vec::new(),
+        }
+    }
+
+    /// Returns the selected tag of the Chart tag list or None if All is selected
+    pub fn get_chart_tag(&self) -> Option<&str> {
+        if self.index == 0 {
+            None
+        } else {
+            Some(&self.titles[self.index])
+        }
+    }
+
+    /// Returns the selected tx method of the Summary method filter or None if every method is selected
+    pub fn get_summary_method(&self) -> Option<&str> {
+        if self.index == 0 {
+            None
+        } else {
+            Some(&self.titles[self.index])
         }
     }
 
@@ -104,9 +195,105 @@ impl IndexedData {
         }
     }
 
+    /// Returns whether the title at the index is a period without any transactions
+    pub fn is_empty_at(&self, index: usize) -> bool {
+        self.empty.get(index).copied().unwrap_or(false)
+    }
+
+    /// Goes to the next index. Titles without any transactions are skipped if skip_empty is true.
+    /// Works the same as next if every title is empty
+    pub fn next_period(&mut self, skip_empty: bool) {
+        let start = self.index;
+        self.next();
+
+        if skip_empty {
+            while self.is_empty_at(self.index) && self.index != start {
+                self.next();
+            }
+
+            // every title is empty
+            if self.index == start && self.is_empty_at(start) {
+                self.next();
+            }
+        }
+    }
+
+    /// Goes to the previous index. Titles without any transactions are skipped if skip_empty is true.
+    /// Works the same as previous if every title is empty
+    pub fn previous_period(&mut self, skip_empty: bool) {
+        let start = self.index;
+        self.previous();
+
+        if skip_empty {
+            while self.is_empty_at(self.index) && self.index != start {
+                self.previous();
+            }
+
+            // every title is empty
+            if self.index == start && self.is_empty_at(start) {
+                self.previous();
+            }
+        }
+    }
+
+    /// Selects the index saved under the setting key. The selection does not change if nothing
+    /// was saved or the saved index is not on the list
+    pub fn restore_index(&mut self, key: &str, conn: &Connection) {
+        if let Some(index) = get_usize_setting(key, conn) {
+            if index < self.titles.len() {
+                self.index = index;
+            }
+        }
+    }
+
+    /// Saves the selected index under the setting key so it can be restored on the next start
+    pub fn save_index(&self, key: &str, conn: &Connection) {
+        set_usize_setting(key, self.index, conn).ok();
+    }
+
     pub fn set_index_zero(&mut self) {
         self.index = 0;
     }
+
+    pub fn set_suffixes(&mut self, suffixes: Vec<String>) {
+        self.suffixes = suffixes;
+    }
+
+    pub fn set_empty(&mut self, empty: Vec<bool>) {
+        self.empty = empty;
+    }
+
+    /// Returns the titles with their suffixes if all of them fit inside a tab of the given width.
+    /// Otherwise the titles are returned without the suffixes
+    pub fn get_titles(&self, width: u16) -> Vec<String> {
+        if self.suffixes.is_empty() {
+            return self.titles.clone();
+        }
+
+        let titles = self
+            .titles
+            .iter()
+            .enumerate()
+            .map(|(index, title)| match self.suffixes.get(index) {
+                Some(suffix) => format!("{title}{suffix}"),
+                None => title.to_string(),
+            })
+            .collect::<Vec<String>>();
+
+        // each title is padded with a space on both sides and followed by a divider.
+        // The final divider is replaced by the 2 borders of the tab
+        let required_width = titles
+            .iter()
+            .map(|title| title.chars().count() + 3)
+            .sum::<usize>()
+            + 1;
+
+        if required_width <= width as usize {
+            titles
+        } else {
+            self.titles.clone()
+        }
+    }
 }
 
 /// The enum is used to keep track of which tab is currently set at active
@@ -146,6 +333,7 @@ impl HomeTab {
 /// This enum is used inside the Add Transaction page.
 /// This is targeted to be used to keep track which widget of the Add Transaction
 /// is currently being interacted with.
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum TxTab {
     Date,
     Details,
@@ -159,6 +347,7 @@ pub enum TxTab {
 
 /// Shows the currently active page in the terminal. Used to properly
 /// direct key presses to the relevant structs and widget selection.
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum CurrentUi {
     Initial,
     Home,
@@ -168,6 +357,375 @@ pub enum CurrentUi {
     Search,
 }
 
+/// The state of a page saved before a cross-page jump so it can be restored on going back.
+/// The indexes are of the mode, month and year tabs of the page. Pages without them keep 0
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PageState {
+    pub page: CurrentUi,
+    pub mode_index: usize,
+    pub month_index: usize,
+    pub year_index: usize,
+    pub selected: Option<usize>,
+}
+
+impl PageState {
+    pub fn new(page: CurrentUi) -> Self {
+        PageState {
+            page,
+            mode_index: 0,
+            month_index: 0,
+            year_index: 0,
+            selected: None,
+        }
+    }
+}
+
+/// The pages that were left through cross-page jumps, the latest one at the end.
+/// Popups are never part of the stack
+pub struct NavigationStack {
+    pages: Vec<PageState>,
+}
+
+impl Default for NavigationStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NavigationStack {
+    pub fn new() -> Self {
+        NavigationStack { pages: Vec::new() }
+    }
+
+    /// Saves the state of the page that is being left
+    pub fn push(&mut self, state: PageState) {
+        self.pages.push(state);
+    }
+
+    /// Returns the state of the last page that was left. None if there is nowhere to go back
+    pub fn pop(&mut self) -> Option<PageState> {
+        self.pages.pop()
+    }
+
+    /// Removes all saved pages. Used when a page is opened directly instead of through a jump
+    pub fn clear(&mut self) {
+        self.pages.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+}
+
+/// The settings of the expense/income gauge of the Home page. Loaded once when the pages
+/// start so drawing the footer does not query them on every frame
+pub struct GaugeSettings {
+    hidden: bool,
+    warning_ratio: f64,
+    danger_ratio: f64,
+}
+
+impl GaugeSettings {
+    pub fn new(conn: &Connection) -> Self {
+        GaugeSettings {
+            hidden: get_bool_setting("gauge_hidden", conn),
+            warning_ratio: get_f64_setting("gauge_warning_ratio", conn),
+            danger_ratio: get_f64_setting("gauge_danger_ratio", conn),
+        }
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Returns the warning and the danger ratio of the gauge colors
+    pub fn get_ratios(&self) -> (f64, f64) {
+        (self.warning_ratio, self.danger_ratio)
+    }
+
+    /// Shows or hides the gauge and saves the choice
+    pub fn toggle_hidden(&mut self, conn: &Connection) {
+        self.hidden = !self.hidden;
+        set_setting("gauge_hidden", &self.hidden.to_string(), conn).ok();
+    }
+}
+
+/// The settings the pages are drawn with. Loaded once when the pages start so drawing does not
+/// query them on every frame. The ones that can be toggled from the pages are saved here too,
+/// the rest only change from the J menu which starts the pages again
+pub struct DisplaySettings {
+    date_format: DateFormat,
+    amount_format: AmountFormat,
+    day_groups: bool,
+    show_hidden_methods: bool,
+    summary_raw_view: bool,
+    summary_include_transfers: bool,
+}
+
+impl DisplaySettings {
+    pub fn new(conn: &Connection) -> Self {
+        DisplaySettings {
+            date_format: get_date_format(conn),
+            amount_format: get_amount_format(conn),
+            day_groups: get_bool_setting("home_day_groups", conn),
+            show_hidden_methods: get_bool_setting("show_hidden_methods", conn),
+            summary_raw_view: get_bool_setting("summary_raw_view", conn),
+            summary_include_transfers: get_bool_setting("summary_include_transfers", conn),
+        }
+    }
+
+    pub fn get_date_format(&self) -> DateFormat {
+        self.date_format
+    }
+
+    pub fn get_amount_format(&self) -> &AmountFormat {
+        &self.amount_format
+    }
+
+    /// Returns whether the Home table is grouped by day
+    pub fn is_day_grouped(&self) -> bool {
+        self.day_groups
+    }
+
+    /// Returns whether the Home balance table shows the hidden tx methods
+    pub fn is_showing_hidden_methods(&self) -> bool {
+        self.show_hidden_methods
+    }
+
+    /// Returns whether the Summary ignores the excluded tags
+    pub fn is_summary_raw_view(&self) -> bool {
+        self.summary_raw_view
+    }
+
+    /// Returns whether the Summary method table counts the transfers
+    pub fn is_summary_including_transfers(&self) -> bool {
+        self.summary_include_transfers
+    }
+
+    /// Groups the Home table by day or stops grouping it and saves the choice
+    pub fn toggle_day_groups(&mut self, conn: &Connection) {
+        self.day_groups = !self.day_groups;
+        set_setting("home_day_groups", &self.day_groups.to_string(), conn).ok();
+    }
+
+    /// Shows or hides the hidden tx methods on the Home balance table and saves the choice
+    pub fn toggle_hidden_methods(&mut self, conn: &Connection) {
+        self.show_hidden_methods = !self.show_hidden_methods;
+        set_setting(
+            "show_hidden_methods",
+            &self.show_hidden_methods.to_string(),
+            conn,
+        )
+        .ok();
+    }
+
+    /// Switches the Summary between the raw view and leaving out the excluded tags and saves the choice
+    pub fn toggle_summary_raw_view(&mut self, conn: &Connection) {
+        self.summary_raw_view = !self.summary_raw_view;
+        set_setting("summary_raw_view", &self.summary_raw_view.to_string(), conn).ok();
+    }
+
+    /// Switches the Summary method table between leaving out and counting the transfers
+    /// and saves the choice
+    pub fn toggle_summary_transfers(&mut self, conn: &Connection) {
+        self.summary_include_transfers = !self.summary_include_transfers;
+        set_setting(
+            "summary_include_transfers",
+            &self.summary_include_transfers.to_string(),
+            conn,
+        )
+        .ok();
+    }
+}
+
+/// How long a transaction opened on the Home table from another page stays highlighted
+pub const HOME_HIGHLIGHT_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// The transaction that was opened on the Home table from another page.
+/// It is shown with a different style for a short while so it can be spotted among the others
+#[derive(Default)]
+pub struct HomeHighlight {
+    highlighted: Option<(i32, Instant)>,
+}
+
+impl HomeHighlight {
+    pub fn new() -> Self {
+        HomeHighlight { highlighted: None }
+    }
+
+    /// Highlights the transaction from now on
+    pub fn start(&mut self, id_num: i32) {
+        self.start_at(id_num, Instant::now());
+    }
+
+    /// Highlights the transaction from the given time
+    pub fn start_at(&mut self, id_num: i32, now: Instant) {
+        self.highlighted = Some((id_num, now));
+    }
+
+    pub fn clear(&mut self) {
+        self.highlighted = None;
+    }
+
+    /// Returns whether the transaction is highlighted and the highlight has not expired
+    pub fn is_highlighted(&self, id_num: i32) -> bool {
+        self.is_highlighted_at(id_num, Instant::now())
+    }
+
+    /// Returns whether the transaction is highlighted and the highlight has not expired at the given time
+    pub fn is_highlighted_at(&self, id_num: i32, now: Instant) -> bool {
+        self.highlighted.map_or(false, |(highlighted_id, started)| {
+            highlighted_id == id_num && now < started + HOME_HIGHLIGHT_TIMEOUT
+        })
+    }
+
+    /// Returns whether a transaction was highlighted for longer than the timeout
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(Instant::now())
+    }
+
+    /// Returns whether a transaction was highlighted for longer than the timeout at the given time
+    pub fn is_expired_at(&self, now: Instant) -> bool {
+        self.highlighted.map_or(false, |(_, started)| {
+            now >= started + HOME_HIGHLIGHT_TIMEOUT
+        })
+    }
+}
+
+/// How long the type-ahead prefix of the Summary table is kept after the last typed key
+pub const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// The prefix typed on the Summary tag table to jump to the first tag that starts with it.
+/// The prefix is cleared once nothing is typed for a while
+pub struct TypeAhead {
+    prefix: String,
+    last_input: Option<Instant>,
+}
+
+impl Default for TypeAhead {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeAhead {
+    pub fn new() -> Self {
+        TypeAhead {
+            prefix: String::new(),
+            last_input: None,
+        }
+    }
+
+    pub fn get_prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prefix.is_empty()
+    }
+
+    /// Adds the character to the end of the prefix
+    pub fn push(&mut self, c: char) {
+        self.push_at(c, Instant::now());
+    }
+
+    /// Adds the character to the end of the prefix at the given time.
+    /// A new prefix is started if the previous one expired
+    pub fn push_at(&mut self, c: char, now: Instant) {
+        if self.is_expired_at(now) {
+            self.prefix.clear();
+        }
+        self.prefix.push(c);
+        self.last_input = Some(now);
+    }
+
+    /// Removes the last character of the prefix
+    pub fn pop(&mut self) {
+        self.prefix.pop();
+        self.last_input = Some(Instant::now());
+    }
+
+    pub fn clear(&mut self) {
+        self.prefix.clear();
+        self.last_input = None;
+    }
+
+    /// Returns whether the prefix was not typed into for longer than the timeout
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(Instant::now())
+    }
+
+    /// Returns whether the prefix was not typed into for longer than the timeout at the given time
+    pub fn is_expired_at(&self, now: Instant) -> bool {
+        !self.prefix.is_empty()
+            && self
+                .last_input
+                .map_or(true, |last_input| now >= last_input + TYPE_AHEAD_TIMEOUT)
+    }
+
+    /// Returns whether the row starts with the prefix. Case is ignored
+    pub fn is_match(&self, row: &[String]) -> bool {
+        !self.prefix.is_empty()
+            && row[0]
+                .to_lowercase()
+                .starts_with(&self.prefix.to_lowercase())
+    }
+
+    /// Returns the index of the first row that starts with the prefix
+    pub fn find_match(&self, rows: &[Vec<String>]) -> Option<usize> {
+        rows.iter().position(|row| self.is_match(row))
+    }
+}
+
+/// The numbers typed on a Months tab to jump to a month. 1 to 9 jump to January to September
+/// and 0 to October. A 1 followed by 0, 1 or 2 before the type-ahead timeout jumps to
+/// October, November or December instead
+pub struct MonthJump {
+    last_one: Option<Instant>,
+}
+
+impl Default for MonthJump {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MonthJump {
+    pub fn new() -> Self {
+        MonthJump { last_one: None }
+    }
+
+    /// Takes the typed number and returns the index of the month to jump to
+    pub fn push(&mut self, c: char) -> Option<usize> {
+        self.push_at(c, Instant::now())
+    }
+
+    /// Takes the typed number at the given time and returns the index of the month to jump to.
+    /// None if the character is not a number
+    pub fn push_at(&mut self, c: char, now: Instant) -> Option<usize> {
+        let digit = c.to_digit(10)? as usize;
+
+        let follows_one = self
+            .last_one
+            .map_or(false, |last_one| now < last_one + TYPE_AHEAD_TIMEOUT);
+        self.last_one = None;
+
+        match digit {
+            0..=2 if follows_one => Some(9 + digit),
+            0 => Some(9),
+            1 => {
+                self.last_one = Some(now);
+                Some(0)
+            }
+            _ => Some(digit - 1),
+        }
+    }
+}
+
 /// Indicates which popup is currently on and is being shown in the screen
 pub enum PopupState {
     NewUpdate(Vec<String>),
@@ -177,7 +735,33 @@ pub enum PopupState {
     SummaryHelp,
     SearchHelp,
     DeleteFailed(String),
-    TxDeletion,
+    /// Every check that failed while submitting the transaction on the add tx page
+    SubmitErrors(CheckingErrors),
+    TxDeletion(TxDeletionData),
+    QuitWarning(String, QuitStatus),
+    RepeatTx(RepeatTxData),
+    TxConfirmation(String, ConfirmStatus),
+    Reminders(ReminderData),
+    TagEdit(TagEditData),
+    NewTags(TagEditData),
+    BalanceDate(BalanceDateData),
+    Dashboard(DashboardData),
+    ExcludedTags(ExcludedTagsData),
+    BiggestTx(String, Vec<String>),
+    TxDetails(TxDetailsData),
+    RefundPicker(TxPickerData),
+    /// Picks the transaction to link. The details popup is shown again once it is closed
+    LinkPicker(TxPickerData, TxDetailsData),
+    /// Picks the date of the date field from a month grid
+    Calendar(CalendarData),
+    /// Picks the tx method of a field when the typed one is about as close to several of them
+    MethodPicker(MethodPickerData),
+    /// Picks a details template and asks for the values of its placeholders
+    DetailsTemplates(DetailsTemplateData),
+    /// Confirms switching read-only mode. Contains whether it is currently on
+    ReadOnlyToggle(bool, ConfirmStatus),
+    /// The report of a background task that finished
+    TaskReport(String),
     Nothing,
 }
 
@@ -185,6 +769,10 @@ pub enum ChartTab {
     ModeSelection,
     Years,
     Months,
+    RangeStart,
+    RangeEnd,
+    Tags,
+    CompareYears,
 }
 
 impl ChartTab {
@@ -193,9 +781,13 @@ impl ChartTab {
     #[cfg(not(tarpaulin_include))]
     pub fn change_tab_up_monthly(&mut self) -> Self {
         match &self {
-            ChartTab::ModeSelection => ChartTab::Months,
+            ChartTab::ModeSelection => ChartTab::Tags,
             ChartTab::Years => ChartTab::ModeSelection,
             ChartTab::Months => ChartTab::Years,
+            ChartTab::Tags => ChartTab::Months,
+            ChartTab::RangeStart | ChartTab::RangeEnd | ChartTab::CompareYears => {
+                ChartTab::ModeSelection
+            }
         }
     }
 
@@ -206,7 +798,11 @@ impl ChartTab {
         match &self {
             ChartTab::ModeSelection => ChartTab::Years,
             ChartTab::Years => ChartTab::Months,
-            ChartTab::Months => ChartTab::ModeSelection,
+            ChartTab::Months => ChartTab::Tags,
+            ChartTab::Tags => ChartTab::ModeSelection,
+            ChartTab::RangeStart | ChartTab::RangeEnd | ChartTab::CompareYears => {
+                ChartTab::ModeSelection
+            }
         }
     }
 
@@ -215,9 +811,13 @@ impl ChartTab {
     #[cfg(not(tarpaulin_include))]
     pub fn change_tab_up_yearly(&mut self) -> Self {
         match &self {
-            ChartTab::ModeSelection => ChartTab::Years,
+            ChartTab::ModeSelection => ChartTab::Tags,
             ChartTab::Years => ChartTab::ModeSelection,
+            ChartTab::Tags => ChartTab::Years,
             ChartTab::Months => ChartTab::Months,
+            ChartTab::RangeStart | ChartTab::RangeEnd | ChartTab::CompareYears => {
+                ChartTab::ModeSelection
+            }
         }
     }
 
@@ -227,16 +827,71 @@ impl ChartTab {
     pub fn change_tab_down_yearly(&mut self) -> Self {
         match &self {
             ChartTab::ModeSelection => ChartTab::Years,
-            ChartTab::Years => ChartTab::ModeSelection,
+            ChartTab::Years => ChartTab::Tags,
+            ChartTab::Tags => ChartTab::ModeSelection,
             ChartTab::Months => ChartTab::Months,
+            ChartTab::RangeStart | ChartTab::RangeEnd | ChartTab::CompareYears => {
+                ChartTab::ModeSelection
+            }
+        }
+    }
+
+    /// Moves the current selected tab to the upper value in the Range mode. If at the 1st value, the
+    /// the final value is selected.
+    #[cfg(not(tarpaulin_include))]
+    pub fn change_tab_up_range(&mut self) -> Self {
+        match &self {
+            ChartTab::ModeSelection => ChartTab::Tags,
+            ChartTab::Years => ChartTab::ModeSelection,
+            ChartTab::Months => ChartTab::Years,
+            ChartTab::RangeStart => ChartTab::Months,
+            ChartTab::RangeEnd => ChartTab::RangeStart,
+            ChartTab::Tags => ChartTab::RangeEnd,
+            ChartTab::CompareYears => ChartTab::ModeSelection,
+        }
+    }
+
+    /// Moves the current selected tab to the bottom value in the Range mode. If at the last value, the
+    /// the 1st value is selected.
+    #[cfg(not(tarpaulin_include))]
+    pub fn change_tab_down_range(&mut self) -> Self {
+        match &self {
+            ChartTab::ModeSelection => ChartTab::Years,
+            ChartTab::Years => ChartTab::Months,
+            ChartTab::Months => ChartTab::RangeStart,
+            ChartTab::RangeStart => ChartTab::RangeEnd,
+            ChartTab::RangeEnd => ChartTab::Tags,
+            ChartTab::Tags => ChartTab::ModeSelection,
+            ChartTab::CompareYears => ChartTab::ModeSelection,
+        }
+    }
+
+    /// Switches between the two year selections of the year comparison view
+    #[cfg(not(tarpaulin_include))]
+    pub fn change_tab_comparison(&mut self) -> Self {
+        match &self {
+            ChartTab::Years => ChartTab::CompareYears,
+            _ => ChartTab::Years,
+        }
+    }
+
+    /// Switches between the mode and the tag selection. All time mode has no other tab
+    #[cfg(not(tarpaulin_include))]
+    pub fn change_tab_all_time(&mut self) -> Self {
+        match &self {
+            ChartTab::ModeSelection => ChartTab::Tags,
+            _ => ChartTab::ModeSelection,
         }
     }
 }
 
 pub enum SummaryTab {
     ModeSelection,
+    Methods,
     Years,
     Months,
+    RangeStart,
+    RangeEnd,
     Table,
 }
 
@@ -247,9 +902,11 @@ impl SummaryTab {
     pub fn change_tab_up_monthly(&mut self) -> Self {
         match &self {
             SummaryTab::ModeSelection => SummaryTab::Table,
-            SummaryTab::Years => SummaryTab::ModeSelection,
+            SummaryTab::Methods => SummaryTab::ModeSelection,
+            SummaryTab::Years => SummaryTab::Methods,
             SummaryTab::Months => SummaryTab::Years,
             SummaryTab::Table => SummaryTab::Months,
+            SummaryTab::RangeStart | SummaryTab::RangeEnd => SummaryTab::ModeSelection,
         }
     }
 
@@ -258,10 +915,12 @@ impl SummaryTab {
     #[cfg(not(tarpaulin_include))]
     pub fn change_tab_down_monthly(&mut self) -> Self {
         match &self {
-            SummaryTab::ModeSelection => SummaryTab::Years,
+            SummaryTab::ModeSelection => SummaryTab::Methods,
+            SummaryTab::Methods => SummaryTab::Years,
             SummaryTab::Years => SummaryTab::Months,
             SummaryTab::Months => SummaryTab::Table,
             SummaryTab::Table => SummaryTab::ModeSelection,
+            SummaryTab::RangeStart | SummaryTab::RangeEnd => SummaryTab::ModeSelection,
         }
     }
 
@@ -271,9 +930,11 @@ impl SummaryTab {
     pub fn change_tab_up_yearly(&mut self) -> Self {
         match &self {
             SummaryTab::ModeSelection => SummaryTab::Table,
-            SummaryTab::Years => SummaryTab::ModeSelection,
+            SummaryTab::Methods => SummaryTab::ModeSelection,
+            SummaryTab::Years => SummaryTab::Methods,
             SummaryTab::Table => SummaryTab::Years,
             SummaryTab::Months => SummaryTab::Months,
+            SummaryTab::RangeStart | SummaryTab::RangeEnd => SummaryTab::ModeSelection,
         }
     }
 
@@ -282,10 +943,12 @@ impl SummaryTab {
     #[cfg(not(tarpaulin_include))]
     pub fn change_tab_down_yearly(&mut self) -> Self {
         match &self {
-            SummaryTab::ModeSelection => SummaryTab::Years,
+            SummaryTab::ModeSelection => SummaryTab::Methods,
+            SummaryTab::Methods => SummaryTab::Years,
             SummaryTab::Years => SummaryTab::Table,
             SummaryTab::Table => SummaryTab::ModeSelection,
             SummaryTab::Months => SummaryTab::Months,
+            SummaryTab::RangeStart | SummaryTab::RangeEnd => SummaryTab::ModeSelection,
         }
     }
 
@@ -295,9 +958,11 @@ impl SummaryTab {
     pub fn change_tab_up_all_time(&mut self) -> Self {
         match &self {
             SummaryTab::ModeSelection => SummaryTab::Table,
-            SummaryTab::Table => SummaryTab::ModeSelection,
+            SummaryTab::Methods => SummaryTab::ModeSelection,
+            SummaryTab::Table => SummaryTab::Methods,
             SummaryTab::Years => SummaryTab::Years,
             SummaryTab::Months => SummaryTab::Months,
+            SummaryTab::RangeStart | SummaryTab::RangeEnd => SummaryTab::ModeSelection,
         }
     }
 
@@ -306,10 +971,40 @@ impl SummaryTab {
     #[cfg(not(tarpaulin_include))]
     pub fn change_tab_down_all_time(&mut self) -> Self {
         match &self {
-            SummaryTab::ModeSelection => SummaryTab::Table,
+            SummaryTab::ModeSelection => SummaryTab::Methods,
+            SummaryTab::Methods => SummaryTab::Table,
             SummaryTab::Table => SummaryTab::ModeSelection,
             SummaryTab::Years => SummaryTab::Years,
             SummaryTab::Months => SummaryTab::Months,
+            SummaryTab::RangeStart | SummaryTab::RangeEnd => SummaryTab::ModeSelection,
+        }
+    }
+
+    /// Moves the current selected tab to the upper value. If at the 1st value, the
+    /// the final value is selected.
+    #[cfg(not(tarpaulin_include))]
+    pub fn change_tab_up_range(&mut self) -> Self {
+        match &self {
+            SummaryTab::ModeSelection => SummaryTab::Table,
+            SummaryTab::Methods => SummaryTab::ModeSelection,
+            SummaryTab::RangeStart => SummaryTab::Methods,
+            SummaryTab::RangeEnd => SummaryTab::RangeStart,
+            SummaryTab::Table => SummaryTab::RangeEnd,
+            SummaryTab::Years | SummaryTab::Months => SummaryTab::ModeSelection,
+        }
+    }
+
+    /// Moves the current selected tab to the bottom value. If at the last value, the
+    /// the 1st value is selected.
+    #[cfg(not(tarpaulin_include))]
+    pub fn change_tab_down_range(&mut self) -> Self {
+        match &self {
+            SummaryTab::ModeSelection => SummaryTab::Methods,
+            SummaryTab::Methods => SummaryTab::RangeStart,
+            SummaryTab::RangeStart => SummaryTab::RangeEnd,
+            SummaryTab::RangeEnd => SummaryTab::Table,
+            SummaryTab::Table => SummaryTab::ModeSelection,
+            SummaryTab::Years | SummaryTab::Months => SummaryTab::ModeSelection,
         }
     }
 }
@@ -318,6 +1013,29 @@ pub enum UserInputType {
     AddNewTxMethod(Vec<String>),
     RenameTxMethod(Vec<String>),
     RepositionTxMethod(Vec<String>),
+    ArchiveTxs(String),
+    UnarchiveTxs,
+    AssignTagGroup(Vec<String>),
+    ChangeSetting(Vec<String>),
+    AddTemplate(Vec<String>),
+    DeleteTemplate(i32),
+    AddDetailsTemplate(String),
+    DeleteDetailsTemplate(i32),
+    SetMinorUnits(String, usize),
+    SetTagBounds(String, Option<f64>, Option<f64>),
+    SetDateRange(DateRange),
+    ToggleHiddenMethod(String),
+    /// The transactions of a journal with the line number of their entry and the lines
+    /// that could not be imported
+    ImportJournal(Vec<(usize, NewTx)>, Vec<JournalProblem>),
+    /// The profile of a CSV export, the tx methods to create for its accounts, its transactions
+    /// with their line number and the lines that could not be imported
+    ImportCsv(
+        ImportProfile,
+        Vec<String>,
+        Vec<(usize, NewTx)>,
+        Vec<CsvProblem>,
+    ),
     CancelledOperation,
     InvalidInput,
 }
@@ -329,16 +1047,36 @@ impl UserInputType {
             "1" => UserInputType::AddNewTxMethod(Vec::new()),
             "2" => UserInputType::RenameTxMethod(Vec::new()),
             "3" => UserInputType::RepositionTxMethod(Vec::new()),
+            "4" => UserInputType::ArchiveTxs(String::new()),
+            "5" => UserInputType::UnarchiveTxs,
+            "6" => UserInputType::AssignTagGroup(Vec::new()),
+            "7" => UserInputType::ChangeSetting(Vec::new()),
+            "8" => UserInputType::AddTemplate(Vec::new()),
+            "9" => UserInputType::SetMinorUnits(String::new(), 2),
+            "10" => UserInputType::SetTagBounds(String::new(), None, None),
+            "11" => UserInputType::SetDateRange(DateRange::default()),
+            "12" => UserInputType::ToggleHiddenMethod(String::new()),
+            "13" => UserInputType::ImportJournal(Vec::new(), Vec::new()),
+            "14" => UserInputType::AddDetailsTemplate(String::new()),
+            "15" => UserInputType::ImportCsv(
+                ImportProfile::FireflyIII,
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+            ),
             "cancel" => UserInputType::CancelledOperation,
             _ => UserInputType::InvalidInput,
         }
     }
 }
 
+/// The column the summary tag table is sorted by
 pub enum SortingType {
     ByTags,
     ByIncome,
     ByExpense,
+    ByIncomePercentage,
+    ByExpensePercentage,
 }
 
 impl SortingType {
@@ -347,7 +1085,147 @@ impl SortingType {
         match self {
             SortingType::ByTags => SortingType::ByIncome,
             SortingType::ByIncome => SortingType::ByExpense,
-            SortingType::ByExpense => SortingType::ByTags,
+            SortingType::ByExpense => SortingType::ByIncomePercentage,
+            SortingType::ByIncomePercentage => SortingType::ByExpensePercentage,
+            SortingType::ByExpensePercentage => SortingType::ByTags,
+        }
+    }
+
+    /// Returns the index of the table column that is sorted
+    pub fn column(&self) -> usize {
+        match self {
+            SortingType::ByTags => 0,
+            SortingType::ByIncome => 1,
+            SortingType::ByExpense => 2,
+            SortingType::ByIncomePercentage => 3,
+            SortingType::ByExpensePercentage => 4,
+        }
+    }
+
+    /// Returns the sorting type of the given table column. None if the column can not be sorted
+    pub fn from_column(column: usize) -> Option<Self> {
+        match column {
+            0 => Some(SortingType::ByTags),
+            1 => Some(SortingType::ByIncome),
+            2 => Some(SortingType::ByExpense),
+            3 => Some(SortingType::ByIncomePercentage),
+            4 => Some(SortingType::ByExpensePercentage),
+            _ => None,
+        }
+    }
+
+    /// Tags are sorted from A to Z and the amounts from the highest one unless flipped
+    pub fn default_order(&self) -> SortOrder {
+        match self {
+            SortingType::ByTags => SortOrder::Ascending,
+            _ => SortOrder::Descending,
+        }
+    }
+}
+
+/// The direction the summary tag table is sorted in
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    #[cfg(not(tarpaulin_include))]
+    pub fn flip(&self) -> Self {
+        match self {
+            SortOrder::Ascending => SortOrder::Descending,
+            SortOrder::Descending => SortOrder::Ascending,
+        }
+    }
+
+    /// Returns the symbol shown next to the sorted column header
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            SortOrder::Ascending => "▲",
+            SortOrder::Descending => "▼",
+        }
+    }
+}
+
+/// How the search result table groups the transactions. Each group ends with a subtotal row
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SearchGrouping {
+    None,
+    Month,
+    Year,
+}
+
+impl SearchGrouping {
+    #[cfg(not(tarpaulin_include))]
+    pub fn next_type(&self) -> Self {
+        match self {
+            SearchGrouping::None => SearchGrouping::Month,
+            SearchGrouping::Month => SearchGrouping::Year,
+            SearchGrouping::Year => SearchGrouping::None,
+        }
+    }
+}
+
+/// What the Chart page plots. The balance chart is the default one
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ChartView {
+    Balance,
+    IncomeExpense,
+    StackedExpense,
+    YearComparison,
+}
+
+impl ChartView {
+    #[cfg(not(tarpaulin_include))]
+    pub fn next_type(&self) -> Self {
+        match self {
+            ChartView::Balance => ChartView::IncomeExpense,
+            ChartView::IncomeExpense => ChartView::StackedExpense,
+            ChartView::StackedExpense => ChartView::YearComparison,
+            ChartView::YearComparison => ChartView::Balance,
+        }
+    }
+
+    /// Returns the title of the chart block
+    pub fn title(&self) -> &'static str {
+        match self {
+            ChartView::Balance => "Balance",
+            ChartView::IncomeExpense => "Monthly Income vs Expense",
+            ChartView::StackedExpense => "Monthly Expense by Method",
+            ChartView::YearComparison => "Monthly Expense Comparison",
+        }
+    }
+}
+
+/// Which transactions the Home table shows. Used for going through the transactions
+/// that still need to be tagged or reviewed
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum HomeFilter {
+    All,
+    Untagged,
+    Cleanup,
+}
+
+impl HomeFilter {
+    #[cfg(not(tarpaulin_include))]
+    pub fn next_type(&self) -> Self {
+        match self {
+            HomeFilter::All => HomeFilter::Untagged,
+            HomeFilter::Untagged => HomeFilter::Cleanup,
+            HomeFilter::Cleanup => HomeFilter::All,
+        }
+    }
+
+    /// Returns whether a transaction with the given tags and review state passes the filter.
+    /// Transactions saved without tags have the Unknown tag
+    pub fn includes(&self, tags: &str, reviewed: bool) -> bool {
+        let untagged = tags.is_empty() || tags == "Unknown";
+
+        match self {
+            HomeFilter::All => true,
+            HomeFilter::Untagged => untagged,
+            HomeFilter::Cleanup => untagged || !reviewed,
         }
     }
 }
@@ -357,7 +1235,40 @@ pub enum DeletionStatus {
     No,
 }
 
+/// The selected option of the quit warning popup. Defaults to waiting for the operation
+#[derive(Debug, PartialEq, Default)]
+pub enum QuitStatus {
+    #[default]
+    Wait,
+    QuitAnyway,
+}
+
+impl QuitStatus {
+    pub fn toggle(&self) -> Self {
+        match self {
+            QuitStatus::Wait => QuitStatus::QuitAnyway,
+            QuitStatus::QuitAnyway => QuitStatus::Wait,
+        }
+    }
+}
+
+/// The selected option of the transaction confirmation popup. Defaults to confirming
+pub enum ConfirmStatus {
+    Confirm,
+    Edit,
+}
+
+impl ConfirmStatus {
+    pub fn toggle(&self) -> Self {
+        match self {
+            ConfirmStatus::Confirm => ConfirmStatus::Edit,
+            ConfirmStatus::Edit => ConfirmStatus::Confirm,
+        }
+    }
+}
+
 impl DeletionStatus {
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Self {
         match self {
             DeletionStatus::Yes => DeletionStatus::No,
@@ -2,4 +2,11 @@ mod popup_data;
 mod popup_ui;
 
 pub use popup_data::PopupData;
-pub use popup_ui::{create_deletion_popup, create_popup};
+pub use popup_ui::{
+    create_balance_date_popup, create_calendar_popup, create_confirmation_popup,
+    create_dashboard_popup, create_deletion_popup, create_details_template_popup,
+    create_excluded_tags_popup, create_method_picker_popup, create_new_tags_popup, create_popup,
+    create_quit_popup, create_read_only_indicator, create_read_only_popup, create_reminder_popup,
+    create_repeat_popup, create_tag_edit_popup, create_task_popup, create_toasts,
+    create_tx_details_popup, create_tx_picker_popup,
+};
@@ -1,5 +1,13 @@
+use crate::outputs::CheckingErrors;
 use crate::page_handler::{DeletionStatus, PopupState};
-use crate::popup_page::{create_deletion_popup, create_popup};
+use crate::popup_page::{
+    create_balance_date_popup, create_calendar_popup, create_confirmation_popup,
+    create_dashboard_popup, create_deletion_popup, create_details_template_popup,
+    create_excluded_tags_popup, create_method_picker_popup, create_new_tags_popup, create_popup,
+    create_quit_popup, create_read_only_popup, create_reminder_popup, create_repeat_popup,
+    create_tag_edit_popup, create_tx_details_popup, create_tx_picker_popup,
+};
+use crate::utility::AmountFormat;
 use ratatui::backend::Backend;
 use ratatui::Frame;
 
@@ -33,6 +41,7 @@ impl<'a> PopupData<'a> {
         f: &mut Frame<B>,
         popup_type: &PopupState,
         deletion_status: &DeletionStatus,
+        amount_format: &AmountFormat,
     ) {
         let status = match popup_type {
             PopupState::NewUpdate(data) => self.get_new_update_text(data),
@@ -41,16 +50,66 @@ impl<'a> PopupData<'a> {
             PopupState::ChartHelp => self.get_chart_help_text(),
             PopupState::SummaryHelp => self.get_summary_help_text(),
             PopupState::DeleteFailed(err) => self.get_delete_failed_text(err),
+            PopupState::SubmitErrors(errors) => self.get_submit_errors_text(errors),
+            PopupState::TaskReport(report) => self.get_task_report_text(report),
             PopupState::SearchHelp => self.get_search_help_text(),
-            PopupState::Nothing | PopupState::TxDeletion => String::new(),
+            PopupState::BiggestTx(tag, tx) => self.get_biggest_tx_text(tag, tx, amount_format),
+            PopupState::Nothing
+            | PopupState::TxDeletion(_)
+            | PopupState::TxDetails(_)
+            | PopupState::RefundPicker(_)
+            | PopupState::MethodPicker(_)
+            | PopupState::DetailsTemplates(_)
+            | PopupState::Calendar(_)
+            | PopupState::LinkPicker(..)
+            | PopupState::QuitWarning(..)
+            | PopupState::ReadOnlyToggle(..)
+            | PopupState::RepeatTx(_)
+            | PopupState::TxConfirmation(..)
+            | PopupState::Reminders(_)
+            | PopupState::TagEdit(_)
+            | PopupState::NewTags(_)
+            | PopupState::BalanceDate(_)
+            | PopupState::Dashboard(_)
+            | PopupState::ExcludedTags(_) => String::new(),
         };
 
-        if let PopupState::TxDeletion = popup_type {
-            create_deletion_popup(f, deletion_status)
-        } else {
-            if !status.is_empty() {
-                create_popup(f, self.x_value, self.y_value, self.title, status);
-            }
+        if let PopupState::TxDeletion(deletion_data) = popup_type {
+            create_deletion_popup(f, deletion_data, deletion_status)
+        } else if let PopupState::QuitWarning(operation, quit_status) = popup_type {
+            create_quit_popup(f, operation, quit_status)
+        } else if let PopupState::ReadOnlyToggle(read_only, confirm_status) = popup_type {
+            create_read_only_popup(f, *read_only, confirm_status)
+        } else if let PopupState::RepeatTx(repeat_data) = popup_type {
+            create_repeat_popup(f, repeat_data)
+        } else if let PopupState::TxConfirmation(summary, confirm_status) = popup_type {
+            create_confirmation_popup(f, summary, confirm_status)
+        } else if let PopupState::Reminders(reminder_data) = popup_type {
+            create_reminder_popup(f, reminder_data)
+        } else if let PopupState::TagEdit(tag_data) = popup_type {
+            create_tag_edit_popup(f, tag_data)
+        } else if let PopupState::NewTags(tag_data) = popup_type {
+            create_new_tags_popup(f, tag_data)
+        } else if let PopupState::BalanceDate(balance_data) = popup_type {
+            create_balance_date_popup(f, balance_data, amount_format)
+        } else if let PopupState::Dashboard(dashboard_data) = popup_type {
+            create_dashboard_popup(f, dashboard_data, amount_format)
+        } else if let PopupState::ExcludedTags(excluded_data) = popup_type {
+            create_excluded_tags_popup(f, excluded_data)
+        } else if let PopupState::TxDetails(details_data) = popup_type {
+            create_tx_details_popup(f, details_data, amount_format)
+        } else if let PopupState::RefundPicker(picker_data) = popup_type {
+            create_tx_picker_popup(f, "Refund Of", picker_data, amount_format)
+        } else if let PopupState::LinkPicker(picker_data, _) = popup_type {
+            create_tx_picker_popup(f, "Link To", picker_data, amount_format)
+        } else if let PopupState::MethodPicker(picker_data) = popup_type {
+            create_method_picker_popup(f, picker_data)
+        } else if let PopupState::DetailsTemplates(template_data) = popup_type {
+            create_details_template_popup(f, template_data)
+        } else if let PopupState::Calendar(calendar_data) = popup_type {
+            create_calendar_popup(f, calendar_data)
+        } else if !status.is_empty() {
+            create_popup(f, self.x_value, self.y_value, self.title, status);
         }
     }
 
@@ -74,28 +133,36 @@ Enter: Redirect to the new version",
 
 On Transfer transaction there will be one additional field pushing Tags to the key 7. 
 
-1: Date         Example: 2022-05-12, YYYY-MM-DD
+1: Date         Example: 2022-05-12, YYYY-MM-DD, t, y, -3
 2: TX details   Example: For Grocery, Salary
-5: TX Type      Example: Income/Expense/I/E
+5: TX Type      Example: Income/Expense/Transfer/Refund/I/E/T/R
 3: TX Method    Example: Cash, Bank, Card
 4: Amount       Example: 1000, 100+50, b - 100
 6: Tags         Example: Food, Car. Add a Comma for a new tag
 
-S: Save the inputted data as a Transaction
+S: Save the inputted data as a Transaction. A summary can be shown before saving from the J menu settings
 Enter: Submit field and continue
 Enter: Selects the first field if nothing is selected
 Esc: Stop editing field
 Tab: Accept Autocompletion
 
 Arrow Up/Down: Steps value up/down by 1
+Ctrl/Shift/Alt + Arrow Up/Down: Steps the date by a month/year/week
+Ctrl+T: On date field sets the date to today
+Ctrl+D: On date field opens a calendar to pick the date from
+Ctrl+T: On details field inserts a details template. Its {placeholder} markers are asked for one by one. Templates are added from the J menu
+Shift/Ctrl + Arrow Up/Down: Steps the amount by 10/100 times the amount step set from the J menu
 Arrow Left/Right: Move cursor on input fields
 C: Clear all fields
+L: On a Refund transaction pick the expense it refunds. Opens by itself if no expense is linked
 b: On amount field 'b' gets replaced with the current balance of Tx Method field
-Calculation: Amount field supports simple calculation with +, -, *, /
+k and m: On amount field 1.5k becomes 1500 and 2m becomes 2000000
+Calculation: Amount field supports simple calculation with +, -, *, /, % and parentheses
+Group separators and currency symbols such as 1,250.50 or $45 are removed. The decimal mark is set from the J menu
 Tags: This field can be treated as the category of this transaction.
 Empty tags field gets replaced with Unknown. Separate more than 1 tags with a comma
 
-Example: 100 + b, b + b, 5 * b
+Example: 100 + b, b + b, 5 * b, (b - 20) / 2, 1500 - 10%, 1k + 250
 
 Other Keys:
 F: Home Page
@@ -103,6 +170,8 @@ R: Chart Page
 Z: Summary Page
 W: Search Page
 H: Show help
+Esc/Backspace: Go back to the page before the last jump or the Home page
+Ctrl+X: Dismiss the newest notification
 Q: Quit
 "
         .to_string()
@@ -116,8 +185,20 @@ Q: Quit
 Following are the supported keys here
 
 R: Hides the top widgets for full chart view
+B: Show the balances at the end of a date and how much they changed until today
+V: Switches between the balance chart, the monthly income and expense bars, the monthly expense stacked by method and the yearly expense comparison
+1-9: Shows or hides the balance line or the expense stack of the tx method with that number on the legend
+G: Stacks the monthly expense by tag groups instead of tx methods
+C: Switches the income and expense bars between the months of the year and the weeks of the selected month
+T: Shows or hides the Total line, the sum of the visible balances
 Arrow Up/Down: Cycle widgets
+Tags widget: Select a tag to show its monthly income and expense bars of the selected year
+Range mode: Type the start and end dates and press Enter. Selecting a month or year fills the dates
 Arrow Left/Right: Move value of the widget
+Arrow Left/Right on a Range date: Steps the date by a day, Ctrl/Shift/Alt by a month/year/week
+0-9 on Months: Jumps to the month with that number, 0 is October. 10, 11 and 12 typed quickly jump to October, November and December
+.: Jumps to the current month and year
+Greyed out months and years have no transactions. The skip_empty_periods setting makes the arrows skip them
 
 Other Keys:
 F: Home Page
@@ -125,6 +206,8 @@ A: Add Transaction Page
 Z: Summary Page
 W: Search Page
 H: Show help
+Esc/Backspace: Go back to the page before the last jump or the Home page
+Ctrl+X: Dismiss the newest notification
 Q: Quit
 "
         .to_string()
@@ -132,7 +215,7 @@ Q: Quit
 
     #[cfg(not(tarpaulin_include))]
     fn get_summary_help_text(&mut self) -> String {
-        self.set("Help", 50, 45);
+        self.set("Help", 50, 50);
         "This page shows various information based on all transactions
 and is for tracking incomes and expenses based on tags
 Transfer Transaction are not shown here
@@ -141,7 +224,24 @@ Following are the supported keys here
 
 Arrow Up/Down: Cycle widgets/table value
 Arrow Left/Right: Move value of the widget
-S: Sorts table by Tag, Total Income or Total Expense
+Arrow Left/Right on Methods: Only shows the transactions of a tx method. Transfers count for both methods
+Arrow Left/Right on a Range date: Steps the date by a day, Ctrl/Shift/Alt by a month/year/week
+Enter on a Range date: Applies the typed start and end date
+0-9 on Months: Jumps to the month with that number, 0 is October. 10, 11 and 12 typed quickly jump to October, November and December
+.: Jumps to the current month and year
+Greyed out months and years have no transactions. The skip_empty_periods setting makes the arrows skip them
+Typing on the table: Jumps to the first tag starting with the typed text. Backspace shrinks it, Esc clears it
+Ctrl + a key on the table: Uses the shortcut of the key as typed keys are taken by the table
+Ctrl+B on the table: Shows the biggest transaction of the selected tag
+S: Sorts table by Tag, Total Income, Total Expense, Income % or Expense %
+Shift+S: Flips the direction of the table sort
+G: Switches the table between tags and tag groups
+C: Compares the expenses of the selected month with the previous month in the Monthly mode
+X: Selects the tags to exclude. Transactions with only excluded tags are left out of the calculations
+V: Switches between the view with exclusions and the raw view with every transaction
+T: Switches the method table between excluding transfers and counting them as income and expense
+Y: Copy the tag table with the column names to the clipboard to paste into a spreadsheet
+Shift+Y: Copy the method table with the column names to the clipboard
 Z: Hides the top widgets for full view
 
 Other Keys:
@@ -150,6 +250,8 @@ A: Add Transaction Page
 R: Chart Page
 W: Search Page
 H: Show help
+Esc/Backspace: Go back to the page before the last jump or the Home page
+Ctrl+X: Dismiss the newest notification
 Q: Quit
 "
         .to_string()
@@ -164,9 +266,27 @@ Following are the supported keys here
 
 Arrow Up/Down: Cycle widgets/table value
 Arrow Left/Right: Move value of the widget
-J: Starts taking input to add/rename/reposition Transaction Method
+0-9 on Months: Jumps to the month with that number, 0 is October. 10, 11 and 12 typed quickly jump to October, November and December
+.: Jumps to the current month and year
+Greyed out months and years have no transactions. The skip_empty_periods setting makes the arrows skip them
+J: Opens the menu to manage and hide Transaction Methods, Tag Groups, Tag Bounds, Templates, the year range and the archive or to import a ledger journal or a CSV export
 E: Edit the selected transaction on the table
-D: Delete the selected transaction on the table
+D: Delete the selected transaction on the table. Amounts at or above the deletion_typing_threshold setting need yes or the amount typed
+G: Show or hide the expense/income gauge
+Y: Repeat the transactions of a past day with today's date
+U: Cycle the table filter between all, untagged and untagged or unreviewed transactions
+C: Switch between the selected month and a single week of it. Arrow Left/Right on Months moves by week across months and years. Weeks start on the week_start setting
+V: Mark or unmark the selected transaction as reviewed
+T: Edit the tags of the selected transaction. New tags are only created after confirming
+B: Show the balances at the end of a date and how much they changed until today
+O: Show the dashboard of the current month. Can be shown on start from the J menu settings
+M: Show or hide the hidden Transaction Methods on the balance table
+I: Show the selected transaction with its refunds and linked transactions. L links another transaction, Enter goes to the selected link
+Ctrl+Y: Copy the selected transaction to the clipboard as a tab separated line
+Shift+Y: Copy the table with the column names to the clipboard to paste into a spreadsheet
+N: Refund the selected expense. The amount is set to what is left to refund
+P: Group the table by day with the expense of each day
+K: Switch read-only mode on or off. Rex can also be started with --read-only
 
 Other Keys:
 A: Add Transaction Page
@@ -174,6 +294,8 @@ R: Chart Page
 Z: Summary Page
 W: Search Page
 H: Show help
+Esc/Backspace: Go back to the page before the last jump or the Home page
+Ctrl+X: Dismiss the newest notification
 Q: Quit
 "
         .to_string()
@@ -185,6 +307,49 @@ Q: Quit
         err.to_string()
     }
 
+    #[cfg(not(tarpaulin_include))]
+    fn get_submit_errors_text(&mut self, errors: &CheckingErrors) -> String {
+        let error_count = errors.len();
+        self.set("Cannot Save", 60, (20 + error_count * 6).min(80) as u16);
+        errors.get_report()
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    fn get_task_report_text(&mut self, report: &str) -> String {
+        let line_count = report.lines().count();
+        self.set("Task Finished", 60, (20 + line_count * 3).min(80) as u16);
+
+        report.to_string()
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    fn get_biggest_tx_text(
+        &mut self,
+        tag: &str,
+        tx: &[String],
+        amount_format: &AmountFormat,
+    ) -> String {
+        self.set("Biggest Transaction", 50, 30);
+        format!(
+            "The biggest transaction of {tag}
+
+Date: {}
+Details: {}
+Tx Method: {}
+Amount: {}
+Tx Type: {}
+Tags: {}
+
+Esc: Close",
+            tx[0],
+            tx[1],
+            tx[2],
+            amount_format.localize(&tx[3]),
+            tx[4],
+            tx[5]
+        )
+    }
+
     #[cfg(not(tarpaulin_include))]
     fn get_search_help_text(&mut self) -> String {
         self.set("Help", 60, 100);
@@ -194,27 +359,41 @@ On Transfer transaction there will be one additional field pushing Tags to the k
 
 1: Date         Example: 2022-05-12, YYYY-MM-DD
 2: TX details   Example: For Grocery, Salary
-5: TX Type      Example: Income/Expense/I/E
+5: TX Type      Example: Income/Expense/Transfer/Refund/I/E/T/R
 3: TX Method    Example: Cash, Bank, Card
 4: Amount       Example: 1000, 100+50, b - 100
 6: Tags         Example: Food, Car. Add a Comma for a new tag
 
 Fields: Minimum 1 field must be filled to search for transactions.
 Fill up multiple fields for better accuracy
+ID: Type # and the id of a transaction in TX details such as #1234 to find only that transaction
 
 S: Search for transactions with the given data
+E/O/Enter: Show the selected result among its month on the Home page. Esc goes back to the results
+V: Include or exclude archived transactions from the search. Archived results can only be viewed and copied
+G: Group the results by month or year with a subtotal row for each group
+I: Show the selected result with its refunds and linked transactions. L links another transaction, Enter goes to the selected link
+N: Refund the selected expense
+Y: Copy the selected result to the clipboard as a tab separated line
+Shift+Y: Copy every result with the column names to the clipboard to paste into a spreadsheet
 Enter: Submit field and continue
 Enter: Selects the first field if nothing is selected
 Esc: Stop editing field
 Tab: Accept Autocompletion
 
 Arrow Up/Down: Steps value up/down by 1
+Ctrl/Shift/Alt + Arrow Up/Down: Steps the date by a month/year/week
+Ctrl+T: On date field sets the date to today
+Ctrl+D: On date field opens a calendar to pick the date from
+Shift/Ctrl + Arrow Up/Down: Steps the amount by 10/100 times the amount step set from the J menu
 Arrow Left/Right: Move cursor on input fields
 C: Clear all fields
 b: On amount field 'b' gets replaced with the current balance of Tx Method field
-Calculation: Amount field supports simple calculation with +, -, *, /
+k and m: On amount field 1.5k becomes 1500 and 2m becomes 2000000
+Calculation: Amount field supports simple calculation with +, -, *, /, % and parentheses
+Group separators and currency symbols such as 1,250.50 or $45 are removed. The decimal mark is set from the J menu
 
-Example: 100 + b, b + b, 5 * b
+Example: 100 + b, b + b, 5 * b, (b - 20) / 2, 1500 - 10%, 1k + 250
 
 Details Field: If details field is filled up, it will try to find transactions
 that matches the given input. It doesn't have to be an exact match
@@ -232,6 +411,8 @@ A: Add Transaction Page
 R: Chart Page
 Z: Summary Page
 H: Show help
+Esc/Backspace: Go back to the page before the last jump or the Home page
+Ctrl+X: Dismiss the newest notification
 Q: Quit
 "
         .to_string()
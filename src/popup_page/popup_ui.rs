@@ -1,10 +1,20 @@
-use crate::page_handler::{DeletionStatus, BACKGROUND, BLUE, BOX, HIGHLIGHTED, RED, TEXT};
-use crate::utility::create_bolded_text;
+use crate::home_page::DashboardData;
+use crate::page_handler::{
+    BackgroundTask, ConfirmStatus, DeletionStatus, QuitStatus, Severity, StatusMessage, BACKGROUND,
+    BLUE, BOX, GREEN, HIGHLIGHTED, RED, SELECTED, TEXT, YELLOW,
+};
+use crate::summary_page::ExcludedTagsData;
+use crate::tx_handler::{
+    BalanceDateData, CalendarData, DetailsTemplateData, MethodPickerData, ReminderData,
+    RepeatTxData, TagEditData, TxDeletionData, TxDetailsData, TxPickerData,
+};
+use crate::utility::{create_bolded_text, AmountFormat};
+use chrono::Datelike;
 use ratatui::backend::Backend;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Modifier, Style};
-use ratatui::text::{Span, Text};
-use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, Cell, Clear, Gauge, Paragraph, Row, Table, Wrap};
 use ratatui::Frame;
 
 /// Creates a popup on top of a window with the given size, title and text attributes
@@ -58,13 +68,36 @@ pub fn create_popup<B: Backend>(
 }
 
 #[cfg(not(tarpaulin_include))]
-pub fn create_deletion_popup<B: Backend>(f: &mut Frame<B>, deletion_status: &DeletionStatus) {
-    let text = "Are you sure you want to delete this transaction?";
+pub fn create_deletion_popup<B: Backend>(
+    f: &mut Frame<B>,
+    deletion_data: &TxDeletionData,
+    deletion_status: &DeletionStatus,
+) {
+    let mut text = format!(
+        "Are you sure you want to delete this transaction?\n\n{}",
+        deletion_data.get_lines().join("\n")
+    );
     let title = "TX Deletion";
-    let x_value = 40;
-    let y_value = 25;
+    let x_value = 50;
+    let mut y_value = 40;
     let size = f.size();
 
+    // the popup grows to fit the warning about the linked refunds
+    let warning = deletion_data.get_warning();
+    if !warning.is_empty() {
+        text.push_str(&format!("\n\n{warning}"));
+        y_value += 10;
+    }
+
+    // large transactions are only deleted once yes or the amount is typed
+    if deletion_data.needs_typing() {
+        text.push_str(&format!(
+            "\n\nType yes or the amount to delete: {}",
+            deletion_data.get_typed()
+        ));
+        y_value += 10;
+    }
+
     let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
     let text = create_bolded_text(&text);
 
@@ -97,7 +130,8 @@ pub fn create_deletion_popup<B: Backend>(f: &mut Frame<B>, deletion_status: &Del
 
     let deletion_text = Paragraph::new(Text::from(text))
         .style(Style::default().bg(BACKGROUND).fg(TEXT))
-        .alignment(Alignment::Center);
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
 
     let yes_text = match deletion_status {
         DeletionStatus::Yes => Span::styled(
@@ -136,10 +170,1435 @@ pub fn create_deletion_popup<B: Backend>(f: &mut Frame<B>, deletion_status: &Del
     f.render_widget(no_sec, selection_chunk[1]);
 }
 
-/// The function takes certain parameters to create an empty space in the layout
-/// and returns an area where we can place various widgets. Taken from tui-rs examples.
-/// This is used as a popup for helpful information.
+/// Creates a popup warning that an operation is still running while trying to quit
+#[cfg(not(tarpaulin_include))]
+pub fn create_quit_popup<B: Backend>(f: &mut Frame<B>, operation: &str, quit_status: &QuitStatus) {
+    let text = format!(
+        "An operation is still running: {operation}\nQuitting now may leave it unfinished."
+    );
+    let title = "Quit";
+    let x_value = 40;
+    let y_value = 25;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Min(1), Constraint::Length(5)].as_ref())
+        .split(area);
+
+    let selection_chunk = Layout::default()
+        .direction(Direction::Horizontal)
+        .margin(2)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(new_chunks[1]);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let warning_text = Paragraph::new(Text::from(text))
+        .style(Style::default().bg(BACKGROUND).fg(TEXT))
+        .alignment(Alignment::Center);
+
+    let wait_style = Style::default().fg(BLUE).add_modifier(Modifier::BOLD);
+    let quit_style = Style::default().fg(RED).add_modifier(Modifier::BOLD);
+
+    let (wait_text, quit_text) = match quit_status {
+        QuitStatus::Wait => (
+            Span::styled(" Wait ", wait_style.bg(HIGHLIGHTED)),
+            Span::styled(" Quit Anyway ", quit_style),
+        ),
+        QuitStatus::QuitAnyway => (
+            Span::styled(" Wait ", wait_style),
+            Span::styled(" Quit Anyway ", quit_style.bg(HIGHLIGHTED)),
+        ),
+    };
+
+    let wait_sec = Paragraph::new(wait_text).alignment(Alignment::Center);
+
+    let quit_sec = Paragraph::new(quit_text).alignment(Alignment::Center);
+
+    f.render_widget(warning_text, new_chunks[0]);
+    f.render_widget(wait_sec, selection_chunk[0]);
+    f.render_widget(quit_sec, selection_chunk[1]);
+}
+
+/// Creates a popup that confirms switching read-only mode on or off
+#[cfg(not(tarpaulin_include))]
+pub fn create_read_only_popup<B: Backend>(
+    f: &mut Frame<B>,
+    read_only: bool,
+    confirm_status: &ConfirmStatus,
+) {
+    let text = if read_only {
+        "Leave read-only mode?\nThe database will be opened for changes again."
+    } else {
+        "Switch to read-only mode?\nNothing can be changed until it is turned off."
+    };
+    let title = "Read-only Mode";
+    let x_value = 40;
+    let y_value = 25;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Min(1), Constraint::Length(5)].as_ref())
+        .split(area);
+
+    let selection_chunk = Layout::default()
+        .direction(Direction::Horizontal)
+        .margin(2)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(new_chunks[1]);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let question_text = Paragraph::new(Text::from(text))
+        .style(Style::default().bg(BACKGROUND).fg(TEXT))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+
+    let yes_style = Style::default().fg(GREEN).add_modifier(Modifier::BOLD);
+    let no_style = Style::default().fg(RED).add_modifier(Modifier::BOLD);
+
+    let (yes_text, no_text) = match confirm_status {
+        ConfirmStatus::Confirm => (
+            Span::styled(" Yes ", yes_style.bg(HIGHLIGHTED)),
+            Span::styled(" No ", no_style),
+        ),
+        ConfirmStatus::Edit => (
+            Span::styled(" Yes ", yes_style),
+            Span::styled(" No ", no_style.bg(HIGHLIGHTED)),
+        ),
+    };
+
+    f.render_widget(question_text, new_chunks[0]);
+    f.render_widget(
+        Paragraph::new(yes_text).alignment(Alignment::Center),
+        selection_chunk[0],
+    );
+    f.render_widget(
+        Paragraph::new(no_text).alignment(Alignment::Center),
+        selection_chunk[1],
+    );
+}
+
+/// Shows that the database is opened in read-only mode on the top line of the page
+#[cfg(not(tarpaulin_include))]
+pub fn create_read_only_indicator<B: Backend>(f: &mut Frame<B>) {
+    let size = f.size();
+    let text = " READ-ONLY ";
+    let width = (text.len() as u16).min(size.width);
+
+    let area = Rect {
+        x: (size.x + size.width).saturating_sub(width + 2),
+        y: size.y,
+        width,
+        height: 1.min(size.height),
+    };
+
+    let indicator = Paragraph::new(Span::styled(
+        text,
+        Style::default()
+            .fg(BACKGROUND)
+            .bg(RED)
+            .add_modifier(Modifier::BOLD),
+    ));
+
+    f.render_widget(indicator, area);
+}
+
+/// Creates a popup with the progress of the running background task. A spinner is shown
+/// until the task sends how many steps it has
+#[cfg(not(tarpaulin_include))]
+pub fn create_task_popup<B: Backend>(f: &mut Frame<B>, task: &BackgroundTask) {
+    let title = Span::styled(
+        task.get_name(),
+        Style::default().add_modifier(Modifier::BOLD),
+    );
+    let size = f.size();
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(50, 20, size);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints(
+            [
+                Constraint::Min(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let (done, total) = task.get_progress();
+
+    let progress_label = match task.get_percentage() {
+        Some(percentage) => format!("{done}/{total} ({percentage}%)"),
+        None => format!("{} Working", task.get_spinner()),
+    };
+
+    let gauge = Gauge::default()
+        .gauge_style(Style::default().fg(GREEN).bg(BACKGROUND))
+        .percent(task.get_percentage().unwrap_or(0))
+        .label(Span::styled(
+            progress_label,
+            Style::default().fg(TEXT).add_modifier(Modifier::BOLD),
+        ));
+
+    let hint = if task.is_cancelled() {
+        "Stopping once the current batch is done"
+    } else {
+        "Esc: Stop"
+    };
+
+    let hint_text =
+        Paragraph::new(Span::styled(hint, Style::default().fg(TEXT))).alignment(Alignment::Center);
+
+    f.render_widget(gauge, chunks[1]);
+    f.render_widget(hint_text, chunks[2]);
+}
+
+/// Creates a popup with the summary of the transaction that is about to be saved
+#[cfg(not(tarpaulin_include))]
+pub fn create_confirmation_popup<B: Backend>(
+    f: &mut Frame<B>,
+    summary: &str,
+    confirm_status: &ConfirmStatus,
+) {
+    let title = "Confirm Transaction";
+    let x_value = 50;
+    // edits with changed fields and amounts outside the tag bounds have extra lines
+    let extra_sections = ["\nChanges:", "\nAmount outside the expected range:"]
+        .iter()
+        .filter(|section| summary.contains(*section))
+        .count() as u16;
+    let y_value = 30 + extra_sections * 15;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Min(1), Constraint::Length(5)].as_ref())
+        .split(area);
+
+    let selection_chunk = Layout::default()
+        .direction(Direction::Horizontal)
+        .margin(2)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(new_chunks[1]);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    // the first line says whether the tx is being added or updated.
+    // Changed fields of an edit are highlighted and tags the amount is out of bounds of are shown in red
+    let summary_lines = summary
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            if index == 0 || line == "Changes:" {
+                Line::from(Span::styled(
+                    line,
+                    Style::default().add_modifier(Modifier::BOLD),
+                ))
+            } else if line == "Amount outside the expected range:" || line.contains(": expected ") {
+                Line::from(Span::styled(
+                    line,
+                    Style::default().fg(RED).add_modifier(Modifier::BOLD),
+                ))
+            } else if line.contains(" → ") {
+                Line::from(Span::styled(
+                    line,
+                    Style::default().fg(BLUE).add_modifier(Modifier::BOLD),
+                ))
+            } else {
+                Line::from(line)
+            }
+        })
+        .collect::<Vec<Line>>();
+
+    let summary_text = Paragraph::new(summary_lines)
+        .style(Style::default().bg(BACKGROUND).fg(TEXT))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+
+    let confirm_style = Style::default().fg(BLUE).add_modifier(Modifier::BOLD);
+    let edit_style = Style::default().fg(RED).add_modifier(Modifier::BOLD);
+
+    let (confirm_text, edit_text) = match confirm_status {
+        ConfirmStatus::Confirm => (
+            Span::styled(" Confirm ", confirm_style.bg(HIGHLIGHTED)),
+            Span::styled(" Edit ", edit_style),
+        ),
+        ConfirmStatus::Edit => (
+            Span::styled(" Confirm ", confirm_style),
+            Span::styled(" Edit ", edit_style.bg(HIGHLIGHTED)),
+        ),
+    };
+
+    let confirm_sec = Paragraph::new(confirm_text).alignment(Alignment::Center);
+
+    let edit_sec = Paragraph::new(edit_text).alignment(Alignment::Center);
+
+    f.render_widget(summary_text, new_chunks[0]);
+    f.render_widget(confirm_sec, selection_chunk[0]);
+    f.render_widget(edit_sec, selection_chunk[1]);
+}
+
+/// Creates the popup for repeating the transactions of a past day with today's date
+#[cfg(not(tarpaulin_include))]
+pub fn create_repeat_popup<B: Backend>(f: &mut Frame<B>, repeat_data: &RepeatTxData) {
+    let title = "Repeat Transactions";
+    let x_value = 60;
+    let y_value = 60;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(2),
+                Constraint::Min(1),
+                Constraint::Length(2),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let date_style = if repeat_data.is_editing_date() {
+        Style::default()
+            .fg(TEXT)
+            .bg(SELECTED)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(TEXT).add_modifier(Modifier::BOLD)
+    };
+
+    let date_text = Line::from(vec![
+        Span::styled("Source Date: ", Style::default().fg(TEXT)),
+        Span::styled(repeat_data.get_date(), date_style),
+    ]);
+
+    let date_sec = Paragraph::new(date_text).style(Style::default().bg(BACKGROUND));
+
+    let mut tx_lines = Vec::new();
+
+    if !repeat_data.is_editing_date() {
+        for (index, tx) in repeat_data.get_txs().iter().enumerate() {
+            let checkbox = if repeat_data.is_selected(index) {
+                "[x]"
+            } else {
+                "[ ]"
+            };
+
+            let mut text = format!(
+                "{checkbox} {} | {} | {} | {} | {}",
+                tx[1], tx[2], tx[3], tx[4], tx[5]
+            );
+
+            if repeat_data.is_duplicate(index) {
+                text.push_str(" (already added today)");
+            }
+
+            let mut style = Style::default().fg(TEXT);
+
+            if repeat_data.is_duplicate(index) {
+                style = style.fg(RED);
+            }
+
+            if index == repeat_data.get_index() {
+                style = style.bg(SELECTED).add_modifier(Modifier::BOLD);
+            }
+
+            tx_lines.push(Line::from(Span::styled(text, style)));
+        }
+    }
+
+    let tx_sec = Paragraph::new(tx_lines).style(Style::default().bg(BACKGROUND));
+
+    let duplicates = repeat_data.selected_duplicates();
+
+    let status_text = if !repeat_data.get_status().is_empty() {
+        repeat_data.get_status().to_string()
+    } else if duplicates > 0 {
+        format!("Warning: {duplicates} selected transaction(s) already exist today")
+    } else {
+        String::new()
+    };
+
+    let status_sec = Paragraph::new(status_text).style(
+        Style::default()
+            .bg(BACKGROUND)
+            .fg(RED)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let keys_text = if repeat_data.is_editing_date() {
+        "Enter: Load Transactions  Esc: Close"
+    } else {
+        "Space: Check/Uncheck  Enter: Add With Today's Date  Tab: Change Date  Esc: Close"
+    };
+
+    let keys_sec = Paragraph::new(keys_text)
+        .style(Style::default().bg(BACKGROUND).fg(BLUE))
+        .alignment(Alignment::Center);
+
+    f.render_widget(date_sec, new_chunks[0]);
+    f.render_widget(tx_sec, new_chunks[1]);
+    f.render_widget(status_sec, new_chunks[2]);
+    f.render_widget(keys_sec, new_chunks[3]);
+}
+
+/// Creates the popup that lists the pending template reminders of the current month
+#[cfg(not(tarpaulin_include))]
+pub fn create_reminder_popup<B: Backend>(f: &mut Frame<B>, reminder_data: &ReminderData) {
+    let title = "Reminders";
+    let x_value = 60;
+    let y_value = 50;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(2),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let header_sec = Paragraph::new("These transactions are due this month but were not added yet")
+        .style(Style::default().bg(BACKGROUND).fg(TEXT));
+
+    let mut reminder_lines = Vec::new();
+
+    for (index, template) in reminder_data.get_reminders().iter().enumerate() {
+        let text = format!(
+            "Day {} | {} | {} | {} | {} | {}",
+            template.remind_day.unwrap_or_default(),
+            template.details,
+            template.tx_method,
+            template.amount,
+            template.tx_type,
+            template.tags
+        );
+
+        let mut style = Style::default().fg(TEXT);
+
+        if index == reminder_data.get_index() {
+            style = style.bg(SELECTED).add_modifier(Modifier::BOLD);
+        }
+
+        reminder_lines.push(Line::from(Span::styled(text, style)));
+    }
+
+    let reminder_sec = Paragraph::new(reminder_lines).style(Style::default().bg(BACKGROUND));
+
+    let keys_sec = Paragraph::new("A: Add Now  D: Dismiss For This Month  Esc: Close")
+        .style(Style::default().bg(BACKGROUND).fg(BLUE))
+        .alignment(Alignment::Center);
+
+    f.render_widget(header_sec, new_chunks[0]);
+    f.render_widget(reminder_sec, new_chunks[1]);
+    f.render_widget(keys_sec, new_chunks[2]);
+}
+
+/// Creates the popup that edits the tags of the selected Home transaction
 #[cfg(not(tarpaulin_include))]
+pub fn create_tag_edit_popup<B: Backend>(f: &mut Frame<B>, tag_data: &TagEditData) {
+    let title = "Edit Tags";
+    let x_value = 50;
+    let y_value = 30;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(2),
+                Constraint::Length(2),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let details_sec = Paragraph::new(format!("Details: {}", tag_data.get_details()))
+        .style(Style::default().bg(BACKGROUND).fg(TEXT));
+
+    let tags_text = Line::from(vec![
+        Span::styled("Tags: ", Style::default().fg(TEXT)),
+        Span::styled(
+            tag_data.get_tags(),
+            Style::default()
+                .fg(TEXT)
+                .bg(SELECTED)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ]);
+
+    let tags_sec = Paragraph::new(tags_text).style(Style::default().bg(BACKGROUND));
+
+    let status_sec = Paragraph::new(tag_data.get_status())
+        .style(
+            Style::default()
+                .bg(BACKGROUND)
+                .fg(RED)
+                .add_modifier(Modifier::BOLD),
+        )
+        .wrap(Wrap { trim: true });
+
+    let keys_sec = Paragraph::new("Enter: Save Tags  Esc: Close")
+        .style(Style::default().bg(BACKGROUND).fg(BLUE))
+        .alignment(Alignment::Center);
+
+    f.render_widget(details_sec, new_chunks[0]);
+    f.render_widget(tags_sec, new_chunks[1]);
+    f.render_widget(status_sec, new_chunks[2]);
+    f.render_widget(keys_sec, new_chunks[3]);
+
+    f.set_cursor(
+        new_chunks[1].x + 6 + tag_data.get_current_index() as u16,
+        new_chunks[1].y,
+    );
+}
+
+/// Creates the popup that asks whether the unknown tags of the tag edit popup should be created
+#[cfg(not(tarpaulin_include))]
+pub fn create_new_tags_popup<B: Backend>(f: &mut Frame<B>, tag_data: &TagEditData) {
+    let title = "New Tags";
+    let x_value = 40;
+    let y_value = 25;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Min(1), Constraint::Length(1)].as_ref())
+        .split(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let prompt_sec = Paragraph::new(tag_data.get_unknown_tags_prompt())
+        .style(Style::default().bg(BACKGROUND).fg(TEXT))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+
+    let keys_sec = Paragraph::new("Y: Create  N: Go Back")
+        .style(Style::default().bg(BACKGROUND).fg(BLUE))
+        .alignment(Alignment::Center);
+
+    f.render_widget(prompt_sec, new_chunks[0]);
+    f.render_widget(keys_sec, new_chunks[1]);
+}
+
+/// Creates the popup that shows the balance of every method at the end of a date
+#[cfg(not(tarpaulin_include))]
+pub fn create_balance_date_popup<B: Backend>(
+    f: &mut Frame<B>,
+    balance_data: &BalanceDateData,
+    amount_format: &AmountFormat,
+) {
+    let title = "Balance On Date";
+    let x_value = 50;
+    let y_value = 50;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(2),
+                Constraint::Min(1),
+                Constraint::Length(3),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let date_text = Line::from(vec![
+        Span::styled("Date: ", Style::default().fg(TEXT)),
+        Span::styled(
+            balance_data.get_date(),
+            Style::default()
+                .fg(TEXT)
+                .bg(SELECTED)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ]);
+
+    let date_sec = Paragraph::new(date_text).style(Style::default().bg(BACKGROUND));
+
+    let mut balance_lines = Vec::new();
+
+    for balance in balance_data.get_balances() {
+        balance_lines.push(Line::from(vec![
+            Span::styled(
+                format!("{}: ", balance[0]),
+                Style::default().fg(TEXT).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                amount_format.localize(&balance[1]),
+                Style::default().fg(TEXT),
+            ),
+            change_span(&balance[2], amount_format),
+        ]));
+    }
+
+    if !balance_data.get_balances().is_empty() {
+        balance_lines.push(Line::from(vec![
+            Span::styled(
+                "Total: ",
+                Style::default().fg(TEXT).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                amount_format.localize(balance_data.get_total()),
+                Style::default().fg(TEXT),
+            ),
+            change_span(balance_data.get_total_change(), amount_format),
+        ]));
+    }
+
+    let balance_sec = Paragraph::new(balance_lines).style(Style::default().bg(BACKGROUND));
+
+    // the status takes the place of the summary line if the date is not accepted
+    let bottom_sec = if balance_data.get_status().is_empty() {
+        Paragraph::new(balance_data.get_summary_line())
+            .style(Style::default().bg(BACKGROUND).fg(TEXT))
+    } else {
+        Paragraph::new(balance_data.get_status()).style(
+            Style::default()
+                .bg(BACKGROUND)
+                .fg(RED)
+                .add_modifier(Modifier::BOLD),
+        )
+    }
+    .wrap(Wrap { trim: true });
+
+    let keys_sec = Paragraph::new("Enter: Show Balances  Esc: Close")
+        .style(Style::default().bg(BACKGROUND).fg(BLUE))
+        .alignment(Alignment::Center);
+
+    f.render_widget(date_sec, new_chunks[0]);
+    f.render_widget(balance_sec, new_chunks[1]);
+    f.render_widget(bottom_sec, new_chunks[2]);
+    f.render_widget(keys_sec, new_chunks[3]);
+
+    f.set_cursor(
+        new_chunks[0].x + 6 + balance_data.get_current_index() as u16,
+        new_chunks[0].y,
+    );
+}
+
+/// Creates the span that shows how much a balance changed until today
+#[cfg(not(tarpaulin_include))]
+fn change_span<'a>(change: &str, amount_format: &AmountFormat) -> Span<'a> {
+    let color = if change.starts_with('+') {
+        GREEN
+    } else if change.starts_with('-') {
+        RED
+    } else {
+        TEXT
+    };
+
+    Span::styled(
+        format!("  ({} by today)", amount_format.localize(change)),
+        Style::default().fg(color),
+    )
+}
+
+/// Creates the popup that summarizes the current month
+#[cfg(not(tarpaulin_include))]
+pub fn create_dashboard_popup<B: Backend>(
+    f: &mut Frame<B>,
+    dashboard_data: &DashboardData,
+    amount_format: &AmountFormat,
+) {
+    let title = format!("Dashboard - {}", dashboard_data.get_month());
+    let x_value = 60;
+    let y_value = 70;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Min(1), Constraint::Length(1)].as_ref())
+        .split(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let bold_style = Style::default().fg(TEXT).add_modifier(Modifier::BOLD);
+    let text_style = Style::default().fg(TEXT);
+
+    let net = dashboard_data.get_net();
+    let net_style = if net < 0.0 {
+        Style::default().fg(RED)
+    } else {
+        Style::default().fg(BLUE)
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Total Balance: ", bold_style),
+            Span::styled(
+                amount_format.format_amount(dashboard_data.get_total_balance(), 2),
+                text_style,
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Income: ", bold_style),
+            Span::styled(
+                amount_format.format_amount(dashboard_data.get_income(), 2),
+                text_style,
+            ),
+            Span::styled("  Expense: ", bold_style),
+            Span::styled(
+                amount_format.format_amount(dashboard_data.get_expense(), 2),
+                text_style,
+            ),
+            Span::styled("  Net: ", bold_style),
+            Span::styled(amount_format.format_amount(net, 2), net_style),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled("Top Expense Tags", bold_style)),
+    ];
+
+    if dashboard_data.get_top_tags().is_empty() {
+        lines.push(Line::from(Span::styled("No expenses yet", text_style)));
+    }
+
+    for (tag, amount) in dashboard_data.get_top_tags() {
+        lines.push(Line::from(Span::styled(
+            format!("{tag}: {}", amount_format.format_amount(*amount, 2)),
+            text_style,
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Tag Bound Warnings", bold_style)));
+
+    if dashboard_data.get_warnings().is_empty() {
+        lines.push(Line::from(Span::styled("None", text_style)));
+    }
+
+    for warning in dashboard_data.get_warnings() {
+        lines.push(Line::from(Span::styled(
+            amount_format.localize_decimal(warning),
+            Style::default().fg(RED),
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Pending Reminders", bold_style)));
+
+    if dashboard_data.get_reminders().is_empty() {
+        lines.push(Line::from(Span::styled("None", text_style)));
+    }
+
+    for reminder in dashboard_data.get_reminders() {
+        lines.push(Line::from(Span::styled(reminder.to_string(), text_style)));
+    }
+
+    let dashboard_sec = Paragraph::new(lines)
+        .style(Style::default().bg(BACKGROUND))
+        .wrap(Wrap { trim: true });
+
+    let keys_sec = Paragraph::new("Press any key to continue")
+        .style(Style::default().bg(BACKGROUND).fg(BLUE))
+        .alignment(Alignment::Center);
+
+    f.render_widget(dashboard_sec, new_chunks[0]);
+    f.render_widget(keys_sec, new_chunks[1]);
+}
+
+/// Creates the popup that selects the tags left out of the Summary page calculations
+#[cfg(not(tarpaulin_include))]
+pub fn create_excluded_tags_popup<B: Backend>(f: &mut Frame<B>, excluded_data: &ExcludedTagsData) {
+    let title = "Excluded Tags";
+    let x_value = 40;
+    let y_value = 60;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(2),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let header_sec =
+        Paragraph::new("Transactions with only excluded tags are left out of the Summary")
+            .style(Style::default().bg(BACKGROUND).fg(TEXT))
+            .wrap(Wrap { trim: true });
+
+    let mut tag_lines = Vec::new();
+
+    for (index, tag) in excluded_data.get_tags().iter().enumerate() {
+        let marker = if excluded_data.is_excluded(tag) {
+            "[x]"
+        } else {
+            "[ ]"
+        };
+
+        let mut style = Style::default().fg(TEXT);
+
+        if index == excluded_data.get_index() {
+            style = style.bg(SELECTED).add_modifier(Modifier::BOLD);
+        }
+
+        tag_lines.push(Line::from(Span::styled(format!("{marker} {tag}"), style)));
+    }
+
+    // scroll so the selected tag stays visible
+    let visible_lines = new_chunks[1].height.max(1) as usize;
+    let scroll = excluded_data.get_index().saturating_sub(visible_lines - 1) as u16;
+
+    let tag_sec = Paragraph::new(tag_lines)
+        .style(Style::default().bg(BACKGROUND))
+        .scroll((scroll, 0));
+
+    let keys_sec = Paragraph::new("Space: Toggle  Enter: Save  Esc: Close")
+        .style(Style::default().bg(BACKGROUND).fg(BLUE))
+        .alignment(Alignment::Center);
+
+    f.render_widget(header_sec, new_chunks[0]);
+    f.render_widget(tag_sec, new_chunks[1]);
+    f.render_widget(keys_sec, new_chunks[2]);
+}
+
+/// Creates a popup to pick a transaction such as the expense a refund is linked to.
+/// Typing filters the transactions
+#[cfg(not(tarpaulin_include))]
+pub fn create_tx_picker_popup<B: Backend>(
+    f: &mut Frame<B>,
+    title: &str,
+    picker_data: &TxPickerData,
+    amount_format: &AmountFormat,
+) {
+    let x_value = 60;
+    let y_value = 60;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(2),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let search_sec = Paragraph::new(Line::from(vec![
+        Span::styled("Search: ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::from(picker_data.get_query()),
+    ]))
+    .style(Style::default().bg(BACKGROUND).fg(TEXT));
+
+    let matches = picker_data.get_matches();
+
+    let tx_lines = if matches.is_empty() {
+        vec![Line::from(Span::styled(
+            "No transaction found",
+            Style::default().fg(TEXT),
+        ))]
+    } else {
+        matches
+            .iter()
+            .enumerate()
+            .map(|(index, tx)| {
+                let mut style = Style::default().fg(TEXT);
+
+                if index == picker_data.get_index() {
+                    style = style.bg(SELECTED).add_modifier(Modifier::BOLD);
+                }
+
+                let line = format!(
+                    "{} · {} · {} · {} · {}",
+                    tx[0],
+                    tx[1],
+                    tx[2],
+                    amount_format.localize(&tx[3]),
+                    tx[5]
+                );
+                Line::from(Span::styled(line, style))
+            })
+            .collect()
+    };
+
+    // scroll so the selected transaction stays visible
+    let visible_lines = new_chunks[1].height.max(1) as usize;
+    let scroll = picker_data.get_index().saturating_sub(visible_lines - 1) as u16;
+
+    let tx_sec = Paragraph::new(tx_lines)
+        .style(Style::default().bg(BACKGROUND))
+        .scroll((scroll, 0));
+
+    let keys_sec = Paragraph::new("Type: Search  Enter: Link  Esc: Close")
+        .style(Style::default().bg(BACKGROUND).fg(BLUE))
+        .alignment(Alignment::Center);
+
+    f.render_widget(search_sec, new_chunks[0]);
+    f.render_widget(tx_sec, new_chunks[1]);
+    f.render_widget(keys_sec, new_chunks[2]);
+}
+
+/// Creates a popup with the month grid of the calendar. The selected day is highlighted
+#[cfg(not(tarpaulin_include))]
+pub fn create_calendar_popup<B: Backend>(f: &mut Frame<B>, calendar_data: &CalendarData) {
+    let title = "Calendar";
+    let x_value = 40;
+    let y_value = 50;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(2),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let month_sec = Paragraph::new(Span::styled(
+        calendar_data.get_title(),
+        Style::default().add_modifier(Modifier::BOLD),
+    ))
+    .style(Style::default().bg(BACKGROUND).fg(TEXT))
+    .alignment(Alignment::Center);
+
+    let header = Row::new(calendar_data.get_day_names())
+        .style(Style::default().fg(BLUE).add_modifier(Modifier::BOLD));
+
+    let selected_day = calendar_data.get_selected().day();
+
+    let rows = calendar_data.get_weeks().into_iter().map(|week| {
+        Row::new(week.map(|day| {
+            match day {
+                Some(day) if day == selected_day => Cell::from(day.to_string())
+                    .style(Style::default().bg(SELECTED).add_modifier(Modifier::BOLD)),
+                Some(day) => Cell::from(day.to_string()),
+                None => Cell::from(""),
+            }
+        }))
+    });
+
+    let grid_sec = Table::new(rows)
+        .header(header)
+        .style(Style::default().bg(BACKGROUND).fg(TEXT))
+        .widths(&[Constraint::Length(4); 7])
+        .column_spacing(1);
+
+    // the grid is centered as the popup is wider than the 7 columns
+    let grid_width = 7 * 5;
+    let grid_area = Rect {
+        x: new_chunks[1].x + new_chunks[1].width.saturating_sub(grid_width) / 2,
+        width: grid_width.min(new_chunks[1].width),
+        ..new_chunks[1]
+    };
+
+    let keys_sec = Paragraph::new("Arrows: Day  PgUp/PgDn: Month  Enter: Pick  Esc: Close")
+        .style(Style::default().bg(BACKGROUND).fg(BLUE))
+        .alignment(Alignment::Center);
+
+    f.render_widget(month_sec, new_chunks[0]);
+    f.render_widget(grid_sec, grid_area);
+    f.render_widget(keys_sec, new_chunks[2]);
+}
+
+/// Creates a popup to pick one of the tx methods that are about as close to the typed one
+#[cfg(not(tarpaulin_include))]
+pub fn create_method_picker_popup<B: Backend>(f: &mut Frame<B>, picker_data: &MethodPickerData) {
+    let title = "Pick TX Method";
+    let x_value = 40;
+    let y_value = 40;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(2),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let typed_sec = Paragraph::new(Line::from(vec![
+        Span::styled("Typed: ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::from(picker_data.get_typed()),
+    ]))
+    .style(Style::default().bg(BACKGROUND).fg(TEXT));
+
+    let method_lines = picker_data
+        .get_candidates()
+        .iter()
+        .enumerate()
+        .map(|(index, method)| {
+            let mut style = Style::default().fg(TEXT);
+
+            if index == picker_data.get_index() {
+                style = style.bg(SELECTED).add_modifier(Modifier::BOLD);
+            }
+
+            Line::from(Span::styled(method.to_string(), style))
+        })
+        .collect::<Vec<Line>>();
+
+    let method_sec = Paragraph::new(method_lines).style(Style::default().bg(BACKGROUND));
+
+    let keys_sec = Paragraph::new("Enter: Pick  Esc: Close")
+        .style(Style::default().bg(BACKGROUND).fg(BLUE))
+        .alignment(Alignment::Center);
+
+    f.render_widget(typed_sec, new_chunks[0]);
+    f.render_widget(method_sec, new_chunks[1]);
+    f.render_widget(keys_sec, new_chunks[2]);
+}
+
+/// Creates a popup to pick a details template and to type the values of its placeholders
+#[cfg(not(tarpaulin_include))]
+pub fn create_details_template_popup<B: Backend>(
+    f: &mut Frame<B>,
+    template_data: &DetailsTemplateData,
+) {
+    let title = "Details Templates";
+    let x_value = 50;
+    let y_value = 40;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Min(1), Constraint::Length(1)].as_ref())
+        .split(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    // the list of templates until one is picked, then the value of each placeholder is asked for
+    let (lines, keys) = if template_data.is_filling() {
+        let (current, total) = template_data.get_progress();
+        let placeholder = template_data.get_current_placeholder().unwrap_or_default();
+
+        let lines = vec![
+            Line::from(template_data.get_preview()),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(
+                    format!("{placeholder} ({current}/{total}): "),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::from(template_data.get_input()),
+            ]),
+        ];
+        (lines, "Enter: Next  Esc: Close")
+    } else {
+        let lines = template_data
+            .get_templates()
+            .iter()
+            .enumerate()
+            .map(|(index, template)| {
+                let mut style = Style::default().fg(TEXT);
+
+                if index == template_data.get_index() {
+                    style = style.bg(SELECTED).add_modifier(Modifier::BOLD);
+                }
+
+                Line::from(Span::styled(template.text.to_string(), style))
+            })
+            .collect::<Vec<Line>>();
+        (lines, "Enter: Pick  Esc: Close")
+    };
+
+    let template_sec = Paragraph::new(lines)
+        .style(Style::default().bg(BACKGROUND).fg(TEXT))
+        .wrap(Wrap { trim: false });
+
+    let keys_sec = Paragraph::new(keys)
+        .style(Style::default().bg(BACKGROUND).fg(BLUE))
+        .alignment(Alignment::Center);
+
+    f.render_widget(template_sec, new_chunks[0]);
+    f.render_widget(keys_sec, new_chunks[1]);
+}
+
+/// Creates a popup with the transaction, its refund links and the transactions linked to it
+#[cfg(not(tarpaulin_include))]
+pub fn create_tx_details_popup<B: Backend>(
+    f: &mut Frame<B>,
+    details_data: &TxDetailsData,
+    amount_format: &AmountFormat,
+) {
+    let title = "Transaction Details";
+    let x_value = 60;
+    let y_value = 60;
+    let size = f.size();
+
+    let title = Span::styled(title, Style::default().add_modifier(Modifier::BOLD));
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(BACKGROUND).fg(BOX));
+
+    let area = centered_rect(x_value, y_value, size);
+
+    let tx = details_data.get_tx();
+    let refund_lines = details_data.get_refund_lines();
+
+    let mut text = format!(
+        "ID: #{}
+Date: {}
+Details: {}
+Tx Method: {}
+Amount: {}
+Tx Type: {}
+Tags: {}",
+        details_data.get_id_num(),
+        tx[0],
+        tx[1],
+        tx[2],
+        amount_format.localize(&tx[3]),
+        tx[4],
+        tx[5]
+    );
+
+    if !refund_lines.is_empty() {
+        text.push_str(&format!("\n\n{}", refund_lines.join("\n")));
+    }
+
+    let tx_height = text.lines().count() as u16 + 1;
+
+    let new_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(tx_height),
+                Constraint::Length(1),
+                Constraint::Min(1),
+                Constraint::Length(2),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let tx_sec = Paragraph::new(create_bolded_text(&text))
+        .style(Style::default().bg(BACKGROUND).fg(TEXT))
+        .wrap(Wrap { trim: true });
+
+    let links_title = Paragraph::new("Linked Transactions:").style(
+        Style::default()
+            .bg(BACKGROUND)
+            .fg(TEXT)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let links = details_data.get_links();
+
+    let link_lines = if links.is_empty() {
+        vec![Line::from(Span::styled(
+            "No linked transaction",
+            Style::default().fg(TEXT),
+        ))]
+    } else {
+        links
+            .iter()
+            .enumerate()
+            .map(|(index, (_, tx))| {
+                let mut style = Style::default().fg(TEXT);
+
+                if index == details_data.get_index() {
+                    style = style.bg(SELECTED).add_modifier(Modifier::BOLD);
+                }
+
+                let line = format!(
+                    "{} · {} · {} · {} · {}",
+                    tx[0],
+                    tx[1],
+                    tx[2],
+                    amount_format.localize(&tx[3]),
+                    tx[4]
+                );
+                Line::from(Span::styled(line, style))
+            })
+            .collect()
+    };
+
+    // scroll so the selected link stays visible
+    let visible_lines = new_chunks[2].height.max(1) as usize;
+    let scroll = details_data.get_index().saturating_sub(visible_lines - 1) as u16;
+
+    let links_sec = Paragraph::new(link_lines)
+        .style(Style::default().bg(BACKGROUND))
+        .scroll((scroll, 0));
+
+    let status_sec = Paragraph::new(details_data.get_status())
+        .style(
+            Style::default()
+                .bg(BACKGROUND)
+                .fg(RED)
+                .add_modifier(Modifier::BOLD),
+        )
+        .wrap(Wrap { trim: true });
+
+    let keys_sec = Paragraph::new("Enter: Go To  L: Link  D: Unlink  Esc: Close")
+        .style(Style::default().bg(BACKGROUND).fg(BLUE))
+        .alignment(Alignment::Center);
+
+    f.render_widget(tx_sec, new_chunks[0]);
+    f.render_widget(links_title, new_chunks[1]);
+    f.render_widget(links_sec, new_chunks[2]);
+    f.render_widget(status_sec, new_chunks[3]);
+    f.render_widget(keys_sec, new_chunks[4]);
+}
+
+/// The function takes certain parameters to create an empty space in the layout
+/// and returns an area where we can place various widgets. Taken from tui-rs examples.
+/// This is used as a popup for helpful information.
+#[cfg(not(tarpaulin_include))]
+/// Draws the toasts stacked in the bottom right corner with the newest one at the bottom.
+/// Only draws on top of the page, the keys still go to the page
+#[cfg(not(tarpaulin_include))]
+pub fn create_toasts<B: Backend>(f: &mut Frame<B>, messages: &[StatusMessage]) {
+    let size = f.size();
+
+    // a bordered single line per toast, kept inside the border of the page
+    let toast_height = 3;
+    let width = size.width.saturating_sub(4).min(40);
+    let mut y = (size.y + size.height).saturating_sub(2);
+
+    for message in messages.iter().rev() {
+        if y < size.y + toast_height {
+            break;
+        }
+        y -= toast_height;
+
+        let (title, color) = match message.get_severity() {
+            Severity::Info => ("Info", GREEN),
+            Severity::Warn => ("Warning", YELLOW),
+            Severity::Error => ("Error", RED),
+        };
+
+        let area = Rect {
+            x: (size.x + size.width).saturating_sub(width + 2),
+            y,
+            width,
+            height: toast_height,
+        };
+
+        let block = Block::default()
+            .title(Span::styled(
+                title,
+                Style::default().add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .style(Style::default().bg(BACKGROUND).fg(color));
+
+        let toast = Paragraph::new(message.get_text())
+            .style(Style::default().bg(BACKGROUND).fg(TEXT))
+            .block(block);
+
+        f.render_widget(Clear, area);
+        f.render_widget(toast, area);
+    }
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -1,20 +1,27 @@
 use crate::outputs::TxType;
-use crate::page_handler::{TableData, TxTab, BACKGROUND, BLUE, GRAY, HEADER, RED, SELECTED, TEXT};
+use crate::page_handler::{
+    SearchGrouping, TableData, TxTab, BACKGROUND, BLUE, GRAY, HEADER, RED, SELECTED, TEXT,
+};
 use crate::tx_handler::TxData;
-use crate::utility::{main_block, styled_block};
+use crate::utility::{
+    get_shown_details, get_table_position, main_block, styled_block, AmountFormat, DateFormat,
+    TX_TABLE_HEADER,
+};
 use ratatui::backend::Backend;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Cell, Paragraph, Row, Table};
 use ratatui::Frame;
-use thousands::Separable;
 
 pub fn search_ui<B: Backend>(
     f: &mut Frame<B>,
     search_data: &TxData,
     search_tab: &TxTab,
     search_table: &mut TableData,
+    search_grouping: SearchGrouping,
+    date_format: &DateFormat,
+    amount_format: &AmountFormat,
 ) {
     // get the data to insert into the Status widget of this page
     let status_data = search_data.get_tx_status();
@@ -33,11 +40,30 @@ pub fn search_ui<B: Backend>(
 
     let rows = search_table.items.iter().map(|item| {
         let height = 1;
-        let cells = item.iter().map(|c| Cell::from(c.separate_with_commas()));
+        let cells = item.iter().enumerate().map(|(index, c)| {
+            if index == 0 {
+                Cell::from(date_format.format_table_date(c))
+            } else if index == 1 {
+                Cell::from(amount_format.localize(&get_shown_details(c)))
+            } else {
+                Cell::from(amount_format.localize(c))
+            }
+        });
+
+        // subtotal rows are bold and gray to separate them from the transactions
+        let style = if item[4] == "Subtotal" {
+            Style::default()
+                .bg(BACKGROUND)
+                .fg(GRAY)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().bg(BACKGROUND).fg(TEXT)
+        };
+
         Row::new(cells)
             .height(height as u16)
             .bottom_margin(0)
-            .style(Style::default().bg(BACKGROUND).fg(TEXT))
+            .style(style)
     });
 
     let from_method_name = match tx_type {
@@ -48,10 +74,20 @@ pub fn search_ui<B: Backend>(
     let mut table_name = "Transactions".to_string();
 
     if !search_table.items.is_empty() {
-        table_name = format!("Transactions: {}", search_table.items.len());
+        let total_txs = search_table
+            .items
+            .iter()
+            .filter(|item| item[4] != "Subtotal")
+            .count();
+
+        table_name = match search_grouping {
+            SearchGrouping::None => format!("Transactions: {total_txs}"),
+            SearchGrouping::Month => format!("Transactions: {total_txs} (By Month)"),
+            SearchGrouping::Year => format!("Transactions: {total_txs} (By Year)"),
+        };
     }
 
-    let header_cells = ["Date", "Details", "TX Method", "Amount", "Type", "Tags"]
+    let header_cells = TX_TABLE_HEADER
         .iter()
         .map(|h| Cell::from(*h).style(Style::default().fg(BACKGROUND)));
 
@@ -79,6 +115,20 @@ pub fn search_ui<B: Backend>(
         )
         .split(size);
 
+    // long tables show where the selection is instead of only the transaction count
+    if let Some(position) = get_table_position(
+        search_table.state.selected(),
+        search_table.state.offset(),
+        search_table.items.len(),
+        chunks[3].height,
+    ) {
+        table_name = match search_grouping {
+            SearchGrouping::None => format!("Transactions: {position}"),
+            SearchGrouping::Month => format!("Transactions: {position} (By Month)"),
+            SearchGrouping::Year => format!("Transactions: {position} (By Year)"),
+        };
+    }
+
     // based on the tx type divide the first chunk into 5 or 6 parts horizontally
     // this chunk contains the input boxes take takes input
     let input_chunk = {
@@ -291,7 +341,7 @@ pub fn search_ui<B: Backend>(
         table_area = table_area.highlight_symbol(">> ");
         if search_table.items[a][4] == "Expense" {
             table_area = table_area.highlight_style(selected_style_expense)
-        } else if search_table.items[a][4] == "Income" {
+        } else if search_table.items[a][4] == "Income" || search_table.items[a][4] == "Refund" {
             table_area = table_area.highlight_style(selected_style_income)
         } else if search_table.items[a][4] == "Transfer" {
             table_area = table_area.highlight_style(Style::default().bg(SELECTED))
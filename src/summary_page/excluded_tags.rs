@@ -0,0 +1,72 @@
+use crate::db::set_tag_excluded;
+use crate::utility::{get_all_tags, get_excluded_tags};
+use rusqlite::{Connection, Result};
+use std::collections::HashSet;
+
+/// Contains all data for the popup that selects the tags left out of the Summary page calculations.
+/// The selection is only saved once confirmed
+///
+/// tags: `["Food", "Reimbursable", "Rent"]` every tag in alphabetical order
+pub struct ExcludedTagsData {
+    tags: Vec<String>,
+    excluded_tags: HashSet<String>,
+    index: usize,
+}
+
+impl ExcludedTagsData {
+    /// Creates an instance of the struct with the currently excluded tags selected
+    pub fn new(conn: &Connection) -> Self {
+        ExcludedTagsData {
+            tags: get_all_tags(conn),
+            excluded_tags: get_excluded_tags(conn),
+            index: 0,
+        }
+    }
+
+    pub fn get_tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    pub fn is_excluded(&self, tag: &str) -> bool {
+        self.excluded_tags.contains(tag)
+    }
+
+    /// Selects the next tag or the 1st one if at the final tag
+    pub fn next(&mut self) {
+        if !self.tags.is_empty() {
+            self.index = (self.index + 1) % self.tags.len();
+        }
+    }
+
+    /// Selects the previous tag or the final one if at the 1st tag
+    pub fn previous(&mut self) {
+        if !self.tags.is_empty() {
+            if self.index > 0 {
+                self.index -= 1;
+            } else {
+                self.index = self.tags.len() - 1;
+            }
+        }
+    }
+
+    /// Excludes the selected tag or includes it back if it was excluded
+    pub fn toggle_selected(&mut self) {
+        if let Some(tag) = self.tags.get(self.index) {
+            if !self.excluded_tags.remove(tag) {
+                self.excluded_tags.insert(tag.to_string());
+            }
+        }
+    }
+
+    /// Saves whether each tag is excluded
+    pub fn save(&self, conn: &mut Connection) -> Result<()> {
+        for tag in &self.tags {
+            set_tag_excluded(tag, self.excluded_tags.contains(tag), conn)?;
+        }
+        Ok(())
+    }
+}
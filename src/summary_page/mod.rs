@@ -1,5 +1,9 @@
+mod excluded_tags;
 mod summary_data;
+mod summary_range;
 mod summary_ui;
 
-pub use summary_data::SummaryData;
+pub use excluded_tags::ExcludedTagsData;
+pub use summary_data::{get_method_table_header, get_tag_table_header, SummaryData};
+pub use summary_range::{SummaryRangeData, RANGE_MODE};
 pub use summary_ui::summary_ui;
@@ -1,60 +1,130 @@
-use crate::db::{MONTHS, YEARS};
+use crate::db::{get_bool_setting, get_date_range, DateRange, MONTHS};
 use crate::page_handler::IndexedData;
-use crate::utility::{get_all_tx_methods, get_all_txs};
+use crate::summary_page::RANGE_MODE;
+use crate::utility::{
+    get_all_tx_methods, get_all_txs, get_excluded_tags, get_last_balances, get_money_on_date,
+    Clock, Money, SystemClock,
+};
+use chrono::{Datelike, NaiveDate};
 use rusqlite::Connection;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 
 type MyVec = Vec<Vec<String>>;
+/// The 4 summary blocks and the method table
+type TxDataTuple = (MyVec, MyVec, MyVec, MyVec, MyVec);
+/// (month index, year index, transactions of the month)
+type PeriodTxs<'a> = Vec<(usize, usize, Vec<&'a Vec<String>>)>;
 type MyTuple = (
-    f64,
-    f64,
-    (f64, String, String),
-    (f64, String, String),
-    f64,
-    f64,
+    Money,
+    Money,
+    (Money, String, String),
+    (Money, String, String),
+    Money,
+    Money,
 );
 
+/// The selection and the settings the data of `get_tx_data` depends on. The transactions are
+/// not a part of it as the struct is created again whenever they change
+#[derive(PartialEq, Eq, Hash)]
+struct TxDataKey {
+    mode: usize,
+    month: usize,
+    year: usize,
+    custom_range: Option<(NaiveDate, NaiveDate)>,
+    tx_method: Option<String>,
+    include_transfers: bool,
+    tx_methods: Vec<String>,
+    today: NaiveDate,
+}
+
 /// Contains the necessary information to construct the Summary Page highlighting
-/// tag based expense and income information, biggest expense and income
+/// tag based expense and income information, biggest expense and income.
+/// The excluded tags are empty in the raw view
 pub struct SummaryData {
     all_txs: HashMap<i32, Vec<Vec<String>>>,
+    date_range: DateRange,
+    excluded_tags: HashSet<String>,
+    tx_data_cache: RefCell<HashMap<TxDataKey, TxDataTuple>>,
+    aggregations: Cell<usize>,
 }
 
 impl SummaryData {
     /// Goes through all transactions to collect data for the summary
     pub fn new(conn: &Connection) -> Self {
         let mut all_txs = HashMap::new();
+        let date_range = get_date_range(conn);
 
-        for x in 0..YEARS.len() {
+        for x in 0..date_range.total_years() {
             for i in 0..MONTHS.len() {
                 let target_id = i as i32 + (x as i32 * 12);
                 let (txs, ..) = get_all_txs(conn, i, x);
                 all_txs.insert(target_id, txs);
             }
         }
-        SummaryData { all_txs }
+
+        let excluded_tags = if get_bool_setting("summary_raw_view", conn) {
+            HashSet::new()
+        } else {
+            get_excluded_tags(conn)
+        };
+
+        SummaryData {
+            all_txs,
+            date_range,
+            excluded_tags,
+            tx_data_cache: RefCell::new(HashMap::new()),
+            aggregations: Cell::new(0),
+        }
+    }
+
+    /// Returns the tags whose transactions are left out of the calculations
+    pub fn get_excluded_tags(&self) -> &HashSet<String> {
+        &self.excluded_tags
+    }
+
+    /// Checks whether the transaction uses the tx method. Transfers use both the method they
+    /// were sent from and the one they were sent to
+    fn is_tx_of_method(&self, tx: &[String], tx_method: &str) -> bool {
+        if tx[4] == "Transfer" {
+            tx[2].starts_with(&format!("{tx_method} to "))
+                || tx[2].ends_with(&format!(" to {tx_method}"))
+        } else {
+            tx[2] == tx_method
+        }
+    }
+
+    /// Checks whether every tag of the transaction is excluded
+    fn is_excluded_tx(&self, tx: &[String]) -> bool {
+        !self.excluded_tags.is_empty()
+            && tx[5]
+                .split(", ")
+                .all(|tag| self.excluded_tags.contains(tag))
     }
 
     /// Iters through the given transactions to collect earning and expense data
     fn get_data(
         &self,
-        txs: &Vec<Vec<String>>,
-        method_earning: &mut HashMap<String, f64>,
-        method_expense: &mut HashMap<String, f64>,
+        txs: &[&Vec<String>],
+        method_earning: &mut HashMap<String, Money>,
+        method_expense: &mut HashMap<String, Money>,
+        method_transfers: &mut HashMap<String, (Money, Money)>,
+        method_count: &mut HashMap<String, usize>,
+        include_transfers: bool,
     ) -> MyTuple {
-        let mut total_income: f64 = 0.0;
-        let mut total_expense: f64 = 0.0;
+        let mut total_income = Money::ZERO;
+        let mut total_expense = Money::ZERO;
 
-        let mut biggest_earning = (0.0, String::new(), String::new());
-        let mut biggest_expense = (0.0, String::new(), String::new());
+        let mut biggest_earning = (Money::ZERO, String::new(), String::new());
+        let mut biggest_expense = (Money::ZERO, String::new(), String::new());
 
-        let mut monthly_earning = 0.0;
-        let mut monthly_expense = 0.0;
+        let mut monthly_earning = Money::ZERO;
+        let mut monthly_expense = Money::ZERO;
 
         for tx in txs {
             let tx_date = &tx[0];
             let tx_method = &tx[2];
-            let tx_amount: f64 = tx[3].parse().unwrap();
+            let tx_amount = Money::parse(&tx[3]).unwrap();
             let tx_type = &tx[4];
 
             match tx_type.as_str() {
@@ -66,6 +136,7 @@ impl SummaryData {
                     monthly_earning += tx_amount;
 
                     *method_earning.get_mut(tx_method).unwrap() += tx_amount;
+                    *method_count.get_mut(tx_method).unwrap() += 1;
                 }
                 "Expense" => {
                     if tx_amount > biggest_expense.0 {
@@ -75,6 +146,31 @@ impl SummaryData {
                     monthly_expense += tx_amount;
 
                     *method_expense.get_mut(tx_method).unwrap() += tx_amount;
+                    *method_count.get_mut(tx_method).unwrap() += 1;
+                }
+                // a refund takes back a part of an expense instead of being an income
+                "Refund" => {
+                    total_expense -= tx_amount;
+                    monthly_expense -= tx_amount;
+
+                    *method_expense.get_mut(tx_method).unwrap() -= tx_amount;
+                    *method_count.get_mut(tx_method).unwrap() += 1;
+                }
+                "Transfer" => {
+                    if let Some((from_method, to_method)) =
+                        self.split_transfer_method(tx_method, method_transfers)
+                    {
+                        method_transfers.get_mut(&to_method).unwrap().0 += tx_amount;
+                        method_transfers.get_mut(&from_method).unwrap().1 += tx_amount;
+                        *method_count.get_mut(&to_method).unwrap() += 1;
+                        *method_count.get_mut(&from_method).unwrap() += 1;
+
+                        // transfers are only counted as income and expense of the methods when enabled
+                        if include_transfers {
+                            *method_earning.get_mut(&to_method).unwrap() += tx_amount;
+                            *method_expense.get_mut(&from_method).unwrap() += tx_amount;
+                        }
+                    }
                 }
                 _ => {}
             }
@@ -91,282 +187,585 @@ impl SummaryData {
     }
 
     /// Returns a vector that will be used to creating table in the Summary UI
-    /// The vector contains tags and their income and expense data
+    /// The vector contains tags and their income and expense data.
+    /// The custom range is only used by the Range mode. Only the transactions of the
+    /// tx method are used if one is given
     pub fn get_table_data(
         &self,
         mode: &IndexedData,
         month: usize,
         year: usize,
+        custom_range: Option<(NaiveDate, NaiveDate)>,
+        tx_method: Option<&str>,
+    ) -> Vec<Vec<String>> {
+        self.get_grouped_table_data(mode, month, year, custom_range, tx_method, &HashMap::new())
+    }
+
+    /// Returns the same vector as `get_table_data` however tags that are assigned to a group
+    /// are rolled up into that group. Tags without a group are shown individually.
+    /// Excluded tags are always shown individually so they can be told apart
+    pub fn get_grouped_table_data(
+        &self,
+        mode: &IndexedData,
+        month: usize,
+        year: usize,
+        custom_range: Option<(NaiveDate, NaiveDate)>,
+        tx_method: Option<&str>,
+        tag_groups: &HashMap<String, String>,
     ) -> Vec<Vec<String>> {
         let mut income_tags = HashMap::new();
         let mut expense_tags = HashMap::new();
-        match mode.index {
-            // 0 = monthly mode. Select the data only of the given month year
-            0 => {
-                let target_id = month as i32 + (year as i32 * 12);
-
-                for tx_data in self.all_txs[&target_id].iter() {
-                    let tx_amount: f64 = tx_data[3].parse().unwrap();
-                    let tx_type = &tx_data[4];
-                    let tx_tags = tx_data[5].split(", ").collect::<Vec<&str>>();
-
-                    // gather data by loop through each tx. If tag exists, add with the value, if not insert it
-                    match tx_type.as_str() {
-                        "Income" => {
-                            for tag in tx_tags {
-                                if income_tags.contains_key(tag) {
-                                    *income_tags.get_mut(tag).unwrap() += tx_amount;
-                                } else {
-                                    income_tags.insert(tag, tx_amount);
-                                }
-                            }
+        // {Tag, (Number of transactions, Biggest transaction amount)}
+        let mut tag_txs = HashMap::new();
+
+        for (.., txs) in self.get_period_txs(mode, month, year, custom_range, tx_method) {
+            for tx_data in txs {
+                let tx_amount = Money::parse(&tx_data[3]).unwrap();
+                let tx_type = &tx_data[4];
+
+                let tx_tags = self.get_tx_tags(tx_data, tag_groups);
+
+                // gather data by loop through each tx. If tag exists, add with the value, if not insert it
+                match tx_type.as_str() {
+                    "Income" => {
+                        for tag in &tx_tags {
+                            *income_tags.entry(*tag).or_default() += tx_amount;
                         }
-                        "Expense" => {
-                            for tag in tx_tags {
-                                if expense_tags.contains_key(tag) {
-                                    *expense_tags.get_mut(tag).unwrap() += tx_amount;
-                                } else {
-                                    expense_tags.insert(tag, tx_amount);
-                                }
-                            }
+                    }
+                    "Expense" => {
+                        for tag in &tx_tags {
+                            *expense_tags.entry(*tag).or_default() += tx_amount;
                         }
-                        _ => {}
                     }
-                }
-            }
-            // 1 = yearly mode. Select the data of all months of the given year
-            1 => {
-                for i in 0..MONTHS.len() {
-                    let target_id = i as i32 + (year as i32 * 12);
-
-                    for tx_data in self.all_txs[&target_id].iter() {
-                        let tx_amount: f64 = tx_data[3].parse().unwrap();
-                        let tx_type = &tx_data[4];
-                        let tx_tags = tx_data[5].split(", ").collect::<Vec<&str>>();
-
-                        // gather data by loop through each tx. If tag exists, add with the value, if not insert it
-                        match tx_type.as_str() {
-                            "Income" => {
-                                for tag in tx_tags {
-                                    if income_tags.contains_key(tag) {
-                                        *income_tags.get_mut(tag).unwrap() += tx_amount;
-                                    } else {
-                                        income_tags.insert(tag, tx_amount);
-                                    }
-                                }
-                            }
-                            "Expense" => {
-                                for tag in tx_tags {
-                                    if expense_tags.contains_key(tag) {
-                                        *expense_tags.get_mut(tag).unwrap() += tx_amount;
-                                    } else {
-                                        expense_tags.insert(tag, tx_amount);
-                                    }
-                                }
-                            }
-                            _ => {}
+                    // refunds reduce the expense of the tags without being counted as their transactions
+                    "Refund" => {
+                        for tag in &tx_tags {
+                            *expense_tags.entry(*tag).or_default() -= tx_amount;
                         }
+                        continue;
                     }
+                    _ => continue,
                 }
-            }
-            //  * 2 = all time mode. Select every single data
-            2 => {
-                for x in 0..YEARS.len() {
-                    for i in 0..MONTHS.len() {
-                        let target_id = i as i32 + (x as i32 * 12);
-
-                        for tx_data in self.all_txs[&target_id].iter() {
-                            let tx_amount: f64 = tx_data[3].parse().unwrap();
-                            let tx_type = &tx_data[4];
-                            let tx_tags = tx_data[5].split(", ").collect::<Vec<&str>>();
-
-                            // gather data by loop through each tx. If tag exists, add with the value, if not insert it
-                            match tx_type.as_str() {
-                                "Income" => {
-                                    for tag in tx_tags {
-                                        if income_tags.contains_key(tag) {
-                                            *income_tags.get_mut(tag).unwrap() += tx_amount;
-                                        } else {
-                                            income_tags.insert(tag, tx_amount);
-                                        }
-                                    }
-                                }
-                                "Expense" => {
-                                    for tag in tx_tags {
-                                        if expense_tags.contains_key(tag) {
-                                            *expense_tags.get_mut(tag).unwrap() += tx_amount;
-                                        } else {
-                                            expense_tags.insert(tag, tx_amount);
-                                        }
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
+
+                for tag in tx_tags {
+                    let (count, biggest) = tag_txs.entry(tag).or_insert((0, Money::ZERO));
+                    *count += 1;
+                    if tx_amount > *biggest {
+                        *biggest = tx_amount;
                     }
                 }
             }
-            _ => {}
         }
-        let mut table_data = self.generate_table_data(income_tags, expense_tags);
+
+        let mut table_data = self.generate_table_data(income_tags, expense_tags, tag_txs);
+        table_data.sort();
+        table_data
+    }
+
+    /// Returns the income or expense transaction with the highest amount of the tag or the tag group
+    /// within the same period and tx method as the table data
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_tag_biggest_tx(
+        &self,
+        mode: &IndexedData,
+        month: usize,
+        year: usize,
+        custom_range: Option<(NaiveDate, NaiveDate)>,
+        tx_method: Option<&str>,
+        tag_groups: &HashMap<String, String>,
+        tag: &str,
+    ) -> Option<Vec<String>> {
+        let mut biggest_tx: Option<(Money, &Vec<String>)> = None;
+
+        for (.., txs) in self.get_period_txs(mode, month, year, custom_range, tx_method) {
+            for tx_data in txs {
+                if tx_data[4] != "Income" && tx_data[4] != "Expense" {
+                    continue;
+                }
+
+                if !self.get_tx_tags(tx_data, tag_groups).contains(&tag) {
+                    continue;
+                }
+
+                let tx_amount = Money::parse(&tx_data[3]).unwrap();
+                if biggest_tx.map_or(true, |(amount, _)| tx_amount > amount) {
+                    biggest_tx = Some((tx_amount, tx_data));
+                }
+            }
+        }
+
+        biggest_tx.map(|(_, tx_data)| tx_data.to_owned())
+    }
+
+    /// Returns the tags of the transaction with the grouped ones replaced by their group names.
+    /// If multiple tags of a tx are in the same group, the group is only returned once.
+    /// Excluded tags are never replaced
+    fn get_tx_tags<'a>(
+        &'a self,
+        tx_data: &'a [String],
+        tag_groups: &'a HashMap<String, String>,
+    ) -> Vec<&'a str> {
+        let mut tx_tags: Vec<&str> = Vec::new();
+        for tag in tx_data[5].split(", ") {
+            let tag = match tag_groups.get(tag) {
+                Some(group_name) if !self.excluded_tags.contains(tag) => group_name.as_str(),
+                _ => tag,
+            };
+            if !tx_tags.contains(&tag) {
+                tx_tags.push(tag);
+            }
+        }
+        tx_tags
+    }
+
+    /// Returns the tag table data of the Summary page. The comparison is only used by the
+    /// monthly mode. Tags are rolled up into their groups if grouped
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_page_table_data(
+        &self,
+        mode: &IndexedData,
+        month: usize,
+        year: usize,
+        custom_range: Option<(NaiveDate, NaiveDate)>,
+        tx_method: Option<&str>,
+        tag_groups: &HashMap<String, String>,
+        compare: bool,
+    ) -> Vec<Vec<String>> {
+        if compare && mode.index == 0 {
+            self.get_comparison_table_data(month, year, tx_method, tag_groups)
+        } else {
+            self.get_grouped_table_data(mode, month, year, custom_range, tx_method, tag_groups)
+        }
+    }
+
+    /// Returns the method table data of the Summary page. The comparison is only used by the
+    /// monthly mode
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_page_method_data(
+        &self,
+        mode: &IndexedData,
+        month: usize,
+        year: usize,
+        custom_range: Option<(NaiveDate, NaiveDate)>,
+        tx_method: Option<&str>,
+        compare: bool,
+        conn: &Connection,
+    ) -> Vec<Vec<String>> {
+        if compare && mode.index == 0 {
+            self.get_comparison_method_data(month, year, tx_method, conn)
+        } else {
+            let (.., method_data) =
+                self.get_tx_data(mode, month, year, custom_range, tx_method, conn);
+            method_data
+        }
+    }
+
+    /// Returns the expense of every tag in the given month next to the expense of the month
+    /// before it with the change between them. Tags of only one of the months get zero on the
+    /// other side. The change % is `-` if the previous month had no expense
+    ///
+    /// example: `[["Food", "120.00", "100.00", "20.00", "20.00"]]`
+    pub fn get_comparison_table_data(
+        &self,
+        month: usize,
+        year: usize,
+        tx_method: Option<&str>,
+        tag_groups: &HashMap<String, String>,
+    ) -> Vec<Vec<String>> {
+        let monthly_mode = IndexedData::new_summary_modes();
+
+        let current_data =
+            self.get_grouped_table_data(&monthly_mode, month, year, None, tx_method, tag_groups);
+
+        let previous_data = match self.get_previous_month(month, year) {
+            Some((month, year)) => {
+                self.get_grouped_table_data(&monthly_mode, month, year, None, tx_method, tag_groups)
+            }
+            None => Vec::new(),
+        };
+
+        // {Tag, (Expense of the month, Expense of the previous month)}
+        let mut tag_expense: HashMap<String, (Money, Money)> = HashMap::new();
+
+        for row in current_data {
+            tag_expense.entry(row[0].to_string()).or_default().0 = Money::parse(&row[2]).unwrap();
+        }
+
+        for row in previous_data {
+            tag_expense.entry(row[0].to_string()).or_default().1 = Money::parse(&row[2]).unwrap();
+        }
+
+        let mut table_data = tag_expense
+            .into_iter()
+            .map(|(tag, (current, previous))| {
+                let (change, change_percentage) = self.get_change(current, previous);
+                vec![
+                    tag,
+                    format!("{:.2}", current),
+                    format!("{:.2}", previous),
+                    change,
+                    change_percentage,
+                ]
+            })
+            .collect::<Vec<Vec<String>>>();
+
         table_data.sort();
         table_data
     }
 
+    /// Returns the balance of every tx method before the first day and at the end of the final day
+    /// of the selected period. All time ends with the current balance. None if the Range mode
+    /// has no usable dates
+    fn get_period_balances(
+        &self,
+        mode: &IndexedData,
+        month: usize,
+        year: usize,
+        custom_range: Option<(NaiveDate, NaiveDate)>,
+        all_methods: &[String],
+        conn: &Connection,
+    ) -> Option<(HashMap<String, Money>, HashMap<String, Money>)> {
+        let year_num = self.date_range.year_at(year);
+
+        let (start_date, end_date) = match mode.index {
+            0 => {
+                let start_date = NaiveDate::from_ymd_opt(year_num, month as u32 + 1, 1).unwrap();
+                let end_date = if month == 11 {
+                    NaiveDate::from_ymd_opt(year_num + 1, 1, 1).unwrap()
+                } else {
+                    NaiveDate::from_ymd_opt(year_num, month as u32 + 2, 1).unwrap()
+                }
+                .pred_opt()
+                .unwrap();
+                (start_date, Some(end_date))
+            }
+            1 => (
+                NaiveDate::from_ymd_opt(year_num, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(year_num, 12, 31),
+            ),
+            RANGE_MODE => {
+                let (start_date, end_date) = custom_range?;
+                (start_date, Some(end_date))
+            }
+            _ => (
+                NaiveDate::from_ymd_opt(self.date_range.start_year, 1, 1).unwrap(),
+                None,
+            ),
+        };
+
+        let start_balance = get_money_on_date(&start_date.pred_opt()?.to_string(), conn);
+
+        let end_balance = match end_date {
+            Some(end_date) => get_money_on_date(&end_date.to_string(), conn),
+            None => all_methods
+                .iter()
+                .cloned()
+                .zip(
+                    get_last_balances(conn)
+                        .iter()
+                        .map(|balance| Money::parse(balance).unwrap_or_default()),
+                )
+                .collect(),
+        };
+
+        Some((start_balance, end_balance))
+    }
+
+    /// Returns the income and expense of every tx method in the given month next to the ones of the
+    /// month before it with the change between them
+    ///
+    /// example: `[["Cash", "50.00", "0.00", "50.00", "120.00", "100.00", "20.00"]]`
+    pub fn get_comparison_method_data(
+        &self,
+        month: usize,
+        year: usize,
+        tx_method: Option<&str>,
+        conn: &Connection,
+    ) -> Vec<Vec<String>> {
+        let monthly_mode = IndexedData::new_summary_modes();
+
+        let (.., current_data) =
+            self.get_tx_data(&monthly_mode, month, year, None, tx_method, conn);
+
+        let previous_data = match self.get_previous_month(month, year) {
+            Some((month, year)) => {
+                let (.., method_data) =
+                    self.get_tx_data(&monthly_mode, month, year, None, tx_method, conn);
+                method_data
+            }
+            None => Vec::new(),
+        };
+
+        current_data
+            .into_iter()
+            .map(|row| {
+                let previous_row = previous_data.iter().find(|previous| previous[0] == row[0]);
+
+                let amount_of = |row: Option<&Vec<String>>, index: usize| {
+                    row.map_or(Money::ZERO, |row| Money::parse(&row[index]).unwrap())
+                };
+
+                let income = amount_of(Some(&row), 1);
+                let previous_income = amount_of(previous_row, 1);
+                let expense = amount_of(Some(&row), 2);
+                let previous_expense = amount_of(previous_row, 2);
+
+                vec![
+                    row[0].to_string(),
+                    format!("{:.2}", income),
+                    format!("{:.2}", previous_income),
+                    format!("{:.2}", income - previous_income),
+                    format!("{:.2}", expense),
+                    format!("{:.2}", previous_expense),
+                    format!("{:.2}", expense - previous_expense),
+                ]
+            })
+            .collect()
+    }
+
+    /// Returns the month and the year index of the month before the given one.
+    /// None if the given month is the first month of the date range
+    fn get_previous_month(&self, month: usize, year: usize) -> Option<(usize, usize)> {
+        match (month, year) {
+            (0, 0) => None,
+            (0, year) => Some((MONTHS.len() - 1, year - 1)),
+            (month, year) => Some((month - 1, year)),
+        }
+    }
+
+    /// Returns the change from the previous amount to the current one and the change in %.
+    /// The % is `-` if the previous amount is zero
+    fn get_change(&self, current: Money, previous: Money) -> (String, String) {
+        let change = current - previous;
+
+        let change_percentage = if previous == Money::ZERO {
+            String::from("-")
+        } else {
+            format!("{:.2}", (change.to_f64() / previous.to_f64()) * 100.0)
+        };
+
+        (format!("{:.2}", change), change_percentage)
+    }
+
     /// Returns a vector that will be used to highlight points such as largest transaction,
-    /// biggest income etc
+    /// biggest income etc. The method table only has the row of the tx method if one is given
     pub fn get_tx_data(
         &self,
         mode: &IndexedData,
         month: usize,
         year: usize,
+        custom_range: Option<(NaiveDate, NaiveDate)>,
+        tx_method: Option<&str>,
         conn: &Connection,
-    ) -> (MyVec, MyVec, MyVec, MyVec, MyVec) {
+    ) -> TxDataTuple {
+        self.get_tx_data_with_clock(
+            &SystemClock,
+            mode,
+            month,
+            year,
+            custom_range,
+            tx_method,
+            conn,
+        )
+    }
+
+    /// Same as `get_tx_data` but the elapsed days and months are counted until the clock's date.
+    /// The result is cached so drawing the page again with the same selection does not go
+    /// through the transactions again
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_tx_data_with_clock(
+        &self,
+        clock: &impl Clock,
+        mode: &IndexedData,
+        month: usize,
+        year: usize,
+        custom_range: Option<(NaiveDate, NaiveDate)>,
+        tx_method: Option<&str>,
+        conn: &Connection,
+    ) -> TxDataTuple {
+        let key = TxDataKey {
+            mode: mode.index,
+            month,
+            year,
+            custom_range,
+            tx_method: tx_method.map(str::to_string),
+            include_transfers: get_bool_setting("summary_include_transfers", conn),
+            tx_methods: get_all_tx_methods(conn),
+            today: clock.today(),
+        };
+
+        if let Some(tx_data) = self.tx_data_cache.borrow().get(&key) {
+            return tx_data.clone();
+        }
+
+        let tx_data =
+            self.aggregate_tx_data(clock, mode, month, year, custom_range, tx_method, conn);
+        self.aggregations.set(self.aggregations.get() + 1);
+        self.tx_data_cache.borrow_mut().insert(key, tx_data.clone());
+
+        tx_data
+    }
+
+    /// Returns how many times `get_tx_data` went through the transactions instead of using the cache
+    pub fn get_aggregation_count(&self) -> usize {
+        self.aggregations.get()
+    }
+
+    /// Goes through the transactions of the selection to create the data of `get_tx_data`
+    #[allow(clippy::too_many_arguments)]
+    fn aggregate_tx_data(
+        &self,
+        clock: &impl Clock,
+        mode: &IndexedData,
+        month: usize,
+        year: usize,
+        custom_range: Option<(NaiveDate, NaiveDate)>,
+        tx_method: Option<&str>,
+        conn: &Connection,
+    ) -> TxDataTuple {
         let all_methods = get_all_tx_methods(conn);
-        let mut total_income: f64 = 0.0;
-        let mut total_expense: f64 = 0.0;
+        let mut total_income = Money::ZERO;
+        let mut total_expense = Money::ZERO;
 
         // (Amount, Method, date)
-        let mut biggest_earning = (0.0, String::from("-"), String::from("-"));
-        let mut biggest_expense = (0.0, String::from("-"), String::from("-"));
+        let mut biggest_earning = (Money::ZERO, String::from("-"), String::from("-"));
+        let mut biggest_expense = (Money::ZERO, String::from("-"), String::from("-"));
 
-        let mut largest_monthly_earning = 0.0;
-        let mut largest_monthly_expense = 0.0;
+        let mut largest_monthly_earning = Money::ZERO;
+        let mut largest_monthly_expense = Money::ZERO;
 
         // (Amount, Method, date)
-        let mut peak_earning = (0.0, String::from("-"));
-        let mut peak_expense = (0.0, String::from("-"));
+        let mut peak_earning = (Money::ZERO, String::from("-"));
+        let mut peak_expense = (Money::ZERO, String::from("-"));
         let mut total_month_checked = 0.0;
 
         // {Method Name, Amount}
         let mut method_earning = HashMap::new();
         let mut method_expense = HashMap::new();
+        // {Method Name, (Transferred in, Transferred out)}
+        let mut method_transfers = HashMap::new();
+        // {Method Name, Number of transactions}
+        let mut method_count = HashMap::new();
 
         for method in &all_methods {
-            method_earning.insert(method.to_string(), 0.0);
-            method_expense.insert(method.to_string(), 0.0);
+            method_earning.insert(method.to_string(), Money::ZERO);
+            method_expense.insert(method.to_string(), Money::ZERO);
+            method_transfers.insert(method.to_string(), (Money::ZERO, Money::ZERO));
+            method_count.insert(method.to_string(), 0);
         }
 
-        match mode.index {
-            0 => {
-                let target_id = month as i32 + (year as i32 * 12);
-                let tx_data = &self.all_txs[&target_id];
-                if !tx_data.is_empty() {
-                    total_month_checked += 1.0;
-                }
+        let include_transfers = get_bool_setting("summary_include_transfers", conn);
 
-                self.update_tx_data(
-                    tx_data,
-                    &mut total_income,
-                    &mut total_expense,
-                    &mut biggest_earning,
-                    &mut biggest_expense,
-                    &mut largest_monthly_earning,
-                    &mut largest_monthly_expense,
-                    &mut peak_earning,
-                    &mut peak_expense,
-                    &mut method_earning,
-                    &mut method_expense,
-                    month,
-                    year,
-                )
-            }
-            1 => {
-                for i in 0..MONTHS.len() {
-                    let target_id = i as i32 + (year as i32 * 12);
-                    let tx_data = &self.all_txs[&target_id];
-                    if !tx_data.is_empty() {
-                        total_month_checked += 1.0;
-                    }
+        for (month, year, tx_data) in
+            self.get_period_txs(mode, month, year, custom_range, tx_method)
+        {
+            let tx_data = tx_data
+                .into_iter()
+                .filter(|tx| !self.is_excluded_tx(tx))
+                .collect::<Vec<&Vec<String>>>();
 
-                    self.update_tx_data(
-                        tx_data,
-                        &mut total_income,
-                        &mut total_expense,
-                        &mut biggest_earning,
-                        &mut biggest_expense,
-                        &mut largest_monthly_earning,
-                        &mut largest_monthly_expense,
-                        &mut peak_earning,
-                        &mut peak_expense,
-                        &mut method_earning,
-                        &mut method_expense,
-                        i,
-                        year,
-                    )
-                }
+            if !tx_data.is_empty() {
+                total_month_checked += 1.0;
             }
-            2 => {
-                for x in 0..YEARS.len() {
-                    for i in 0..MONTHS.len() {
-                        let target_id = i as i32 + (x as i32 * 12);
-                        let tx_data = &self.all_txs[&target_id];
-                        if !tx_data.is_empty() {
-                            total_month_checked += 1.0;
-                        }
 
-                        self.update_tx_data(
-                            tx_data,
-                            &mut total_income,
-                            &mut total_expense,
-                            &mut biggest_earning,
-                            &mut biggest_expense,
-                            &mut largest_monthly_earning,
-                            &mut largest_monthly_expense,
-                            &mut peak_earning,
-                            &mut peak_expense,
-                            &mut method_earning,
-                            &mut method_expense,
-                            i,
-                            x,
-                        )
-                    }
-                }
-            }
-            _ => {}
+            self.update_tx_data(
+                &tx_data,
+                &mut total_income,
+                &mut total_expense,
+                &mut biggest_earning,
+                &mut biggest_expense,
+                &mut largest_monthly_earning,
+                &mut largest_monthly_expense,
+                &mut peak_earning,
+                &mut peak_expense,
+                &mut method_earning,
+                &mut method_expense,
+                &mut method_transfers,
+                &mut method_count,
+                include_transfers,
+                month,
+                year,
+            )
         }
 
         let (income_percentage, expense_percentage) =
-            self.get_percentages(total_income, total_expense);
+            self.get_percentages(total_income.to_f64(), total_expense.to_f64());
 
-        let average_income = if total_income != 0.0 {
-            total_income / total_month_checked
+        let average_income = if total_income != Money::ZERO {
+            total_income.to_f64() / total_month_checked
         } else {
             0.0
         };
 
-        let average_expense = if total_income != 0.0 {
-            total_expense / total_month_checked
+        let average_expense = if total_income != Money::ZERO {
+            total_expense.to_f64() / total_month_checked
         } else {
             0.0
         };
 
+        let period_balances =
+            self.get_period_balances(mode, month, year, custom_range, &all_methods, conn);
+
         let mut method_data = Vec::new();
 
-        for method in all_methods.iter() {
-            let earning_percentage = if method_earning[method] != 0.0 {
-                format!("{:.2}%", (method_earning[method] / total_income) * 100.0)
-            } else {
-                format!("{:.2}", 0.0)
-            };
+        let shown_methods = all_methods
+            .iter()
+            .filter(|method| tx_method.map_or(true, |tx_method| tx_method == method.as_str()));
+
+        for method in shown_methods {
+            // transfers can give a method income or expense even when the totals are zero
+            let earning_percentage =
+                if method_earning[method] != Money::ZERO && total_income != Money::ZERO {
+                    let earning = method_earning[method].to_f64();
+                    format!("{:.2}%", (earning / total_income.to_f64()) * 100.0)
+                } else {
+                    format!("{:.2}", 0.0)
+                };
 
-            let expense_percentage = if method_expense[method] != 0.0 {
-                format!("{:.2}%", (method_expense[method] / total_expense) * 100.0)
+            let expense_percentage =
+                if method_expense[method] != Money::ZERO && total_expense != Money::ZERO {
+                    let expense = method_expense[method].to_f64();
+                    format!("{:.2}%", (expense / total_expense.to_f64()) * 100.0)
+                } else {
+                    format!("{:.2}", 0.0)
+                };
+
+            let average_earning = if method_earning[method] != Money::ZERO {
+                format!(
+                    "{:.2}",
+                    method_earning[method].to_f64() / total_month_checked
+                )
             } else {
                 format!("{:.2}", 0.0)
             };
 
-            let average_earning = if method_earning[method] != 0.0 {
-                format!("{:.2}", method_earning[method] / total_month_checked)
+            let average_expense = if method_expense[method] != Money::ZERO {
+                format!(
+                    "{:.2}",
+                    method_expense[method].to_f64() / total_month_checked
+                )
             } else {
                 format!("{:.2}", 0.0)
             };
+            // transfers counted as income and expense are taken out so they add nothing to the net
+            let mut net = method_earning[method] - method_expense[method];
+            if include_transfers {
+                net -= method_transfers[method].0 - method_transfers[method].1;
+            }
 
-            let average_expense = if method_expense[method] != 0.0 {
-                format!("{:.2}", method_expense[method] / total_month_checked)
-            } else {
-                format!("{:.2}", 0.0)
+            let (start_balance, end_balance, balance_change) = match &period_balances {
+                Some((start_balances, end_balances)) => {
+                    let start_balance = start_balances.get(method).copied().unwrap_or_default();
+                    let end_balance = end_balances.get(method).copied().unwrap_or_default();
+                    (
+                        format!("{:.2}", start_balance),
+                        format!("{:.2}", end_balance),
+                        format!("{:.2}", end_balance - start_balance),
+                    )
+                }
+                None => (String::from("-"), String::from("-"), String::from("-")),
             };
+
             method_data.push(vec![
                 method.to_string(),
                 format!("{:.2}", method_earning[method]),
@@ -375,6 +774,13 @@ impl SummaryData {
                 expense_percentage,
                 average_earning,
                 average_expense,
+                format!("{:.2}", method_transfers[method].0),
+                format!("{:.2}", method_transfers[method].1),
+                format!("{:.2}", net),
+                method_count[method].to_string(),
+                start_balance,
+                end_balance,
+                balance_change,
             ])
         }
 
@@ -396,7 +802,7 @@ impl SummaryData {
             ],
         ];
 
-        let summary_data_2 = vec![
+        let mut summary_data_2 = vec![
             vec![
                 String::from("Average Income"),
                 format!("{:.2}", average_income),
@@ -409,6 +815,16 @@ impl SummaryData {
             ],
         ];
 
+        summary_data_2.extend(self.get_time_averages(
+            clock,
+            mode,
+            month,
+            year,
+            custom_range,
+            total_income,
+            total_expense,
+        ));
+
         let summary_data_3 = vec![
             vec![
                 String::from("Largest Income"),
@@ -454,20 +870,202 @@ impl SummaryData {
         )
     }
 
+    /// Returns the income and expense averaged over the time the mode covers. The Monthly mode
+    /// uses daily averages with the current month only counting the days until today and
+    /// projects the month-end expense from it. The Range mode uses daily averages and the
+    /// rest use monthly averages with the current year only counting the months until today
+    #[allow(clippy::too_many_arguments)]
+    fn get_time_averages(
+        &self,
+        clock: &impl Clock,
+        mode: &IndexedData,
+        month: usize,
+        year: usize,
+        custom_range: Option<(NaiveDate, NaiveDate)>,
+        total_income: Money,
+        total_expense: Money,
+    ) -> MyVec {
+        let today = clock.today();
+
+        let average_row = |name: &str, total: Money, periods: i64| {
+            vec![
+                String::from(name),
+                format!("{:.2}", total.to_f64() / periods.max(1) as f64),
+                String::from("-"),
+            ]
+        };
+
+        match mode.index {
+            0 => {
+                let year_num = self.date_range.year_at(year);
+                let start_date = NaiveDate::from_ymd_opt(year_num, month as u32 + 1, 1).unwrap();
+
+                let end_date = if month == 11 {
+                    NaiveDate::from_ymd_opt(year_num + 1, 1, 1).unwrap()
+                } else {
+                    NaiveDate::from_ymd_opt(year_num, month as u32 + 2, 1).unwrap()
+                }
+                .pred_opt()
+                .unwrap();
+
+                let days_in_month = end_date.signed_duration_since(start_date).num_days() + 1;
+                let is_current = today >= start_date && today <= end_date;
+
+                let days = if is_current {
+                    today.signed_duration_since(start_date).num_days() + 1
+                } else {
+                    days_in_month
+                };
+
+                let projected_expense = if is_current {
+                    let daily_expense = total_expense.to_f64() / days as f64;
+                    format!("{:.2}", daily_expense * days_in_month as f64)
+                } else {
+                    String::from("-")
+                };
+
+                vec![
+                    average_row("Daily Income", total_income, days),
+                    average_row("Daily Expense", total_expense, days),
+                    vec![
+                        String::from("Projected Expense"),
+                        projected_expense,
+                        String::from("-"),
+                    ],
+                ]
+            }
+            RANGE_MODE => {
+                let days = match custom_range {
+                    Some((start_date, end_date)) => {
+                        end_date.signed_duration_since(start_date).num_days() + 1
+                    }
+                    None => 1,
+                };
+
+                vec![
+                    average_row("Daily Income", total_income, days),
+                    average_row("Daily Expense", total_expense, days),
+                ]
+            }
+            _ => {
+                // the months from the first one the mode covers until the current one
+                let start_year = if mode.index == 1 {
+                    self.date_range.year_at(year)
+                } else {
+                    self.date_range.start_year
+                };
+                let end_year = if mode.index == 1 {
+                    start_year
+                } else {
+                    self.date_range.end_year
+                };
+
+                let months = if today.year() > end_year {
+                    (end_year - start_year + 1) as i64 * 12
+                } else {
+                    (today.year() - start_year) as i64 * 12 + today.month() as i64
+                };
+
+                vec![
+                    average_row("Monthly Income", total_income, months),
+                    average_row("Monthly Expense", total_expense, months),
+                ]
+            }
+        }
+    }
+
+    /// Returns the month index, the year index and the transactions of every month the mode covers.
+    /// The Range mode only returns the transactions between the start and the end date of the
+    /// custom range and nothing if there is no range. If a tx method is given, only its transactions
+    /// and the transfers it sent or received are returned
+    fn get_period_txs(
+        &self,
+        mode: &IndexedData,
+        month: usize,
+        year: usize,
+        custom_range: Option<(NaiveDate, NaiveDate)>,
+        tx_method: Option<&str>,
+    ) -> PeriodTxs {
+        let mut periods = Vec::new();
+
+        // 0 = monthly mode. Select the data only of the given month year
+        // 1 = yearly mode. Select the data of all months of the given year
+        // 2 = all time mode. Select every single data
+        // 3 = range mode. Select the data between the start and the end date
+        let (years, months) = match mode.index {
+            0 => (year..year + 1, month..month + 1),
+            1 => (year..year + 1, 0..MONTHS.len()),
+            2 | RANGE_MODE => (0..self.date_range.total_years(), 0..MONTHS.len()),
+            _ => return periods,
+        };
+
+        let custom_range = if mode.index == RANGE_MODE {
+            match custom_range {
+                Some(range) => Some(range),
+                None => return periods,
+            }
+        } else {
+            None
+        };
+
+        for x in years {
+            for i in months.clone() {
+                let target_id = i as i32 + (x as i32 * 12);
+                let all_txs = self.all_txs[&target_id].iter();
+
+                let txs: Vec<&Vec<String>> = match custom_range {
+                    Some((start_date, end_date)) => {
+                        let month_id = self.date_range.year_at(x) * 12 + i as i32;
+                        let start_id = start_date.year() * 12 + start_date.month0() as i32;
+                        let end_id = end_date.year() * 12 + end_date.month0() as i32;
+
+                        if month_id < start_id || month_id > end_id {
+                            continue;
+                        }
+
+                        all_txs
+                            .filter(|tx| {
+                                NaiveDate::parse_from_str(&tx[0], "%d-%m-%Y")
+                                    .map_or(false, |date| date >= start_date && date <= end_date)
+                            })
+                            .collect()
+                    }
+                    None => all_txs.collect(),
+                };
+
+                let txs = match tx_method {
+                    Some(tx_method) => txs
+                        .into_iter()
+                        .filter(|tx| self.is_tx_of_method(tx, tx_method))
+                        .collect(),
+                    None => txs,
+                };
+
+                periods.push((i, x, txs));
+            }
+        }
+
+        periods
+    }
+
     /// Updates values based on the gathered data
+    #[allow(clippy::too_many_arguments)]
     fn update_tx_data(
         &self,
-        tx_data: &Vec<Vec<String>>,
-        total_income: &mut f64,
-        total_expense: &mut f64,
-        biggest_earning: &mut (f64, String, String),
-        biggest_expense: &mut (f64, String, String),
-        largest_monthly_earning: &mut f64,
-        largest_monthly_expense: &mut f64,
-        peak_earning: &mut (f64, String),
-        peak_expense: &mut (f64, String),
-        method_earning: &mut HashMap<String, f64>,
-        method_expense: &mut HashMap<String, f64>,
+        tx_data: &[&Vec<String>],
+        total_income: &mut Money,
+        total_expense: &mut Money,
+        biggest_earning: &mut (Money, String, String),
+        biggest_expense: &mut (Money, String, String),
+        largest_monthly_earning: &mut Money,
+        largest_monthly_expense: &mut Money,
+        peak_earning: &mut (Money, String),
+        peak_expense: &mut (Money, String),
+        method_earning: &mut HashMap<String, Money>,
+        method_expense: &mut HashMap<String, Money>,
+        method_transfers: &mut HashMap<String, (Money, Money)>,
+        method_count: &mut HashMap<String, usize>,
+        include_transfers: bool,
         month: usize,
         year: usize,
     ) {
@@ -479,7 +1077,14 @@ impl SummaryData {
             current_biggest_expense,
             current_monthly_earning,
             current_monthly_expense,
-        ) = self.get_data(tx_data, method_earning, method_expense);
+        ) = self.get_data(
+            tx_data,
+            method_earning,
+            method_expense,
+            method_transfers,
+            method_count,
+            include_transfers,
+        );
 
         *total_income += current_total_income;
         *total_expense += current_total_expense;
@@ -496,7 +1101,7 @@ impl SummaryData {
             *largest_monthly_earning = current_monthly_earning;
             *peak_earning = (
                 *largest_monthly_earning,
-                format!("{}-{}", month + 1, YEARS[year]),
+                format!("{}-{}", month + 1, self.date_range.year_at(year)),
             );
         }
 
@@ -504,29 +1109,36 @@ impl SummaryData {
             *largest_monthly_expense = current_monthly_expense;
             *peak_expense = (
                 *largest_monthly_expense,
-                format!("{}-{}", month + 1, YEARS[year]),
+                format!("{}-{}", month + 1, self.date_range.year_at(year)),
             );
         }
     }
 
-    /// Generates a vector to be used as table data from tag list
+    /// Generates a vector to be used as table data from tag list.
+    /// Excluded tags are not a part of the percentages and get `-` instead
     fn generate_table_data(
         &self,
-        income_tags: HashMap<&str, f64>,
-        expense_tags: HashMap<&str, f64>,
+        income_tags: HashMap<&str, Money>,
+        expense_tags: HashMap<&str, Money>,
+        tag_txs: HashMap<&str, (usize, Money)>,
     ) -> Vec<Vec<String>> {
         let mut to_return = Vec::new();
-        let mut total_income = 0.0;
-        let mut total_expense = 0.0;
+        let mut total_income = Money::ZERO;
+        let mut total_expense = Money::ZERO;
 
         for (key, value) in income_tags.iter() {
+            let excluded = self.excluded_tags.contains(*key);
             let mut to_push = vec![key.to_string(), format!("{:.2}", value)];
-            total_income += value;
+            if !excluded {
+                total_income += *value;
+            }
 
             // if the same tag already exists on expense, get that value as well
             if expense_tags.contains_key(key) {
                 to_push.push(format!("{:.2}", expense_tags[key]));
-                total_expense += expense_tags[key];
+                if !excluded {
+                    total_expense += expense_tags[key];
+                }
             } else {
                 to_push.push(format!("{:.2}", 0.0))
             }
@@ -541,33 +1153,66 @@ impl SummaryData {
                     format!("{:.2}", 0.0),
                     format!("{:.2}", value),
                 ]);
-                total_expense += value;
+                if !self.excluded_tags.contains(*key) {
+                    total_expense += *value;
+                }
             }
         }
         // we got the income and expense data earlier. Now need to loop again
         // to gather the % data
         for x in to_return.iter_mut() {
-            let income_percentage = if &x[1] != "0.00" {
-                let income = &x[1].parse::<f64>().unwrap();
-                format!("{:.2}", ((income / total_income) * 100.0))
+            let excluded = self.excluded_tags.contains(&x[0]);
+
+            let income_percentage = if excluded {
+                String::from("-")
+            } else if &x[1] != "0.00" {
+                let income = Money::parse(&x[1]).unwrap().to_f64();
+                format!("{:.2}", ((income / total_income.to_f64()) * 100.0))
             } else {
                 format!("{:.2}", 0.0)
             };
 
-            let expense_percentage = if &x[2] != "0.00" {
-                let expense = &x[2].parse::<f64>().unwrap();
-                format!("{:.2}", ((expense / total_expense) * 100.0))
+            let expense_percentage = if excluded {
+                String::from("-")
+            } else if &x[2] != "0.00" {
+                let expense = Money::parse(&x[2]).unwrap().to_f64();
+                format!("{:.2}", ((expense / total_expense.to_f64()) * 100.0))
             } else {
                 format!("{:.2}", 0.0)
             };
 
+            // tags only get income and expense so transfers never change the net
+            let net = Money::parse(&x[1]).unwrap() - Money::parse(&x[2]).unwrap();
+
             x.push(income_percentage);
             x.push(expense_percentage);
+            x.push(format!("{:.2}", net));
+
+            let (count, biggest) = tag_txs[x[0].as_str()];
+            x.push(count.to_string());
+            x.push(format!("{:.2}", biggest));
         }
 
         to_return
     }
 
+    /// Splits the tx method of a transfer into the from and to method.
+    /// Tx methods can contain spaces so the known method names are used to find the split point
+    fn split_transfer_method(
+        &self,
+        tx_method: &str,
+        method_transfers: &HashMap<String, (Money, Money)>,
+    ) -> Option<(String, String)> {
+        method_transfers.keys().find_map(|from_method| {
+            let to_method = tx_method.strip_prefix(&format!("{from_method} to "))?;
+            if method_transfers.contains_key(to_method) {
+                Some((from_method.to_string(), to_method.to_string()))
+            } else {
+                None
+            }
+        })
+    }
+
     /// Takes 2 numbers and returns how much % are each of them
     fn get_percentages(&self, value1: f64, value2: f64) -> (String, String) {
         if value1 == 0.0 && value2 == 0.0 {
@@ -582,3 +1227,56 @@ impl SummaryData {
         )
     }
 }
+
+/// Returns the column names of the tag table of the Summary page. The 1st column is
+/// named after the tag groups if grouped
+pub fn get_tag_table_header(grouped: bool, comparing: bool) -> Vec<&'static str> {
+    let tag_name = if grouped { "Groups" } else { "Tags" };
+
+    if comparing {
+        vec![tag_name, "Expense (this)", "Expense (prev)", "Δ", "Δ%"]
+    } else {
+        vec![
+            tag_name,
+            "Total Income",
+            "Total Expense",
+            "Income %",
+            "Expense %",
+            "Net",
+            "Count",
+            "Biggest Tx",
+        ]
+    }
+}
+
+/// Returns the column names of the method table of the Summary page
+pub fn get_method_table_header(comparing: bool) -> Vec<&'static str> {
+    if comparing {
+        vec![
+            "Method",
+            "Income (this)",
+            "Income (prev)",
+            "Δ Income",
+            "Expense (this)",
+            "Expense (prev)",
+            "Δ Expense",
+        ]
+    } else {
+        vec![
+            "Method",
+            "Total Income",
+            "Total Expense",
+            "Income %",
+            "Expense %",
+            "Average Income",
+            "Average Expense",
+            "Transfers In",
+            "Transfers Out",
+            "Net",
+            "Count",
+            "Start Balance",
+            "End Balance",
+            "Change",
+        ]
+    }
+}
@@ -0,0 +1,227 @@
+use crate::db::get_date_range;
+use crate::outputs::{DateStepSize, StepType, VerifyingOutput};
+use crate::page_handler::IndexedData;
+use crate::utility::traits::{DataVerifier, FieldStepper};
+use crate::utility::{add_char_to, get_date_format, Clock, SystemClock};
+use chrono::{Duration, NaiveDate};
+use rusqlite::Connection;
+
+/// The index of the Range mode in the Summary and Chart page mode lists
+pub const RANGE_MODE: usize = 3;
+
+/// The number of days the range covers when the page is opened, today included
+const DEFAULT_RANGE_DAYS: i64 = 90;
+
+/// Contains the start and end date boxes of the Summary and Chart page Range mode.
+/// Both dates are inclusive
+pub struct SummaryRangeData {
+    start_date: String,
+    end_date: String,
+    start_index: usize,
+    end_index: usize,
+    status: String,
+}
+
+impl DataVerifier for SummaryRangeData {}
+
+impl FieldStepper for SummaryRangeData {}
+
+impl SummaryRangeData {
+    /// Creates an instance of the struct covering the last 90 days
+    pub fn new(conn: &Connection) -> Self {
+        Self::new_with_clock(&SystemClock, conn)
+    }
+
+    /// Creates an instance of the struct covering the 90 days until the clock's date.
+    /// The dates are moved inside the date range if needed
+    pub fn new_with_clock(clock: &impl Clock, conn: &Connection) -> Self {
+        let date_format = get_date_format(conn);
+        let today = clock.today();
+
+        let mut range_data = SummaryRangeData {
+            start_date: date_format.format_date(today - Duration::days(DEFAULT_RANGE_DAYS - 1)),
+            end_date: date_format.format_date(today),
+            start_index: 0,
+            end_index: 0,
+            status: String::new(),
+        };
+        range_data.verify_dates(conn);
+        range_data
+    }
+
+    pub fn get_start_date(&self) -> &str {
+        &self.start_date
+    }
+
+    pub fn get_end_date(&self) -> &str {
+        &self.end_date
+    }
+
+    pub fn get_start_index(&self) -> usize {
+        self.start_index
+    }
+
+    pub fn get_end_index(&self) -> usize {
+        self.end_index
+    }
+
+    pub fn get_status(&self) -> &str {
+        &self.status
+    }
+
+    /// Insert or remove from the start date box
+    pub fn edit_start_date(&mut self, to_add: Option<char>) {
+        add_char_to(to_add, &mut self.start_index, &mut self.start_date);
+    }
+
+    /// Insert or remove from the end date box
+    pub fn edit_end_date(&mut self, to_add: Option<char>) {
+        add_char_to(to_add, &mut self.end_index, &mut self.end_date);
+    }
+
+    /// Fills the boxes with the first and the last day of the month. month is 0 to 11
+    pub fn set_month(&mut self, year: i32, month: usize, conn: &Connection) {
+        let start_date = NaiveDate::from_ymd_opt(year, month as u32 + 1, 1).unwrap();
+        let end_date = if month == 11 {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap()
+        } else {
+            NaiveDate::from_ymd_opt(year, month as u32 + 2, 1).unwrap()
+        }
+        .pred_opt()
+        .unwrap();
+
+        self.set_dates(start_date, end_date, conn);
+    }
+
+    /// Fills the boxes with the first and the last day of the year
+    pub fn set_year(&mut self, year: i32, conn: &Connection) {
+        let start_date = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+        let end_date = NaiveDate::from_ymd_opt(year, 12, 31).unwrap();
+
+        self.set_dates(start_date, end_date, conn);
+    }
+
+    /// Fills the boxes with the two dates and verifies them
+    fn set_dates(&mut self, start_date: NaiveDate, end_date: NaiveDate, conn: &Connection) {
+        let date_format = get_date_format(conn);
+
+        self.start_date = date_format.format_date(start_date);
+        self.end_date = date_format.format_date(end_date);
+        self.verify_dates(conn);
+    }
+
+    /// Verifies both date boxes and keeps the status of the first one that was not accepted
+    pub fn verify_dates(&mut self, conn: &Connection) {
+        let date_range = get_date_range(conn);
+        let date_format = get_date_format(conn);
+
+        let mut start_date = self.start_date.clone();
+        let mut end_date = self.end_date.clone();
+
+        let start_status = self.verify_date(&mut start_date, &date_range, &date_format);
+        let end_status = self.verify_date(&mut end_date, &date_range, &date_format);
+
+        self.start_date = start_date;
+        self.end_date = end_date;
+        self.start_index = self.start_date.len();
+        self.end_index = self.end_date.len();
+
+        self.status.clear();
+
+        for status in [start_status, end_status] {
+            if let VerifyingOutput::NotAccepted(_) | VerifyingOutput::Nothing(_) = status {
+                self.status = status.to_string();
+                break;
+            }
+        }
+    }
+
+    /// Steps the start date box by the given size
+    pub fn step_start_date(
+        &mut self,
+        step_type: StepType,
+        step_size: DateStepSize,
+        conn: &Connection,
+    ) {
+        let mut start_date = self.start_date.clone();
+        let status = self.step_date_by(
+            &mut start_date,
+            step_type,
+            step_size,
+            &get_date_range(conn),
+            &get_date_format(conn),
+        );
+
+        self.start_date = start_date;
+        self.start_index = self.start_date.len();
+
+        match status {
+            Ok(_) => self.status.clear(),
+            Err(e) => self.status = e.to_string(),
+        }
+    }
+
+    /// Steps the end date box by the given size
+    pub fn step_end_date(
+        &mut self,
+        step_type: StepType,
+        step_size: DateStepSize,
+        conn: &Connection,
+    ) {
+        let mut end_date = self.end_date.clone();
+        let status = self.step_date_by(
+            &mut end_date,
+            step_type,
+            step_size,
+            &get_date_range(conn),
+            &get_date_format(conn),
+        );
+
+        self.end_date = end_date;
+        self.end_index = self.end_date.len();
+
+        match status {
+            Ok(_) => self.status.clear(),
+            Err(e) => self.status = e.to_string(),
+        }
+    }
+
+    /// Returns the start and end date of the boxes or the reason they can not be used
+    pub fn get_range(&self, conn: &Connection) -> Result<(NaiveDate, NaiveDate), String> {
+        if !self.status.is_empty() {
+            return Err(self.status.to_string());
+        }
+
+        let pattern = get_date_format(conn).pattern();
+
+        let start_date = match NaiveDate::parse_from_str(&self.start_date, pattern) {
+            Ok(date) => date,
+            Err(_) => return Err("Range: Start date is not a valid date".to_string()),
+        };
+
+        let end_date = match NaiveDate::parse_from_str(&self.end_date, pattern) {
+            Ok(date) => date,
+            Err(_) => return Err("Range: End date is not a valid date".to_string()),
+        };
+
+        if end_date < start_date {
+            return Err("Range: End date cannot be before the start date".to_string());
+        }
+
+        Ok((start_date, end_date))
+    }
+
+    /// Returns the range to aggregate the Summary over if the Range mode is selected
+    /// and both dates are usable
+    pub fn get_active_range(
+        &self,
+        mode: &IndexedData,
+        conn: &Connection,
+    ) -> Option<(NaiveDate, NaiveDate)> {
+        if mode.index == RANGE_MODE {
+            self.get_range(conn).ok()
+        } else {
+            None
+        }
+    }
+}
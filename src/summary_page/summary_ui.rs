@@ -1,32 +1,83 @@
 use crate::page_handler::{
-    IndexedData, SortingType, SummaryTab, TableData, BACKGROUND, BOX, HEADER, SELECTED, TEXT,
+    DisplaySettings, IndexedData, NameCache, SortOrder, SortingType, SummaryTab, TableData,
+    TypeAhead, BACKGROUND, BOX, GRAY, HEADER, RED, SELECTED, TEXT,
 };
-use crate::summary_page::SummaryData;
-use crate::utility::{create_tab, get_all_tx_methods, main_block, styled_block};
+use crate::summary_page::{
+    get_method_table_header, get_tag_table_header, SummaryData, SummaryRangeData, RANGE_MODE,
+};
+use crate::utility::{create_tab, get_table_position, main_block, styled_block};
 use ratatui::backend::Backend;
-use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Modifier, Style};
-use ratatui::widgets::{Cell, Row, Table};
+use ratatui::widgets::{Cell, Paragraph, Row, Table};
 use ratatui::Frame;
 use rusqlite::Connection;
-use thousands::Separable;
 
 /// The function draws the Summary page of the interface.
 #[cfg(not(tarpaulin_include))]
+#[allow(clippy::too_many_arguments)]
 pub fn summary_ui<B: Backend>(
     f: &mut Frame<B>,
     months: &IndexedData,
     years: &IndexedData,
     mode_selection: &IndexedData,
+    summary_methods: &IndexedData,
     summary_data: &SummaryData,
+    summary_range: &SummaryRangeData,
     table_data: &mut TableData,
     current_page: &SummaryTab,
     summary_hidden_mode: bool,
+    summary_grouped: bool,
+    summary_compare: bool,
+    summary_type_ahead: &TypeAhead,
     summary_sort: &SortingType,
+    summary_sort_order: SortOrder,
+    display_settings: &DisplaySettings,
+    names: &NameCache,
     conn: &Connection,
 ) {
-    let (summary_data_1, summary_data_2, summary_data_3, summary_data_4, method_data) =
-        summary_data.get_tx_data(mode_selection, months.index, years.index, conn);
+    let size = f.size();
+
+    // an unusable range shows the reason instead of empty tables
+    if mode_selection.index == RANGE_MODE {
+        if let Err(error) = summary_range.get_range(conn) {
+            range_error_ui(
+                f,
+                mode_selection,
+                summary_methods,
+                summary_range,
+                current_page,
+                summary_hidden_mode,
+                &error,
+            );
+            return;
+        }
+    }
+
+    let custom_range = summary_range.get_active_range(mode_selection, conn);
+
+    // the comparison with the previous month is only a part of the Monthly mode
+    let comparing = summary_compare && mode_selection.index == 0;
+
+    let (summary_data_1, summary_data_2, summary_data_3, summary_data_4, _) = summary_data
+        .get_tx_data(
+            mode_selection,
+            months.index,
+            years.index,
+            custom_range,
+            summary_methods.get_summary_method(),
+            conn,
+        );
+
+    let method_data = summary_data.get_page_method_data(
+        mode_selection,
+        months.index,
+        years.index,
+        custom_range,
+        summary_methods.get_summary_method(),
+        summary_compare,
+        conn,
+    );
 
     let mut summary_table_1 = TableData::new(summary_data_1);
     let mut summary_table_2 = TableData::new(summary_data_2);
@@ -34,47 +85,45 @@ pub fn summary_ui<B: Backend>(
     let mut summary_table_4 = TableData::new(summary_data_4);
     let mut method_table = TableData::new(method_data);
 
-    let size = f.size();
+    let amount_format = display_settings.get_amount_format();
 
-    let tag_header = if let SortingType::ByTags = summary_sort {
-        "Tags▼"
-    } else {
-        "Tags"
-    };
+    let tag_name = if summary_grouped { "Groups" } else { "Tags" };
 
-    let total_income_header = if let SortingType::ByIncome = summary_sort {
-        "Total Income▼"
+    // the raw view ignores the excluded tags
+    let mut table_title = if display_settings.is_summary_raw_view() {
+        format!("{tag_name} (Raw)")
     } else {
-        "Total Income"
+        tag_name.to_string()
     };
 
-    let total_expense_header = if let SortingType::ByExpense = summary_sort {
-        "Total Expense▼"
-    } else {
-        "Total Expense"
-    };
+    if comparing {
+        table_title.push_str(" (vs Previous Month)");
+    }
 
-    let header_cells = [
-        tag_header,
-        total_income_header,
-        total_expense_header,
-        "Income %",
-        "Expense %",
-    ]
-    .into_iter()
-    .map(|h| Cell::from(h).style(Style::default().fg(BACKGROUND)));
-
-    let method_header_cells = [
-        "Method",
-        "Total Income",
-        "Total Expense",
-        "Income %",
-        "Expense %",
-        "Average Income",
-        "Average Expense",
-    ]
-    .iter()
-    .map(|h| Cell::from(*h).style(Style::default().fg(BACKGROUND)));
+    if !summary_type_ahead.is_empty() {
+        table_title.push_str(&format!(" - Jump: {}", summary_type_ahead.get_prefix()));
+    }
+
+    let header_titles = get_tag_table_header(summary_grouped, comparing);
+
+    // the sorted column gets the symbol of the sort direction
+    let header_cells = header_titles
+        .into_iter()
+        .enumerate()
+        .map(|(index, title)| {
+            if index == summary_sort.column() {
+                format!("{title}{}", summary_sort_order.symbol())
+            } else {
+                title.to_string()
+            }
+        })
+        .map(|h| Cell::from(h).style(Style::default().fg(BACKGROUND)));
+
+    let method_header_titles = get_method_table_header(comparing);
+
+    let method_header_cells = method_header_titles
+        .iter()
+        .map(|h| Cell::from(*h).style(Style::default().fg(BACKGROUND)));
 
     let header = Row::new(header_cells)
         .style(Style::default().bg(HEADER))
@@ -86,7 +135,12 @@ pub fn summary_ui<B: Backend>(
         .height(1)
         .bottom_margin(0);
 
-    let method_len = get_all_tx_methods(conn).len() as u16;
+    // the method table only has the row of the filtered tx method
+    let method_len = if summary_methods.get_summary_method().is_some() {
+        1
+    } else {
+        names.get_tx_methods(conn).len() as u16
+    };
 
     let mut main_layout = Layout::default().direction(Direction::Vertical).margin(2);
     let mut summary_layout = Layout::default().direction(Direction::Horizontal);
@@ -95,7 +149,7 @@ pub fn summary_ui<B: Backend>(
         main_layout = main_layout.constraints(
             [
                 Constraint::Length(method_len + 3),
-                Constraint::Length(9),
+                Constraint::Length(12),
                 Constraint::Min(0),
             ]
             .as_ref(),
@@ -111,7 +165,7 @@ pub fn summary_ui<B: Backend>(
                         Constraint::Length(3),
                         Constraint::Length(3),
                         Constraint::Length(method_len + 3),
-                        Constraint::Length(9),
+                        Constraint::Length(12),
                         Constraint::Min(0),
                     ]
                     .as_ref(),
@@ -119,13 +173,13 @@ pub fn summary_ui<B: Backend>(
                 summary_layout = summary_layout
                     .constraints([Constraint::Percentage(50), Constraint::Percentage(50)]);
             }
-            1 => {
+            1 | RANGE_MODE => {
                 main_layout = main_layout.constraints(
                     [
                         Constraint::Length(3),
                         Constraint::Length(3),
                         Constraint::Length(method_len + 3),
-                        Constraint::Length(9),
+                        Constraint::Length(12),
                         Constraint::Min(0),
                     ]
                     .as_ref(),
@@ -138,7 +192,7 @@ pub fn summary_ui<B: Backend>(
                     [
                         Constraint::Length(3),
                         Constraint::Length(method_len + 3),
-                        Constraint::Length(9),
+                        Constraint::Length(12),
                         Constraint::Min(0),
                     ]
                     .as_ref(),
@@ -153,13 +207,35 @@ pub fn summary_ui<B: Backend>(
     let chunks = main_layout.split(size);
     let summary_chunk = if summary_hidden_mode {
         summary_layout.split(chunks[1])
+    } else if mode_selection.index == RANGE_MODE {
+        summary_layout.split(chunks[3])
     } else {
         summary_layout.split(chunks[4 - mode_selection.index])
     };
 
+    let table_chunk = if summary_hidden_mode {
+        chunks[2]
+    } else {
+        match mode_selection.index {
+            0 => chunks[5],
+            2 => chunks[3],
+            _ => chunks[4],
+        }
+    };
+
+    if let Some(position) = get_table_position(
+        table_data.state.selected(),
+        table_data.state.offset(),
+        table_data.items.len(),
+        table_chunk.height,
+    ) {
+        table_title.push_str(&format!(" - {position}"));
+    }
+
+    // the averages below the totals get the rest of the height for the time based rows
     let left_summary = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .constraints([Constraint::Length(5), Constraint::Min(0)])
         .split(summary_chunk[0]);
 
     let right_summary = Layout::default()
@@ -169,28 +245,64 @@ pub fn summary_ui<B: Backend>(
 
     f.render_widget(main_block(), size);
 
-    let mut month_tab = create_tab(months, "Months");
+    let mut month_tab = create_tab(months, "Months", chunks[2].width);
 
-    let mut year_tab = create_tab(years, "Years");
+    let mut year_tab = create_tab(years, "Years", chunks[1].width);
 
-    let mut mode_selection_tab = create_tab(mode_selection, "Modes");
+    // the mode selection shares its row with the tx method filter
+    let selection_chunk = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[0]);
 
-    // Goes through all tags provided and creates row for the table
+    let mut mode_selection_tab = create_tab(mode_selection, "Modes", selection_chunk[0].width);
+
+    let mut method_filter_tab = create_tab(summary_methods, "Methods", selection_chunk[1].width);
+
+    let excluded_tags = summary_data.get_excluded_tags();
+
+    // Goes through all tags provided and creates row for the table.
+    // Excluded tags are greyed out and marked as they are not a part of the calculations
     let rows = table_data.items.iter().map(|item| {
         let height = 1;
-        let cells = item.iter().map(|c| Cell::from(c.separate_with_commas()));
+        let excluded = excluded_tags.contains(&item[0]);
+        let type_ahead_match = summary_type_ahead.is_match(item);
+        let cells = item.iter().enumerate().map(|(j, c)| {
+            if excluded {
+                let text = if j == 0 {
+                    format!("{c} (excluded)")
+                } else {
+                    amount_format.localize(c)
+                };
+                return Cell::from(text);
+            }
+
+            let mut cell = Cell::from(amount_format.localize(c));
+            if j == 0 && type_ahead_match {
+                // the tag that the typed prefix jumped to
+                cell = cell
+                    .style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+            } else if j == 5 {
+                cell = cell.style(net_style(c));
+            }
+            cell
+        });
+        let row_color = if excluded { GRAY } else { TEXT };
         Row::new(cells)
             .height(height as u16)
             .bottom_margin(0)
-            .style(Style::default().fg(TEXT))
+            .style(Style::default().fg(row_color))
     });
 
     let summary_rows_1 = summary_table_1.items.iter().map(|item| {
         let height = 1;
+        let is_net = item[0] == "Net";
         let cells = item.iter().enumerate().map(|(j, c)| {
-            let mut cell = Cell::from(c.separate_with_commas());
+            let mut cell = Cell::from(amount_format.localize(c));
             if j == 0 {
                 cell = cell.style(Style::default().fg(TEXT).add_modifier(Modifier::BOLD));
+            } else if j == 1 && is_net {
+                cell = cell.style(net_style(c));
             }
             cell
         });
@@ -203,7 +315,7 @@ pub fn summary_ui<B: Backend>(
     let summary_rows_2 = summary_table_2.items.iter().map(|item| {
         let height = 1;
         let cells = item.iter().enumerate().map(|(j, c)| {
-            let mut cell = Cell::from(c.separate_with_commas());
+            let mut cell = Cell::from(amount_format.localize(c));
             if j == 0 {
                 cell = cell.style(Style::default().fg(TEXT).add_modifier(Modifier::BOLD));
             }
@@ -218,7 +330,7 @@ pub fn summary_ui<B: Backend>(
     let summary_rows_3 = summary_table_3.items.iter().map(|item| {
         let height = 1;
         let cells = item.iter().enumerate().map(|(j, c)| {
-            let mut cell = Cell::from(c.separate_with_commas());
+            let mut cell = Cell::from(amount_format.localize(c));
             if j == 0 {
                 cell = cell.style(Style::default().fg(TEXT).add_modifier(Modifier::BOLD));
             }
@@ -233,7 +345,7 @@ pub fn summary_ui<B: Backend>(
     let summary_rows_4 = summary_table_4.items.iter().map(|item| {
         let height = 1;
         let cells = item.iter().enumerate().map(|(j, c)| {
-            let mut cell = Cell::from(c.separate_with_commas());
+            let mut cell = Cell::from(amount_format.localize(c));
             if j == 0 {
                 cell = cell.style(Style::default().fg(TEXT).add_modifier(Modifier::BOLD));
             }
@@ -248,9 +360,11 @@ pub fn summary_ui<B: Backend>(
     let method_rows = method_table.items.iter().map(|item| {
         let height = 1;
         let cells = item.iter().enumerate().map(|(j, c)| {
-            let mut cell = Cell::from(c.separate_with_commas());
+            let mut cell = Cell::from(amount_format.localize(c));
             if j == 0 {
                 cell = cell.style(Style::default().fg(TEXT).add_modifier(Modifier::BOLD));
+            } else if j == 9 || j == 13 {
+                cell = cell.style(net_style(c));
             }
             cell
         });
@@ -260,16 +374,60 @@ pub fn summary_ui<B: Backend>(
             .style(Style::default().fg(TEXT))
     });
 
-    let mut table_area = Table::new(rows)
-        .header(header)
-        .block(styled_block("Tags"))
-        .widths(&[
+    let table_widths = if comparing {
+        vec![
             Constraint::Percentage(20),
             Constraint::Percentage(20),
             Constraint::Percentage(20),
             Constraint::Percentage(20),
             Constraint::Percentage(20),
-        ])
+        ]
+    } else {
+        vec![
+            Constraint::Percentage(16),
+            Constraint::Percentage(12),
+            Constraint::Percentage(12),
+            Constraint::Percentage(12),
+            Constraint::Percentage(12),
+            Constraint::Percentage(12),
+            Constraint::Percentage(12),
+            Constraint::Percentage(12),
+        ]
+    };
+
+    let method_widths = if comparing {
+        vec![
+            Constraint::Percentage(16),
+            Constraint::Percentage(14),
+            Constraint::Percentage(14),
+            Constraint::Percentage(14),
+            Constraint::Percentage(14),
+            Constraint::Percentage(14),
+            Constraint::Percentage(14),
+        ]
+    } else {
+        vec![
+            Constraint::Percentage(9),
+            Constraint::Percentage(7),
+            Constraint::Percentage(7),
+            Constraint::Percentage(7),
+            Constraint::Percentage(7),
+            Constraint::Percentage(7),
+            Constraint::Percentage(7),
+            Constraint::Percentage(7),
+            Constraint::Percentage(7),
+            Constraint::Percentage(7),
+            Constraint::Percentage(6),
+            Constraint::Percentage(7),
+            Constraint::Percentage(7),
+            Constraint::Percentage(7),
+        ]
+    };
+
+    let mut table_area = Table::new(rows)
+        .header(header)
+        .block(styled_block(&table_title))
+        .widths(&table_widths)
         .style(Style::default().fg(BOX));
 
     let summary_area_1 = Table::new(summary_rows_1)
@@ -310,18 +468,17 @@ pub fn summary_ui<B: Backend>(
         ])
         .style(Style::default().fg(BOX));
 
+    // the tag table and the top widgets never count transfers, only the method table follows the setting
+    let method_title = if display_settings.is_summary_including_transfers() {
+        "Methods - Transfers: included"
+    } else {
+        "Methods - Transfers: excluded"
+    };
+
     let method_area = Table::new(method_rows)
         .header(method_header)
-        .block(styled_block(""))
-        .widths(&[
-            Constraint::Percentage(14),
-            Constraint::Percentage(14),
-            Constraint::Percentage(14),
-            Constraint::Percentage(14),
-            Constraint::Percentage(14),
-            Constraint::Percentage(14),
-            Constraint::Percentage(14),
-        ])
+        .block(styled_block(method_title))
+        .widths(&method_widths)
         .style(Style::default().fg(BOX));
 
     match current_page {
@@ -340,11 +497,16 @@ pub fn summary_ui<B: Backend>(
             mode_selection_tab = mode_selection_tab
                 .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(SELECTED));
         }
+        SummaryTab::Methods => {
+            method_filter_tab = method_filter_tab
+                .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(SELECTED));
+        }
         SummaryTab::Table => {
             table_area = table_area
                 .highlight_style(Style::default().bg(SELECTED))
                 .highlight_symbol(">> ")
         }
+        SummaryTab::RangeStart | SummaryTab::RangeEnd => {}
     }
 
     if summary_hidden_mode {
@@ -355,7 +517,8 @@ pub fn summary_ui<B: Backend>(
         f.render_stateful_widget(table_area, chunks[2], &mut table_data.state);
         f.render_stateful_widget(method_area, chunks[0], &mut method_table.state);
     } else {
-        f.render_widget(mode_selection_tab, chunks[0]);
+        f.render_widget(mode_selection_tab, selection_chunk[0]);
+        f.render_widget(method_filter_tab, selection_chunk[1]);
         f.render_stateful_widget(summary_area_1, left_summary[0], &mut summary_table_1.state);
         f.render_stateful_widget(summary_area_2, left_summary[1], &mut summary_table_2.state);
         f.render_stateful_widget(summary_area_3, right_summary[0], &mut summary_table_3.state);
@@ -377,7 +540,128 @@ pub fn summary_ui<B: Backend>(
                 f.render_stateful_widget(table_area, chunks[3], &mut table_data.state);
                 f.render_stateful_widget(method_area, chunks[1], &mut method_table.state);
             }
+            RANGE_MODE => {
+                range_boxes_ui(f, chunks[1], summary_range, current_page);
+                f.render_stateful_widget(table_area, chunks[4], &mut table_data.state);
+                f.render_stateful_widget(method_area, chunks[2], &mut method_table.state);
+            }
             _ => {}
         }
     }
 }
+
+/// Returns the style of a net value. Values below zero are shown in red
+#[cfg(not(tarpaulin_include))]
+fn net_style(value: &str) -> Style {
+    if value.starts_with('-') {
+        Style::default().fg(RED)
+    } else {
+        Style::default().fg(TEXT)
+    }
+}
+
+/// Draws the start and end date boxes of the Range mode
+#[cfg(not(tarpaulin_include))]
+fn range_boxes_ui<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    summary_range: &SummaryRangeData,
+    current_page: &SummaryTab,
+) {
+    let range_chunk = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let start_sec = Paragraph::new(summary_range.get_start_date())
+        .style(Style::default().bg(BACKGROUND).fg(TEXT))
+        .block(styled_block("Start Date"))
+        .alignment(Alignment::Left);
+
+    let end_sec = Paragraph::new(summary_range.get_end_date())
+        .style(Style::default().bg(BACKGROUND).fg(TEXT))
+        .block(styled_block("End Date"))
+        .alignment(Alignment::Left);
+
+    f.render_widget(start_sec, range_chunk[0]);
+    f.render_widget(end_sec, range_chunk[1]);
+
+    match current_page {
+        SummaryTab::RangeStart => f.set_cursor(
+            range_chunk[0].x + summary_range.get_start_index() as u16 + 1,
+            range_chunk[0].y + 1,
+        ),
+        SummaryTab::RangeEnd => f.set_cursor(
+            range_chunk[1].x + summary_range.get_end_index() as u16 + 1,
+            range_chunk[1].y + 1,
+        ),
+        _ => {}
+    }
+}
+
+/// Draws the Range mode with the reason the range can not be used in place of the tables
+#[cfg(not(tarpaulin_include))]
+fn range_error_ui<B: Backend>(
+    f: &mut Frame<B>,
+    mode_selection: &IndexedData,
+    summary_methods: &IndexedData,
+    summary_range: &SummaryRangeData,
+    current_page: &SummaryTab,
+    summary_hidden_mode: bool,
+    error: &str,
+) {
+    let size = f.size();
+
+    let constraints = if summary_hidden_mode {
+        vec![Constraint::Min(0)]
+    } else {
+        vec![
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(0),
+        ]
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(constraints)
+        .split(size);
+
+    f.render_widget(main_block(), size);
+
+    let error_sec = Paragraph::new(error)
+        .style(Style::default().bg(BACKGROUND).fg(RED))
+        .block(styled_block("Status"))
+        .alignment(Alignment::Left);
+
+    if summary_hidden_mode {
+        f.render_widget(error_sec, chunks[0]);
+        return;
+    }
+
+    let selection_chunk = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[0]);
+
+    let mut mode_selection_tab = create_tab(mode_selection, "Modes", selection_chunk[0].width);
+    let mut method_filter_tab = create_tab(summary_methods, "Methods", selection_chunk[1].width);
+
+    match current_page {
+        SummaryTab::ModeSelection => {
+            mode_selection_tab = mode_selection_tab
+                .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(SELECTED));
+        }
+        SummaryTab::Methods => {
+            method_filter_tab = method_filter_tab
+                .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(SELECTED));
+        }
+        _ => {}
+    }
+
+    f.render_widget(mode_selection_tab, selection_chunk[0]);
+    f.render_widget(method_filter_tab, selection_chunk[1]);
+    range_boxes_ui(f, chunks[1], summary_range, current_page);
+    f.render_widget(error_sec, chunks[2]);
+}
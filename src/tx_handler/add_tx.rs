@@ -1,8 +1,8 @@
+use crate::db::get_date_range;
 use crate::utility::{
-    get_all_tx_methods, get_last_balance_id, get_last_balances, get_last_time_balance,
-    get_last_tx_id,
+    get_all_tx_methods, get_last_balance_id, get_last_balances, get_last_time_money, Money,
 };
-use rusqlite::{Connection, Result as sqlResult};
+use rusqlite::{Connection, Result as sqlResult, Savepoint};
 use std::collections::HashMap;
 
 /// Adds a transaction to the database with the given info. The flow of this goes like this:
@@ -11,6 +11,9 @@ use std::collections::HashMap;
 /// - Calculate the absolute final balance
 /// - Find the Changes that happened due to the transaction
 /// - Push them to the database
+///
+/// Returns the id_num of the added transaction
+#[allow(clippy::too_many_arguments)]
 pub fn add_tx(
     date: &str,
     details: &str,
@@ -20,10 +23,40 @@ pub fn add_tx(
     tags: &str,
     id_num: Option<&str>,
     conn: &mut Connection,
-) -> sqlResult<()> {
+) -> sqlResult<i32> {
     // create a connection and a savepoint
     let sp = conn.savepoint()?;
+    let id = insert_tx(date, details, tx_method, amount, tx_type, tags, id_num, &sp)?;
+    sp.commit()?;
+    Ok(id)
+}
+
+/// Adds multiple transactions to the database inside a single savepoint. Either all of them
+/// get added or none of them do.
+///
+/// Each tx must be in this order: `[date, details, tx_method, amount, tx_type, tags]`
+pub fn add_txs(txs: &[Vec<String>], conn: &mut Connection) -> sqlResult<()> {
+    let sp = conn.savepoint()?;
+    for tx in txs {
+        insert_tx(&tx[0], &tx[1], &tx[2], &tx[3], &tx[4], &tx[5], None, &sp)?;
+    }
+    sp.commit()?;
+    Ok(())
+}
 
+/// Does the actual work of adding a transaction on the given savepoint without committing it.
+/// Returns the id_num of the added transaction
+#[allow(clippy::too_many_arguments)]
+pub fn insert_tx(
+    date: &str,
+    details: &str,
+    tx_method: &str,
+    amount: &str,
+    tx_type: &str,
+    tags: &str,
+    id_num: Option<&str>,
+    sp: &Savepoint,
+) -> sqlResult<i32> {
     // the process goes through 4 parts
     // Add the tx itself in the db
     // calculate the amount to add/subtract from the balance_all table
@@ -41,12 +74,12 @@ pub fn add_tx(
     }
 
     // 2025-05-10
-    // take 2025 and subtract 2022 = 3, means the year number 3
+    // take 2025 and subtract the first year of the date range, 2022 = 3, means the year number 3
     // take 05 -> 5 -> 5th month. 5 + (3 * 12) =  the row of this month's balance on balance_all table
     // we are not subtracting 1 from month because balance_all table starts at 1
     let splitted = date.split('-').collect::<Vec<&str>>();
     let (year, month) = (
-        splitted[0].parse::<i32>().unwrap() - 2022,
+        splitted[0].parse::<i32>().unwrap() - get_date_range(sp).start_year,
         splitted[1].parse::<i32>().unwrap(),
     );
 
@@ -62,12 +95,13 @@ pub fn add_tx(
     let target_id_num = month + (year * 12);
 
     // This is necessary for the foreign key field in the changes_all table
-    // and must align with the latest transaction id_num
-    let mut last_id = get_last_tx_id(&sp)?;
-    if let Some(id) = id_num {
-        last_id = id.parse().unwrap();
-    }
-    let last_balance_id = get_last_balance_id(&sp)?;
+    // and must align with the id_num of the transaction inserted above.
+    // The last inserted row of this connection is used as another process may add transactions too
+    let last_id = match id_num {
+        Some(id) => id.parse().unwrap(),
+        None => sp.last_insert_rowid() as i32,
+    };
+    let last_balance_id = get_last_balance_id(sp)?;
 
     // we have to get these following data to push to the database
     // new_balance_data: the working month balance after the transaction
@@ -77,14 +111,14 @@ pub fn add_tx(
     let mut new_changes_data = Vec::new();
     let mut last_balance_data = HashMap::new();
 
-    let all_tx_methods = get_all_tx_methods(&sp);
-    let last_balance = get_last_balances(&sp);
+    let all_tx_methods = get_all_tx_methods(sp);
+    let last_balance = get_last_balances(sp);
 
     // Retrieve the current month's balance for each transaction method.
     let mut current_month_balance =
-        get_last_time_balance(month as usize, year as usize, &all_tx_methods, &sp);
+        get_last_time_money(month as usize, year as usize, &all_tx_methods, sp);
 
-    let int_amount = amount.parse::<f64>().unwrap();
+    let int_amount = Money::parse(amount).unwrap();
 
     // Update the current month's balance based on the transaction type.
     match tx_type {
@@ -102,7 +136,8 @@ pub fn add_tx(
             // Update the current month's balance for the relevant method.
             *current_month_balance.get_mut(tx_method).unwrap() = new_balance;
         }
-        "Income" => {
+        // a refund credits the method back the same way an income does
+        "Income" | "Refund" => {
             let new_balance = current_month_balance[tx_method] + int_amount;
             // Update the current month's balance for the relevant method.
             *current_month_balance.get_mut(tx_method).unwrap() = new_balance;
@@ -113,37 +148,37 @@ pub fn add_tx(
     // Add the current month's balances to the new balance data vector.
     // It's done this way to match the tx method location
     for i in &all_tx_methods {
-        new_balance_data.push(format!("{:.2}", current_month_balance[i]))
+        new_balance_data.push(current_month_balance[i].to_stored_string())
     }
 
     //
     for i in 0..all_tx_methods.len() {
         // the variable to keep track whether any changes were made to the tx method
-        let current_last_balance = last_balance[i].parse::<f64>().unwrap();
-        let mut current_change = format!("{:.2}", 0.0);
+        let current_last_balance = Money::parse(&last_balance[i]).unwrap();
+        let mut current_change = Money::ZERO.to_string();
 
         // add the proper values and changes based on the tx type
         if tx_type == "Transfer" && all_tx_methods[i] == from_method {
-            current_change = format!("↓{:.2}", &int_amount);
+            current_change = format!("↓{int_amount}");
 
             let edited_balance = current_last_balance - int_amount;
-            last_balance_data.insert(&from_method, format!("{edited_balance:.2}"));
+            last_balance_data.insert(&from_method, edited_balance.to_stored_string());
         } else if tx_type == "Transfer" && all_tx_methods[i] == to_method {
-            current_change = format!("↑{:.2}", &int_amount);
+            current_change = format!("↑{int_amount}");
 
             let edited_balance = current_last_balance + int_amount;
-            last_balance_data.insert(&to_method, format!("{edited_balance:.2}"));
+            last_balance_data.insert(&to_method, edited_balance.to_stored_string());
         } else if tx_type != "Transfer" && all_tx_methods[i] == tx_method {
             if tx_type == "Expense" {
-                current_change = format!("↓{:.2}", &int_amount);
+                current_change = format!("↓{int_amount}");
 
                 let edited_balance = current_last_balance - int_amount;
-                last_balance_data.insert(&all_tx_methods[i], format!("{edited_balance:.2}"));
-            } else if tx_type == "Income" {
-                current_change = format!("↑{:.2}", &int_amount);
+                last_balance_data.insert(&all_tx_methods[i], edited_balance.to_stored_string());
+            } else if tx_type == "Income" || tx_type == "Refund" {
+                current_change = format!("↑{int_amount}");
 
                 let edited_balance = current_last_balance + int_amount;
-                last_balance_data.insert(&all_tx_methods[i], format!("{edited_balance:.2}"));
+                last_balance_data.insert(&all_tx_methods[i], edited_balance.to_stored_string());
             }
         }
         new_changes_data.push(current_change);
@@ -192,6 +227,5 @@ pub fn add_tx(
     sp.execute(&balance_query, [])?;
     sp.execute(&last_balance_query, [])?;
     sp.execute(&changes_query, [date])?;
-    sp.commit()?;
-    Ok(())
+    Ok(last_id)
 }
@@ -0,0 +1,144 @@
+use crate::db::get_date_range;
+use crate::outputs::VerifyingOutput;
+use crate::utility::traits::DataVerifier;
+use crate::utility::{
+    add_char_to, get_all_tx_methods, get_date_format, get_money_on_date, Clock, Money, SystemClock,
+};
+use rusqlite::Connection;
+
+/// Contains all data for the popup that shows the balance of every method at the end of a date
+/// and how much it changed until today
+///
+/// balances: `[["Cash", "100.00", "+20.00"], ["Bank", "50.00", "-5.00"]]`
+pub struct BalanceDateData {
+    date: String,
+    current_index: usize,
+    balances: Vec<Vec<String>>,
+    total: String,
+    total_change: String,
+    status: String,
+}
+
+impl DataVerifier for BalanceDateData {}
+
+impl Default for BalanceDateData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BalanceDateData {
+    pub fn new() -> Self {
+        BalanceDateData {
+            date: String::new(),
+            current_index: 0,
+            balances: Vec::new(),
+            total: String::new(),
+            total_change: String::new(),
+            status: String::new(),
+        }
+    }
+
+    pub fn get_date(&self) -> &str {
+        &self.date
+    }
+
+    pub fn get_current_index(&self) -> usize {
+        self.current_index
+    }
+
+    pub fn get_balances(&self) -> &Vec<Vec<String>> {
+        &self.balances
+    }
+
+    pub fn get_total(&self) -> &str {
+        &self.total
+    }
+
+    pub fn get_total_change(&self) -> &str {
+        &self.total_change
+    }
+
+    pub fn get_status(&self) -> &str {
+        &self.status
+    }
+
+    /// Insert or remove from the date field
+    pub fn edit_date(&mut self, to_add: Option<char>) {
+        add_char_to(to_add, &mut self.current_index, &mut self.date);
+    }
+
+    /// Verifies the date and loads the balances at the end of it.
+    /// The previous balances are cleared if the date is not accepted
+    pub fn load_balances(&mut self, conn: &Connection) {
+        self.load_balances_with_clock(&SystemClock, conn);
+    }
+
+    /// Same as `load_balances` but the changes are calculated until the clock's date
+    pub fn load_balances_with_clock(&mut self, clock: &impl Clock, conn: &Connection) {
+        let mut user_date = self.date.clone();
+        let date_format = get_date_format(conn);
+        let status = self.verify_date(&mut user_date, &get_date_range(conn), &date_format);
+        self.date = user_date;
+        self.current_index = self.date.len();
+
+        if let VerifyingOutput::NotAccepted(_) | VerifyingOutput::Nothing(_) = status {
+            self.status = status.to_string();
+            self.balances.clear();
+            self.total.clear();
+            self.total_change.clear();
+            return;
+        }
+
+        let balance = get_money_on_date(&date_format.to_iso(&self.date), conn);
+        let today_balance = get_money_on_date(&clock.today().to_string(), conn);
+
+        let mut total = Money::ZERO;
+        let mut total_change = Money::ZERO;
+        self.balances = get_all_tx_methods(conn)
+            .into_iter()
+            .map(|method| {
+                let method_balance = balance.get(&method).copied().unwrap_or_default();
+                let method_change =
+                    today_balance.get(&method).copied().unwrap_or_default() - method_balance;
+                total += method_balance;
+                total_change += method_change;
+                vec![
+                    method,
+                    method_balance.to_string(),
+                    format_change(method_change),
+                ]
+            })
+            .collect();
+
+        self.total = total.to_string();
+        self.total_change = format_change(total_change);
+        self.status.clear();
+    }
+
+    /// Returns the loaded balances as a single line that can be copied elsewhere.
+    /// Empty if nothing is loaded
+    pub fn get_summary_line(&self) -> String {
+        if self.balances.is_empty() {
+            return String::new();
+        }
+
+        let methods = self
+            .balances
+            .iter()
+            .map(|balance| format!("{} {}", balance[0], balance[1]))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        format!("Balance on {}: {methods}, Total {}", self.date, self.total)
+    }
+}
+
+/// Formats a balance change with a sign in front of it
+fn format_change(change: Money) -> String {
+    if change > Money::ZERO {
+        format!("+{change}")
+    } else {
+        change.to_string()
+    }
+}
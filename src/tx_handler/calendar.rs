@@ -0,0 +1,159 @@
+use crate::db::DateRange;
+use crate::utility::DateFormat;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+/// Returns the weeks of the month as rows of 7 days, each row starting on the given weekday.
+/// The cells before the 1st and after the final day of the month are None
+///
+/// return example: `[[None, None, Some(1), Some(2), Some(3), Some(4), Some(5)], ...]`
+pub fn get_month_weeks(year: i32, month: u32, week_start: Weekday) -> Vec<[Option<u32>; 7]> {
+    let first_day = match NaiveDate::from_ymd_opt(year, month, 1) {
+        Some(date) => date,
+        None => return Vec::new(),
+    };
+
+    let mut column = ((first_day.weekday().num_days_from_monday() + 7
+        - week_start.num_days_from_monday())
+        % 7) as usize;
+
+    let mut weeks = Vec::new();
+    let mut week = [None; 7];
+
+    for day in 1..=get_month_length(year, month) {
+        week[column] = Some(day);
+        column += 1;
+
+        if column == 7 {
+            weeks.push(week);
+            week = [None; 7];
+            column = 0;
+        }
+    }
+
+    if column > 0 {
+        weeks.push(week);
+    }
+
+    weeks
+}
+
+/// Returns the number of days of the month
+fn get_month_length(year: i32, month: u32) -> u32 {
+    let next_month = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)
+    };
+
+    match next_month {
+        Some(date) => date.pred_opt().map_or(0, |last_day| last_day.day()),
+        None => 0,
+    }
+}
+
+/// Contains all data for the popup that picks a date from a month grid.
+/// The selected date always stays inside the year range
+pub struct CalendarData {
+    selected: NaiveDate,
+    first_date: NaiveDate,
+    last_date: NaiveDate,
+    week_start: Weekday,
+}
+
+impl CalendarData {
+    /// Creates an instance of the struct on the date of the field if it is a valid one,
+    /// otherwise on today
+    pub fn new(
+        field_date: &str,
+        date_format: &DateFormat,
+        date_range: &DateRange,
+        week_start: Weekday,
+        today: NaiveDate,
+    ) -> Self {
+        let first_date = NaiveDate::from_ymd_opt(date_range.start_year, 1, 1).unwrap_or(today);
+        let last_date = NaiveDate::from_ymd_opt(date_range.end_year, 12, 31).unwrap_or(today);
+
+        let selected =
+            NaiveDate::parse_from_str(&date_format.to_iso(field_date.trim()), "%Y-%m-%d")
+                .ok()
+                .filter(|date| date_range.contains(date.year()))
+                .unwrap_or(today);
+
+        CalendarData {
+            selected: selected.clamp(first_date, last_date),
+            first_date,
+            last_date,
+            week_start,
+        }
+    }
+
+    pub fn get_selected(&self) -> NaiveDate {
+        self.selected
+    }
+
+    /// Returns the selected date in YYYY-MM-DD
+    pub fn get_iso_date(&self) -> String {
+        self.selected.format("%Y-%m-%d").to_string()
+    }
+
+    /// Returns the weeks of the month of the selected date
+    pub fn get_weeks(&self) -> Vec<[Option<u32>; 7]> {
+        get_month_weeks(self.selected.year(), self.selected.month(), self.week_start)
+    }
+
+    /// Returns the short names of the days in the order of the grid columns
+    pub fn get_day_names(&self) -> Vec<String> {
+        let mut day = self.week_start;
+        let mut names = Vec::new();
+
+        for _ in 0..7 {
+            names.push(day.to_string()[..2].to_string());
+            day = day.succ();
+        }
+        names
+    }
+
+    /// Returns the title of the grid
+    ///
+    /// return example: `May 2022`
+    pub fn get_title(&self) -> String {
+        self.selected.format("%B %Y").to_string()
+    }
+
+    /// Moves the selected date by the given days. Stops at the ends of the year range
+    pub fn move_days(&mut self, days: i64) {
+        if let Some(date) = self.selected.checked_add_signed(Duration::days(days)) {
+            self.selected = date.clamp(self.first_date, self.last_date);
+        }
+    }
+
+    /// Moves the selected date to the same day of the next month. The day is cut to the
+    /// final day of the month if that month is shorter
+    pub fn next_month(&mut self) {
+        let (year, month) = if self.selected.month() == 12 {
+            (self.selected.year() + 1, 1)
+        } else {
+            (self.selected.year(), self.selected.month() + 1)
+        };
+        self.go_month(year, month);
+    }
+
+    /// Moves the selected date to the same day of the previous month. The day is cut to the
+    /// final day of the month if that month is shorter
+    pub fn previous_month(&mut self) {
+        let (year, month) = if self.selected.month() == 1 {
+            (self.selected.year() - 1, 12)
+        } else {
+            (self.selected.year(), self.selected.month() - 1)
+        };
+        self.go_month(year, month);
+    }
+
+    fn go_month(&mut self, year: i32, month: u32) {
+        let day = self.selected.day().min(get_month_length(year, month));
+
+        if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
+            self.selected = date.clamp(self.first_date, self.last_date);
+        }
+    }
+}
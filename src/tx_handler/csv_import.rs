@@ -0,0 +1,539 @@
+use crate::api::{add_transaction, NewTx};
+use crate::db::add_new_tx_methods;
+use crate::outputs::ApiError;
+use crate::page_handler::TaskProgress;
+use crate::tx_handler::{IMPORT_BATCH_SIZE, REPORTED_PROBLEMS};
+use crate::utility::{check_restricted, get_colliding_name, normalize_name, Money};
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::fmt;
+
+/// The CSV exports of other programs that can be imported. Each profile knows the columns
+/// of its export and how its transaction types map to the Rex ones
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ImportProfile {
+    FireflyIII,
+}
+
+/// The header names of the columns a profile reads. Headers are matched ignoring the case
+pub struct CsvColumns {
+    pub id: &'static str,
+    pub tx_type: &'static str,
+    pub amount: &'static str,
+    pub date: &'static str,
+    pub details: &'static str,
+    pub source: &'static str,
+    pub destination: &'static str,
+    /// Every column here is merged into the tags of the transaction
+    pub tags: &'static [&'static str],
+}
+
+impl ImportProfile {
+    pub fn all() -> Vec<ImportProfile> {
+        vec![ImportProfile::FireflyIII]
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ImportProfile::FireflyIII => "Firefly III",
+        }
+    }
+
+    pub fn get_columns(&self) -> CsvColumns {
+        match self {
+            ImportProfile::FireflyIII => CsvColumns {
+                id: "journal_id",
+                tx_type: "type",
+                amount: "amount",
+                date: "date",
+                details: "description",
+                source: "source_name",
+                destination: "destination_name",
+                tags: &["category", "tags"],
+            },
+        }
+    }
+
+    /// Returns the Rex tx type of a transaction type of the export. None for the types that are not imported
+    ///
+    /// example: `withdrawal` => `Expense`
+    pub fn get_tx_type(&self, tx_type: &str) -> Option<&'static str> {
+        match self {
+            ImportProfile::FireflyIII => match tx_type.trim().to_lowercase().as_str() {
+                "withdrawal" => Some("Expense"),
+                "deposit" => Some("Income"),
+                "transfer" => Some("Transfer"),
+                _ => None,
+            },
+        }
+    }
+
+    /// Returns the external id saved with a transaction so ids of different programs can not clash
+    ///
+    /// example: `412` => `firefly-412`
+    pub fn get_external_id(&self, id: &str) -> String {
+        match self {
+            ImportProfile::FireflyIII => format!("firefly-{}", id.trim()),
+        }
+    }
+}
+
+/// A row of a CSV export turned into a Rex transaction. The accounts are the names used by the
+/// export and still need a tx method. Expense and Income only use from_account
+#[derive(Debug, PartialEq, Clone)]
+pub struct CsvTx {
+    pub line: usize,
+    pub external_id: String,
+    pub date: String,
+    pub details: String,
+    pub from_account: String,
+    pub to_account: String,
+    pub amount: Money,
+    pub tx_type: String,
+    pub tags: String,
+}
+
+/// A CSV line or transaction that could not be imported
+#[derive(Debug, PartialEq, Clone)]
+pub struct CsvProblem {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl CsvProblem {
+    pub fn new(line: usize, reason: impl Into<String>) -> Self {
+        CsvProblem {
+            line,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for CsvProblem {
+    #[cfg(not(tarpaulin_include))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.reason)
+    }
+}
+
+/// Parses a comma separated file into its records with the line number each record starts on.
+/// Fields can be quoted with `"` to hold commas, new lines and `""` for a quote
+pub fn parse_csv(text: &str) -> Result<Vec<(usize, Vec<String>)>, CsvProblem> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut quote_line = 1;
+    let mut in_quotes = false;
+
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_quotes = true;
+                quote_line = line;
+            }
+            ',' => fields.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                let record = std::mem::take(&mut fields);
+
+                // empty lines are not records
+                if record.len() > 1 || !record[0].trim().is_empty() {
+                    records.push((record_line, record));
+                }
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(CsvProblem::new(
+            quote_line,
+            "The quoted field is never closed",
+        ));
+    }
+
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((record_line, fields));
+    }
+
+    Ok(records)
+}
+
+/// Reads the export with the columns of the profile. Rows that can not be turned into a
+/// transaction are collected with the reason. Returns an error if the export is missing
+/// a column the profile needs
+pub fn read_csv_export(
+    text: &str,
+    profile: ImportProfile,
+) -> Result<(Vec<CsvTx>, Vec<CsvProblem>), String> {
+    let mut records = parse_csv(text)
+        .map_err(|problem| problem.to_string())?
+        .into_iter();
+
+    let header = match records.next() {
+        Some((_, header)) => header,
+        None => return Err("The file is empty".to_string()),
+    };
+
+    let columns = profile.get_columns();
+
+    let find_column = |name: &str| {
+        header
+            .iter()
+            .position(|column| column.trim().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                format!(
+                    "The file has no {name} column. Is it a {} export?",
+                    profile.get_name()
+                )
+            })
+    };
+
+    let indexes = ColumnIndexes {
+        id: find_column(columns.id)?,
+        tx_type: find_column(columns.tx_type)?,
+        amount: find_column(columns.amount)?,
+        date: find_column(columns.date)?,
+        details: find_column(columns.details)?,
+        source: find_column(columns.source)?,
+        destination: find_column(columns.destination)?,
+        tags: columns
+            .tags
+            .iter()
+            .map(|name| find_column(name))
+            .collect::<Result<Vec<usize>, String>>()?,
+    };
+
+    let mut txs = Vec::new();
+    let mut problems = Vec::new();
+
+    for (line, record) in records {
+        match record_to_tx(line, &record, &indexes, profile) {
+            Ok(tx) => txs.push(tx),
+            Err(reason) => problems.push(CsvProblem::new(line, reason)),
+        }
+    }
+
+    Ok((txs, problems))
+}
+
+/// The positions of the columns a profile reads within the header of an export
+struct ColumnIndexes {
+    id: usize,
+    tx_type: usize,
+    amount: usize,
+    date: usize,
+    details: usize,
+    source: usize,
+    destination: usize,
+    tags: Vec<usize>,
+}
+
+/// Turns a record into a transaction after checking its id, type, amount, date and accounts
+fn record_to_tx(
+    line: usize,
+    record: &[String],
+    indexes: &ColumnIndexes,
+    profile: ImportProfile,
+) -> Result<CsvTx, String> {
+    let get = |index: usize| record.get(index).map_or("", |value| value.trim());
+
+    let id = get(indexes.id);
+    if id.is_empty() {
+        return Err("The transaction has no id".to_string());
+    }
+
+    let tx_type = get(indexes.tx_type);
+    let tx_type = profile
+        .get_tx_type(tx_type)
+        .ok_or_else(|| format!("Transaction type {tx_type} is not imported"))?;
+
+    let amount = get(indexes.amount);
+    let amount = Money::parse(amount)
+        .ok_or_else(|| format!("Could not read the amount {amount}"))?
+        .abs();
+
+    let date = get(indexes.date);
+    let date = parse_csv_date(date).ok_or_else(|| format!("Could not read the date {date}"))?;
+
+    let (source, destination) = (get(indexes.source), get(indexes.destination));
+
+    // the account the money leaves for Expense and arrives at for Income
+    let (from_account, to_account) = match tx_type {
+        "Expense" => (source, ""),
+        "Income" => (destination, ""),
+        _ => (source, destination),
+    };
+
+    if from_account.is_empty() || (tx_type == "Transfer" && to_account.is_empty()) {
+        return Err("The transaction is missing its account".to_string());
+    }
+
+    let tags = indexes
+        .tags
+        .iter()
+        .flat_map(|index| get(*index).split(','))
+        .collect::<Vec<&str>>();
+
+    Ok(CsvTx {
+        line,
+        external_id: profile.get_external_id(id),
+        date,
+        details: get(indexes.details).to_string(),
+        from_account: normalize_name(from_account),
+        to_account: normalize_name(to_account),
+        amount,
+        tx_type: tx_type.to_string(),
+        tags: merge_csv_tags(&tags),
+    })
+}
+
+/// Parses the date of an export into YYYY-MM-DD. The time after the date is not used
+///
+/// example: `2023-01-05T00:00:00+01:00` => `2023-01-05`
+pub fn parse_csv_date(text: &str) -> Option<String> {
+    let date_text = text.split(['T', ' ']).next().unwrap_or_default();
+
+    NaiveDate::parse_from_str(date_text, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+/// Merges the tags of a row into a single tag list. Names that only differ by case are kept once
+///
+/// example: `[Groceries, food, Food]` => `Groceries, food`
+pub fn merge_csv_tags(tags: &[&str]) -> String {
+    let mut merged = Vec::new();
+
+    for tag in tags {
+        let tag = normalize_name(tag);
+
+        if !tag.is_empty() && get_colliding_name(&tag, &merged).is_none() {
+            merged.push(tag);
+        }
+    }
+
+    merged.join(", ")
+}
+
+/// Returns the accounts of the transactions in the order they first appear.
+/// These are the accounts that need a tx method
+pub fn get_csv_accounts(txs: &[CsvTx]) -> Vec<String> {
+    let mut accounts = Vec::new();
+
+    for tx in txs {
+        for account in [&tx.from_account, &tx.to_account] {
+            if !account.is_empty() && !accounts.contains(account) {
+                accounts.push(account.to_string());
+            }
+        }
+    }
+    accounts
+}
+
+/// Returns whether a tx method can be created with the name of an account.
+/// Follows the same rules as adding tx methods from the J menu
+pub fn is_valid_new_method(name: &str) -> bool {
+    !name.is_empty()
+        && !check_restricted(name, None)
+        && !name.to_lowercase().contains("to")
+        && !name.contains('"')
+}
+
+/// Turns the rows into transactions with their line number. Rows with an account that is
+/// not mapped to a tx method are collected with the reason
+pub fn csv_to_txs(
+    txs: &[CsvTx],
+    method_map: &HashMap<String, String>,
+) -> (Vec<(usize, NewTx)>, Vec<CsvProblem>) {
+    let mut new_txs = Vec::new();
+    let mut problems = Vec::new();
+
+    for tx in txs {
+        let get_method = |account: &str| {
+            if account.is_empty() {
+                return Ok(String::new());
+            }
+
+            method_map
+                .get(account)
+                .cloned()
+                .ok_or_else(|| format!("Account {account} is not mapped to a tx method"))
+        };
+
+        let methods = get_method(&tx.from_account)
+            .and_then(|from| get_method(&tx.to_account).map(|to| (from, to)));
+
+        match methods {
+            Ok((from_method, to_method)) => new_txs.push((
+                tx.line,
+                NewTx {
+                    date: tx.date.clone(),
+                    details: tx.details.clone(),
+                    from_method,
+                    to_method,
+                    amount: tx.amount.to_stored_string(),
+                    tx_type: tx.tx_type.clone(),
+                    tags: tx.tags.clone(),
+                    refund_of: None,
+                    external_id: Some(tx.external_id.clone()),
+                },
+            )),
+            Err(reason) => problems.push(CsvProblem::new(tx.line, reason)),
+        }
+    }
+
+    (new_txs, problems)
+}
+
+/// Adds the transactions one by one with the same checks as the Add Tx page. Transactions that
+/// were imported before are skipped and the ones that fail are collected with their line number.
+/// Returns the number of added and skipped transactions
+pub fn import_csv_txs(
+    txs: &[(usize, NewTx)],
+    conn: &mut Connection,
+) -> (usize, usize, Vec<CsvProblem>) {
+    let mut added = 0;
+    let mut skipped = 0;
+    let mut problems = Vec::new();
+
+    for (line, tx) in txs {
+        match add_transaction(tx, conn) {
+            Ok(_) => added += 1,
+            Err(ApiError::AlreadyImported(_)) => skipped += 1,
+            Err(ApiError::InvalidTx(errors)) => problems.push(CsvProblem::new(
+                *line,
+                errors.to_string().replace('\n', ". "),
+            )),
+            Err(e) => problems.push(CsvProblem::new(*line, e.to_string())),
+        }
+    }
+
+    (added, skipped, problems)
+}
+
+/// Creates the new tx methods and imports the transactions in batches as a background task,
+/// sending the progress after every batch. Stops before the next batch once the task is cancelled.
+/// Returns the report of the import
+pub fn import_csv_task(
+    profile: ImportProfile,
+    new_methods: Vec<String>,
+    txs: &[(usize, NewTx)],
+    mut problems: Vec<CsvProblem>,
+    progress: &TaskProgress,
+    conn: &mut Connection,
+) -> Result<String, String> {
+    if !new_methods.is_empty() {
+        add_new_tx_methods(new_methods.clone(), conn)
+            .map_err(|e| format!("Could not create the tx methods. Error: {e}"))?;
+    }
+
+    let mut added = 0;
+    let mut skipped = 0;
+    let mut done = 0;
+
+    progress.update(done, txs.len());
+
+    for batch in txs.chunks(IMPORT_BATCH_SIZE) {
+        if progress.is_cancelled() {
+            break;
+        }
+
+        let (batch_added, batch_skipped, batch_problems) = import_csv_txs(batch, conn);
+        added += batch_added;
+        skipped += batch_skipped;
+        done += batch.len();
+        problems.extend(batch_problems);
+
+        progress.update(done, txs.len());
+    }
+
+    problems.sort_by_key(|problem| problem.line);
+    Ok(get_csv_report(
+        profile,
+        &new_methods,
+        added,
+        skipped,
+        done < txs.len(),
+        &problems,
+    ))
+}
+
+/// Returns the text of the report shown once a CSV import is done or stopped
+pub fn get_csv_report(
+    profile: ImportProfile,
+    new_methods: &[String],
+    added: usize,
+    skipped: usize,
+    stopped: bool,
+    problems: &[CsvProblem],
+) -> String {
+    let mut report = if stopped {
+        format!(
+            "Stopped after importing {added} transactions from the {} export.",
+            profile.get_name()
+        )
+    } else {
+        format!(
+            "Imported {added} transactions from the {} export.",
+            profile.get_name()
+        )
+    };
+
+    report.push_str(&format!(
+        "\n{skipped} transactions were already imported and skipped."
+    ));
+
+    if !new_methods.is_empty() {
+        report.push_str(&format!("\nCreated tx methods: {}", new_methods.join(", ")));
+    }
+
+    if !problems.is_empty() {
+        report.push_str(&format!(
+            "\n\n{} lines or transactions were not imported:\n",
+            problems.len()
+        ));
+
+        for problem in problems.iter().take(REPORTED_PROBLEMS) {
+            report.push_str(&format!("\n{problem}"));
+        }
+
+        if problems.len() > REPORTED_PROBLEMS {
+            report.push_str(&format!(
+                "\n...and {} more",
+                problems.len() - REPORTED_PROBLEMS
+            ));
+        }
+    }
+
+    report
+}
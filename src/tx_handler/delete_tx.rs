@@ -1,8 +1,10 @@
-use crate::utility::{get_all_tx_methods, get_last_balance_id, get_last_balances};
+use crate::db::get_date_range;
+use crate::utility::{get_all_tx_methods, get_last_balance_id, get_last_balances, Money};
 use rusqlite::{Connection, Result as sqlResult};
 
 /// Updates the absolute final balance, balance data and deletes the selected transaction.
-/// Foreign key cascade takes care of the Changes data in the database.
+/// Foreign key cascade takes care of the Changes data in the database. The refund link of the
+/// transaction is removed with it so the expense it refunded no longer counts it
 pub fn delete_tx(id_num: usize, conn: &mut Connection) -> sqlResult<()> {
     let sp = conn.savepoint()?;
 
@@ -23,12 +25,12 @@ pub fn delete_tx(id_num: usize, conn: &mut Connection) -> sqlResult<()> {
     })?;
 
     // 2025-05-10
-    // take 2025 and subtract 2022 = 3, means the year number 3
+    // take 2025 and subtract the first year of the date range, 2022 = 3, means the year number 3
     // take 05 -> 5 -> 5th month. 5 + (3 * 12) =  the row of this month's balance on balance_all table
     // we are not subtracting 1 from month because balance_all table starts at 1
     let splitted = data[0].split('-').collect::<Vec<&str>>();
     let (year, month) = (
-        splitted[0].parse::<i32>().unwrap() - 2022,
+        splitted[0].parse::<i32>().unwrap() - get_date_range(&sp).start_year,
         splitted[1].parse::<i32>().unwrap(),
     );
 
@@ -48,11 +50,11 @@ pub fn delete_tx(id_num: usize, conn: &mut Connection) -> sqlResult<()> {
         to_method = from_to[1];
     }
 
-    let amount = &data[2].parse::<f64>().unwrap();
+    let amount = Money::parse(&data[2]).unwrap();
     let tx_type: &str = &data[3];
 
     // loop through all rows in the balance_all table from the deletion point and update balance
-    // basically there are 193 rows(at the time of writing) on balance_all table. each row = 1 month. if month 4 had balance of 100,
+    // basically there are total year * 12 + 1 rows on balance_all table. each row = 1 month. if month 4 had balance of 100,
     // month 5 will also have the balance of 100 if no tx was added on month 5.
     // if the tx deletion happens on row/month 5, that means month 4 balance is correct but from the month from
     // 5 to the final row needs to be deduct that amount.
@@ -73,26 +75,20 @@ pub fn delete_tx(id_num: usize, conn: &mut Connection) -> sqlResult<()> {
         );
 
         let current_month_balance = sp.query_row(&query, [], |row| {
-            let mut final_data: Vec<String> = Vec::new();
+            let mut final_data: Vec<Money> = Vec::new();
             for i in 0..tx_methods.len() {
-                let row_data: f64 = row.get(i)?;
-                final_data.push(row_data.to_string())
+                final_data.push(Money::from_f64(row.get(i)?))
             }
             Ok(final_data)
         })?;
 
-        let mut untouched = true;
-
-        for x in current_month_balance.iter() {
-            if x != "0" {
-                untouched = false;
-                break;
-            }
-        }
+        let untouched = current_month_balance
+            .iter()
+            .all(|balance| *balance == Money::ZERO);
 
         if untouched {
             target_id_num += 1;
-            if target_id_num == 193 {
+            if target_id_num == last_balance_id {
                 break;
             }
             continue;
@@ -101,32 +97,20 @@ pub fn delete_tx(id_num: usize, conn: &mut Connection) -> sqlResult<()> {
         let mut updated_month_balance = vec![];
 
         // add or subtract based on the tx type to the relevant method
-
-        // check the month balance as not zero because if it is 0, there was never any transaction
-        // done on that month
         for i in 0..tx_methods.len() {
-            if &tx_methods[i] == source && current_month_balance[i] != "0.00" {
-                let mut current_amount = current_month_balance[i].parse::<f64>().unwrap();
+            let mut current_amount = current_month_balance[i];
+            if &tx_methods[i] == source {
                 if tx_type == "Expense" {
                     current_amount += amount;
-                } else if tx_type == "Income" {
+                } else if tx_type == "Income" || tx_type == "Refund" {
                     current_amount -= amount;
                 }
-                updated_month_balance.push(format!("{:.2}", current_amount));
-            } else if tx_methods[i] == from_method && current_month_balance[i] != "0.00" {
-                let mut current_amount = current_month_balance[i].parse::<f64>().unwrap();
+            } else if tx_methods[i] == from_method {
                 current_amount += amount;
-                updated_month_balance.push(format!("{:.2}", current_amount));
-            } else if tx_methods[i] == to_method && current_month_balance[i] != "0.00" {
-                let mut current_amount = current_month_balance[i].parse::<f64>().unwrap();
+            } else if tx_methods[i] == to_method {
                 current_amount -= amount;
-                updated_month_balance.push(format!("{:.2}", current_amount));
-            } else {
-                updated_month_balance.push(format!(
-                    "{:.2}",
-                    current_month_balance[i].parse::<f64>().unwrap()
-                ));
             }
+            updated_month_balance.push(current_amount.to_stored_string());
         }
 
         let set_values = tx_methods
@@ -143,22 +127,22 @@ pub fn delete_tx(id_num: usize, conn: &mut Connection) -> sqlResult<()> {
 
         sp.execute(&balance_query, [])?;
 
-        // 193 is the absolute final balance which we don't need to modify
+        // the last row is the absolute final balance which we don't need to modify
         target_id_num += 1;
-        if target_id_num == 193 {
+        if target_id_num == last_balance_id {
             break;
         }
     }
 
-    // we need to update the 193 row with the latest balance.
-    // Based on the tx_type and method, edit the amount from the 193 row's balance
+    // we need to update the last row with the latest balance.
+    // Based on the tx_type and method, edit the amount from the last row's balance
     // we fetched earlier
     for i in 0..tx_methods.len() {
-        let mut current_balance = last_balance[i].parse::<f64>().unwrap();
+        let mut current_balance = Money::parse(&last_balance[i]).unwrap();
         if &tx_methods[i] == source && tx_type != "Transfer" {
             match tx_type {
                 "Expense" => current_balance += amount,
-                "Income" => current_balance -= amount,
+                "Income" | "Refund" => current_balance -= amount,
                 _ => {}
             }
         } else if tx_methods[i] == from_method && tx_type == "Transfer" {
@@ -166,7 +150,7 @@ pub fn delete_tx(id_num: usize, conn: &mut Connection) -> sqlResult<()> {
         } else if tx_methods[i] == to_method && tx_type == "Transfer" {
             current_balance -= amount;
         }
-        final_last_balance.push(format!("{:.2}", current_balance));
+        final_last_balance.push(current_balance.to_stored_string());
     }
 
     let del_query = format!("DELETE FROM tx_all WHERE id_num = {id_num}");
@@ -184,6 +168,8 @@ pub fn delete_tx(id_num: usize, conn: &mut Connection) -> sqlResult<()> {
 
     sp.execute(&last_balance_query, [])?;
     sp.execute(&del_query, [])?;
+    sp.execute("DELETE FROM refund_links WHERE id_num = ?", [id_num])?;
+    sp.execute("DELETE FROM external_ids WHERE id_num = ?", [id_num])?;
 
     sp.commit()?;
     Ok(())
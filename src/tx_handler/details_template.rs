@@ -0,0 +1,217 @@
+use crate::db::DetailsTemplate;
+
+/// A piece of a details template
+#[derive(Debug, PartialEq)]
+pub enum TemplatePart {
+    Text(String),
+    /// The name between the braces of a `{placeholder}` marker
+    Placeholder(String),
+}
+
+/// Splits a details template into plain text and `{placeholder}` markers. `{{` and `}}` become
+/// a single brace. A brace that does not start or end a marker is kept as it is
+///
+/// `"Fuel {liters}L"` becomes `[Text("Fuel "), Placeholder("liters"), Text("L")]`
+pub fn parse_details_template(template: &str) -> Vec<TemplatePart> {
+    let chars = template.chars().collect::<Vec<char>>();
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        match chars[index] {
+            '{' if chars.get(index + 1) == Some(&'{') => {
+                text.push('{');
+                index += 2;
+            }
+            '}' if chars.get(index + 1) == Some(&'}') => {
+                text.push('}');
+                index += 2;
+            }
+            '{' => {
+                // the marker ends at the next closing brace unless another one opens first
+                let closing = chars[index + 1..]
+                    .iter()
+                    .position(|c| *c == '}' || *c == '{')
+                    .map(|position| index + 1 + position)
+                    .filter(|position| chars[*position] == '}');
+
+                match closing {
+                    Some(closing) => {
+                        if !text.is_empty() {
+                            parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                        }
+                        let name = chars[index + 1..closing].iter().collect::<String>();
+                        parts.push(TemplatePart::Placeholder(name.trim().to_string()));
+                        index = closing + 1;
+                    }
+                    None => {
+                        text.push('{');
+                        index += 1;
+                    }
+                }
+            }
+            c => {
+                text.push(c);
+                index += 1;
+            }
+        }
+    }
+
+    if !text.is_empty() {
+        parts.push(TemplatePart::Text(text));
+    }
+
+    parts
+}
+
+/// Contains all data for the popup that inserts a details template into the details field.
+/// A template is picked from the list first, then a value is typed for each of its placeholders.
+/// A placeholder used more than once is asked for only once
+///
+/// placeholders: `["place", "price"]` of the picked template in the order they first appear
+///
+/// values: `["Cafe"]` typed so far
+pub struct DetailsTemplateData {
+    templates: Vec<DetailsTemplate>,
+    index: usize,
+    parts: Vec<TemplatePart>,
+    placeholders: Vec<String>,
+    values: Vec<String>,
+    input: String,
+    filling: bool,
+}
+
+impl DetailsTemplateData {
+    pub fn new(templates: Vec<DetailsTemplate>) -> Self {
+        DetailsTemplateData {
+            templates,
+            index: 0,
+            parts: Vec::new(),
+            placeholders: Vec::new(),
+            values: Vec::new(),
+            input: String::new(),
+            filling: false,
+        }
+    }
+
+    pub fn get_templates(&self) -> &Vec<DetailsTemplate> {
+        &self.templates
+    }
+
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    /// Selects the next template or the 1st one if at the final one
+    pub fn next(&mut self) {
+        if !self.templates.is_empty() {
+            self.index = (self.index + 1) % self.templates.len();
+        }
+    }
+
+    /// Selects the previous template or the final one if at the 1st one
+    pub fn previous(&mut self) {
+        if !self.templates.is_empty() {
+            if self.index > 0 {
+                self.index -= 1;
+            } else {
+                self.index = self.templates.len() - 1;
+            }
+        }
+    }
+
+    /// Picks the selected template and starts asking for its placeholders.
+    /// Returns the details right away if the template has no placeholders
+    pub fn select(&mut self) -> Option<String> {
+        let template = self.templates.get(self.index)?;
+
+        self.parts = parse_details_template(&template.text);
+        self.placeholders = Vec::new();
+
+        for part in self.parts.iter() {
+            if let TemplatePart::Placeholder(name) = part {
+                if !self.placeholders.contains(name) {
+                    self.placeholders.push(name.to_string());
+                }
+            }
+        }
+
+        self.values = Vec::new();
+        self.input = String::new();
+        self.filling = true;
+
+        self.get_details()
+    }
+
+    /// Returns whether a template was picked and its placeholders are being asked for
+    pub fn is_filling(&self) -> bool {
+        self.filling
+    }
+
+    /// Returns the name of the placeholder the value is being typed for
+    pub fn get_current_placeholder(&self) -> Option<&str> {
+        self.placeholders
+            .get(self.values.len())
+            .map(|name| name.as_str())
+    }
+
+    /// Returns the number of the current placeholder and the total placeholders
+    pub fn get_progress(&self) -> (usize, usize) {
+        (self.values.len() + 1, self.placeholders.len())
+    }
+
+    pub fn get_input(&self) -> &str {
+        &self.input
+    }
+
+    /// Adds a character to the typed value or removes the last one
+    pub fn edit_input(&mut self, to_add: Option<char>) {
+        match to_add {
+            Some(c) => self.input.push(c),
+            None => {
+                self.input.pop();
+            }
+        }
+    }
+
+    /// Saves the typed value for the current placeholder. Returns the details once
+    /// every placeholder has a value
+    pub fn confirm_value(&mut self) -> Option<String> {
+        if self.get_current_placeholder().is_some() {
+            self.values
+                .push(std::mem::take(&mut self.input).trim().to_string());
+        }
+        self.get_details()
+    }
+
+    /// Returns the template with the typed values. The placeholders without a value are shown as markers
+    pub fn get_preview(&self) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                TemplatePart::Text(text) => text.to_string(),
+                TemplatePart::Placeholder(name) => match self.get_value(name) {
+                    Some(value) => value.to_string(),
+                    None => format!("{{{name}}}"),
+                },
+            })
+            .collect()
+    }
+
+    /// Returns the final details if every placeholder has a value
+    fn get_details(&self) -> Option<String> {
+        if self.values.len() < self.placeholders.len() {
+            return None;
+        }
+        Some(self.get_preview())
+    }
+
+    fn get_value(&self, name: &str) -> Option<&str> {
+        self.placeholders
+            .iter()
+            .position(|placeholder| placeholder == name)
+            .and_then(|index| self.values.get(index))
+            .map(|value| value.as_str())
+    }
+}
@@ -0,0 +1,555 @@
+use crate::api::{add_transaction, NewTx};
+use crate::outputs::ApiError;
+use crate::page_handler::TaskProgress;
+use crate::utility::Money;
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Top level directives of a journal. They and the lines indented under them are skipped
+const SKIPPED_DIRECTIVES: [&str; 14] = [
+    "account",
+    "alias",
+    "apply",
+    "commodity",
+    "D",
+    "decimal-mark",
+    "end",
+    "include",
+    "P",
+    "payee",
+    "tag",
+    "Y",
+    "year",
+    "N",
+];
+
+/// The date formats a transaction header can start with
+const JOURNAL_DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d"];
+
+/// The transactions added between two progress updates. A running import can only stop between batches
+pub const IMPORT_BATCH_SIZE: usize = 25;
+
+/// The most problems listed on the import report. The rest are only counted
+pub const REPORTED_PROBLEMS: usize = 15;
+
+/// What an account of a journal becomes in Rex, decided by its top level name
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AccountKind {
+    /// Assets and liabilities, imported as tx methods
+    Method,
+    /// Expenses, imported as tags
+    Expense,
+    /// Income and revenue, imported as tags
+    Income,
+    Other,
+}
+
+/// A posting line of a journal transaction. The amount is None when it is left out
+/// to balance the transaction
+#[derive(Debug, PartialEq, Clone)]
+pub struct JournalPosting {
+    pub account: String,
+    pub amount: Option<Money>,
+}
+
+/// A transaction of a journal with the line number of its header. Dates are in YYYY-MM-DD format
+#[derive(Debug, PartialEq, Clone)]
+pub struct JournalEntry {
+    pub line: usize,
+    pub date: String,
+    pub payee: String,
+    pub postings: Vec<JournalPosting>,
+}
+
+/// A journal line or transaction that could not be imported
+#[derive(Debug, PartialEq, Clone)]
+pub struct JournalProblem {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl JournalProblem {
+    pub fn new(line: usize, reason: impl Into<String>) -> Self {
+        JournalProblem {
+            line,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for JournalProblem {
+    #[cfg(not(tarpaulin_include))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.reason)
+    }
+}
+
+/// Returns what the account becomes in Rex from its top level name
+///
+/// example: `Assets:Bank:Checking` => `AccountKind::Method`
+pub fn get_account_kind(account: &str) -> AccountKind {
+    let top_level = account.split(':').next().unwrap_or_default().trim();
+
+    match top_level.to_lowercase().as_str() {
+        "assets" | "asset" | "liabilities" | "liability" => AccountKind::Method,
+        "expenses" | "expense" => AccountKind::Expense,
+        "income" | "revenue" | "revenues" => AccountKind::Income,
+        _ => AccountKind::Other,
+    }
+}
+
+/// Parses a journal amount. Commodity symbols and comma digit grouping are removed.
+/// Returns None if there is no number in it
+///
+/// example: `$-1,250.50` => `Money(-1250500)`, `12 EUR` => `Money(12000)`
+pub fn parse_journal_amount(text: &str) -> Option<Money> {
+    let number = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() || ['.', '-', '+'].contains(c))
+        .collect::<String>();
+
+    if !number.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Money::parse(&number)
+}
+
+/// Parses the common subset of ledger and hledger journals. Transactions with a line that
+/// cannot be read are left out and every such line is collected instead of stopping
+pub fn parse_journal(text: &str) -> (Vec<JournalEntry>, Vec<JournalProblem>) {
+    let mut entries = Vec::new();
+    let mut problems = Vec::new();
+
+    // the transaction being read and whether any of its postings failed to parse
+    let mut current: Option<(JournalEntry, bool)> = None;
+    let mut skipping_block = false;
+    let mut in_comment_block = false;
+
+    let mut finish = |current: &mut Option<(JournalEntry, bool)>| {
+        if let Some((entry, broken)) = current.take() {
+            if !broken {
+                entries.push(entry);
+            }
+        }
+    };
+
+    for (index, raw_line) in text.lines().enumerate() {
+        let line_number = index + 1;
+
+        if in_comment_block {
+            if raw_line.trim() == "end comment" {
+                in_comment_block = false;
+            }
+            continue;
+        }
+
+        let line = strip_journal_comment(raw_line);
+
+        if line.trim().is_empty() {
+            // a blank line ends the transaction but a comment line within it does not
+            if raw_line.trim().is_empty() {
+                finish(&mut current);
+                skipping_block = false;
+            }
+            continue;
+        }
+
+        if raw_line.starts_with(' ') || raw_line.starts_with('\t') {
+            if skipping_block {
+                continue;
+            }
+
+            match current.as_mut() {
+                Some((entry, broken)) => match parse_posting(line) {
+                    Ok(posting) => entry.postings.push(posting),
+                    Err(reason) => {
+                        problems.push(JournalProblem::new(line_number, reason));
+                        *broken = true;
+                    }
+                },
+                None => problems.push(JournalProblem::new(
+                    line_number,
+                    "Posting without a transaction header",
+                )),
+            }
+            continue;
+        }
+
+        // any top level line ends the previous transaction
+        finish(&mut current);
+        skipping_block = false;
+
+        let first_word = line.split_whitespace().next().unwrap_or_default();
+
+        match line.chars().next() {
+            Some('#' | '%' | '|' | '*') => {}
+            // periodic and automated transactions do not add anything on their own
+            Some('~' | '=') => skipping_block = true,
+            Some(c) if c.is_ascii_digit() => match parse_header(line) {
+                Ok((date, payee)) => {
+                    let entry = JournalEntry {
+                        line: line_number,
+                        date,
+                        payee,
+                        postings: Vec::new(),
+                    };
+                    current = Some((entry, false));
+                }
+                Err(reason) => {
+                    problems.push(JournalProblem::new(line_number, reason));
+                    skipping_block = true;
+                }
+            },
+            _ if first_word == "comment" => in_comment_block = true,
+            _ if SKIPPED_DIRECTIVES.contains(&first_word) => skipping_block = true,
+            _ => {
+                problems.push(JournalProblem::new(
+                    line_number,
+                    format!("Could not read the line {}", line.trim()),
+                ));
+                skipping_block = true;
+            }
+        }
+    }
+
+    finish(&mut current);
+    (entries, problems)
+}
+
+/// Removes the `;` comment of a journal line along with the trailing whitespace
+fn strip_journal_comment(line: &str) -> &str {
+    match line.find(';') {
+        Some(index) => line[..index].trim_end(),
+        None => line.trim_end(),
+    }
+}
+
+/// Parses a `DATE [*|!] [(CODE)] PAYEE` line into the YYYY-MM-DD date and the payee
+fn parse_header(line: &str) -> Result<(String, String), String> {
+    let (date_text, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+    // the secondary date after = is not used
+    let date_text = date_text.split('=').next().unwrap_or_default();
+
+    let date = JOURNAL_DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date_text, format).ok())
+        .ok_or_else(|| format!("Could not read the date {date_text}. Dates need the year"))?;
+
+    let mut payee = rest.trim();
+    payee = payee
+        .strip_prefix(['*', '!'])
+        .map_or(payee, |stripped| stripped.trim_start());
+
+    if payee.starts_with('(') {
+        if let Some(index) = payee.find(')') {
+            payee = payee[index + 1..].trim_start();
+        }
+    }
+
+    Ok((date.format("%Y-%m-%d").to_string(), payee.to_string()))
+}
+
+/// Parses an indented `ACCOUNT  AMOUNT` line. The account and the amount are separated
+/// by a tab or at least two spaces
+fn parse_posting(line: &str) -> Result<JournalPosting, String> {
+    let mut text = line.trim();
+    text = text
+        .strip_prefix(['*', '!'])
+        .map_or(text, |stripped| stripped.trim_start());
+
+    let separator = [text.find("  "), text.find('\t')]
+        .into_iter()
+        .flatten()
+        .min();
+
+    let (account, amount_text) = match separator {
+        Some(index) => (text[..index].trim(), text[index..].trim()),
+        None => (text, ""),
+    };
+
+    if account.starts_with(['(', '[']) {
+        return Err(format!("Virtual posting {account} is not supported"));
+    }
+
+    if amount_text.is_empty() {
+        return Ok(JournalPosting {
+            account: account.to_string(),
+            amount: None,
+        });
+    }
+
+    // the balance assertion after = and the cost after @ are not used
+    let amount_text = amount_text
+        .split(['=', '@'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+
+    let amount = parse_journal_amount(amount_text)
+        .ok_or_else(|| format!("Could not read the amount {amount_text}"))?;
+
+    Ok(JournalPosting {
+        account: account.to_string(),
+        amount: Some(amount),
+    })
+}
+
+/// Returns the asset and liability accounts of the entries in the order they first appear.
+/// These are the accounts that need a tx method
+pub fn get_journal_accounts(entries: &[JournalEntry]) -> Vec<String> {
+    let mut accounts = Vec::new();
+
+    for posting in entries.iter().flat_map(|entry| &entry.postings) {
+        if get_account_kind(&posting.account) == AccountKind::Method
+            && !accounts.contains(&posting.account)
+        {
+            accounts.push(posting.account.clone());
+        }
+    }
+    accounts
+}
+
+/// Returns the tx method the account is mapped to without asking. Either the full account
+/// or its final part must match a tx method ignoring the case
+///
+/// example: `Assets:Cash` => `Cash`
+pub fn get_default_method(account: &str, tx_methods: &[String]) -> Option<String> {
+    let last_part = account.rsplit(':').next().unwrap_or_default().trim();
+
+    tx_methods
+        .iter()
+        .find(|method| {
+            method.eq_ignore_ascii_case(account.trim()) || method.eq_ignore_ascii_case(last_part)
+        })
+        .cloned()
+}
+
+/// Returns the tags of an expense or income account, every part after the top level one
+///
+/// example: `Expenses:Food:Groceries` => `Food, Groceries`
+fn get_account_tags(account: &str) -> String {
+    account
+        .split(':')
+        .skip(1)
+        .map(|part| part.trim())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join(", ")
+}
+
+/// Turns the entries into transactions with the line number of their header.
+/// A posting to an expense or income account becomes an Expense if money went to it and
+/// an Income otherwise. Two asset or liability postings become a Transfer.
+/// Entries with more than two postings are split into one transaction per expense or income
+/// posting if they only have a single asset or liability account. Every entry that cannot be
+/// turned into transactions is collected with the reason
+pub fn journal_to_txs(
+    entries: &[JournalEntry],
+    method_map: &HashMap<String, String>,
+) -> (Vec<(usize, NewTx)>, Vec<JournalProblem>) {
+    let mut txs = Vec::new();
+    let mut problems = Vec::new();
+
+    for entry in entries {
+        match entry_to_txs(entry, method_map) {
+            Ok(entry_txs) => txs.extend(entry_txs.into_iter().map(|tx| (entry.line, tx))),
+            Err(reason) => problems.push(JournalProblem::new(entry.line, reason)),
+        }
+    }
+
+    (txs, problems)
+}
+
+fn entry_to_txs(
+    entry: &JournalEntry,
+    method_map: &HashMap<String, String>,
+) -> Result<Vec<NewTx>, String> {
+    if entry.postings.len() < 2 {
+        return Err("A transaction needs at least two postings".to_string());
+    }
+
+    let known_total = entry
+        .postings
+        .iter()
+        .filter_map(|posting| posting.amount)
+        .fold(Money::ZERO, |total, amount| total + amount);
+
+    let missing_amounts = entry
+        .postings
+        .iter()
+        .filter(|posting| posting.amount.is_none())
+        .count();
+
+    if missing_amounts > 1 {
+        return Err("Only one posting can leave out its amount".to_string());
+    }
+
+    if missing_amounts == 0 && known_total != Money::ZERO {
+        return Err(format!("The postings are off balance by {known_total}"));
+    }
+
+    // the posting without an amount takes whatever balances the transaction
+    let postings = entry
+        .postings
+        .iter()
+        .map(|posting| {
+            (
+                posting.account.as_str(),
+                posting.amount.unwrap_or(-known_total),
+            )
+        })
+        .collect::<Vec<(&str, Money)>>();
+
+    let mut method_postings = Vec::new();
+    let mut tag_postings = Vec::new();
+
+    for (account, amount) in postings {
+        match get_account_kind(account) {
+            AccountKind::Method => {
+                let method = method_map
+                    .get(account)
+                    .ok_or_else(|| format!("Account {account} is not mapped to a tx method"))?;
+                method_postings.push((method.to_string(), amount));
+            }
+            AccountKind::Expense | AccountKind::Income => tag_postings.push((account, amount)),
+            AccountKind::Other => {
+                return Err(format!(
+                    "Account {account} is not an asset, liability, expense or income account"
+                ))
+            }
+        }
+    }
+
+    let new_tx = |from_method: &str, to_method: &str, amount: Money, tx_type: &str, tags| NewTx {
+        date: entry.date.clone(),
+        details: entry.payee.clone(),
+        from_method: from_method.to_string(),
+        to_method: to_method.to_string(),
+        amount: amount.abs().to_stored_string(),
+        tx_type: tx_type.to_string(),
+        tags,
+        refund_of: None,
+        external_id: None,
+    };
+
+    match (method_postings.as_slice(), tag_postings.is_empty()) {
+        ([(first, first_amount), (second, _)], true) => {
+            // money leaves the account with the negative amount
+            let (from, to) = if *first_amount < Money::ZERO {
+                (first, second)
+            } else {
+                (second, first)
+            };
+            Ok(vec![new_tx(
+                from,
+                to,
+                *first_amount,
+                "Transfer",
+                String::new(),
+            )])
+        }
+        ([(method, _)], false) => Ok(tag_postings
+            .into_iter()
+            .map(|(account, amount)| {
+                let tx_type = if amount > Money::ZERO {
+                    "Expense"
+                } else {
+                    "Income"
+                };
+                new_tx(method, "", amount, tx_type, get_account_tags(account))
+            })
+            .collect()),
+        ([], _) => Err("A transaction needs an asset or liability account".to_string()),
+        _ => Err(format!(
+            "A transaction with {} postings can only have a single asset or liability account",
+            entry.postings.len()
+        )),
+    }
+}
+
+/// Adds the transactions one by one with the same checks as the Add Tx page. Transactions that
+/// fail are collected with the line number of their entry instead of stopping the import.
+/// Returns the number of added transactions
+pub fn import_journal_txs(
+    txs: &[(usize, NewTx)],
+    conn: &mut Connection,
+) -> (usize, Vec<JournalProblem>) {
+    let mut added = 0;
+    let mut problems = Vec::new();
+
+    for (line, tx) in txs {
+        match add_transaction(tx, conn) {
+            Ok(_) => added += 1,
+            Err(ApiError::InvalidTx(errors)) => problems.push(JournalProblem::new(
+                *line,
+                errors.to_string().replace('\n', ". "),
+            )),
+            Err(e) => problems.push(JournalProblem::new(*line, e.to_string())),
+        }
+    }
+
+    (added, problems)
+}
+
+/// Imports the transactions in batches as a background task, sending the progress after every batch.
+/// Stops before the next batch once the task is cancelled. Returns the report of the import
+pub fn import_journal_task(
+    txs: &[(usize, NewTx)],
+    mut problems: Vec<JournalProblem>,
+    progress: &TaskProgress,
+    conn: &mut Connection,
+) -> String {
+    let mut added = 0;
+    let mut done = 0;
+
+    progress.update(done, txs.len());
+
+    for batch in txs.chunks(IMPORT_BATCH_SIZE) {
+        if progress.is_cancelled() {
+            break;
+        }
+
+        let (batch_added, batch_problems) = import_journal_txs(batch, conn);
+        added += batch_added;
+        done += batch.len();
+        problems.extend(batch_problems);
+
+        progress.update(done, txs.len());
+    }
+
+    problems.sort_by_key(|problem| problem.line);
+    get_journal_report(added, done < txs.len(), &problems)
+}
+
+/// Returns the text of the report shown once a journal import is done or stopped
+pub fn get_journal_report(added: usize, stopped: bool, problems: &[JournalProblem]) -> String {
+    let mut report = if stopped {
+        format!("Stopped after importing {added} transactions from the journal.")
+    } else {
+        format!("Imported {added} transactions from the journal.")
+    };
+
+    if !problems.is_empty() {
+        report.push_str(&format!(
+            "\n\n{} lines or transactions were not imported:\n",
+            problems.len()
+        ));
+
+        for problem in problems.iter().take(REPORTED_PROBLEMS) {
+            report.push_str(&format!("\n{problem}"));
+        }
+
+        if problems.len() > REPORTED_PROBLEMS {
+            report.push_str(&format!(
+                "\n...and {} more",
+                problems.len() - REPORTED_PROBLEMS
+            ));
+        }
+    }
+
+    report
+}
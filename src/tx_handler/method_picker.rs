@@ -0,0 +1,66 @@
+use crate::page_handler::TxTab;
+
+/// Contains all data for the popup that picks a tx method when the typed one is about as close
+/// to several tx methods
+///
+/// candidates: `["Visa Card", "Virtual Card"]` closest first
+pub struct MethodPickerData {
+    tab: TxTab,
+    typed: String,
+    candidates: Vec<String>,
+    index: usize,
+}
+
+impl MethodPickerData {
+    /// Creates an instance of the struct for the method field of the tab
+    pub fn new(tab: TxTab, typed: &str, candidates: Vec<String>) -> Self {
+        MethodPickerData {
+            tab,
+            typed: typed.trim().to_string(),
+            candidates,
+            index: 0,
+        }
+    }
+
+    /// Returns the tab of the field the picked method is written to
+    pub fn get_tab(&self) -> TxTab {
+        self.tab
+    }
+
+    pub fn get_typed(&self) -> &str {
+        &self.typed
+    }
+
+    pub fn get_candidates(&self) -> &Vec<String> {
+        &self.candidates
+    }
+
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the selected tx method
+    pub fn get_selected(&self) -> Option<&str> {
+        self.candidates
+            .get(self.index)
+            .map(|method| method.as_str())
+    }
+
+    /// Selects the next tx method or the 1st one if at the final one
+    pub fn next(&mut self) {
+        if !self.candidates.is_empty() {
+            self.index = (self.index + 1) % self.candidates.len();
+        }
+    }
+
+    /// Selects the previous tx method or the final one if at the 1st one
+    pub fn previous(&mut self) {
+        if !self.candidates.is_empty() {
+            if self.index > 0 {
+                self.index -= 1;
+            } else {
+                self.index = self.candidates.len() - 1;
+            }
+        }
+    }
+}
@@ -1,7 +1,31 @@
 mod add_tx;
+mod balance_date;
+mod calendar;
+mod csv_import;
 mod delete_tx;
+mod details_template;
+mod journal;
+mod method_picker;
+mod reminder;
+mod repeat_tx;
+mod tag_edit;
 mod tx_data;
+mod tx_deletion;
+mod tx_details;
+mod tx_picker;
 
-pub use add_tx::add_tx;
+pub use add_tx::{add_tx, add_txs, insert_tx};
+pub use balance_date::BalanceDateData;
+pub use calendar::{get_month_weeks, CalendarData};
+pub use csv_import::*;
 pub use delete_tx::delete_tx;
+pub use details_template::*;
+pub use journal::*;
+pub use method_picker::MethodPickerData;
+pub use reminder::ReminderData;
+pub use repeat_tx::RepeatTxData;
+pub use tag_edit::TagEditData;
 pub use tx_data::*;
+pub use tx_deletion::TxDeletionData;
+pub use tx_details::TxDetailsData;
+pub use tx_picker::TxPickerData;
@@ -0,0 +1,75 @@
+use crate::db::{dismiss_reminder, get_pending_reminders, TxTemplate};
+use crate::utility::{Clock, SystemClock};
+use rusqlite::Connection;
+
+/// Contains all data for the popup that lists the pending template reminders of the current month
+pub struct ReminderData {
+    reminders: Vec<TxTemplate>,
+    month: String,
+    index: usize,
+}
+
+impl ReminderData {
+    /// Creates an instance of the struct with the pending reminders of today's month
+    pub fn new(conn: &Connection) -> Self {
+        Self::new_with_clock(&SystemClock, conn)
+    }
+
+    /// Creates an instance of the struct with the pending reminders of the clock's month
+    pub fn new_with_clock(clock: &impl Clock, conn: &Connection) -> Self {
+        let today = clock.today();
+
+        ReminderData {
+            reminders: get_pending_reminders(today, conn),
+            month: today.format("%Y-%m").to_string(),
+            index: 0,
+        }
+    }
+
+    pub fn get_reminders(&self) -> &Vec<TxTemplate> {
+        &self.reminders
+    }
+
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reminders.is_empty()
+    }
+
+    /// Returns the currently selected reminder
+    pub fn get_selected(&self) -> Option<&TxTemplate> {
+        self.reminders.get(self.index)
+    }
+
+    /// Selects the next reminder or the 1st one if at the final reminder
+    pub fn next(&mut self) {
+        if !self.reminders.is_empty() {
+            self.index = (self.index + 1) % self.reminders.len();
+        }
+    }
+
+    /// Selects the previous reminder or the final one if at the 1st reminder
+    pub fn previous(&mut self) {
+        if !self.reminders.is_empty() {
+            if self.index > 0 {
+                self.index -= 1;
+            } else {
+                self.index = self.reminders.len() - 1;
+            }
+        }
+    }
+
+    /// Dismisses the selected reminder for the current month and removes it from the list
+    pub fn dismiss_selected(&mut self, conn: &Connection) {
+        if let Some(template) = self.reminders.get(self.index) {
+            if dismiss_reminder(template.id_num, &self.month, conn).is_ok() {
+                self.reminders.remove(self.index);
+                if self.index > 0 && self.index >= self.reminders.len() {
+                    self.index -= 1;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use crate::db::get_date_range;
+use crate::outputs::{TxUpdateError, VerifyingOutput};
+use crate::tx_handler::add_txs;
+use crate::utility::traits::DataVerifier;
+use crate::utility::{
+    add_char_to, check_duplicate_tx, get_date_format, get_txs_by_date, Clock, SystemClock,
+};
+use chrono::Duration;
+use rusqlite::Connection;
+
+/// Contains all data for the popup that repeats the transactions of a past day with today's date
+pub struct RepeatTxData {
+    date: String,
+    today: String,
+    txs: Vec<Vec<String>>,
+    selected: Vec<bool>,
+    duplicates: Vec<bool>,
+    index: usize,
+    current_index: usize,
+    editing_date: bool,
+    status: String,
+}
+
+impl DataVerifier for RepeatTxData {}
+
+impl Default for RepeatTxData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RepeatTxData {
+    /// Creates an instance of the struct with the source date set to yesterday
+    pub fn new() -> Self {
+        Self::new_with_clock(&SystemClock)
+    }
+
+    /// Creates an instance of the struct with the source date set to the day before the clock's date
+    pub fn new_with_clock(clock: &impl Clock) -> Self {
+        let today = clock.today();
+        let yesterday = (today - Duration::days(1)).format("%Y-%m-%d").to_string();
+
+        RepeatTxData {
+            current_index: yesterday.len(),
+            date: yesterday,
+            today: today.format("%Y-%m-%d").to_string(),
+            txs: Vec::new(),
+            selected: Vec::new(),
+            duplicates: Vec::new(),
+            index: 0,
+            editing_date: true,
+            status: String::new(),
+        }
+    }
+
+    pub fn get_date(&self) -> &str {
+        &self.date
+    }
+
+    pub fn get_txs(&self) -> &Vec<Vec<String>> {
+        &self.txs
+    }
+
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    pub fn get_status(&self) -> &str {
+        &self.status
+    }
+
+    pub fn is_editing_date(&self) -> bool {
+        self.editing_date
+    }
+
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selected[index]
+    }
+
+    pub fn is_duplicate(&self, index: usize) -> bool {
+        self.duplicates[index]
+    }
+
+    /// Returns the amount of selected transactions that already exist today
+    pub fn selected_duplicates(&self) -> usize {
+        self.selected
+            .iter()
+            .zip(self.duplicates.iter())
+            .filter(|(selected, duplicate)| **selected && **duplicate)
+            .count()
+    }
+
+    /// Insert or remove from the source date field
+    pub fn edit_date(&mut self, to_add: Option<char>) {
+        add_char_to(to_add, &mut self.current_index, &mut self.date);
+    }
+
+    /// Goes back to editing the source date
+    pub fn start_editing_date(&mut self) {
+        self.editing_date = true;
+        self.status.clear();
+    }
+
+    /// Verifies the source date and loads the transactions of that day. Transactions that
+    /// already exist today are marked as duplicates and are not selected by default.
+    pub fn load_txs(&mut self, conn: &Connection) {
+        let mut user_date = self.date.clone();
+        let date_format = get_date_format(conn);
+        let status = self.verify_date(&mut user_date, &get_date_range(conn), &date_format);
+        self.date = user_date;
+        self.current_index = self.date.len();
+
+        if let VerifyingOutput::NotAccepted(_) | VerifyingOutput::Nothing(_) = status {
+            self.status = status.to_string();
+            return;
+        }
+
+        let txs = get_txs_by_date(&date_format.to_iso(&self.date), conn);
+
+        if txs.is_empty() {
+            self.status = format!("Date: No transactions found on {}", self.date);
+            return;
+        }
+
+        self.duplicates = txs
+            .iter()
+            .map(|tx| check_duplicate_tx(&self.today, &tx[1], &tx[2], &tx[3], &tx[4], &tx[5], conn))
+            .collect();
+        self.selected = self.duplicates.iter().map(|duplicate| !duplicate).collect();
+        self.txs = txs;
+        self.index = 0;
+        self.editing_date = false;
+        self.status.clear();
+    }
+
+    /// Selects the next transaction or the 1st one if at the final transaction
+    pub fn next(&mut self) {
+        if !self.txs.is_empty() {
+            self.index = (self.index + 1) % self.txs.len();
+        }
+    }
+
+    /// Selects the previous transaction or the final one if at the 1st transaction
+    pub fn previous(&mut self) {
+        if !self.txs.is_empty() {
+            if self.index > 0 {
+                self.index -= 1;
+            } else {
+                self.index = self.txs.len() - 1;
+            }
+        }
+    }
+
+    /// Checks or unchecks the currently selected transaction
+    pub fn toggle_selected(&mut self) {
+        if let Some(selected) = self.selected.get_mut(self.index) {
+            *selected = !*selected;
+        }
+    }
+
+    /// Adds all the checked transactions with today's date in a single batch.
+    /// Returns the amount of added transactions.
+    pub fn add_selected_txs(&mut self, conn: &mut Connection) -> Result<usize, String> {
+        let to_add = self
+            .txs
+            .iter()
+            .zip(self.selected.iter())
+            .filter(|(_, selected)| **selected)
+            .map(|(tx, _)| {
+                let mut tx = tx.to_owned();
+                tx[0] = self.today.to_string();
+                tx
+            })
+            .collect::<Vec<Vec<String>>>();
+
+        if to_add.is_empty() {
+            self.status = "Repeat: No transactions are selected".to_string();
+            return Err(self.status.to_string());
+        }
+
+        match add_txs(&to_add, conn) {
+            Ok(_) => Ok(to_add.len()),
+            Err(e) => {
+                self.status = TxUpdateError::FailedAddTx(e).to_string();
+                Err(self.status.to_string())
+            }
+        }
+    }
+}
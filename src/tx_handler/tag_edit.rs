@@ -0,0 +1,147 @@
+use crate::db::{get_tag_limits, update_tx_tags};
+use crate::outputs::{NAType, VerifyingOutput};
+use crate::utility::traits::DataVerifier;
+use crate::utility::{add_char_to, get_all_tags};
+use rusqlite::Connection;
+
+/// Contains all data for the popup that edits the tags of a single transaction from the Home page
+///
+/// unknown_tags: `["Vacation", "Hotel"]` tags that were typed but do not exist yet.
+/// previous_tags keeps the typed text until the user decides whether to create them
+pub struct TagEditData {
+    id_num: i32,
+    details: String,
+    tags: String,
+    current_index: usize,
+    status: String,
+    previous_tags: String,
+    unknown_tags: Vec<String>,
+}
+
+impl DataVerifier for TagEditData {}
+
+impl TagEditData {
+    /// Creates an instance of the struct for the transaction. Unknown tags start as an empty field
+    pub fn new(id_num: i32, details: &str, tags: &str) -> Self {
+        let tags = if tags == "Unknown" {
+            String::new()
+        } else {
+            tags.to_string()
+        };
+
+        TagEditData {
+            id_num,
+            details: details.to_string(),
+            current_index: tags.len(),
+            tags,
+            status: String::new(),
+            previous_tags: String::new(),
+            unknown_tags: Vec::new(),
+        }
+    }
+
+    pub fn get_details(&self) -> &str {
+        &self.details
+    }
+
+    pub fn get_tags(&self) -> &str {
+        &self.tags
+    }
+
+    pub fn get_current_index(&self) -> usize {
+        self.current_index
+    }
+
+    pub fn get_status(&self) -> &str {
+        &self.status
+    }
+
+    pub fn get_unknown_tags(&self) -> &Vec<String> {
+        &self.unknown_tags
+    }
+
+    /// Returns whether the last save found tags that need to be created first
+    pub fn has_unknown_tags(&self) -> bool {
+        !self.unknown_tags.is_empty()
+    }
+
+    /// Returns the question asked before creating the unknown tags
+    pub fn get_unknown_tags_prompt(&self) -> String {
+        let tags = self
+            .unknown_tags
+            .iter()
+            .map(|tag| format!("'{tag}'"))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        if self.unknown_tags.len() == 1 {
+            format!("Tag {tags} doesn't exist. Create it? [Y/N]")
+        } else {
+            format!("Tags {tags} don't exist. Create them? [Y/N]")
+        }
+    }
+
+    /// Insert or remove from the tags field
+    pub fn edit_tags(&mut self, to_add: Option<char>) {
+        add_char_to(to_add, &mut self.current_index, &mut self.tags);
+    }
+
+    /// Saves the tags if all of them already exist. Otherwise the status gets set
+    /// and the field is left with only the accepted tags. Tags that do not exist are kept
+    /// aside to be created or discarded
+    pub fn save_tags(&mut self, conn: &mut Connection) -> Result<(), String> {
+        let all_tags = get_all_tags(conn);
+        self.previous_tags = self.tags.clone();
+        self.save_known_tags(&all_tags, conn)
+    }
+
+    /// Creates the unknown tags by saving them with the previously typed tags
+    pub fn create_unknown_tags(&mut self, conn: &mut Connection) -> Result<(), String> {
+        let mut known_tags = get_all_tags(conn);
+        known_tags.append(&mut self.unknown_tags);
+
+        self.tags = self.previous_tags.clone();
+        self.save_known_tags(&known_tags, conn)
+    }
+
+    /// Brings back the previously typed tags without creating the unknown tags
+    pub fn discard_unknown_tags(&mut self) {
+        self.unknown_tags.clear();
+        self.tags = self.previous_tags.clone();
+        self.current_index = self.tags.len();
+        self.status.clear();
+    }
+
+    /// Verifies the tags against the known tags and saves them if accepted
+    fn save_known_tags(
+        &mut self,
+        known_tags: &[String],
+        conn: &mut Connection,
+    ) -> Result<(), String> {
+        let mut tags = self.tags.clone();
+        let status = self.verify_tags_known(&mut tags, known_tags, &get_tag_limits(conn));
+        self.tags = tags;
+        self.current_index = self.tags.len();
+
+        self.unknown_tags = match &status {
+            VerifyingOutput::NotAccepted(NAType::UnknownTags(unknown)) => unknown.clone(),
+            _ => Vec::new(),
+        };
+
+        if let VerifyingOutput::NotAccepted(_) | VerifyingOutput::Nothing(_) = status {
+            self.status = status.to_string();
+            return Err(self.status.clone());
+        }
+
+        match update_tx_tags(self.id_num, &self.tags, conn) {
+            Ok(()) => {
+                self.status.clear();
+                Ok(())
+            }
+            Err(e) => {
+                self.status = e.to_string();
+                Err(self.status.clone())
+            }
+        }
+    }
+}
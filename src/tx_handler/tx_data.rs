@@ -1,16 +1,25 @@
+use crate::api::{add_transaction, NewTx};
+use crate::db::{
+    get_amount_step, get_bool_setting, get_date_range, get_details_max_length, set_refund_link,
+    TxTemplate,
+};
 use crate::outputs::{
-    CheckingError, ComparisonType, NAType, StepType, SteppingError, TxType, TxUpdateError,
-    VerifyingOutput,
+    AmountStepSize, ApiError, CheckingError, CheckingErrors, ComparisonType, DateStepSize, NAType,
+    StepType, SteppingError, TxType, TxUpdateError, VerifyingOutput,
 };
-use crate::page_handler::TxTab;
+use crate::page_handler::{NameCache, TxTab};
 use crate::tx_handler::{add_tx, delete_tx};
 use crate::utility::traits::{AutoFiller, DataVerifier, FieldStepper};
 use crate::utility::{
-    add_char_to, check_comparison, get_all_tx_methods, get_last_balances, get_search_data,
+    add_char_to, check_comparison, format_amount, get_amount_format, get_amount_preview,
+    get_date_format, get_method_balance, get_minor_units, get_refund_original,
+    get_refundable_amount, get_search_data, get_single_line, get_tag_bounds, get_tx_by_id,
+    is_amount_expression, Clock, DateFormat, Money, SystemClock,
 };
-use chrono::prelude::Local;
+use chrono::NaiveDate;
 use rusqlite::Connection;
 use std::cmp::Ordering;
+use thousands::Separable;
 
 /// Contains all data for a Transaction to work
 pub struct TxData {
@@ -22,10 +31,14 @@ pub struct TxData {
     tx_type: String,
     tags: String,
     tx_status: Vec<String>,
+    invalid_fields: Vec<TxTab>,
+    original_tx: Vec<(TxTab, String)>,
     editing_tx: bool,
     id_num: i32,
     current_index: usize,
     autofill: String,
+    tag_prefix: Option<String>,
+    refund_of: Option<i32>,
 }
 
 impl DataVerifier for TxData {}
@@ -34,14 +47,23 @@ impl AutoFiller for TxData {}
 
 impl FieldStepper for TxData {}
 
+impl Default for TxData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TxData {
     /// Creates an instance of the struct however the date field is
     /// edited with the current local date of the device.
     pub fn new() -> Self {
-        let current_date = Local::now().to_string();
-        let formatted_current_date = &current_date[0..10];
+        Self::new_with_clock(&SystemClock)
+    }
+
+    /// Creates an instance of the struct with the date field set to the clock's date
+    pub fn new_with_clock(clock: &impl Clock) -> Self {
         TxData {
-            date: formatted_current_date.to_string(),
+            date: clock.today().format("%Y-%m-%d").to_string(),
             details: String::new(),
             from_method: String::new(),
             to_method: String::new(),
@@ -49,15 +71,20 @@ impl TxData {
             tx_type: String::new(),
             tags: String::new(),
             tx_status: Vec::new(),
+            invalid_fields: Vec::new(),
+            original_tx: Vec::new(),
             editing_tx: false,
             id_num: 0,
             current_index: 0,
             autofill: String::new(),
+            tag_prefix: None,
+            refund_of: None,
         }
     }
 
     /// Used to adding custom pre-defined data inside the widgets of Add Transaction Page.
     /// Currently used on Editing transaction.
+    #[allow(clippy::too_many_arguments)]
     pub fn custom(
         date: &str,
         details: &str,
@@ -78,7 +105,7 @@ impl TxData {
             String::new()
         };
 
-        TxData {
+        let mut tx_data = TxData {
             date: new_date,
             details: details.to_string(),
             from_method: from_method.to_string(),
@@ -87,13 +114,155 @@ impl TxData {
             tx_type: tx_type.to_string(),
             tags: tags.to_string(),
             tx_status: Vec::new(),
+            invalid_fields: Vec::new(),
+            original_tx: Vec::new(),
             editing_tx: true,
             id_num,
             current_index: 0,
             autofill: String::new(),
+            tag_prefix: None,
+            refund_of: None,
+        };
+
+        // keep the values of the tx being edited to show what was changed
+        tx_data.original_tx = tx_data.get_field_values();
+        tx_data
+    }
+
+    /// Returns the name and value of every field in the order they are shown in a diff
+    fn get_field_values(&self) -> Vec<(TxTab, String)> {
+        vec![
+            (TxTab::Date, self.date.to_string()),
+            (TxTab::Details, self.details.to_string()),
+            (TxTab::TxType, self.tx_type.to_string()),
+            (TxTab::FromMethod, self.from_method.to_string()),
+            (TxTab::ToMethod, self.to_method.to_string()),
+            (TxTab::Amount, self.amount.to_string()),
+            (TxTab::Tags, self.tags.to_string()),
+        ]
+    }
+
+    /// Returns the original value of the field if the tx is being edited and the field was changed
+    pub fn get_original_value(&self, field: &TxTab) -> Option<&str> {
+        let current_values = self.get_field_values();
+
+        self.original_tx
+            .iter()
+            .zip(current_values.iter())
+            .find(|((tab, old), (_, new))| tab == field && old != new)
+            .map(|((_, old), _)| old.as_str())
+    }
+
+    /// Returns a line for every field that was changed while editing in `Field: old → new` format.
+    /// Unchanged fields are left out
+    pub fn get_changes(&self) -> Vec<String> {
+        self.original_tx
+            .iter()
+            .zip(self.get_field_values())
+            .filter(|((_, old), (_, new))| old != new)
+            .map(|((field, old), (_, new))| {
+                let name = match field {
+                    TxTab::Date => "Date",
+                    TxTab::Details => "Details",
+                    TxTab::TxType => "Tx Type",
+                    TxTab::FromMethod if self.tx_type == "Transfer" => "From Method",
+                    TxTab::FromMethod => "Tx Method",
+                    TxTab::ToMethod => "To Method",
+                    TxTab::Amount => "Amount",
+                    TxTab::Tags => "Tags",
+                    TxTab::Nothing => "",
+                };
+                let old = if old.is_empty() { "-" } else { old };
+                let new = if new.is_empty() { "-".to_string() } else { new };
+                format!("{name}: {old} → {new}")
+            })
+            .collect()
+    }
+
+    /// Creates an instance of the struct for a new transaction with the given data. Date is in YYYY-MM-DD format
+    pub fn new_with_data(
+        date: &str,
+        details: &str,
+        from_method: &str,
+        to_method: &str,
+        amount: &str,
+        tx_type: &str,
+        tags: &str,
+    ) -> Self {
+        let mut tx_data = TxData::new();
+        tx_data.date = date.to_string();
+        tx_data.details = details.to_string();
+        tx_data.from_method = from_method.to_string();
+        tx_data.to_method = to_method.to_string();
+        tx_data.amount = amount.to_string();
+        tx_data.tx_type = tx_type.to_string();
+        tx_data.tags = tags.to_string();
+        tx_data
+    }
+
+    /// Creates an instance of the struct for a new transaction with the data of the template
+    /// and the current local date of the device.
+    pub fn from_template(template: &TxTemplate) -> Self {
+        let mut tx_data = TxData::new();
+        tx_data.details = template.details.to_string();
+        tx_data.from_method = template.tx_method.to_string();
+        tx_data.amount = template.amount.to_string();
+        tx_data.tx_type = template.tx_type.to_string();
+        tx_data.tags = template.tags.to_string();
+        tx_data
+    }
+
+    /// Creates an instance of the struct for a refund of the expense with the current local
+    /// date of the device. The refund goes back to the tx method of the expense with its tags
+    /// and the amount is set to what is left to refund
+    pub fn new_refund(original_id: i32, original: &[String], amount: &str) -> Self {
+        let mut tx_data = TxData::new();
+        tx_data.details = format!("Refund: {}", original[1]);
+        tx_data.from_method = original[2].to_string();
+        tx_data.amount = amount.to_string();
+        tx_data.tx_type = "Refund".to_string();
+        tx_data.tags = original[5].to_string();
+        tx_data.refund_of = Some(original_id);
+        tx_data
+    }
+
+    /// Moves the date field to the clock's date if it is still set to the previous day.
+    /// Used when the day changes while the app is running
+    pub fn roll_date(
+        &mut self,
+        previous_day: NaiveDate,
+        clock: &impl Clock,
+        date_format: &DateFormat,
+    ) {
+        if date_format.to_iso(&self.date) == previous_day.format("%Y-%m-%d").to_string() {
+            self.date = date_format.format_date(clock.today());
+        }
+    }
+
+    /// Rewrites the date field in the given format. The date of an edited transaction
+    /// is rewritten as well so it does not show up as a change
+    pub fn localize_date(&mut self, date_format: &DateFormat) {
+        self.date = date_format.localize(&self.date);
+
+        for (field, value) in self.original_tx.iter_mut() {
+            if field == &TxTab::Date {
+                *value = date_format.localize(value);
+            }
         }
     }
 
+    /// Replaces the date field with a picked date
+    pub fn set_date(&mut self, date: &str) {
+        self.date = date.to_string();
+        self.invalid_fields.retain(|field| field != &TxTab::Date);
+        self.go_current_index(&TxTab::Date);
+    }
+
+    /// Returns the date field in YYYY-MM-DD format as it is saved in the database
+    pub fn get_iso_date(&self, conn: &Connection) -> String {
+        get_date_format(conn).to_iso(&self.date)
+    }
+
     /// Returns all the data saved
     pub fn get_all_texts(&self) -> Vec<&str> {
         vec![
@@ -108,7 +277,8 @@ impl TxData {
         ]
     }
 
-    fn get_tx_method(&self) -> String {
+    /// Returns the tx method as saved in the database. Transfers are in `From to To` format
+    pub fn get_tx_method(&self) -> String {
         if self.tx_type == "Transfer" {
             format!("{} to {}", self.from_method, self.to_method)
         } else {
@@ -116,14 +286,168 @@ impl TxData {
         }
     }
 
+    /// Returns the text of the From or To Method field
+    pub fn get_method(&self, tab: &TxTab) -> &str {
+        match tab {
+            TxTab::ToMethod => &self.to_method,
+            _ => &self.from_method,
+        }
+    }
+
+    /// Replaces the From or To Method field with a picked tx method
+    pub fn set_method(&mut self, tab: &TxTab, method: &str) {
+        match tab {
+            TxTab::ToMethod => self.to_method = method.to_string(),
+            _ => self.from_method = method.to_string(),
+        }
+        self.invalid_fields.retain(|field| field != tab);
+        self.go_current_index(tab);
+    }
+
     pub fn get_tx_status(&self) -> &Vec<String> {
         &self.tx_status
     }
 
+    /// Returns whether an existing transaction is being edited
+    pub fn is_editing(&self) -> bool {
+        self.editing_tx
+    }
+
+    /// Returns a summary of the transaction to be shown before saving it.
+    /// The fields must be checked with `check_all_fields` first.
+    pub fn get_summary(&self) -> String {
+        let action = if self.editing_tx {
+            "Updating transaction"
+        } else {
+            "Adding transaction"
+        };
+
+        let method = if self.tx_type == "Transfer" {
+            format!("from {} to {}", self.from_method, self.to_method)
+        } else {
+            format!("from {}", self.from_method)
+        };
+
+        let mut summary = format!(
+            "{action}\n{} · {} · {}",
+            self.date,
+            self.tx_type,
+            self.amount.separate_with_commas()
+        );
+
+        if !self.details.is_empty() {
+            summary.push_str(&format!(" · {}", self.details));
+        }
+
+        summary.push_str(&format!(" · {method}\nTags: {}", self.tags));
+
+        let changes = self.get_changes();
+        if !changes.is_empty() {
+            summary.push_str(&format!("\n\nChanges:\n{}", changes.join("\n")));
+        }
+        summary
+    }
+
+    /// Checks whether a summary should be shown before saving based on the confirmation
+    /// mode (always/threshold/never) and the amount threshold
+    pub fn needs_confirmation(&self, mode: &str, threshold: f64) -> bool {
+        match mode {
+            "always" => true,
+            "threshold" => match self.amount.parse::<f64>() {
+                Ok(amount) => amount > threshold,
+                Err(_) => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns one line for every tag of the tx that has an amount bound the amount is outside of.
+    /// Tags without a bound are not checked
+    pub fn get_bound_warnings(&self, conn: &Connection) -> Vec<String> {
+        let amount = match self.amount.parse::<f64>() {
+            Ok(amount) => amount,
+            Err(_) => return Vec::new(),
+        };
+
+        let tag_bounds = get_tag_bounds(conn);
+        let minor_units = get_minor_units(&self.from_method, conn);
+        let mut warnings = Vec::new();
+
+        for tag in self.tags.split(',').map(|tag| tag.trim()) {
+            let (min_amount, max_amount) = match tag_bounds.get(tag) {
+                Some(bounds) => bounds,
+                None => continue,
+            };
+
+            let below_min = min_amount.map_or(false, |min| amount < min);
+            let above_max = max_amount.map_or(false, |max| amount > max);
+
+            if below_min || above_max {
+                let min = min_amount.map_or("-".to_string(), |min| format_amount(min, minor_units));
+                let max = max_amount.map_or("-".to_string(), |max| format_amount(max, minor_units));
+                warnings.push(format!("{tag}: expected {min} to {max}"));
+            }
+        }
+
+        warnings
+    }
+
+    /// Returns the id_num of the expense the refund is linked to
+    pub fn get_refund_of(&self) -> Option<i32> {
+        self.refund_of
+    }
+
+    pub fn set_refund_of(&mut self, refund_of: Option<i32>) {
+        self.refund_of = refund_of;
+    }
+
+    /// Loads the expense the edited transaction is linked to if it is a refund
+    pub fn load_refund_link(&mut self, conn: &Connection) {
+        self.refund_of = get_refund_original(self.id_num, conn);
+    }
+
+    pub fn is_refund(&self) -> bool {
+        self.tx_type == "Refund"
+    }
+
+    /// Returns whether the tx is a refund that is not linked to an expense yet
+    pub fn needs_refund_original(&self) -> bool {
+        self.is_refund() && self.refund_of.is_none()
+    }
+
+    /// Links the refund to the expense and fills the tx method and tags with the ones of the
+    /// expense. The details and the amount are only filled if empty
+    pub fn set_refund_original(&mut self, original_id: i32, conn: &Connection) {
+        let original = match get_tx_by_id(original_id, conn) {
+            Some(tx) => tx,
+            None => return,
+        };
+
+        self.refund_of = Some(original_id);
+        self.from_method = original[2].to_string();
+        self.tags = original[5].to_string();
+
+        if self.details.is_empty() {
+            self.details = format!("Refund: {}", original[1]);
+        }
+
+        if self.amount.is_empty() {
+            let editing_id = self.editing_tx.then_some(self.id_num);
+            if let Some(refundable) = get_refundable_amount(original_id, editing_id, conn) {
+                self.amount = refundable.format(get_minor_units(&self.from_method, conn));
+            }
+        }
+    }
+
+    /// Returns whether the field failed any check during the last submit
+    pub fn is_invalid_field(&self, field: &TxTab) -> bool {
+        self.invalid_fields.contains(field)
+    }
+
     pub fn get_tx_type(&self) -> TxType {
         if let Some(first_letter) = self.tx_type.chars().next() {
             match first_letter.to_ascii_lowercase() {
-                'i' | 'e' => return TxType::IncomeExpense,
+                'i' | 'e' | 'r' => return TxType::IncomeExpense,
                 't' => return TxType::Transfer,
                 _ => {}
             }
@@ -134,6 +458,7 @@ impl TxData {
     /// Insert or remove from date field according to the index point
     pub fn edit_date(&mut self, to_add: Option<char>) {
         add_char_to(to_add, &mut self.current_index, &mut self.date);
+        self.invalid_fields.retain(|field| field != &TxTab::Date);
     }
 
     /// Insert or remove from details field according to the index point
@@ -144,34 +469,68 @@ impl TxData {
     /// Insert or remove from from method field according to the index point
     pub fn edit_from_method(&mut self, to_add: Option<char>) {
         add_char_to(to_add, &mut self.current_index, &mut self.from_method);
+        self.invalid_fields
+            .retain(|field| field != &TxTab::FromMethod);
     }
 
     /// Insert or remove from to method field according to the index point
     pub fn edit_to_method(&mut self, to_add: Option<char>) {
         add_char_to(to_add, &mut self.current_index, &mut self.to_method);
+        self.invalid_fields
+            .retain(|field| field != &TxTab::ToMethod);
     }
 
     /// Insert or remove from amount field according to the index point
     pub fn edit_amount(&mut self, to_add: Option<char>) {
         add_char_to(to_add, &mut self.current_index, &mut self.amount);
+        self.invalid_fields.retain(|field| field != &TxTab::Amount);
     }
 
     /// Insert or remove from tx type field according to the index point
     pub fn edit_tx_type(&mut self, to_add: Option<char>) {
         add_char_to(to_add, &mut self.current_index, &mut self.tx_type);
+        self.invalid_fields.retain(|field| field != &TxTab::TxType);
     }
 
     /// Insert or remove from tags field according to the index point
     pub fn edit_tags(&mut self, to_add: Option<char>) {
         add_char_to(to_add, &mut self.current_index, &mut self.tags);
+        self.tag_prefix = None;
     }
 
-    /// Takes all data and adds it as a transaction
-    pub fn add_tx(&mut self, conn: &mut Connection) -> Result<(), String> {
-        if let Some(output) = self.check_all_fields() {
-            return Err(output.to_string());
+    /// Inserts the pasted text at the index point of the field of the tab.
+    /// Line breaks are collapsed into spaces as every field is a single line
+    pub fn paste_text(&mut self, current_tab: &TxTab, text: &str) {
+        let text = get_single_line(text);
+
+        let field = match current_tab {
+            TxTab::Date => &mut self.date,
+            TxTab::Details => &mut self.details,
+            TxTab::FromMethod => &mut self.from_method,
+            TxTab::ToMethod => &mut self.to_method,
+            TxTab::Amount => &mut self.amount,
+            TxTab::TxType => &mut self.tx_type,
+            TxTab::Tags => &mut self.tags,
+            TxTab::Nothing => return,
+        };
+
+        let mut index = self.current_index.min(field.len());
+        while !field.is_char_boundary(index) {
+            index -= 1;
         }
 
+        field.insert_str(index, &text);
+        self.current_index = index + text.len();
+
+        self.invalid_fields.retain(|field| field != current_tab);
+        if let TxTab::Tags = current_tab {
+            self.tag_prefix = None;
+        }
+    }
+
+    /// Takes all data and adds it as a transaction. The fields must be checked
+    /// with `check_all_fields` first.
+    pub fn add_tx(&mut self, conn: &mut Connection) -> Result<(), String> {
         let tx_method = self.get_tx_method();
 
         if self.editing_tx {
@@ -187,7 +546,7 @@ impl TxData {
             }
 
             let status_add = add_tx(
-                &self.date,
+                &self.get_iso_date(conn),
                 &self.details,
                 &tx_method,
                 &self.amount,
@@ -197,31 +556,42 @@ impl TxData {
                 conn,
             );
 
-            match status_add {
+            if let Err(e) = status_add {
+                return Err(TxUpdateError::FailedEditTx(e).to_string());
+            }
+
+            // the link is removed if the tx is no longer a refund
+            let refund_of = self.refund_of.filter(|_| self.tx_type == "Refund");
+
+            match set_refund_link(self.id_num, refund_of, conn) {
                 Ok(_) => Ok(()),
                 Err(e) => Err(TxUpdateError::FailedEditTx(e).to_string()),
             }
         } else {
-            let status = add_tx(
-                &self.date,
-                &self.details,
-                &tx_method,
-                &self.amount,
-                &self.tx_type,
-                &self.tags,
-                None,
-                conn,
-            );
-            match status {
+            // new transactions are added the same way external programs add them
+            let new_tx = NewTx {
+                date: self.date.to_string(),
+                details: self.details.to_string(),
+                from_method: self.from_method.to_string(),
+                to_method: self.to_method.to_string(),
+                amount: self.amount.to_string(),
+                tx_type: self.tx_type.to_string(),
+                tags: self.tags.to_string(),
+                refund_of: self.refund_of,
+                external_id: None,
+            };
+
+            match add_transaction(&new_tx, conn) {
                 Ok(_) => Ok(()),
-                Err(e) => Err(TxUpdateError::FailedAddTx(e).to_string()),
+                Err(ApiError::Sql(e)) => Err(TxUpdateError::FailedAddTx(e).to_string()),
+                Err(e) => Err(e.to_string()),
             }
         }
     }
 
-    pub fn get_search_tx(&self, conn: &Connection) -> (Vec<Vec<String>>, Vec<String>) {
+    pub fn get_search_tx(&self, conn: &Connection) -> (Vec<Vec<String>>, Vec<String>, Vec<bool>) {
         get_search_data(
-            &self.date,
+            &self.get_iso_date(conn),
             &self.details,
             &self.from_method,
             &self.to_method,
@@ -240,14 +610,14 @@ impl TxData {
         self.tx_status.push(data);
     }
 
-    pub fn check_autofill(&mut self, current_tab: &TxTab, conn: &Connection) {
+    pub fn check_autofill(&mut self, current_tab: &TxTab, names: &NameCache, conn: &Connection) {
         self.autofill.clear();
 
         self.autofill = match current_tab {
             TxTab::Details => self.autofill_details(&self.details, conn),
-            TxTab::FromMethod => self.autofill_tx_method(&self.from_method, conn),
-            TxTab::ToMethod => self.autofill_tx_method(&self.to_method, conn),
-            TxTab::Tags => self.autofill_tags(&self.tags, conn),
+            TxTab::FromMethod => self.autofill_tx_method(&self.from_method, names, conn),
+            TxTab::ToMethod => self.autofill_tx_method(&self.to_method, names, conn),
+            TxTab::Tags => self.autofill_tags(&self.tags, names, conn),
             _ => String::new(),
         }
     }
@@ -268,6 +638,7 @@ impl TxData {
 
                 splitted.push(&self.autofill);
                 self.tags = splitted.join(", ");
+                self.tag_prefix = None;
             }
             _ => {}
         }
@@ -276,9 +647,13 @@ impl TxData {
     }
 
     /// Checks the inputted Date by the user upon pressing Enter/Esc for various error.
-    pub fn check_date(&mut self) -> VerifyingOutput {
+    pub fn check_date(&mut self, conn: &Connection) -> VerifyingOutput {
         let mut user_date = self.date.clone();
-        let status = self.verify_date(&mut user_date);
+        let status = self.verify_date(
+            &mut user_date,
+            &get_date_range(conn),
+            &get_date_format(conn),
+        );
 
         self.date = user_date;
         self.go_current_index(&TxTab::Date);
@@ -286,10 +661,10 @@ impl TxData {
     }
 
     /// Checks the inputted From Method by the user upon pressing Enter/Esc for various error.
-    pub fn check_from_method(&mut self, conn: &Connection) -> VerifyingOutput {
+    pub fn check_from_method(&mut self, names: &NameCache, conn: &Connection) -> VerifyingOutput {
         let mut current_method = self.from_method.clone();
 
-        let status = self.verify_tx_method(&mut current_method, conn);
+        let status = self.verify_tx_method(&mut current_method, names, conn);
 
         self.from_method = current_method;
         self.go_current_index(&TxTab::FromMethod);
@@ -297,10 +672,10 @@ impl TxData {
     }
 
     /// Checks the inputted To Method by the user upon pressing Enter/Esc for various error.
-    pub fn check_to_method(&mut self, conn: &Connection) -> VerifyingOutput {
+    pub fn check_to_method(&mut self, names: &NameCache, conn: &Connection) -> VerifyingOutput {
         let mut current_method = self.to_method.clone();
 
-        let status = self.verify_tx_method(&mut current_method, conn);
+        let status = self.verify_tx_method(&mut current_method, names, conn);
 
         self.to_method = current_method;
         self.go_current_index(&TxTab::ToMethod);
@@ -331,7 +706,9 @@ impl TxData {
             user_amount = user_amount.replace(symbol, "");
         }
 
-        let status = self.verify_amount(&mut user_amount);
+        let minor_units = get_minor_units(&self.from_method, conn);
+        let status =
+            self.verify_amount_units(&mut user_amount, minor_units, &get_amount_format(conn));
 
         if let Some(symbol) = comparison_symbol {
             user_amount = format!("{symbol}{user_amount}");
@@ -343,6 +720,17 @@ impl TxData {
         status
     }
 
+    /// Checks the inputted Details by the user upon pressing Enter/Esc for various error.
+    pub fn check_details(&mut self, conn: &Connection) -> VerifyingOutput {
+        let mut details = self.details.clone();
+
+        let status = self.verify_details_length(&mut details, get_details_max_length(conn));
+
+        self.details = details;
+        self.go_current_index(&TxTab::Details);
+        status
+    }
+
     /// Checks the inputted Transaction Type by the user upon pressing Enter/Esc for various error.
     pub fn check_tx_type(&mut self) -> VerifyingOutput {
         let mut tx_type = self.tx_type.clone();
@@ -355,48 +743,131 @@ impl TxData {
     }
 
     /// Checks the inputted tags to make sure it's properly separated by a comma
-    pub fn check_tags(&mut self) {
+    /// and within the tag limits
+    pub fn check_tags(&mut self, names: &NameCache, conn: &Connection) -> VerifyingOutput {
         let mut tags = self.tags.clone();
 
-        self.verify_tags(&mut tags);
+        let status = self.verify_tags_limits(
+            &mut tags,
+            &names.get_tags(conn),
+            &names.get_tag_limits(conn),
+        );
 
         self.tags = tags;
+        self.tag_prefix = None;
         self.go_current_index(&TxTab::Tags);
+        status
     }
 
     /// Checks the inputted tags to make sure it's properly separated by a comma
-    pub fn check_tags_forced(&mut self, conn: &Connection) -> VerifyingOutput {
+    pub fn check_tags_forced(&mut self, names: &NameCache, conn: &Connection) -> VerifyingOutput {
         let mut tags = self.tags.clone();
 
-        let status = self.verify_tags_forced(&mut tags, conn);
+        let status = self.verify_tags_forced(&mut tags, names, conn);
 
         self.tags = tags;
+        self.tag_prefix = None;
         self.go_current_index(&TxTab::Tags);
         status
     }
 
-    /// Checks all field and verifies anything important is not empty
-    pub fn check_all_fields(&mut self) -> Option<CheckingError> {
+    /// Checks all fields and verifies anything important is not empty or invalid.
+    /// Every failed check is collected so all of them can be shown at once.
+    pub fn check_all_fields(&mut self, names: &NameCache, conn: &Connection) -> CheckingErrors {
+        let mut errors = CheckingErrors::new();
+
         if self.date.is_empty() {
-            return Some(CheckingError::EmptyDate);
-        } else if self.from_method.is_empty() && self.tx_type != "Transfer" {
-            return Some(CheckingError::EmptyMethod);
-        } else if self.amount.is_empty() {
-            return Some(CheckingError::EmptyAmount);
-        } else if self.tx_type.is_empty() {
-            return Some(CheckingError::EmptyTxType);
-        } else if self.tx_type == "Transfer" && self.from_method == self.to_method {
-            return Some(CheckingError::SameTxMethod);
-        } else if self.tx_type == "Transfer"
-            && (self.from_method.is_empty() || self.to_method.is_empty())
+            errors.add(TxTab::Date, CheckingError::EmptyDate);
+        } else if let VerifyingOutput::NotAccepted(value) = self.check_date(conn) {
+            errors.add(TxTab::Date, CheckingError::NotAccepted(value));
+        }
+
+        if let VerifyingOutput::NotAccepted(value) = self.check_details(conn) {
+            errors.add(TxTab::Details, CheckingError::NotAccepted(value));
+        }
+
+        if self.tx_type.is_empty() {
+            errors.add(TxTab::TxType, CheckingError::EmptyTxType);
+        } else if let VerifyingOutput::NotAccepted(value) = self.check_tx_type() {
+            errors.add(TxTab::TxType, CheckingError::NotAccepted(value));
+        }
+
+        if self.from_method.is_empty() {
+            errors.add(TxTab::FromMethod, CheckingError::EmptyMethod);
+        } else if let VerifyingOutput::NotAccepted(value) = self.check_from_method(names, conn) {
+            errors.add(TxTab::FromMethod, CheckingError::NotAccepted(value));
+        }
+
+        if self.tx_type == "Transfer" {
+            if self.to_method.is_empty() {
+                errors.add(TxTab::ToMethod, CheckingError::EmptyMethod);
+            } else if let VerifyingOutput::NotAccepted(value) = self.check_to_method(names, conn) {
+                errors.add(TxTab::ToMethod, CheckingError::NotAccepted(value));
+            } else if self.from_method == self.to_method {
+                errors.add(TxTab::ToMethod, CheckingError::SameTxMethod);
+            }
+        }
+
+        if self.amount.is_empty() {
+            errors.add(TxTab::Amount, CheckingError::EmptyAmount);
+        } else if let VerifyingOutput::NotAccepted(value) = self.check_amount(false, conn) {
+            errors.add(TxTab::Amount, CheckingError::NotAccepted(value));
+        }
+
+        if self.tx_type == "Refund" {
+            self.check_refund(&mut errors, conn);
+        }
+
+        // tags of an edited tx are checked as well even if the field was never selected
+        if let VerifyingOutput::NotAccepted(value) = self.check_tags(names, conn) {
+            errors.add(TxTab::Tags, CheckingError::NotAccepted(value));
+        } else if self.tags.split(',').all(|tag| tag.trim().is_empty())
+            && get_bool_setting("require_tags", conn)
         {
-            return Some(CheckingError::EmptyMethod);
+            errors.add(TxTab::Tags, CheckingError::EmptyTags);
         }
+
+        self.invalid_fields = errors.get_fields();
+
         // empty tags in a tx becomes as unknown
-        if self.tags.is_empty() {
+        if errors.is_empty() && self.tags.is_empty() {
             self.tags = "Unknown".to_string();
         }
-        None
+        errors
+    }
+
+    /// Checks that the refund is linked to an expense and does not refund more than what is left
+    /// of it. A refund whose expense was deleted can still be edited
+    fn check_refund(&self, errors: &mut CheckingErrors, conn: &Connection) {
+        let original_id = match self.refund_of {
+            Some(original_id) => original_id,
+            None => {
+                errors.add(TxTab::TxType, CheckingError::NoRefundOriginal);
+                return;
+            }
+        };
+
+        let editing_id = self.editing_tx.then_some(self.id_num);
+
+        let refundable = match get_refundable_amount(original_id, editing_id, conn) {
+            Some(refundable) => refundable,
+            None if self.editing_tx && get_tx_by_id(original_id, conn).is_none() => return,
+            None => {
+                errors.add(TxTab::TxType, CheckingError::InvalidRefundOriginal);
+                return;
+            }
+        };
+
+        // the amount has its own error if it could not be read
+        if let Some(amount) = Money::parse(&self.amount) {
+            if amount > refundable {
+                let minor_units = get_minor_units(&self.from_method, conn);
+                errors.add(
+                    TxTab::Amount,
+                    CheckingError::RefundTooBig(refundable.format(minor_units)),
+                );
+            }
+        }
     }
 
     pub fn check_all_empty(&self) -> bool {
@@ -418,27 +889,57 @@ impl TxData {
         false
     }
 
-    /// Checks for b on amount field to replace with the balance of the tx method field
+    /// Checks for b on amount field to replace with the balance of the tx method field.
+    /// On transfers it is the balance of the method the amount is sent from
+    ///
+    /// example: `b-100` with a balance of 500 => `500.00-100`
     fn check_b_field(&mut self, conn: &Connection) -> Result<(), VerifyingOutput> {
+        if !self.amount.to_lowercase().contains('b') {
+            return Ok(());
+        }
+
+        match self.get_b_replaced_amount(conn) {
+            Some(amount) => {
+                self.amount = amount;
+                Ok(())
+            }
+            None => Err(VerifyingOutput::NotAccepted(NAType::InvalidBValue)),
+        }
+    }
+
+    /// Returns the lowercase amount with b replaced by the balance of the tx method field.
+    /// None if the balance can not be known
+    fn get_b_replaced_amount(&self, conn: &Connection) -> Option<String> {
         let user_amount = self.amount.to_lowercase();
 
-        // 'b' represents the current balance of the original tx method
-        if user_amount.contains('b') && !self.from_method.is_empty() {
-            let all_methods = get_all_tx_methods(conn);
+        if !user_amount.contains('b') {
+            return Some(user_amount);
+        }
 
-            // get all the method's final balance, loop through the balances and match the tx method name
-            let last_balances = get_last_balances(conn);
+        // the balance can not be known until a valid tx method is selected
+        let balance = get_method_balance(&self.from_method, conn)?;
 
-            for x in 0..all_methods.len() {
-                if all_methods[x] == self.from_method {
-                    self.amount = user_amount.replace('b', &last_balances[x]);
-                    break;
-                }
-            }
-        } else if user_amount.contains('b') && self.from_method.is_empty() {
-            return Err(VerifyingOutput::NotAccepted(NAType::InvalidBValue));
+        // the balance is written the way the user types amounts so the verifier reads it back the same
+        let balance = balance.format(get_minor_units(&self.from_method, conn));
+        let balance = get_amount_format(conn).localize_decimal(&balance);
+
+        Some(user_amount.replace('b', &balance))
+    }
+
+    /// Returns the result of the calculation in the amount field while it is being typed.
+    /// None if the amount is not a calculation
+    ///
+    /// return example: `= 503.33`
+    pub fn get_amount_preview(&self, conn: &Connection) -> Option<String> {
+        match self.get_b_replaced_amount(conn) {
+            Some(amount) => get_amount_preview(
+                &amount,
+                get_minor_units(&self.from_method, conn),
+                &get_amount_format(conn),
+            ),
+            None if is_amount_expression(&self.amount) => Some("…".to_string()),
+            None => None,
         }
-        Ok(())
     }
 
     /// Returns the current index
@@ -487,11 +988,21 @@ impl TxData {
         self.current_index = self.get_data_len(current_tab)
     }
 
-    /// Steps up Date value by one
-    pub fn do_date_up(&mut self) -> Result<(), SteppingError> {
+    /// Steps up Date value by the step size
+    pub fn do_date_up(
+        &mut self,
+        step_size: DateStepSize,
+        conn: &Connection,
+    ) -> Result<(), SteppingError> {
         let mut user_date = self.date.clone();
 
-        let step_status = self.step_date(&mut user_date, StepType::StepUp);
+        let step_status = self.step_date_by(
+            &mut user_date,
+            StepType::StepUp,
+            step_size,
+            &get_date_range(conn),
+            &get_date_format(conn),
+        );
         self.date = user_date;
 
         // reload index to the final point as some data just got added/changed
@@ -499,11 +1010,21 @@ impl TxData {
         step_status
     }
 
-    /// Steps down Date value by one
-    pub fn do_date_down(&mut self) -> Result<(), SteppingError> {
+    /// Steps down Date value by the step size
+    pub fn do_date_down(
+        &mut self,
+        step_size: DateStepSize,
+        conn: &Connection,
+    ) -> Result<(), SteppingError> {
         let mut user_date = self.date.clone();
 
-        let step_status = self.step_date(&mut user_date, StepType::StepDown);
+        let step_status = self.step_date_by(
+            &mut user_date,
+            StepType::StepDown,
+            step_size,
+            &get_date_range(conn),
+            &get_date_format(conn),
+        );
         self.date = user_date;
 
         // reload index to the final point as some data just got added/changed
@@ -511,11 +1032,39 @@ impl TxData {
         step_status
     }
 
+    /// Sets the Date to today and returns the status of what happened
+    pub fn do_date_today(&mut self, conn: &Connection) -> String {
+        let mut user_date = self.date.clone();
+
+        let in_range = self.set_today(
+            &mut user_date,
+            &get_date_range(conn),
+            &get_date_format(conn),
+        );
+        self.date = user_date;
+
+        // reload index to the final point as some data just got added/changed
+        self.go_current_index(&TxTab::Date);
+
+        if in_range {
+            "Date: Set to today".to_string()
+        } else {
+            format!(
+                "Date: Today is outside the year range. Set to {}",
+                self.date
+            )
+        }
+    }
+
     /// Steps up From Method value by one
-    pub fn do_from_method_up(&mut self, conn: &Connection) -> Result<(), SteppingError> {
+    pub fn do_from_method_up(
+        &mut self,
+        names: &NameCache,
+        conn: &Connection,
+    ) -> Result<(), SteppingError> {
         let mut user_method = self.from_method.clone();
 
-        let step_status = self.step_tx_method(&mut user_method, StepType::StepUp, conn);
+        let step_status = self.step_tx_method(&mut user_method, StepType::StepUp, names, conn);
         self.from_method = user_method;
 
         // reload index to the final point as some data just got added/changed
@@ -524,10 +1073,14 @@ impl TxData {
     }
 
     /// Steps down From Method value by one
-    pub fn do_from_method_down(&mut self, conn: &Connection) -> Result<(), SteppingError> {
+    pub fn do_from_method_down(
+        &mut self,
+        names: &NameCache,
+        conn: &Connection,
+    ) -> Result<(), SteppingError> {
         let mut user_method = self.from_method.clone();
 
-        let step_status = self.step_tx_method(&mut user_method, StepType::StepDown, conn);
+        let step_status = self.step_tx_method(&mut user_method, StepType::StepDown, names, conn);
         self.from_method = user_method;
 
         // reload index to the final point as some data just got added/changed
@@ -536,10 +1089,14 @@ impl TxData {
     }
 
     /// Steps up To Value value by one
-    pub fn do_to_method_up(&mut self, conn: &Connection) -> Result<(), SteppingError> {
+    pub fn do_to_method_up(
+        &mut self,
+        names: &NameCache,
+        conn: &Connection,
+    ) -> Result<(), SteppingError> {
         let mut user_method = self.to_method.clone();
 
-        let step_status = self.step_tx_method(&mut user_method, StepType::StepUp, conn);
+        let step_status = self.step_tx_method(&mut user_method, StepType::StepUp, names, conn);
         self.to_method = user_method;
 
         // reload index to the final point as some data just got added/changed
@@ -548,10 +1105,14 @@ impl TxData {
     }
 
     /// Steps down To Method value by one
-    pub fn do_to_method_down(&mut self, conn: &Connection) -> Result<(), SteppingError> {
+    pub fn do_to_method_down(
+        &mut self,
+        names: &NameCache,
+        conn: &Connection,
+    ) -> Result<(), SteppingError> {
         let mut user_method = self.to_method.clone();
 
-        let step_status = self.step_tx_method(&mut user_method, StepType::StepDown, conn);
+        let step_status = self.step_tx_method(&mut user_method, StepType::StepDown, names, conn);
         self.to_method = user_method;
 
         // reload index to the final point as some data just got added/changed
@@ -583,10 +1144,11 @@ impl TxData {
         step_status
     }
 
-    /// Steps up Amount value by one
+    /// Steps up Amount value by the amount step multiplied by the step size
     pub fn do_amount_up(
         &mut self,
         is_search: bool,
+        step_size: AmountStepSize,
         conn: &Connection,
     ) -> Result<(), SteppingError> {
         if self.check_b_field(conn).is_err() {
@@ -611,7 +1173,14 @@ impl TxData {
             user_amount = user_amount.replace(symbol, "");
         }
 
-        let step_status = self.step_amount(&mut user_amount, StepType::StepUp);
+        let minor_units = get_minor_units(&self.from_method, conn);
+        let step_status = self.step_amount_units(
+            &mut user_amount,
+            StepType::StepUp,
+            get_amount_step(conn) * step_size.multiplier(),
+            minor_units,
+            &get_amount_format(conn),
+        );
 
         if let Some(symbol) = comparison_symbol {
             user_amount = format!("{symbol}{user_amount}");
@@ -624,10 +1193,11 @@ impl TxData {
         step_status
     }
 
-    /// Steps down Amount value by one
+    /// Steps down Amount value by the amount step multiplied by the step size
     pub fn do_amount_down(
         &mut self,
         is_search: bool,
+        step_size: AmountStepSize,
         conn: &Connection,
     ) -> Result<(), SteppingError> {
         if self.check_b_field(conn).is_err() {
@@ -652,7 +1222,14 @@ impl TxData {
             user_amount = user_amount.replace(symbol, "");
         }
 
-        let step_status = self.step_amount(&mut user_amount, StepType::StepDown);
+        let minor_units = get_minor_units(&self.from_method, conn);
+        let step_status = self.step_amount_units(
+            &mut user_amount,
+            StepType::StepDown,
+            get_amount_step(conn) * step_size.multiplier(),
+            minor_units,
+            &get_amount_format(conn),
+        );
 
         if let Some(symbol) = comparison_symbol {
             user_amount = format!("{symbol}{user_amount}");
@@ -665,11 +1242,38 @@ impl TxData {
         step_status
     }
 
+    /// Returns the tag the user typed before stepping the tags. Stays the same on the
+    /// following steps until the tags are edited
+    fn get_tag_prefix(&mut self) -> String {
+        self.tag_prefix
+            .get_or_insert_with(|| {
+                self.tags
+                    .split(',')
+                    .last()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string()
+            })
+            .to_string()
+    }
+
     /// Steps up Tags value by one
-    pub fn do_tags_up(&mut self, conn: &Connection) -> Result<(), SteppingError> {
+    pub fn do_tags_up(
+        &mut self,
+        names: &NameCache,
+        conn: &Connection,
+    ) -> Result<(), SteppingError> {
         let mut user_tag = self.tags.clone();
+        let prefix = self.get_tag_prefix();
 
-        let status = self.step_tags(&mut user_tag, &self.autofill, StepType::StepUp, conn);
+        let status = self.step_tags_matching(
+            &mut user_tag,
+            &prefix,
+            &self.autofill,
+            StepType::StepUp,
+            names,
+            conn,
+        );
         self.tags = user_tag;
 
         // reload index to the final point as some data just got added/changed
@@ -678,10 +1282,22 @@ impl TxData {
     }
 
     /// Steps down Tags value by one
-    pub fn do_tags_down(&mut self, conn: &Connection) -> Result<(), SteppingError> {
+    pub fn do_tags_down(
+        &mut self,
+        names: &NameCache,
+        conn: &Connection,
+    ) -> Result<(), SteppingError> {
         let mut user_tag = self.tags.clone();
+        let prefix = self.get_tag_prefix();
 
-        let status = self.step_tags(&mut user_tag, &self.autofill, StepType::StepDown, conn);
+        let status = self.step_tags_matching(
+            &mut user_tag,
+            &prefix,
+            &self.autofill,
+            StepType::StepDown,
+            names,
+            conn,
+        );
         self.tags = user_tag;
 
         // reload index to the final point as some data just got added/changed
@@ -0,0 +1,106 @@
+use crate::utility::{AmountFormat, DateFormat, Money};
+
+/// The most characters of the details shown on the deletion popup
+const SHOWN_DETAILS_LENGTH: usize = 80;
+
+/// Contains all data for the popup that confirms deleting the selected transaction.
+/// Transactions with an amount at or above the deletion threshold are only deleted
+/// once `yes` or their amount is typed
+///
+/// lines: `["Date: 12-05-2023", "Details: Rent", "Tx Method: Cash", "Amount: 1,450.00", "Tx Type: Expense"]`
+pub struct TxDeletionData {
+    lines: Vec<String>,
+    amount: String,
+    warning: String,
+    typed: Option<String>,
+}
+
+impl TxDeletionData {
+    /// Creates an instance of the struct for a Home table row. A threshold of 0 or below never
+    /// asks for typing
+    pub fn new(
+        tx: &[String],
+        warning: String,
+        threshold: f64,
+        date_format: &DateFormat,
+        amount_format: &AmountFormat,
+    ) -> Self {
+        let details = if tx[1].chars().count() > SHOWN_DETAILS_LENGTH {
+            let shortened = tx[1]
+                .chars()
+                .take(SHOWN_DETAILS_LENGTH - 1)
+                .collect::<String>();
+            format!("{shortened}…")
+        } else {
+            tx[1].clone()
+        };
+
+        let lines = vec![
+            format!("Date: {}", date_format.format_table_date(&tx[0])),
+            format!("Details: {details}"),
+            format!("Tx Method: {}", tx[2]),
+            format!("Amount: {}", amount_format.localize(&tx[3])),
+            format!("Tx Type: {}", tx[4]),
+        ];
+
+        let needs_typing = threshold > 0.0
+            && tx[3]
+                .parse::<f64>()
+                .map_or(false, |amount| amount >= threshold);
+
+        TxDeletionData {
+            lines,
+            amount: tx[3].clone(),
+            warning,
+            typed: needs_typing.then(String::new),
+        }
+    }
+
+    pub fn get_lines(&self) -> &Vec<String> {
+        &self.lines
+    }
+
+    pub fn get_warning(&self) -> &str {
+        &self.warning
+    }
+
+    /// Returns whether `yes` or the amount must be typed before deleting
+    pub fn needs_typing(&self) -> bool {
+        self.typed.is_some()
+    }
+
+    pub fn get_typed(&self) -> &str {
+        self.typed.as_deref().unwrap_or_default()
+    }
+
+    pub fn push(&mut self, c: char) {
+        if let Some(typed) = self.typed.as_mut() {
+            typed.push(c);
+        }
+    }
+
+    pub fn pop(&mut self) {
+        if let Some(typed) = self.typed.as_mut() {
+            typed.pop();
+        }
+    }
+
+    /// Returns whether the transaction can be deleted. The typed amount can be in the
+    /// decimal mark and with the currency symbols of the format
+    pub fn is_confirmed(&self, amount_format: &AmountFormat) -> bool {
+        let typed = match &self.typed {
+            Some(typed) => typed.trim(),
+            None => return true,
+        };
+
+        if typed.eq_ignore_ascii_case("yes") {
+            return true;
+        }
+
+        let typed_amount = amount_format
+            .normalize(typed)
+            .and_then(|amount| Money::parse(&amount));
+
+        typed_amount.is_some() && typed_amount == Money::parse(&self.amount)
+    }
+}
@@ -0,0 +1,104 @@
+use crate::db::RELATED_LINK;
+use crate::utility::{get_date_format, get_linked_txs, get_refund_lines, get_tx_by_id};
+use rusqlite::Connection;
+
+/// Contains all data for the popup that shows a transaction with its refund links
+/// and the transactions linked to it. Dates are in the date format setting
+///
+/// links: `[(5, ["2022-05-01", "Deposit", "Cash", "100.00", "Expense", "Rent"])]` oldest first
+pub struct TxDetailsData {
+    id_num: i32,
+    tx: Vec<String>,
+    refund_lines: Vec<String>,
+    links: Vec<(i32, Vec<String>)>,
+    index: usize,
+    status: String,
+}
+
+impl TxDetailsData {
+    /// Creates an instance of the struct for the transaction. None if it is not in the database
+    pub fn new(id_num: i32, conn: &Connection) -> Option<Self> {
+        let mut tx = get_tx_by_id(id_num, conn)?;
+        tx[0] = get_date_format(conn).localize(&tx[0]);
+
+        let mut details_data = TxDetailsData {
+            id_num,
+            tx,
+            refund_lines: get_refund_lines(id_num, conn),
+            links: Vec::new(),
+            index: 0,
+            status: String::new(),
+        };
+        details_data.reload_links(conn);
+
+        Some(details_data)
+    }
+
+    pub fn get_id_num(&self) -> i32 {
+        self.id_num
+    }
+
+    pub fn get_tx(&self) -> &Vec<String> {
+        &self.tx
+    }
+
+    pub fn get_refund_lines(&self) -> &Vec<String> {
+        &self.refund_lines
+    }
+
+    pub fn get_links(&self) -> &Vec<(i32, Vec<String>)> {
+        &self.links
+    }
+
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    pub fn get_status(&self) -> &str {
+        &self.status
+    }
+
+    pub fn set_status(&mut self, status: String) {
+        self.status = status;
+    }
+
+    /// Returns the id_num of the selected linked transaction
+    pub fn get_selected_link(&self) -> Option<i32> {
+        self.links.get(self.index).map(|(id_num, _)| *id_num)
+    }
+
+    /// Loads the linked transactions again. The selection is kept if it is still in range
+    pub fn reload_links(&mut self, conn: &Connection) {
+        let date_format = get_date_format(conn);
+
+        self.links = get_linked_txs(self.id_num, RELATED_LINK, conn)
+            .into_iter()
+            .map(|(id_num, mut tx)| {
+                tx[0] = date_format.localize(&tx[0]);
+                (id_num, tx)
+            })
+            .collect();
+
+        if self.index >= self.links.len() {
+            self.index = self.links.len().saturating_sub(1);
+        }
+    }
+
+    /// Selects the next linked transaction or the 1st one if at the final one
+    pub fn next(&mut self) {
+        if !self.links.is_empty() {
+            self.index = (self.index + 1) % self.links.len();
+        }
+    }
+
+    /// Selects the previous linked transaction or the final one if at the 1st one
+    pub fn previous(&mut self) {
+        if !self.links.is_empty() {
+            if self.index > 0 {
+                self.index -= 1;
+            } else {
+                self.index = self.links.len() - 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::utility::get_date_format;
+use rusqlite::{Connection, Params};
+
+/// Contains all data for the popups that pick a transaction, such as the expense a refund
+/// is linked to. Only the transactions that contain the typed text are shown
+///
+/// txs: `[(12, ["2022-05-01", "Shoes", "Cash", "50.00", "Expense", "Clothes"])]` newest first
+pub struct TxPickerData {
+    query: String,
+    txs: Vec<(i32, Vec<String>)>,
+    matches: Vec<usize>,
+    index: usize,
+}
+
+impl TxPickerData {
+    /// Creates an instance of the struct with every expense. Dates are in the date format setting
+    pub fn new_expenses(conn: &Connection) -> Self {
+        Self::from_query("WHERE tx_type = 'Expense'", [], conn)
+    }
+
+    /// Creates an instance of the struct with every transaction that is not the given one
+    /// or already linked to it
+    pub fn new_link_targets(id_num: i32, link_type: &str, conn: &Connection) -> Self {
+        Self::from_query(
+            "WHERE id_num != ?1 AND id_num NOT IN
+            (SELECT linked_id FROM tx_links WHERE id_num = ?1 AND link_type = ?2)",
+            (id_num, link_type),
+            conn,
+        )
+    }
+
+    fn from_query(condition: &str, params: impl Params, conn: &Connection) -> Self {
+        let date_format = get_date_format(conn);
+
+        let mut query = conn
+            .prepare(&format!(
+                "SELECT id_num, date, details, tx_method, amount, tx_type, tags FROM tx_all
+                {condition} ORDER BY date DESC, id_num DESC"
+            ))
+            .expect("could not prepare statement");
+
+        let mut txs = Vec::new();
+
+        if let Ok(rows) = query.query_map(params, |row| {
+            let mut tx = Vec::new();
+            for i in 1..7 {
+                tx.push(row.get::<_, String>(i)?);
+            }
+            Ok((row.get::<_, i32>(0)?, tx))
+        }) {
+            for (id_num, mut tx) in rows.flatten() {
+                tx[0] = date_format.localize(&tx[0]);
+                txs.push((id_num, tx));
+            }
+        }
+
+        let matches = (0..txs.len()).collect();
+
+        TxPickerData {
+            query: String::new(),
+            txs,
+            matches,
+            index: 0,
+        }
+    }
+
+    pub fn get_query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the transactions that contain the typed text
+    pub fn get_matches(&self) -> Vec<&Vec<String>> {
+        self.matches
+            .iter()
+            .map(|index| &self.txs[*index].1)
+            .collect()
+    }
+
+    /// Returns the id_num of the selected transaction
+    pub fn get_selected(&self) -> Option<i32> {
+        self.matches.get(self.index).map(|index| self.txs[*index].0)
+    }
+
+    /// Adds or removes a character of the typed text and selects the first match
+    pub fn edit_query(&mut self, to_add: Option<char>) {
+        match to_add {
+            Some(ch) => self.query.push(ch),
+            None => {
+                self.query.pop();
+            }
+        }
+
+        let query = self.query.to_lowercase();
+
+        self.matches = self
+            .txs
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, tx))| tx.iter().any(|field| field.to_lowercase().contains(&query)))
+            .map(|(index, _)| index)
+            .collect();
+        self.index = 0;
+    }
+
+    /// Selects the next transaction or the 1st one if at the final transaction
+    pub fn next(&mut self) {
+        if !self.matches.is_empty() {
+            self.index = (self.index + 1) % self.matches.len();
+        }
+    }
+
+    /// Selects the previous transaction or the final one if at the 1st transaction
+    pub fn previous(&mut self) {
+        if !self.matches.is_empty() {
+            if self.index > 0 {
+                self.index -= 1;
+            } else {
+                self.index = self.matches.len() - 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use crate::db::{get_default_setting, get_setting};
+use crate::utility::RoundingMode;
+use rusqlite::Connection;
+use thousands::Separable;
+
+/// The characters that split an amount expression into separate numbers
+const OPERATORS: [char; 7] = ['+', '-', '*', '/', '(', ')', '%'];
+
+/// How amounts are typed. Amounts are always calculated and saved with a dot as the decimal mark
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmountFormat {
+    decimal_mark: char,
+    currency_symbols: Vec<String>,
+    rounding: RoundingMode,
+}
+
+impl Default for AmountFormat {
+    fn default() -> Self {
+        Self::new('.', &get_default_setting("currency_symbols"))
+    }
+}
+
+impl AmountFormat {
+    /// Creates the format from the decimal mark and a comma separated list of currency symbols.
+    /// Any decimal mark other than a comma is taken as a dot
+    pub fn new(decimal_mark: char, currency_symbols: &str) -> Self {
+        let decimal_mark = if decimal_mark == ',' { ',' } else { '.' };
+
+        let currency_symbols = currency_symbols
+            .split(',')
+            .map(|symbol| symbol.trim().to_string())
+            .filter(|symbol| !symbol.is_empty())
+            .collect();
+
+        AmountFormat {
+            decimal_mark,
+            currency_symbols,
+            rounding: RoundingMode::default(),
+        }
+    }
+
+    /// Sets how the extra decimal places of typed and calculated amounts are dropped
+    pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    pub fn get_rounding(&self) -> RoundingMode {
+        self.rounding
+    }
+
+    /// Returns the character that separates the digit groups of the format
+    fn group_mark(&self) -> char {
+        if self.decimal_mark == ',' {
+            '.'
+        } else {
+            ','
+        }
+    }
+
+    /// Removes the currency symbols and group separators of every number in the expression and
+    /// turns the decimal mark into a dot. Group separators are only removed when every group
+    /// after the first one has exactly 3 digits.
+    /// Returns None if a number has group separators in the wrong positions
+    ///
+    /// example: `$1,250.50 + 1 000` => `1250.50 + 1000`
+    pub fn normalize(&self, amount: &str) -> Option<String> {
+        let mut normalized = String::new();
+        let mut number = String::new();
+
+        for c in amount.chars() {
+            if OPERATORS.contains(&c) {
+                normalized.push_str(&self.normalize_number(&number)?);
+                normalized.push(c);
+                number.clear();
+            } else {
+                number.push(c);
+            }
+        }
+
+        normalized.push_str(&self.normalize_number(&number)?);
+
+        Some(normalized)
+    }
+
+    /// Normalizes a single number of the expression
+    fn normalize_number(&self, number: &str) -> Option<String> {
+        let mut number = number.trim();
+
+        for symbol in &self.currency_symbols {
+            if let Some(stripped) = number.strip_prefix(symbol.as_str()) {
+                number = stripped.trim_start();
+                break;
+            }
+        }
+
+        for symbol in &self.currency_symbols {
+            if let Some(stripped) = number.strip_suffix(symbol.as_str()) {
+                number = stripped.trim_end();
+                break;
+            }
+        }
+
+        // a multiplier such as the k of 1.5k is kept after the number
+        let (number, multiplier) = match number.strip_suffix(['k', 'K', 'm', 'M']) {
+            Some(stripped) => (stripped, &number[stripped.len()..]),
+            None => (number, ""),
+        };
+
+        let (whole, fraction) = match number.split_once(self.decimal_mark) {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (number, None),
+        };
+
+        let group_mark = self.group_mark();
+        let groups = whole
+            .split(|c: char| c == group_mark || c == ' ')
+            .collect::<Vec<&str>>();
+
+        let whole = if groups.len() == 1 {
+            whole.to_string()
+        } else if self.is_grouped(&groups) {
+            groups.concat()
+        } else if whole.contains(group_mark) {
+            // a group separator in the wrong position can not be told apart from a decimal mark
+            return None;
+        } else {
+            // spaces that do not form groups are left for the verifier to remove
+            whole.to_string()
+        };
+
+        match fraction {
+            Some(fraction) if fraction.contains(group_mark) => None,
+            Some(fraction) => Some(format!("{whole}.{fraction}{multiplier}")),
+            None => Some(format!("{whole}{multiplier}")),
+        }
+    }
+
+    /// Rewrites the numbers of a text to be shown with the digit groups separated
+    /// and the decimal mark of the format
+    ///
+    /// example with a comma decimal mark: `1234.56` => `1.234,56`
+    pub fn localize(&self, text: &str) -> String {
+        self.swap_marks(&text.separate_with_commas())
+    }
+
+    /// Same as `localize` but the digit groups are not separated
+    pub fn localize_decimal(&self, text: &str) -> String {
+        self.swap_marks(text)
+    }
+
+    /// Formats an amount with the minor units and the separators of the format
+    pub fn format_amount(&self, amount: f64, minor_units: usize) -> String {
+        self.localize(&format!("{amount:.minor_units$}"))
+    }
+
+    /// Swaps the dots and commas that are between two digits if the decimal mark is a comma
+    fn swap_marks(&self, text: &str) -> String {
+        if self.decimal_mark == '.' {
+            return text.to_string();
+        }
+
+        let chars = text.chars().collect::<Vec<char>>();
+
+        chars
+            .iter()
+            .enumerate()
+            .map(|(index, c)| {
+                let between_digits = index > 0
+                    && index + 1 < chars.len()
+                    && chars[index - 1].is_ascii_digit()
+                    && chars[index + 1].is_ascii_digit();
+
+                match c {
+                    ',' if between_digits => '.',
+                    '.' if between_digits => ',',
+                    _ => *c,
+                }
+            })
+            .collect()
+    }
+
+    /// Checks whether the groups are of a grouped number such as `1,000,000`
+    fn is_grouped(&self, groups: &[&str]) -> bool {
+        let all_digits = |group: &str| group.chars().all(|c| c.is_ascii_digit());
+
+        let first_valid = (1..=3).contains(&groups[0].len()) && all_digits(groups[0]);
+        let rest_valid = groups[1..]
+            .iter()
+            .all(|group| group.len() == 3 && all_digits(group));
+
+        first_valid && rest_valid
+    }
+}
+
+/// Returns the amount format of the decimal_mark, currency_symbols and amount_rounding settings
+pub fn get_amount_format(conn: &Connection) -> AmountFormat {
+    let decimal_mark = get_setting("decimal_mark", conn)
+        .chars()
+        .next()
+        .unwrap_or('.');
+
+    AmountFormat::new(decimal_mark, &get_setting("currency_symbols", conn)).with_rounding(
+        RoundingMode::from_setting(&get_setting("amount_rounding", conn)),
+    )
+}
@@ -0,0 +1,67 @@
+use std::io;
+#[cfg(feature = "clipboard")]
+use std::io::Write;
+#[cfg(feature = "clipboard")]
+use std::process::{Command, Stdio};
+
+/// The commands that take text on stdin and put it on the system clipboard.
+/// They are tried in order until one of them works
+#[cfg(feature = "clipboard")]
+fn get_clipboard_commands() -> Vec<(&'static str, Vec<&'static str>)> {
+    if cfg!(target_os = "windows") {
+        vec![("clip", vec![])]
+    } else if cfg!(target_os = "macos") {
+        vec![("pbcopy", vec![])]
+    } else {
+        vec![
+            ("wl-copy", vec![]),
+            ("xclip", vec!["-selection", "clipboard"]),
+            ("xsel", vec!["--clipboard", "--input"]),
+        ]
+    }
+}
+
+/// Puts the text on the system clipboard. Fails if none of the clipboard commands work,
+/// such as over SSH without a display server
+#[cfg(feature = "clipboard")]
+#[cfg(not(tarpaulin_include))]
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    for (program, args) in get_clipboard_commands() {
+        let mut child = match Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(_) => continue,
+        };
+
+        // the stdin is dropped after writing so the command knows the text ended
+        let written = match child.stdin.take() {
+            Some(mut stdin) => stdin.write_all(text.as_bytes()).is_ok(),
+            None => false,
+        };
+
+        match child.wait() {
+            Ok(status) if written && status.success() => return Ok(()),
+            _ => continue,
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "no clipboard is available",
+    ))
+}
+
+/// Puts the text on the system clipboard. Always fails as the app was built without the clipboard feature
+#[cfg(not(feature = "clipboard"))]
+#[cfg(not(tarpaulin_include))]
+pub fn copy_to_clipboard(_text: &str) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the app was built without clipboard support",
+    ))
+}
@@ -0,0 +1,39 @@
+use chrono::prelude::Local;
+use chrono::NaiveDate;
+
+/// Source of the current date. Date sensitive features take a Clock so they can be
+/// checked with a fixed date
+pub trait Clock {
+    fn today(&self) -> NaiveDate;
+}
+
+/// Clock that follows the local date of the device
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> NaiveDate {
+        Local::now().date_naive()
+    }
+}
+
+/// Clock that always returns the date it was set to
+pub struct FixedClock {
+    today: NaiveDate,
+}
+
+impl FixedClock {
+    pub fn new(today: NaiveDate) -> Self {
+        FixedClock { today }
+    }
+
+    /// Changes the date the clock returns
+    pub fn set(&mut self, today: NaiveDate) {
+        self.today = today;
+    }
+}
+
+impl Clock for FixedClock {
+    fn today(&self) -> NaiveDate {
+        self.today
+    }
+}
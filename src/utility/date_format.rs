@@ -0,0 +1,123 @@
+use crate::db::get_setting;
+use crate::utility::Clock;
+use chrono::{Duration, NaiveDate};
+use rusqlite::Connection;
+
+/// The order the date parts are typed and shown in. Dates are always saved in the db as YYYY-MM-DD
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateFormat {
+    Ymd,
+    Dmy,
+    Mdy,
+}
+
+impl DateFormat {
+    /// Returns the format of the date_format setting value. Unknown values fall back to YYYY-MM-DD
+    pub fn from_setting(value: &str) -> Self {
+        match value {
+            "dmy" => DateFormat::Dmy,
+            "mdy" => DateFormat::Mdy,
+            _ => DateFormat::Ymd,
+        }
+    }
+
+    /// Returns the chrono pattern of the format
+    pub fn pattern(&self) -> &'static str {
+        match self {
+            DateFormat::Ymd => "%Y-%m-%d",
+            DateFormat::Dmy => "%d-%m-%Y",
+            DateFormat::Mdy => "%m-%d-%Y",
+        }
+    }
+
+    /// Returns the date written in the format
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        date.format(self.pattern()).to_string()
+    }
+
+    /// Reorders a date typed in the format to YYYY-MM-DD. Slashes and dots are turned into dashes.
+    /// A date with a 4 character first part is already in YYYY-MM-DD order and is only normalized.
+    /// Dates without exactly 3 parts are returned normalized so the verifier can handle them
+    pub fn to_iso(&self, date: &str) -> String {
+        let normalized = date.replace(['/', '.'], "-");
+        let parts = normalized.split('-').collect::<Vec<&str>>();
+
+        if parts.len() != 3 || parts[0].len() == 4 {
+            return normalized;
+        }
+
+        match self {
+            DateFormat::Ymd => normalized,
+            DateFormat::Dmy => format!("{}-{}-{}", parts[2], parts[1], parts[0]),
+            DateFormat::Mdy => format!("{}-{}-{}", parts[2], parts[0], parts[1]),
+        }
+    }
+
+    /// Reorders a YYYY-MM-DD date to the format. Dates without exactly 3 parts are returned as they are
+    pub fn from_iso(&self, date: &str) -> String {
+        let parts = date.split('-').collect::<Vec<&str>>();
+
+        if parts.len() != 3 {
+            return date.to_string();
+        }
+
+        match self {
+            DateFormat::Ymd => date.to_string(),
+            DateFormat::Dmy => format!("{}-{}-{}", parts[2], parts[1], parts[0]),
+            DateFormat::Mdy => format!("{}-{}-{}", parts[1], parts[2], parts[0]),
+        }
+    }
+
+    /// Rewrites a DD-MM-YYYY table date in the format. Values that are not a date,
+    /// such as subtotal labels, are returned as they are
+    pub fn format_table_date(&self, date: &str) -> String {
+        match NaiveDate::parse_from_str(date, "%d-%m-%Y") {
+            Ok(parsed) => self.format_date(parsed),
+            Err(_) => date.to_string(),
+        }
+    }
+
+    /// Rewrites a date typed either in the format or as YYYY-MM-DD in the format
+    pub fn localize(&self, date: &str) -> String {
+        self.from_iso(&self.to_iso(date))
+    }
+}
+
+/// Expands a relative date to the YYYY-MM-DD date it points to from the clock's date.
+/// Accepts t/today, y/yesterday and +N/-N days. Returns None for any other value
+pub fn expand_relative_date(date: &str, clock: &impl Clock) -> Option<String> {
+    let date = date.trim().to_lowercase();
+
+    let day_offset = match date.as_str() {
+        "t" | "today" => 0,
+        "y" | "yesterday" => -1,
+        _ => {
+            let (sign, days) = match date.split_at(date.len().min(1)) {
+                ("+", days) => (1, days),
+                ("-", days) => (-1, days),
+                _ => return None,
+            };
+
+            if days.is_empty() || !days.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+
+            sign * days.parse::<i64>().ok()?
+        }
+    };
+
+    // Duration panics on values this big while no date can be that far anyway
+    if day_offset.abs() > 1_000_000_000 {
+        return None;
+    }
+
+    clock
+        .today()
+        .checked_add_signed(Duration::days(day_offset))
+        .map(|expanded| expanded.format("%Y-%m-%d").to_string())
+}
+
+/// Returns the date format of the date_format setting
+pub fn get_date_format(conn: &Connection) -> DateFormat {
+    DateFormat::from_setting(&get_setting("date_format", conn))
+}
@@ -0,0 +1,147 @@
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+
+/// The lock file created next to the database while an instance of the app is using it
+pub const LOCK_FILE: &str = "rex.lock";
+
+/// The result of trying to take the instance lock
+pub enum LockStatus {
+    Acquired(InstanceLock),
+    /// Another running instance holds the lock. Contains its PID if the lock file could be parsed
+    HeldBy(Option<u32>),
+}
+
+/// Keeps other instances of the app from writing to the same database. The lock is a file that
+/// contains the PID of the holder and is removed once this is dropped
+pub struct InstanceLock {
+    path: PathBuf,
+    pid: u32,
+}
+
+impl InstanceLock {
+    /// Takes the lock for this process. A lock left behind by a process that is no longer
+    /// running is removed and taken over
+    pub fn acquire(path: impl AsRef<Path>) -> io::Result<LockStatus> {
+        Self::acquire_with(path, process::id(), is_process_running)
+    }
+
+    /// Same as `acquire` but with the PID of the holder and the check for whether
+    /// the PID of an existing lock is still running
+    pub fn acquire_with(
+        path: impl AsRef<Path>,
+        pid: u32,
+        is_running: impl Fn(u32) -> bool,
+    ) -> io::Result<LockStatus> {
+        let path = path.as_ref().to_path_buf();
+
+        // a stale lock is removed once and the lock is taken again
+        for _ in 0..2 {
+            match create_lock_file(&path, pid) {
+                Ok(()) => return Ok(LockStatus::Acquired(InstanceLock { path, pid })),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e),
+            }
+
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                // the holder released it in the meantime
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+
+            // a lock file is never written partially so one without a PID is not taken over
+            match content.trim().parse::<u32>() {
+                Ok(holder) if holder == pid || !is_running(holder) => fs::remove_file(&path)?,
+                Ok(holder) => return Ok(LockStatus::HeldBy(Some(holder))),
+                Err(_) => return Ok(LockStatus::HeldBy(None)),
+            }
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "the lock file was created again while taking it",
+        ))
+    }
+
+    /// Removes the lock file on panic before the previous panic hook runs.
+    /// Drop does not run when a panic aborts the app
+    pub fn set_panic_hook(&self) {
+        let path = self.path.clone();
+        let pid = self.pid;
+        let previous_hook = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            release_lock(&path, pid);
+            previous_hook(info);
+        }));
+    }
+
+    /// Removes the lock file if it still belongs to this lock
+    pub fn release(&self) {
+        release_lock(&self.path, self.pid);
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Writes the PID to a temporary file and then links it to the lock path so the lock file
+/// never exists without the PID. Unlike renaming, linking fails if the lock already exists
+fn create_lock_file(path: &Path, pid: u32) -> io::Result<()> {
+    let temp_path = path.with_extension(format!("{pid}.tmp"));
+    fs::write(&temp_path, pid.to_string())?;
+
+    let linked = fs::hard_link(&temp_path, path);
+    fs::remove_file(&temp_path).ok();
+    linked
+}
+
+/// Removes the lock file only if it contains the given PID
+fn release_lock(path: &Path, pid: u32) {
+    if read_lock_pid(path) == Some(pid) {
+        fs::remove_file(path).ok();
+    }
+}
+
+/// Returns the PID written in the lock file
+fn read_lock_pid(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Returns whether a process with the PID is running
+#[cfg(not(tarpaulin_include))]
+pub fn is_process_running(pid: u32) -> bool {
+    if cfg!(target_os = "windows") {
+        let output = Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {pid}"), "/NH"])
+            .stderr(Stdio::null())
+            .output();
+
+        match output {
+            Ok(output) => String::from_utf8_lossy(&output.stdout)
+                .split_whitespace()
+                .any(|word| word == pid.to_string()),
+            // the lock is kept if it can not be checked
+            Err(_) => true,
+        }
+    } else {
+        match Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stdout(Stdio::null())
+            .output()
+        {
+            // a process of another user can not be signaled but is still running
+            Ok(output) => {
+                output.status.success()
+                    || String::from_utf8_lossy(&output.stderr).contains("not permitted")
+            }
+            Err(_) => true,
+        }
+    }
+}
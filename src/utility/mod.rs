@@ -1,6 +1,22 @@
+mod amount_format;
+mod clipboard;
+mod clock;
+mod date_format;
+mod instance_lock;
+mod money;
 mod sub_func;
+mod suggestion_index;
 pub mod traits;
 mod utils;
+mod week_range;
 
+pub use amount_format::*;
+pub use clipboard::*;
+pub use clock::*;
+pub use date_format::*;
+pub use instance_lock::*;
+pub use money::*;
 pub use sub_func::*;
+pub use suggestion_index::*;
 pub use utils::*;
+pub use week_range::*;
@@ -0,0 +1,277 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// The number of decimal places a Money value keeps. Tx methods can use up to 3 decimal places
+const SCALE_DIGITS: usize = 3;
+
+/// The number of stored units in a whole amount
+const SCALE: i64 = 1000;
+
+/// How the decimal places after the minor units of a typed or calculated amount are dropped
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RoundingMode {
+    /// Halves are rounded away from zero. `0.005` => `0.01`
+    #[default]
+    HalfUp,
+    /// Halves are rounded to the even number. `0.005` => `0.00` and `0.015` => `0.02`
+    HalfEven,
+    /// The extra decimal places are cut off. `0.009` => `0.00`
+    Truncate,
+}
+
+impl RoundingMode {
+    /// Returns the mode of the amount_rounding setting value. Unknown values fall back to half up
+    pub fn from_setting(value: &str) -> Self {
+        match value {
+            "half_even" => RoundingMode::HalfEven,
+            "truncate" => RoundingMode::Truncate,
+            _ => RoundingMode::HalfUp,
+        }
+    }
+}
+
+impl fmt::Display for RoundingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoundingMode::HalfUp => write!(f, "rounded half up"),
+            RoundingMode::HalfEven => write!(f, "rounded half to even"),
+            RoundingMode::Truncate => write!(f, "cut off"),
+        }
+    }
+}
+
+/// An amount saved as a whole number of thousandths so adding and subtracting amounts never
+/// loses precision. Amounts are still saved as text in the db and only turned into Money
+/// to do the calculations.
+///
+/// example: `12.50` => `Money(12500)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    /// Creates the amount from the number of thousandths
+    pub fn from_minor(minor: i64) -> Self {
+        Money(minor)
+    }
+
+    /// Creates the amount from a whole number
+    pub fn from_whole(whole: i64) -> Self {
+        Money(whole * SCALE)
+    }
+
+    /// Returns the number of thousandths of the amount
+    pub fn minor(&self) -> i64 {
+        self.0
+    }
+
+    /// Parses an amount such as `-12.50` without going through f64. Numbers after the
+    /// 3rd decimal place are cut off. Returns None if the text is not a number or too big
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+
+        let (negative, number) = match text.strip_prefix('-') {
+            Some(number) => (true, number),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+
+        let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+
+        if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+            return None;
+        }
+
+        let whole_value = if whole.is_empty() {
+            0
+        } else {
+            whole.parse::<i64>().ok()?
+        };
+
+        let mut fraction_value = 0;
+        for (index, digit) in fraction.chars().take(SCALE_DIGITS).enumerate() {
+            let place = 10_i64.pow((SCALE_DIGITS - index - 1) as u32);
+            fraction_value += digit.to_digit(10)? as i64 * place;
+        }
+
+        let value = whole_value
+            .checked_mul(SCALE)?
+            .checked_add(fraction_value)?;
+
+        if negative {
+            Some(Money(-value))
+        } else {
+            Some(Money(value))
+        }
+    }
+
+    /// Same as `parse` but the decimal places after the minor units are dropped with the
+    /// rounding mode. Returns None if the text is not a number or too big
+    ///
+    /// example: `9.995` with 2 minor units rounded half up => `Money(10000)`
+    pub fn parse_rounded(text: &str, minor_units: usize, mode: RoundingMode) -> Option<Self> {
+        let units = minor_units.min(SCALE_DIGITS);
+        let text = text.trim();
+
+        let (negative, number) = match text.strip_prefix('-') {
+            Some(number) => (true, number),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+
+        if whole.is_empty() && fraction.is_empty() {
+            return None;
+        }
+
+        if !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let (kept, dropped) = fraction.split_at(units.min(fraction.len()));
+        let whole = if whole.is_empty() { "0" } else { whole };
+        let kept_value = Money::parse(&format!("{whole}.{kept}"))?;
+
+        let step = 10_i64.pow((SCALE_DIGITS - units) as u32);
+        let first_dropped = dropped.chars().next().unwrap_or('0');
+        let rest_dropped = dropped.chars().skip(1).any(|c| c != '0');
+
+        let round_up = match mode {
+            RoundingMode::HalfUp => first_dropped >= '5',
+            RoundingMode::HalfEven => {
+                let kept_odd = (kept_value.0 / step) % 2 == 1;
+                first_dropped > '5' || (first_dropped == '5' && (rest_dropped || kept_odd))
+            }
+            RoundingMode::Truncate => false,
+        };
+
+        let value = if round_up {
+            kept_value.0.checked_add(step)?
+        } else {
+            kept_value.0
+        };
+
+        if negative {
+            Some(Money(-value))
+        } else {
+            Some(Money(value))
+        }
+    }
+
+    /// Drops the decimal places after the minor units with the rounding mode
+    ///
+    /// example: `Money(1005)` with 2 minor units rounded half even => `Money(1000)`
+    pub fn round(&self, minor_units: usize, mode: RoundingMode) -> Self {
+        Money::parse_rounded(&self.to_stored_string(), minor_units, mode).unwrap_or(*self)
+    }
+
+    /// Creates the amount from a f64 rounded to the nearest thousandth.
+    /// Used for the balances that are read from REAL columns
+    pub fn from_f64(value: f64) -> Self {
+        Money((value * SCALE as f64).round() as i64)
+    }
+
+    /// Returns the amount as f64. Only meant for ratios and drawing, not for further calculations
+    pub fn to_f64(&self) -> f64 {
+        self.0 as f64 / SCALE as f64
+    }
+
+    /// Returns the absolute amount
+    pub fn abs(&self) -> Self {
+        Money(self.0.saturating_abs())
+    }
+
+    /// Cuts off the decimal places after the given minor units
+    pub fn truncate(&self, minor_units: usize) -> Self {
+        let divisor = 10_i64.pow(SCALE_DIGITS.saturating_sub(minor_units) as u32);
+        Money(self.0 / divisor * divisor)
+    }
+
+    /// Returns the amount with the given number of decimal places. Extra decimal
+    /// places are rounded half away from zero. With 0 minor units the text has no dot
+    ///
+    /// example: `Money(1005)` with 2 minor units => `1.01`
+    pub fn format(&self, minor_units: usize) -> String {
+        let units = minor_units.min(SCALE_DIGITS);
+        let divisor = 10_i64.pow((SCALE_DIGITS - units) as u32);
+        let rounded = (self.0.saturating_abs() + divisor / 2) / divisor;
+
+        let unit_scale = 10_i64.pow(units as u32);
+        let whole = rounded / unit_scale;
+        let fraction = rounded % unit_scale;
+
+        let sign = if self.0 < 0 && rounded != 0 { "-" } else { "" };
+
+        if minor_units == 0 {
+            return format!("{sign}{whole}");
+        }
+
+        let extra_zeros = "0".repeat(minor_units - units);
+        format!("{sign}{whole}.{fraction:0units$}{extra_zeros}")
+    }
+
+    /// Returns the amount with every decimal place it keeps. Used for the balances saved in the db
+    pub fn to_stored_string(&self) -> String {
+        self.format(SCALE_DIGITS)
+    }
+}
+
+/// Shows 2 decimal places unless a precision such as `{:.3}` is given
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(f.precision().unwrap_or(2)))
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        Money(self.0 + other.0)
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        Money(self.0 - other.0)
+    }
+}
+
+impl Mul<i64> for Money {
+    type Output = Money;
+
+    fn mul(self, times: i64) -> Money {
+        Money(self.0 * times)
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        self.0 += other.0;
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, other: Money) {
+        self.0 -= other.0;
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, |total, amount| total + amount)
+    }
+}
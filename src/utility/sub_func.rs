@@ -1,12 +1,28 @@
+use crate::db::{
+    check_db_health, find_newest_backup, get_archive_year_bounds, get_date_range,
+    get_details_templates, get_moved_aside_path, get_recovery_actions, get_setting, get_templates,
+    is_archive_attached, is_valid_setting, merge_tags, merge_tx_methods, DateRange, DbHealth,
+    RecoveryAction, ARCHIVE_DB, SETTINGS,
+};
 use crate::outputs::{ComparisonType, TerminalExecutionError};
 use crate::page_handler::UserInputType;
+use crate::tx_handler::{
+    csv_to_txs, get_csv_accounts, get_default_method, get_journal_accounts, is_valid_new_method,
+    journal_to_txs, parse_journal, read_csv_export, ImportProfile,
+};
 use crate::utility::{
-    check_comparison, check_restricted, clear_terminal, flush_output, get_all_tx_methods,
-    get_sql_dates, take_input,
+    check_comparison, check_n_create_db, check_restricted, clear_terminal, flush_output,
+    get_all_tags, get_all_tx_methods, get_colliding_name, get_hidden_tx_methods, get_minor_units,
+    get_name_collisions, get_sql_dates, get_tag_bounds, get_tag_groups, get_tx_year_bounds,
+    match_tokens, normalize_name, take_input, Money, LOCK_FILE,
 };
-use rusqlite::Connection;
+use chrono::{Datelike, Local, NaiveDate};
+use rusqlite::{Connection, OpenFlags};
 use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs;
 use std::io::stdout;
+use std::path::Path;
 use std::process::Command;
 
 /// Returns the balance of all methods based on year and month point.
@@ -18,16 +34,29 @@ pub fn get_last_time_balance(
     tx_method: &Vec<String>,
     conn: &Connection,
 ) -> HashMap<String, f64> {
+    get_last_time_money(month, year, tx_method, conn)
+        .into_iter()
+        .map(|(method, balance)| (method, balance.to_f64()))
+        .collect()
+}
+
+/// Same as `get_last_time_balance` but the balances are returned as Money to calculate with
+pub fn get_last_time_money(
+    month: usize,
+    year: usize,
+    tx_method: &Vec<String>,
+    conn: &Connection,
+) -> HashMap<String, Money> {
     // We can get the id_num of the month which is saved in the database based on the
     // month and year index there is passed.
     let target_id_num = month as i32 + (year as i32 * 12);
 
     let mut final_value = HashMap::new();
     for i in tx_method {
-        final_value.insert(i.to_string(), 0.0);
+        final_value.insert(i.to_string(), Money::ZERO);
     }
 
-    // balance_all starts at point 1. 1 means month 1, year 2022/0.
+    // balance_all starts at point 1. 1 means month 1 of the first year of the date range/0.
     // There is no earlier balance than this
     if target_id_num == 0 {
         return final_value;
@@ -66,10 +95,10 @@ pub fn get_last_time_balance(
         // check each tx_method column in the current row
         for (i, item) in tx_method.iter().enumerate() {
             if !checked_methods.contains(&item.as_str()) {
-                let balance: f64 = row.get(i).unwrap();
+                let balance = Money::from_f64(row.get(i).unwrap());
 
                 // we only need non-zero balance
-                if balance != 0.0 {
+                if balance != Money::ZERO {
                     *final_value.get_mut(item).unwrap() = balance;
                     checked_methods.push(item);
                 }
@@ -80,12 +109,103 @@ pub fn get_last_time_balance(
     final_value
 }
 
+/// Returns the balance of all methods at the end of the given YYYY-MM-DD date. Starts from the
+/// balance saved before the month of the date and applies the month's transactions up to the date.
+/// Dates before the first transaction return the starting balances
+pub fn get_all_balances_on_date(date: &str, conn: &Connection) -> HashMap<String, f64> {
+    get_money_on_date(date, conn)
+        .into_iter()
+        .map(|(method, balance)| (method, balance.to_f64()))
+        .collect()
+}
+
+/// Returns the balance of a single tx method at the end of the given YYYY-MM-DD date.
+/// Unknown methods return 0
+pub fn get_balance_on_date(tx_method: &str, date: &str, conn: &Connection) -> f64 {
+    get_money_on_date(date, conn)
+        .get(tx_method)
+        .copied()
+        .unwrap_or_default()
+        .to_f64()
+}
+
+/// Applies a transaction on the balances of its tx methods. Every balance calculation
+/// goes through this so the balances on a date always match the ones the chart plots
+pub fn apply_tx_to_money(
+    balance: &mut HashMap<String, Money>,
+    tx_method: &str,
+    amount: Money,
+    tx_type: &str,
+) {
+    match tx_type {
+        "Expense" => *balance.entry(tx_method.to_string()).or_default() -= amount,
+        "Income" | "Refund" => *balance.entry(tx_method.to_string()).or_default() += amount,
+        "Transfer" => {
+            let splitted = tx_method.split(" to ").collect::<Vec<&str>>();
+            if splitted.len() == 2 {
+                *balance.entry(splitted[0].to_string()).or_default() -= amount;
+                *balance.entry(splitted[1].to_string()).or_default() += amount;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Same as `get_all_balances_on_date` but the balances are returned as Money to calculate with
+pub fn get_money_on_date(date: &str, conn: &Connection) -> HashMap<String, Money> {
+    let all_tx_methods = get_all_tx_methods(conn);
+    let date_range = get_date_range(conn);
+
+    let parsed_date = match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        Ok(parsed) => parsed,
+        Err(_) => return get_last_time_money(0, 0, &all_tx_methods, conn),
+    };
+
+    // dates after the range start from the last month of the range
+    let (month, year) = if parsed_date.year() < date_range.start_year {
+        return get_last_time_money(0, 0, &all_tx_methods, conn);
+    } else if parsed_date.year() > date_range.end_year {
+        (11, date_range.total_years() - 1)
+    } else {
+        (
+            parsed_date.month0() as usize,
+            date_range.year_index(parsed_date.year()),
+        )
+    };
+
+    let mut balance = get_last_time_money(month, year, &all_tx_methods, conn);
+
+    let (datetime_1, _) = get_sql_dates(month, year, &date_range);
+
+    let mut statement = conn
+        .prepare(
+            "SELECT tx_method, amount, tx_type FROM tx_all Where date BETWEEN date(?) AND date(?) ORDER BY date, id_num",
+        )
+        .expect("could not prepare statement");
+
+    let rows = statement
+        .query_map([&datetime_1, date], |row| {
+            let tx_method: String = row.get(0)?;
+            let amount: String = row.get(1)?;
+            let tx_type: String = row.get(2)?;
+            Ok((tx_method, amount, tx_type))
+        })
+        .unwrap();
+
+    for (tx_method, amount, tx_type) in rows.flatten() {
+        let amount = Money::parse(&amount).unwrap_or_default();
+        apply_tx_to_money(&mut balance, &tx_method, amount, &tx_type);
+    }
+
+    balance
+}
+
 /// The functions sends all the changes that happened after transactions on the month and year provided
 pub fn get_all_changes(month: usize, year: usize, conn: &Connection) -> Vec<Vec<String>> {
     let mut final_result = Vec::new();
     let tx_methods = get_all_tx_methods(conn);
 
-    let (datetime_1, datetime_2) = get_sql_dates(month, year);
+    let (datetime_1, datetime_2) = get_sql_dates(month, year, &get_date_range(conn));
 
     let mut statement = conn
         .prepare("SELECT * FROM changes_all Where date BETWEEN date(?) AND date(?) ORDER BY date, id_num")
@@ -126,9 +246,9 @@ pub fn get_all_txs(
     // current month's transactions to the related tx method. After each tx calculation, add whatever
     // balance for each tx method inside a vec to finally return them
 
-    let mut last_month_balance = get_last_time_balance(month, year, &all_tx_methods, conn);
+    let mut last_month_balance = get_last_time_money(month, year, &all_tx_methods, conn);
 
-    let (datetime_1, datetime_2) = get_sql_dates(month, year);
+    let (datetime_1, datetime_2) = get_sql_dates(month, year, &get_date_range(conn));
 
     // preparing the query for db, getting current month's all transactions
     let mut statement = conn
@@ -171,64 +291,34 @@ pub fn get_all_txs(
     for i in &final_all_txs {
         // this is where the calculation for the balance happens. We will loop through each tx,
         // look at the tx type, tx method and add/subtract the amount on last month balance which was fetched earlier
-
-        // collect data inside variables
-        let tx_type = &i[4];
-        let amount = &i[3].to_string().parse::<f64>().unwrap();
-        let tx_method = &i[2];
-
-        // If the transaction is not a transfer, default balance goes to new_balance_from
-        // and new_balance_to remains empty. On transfer TX both of them are used
-
-        let mut new_balance_from: f64 = 0.0;
-        let mut new_balance_to: f64 = 0.0;
-
-        let mut from_method = "".to_string();
-        let mut to_method = "".to_string();
-
-        // add or subtract the amount based on the tx type
-        if tx_type == "Expense" {
-            new_balance_from = last_month_balance[tx_method] - amount;
-        } else if tx_type == "Income" {
-            new_balance_from = last_month_balance[tx_method] + amount;
-        } else if tx_type == "Transfer" {
-            let splitted = tx_method.split(" to ").collect::<Vec<&str>>();
-            from_method = splitted[0].to_string();
-            to_method = splitted[1].to_string();
-            new_balance_from = last_month_balance[&from_method] - amount;
-            new_balance_to = last_month_balance[&to_method] + amount;
-        }
-
-        // make changes to the balance map based on the tx
-        // for transfer TX first block executes
-        // new_balance_to != 0 means it's a transfer transaction
-        if new_balance_to != 0.0 {
-            *last_month_balance.get_mut(&from_method).unwrap() = new_balance_from;
-            *last_month_balance.get_mut(&to_method).unwrap() = new_balance_to;
-        } else {
-            *last_month_balance.get_mut(tx_method).unwrap() = new_balance_from;
-        }
+        let amount = Money::parse(&i[3]).unwrap();
+        apply_tx_to_money(&mut last_month_balance, &i[2], amount, &i[4]);
 
         // push all the changes gathered to the return variable
         let mut to_push = vec![];
         for i in &all_tx_methods {
-            to_push.push(format!("{:.2}", last_month_balance[i]))
+            to_push.push(last_month_balance[i].to_string())
         }
 
         final_all_balances.push(to_push);
     }
 
     // pushes the final balance that was calculated just now to the db on the balance_all table
+    // every decimal place is kept so the saved balance is exactly the calculated one
     if !final_all_balances.is_empty() {
         let target_id_num = month as i32 + 1 + (year as i32 * 12);
-        let final_index = final_all_balances.len() - 1;
 
         let balance_query = format!(
             "UPDATE balance_all SET {} WHERE id_num = {}",
-            final_all_balances[final_index]
+            all_tx_methods
                 .iter()
-                .enumerate()
-                .map(|(i, balance)| format!(r#""{}" = "{}""#, all_tx_methods[i], balance))
+                .map(|method| {
+                    format!(
+                        r#""{}" = "{}""#,
+                        method,
+                        last_month_balance[method].to_stored_string()
+                    )
+                })
                 .collect::<Vec<String>>()
                 .join(", "),
             target_id_num
@@ -261,6 +351,15 @@ pub fn get_last_balances(conn: &Connection) -> Vec<String> {
     final_balance.unwrap()
 }
 
+/// Returns the absolute final balance of the given tx method. None if the method does not exist
+pub fn get_method_balance(tx_method: &str, conn: &Connection) -> Option<Money> {
+    let index = get_all_tx_methods(conn)
+        .iter()
+        .position(|method| method == tx_method)?;
+
+    Money::parse(&get_last_balances(conn)[index])
+}
+
 /// Prompts the user to select and option and start taking relevant inputs
 #[cfg(not(tarpaulin_include))]
 pub fn start_taking_input(conn: &Connection) -> UserInputType {
@@ -273,7 +372,19 @@ pub fn start_taking_input(conn: &Connection) -> UserInputType {
 
 1. Add New Transaction Methods
 2. Rename Transaction Method
-3. Reposition Transactions Methods\n"
+3. Reposition Transactions Methods
+4. Archive Old Transactions
+5. Un-archive Transactions
+6. Assign Tag Groups
+7. Change Settings
+8. Manage Transaction Templates
+9. Set Transaction Method Decimal Places
+10. Set Tag Amount Bounds
+11. Change Year Range
+12. Hide or Show Transaction Methods
+13. Import a Ledger Journal
+14. Manage Details Templates
+15. Import a CSV Export\n"
         );
         print!("Proceed with option number: ");
         flush_output(&stdout);
@@ -285,6 +396,22 @@ pub fn start_taking_input(conn: &Connection) -> UserInputType {
             UserInputType::AddNewTxMethod(_) => return get_user_tx_methods(true, Some(conn)),
             UserInputType::RenameTxMethod(_) => return get_rename_data(conn),
             UserInputType::RepositionTxMethod(_) => return get_reposition_data(conn),
+            UserInputType::ArchiveTxs(_) => return get_archive_data(),
+            UserInputType::UnarchiveTxs => return get_unarchive_data(),
+            UserInputType::AssignTagGroup(_) => return get_tag_group_data(conn),
+            UserInputType::ChangeSetting(_) => return get_setting_data(conn),
+            UserInputType::AddTemplate(_) | UserInputType::DeleteTemplate(_) => {
+                return get_template_data(conn)
+            }
+            UserInputType::SetMinorUnits(..) => return get_minor_units_data(conn),
+            UserInputType::SetTagBounds(..) => return get_tag_bounds_data(conn),
+            UserInputType::SetDateRange(_) => return get_date_range_data(conn),
+            UserInputType::ToggleHiddenMethod(_) => return get_hidden_method_data(conn),
+            UserInputType::ImportJournal(..) => return get_journal_data(conn),
+            UserInputType::ImportCsv(..) => return get_csv_data(conn),
+            UserInputType::AddDetailsTemplate(_) | UserInputType::DeleteDetailsTemplate(_) => {
+                return get_details_template_data(conn)
+            }
             UserInputType::CancelledOperation => return input_type,
             UserInputType::InvalidInput => clear_terminal(&mut stdout),
         }
@@ -351,11 +478,11 @@ Example input: Bank, Cash, PayPal.\n\nEnter Transaction Methods: "
         }
 
         // split them and remove duplicates
-        let mut inputted_methods: Vec<&str> = line
+        let mut inputted_methods: Vec<String> = line
             .split(',')
-            .map(|s| s.trim())
+            .map(normalize_name)
             .filter(|s| !s.is_empty())
-            .collect::<Vec<&str>>();
+            .collect::<Vec<String>>();
 
         // Check if the input is not empty. If yes, start from the beginning
         if inputted_methods.is_empty() {
@@ -372,10 +499,9 @@ Example input: Bank, Cash, PayPal.\n\nEnter Transaction Methods: "
                 continue 'outer_loop;
             }
 
-            if !current_tx_methods.is_empty() && check_restricted(method, Some(&current_tx_methods))
-            {
+            if let Some(existing_method) = get_colliding_name(method, &current_tx_methods) {
                 clear_terminal(&mut stdout);
-                println!("Transaction Methods already exists. Use a different value\n");
+                println!("Transaction Method {method} already exists as {existing_method}. Use {existing_method} or a different value\n");
                 continue 'outer_loop;
             }
         }
@@ -403,9 +529,7 @@ Example input: Bank, Cash, PayPal.\n\nEnter Transaction Methods: "
 
         // until the answer is y/cancel continue the loop
         if verify_line.to_lowercase().starts_with('y') {
-            for i in inputted_methods {
-                db_tx_methods.push(i.to_string());
-            }
+            db_tx_methods.extend(inputted_methods);
             break;
         } else {
             clear_terminal(&mut stdout);
@@ -476,7 +600,7 @@ Currently added Transaction Methods: \n"
         print!("New method name: ");
         flush_output(&stdout);
 
-        let new_method_name = take_input();
+        let new_method_name = normalize_name(&take_input());
 
         if new_method_name.to_lowercase().contains("to") {
             clear_terminal(&mut stdout);
@@ -485,9 +609,9 @@ Currently added Transaction Methods: \n"
         }
 
         // Start from the beginning if the given tx method already exists
-        if check_restricted(&new_method_name, Some(&tx_methods)) {
+        if let Some(existing_method) = get_colliding_name(&new_method_name, &tx_methods) {
             clear_terminal(&mut stdout);
-            println!("Transaction Methods already exists. Use a different value\n");
+            println!("Transaction Method {new_method_name} already exists as {existing_method}. Use a different value\n");
             continue;
         }
 
@@ -619,156 +743,1227 @@ Currently added Transaction Methods: \n".to_string();
     UserInputType::RepositionTxMethod(reposition_data)
 }
 
-/// Tries to open terminal/cmd and run this app
+/// Asks the user for the month before which all transactions will be moved to the archive
 #[cfg(not(tarpaulin_include))]
-pub fn start_terminal(original_dir: &str) -> Result<(), TerminalExecutionError> {
-    if cfg!(target_os = "windows") {
-        Command::new("cmd.exe")
-            .arg("start")
-            .arg("rex")
-            .output()
-            .map_err(TerminalExecutionError::ExecutionFailed)?;
-    } else {
-        let mut all_terminals = HashMap::new();
-        let gnome_dir = format!("--working-directory={}", original_dir);
+pub fn get_archive_data() -> UserInputType {
+    let mut stdout = stdout();
+    clear_terminal(&mut stdout);
 
-        // TODO add more terminal support
-        all_terminals.insert(
-            "konsole",
-            vec![
-                "--new-tab".to_string(),
-                "--workdir".to_string(),
-                original_dir.to_string(),
-                "-e".to_string(),
-                "./rex".to_string(),
-            ],
+    loop {
+        println!(
+            "All transactions before the given month will be moved to the archive database.
+Balances will stay the same. Input 'Cancel' to cancel the operation.\n"
         );
+        print!("Enter the cutoff month. Example: 2023-01: ");
+        flush_output(&stdout);
 
-        all_terminals.insert(
-            "gnome-terminal",
-            vec![
-                gnome_dir,
-                "--maximize".to_string(),
-                "--".to_string(),
-                "./rex".to_string(),
-            ],
+        let user_input = take_input();
+
+        if user_input.to_lowercase().starts_with("cancel") {
+            return UserInputType::CancelledOperation;
+        }
+
+        let cutoff = format!("{}-01", user_input.trim());
+
+        if NaiveDate::parse_from_str(&cutoff, "%Y-%m-%d").is_err() {
+            clear_terminal(&mut stdout);
+            println!("Invalid month given. Example input: 2023-01\n");
+            continue;
+        }
+
+        print!("\nArchive all transactions before {cutoff}? y/n: ");
+        flush_output(&stdout);
+
+        if take_input().to_lowercase().starts_with('y') {
+            return UserInputType::ArchiveTxs(cutoff);
+        }
+        clear_terminal(&mut stdout);
+    }
+}
+
+/// Asks the user for a ledger journal file and the tx method of every asset and liability
+/// account in it that does not match a tx method already
+#[cfg(not(tarpaulin_include))]
+pub fn get_journal_data(conn: &Connection) -> UserInputType {
+    let mut stdout = stdout();
+    clear_terminal(&mut stdout);
+
+    let tx_methods = get_all_tx_methods(conn);
+
+    let (entries, mut problems) = loop {
+        println!(
+            "Transactions of a ledger or hledger journal file will be added to the database.
+Asset and liability accounts become tx methods and expense and income accounts become tags.
+Input 'Cancel' to cancel the operation.\n"
         );
+        print!("Enter the path of the journal file: ");
+        flush_output(&stdout);
 
-        let mut terminal_opened = false;
-        let mut result = None;
+        let user_input = take_input();
 
-        for (key, value) in all_terminals {
-            let status = Command::new(key).args(value).output();
-            match status {
-                Ok(out) => {
-                    if out.stderr.len() > 2 {
-                        result = Some(TerminalExecutionError::NotFound(out))
-                    } else {
-                        terminal_opened = true;
-                        break;
-                    }
-                }
-                Err(err) => result = Some(TerminalExecutionError::ExecutionFailed(err)),
-            }
+        if user_input.to_lowercase().starts_with("cancel") {
+            return UserInputType::CancelledOperation;
         }
-        if !terminal_opened {
-            return Err(result.unwrap());
+
+        let text = match fs::read_to_string(&user_input) {
+            Ok(text) => text,
+            Err(e) => {
+                clear_terminal(&mut stdout);
+                println!("Could not read the file. Error: {e}\n");
+                continue;
+            }
+        };
+
+        let (entries, problems) = parse_journal(&text);
+
+        if entries.is_empty() {
+            clear_terminal(&mut stdout);
+            println!("No transactions were found in the file.\n");
+            continue;
         }
+        break (entries, problems);
     };
-    Ok(())
-}
 
-/// Creates the query to search for specific tx, gathers all rows and id numbers
-pub fn get_search_data(
-    date: &str,
-    details: &str,
-    from_method: &str,
-    to_method: &str,
-    amount: &str,
-    tx_type: &str,
-    tags: &str,
-    conn: &Connection,
-) -> (Vec<Vec<String>>, Vec<String>) {
-    let mut all_txs = Vec::new();
-    let mut all_ids = Vec::new();
+    let mut method_map = HashMap::new();
+    let mut method_line = "Accounts and their tx methods:\n".to_string();
 
-    let mut query = "SELECT * FROM tx_all WHERE 1=1".to_string();
+    for account in get_journal_accounts(&entries) {
+        let method = match get_default_method(&account, &tx_methods) {
+            Some(method) => Some(method),
+            None => ask_journal_method(&account, &tx_methods),
+        };
 
-    if !date.is_empty() {
-        query.push_str(&format!(r#" AND date = "{}""#, date));
+        match method {
+            Some(method) => {
+                method_line.push_str(&format!("\n{account} -> {method}"));
+                method_map.insert(account, method);
+            }
+            None => method_line.push_str(&format!("\n{account} -> Skipped")),
+        }
     }
 
-    if !details.is_empty() {
-        query.push_str(&format!(r#" AND details LIKE "%{}%""#, details));
-    }
+    let (txs, entry_problems) = journal_to_txs(&entries, &method_map);
+    problems.extend(entry_problems);
+
+    clear_terminal(&mut stdout);
+    println!("{method_line}\n");
+    println!(
+        "{} transactions are ready to be imported. {} lines or transactions cannot be imported",
+        txs.len(),
+        problems.len()
+    );
+    print!("\nImport the transactions? y/n: ");
+    flush_output(&stdout);
 
-    if !tx_type.is_empty() {
-        query.push_str(&format!(r#" AND tx_type = "{}""#, tx_type));
+    if take_input().to_lowercase().starts_with('y') {
+        UserInputType::ImportJournal(txs, problems)
+    } else {
+        UserInputType::CancelledOperation
     }
+}
+
+/// Asks the user for a CSV export file and its profile. Accounts that do not match a tx method
+/// become new tx methods once the user confirms it, otherwise they are asked like journal accounts
+#[cfg(not(tarpaulin_include))]
+pub fn get_csv_data(conn: &Connection) -> UserInputType {
+    let mut stdout = stdout();
+    clear_terminal(&mut stdout);
+
+    let tx_methods = get_all_tx_methods(conn);
+    let profiles = ImportProfile::all();
+
+    let (profile, txs, mut problems) = loop {
+        let mut profile_line = "Transactions of a CSV export will be added to the database.
+Imported transactions are remembered so importing the same export again only adds the new ones.
+Input 'Cancel' to cancel the operation.\n\nSupported exports:\n"
+            .to_string();
+        for (i, profile) in profiles.iter().enumerate() {
+            profile_line.push_str(&format!("\n{}. {}", i + 1, profile.get_name()));
+        }
+        println!("{profile_line}\n");
+        print!("Enter the export number: ");
+        flush_output(&stdout);
 
-    if !amount.is_empty() {
-        let comparison_type = check_comparison(amount);
+        let user_input = take_input();
+
+        if user_input.to_lowercase().starts_with("cancel") {
+            return UserInputType::CancelledOperation;
+        }
 
-        let comparison_symbol = match comparison_type {
-            ComparisonType::BiggerThan => ">",
-            ComparisonType::SmallerThan => "<",
-            ComparisonType::Equal => "",
-            ComparisonType::EqualOrBigger => ">=",
-            ComparisonType::EqualOrSmaller => "<=",
+        let profile = match user_input.parse::<usize>() {
+            Ok(num) if num != 0 && num <= profiles.len() => profiles[num - 1],
+            _ => {
+                clear_terminal(&mut stdout);
+                println!("Invalid export number. Example input: 1\n");
+                continue;
+            }
         };
-        let amount = amount.replace(comparison_symbol, "");
 
-        query.push_str(&format!(r#" AND {} "{}""#, comparison_type, amount));
-    }
+        print!("Enter the path of the {} CSV file: ", profile.get_name());
+        flush_output(&stdout);
+
+        let user_input = take_input();
+
+        if user_input.to_lowercase().starts_with("cancel") {
+            return UserInputType::CancelledOperation;
+        }
 
-    if tx_type == "Transfer" && !from_method.is_empty() && !to_method.is_empty() {
-        query.push_str(&format!(
-            r#" AND tx_method = "{} to {}""#,
-            from_method, to_method
-        ));
-    } else if tx_type != "Transfer" && !from_method.is_empty() {
-        query.push_str(&format!(r#" AND tx_method = "{}""#, from_method));
+        let text = match fs::read_to_string(&user_input) {
+            Ok(text) => text,
+            Err(e) => {
+                clear_terminal(&mut stdout);
+                println!("Could not read the file. Error: {e}\n");
+                continue;
+            }
+        };
+
+        match read_csv_export(&text, profile) {
+            Ok((txs, _)) if txs.is_empty() => {
+                clear_terminal(&mut stdout);
+                println!("No transactions were found in the file.\n");
+            }
+            Ok((txs, problems)) => break (profile, txs, problems),
+            Err(e) => {
+                clear_terminal(&mut stdout);
+                println!("{e}\n");
+            }
+        }
+    };
+
+    let mut method_map = HashMap::new();
+    let mut unseen_accounts = Vec::new();
+
+    for account in get_csv_accounts(&txs) {
+        match get_colliding_name(&account, &tx_methods) {
+            Some(method) => {
+                method_map.insert(account, method);
+            }
+            None => unseen_accounts.push(account),
+        }
     }
 
-    if !tags.is_empty() {
-        let all_tags = tags.split(", ");
-        let tag_conditions = all_tags
-            .map(|tag| format!(r#""," || tags || "," LIKE "%{}%""#, tag))
-            .collect::<Vec<String>>()
-            .join(" OR ");
-        query.push_str(&format!(" AND ({})", tag_conditions));
+    let (creatable, mut unmapped): (Vec<String>, Vec<String>) = unseen_accounts
+        .into_iter()
+        .partition(|account| is_valid_new_method(account));
+
+    let mut new_methods = Vec::new();
+
+    if !creatable.is_empty() {
+        clear_terminal(&mut stdout);
+        println!("These accounts do not match any tx method:\n");
+        for account in &creatable {
+            println!("- {account}");
+        }
+        print!("\nCreate them as new tx methods? y/n: ");
+        flush_output(&stdout);
+
+        if take_input().to_lowercase().starts_with('y') {
+            for account in creatable {
+                method_map.insert(account.clone(), account.clone());
+                new_methods.push(account);
+            }
+        } else {
+            unmapped.extend(creatable);
+        }
     }
 
-    let mut statement = conn.prepare(&query).unwrap();
+    let mut method_line = "Accounts and their tx methods:\n".to_string();
 
-    let rows = statement
-        .query_map([], |row| {
-            let date: String = row.get(0).unwrap();
-            let id_num: i32 = row.get(5).unwrap();
-            let collected_date = date.split('-').collect::<Vec<&str>>();
-            let new_date = format!(
-                "{}-{}-{}",
-                collected_date[2], collected_date[1], collected_date[0]
-            );
+    for account in get_csv_accounts(&txs) {
+        if unmapped.contains(&account) {
+            if let Some(method) = ask_journal_method(&account, &tx_methods) {
+                method_map.insert(account.clone(), method);
+            }
+        }
 
-            Ok(vec![
-                new_date,
-                row.get(1).unwrap(),
-                row.get(2).unwrap(),
-                row.get(3).unwrap(),
-                row.get(4).unwrap(),
-                row.get(6).unwrap(),
-                id_num.to_string(),
-            ])
-        })
-        .unwrap();
+        match method_map.get(&account) {
+            Some(method) if new_methods.contains(method) => {
+                method_line.push_str(&format!("\n{account} -> {method} (new)"))
+            }
+            Some(method) => method_line.push_str(&format!("\n{account} -> {method}")),
+            None => method_line.push_str(&format!("\n{account} -> Skipped")),
+        }
+    }
 
-    for i in rows.flatten() {
-        let mut data = i;
-        let id_num = &data.pop().unwrap();
-        all_ids.push(id_num.to_string());
-        all_txs.push(data);
+    let (new_txs, tx_problems) = csv_to_txs(&txs, &method_map);
+    problems.extend(tx_problems);
+
+    clear_terminal(&mut stdout);
+    println!("{method_line}\n");
+    println!(
+        "{} transactions are ready to be imported. {} lines or transactions cannot be imported",
+        new_txs.len(),
+        problems.len()
+    );
+    print!("\nImport the transactions? y/n: ");
+    flush_output(&stdout);
+
+    if take_input().to_lowercase().starts_with('y') {
+        UserInputType::ImportCsv(profile, new_methods, new_txs, problems)
+    } else {
+        UserInputType::CancelledOperation
     }
+}
 
-    (all_txs, all_ids)
+/// Asks the tx method of a journal account. None if the user skips the account
+#[cfg(not(tarpaulin_include))]
+fn ask_journal_method(account: &str, tx_methods: &[String]) -> Option<String> {
+    let mut stdout = stdout();
+
+    loop {
+        let mut method_line = format!("\nAccount {account} does not match any tx method.\n");
+        for (i, method) in tx_methods.iter().enumerate() {
+            method_line.push_str(&format!("\n{}. {}", i + 1, method));
+        }
+        println!("{method_line}");
+        print!("\nEnter the tx method number or leave it empty to skip its transactions: ");
+        flush_output(&stdout);
+
+        let user_input = take_input();
+
+        if user_input.is_empty() {
+            return None;
+        }
+
+        match user_input.parse::<usize>() {
+            Ok(num) if num != 0 && num <= tx_methods.len() => {
+                return Some(tx_methods[num - 1].clone())
+            }
+            _ => {
+                clear_terminal(&mut stdout);
+                println!("Invalid tx method number. Example input: 1");
+            }
+        }
+    }
+}
+
+/// Asks the user for the confirmation to move all archived transactions back to the main database
+#[cfg(not(tarpaulin_include))]
+pub fn get_unarchive_data() -> UserInputType {
+    let stdout = stdout();
+
+    print!("\nMove all archived transactions back to the main database? y/n: ");
+    flush_output(&stdout);
+
+    if take_input().to_lowercase().starts_with('y') {
+        UserInputType::UnarchiveTxs
+    } else {
+        UserInputType::CancelledOperation
+    }
+}
+
+/// Asks the user to select a tag and the group it will be rolled up to on the Summary page
+#[cfg(not(tarpaulin_include))]
+pub fn get_tag_group_data(conn: &Connection) -> UserInputType {
+    let mut stdout = stdout();
+    clear_terminal(&mut stdout);
+
+    let all_tags = get_all_tags(conn);
+    let tag_groups = get_tag_groups(conn);
+
+    if all_tags.is_empty() {
+        return UserInputType::CancelledOperation;
+    }
+
+    loop {
+        let mut tag_line =
+            "Select a Tag to assign a group to. Input 'Cancel' to cancel the operation.
+
+Currently added Tags: \n"
+                .to_string();
+
+        for (i, tag) in all_tags.iter().enumerate() {
+            match tag_groups.get(tag) {
+                Some(group_name) => {
+                    tag_line.push_str(&format!("\n{}. {} -> {}", i + 1, tag, group_name))
+                }
+                None => tag_line.push_str(&format!("\n{}. {}", i + 1, tag)),
+            }
+        }
+        println!("{tag_line}");
+        print!("\nEnter the tag number to edit: ");
+        flush_output(&stdout);
+
+        let user_input = take_input();
+
+        if user_input.to_lowercase().starts_with("cancel") {
+            return UserInputType::CancelledOperation;
+        }
+
+        let tag_number = match user_input.parse::<usize>() {
+            Ok(num) if num != 0 && num <= all_tags.len() => num,
+            _ => {
+                clear_terminal(&mut stdout);
+                println!("Invalid tag number. Example input: 1\n");
+                continue;
+            }
+        };
+
+        let selected_tag = &all_tags[tag_number - 1];
+
+        println!(
+            "\nSelected tag: {selected_tag}. Enter the group name. Leave empty to remove it from its group."
+        );
+        print!("Group name: ");
+        flush_output(&stdout);
+
+        let group_name = take_input();
+
+        if check_restricted(&group_name, None) {
+            clear_terminal(&mut stdout);
+            println!("Group name cannot be a restricted word\n");
+            continue;
+        }
+
+        return UserInputType::AssignTagGroup(vec![selected_tag.to_string(), group_name]);
+    }
+}
+
+/// Asks the user to select a tx method and the number of decimal places its amounts use
+#[cfg(not(tarpaulin_include))]
+pub fn get_minor_units_data(conn: &Connection) -> UserInputType {
+    let mut stdout = stdout();
+    clear_terminal(&mut stdout);
+
+    let tx_methods = get_all_tx_methods(conn);
+
+    loop {
+        let mut method_line =
+            "Select a Transaction Method to set the decimal places of. Input 'Cancel' to cancel the operation.
+
+Currently added Transaction Methods: \n"
+                .to_string();
+
+        for (i, method) in tx_methods.iter().enumerate() {
+            method_line.push_str(&format!(
+                "\n{}. {} -> {}",
+                i + 1,
+                method,
+                get_minor_units(method, conn)
+            ));
+        }
+        println!("{method_line}");
+        print!("\nEnter the method number to edit: ");
+        flush_output(&stdout);
+
+        let user_input = take_input();
+
+        if user_input.to_lowercase().starts_with("cancel") {
+            return UserInputType::CancelledOperation;
+        }
+
+        let method_number = match user_input.parse::<usize>() {
+            Ok(num) if num != 0 && num <= tx_methods.len() => num,
+            _ => {
+                clear_terminal(&mut stdout);
+                println!("Invalid method number. Example input: 1\n");
+                continue;
+            }
+        };
+
+        let selected_method = &tx_methods[method_number - 1];
+
+        println!(
+            "\nSelected method: {selected_method}. Enter the decimal places. Supported: 0, 2, 3"
+        );
+        print!("Decimal places: ");
+        flush_output(&stdout);
+
+        let units = match take_input().parse::<usize>() {
+            Ok(units) if [0, 2, 3].contains(&units) => units,
+            _ => {
+                clear_terminal(&mut stdout);
+                println!("Invalid decimal places. Supported: 0, 2, 3\n");
+                continue;
+            }
+        };
+
+        return UserInputType::SetMinorUnits(selected_method.to_string(), units);
+    }
+}
+
+/// Asks the user to select a tx method to hide or show again
+#[cfg(not(tarpaulin_include))]
+pub fn get_hidden_method_data(conn: &Connection) -> UserInputType {
+    let mut stdout = stdout();
+    clear_terminal(&mut stdout);
+
+    let tx_methods = get_all_tx_methods(conn);
+    let hidden_methods = get_hidden_tx_methods(conn);
+
+    loop {
+        let mut method_line =
+            "Select a Transaction Method to hide or show. Hidden methods keep their transactions but are skipped when stepping and autocompleting. Input 'Cancel' to cancel the operation.
+
+Currently added Transaction Methods: \n"
+                .to_string();
+
+        for (i, method) in tx_methods.iter().enumerate() {
+            let status = if hidden_methods.contains(method) {
+                "Hidden"
+            } else {
+                "Shown"
+            };
+            method_line.push_str(&format!("\n{}. {} -> {}", i + 1, method, status));
+        }
+        println!("{method_line}");
+        print!("\nEnter the method number to hide or show: ");
+        flush_output(&stdout);
+
+        let user_input = take_input();
+
+        if user_input.to_lowercase().starts_with("cancel") {
+            return UserInputType::CancelledOperation;
+        }
+
+        match user_input.parse::<usize>() {
+            Ok(num) if num != 0 && num <= tx_methods.len() => {
+                return UserInputType::ToggleHiddenMethod(tx_methods[num - 1].to_string())
+            }
+            _ => {
+                clear_terminal(&mut stdout);
+                println!("Invalid method number. Example input: 1\n");
+            }
+        }
+    }
+}
+
+/// Asks the user to select a tag and the amount range a single transaction of it is expected to be in
+#[cfg(not(tarpaulin_include))]
+pub fn get_tag_bounds_data(conn: &Connection) -> UserInputType {
+    let mut stdout = stdout();
+    clear_terminal(&mut stdout);
+
+    let all_tags = get_all_tags(conn);
+    let tag_bounds = get_tag_bounds(conn);
+
+    if all_tags.is_empty() {
+        return UserInputType::CancelledOperation;
+    }
+
+    // parses an optional bound. Empty input means no bound
+    let parse_bound = |input: &str| -> Result<Option<f64>, ()> {
+        if input.is_empty() {
+            Ok(None)
+        } else {
+            match input.parse::<f64>() {
+                Ok(amount) if amount >= 0.0 => Ok(Some(amount)),
+                _ => Err(()),
+            }
+        }
+    };
+
+    loop {
+        let mut tag_line =
+            "Select a Tag to set the amount bounds of. A transaction with an amount outside the bounds
+will need to be confirmed before saving. Input 'Cancel' to cancel the operation.
+
+Currently added Tags: \n"
+                .to_string();
+
+        for (i, tag) in all_tags.iter().enumerate() {
+            match tag_bounds.get(tag) {
+                Some((min_amount, max_amount)) => {
+                    let min = min_amount.map_or("-".to_string(), |min| format!("{min:.2}"));
+                    let max = max_amount.map_or("-".to_string(), |max| format!("{max:.2}"));
+                    tag_line.push_str(&format!("\n{}. {} -> {} to {}", i + 1, tag, min, max))
+                }
+                None => tag_line.push_str(&format!("\n{}. {}", i + 1, tag)),
+            }
+        }
+        println!("{tag_line}");
+        print!("\nEnter the tag number to edit: ");
+        flush_output(&stdout);
+
+        let user_input = take_input();
+
+        if user_input.to_lowercase().starts_with("cancel") {
+            return UserInputType::CancelledOperation;
+        }
+
+        let tag_number = match user_input.parse::<usize>() {
+            Ok(num) if num != 0 && num <= all_tags.len() => num,
+            _ => {
+                clear_terminal(&mut stdout);
+                println!("Invalid tag number. Example input: 1\n");
+                continue;
+            }
+        };
+
+        let selected_tag = &all_tags[tag_number - 1];
+
+        println!(
+            "\nSelected tag: {selected_tag}. Enter the bounds. Leave a bound empty to not check that side.
+Leave both empty to remove the bounds."
+        );
+        print!("Minimum amount: ");
+        flush_output(&stdout);
+
+        let min_amount = match parse_bound(&take_input()) {
+            Ok(amount) => amount,
+            Err(_) => {
+                clear_terminal(&mut stdout);
+                println!("Invalid amount. Example input: 5.50\n");
+                continue;
+            }
+        };
+
+        print!("Maximum amount: ");
+        flush_output(&stdout);
+
+        let max_amount = match parse_bound(&take_input()) {
+            Ok(amount) => amount,
+            Err(_) => {
+                clear_terminal(&mut stdout);
+                println!("Invalid amount. Example input: 10\n");
+                continue;
+            }
+        };
+
+        if let (Some(min), Some(max)) = (min_amount, max_amount) {
+            if min > max {
+                clear_terminal(&mut stdout);
+                println!("Minimum amount cannot be bigger than the maximum amount\n");
+                continue;
+            }
+        }
+
+        return UserInputType::SetTagBounds(selected_tag.to_string(), min_amount, max_amount);
+    }
+}
+
+/// Asks the user for the first and the last year transactions can be added to.
+/// Years with transactions must stay inside the new range
+#[cfg(not(tarpaulin_include))]
+pub fn get_date_range_data(conn: &Connection) -> UserInputType {
+    let mut stdout = stdout();
+    clear_terminal(&mut stdout);
+
+    let date_range = get_date_range(conn);
+
+    // archived transactions go back to the same years once they are restored
+    let tx_years = match (
+        get_tx_year_bounds(conn),
+        get_archive_year_bounds(ARCHIVE_DB, conn),
+    ) {
+        (Some((first, last)), Some((archive_first, archive_last))) => {
+            Some((first.min(archive_first), last.max(archive_last)))
+        }
+        (bounds, None) | (None, bounds) => bounds,
+    };
+
+    loop {
+        let mut range_line = format!(
+            "Enter the first and the last year transactions can be added to. Input 'Cancel' to cancel the operation.
+
+Current year range: {}-{}",
+            date_range.start_year, date_range.end_year
+        );
+
+        if let Some((first, last)) = tx_years {
+            range_line.push_str(&format!("\nYears with transactions: {first}-{last}"));
+        }
+
+        println!("{range_line}");
+        print!("\nFirst year: ");
+        flush_output(&stdout);
+
+        let user_input = take_input();
+
+        if user_input.to_lowercase().starts_with("cancel") {
+            return UserInputType::CancelledOperation;
+        }
+
+        print!("Last year: ");
+        flush_output(&stdout);
+
+        let years = (user_input.parse::<i32>(), take_input().parse::<i32>());
+
+        let (start_year, end_year) = match years {
+            (Ok(start), Ok(end))
+                if (1000..=9999).contains(&start) && (start..=9999).contains(&end) =>
+            {
+                (start, end)
+            }
+            _ => {
+                clear_terminal(&mut stdout);
+                println!("Invalid years. The last year cannot be smaller than the first year. Example input: 2015, 2040\n");
+                continue;
+            }
+        };
+
+        if let Some((first, last)) = tx_years {
+            if start_year > first || end_year < last {
+                clear_terminal(&mut stdout);
+                println!("The range must include all years with transactions: {first}-{last}\n");
+                continue;
+            }
+        }
+
+        return UserInputType::SetDateRange(DateRange::new(start_year, end_year));
+    }
+}
+
+/// Asks the user to select a setting and the new value for it
+#[cfg(not(tarpaulin_include))]
+pub fn get_setting_data(conn: &Connection) -> UserInputType {
+    let mut stdout = stdout();
+    clear_terminal(&mut stdout);
+
+    loop {
+        let mut setting_line =
+            "Select a setting to change. Input 'Cancel' to cancel the operation.\n".to_string();
+
+        for (i, (key, _, description)) in SETTINGS.iter().enumerate() {
+            setting_line.push_str(&format!(
+                "\n{}. {}: {}\n   {}",
+                i + 1,
+                key,
+                get_setting(key, conn),
+                description
+            ));
+        }
+        println!("{setting_line}");
+        print!("\nEnter the setting number to edit: ");
+        flush_output(&stdout);
+
+        let user_input = take_input();
+
+        if user_input.to_lowercase().starts_with("cancel") {
+            return UserInputType::CancelledOperation;
+        }
+
+        let setting_number = match user_input.parse::<usize>() {
+            Ok(num) if num != 0 && num <= SETTINGS.len() => num,
+            _ => {
+                clear_terminal(&mut stdout);
+                println!("Invalid setting number. Example input: 1\n");
+                continue;
+            }
+        };
+
+        let (key, default, _) = SETTINGS[setting_number - 1];
+
+        println!("\nSelected setting: {key}. Enter the new value. Leave empty to use the default value {default}.");
+        print!("New value: ");
+        flush_output(&stdout);
+
+        let mut new_value = take_input();
+
+        if new_value.is_empty() {
+            new_value = default.to_string();
+        }
+
+        if !is_valid_setting(key, &new_value) {
+            clear_terminal(&mut stdout);
+            println!("Invalid value given for {key}. Example value: {default}\n");
+            continue;
+        }
+
+        return UserInputType::ChangeSetting(vec![key.to_string(), new_value]);
+    }
+}
+
+/// Asks the user to add a new transaction template or to select one to delete
+#[cfg(not(tarpaulin_include))]
+pub fn get_template_data(conn: &Connection) -> UserInputType {
+    let mut stdout = stdout();
+    clear_terminal(&mut stdout);
+
+    let templates = get_templates(conn);
+    let tx_methods = get_all_tx_methods(conn);
+
+    loop {
+        let mut template_line = "Input 'Add' to add a new template or a template number to delete it. Input 'Cancel' to cancel the operation.
+
+Currently added Templates: \n"
+            .to_string();
+
+        for (i, template) in templates.iter().enumerate() {
+            template_line.push_str(&format!(
+                "\n{}. {} | {} | {} | {} | {}",
+                i + 1,
+                template.details,
+                template.tx_method,
+                template.amount,
+                template.tx_type,
+                template.tags
+            ));
+            if let Some(day) = template.remind_day {
+                template_line.push_str(&format!(" | Remind on day {day}"));
+            }
+        }
+        println!("{template_line}");
+        print!("\nEnter your choice: ");
+        flush_output(&stdout);
+
+        let user_input = take_input();
+
+        if user_input.to_lowercase().starts_with("cancel") {
+            return UserInputType::CancelledOperation;
+        }
+
+        if user_input.to_lowercase() != "add" {
+            match user_input.parse::<usize>() {
+                Ok(num) if num != 0 && num <= templates.len() => {
+                    return UserInputType::DeleteTemplate(templates[num - 1].id_num)
+                }
+                _ => {
+                    clear_terminal(&mut stdout);
+                    println!("Invalid input. Example input: Add, 1\n");
+                    continue;
+                }
+            }
+        }
+
+        print!("\nDetails: ");
+        flush_output(&stdout);
+        let details = take_input();
+
+        print!("Transaction Method ({}): ", tx_methods.join(", "));
+        flush_output(&stdout);
+        let tx_method = take_input();
+
+        let tx_method = match tx_methods
+            .iter()
+            .find(|method| method.to_lowercase() == tx_method.to_lowercase())
+        {
+            Some(method) => method,
+            None => {
+                clear_terminal(&mut stdout);
+                println!("Transaction Method not found\n");
+                continue;
+            }
+        };
+
+        print!("Amount: ");
+        flush_output(&stdout);
+        let amount = match take_input().parse::<f64>() {
+            Ok(amount) if amount > 0.0 => format!("{amount:.2}"),
+            _ => {
+                clear_terminal(&mut stdout);
+                println!("Amount must be a number bigger than zero. Example input: 100.50\n");
+                continue;
+            }
+        };
+
+        print!("Transaction Type (Income/Expense): ");
+        flush_output(&stdout);
+        let tx_type = match take_input().to_lowercase().chars().next() {
+            Some('i') => "Income",
+            Some('e') => "Expense",
+            _ => {
+                clear_terminal(&mut stdout);
+                println!("Transaction Type must be Income or Expense\n");
+                continue;
+            }
+        };
+
+        print!("Tags: ");
+        flush_output(&stdout);
+        let mut tags = take_input()
+            .split(',')
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty())
+            .collect::<Vec<&str>>()
+            .join(", ");
+
+        if tags.is_empty() {
+            tags = "Unknown".to_string();
+        }
+
+        print!("Remind on day of the month (1-31). Leave empty for no reminder: ");
+        flush_output(&stdout);
+        let remind_day = take_input();
+
+        if !remind_day.is_empty() && !matches!(remind_day.parse::<u32>(), Ok(1..=31)) {
+            clear_terminal(&mut stdout);
+            println!("Invalid day. Example input: 5\n");
+            continue;
+        }
+
+        return UserInputType::AddTemplate(vec![
+            details,
+            tx_method.to_string(),
+            amount,
+            tx_type.to_string(),
+            tags,
+            remind_day,
+        ]);
+    }
+}
+
+/// Asks the user to add a new details template or to select one to delete
+#[cfg(not(tarpaulin_include))]
+pub fn get_details_template_data(conn: &Connection) -> UserInputType {
+    let mut stdout = stdout();
+    clear_terminal(&mut stdout);
+
+    let templates = get_details_templates(conn);
+
+    loop {
+        let mut template_line = "Input 'Add' to add a new details template or a template number to delete it. Input 'Cancel' to cancel the operation.
+
+Details templates are inserted into the details field with Ctrl+T on the Add Transaction page.
+Parts within braces such as {place} are asked for while inserting. Use {{ and }} for a brace.
+
+Currently added Details Templates: \n"
+            .to_string();
+
+        for (i, template) in templates.iter().enumerate() {
+            template_line.push_str(&format!("\n{}. {}", i + 1, template.text));
+        }
+        println!("{template_line}");
+        print!("\nEnter your choice: ");
+        flush_output(&stdout);
+
+        let user_input = take_input();
+
+        if user_input.to_lowercase().starts_with("cancel") {
+            return UserInputType::CancelledOperation;
+        }
+
+        if user_input.to_lowercase() != "add" {
+            match user_input.parse::<usize>() {
+                Ok(num) if num != 0 && num <= templates.len() => {
+                    return UserInputType::DeleteDetailsTemplate(templates[num - 1].id_num)
+                }
+                _ => {
+                    clear_terminal(&mut stdout);
+                    println!("Invalid input. Example input: Add, 1\n");
+                    continue;
+                }
+            }
+        }
+
+        print!("\nTemplate: ");
+        flush_output(&stdout);
+        let text = take_input();
+
+        if text.is_empty() {
+            clear_terminal(&mut stdout);
+            println!("Template cannot be empty. Example input: Lunch at {{place}}\n");
+            continue;
+        }
+
+        return UserInputType::AddDetailsTemplate(text);
+    }
+}
+
+/// Tries to open terminal/cmd and run this app
+#[cfg(not(tarpaulin_include))]
+pub fn start_terminal(original_dir: &str) -> Result<(), TerminalExecutionError> {
+    if cfg!(target_os = "windows") {
+        Command::new("cmd.exe")
+            .arg("start")
+            .arg("rex")
+            .output()
+            .map_err(TerminalExecutionError::ExecutionFailed)?;
+    } else {
+        let mut all_terminals = HashMap::new();
+        let gnome_dir = format!("--working-directory={}", original_dir);
+
+        // TODO add more terminal support
+        all_terminals.insert(
+            "konsole",
+            vec![
+                "--new-tab".to_string(),
+                "--workdir".to_string(),
+                original_dir.to_string(),
+                "-e".to_string(),
+                "./rex".to_string(),
+            ],
+        );
+
+        all_terminals.insert(
+            "gnome-terminal",
+            vec![
+                gnome_dir,
+                "--maximize".to_string(),
+                "--".to_string(),
+                "./rex".to_string(),
+            ],
+        );
+
+        let mut terminal_opened = false;
+        let mut result = None;
+
+        for (key, value) in all_terminals {
+            let status = Command::new(key).args(value).output();
+            match status {
+                Ok(out) => {
+                    if out.stderr.len() > 2 {
+                        result = Some(TerminalExecutionError::NotFound(out))
+                    } else {
+                        terminal_opened = true;
+                        break;
+                    }
+                }
+                Err(err) => result = Some(TerminalExecutionError::ExecutionFailed(err)),
+            }
+        }
+        if !terminal_opened {
+            return Err(result.unwrap());
+        }
+    };
+    Ok(())
+}
+
+/// Returns the id_num searched with `#1234` in the details field
+pub fn get_searched_id(details: &str) -> Option<i32> {
+    details.trim().strip_prefix('#')?.trim().parse::<i32>().ok()
+}
+
+/// Creates the query to search for specific tx, gathers all rows, id numbers and whether each
+/// row comes from the attached archive. Searching `#1234` in the details returns only the
+/// transaction with that id_num
+#[allow(clippy::too_many_arguments)]
+pub fn get_search_data(
+    date: &str,
+    details: &str,
+    from_method: &str,
+    to_method: &str,
+    amount: &str,
+    tx_type: &str,
+    tags: &str,
+    conn: &Connection,
+) -> (Vec<Vec<String>>, Vec<String>, Vec<bool>) {
+    let mut all_txs = Vec::new();
+    let mut all_ids = Vec::new();
+    let mut all_archived = Vec::new();
+
+    // if the archive is attached, search through the archived transactions as well.
+    // The archived rows are tagged as their id_num can belong to a transaction of the main db
+    let mut query = if is_archive_attached(conn) {
+        "SELECT * FROM (SELECT *, 0 AS archived FROM tx_all \
+         UNION ALL SELECT *, 1 FROM archive.tx_all) WHERE 1=1"
+            .to_string()
+    } else {
+        "SELECT *, 0 AS archived FROM tx_all WHERE 1=1".to_string()
+    };
+
+    let searched_id = get_searched_id(details);
+
+    // an id_num ignores every other field
+    if let Some(id_num) = searched_id {
+        query.push_str(&format!(" AND id_num = {id_num}"));
+    } else {
+        if !date.is_empty() {
+            query.push_str(&format!(r#" AND date = "{}""#, date));
+        }
+
+        // every word of the details must be found. The exact word matching is done after the query
+        for token in details.split_whitespace() {
+            query.push_str(&format!(r#" AND details LIKE "%{}%""#, token));
+        }
+
+        if !tx_type.is_empty() {
+            query.push_str(&format!(r#" AND tx_type = "{}""#, tx_type));
+        }
+
+        if !amount.is_empty() {
+            let comparison_type = check_comparison(amount);
+
+            let comparison_symbol = match comparison_type {
+                ComparisonType::BiggerThan => ">",
+                ComparisonType::SmallerThan => "<",
+                ComparisonType::Equal => "",
+                ComparisonType::EqualOrBigger => ">=",
+                ComparisonType::EqualOrSmaller => "<=",
+            };
+            let amount = amount.replace(comparison_symbol, "");
+
+            query.push_str(&format!(r#" AND {} "{}""#, comparison_type, amount));
+        }
+
+        if tx_type == "Transfer" && !from_method.is_empty() && !to_method.is_empty() {
+            query.push_str(&format!(
+                r#" AND tx_method = "{} to {}""#,
+                from_method, to_method
+            ));
+        } else if tx_type != "Transfer" && !from_method.is_empty() {
+            query.push_str(&format!(r#" AND tx_method = "{}""#, from_method));
+        }
+
+        if !tags.is_empty() {
+            let all_tags = tags.split(", ");
+            let tag_conditions = all_tags
+                .map(|tag| format!(r#""," || tags || "," LIKE "%{}%""#, tag))
+                .collect::<Vec<String>>()
+                .join(" OR ");
+            query.push_str(&format!(" AND ({})", tag_conditions));
+        }
+    }
+
+    // the date order keeps every month and year group together so the grouped table
+    // has the transactions in the same order as the search result
+    query.push_str(" ORDER BY date, id_num");
+
+    let mut statement = conn.prepare(&query).unwrap();
+
+    let rows = statement
+        .query_map([], |row| {
+            let date: String = row.get(0).unwrap();
+            let id_num: i32 = row.get(5).unwrap();
+            let archived: bool = row.get("archived").unwrap();
+            let collected_date = date.split('-').collect::<Vec<&str>>();
+            let new_date = format!(
+                "{}-{}-{}",
+                collected_date[2], collected_date[1], collected_date[0]
+            );
+
+            Ok((
+                vec![
+                    new_date,
+                    row.get(1).unwrap(),
+                    row.get(2).unwrap(),
+                    row.get(3).unwrap(),
+                    row.get(4).unwrap(),
+                    row.get(6).unwrap(),
+                ],
+                id_num,
+                archived,
+            ))
+        })
+        .unwrap();
+
+    for (data, id_num, archived) in rows.flatten() {
+        if searched_id.is_none()
+            && !details.trim().is_empty()
+            && match_tokens(details, &data[1]).is_none()
+        {
+            continue;
+        }
+
+        all_ids.push(id_num.to_string());
+        all_txs.push(data);
+        all_archived.push(archived);
+    }
+
+    (all_txs, all_ids, all_archived)
+}
+
+/// Reports the tx methods and tags that only differ by case or spacing and asks whether
+/// to merge each group into its first entry. Used on startup as these can exist in
+/// databases created before names were normalized
+#[cfg(not(tarpaulin_include))]
+pub fn check_name_collisions(conn: &mut Connection) {
+    let stdout = stdout();
+
+    let method_collisions = get_name_collisions(&get_all_tx_methods(conn));
+    let tag_collisions = get_name_collisions(&get_all_tags(conn));
+
+    for (collisions, name_type) in [
+        (method_collisions, "Transaction Methods"),
+        (tag_collisions, "Tags"),
+    ] {
+        for group in collisions {
+            let into_name = &group[0];
+
+            println!(
+                "\n{name_type} {} only differ by case or spacing.",
+                group.join(", ")
+            );
+            print!("Merge them into {into_name}? y/n: ");
+            flush_output(&stdout);
+
+            if !take_input().to_lowercase().starts_with('y') {
+                continue;
+            }
+
+            for from_name in &group[1..] {
+                let status = if name_type == "Tags" {
+                    merge_tags(from_name, into_name, conn)
+                } else {
+                    merge_tx_methods(from_name, into_name, conn)
+                };
+
+                match status {
+                    Ok(_) => println!("Merged {from_name} into {into_name}."),
+                    Err(e) => println!("Error while merging {from_name}. Error: {e:?}."),
+                }
+            }
+        }
+    }
+}
+
+/// Tells that another instance of the app is using the database and asks whether to open it
+/// in read-only mode. Returns false if the app should exit instead
+#[cfg(not(tarpaulin_include))]
+pub fn ask_read_only_mode(pid: Option<u32>) -> bool {
+    let stdout = stdout();
+
+    match pid {
+        Some(pid) => println!("\nAnother Rex instance (PID {pid}) is using the database."),
+        None => {
+            println!("\nThe lock file {LOCK_FILE} does not contain a PID.");
+            println!("Another Rex instance may be using the database. Remove the file if none is running.");
+        }
+    }
+    print!("Open it in read-only mode? Otherwise Rex will exit. y/n: ");
+    flush_output(&stdout);
+
+    take_input().to_lowercase().starts_with('y')
+}
+
+/// Checks the database before anything else uses it. If it is corrupted or not a database of
+/// the app, explains the problem and asks whether to restore the newest backup, start with a new
+/// database or exit. Returns false if the app should exit
+#[cfg(not(tarpaulin_include))]
+pub fn check_db_on_startup(verifying_path: &str, read_only: bool) -> Result<bool, Box<dyn Error>> {
+    let stdout = stdout();
+
+    loop {
+        // opened read-only so checking never changes the file
+        let health =
+            match Connection::open_with_flags(verifying_path, OpenFlags::SQLITE_OPEN_READ_ONLY) {
+                Ok(conn) => check_db_health(&conn),
+                Err(e) => DbHealth::Unreadable(e.to_string()),
+            };
+
+        let newest_backup = find_newest_backup(Path::new("."));
+        let actions = get_recovery_actions(&health, newest_backup.as_deref(), read_only);
+
+        if actions.is_empty() {
+            return Ok(true);
+        }
+
+        println!("\n{}\n", health.get_message());
+        for (index, action) in actions.iter().enumerate() {
+            println!("{}. {}", index + 1, action.get_text());
+        }
+        print!("\nEnter an option: ");
+        flush_output(&stdout);
+
+        let action = match take_input()
+            .parse::<usize>()
+            .ok()
+            .and_then(|num| actions.get(num.checked_sub(1)?))
+        {
+            Some(action) => action,
+            None => {
+                println!("Invalid option. Try again.");
+                continue;
+            }
+        };
+
+        let moved_path = get_moved_aside_path(verifying_path, Local::now().naive_local());
+
+        match action {
+            RecoveryAction::RestoreBackup(backup) => {
+                fs::rename(verifying_path, &moved_path)?;
+                fs::copy(backup, verifying_path)?;
+                println!(
+                    "Moved the file to {moved_path} and restored {}.",
+                    backup.display()
+                );
+            }
+            RecoveryAction::StartFresh => {
+                fs::rename(verifying_path, &moved_path)?;
+                println!("Moved the file to {moved_path}.");
+                check_n_create_db(verifying_path)?;
+            }
+            RecoveryAction::Exit => return Ok(false),
+        }
+    }
 }
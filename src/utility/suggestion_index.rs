@@ -0,0 +1,85 @@
+use crate::utility::get_best_match;
+use std::cell::Cell;
+
+/// The most used values the typo tolerant matching goes through when nothing starts with the
+/// typed text. Keeps a key press fast with thousands of tags
+pub const FUZZY_CANDIDATES: usize = 100;
+
+/// Tags or tx methods ordered by their usage with a lowercase sorted copy for looking up
+/// the typed prefix. Built once after the names are loaded so typing does not go
+/// through every value on each key press
+///
+/// values: `["Rent", "Food"]`
+///
+/// keys: `[("food", 1), ("rent", 0)]`
+pub struct SuggestionIndex {
+    values: Vec<String>,
+    keys: Vec<(String, usize)>,
+    scanned: Cell<usize>,
+}
+
+impl SuggestionIndex {
+    /// Creates the index from the values ordered by their usage, the most used first
+    pub fn new(ranked_values: Vec<String>) -> Self {
+        let mut keys = ranked_values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (value.to_lowercase(), index))
+            .collect::<Vec<(String, usize)>>();
+
+        keys.sort();
+
+        SuggestionIndex {
+            values: ranked_values,
+            keys,
+            scanned: Cell::new(0),
+        }
+    }
+
+    /// Returns the value the typed text autofills to. The first value starting with the text
+    /// is found with a binary search, then every value with that prefix is compared and the
+    /// shortest of them wins, then the more used one. A short prefix shared by many values
+    /// takes longer than a longer one. If nothing starts with it, the closest of the most used
+    /// values is picked
+    pub fn get_suggestion(&self, typed: &str) -> Option<String> {
+        let typed = typed.trim();
+        self.scanned.set(0);
+
+        if typed.is_empty() || self.values.is_empty() {
+            return None;
+        }
+
+        let lowercase = typed.to_lowercase();
+        let start = self
+            .keys
+            .partition_point(|(key, _)| key.as_str() < lowercase.as_str());
+
+        let mut scanned = 0;
+        let prefix_match = self.keys[start..]
+            .iter()
+            .take_while(|(key, _)| key.starts_with(&lowercase))
+            .inspect(|_| scanned += 1)
+            .min_by_key(|(key, index)| (key.chars().count(), *index));
+
+        self.scanned.set(scanned);
+
+        match prefix_match {
+            Some((_, index)) => Some(self.values[*index].to_string()),
+            None => {
+                let candidates = self
+                    .values
+                    .iter()
+                    .take(FUZZY_CANDIDATES)
+                    .cloned()
+                    .collect::<Vec<String>>();
+                self.scanned.set(candidates.len());
+                Some(get_best_match(typed, candidates))
+            }
+        }
+    }
+
+    /// Returns how many values the last suggestion compared the typed text with
+    pub fn get_scan_count(&self) -> usize {
+        self.scanned.get()
+    }
+}
@@ -1,53 +1,37 @@
-use crate::utility::{get_all_details, get_all_tags, get_all_tx_methods, get_best_match};
+use crate::page_handler::NameCache;
+use crate::utility::{get_all_details, get_best_match, get_best_token_match};
 use rusqlite::Connection;
 
+/// Tags and tx methods are ranked by their usage when their autofill index is built so that
+/// the more often and more recently used value wins when the match is equally close.
+/// A value starting with the typed text is preferred over the typo tolerant matching.
+/// Hidden tx methods are never suggested.
+/// Details are first matched word by word so "rewe koeln" finds "REWE Markt Koeln 1234"
 pub trait AutoFiller {
-    fn autofill_tx_method(&self, user_input: &str, conn: &Connection) -> String {
-        let all_tx_methods = get_all_tx_methods(conn);
-        if !user_input.trim().is_empty() && !all_tx_methods.is_empty() {
-            let best_match = get_best_match(user_input, all_tx_methods);
-
-            if best_match == user_input.trim() {
-                String::new()
-            } else {
-                best_match
-            }
-        } else {
-            String::new()
+    fn autofill_tx_method(&self, user_input: &str, names: &NameCache, conn: &Connection) -> String {
+        match names.get_method_suggestion(user_input, conn) {
+            Some(best_match) if best_match != user_input.trim() => best_match,
+            _ => String::new(),
         }
     }
 
-    fn autofill_tags(&self, user_input: &str, conn: &Connection) -> String {
-        let all_tags = get_all_tags(conn);
-
-        if !user_input.trim().is_empty() && !all_tags.is_empty() {
-            let splitted = user_input
-                .split(',')
-                .map(|s| s.trim())
-                .collect::<Vec<&str>>();
-
-            let last_value = splitted.last().unwrap().trim();
+    fn autofill_tags(&self, user_input: &str, names: &NameCache, conn: &Connection) -> String {
+        let last_value = user_input.split(',').last().unwrap_or_default().trim();
 
-            if last_value.is_empty() {
-                return String::new();
-            }
-
-            let best_match = get_best_match(last_value, all_tags);
-
-            if best_match == last_value {
-                String::new()
-            } else {
-                best_match
-            }
-        } else {
-            String::new()
+        match names.get_tag_suggestion(last_value, conn) {
+            Some(best_match) if best_match != last_value => best_match,
+            _ => String::new(),
         }
     }
 
     fn autofill_details(&self, user_input: &str, conn: &Connection) -> String {
         let all_details = get_all_details(conn);
         if !user_input.trim().is_empty() && !all_details.is_empty() {
-            let best_match = get_best_match(user_input, all_details);
+            // details are matched by their words first, falling back to the closest whole value
+            let best_match = match get_best_token_match(user_input, &all_details) {
+                Some(token_match) => token_match,
+                None => get_best_match(user_input, all_details),
+            };
 
             if best_match == user_input.trim() {
                 String::new()
@@ -1,80 +1,162 @@
-use crate::outputs::{NAType, StepType, SteppingError, VerifyingOutput};
+use crate::db::DateRange;
+use crate::outputs::{
+    AmountStepSize, DateStepSize, NAType, StepType, SteppingError, VerifyingOutput,
+};
+use crate::page_handler::NameCache;
 use crate::utility::traits::DataVerifier;
-use crate::utility::{get_all_tags, get_all_tx_methods};
-use chrono::{Duration, NaiveDate};
+use crate::utility::{AmountFormat, Clock, DateFormat, Money, SystemClock};
+use chrono::{Duration, Months, NaiveDate};
 use rusqlite::Connection;
 
+/// The highest amount in thousandths the amount can be stepped to
+const MAX_AMOUNT: i64 = 9_999_999_999_990;
+
+/// The characters that make an amount an expression instead of a single number
+const EXPRESSION_SYMBOLS: [char; 7] = ['+', '-', '*', '/', '(', ')', '%'];
+
 pub trait FieldStepper: DataVerifier {
-    fn step_date(&self, user_date: &mut String, step_type: StepType) -> Result<(), SteppingError> {
-        let verify_status = self.verify_date(user_date);
+    fn step_date(
+        &self,
+        user_date: &mut String,
+        step_type: StepType,
+        date_range: &DateRange,
+        date_format: &DateFormat,
+    ) -> Result<(), SteppingError> {
+        self.step_date_by(
+            user_date,
+            step_type,
+            DateStepSize::Day,
+            date_range,
+            date_format,
+        )
+    }
+
+    /// Steps the date by a day, week, month or year. Stepping by months or years keeps the day
+    /// unless the target month is shorter, then the last day of that month is taken.
+    /// The date never goes past the first or the last date of the range
+    fn step_date_by(
+        &self,
+        user_date: &mut String,
+        step_type: StepType,
+        step_size: DateStepSize,
+        date_range: &DateRange,
+        date_format: &DateFormat,
+    ) -> Result<(), SteppingError> {
+        let verify_status = self.verify_date(user_date, date_range, date_format);
+
+        let first_date = NaiveDate::from_ymd_opt(date_range.start_year, 1, 1).unwrap();
+        let final_date = NaiveDate::from_ymd_opt(date_range.end_year, 12, 31).unwrap();
 
         match verify_status {
             VerifyingOutput::Accepted(_) => {
-                let mut current_date = NaiveDate::parse_from_str(user_date, "%Y-%m-%d").unwrap();
-                match step_type {
+                let current_date =
+                    NaiveDate::parse_from_str(&date_format.to_iso(user_date), "%Y-%m-%d").unwrap();
+
+                let stepped_date = match step_type {
                     StepType::StepUp => {
-                        let final_date =
-                            NaiveDate::parse_from_str("2037-12-31", "%Y-%m-%d").unwrap();
-                        if current_date != final_date {
-                            current_date += Duration::days(1);
-                        }
+                        step_date_forward(current_date, step_size).unwrap_or(final_date)
                     }
                     StepType::StepDown => {
-                        let final_date =
-                            NaiveDate::parse_from_str("2022-01-01", "%Y-%m-%d").unwrap();
-                        if current_date != final_date {
-                            current_date -= Duration::days(1);
-                        }
+                        step_date_backward(current_date, step_size).unwrap_or(first_date)
                     }
-                }
-                *user_date = current_date.to_string();
+                };
+
+                *user_date = date_format.format_date(stepped_date.clamp(first_date, final_date));
             }
             VerifyingOutput::NotAccepted(_) => {
                 return Err(SteppingError::InvalidDate);
             }
             // Nothing -> Empty box.
-            // If nothing and pressed Up, make it the first possible date
+            // If nothing and pressed Up, make it the first possible date. Down makes it the last one
             VerifyingOutput::Nothing(_) => {
-                *user_date = String::from("2022-01-01");
+                let edge_date = match step_type {
+                    StepType::StepUp => first_date,
+                    StepType::StepDown => final_date,
+                };
+                *user_date = date_format.format_date(edge_date);
             }
         }
 
         Ok(())
     }
 
+    /// Sets the date to today. Whatever the field had is replaced, even if it was not a valid date.
+    /// Returns false if today is outside the date range and the closest date of the range was set
+    fn set_today(
+        &self,
+        user_date: &mut String,
+        date_range: &DateRange,
+        date_format: &DateFormat,
+    ) -> bool {
+        self.set_today_with_clock(user_date, date_range, date_format, &SystemClock)
+    }
+
+    /// Same as `set_today` but today is the clock's date
+    fn set_today_with_clock(
+        &self,
+        user_date: &mut String,
+        date_range: &DateRange,
+        date_format: &DateFormat,
+        clock: &impl Clock,
+    ) -> bool {
+        let first_date = NaiveDate::from_ymd_opt(date_range.start_year, 1, 1).unwrap();
+        let final_date = NaiveDate::from_ymd_opt(date_range.end_year, 12, 31).unwrap();
+
+        let today = clock.today();
+        let date = today.clamp(first_date, final_date);
+
+        *user_date = date_format.format_date(date);
+        date == today
+    }
+
+    /// Hidden tx methods are skipped. Stepping from a hidden method that was typed
+    /// in full moves to the next method that is not hidden
     fn step_tx_method(
         &self,
         user_method: &mut String,
         step_type: StepType,
+        names: &NameCache,
         conn: &Connection,
     ) -> Result<(), SteppingError> {
-        let all_methods = get_all_tx_methods(conn);
-        let verify_status: VerifyingOutput = self.verify_tx_method(user_method, conn);
+        let all_methods = names.get_tx_methods(conn);
+        let active_methods = names.get_active_tx_methods(conn);
+        let verify_status: VerifyingOutput = self.verify_tx_method(user_method, names, conn);
 
         match verify_status {
             VerifyingOutput::Accepted(_) => {
                 let current_method_index =
                     all_methods.iter().position(|e| e == user_method).unwrap();
+                let total_methods = all_methods.len();
 
-                let next_method_index = match step_type {
-                    StepType::StepUp => (current_method_index + 1) % all_methods.len(),
-                    StepType::StepDown => {
-                        if current_method_index == 0 {
-                            all_methods.len() - 1
-                        } else {
-                            (current_method_index - 1) % all_methods.len()
+                let next_method = (1..=total_methods)
+                    .map(|offset| match step_type {
+                        StepType::StepUp => (current_method_index + offset) % total_methods,
+                        StepType::StepDown => {
+                            (current_method_index + total_methods - offset) % total_methods
                         }
-                    }
-                };
-                *user_method = String::from(&all_methods[next_method_index]);
+                    })
+                    .map(|index| &all_methods[index])
+                    .find(|method| active_methods.contains(method));
+
+                if let Some(method) = next_method {
+                    *user_method = method.to_string();
+                }
             }
             VerifyingOutput::NotAccepted(_) => {
                 return Err(SteppingError::InvalidTxMethod);
             }
             // Nothing -> Empty box.
-            // If nothing and pressed Up, make it the first possible method
+            // If nothing and pressed Up, make it the first possible method. Down makes it the last one
             VerifyingOutput::Nothing(_) => {
-                *user_method = String::from(&all_methods[0]);
+                let method = match step_type {
+                    StepType::StepUp => active_methods.first(),
+                    StepType::StepDown => active_methods.last(),
+                };
+
+                match method {
+                    Some(method) => *user_method = method.to_string(),
+                    None => return Err(SteppingError::InvalidTxMethod),
+                }
             }
         }
 
@@ -85,40 +167,74 @@ pub trait FieldStepper: DataVerifier {
         &self,
         user_amount: &mut String,
         step_type: StepType,
+        step_size: AmountStepSize,
     ) -> Result<(), SteppingError> {
-        let verify_status: VerifyingOutput = self.verify_amount(user_amount);
+        let step = Money::from_whole(1) * step_size.multiplier();
+        self.step_amount_units(user_amount, step_type, step, 2, &AmountFormat::default())
+    }
+
+    /// Same as `step_amount` but the amount is stepped by the given step, the final value keeps
+    /// `minor_units` numbers after the dot and the amount is typed in the given format.
+    /// The amount stops at 0 and 9999999999.99 if the step would go past them.
+    /// An expression such as `1200+350` is kept as it is and only its trailing number is stepped
+    fn step_amount_units(
+        &self,
+        user_amount: &mut String,
+        step_type: StepType,
+        step: Money,
+        minor_units: usize,
+        amount_format: &AmountFormat,
+    ) -> Result<(), SteppingError> {
+        if is_amount_expression(user_amount) {
+            return step_amount_expression(
+                self,
+                user_amount,
+                step_type,
+                step,
+                minor_units,
+                amount_format,
+            );
+        }
+
+        let verify_status: VerifyingOutput =
+            self.verify_amount_units(user_amount, minor_units, amount_format);
 
         match verify_status {
             VerifyingOutput::Accepted(_) => {
-                let mut current_amount: f64 = user_amount.parse().unwrap();
+                let mut current_amount = match Money::parse(user_amount) {
+                    Some(v) => v,
+                    None => return Err(SteppingError::InvalidAmount),
+                };
+
+                let max_amount = Money::from_minor(MAX_AMOUNT);
 
                 match step_type {
-                    StepType::StepUp => {
-                        if 9999999999.99 >= current_amount + 1.0 {
-                            current_amount += 1.0;
-                        }
-                    }
-                    StepType::StepDown => {
-                        if (current_amount - 1.0) >= 0.00 {
-                            current_amount -= 1.0;
-                        }
-                    }
+                    StepType::StepUp => current_amount = (current_amount + step).min(max_amount),
+                    StepType::StepDown => current_amount = (current_amount - step).max(Money::ZERO),
                 }
 
-                *user_amount = format!("{current_amount:.2}");
+                *user_amount = current_amount
+                    .round(minor_units, amount_format.get_rounding())
+                    .format(minor_units);
             }
             VerifyingOutput::NotAccepted(err_type) => match err_type {
-                // if value went below 0, make it 1
+                // if value went below 0, make it the step
                 NAType::AmountBelowZero => {
                     if let StepType::StepUp = step_type {
-                        *user_amount = String::from("1.00")
+                        *user_amount = step
+                            .round(minor_units, amount_format.get_rounding())
+                            .format(minor_units)
                     }
                 }
                 _ => {
                     return Err(SteppingError::InvalidAmount);
                 }
             },
-            VerifyingOutput::Nothing(_) => *user_amount = "1.00".to_string(),
+            VerifyingOutput::Nothing(_) => {
+                *user_amount = step
+                    .round(minor_units, amount_format.get_rounding())
+                    .format(minor_units)
+            }
         }
         Ok(())
     }
@@ -129,10 +245,14 @@ pub trait FieldStepper: DataVerifier {
         step_type: StepType,
     ) -> Result<(), SteppingError> {
         let verify_status: VerifyingOutput = self.verify_tx_type(user_type);
-        let tx_types = ["Income", "Expense", "Transfer"];
+        let tx_types = ["Income", "Expense", "Transfer", "Refund"];
 
+        // empty field selects the first type on Up and the last type on Down
         if user_type.is_empty() {
-            *user_type = "Income".to_string();
+            *user_type = match step_type {
+                StepType::StepUp => tx_types[0].to_string(),
+                StepType::StepDown => tx_types[tx_types.len() - 1].to_string(),
+            };
             return Ok(());
         }
 
@@ -141,6 +261,7 @@ pub trait FieldStepper: DataVerifier {
             'i' => 0,
             'e' => 1,
             't' => 2,
+            'r' => 3,
             _ => 0,
         };
 
@@ -165,15 +286,38 @@ pub trait FieldStepper: DataVerifier {
         user_tag: &mut String,
         autofill: &str,
         step_type: StepType,
+        names: &NameCache,
+        conn: &Connection,
+    ) -> Result<(), SteppingError> {
+        let prefix = get_last_tag(user_tag);
+        self.step_tags_matching(user_tag, &prefix, autofill, step_type, names, conn)
+    }
+
+    /// Same as `step_tags` but only cycles through the tags that start with the prefix, or
+    /// contain it if none start with it. All tags are used if nothing matches or the prefix is
+    /// already a complete tag. The prefix is the last tag the user typed before stepping
+    fn step_tags_matching(
+        &self,
+        user_tag: &mut String,
+        prefix: &str,
+        autofill: &str,
+        step_type: StepType,
+        names: &NameCache,
         conn: &Connection,
     ) -> Result<(), SteppingError> {
-        let all_tags = get_all_tags(conn);
+        let (all_tags, filtered) = filter_tags_by_prefix(names.get_tags(conn), prefix);
+
+        // the tag selected when stepping from an empty tag. First tag on Up, last tag on Down
+        let edge_tag = match step_type {
+            StepType::StepUp => all_tags.first(),
+            StepType::StepDown => all_tags.last(),
+        };
 
         // if current tag is empty
-        // select the first possible tag if available
+        // select the first or the last possible tag if available
         if user_tag.is_empty() {
-            if !all_tags.is_empty() {
-                *user_tag = String::from(&all_tags[0]);
+            if let Some(tag) = edge_tag {
+                *user_tag = tag.to_string();
                 return Ok(());
             } else {
                 return Err(SteppingError::InvalidTags);
@@ -197,10 +341,11 @@ pub trait FieldStepper: DataVerifier {
         {
             // tag3, tag2,
             // if kept like this with extra comma, the last_tag would be empty. In this case
-            // select the first tag available in the list or just join the first two tag with , + space
-            if last_tag.is_empty() {
-                if !all_tags.is_empty() {
-                    current_tags.push(all_tags[0].to_owned());
+            // select the first or the last tag available in the list or just join the first two tag with , + space.
+            // A partially typed tag is the same when some tags start with it
+            if last_tag.is_empty() || filtered {
+                if let Some(tag) = edge_tag {
+                    current_tags.push(tag.to_owned());
                     *user_tag = current_tags.join(", ");
                 } else {
                     *user_tag = current_tags.join(", ");
@@ -236,3 +381,183 @@ pub trait FieldStepper: DataVerifier {
         Ok(())
     }
 }
+
+/// Returns the last comma separated tag of the text
+fn get_last_tag(user_tag: &str) -> String {
+    user_tag
+        .split(',')
+        .last()
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// Keeps the tags that start with the prefix or if there are none, the ones that contain it.
+/// Returns all the tags if nothing matches or the prefix is a complete tag. The bool
+/// is whether the tags were filtered
+fn filter_tags_by_prefix(all_tags: Vec<String>, prefix: &str) -> (Vec<String>, bool) {
+    let prefix = prefix.trim().to_lowercase();
+
+    if prefix.is_empty() || all_tags.iter().any(|tag| tag.to_lowercase() == prefix) {
+        return (all_tags, false);
+    }
+
+    let starting = all_tags
+        .iter()
+        .filter(|tag| tag.to_lowercase().starts_with(&prefix))
+        .cloned()
+        .collect::<Vec<String>>();
+
+    if !starting.is_empty() {
+        return (starting, true);
+    }
+
+    let containing = all_tags
+        .iter()
+        .filter(|tag| tag.to_lowercase().contains(&prefix))
+        .cloned()
+        .collect::<Vec<String>>();
+
+    if containing.is_empty() {
+        (all_tags, false)
+    } else {
+        (containing, true)
+    }
+}
+
+/// Moves the date forward by the step size. None if the date goes out of the supported dates
+fn step_date_forward(date: NaiveDate, step_size: DateStepSize) -> Option<NaiveDate> {
+    match step_size {
+        DateStepSize::Day => date.checked_add_signed(Duration::days(1)),
+        DateStepSize::Week => date.checked_add_signed(Duration::weeks(1)),
+        DateStepSize::Month => date.checked_add_months(Months::new(1)),
+        DateStepSize::Year => date.checked_add_months(Months::new(12)),
+    }
+}
+
+/// Moves the date backward by the step size. None if the date goes out of the supported dates
+fn step_date_backward(date: NaiveDate, step_size: DateStepSize) -> Option<NaiveDate> {
+    match step_size {
+        DateStepSize::Day => date.checked_sub_signed(Duration::days(1)),
+        DateStepSize::Week => date.checked_sub_signed(Duration::weeks(1)),
+        DateStepSize::Month => date.checked_sub_months(Months::new(1)),
+        DateStepSize::Year => date.checked_sub_months(Months::new(12)),
+    }
+}
+
+/// Checks whether the amount is an expression. A sign at the start alone does not make one
+fn is_amount_expression(user_amount: &str) -> bool {
+    let amount = user_amount.trim();
+    let amount = amount.strip_prefix(['-', '+']).unwrap_or(amount);
+
+    amount.contains(EXPRESSION_SYMBOLS)
+}
+
+/// Returns the value of the amount expression without changing it
+fn get_expression_value<V: DataVerifier + ?Sized>(
+    verifier: &V,
+    expression: &str,
+    minor_units: usize,
+    amount_format: &AmountFormat,
+) -> Option<Money> {
+    let mut amount = expression.to_string();
+
+    match verifier.verify_amount_units(&mut amount, minor_units, amount_format) {
+        VerifyingOutput::Accepted(_) => Money::parse(&amount),
+        VerifyingOutput::NotAccepted(NAType::AmountBelowZero) => Some(Money::ZERO),
+        _ => None,
+    }
+}
+
+/// Splits the expression into the part before the trailing number and the signed trailing number.
+/// None if the expression does not end with a number that is added or subtracted or the part
+/// before it is a single number, so the typed breakdown such as `1200+350` is never merged
+///
+/// example: `1200+350-5` => `("1200+350", -5)`
+fn split_trailing_number(
+    expression: &str,
+    amount_format: &AmountFormat,
+) -> Option<(String, Money)> {
+    let expression = expression.trim_end();
+    let operator_index = expression.rfind(EXPRESSION_SYMBOLS)?;
+
+    let (head, tail) = expression.split_at(operator_index);
+    let operator = tail.chars().next()?;
+    let number = tail[1..].trim();
+
+    let head = head.trim_end();
+
+    // the number must be on its own, not multiplied, divided or the second number of the expression
+    if !['+', '-'].contains(&operator)
+        || number.is_empty()
+        || !is_amount_expression(head)
+        || (head.ends_with(EXPRESSION_SYMBOLS) && !head.ends_with([')', '%']))
+    {
+        return None;
+    }
+
+    let number = Money::parse(&amount_format.normalize(number)?)?;
+
+    if operator == '-' {
+        Some((head.to_string(), -number))
+    } else {
+        Some((head.to_string(), number))
+    }
+}
+
+/// Steps an amount expression by changing its trailing number or adding one when
+/// it does not end with one. The expression is left unchanged if stepping down would take
+/// the value to 0 or below or stepping up would go above the highest amount
+///
+/// example: `1200+350` => `1200+350+1`, `1200+350+1` => `1200+350+2`
+fn step_amount_expression<V: DataVerifier + ?Sized>(
+    verifier: &V,
+    user_amount: &mut String,
+    step_type: StepType,
+    step: Money,
+    minor_units: usize,
+    amount_format: &AmountFormat,
+) -> Result<(), SteppingError> {
+    if get_expression_value(verifier, user_amount, minor_units, amount_format).is_none() {
+        return Err(SteppingError::InvalidAmount);
+    }
+
+    let signed_step = match step_type {
+        StepType::StepUp => step,
+        StepType::StepDown => -step,
+    };
+
+    let (head, trailing) = match split_trailing_number(user_amount, amount_format) {
+        Some((head, number)) => (head, number + signed_step),
+        None => (user_amount.trim_end().to_string(), signed_step),
+    };
+
+    // a trailing number that becomes 0 is removed instead of leaving +0 or -0
+    let new_expression = if trailing == Money::ZERO {
+        head
+    } else {
+        let sign = if trailing < Money::ZERO { '-' } else { '+' };
+        let number = trailing
+            .abs()
+            .round(minor_units, amount_format.get_rounding())
+            .format(minor_units);
+        let number = if number.contains('.') {
+            number.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            &number
+        };
+
+        format!("{head}{sign}{}", amount_format.localize_decimal(number))
+    };
+
+    let new_value = get_expression_value(verifier, &new_expression, minor_units, amount_format);
+
+    match new_value {
+        Some(value) if value > Money::from_minor(MAX_AMOUNT) => {}
+        Some(value) if value <= Money::ZERO && matches!(step_type, StepType::StepDown) => {}
+        Some(_) => *user_amount = new_expression,
+        None => return Err(SteppingError::InvalidAmount),
+    }
+
+    Ok(())
+}
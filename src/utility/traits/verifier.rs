@@ -1,5 +1,11 @@
+use crate::db::{get_default_setting, DateRange, TagLimits};
 use crate::outputs::{AType, NAType, VerifyingOutput};
-use crate::utility::{get_all_tags, get_all_tx_methods, get_best_match};
+use crate::page_handler::NameCache;
+use crate::utility::{
+    clean_details, evaluate_amount_expression, expand_amount_multipliers, expand_relative_date,
+    filter_amount_expression, get_close_matches, get_colliding_name, is_amount_expression,
+    normalize_name, AmountFormat, Clock, DateFormat, Money, SystemClock, CALCULATION_DIGITS,
+};
 use chrono::naive::NaiveDate;
 use rusqlite::Connection;
 use std::cmp::Ordering;
@@ -9,7 +15,7 @@ pub trait DataVerifier {
     /// Checks if:
     ///
     /// - the date length is 10 characters
-    /// - the inputted year is between 2022 to 2037
+    /// - the inputted year is inside the date range
     /// - the inputted month is between 01 to 12
     /// - the inputted date is between 01 to 31
     /// - the inputted date is empty
@@ -21,7 +27,40 @@ pub trait DataVerifier {
     /// adding 0 if the beginning if the length is smaller than necessary
     /// or restores to the smallest or the largest date if date is beyond the
     /// accepted value.
-    fn verify_date(&self, user_date: &mut String) -> VerifyingOutput {
+    ///
+    /// The date is typed and returned in the given format. Slashes and dots are accepted as separators.
+    /// Relative dates such as today, yesterday and +N/-N days are expanded to the actual date first
+    fn verify_date(
+        &self,
+        user_date: &mut String,
+        date_range: &DateRange,
+        date_format: &DateFormat,
+    ) -> VerifyingOutput {
+        self.verify_date_with_clock(user_date, date_range, date_format, &SystemClock)
+    }
+
+    /// Same as `verify_date` but relative dates are expanded from the clock's date
+    fn verify_date_with_clock(
+        &self,
+        user_date: &mut String,
+        date_range: &DateRange,
+        date_format: &DateFormat,
+        clock: &impl Clock,
+    ) -> VerifyingOutput {
+        if let Some(expanded_date) = expand_relative_date(user_date, clock) {
+            *user_date = expanded_date;
+        }
+
+        let mut iso_date = date_format.to_iso(user_date);
+        let status = self.verify_iso_date(&mut iso_date, date_range);
+        *user_date = date_format.from_iso(&iso_date);
+        status
+    }
+
+    /// Same as `verify_date` but the date is typed and returned in YYYY-MM-DD format
+    fn verify_iso_date(&self, user_date: &mut String, date_range: &DateRange) -> VerifyingOutput {
+        let (start_year, end_year) = (date_range.start_year, date_range.end_year);
+
         // cancel other verification if there is no text
         if user_date.is_empty() {
             return VerifyingOutput::Nothing(AType::Date);
@@ -39,11 +78,11 @@ pub trait DataVerifier {
 
         // if one part of the date is missing, return unknown date
         if splitted_date.len() != 3 {
-            *user_date = "2022-01-01".to_string();
+            *user_date = format!("{start_year}-01-01");
             return VerifyingOutput::NotAccepted(NAType::InvalidDate);
         }
 
-        let int_year: u32 = match splitted_date[0].parse() {
+        let int_year: i32 = match splitted_date[0].parse() {
             Ok(v) => v,
             Err(_) => return VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Date)),
         };
@@ -58,12 +97,12 @@ pub trait DataVerifier {
             Err(_) => return VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Date)),
         };
 
-        // checks if the year part length is 4. If not 4, turn the year to 2022 + the other character entered by the user
+        // checks if the year part length is 4. If not 4, turn the year to the first year of the range + the other character entered by the user
         // and return the new date
         if splitted_date[0].len() != 4 {
             match splitted_date[0].len().cmp(&4) {
                 Ordering::Less => {
-                    *user_date = format!("2022-{}-{}", splitted_date[1], splitted_date[2]);
+                    *user_date = format!("{start_year}-{}-{}", splitted_date[1], splitted_date[2]);
                 }
                 Ordering::Greater => {
                     *user_date = format!(
@@ -99,15 +138,15 @@ pub trait DataVerifier {
 
             return VerifyingOutput::NotAccepted(NAType::InvalidDay);
 
-        // checks if the year value is between 2022 and 2037
-        } else if !(2022..=2037).contains(&int_year) {
-            if int_year < 2022 {
-                *user_date = format!("2022-{}-{}", splitted_date[1], splitted_date[2]);
-            } else if int_year > 2037 {
-                *user_date = format!("2037-{}-{}", splitted_date[1], splitted_date[2]);
+        // checks if the year value is inside the date range
+        } else if !date_range.contains(int_year) {
+            if int_year < start_year {
+                *user_date = format!("{start_year}-{}-{}", splitted_date[1], splitted_date[2]);
+            } else if int_year > end_year {
+                *user_date = format!("{end_year}-{}-{}", splitted_date[1], splitted_date[2]);
             }
 
-            return VerifyingOutput::NotAccepted(NAType::YearTooBig);
+            return VerifyingOutput::NotAccepted(NAType::YearTooBig(start_year, end_year));
 
         // checks if the month value is between 1 and 12
         } else if !(1..=12).contains(&int_month) {
@@ -130,16 +169,56 @@ pub trait DataVerifier {
             return VerifyingOutput::NotAccepted(NAType::DayTooBig);
         }
 
-        // We will check if the date actually exists otherwise
+        // We will check if the date actually exists otherwise the day is moved
+        // to the last day of the month. Eg: 2023-02-30 becomes 2023-02-28
         let naive_date = NaiveDate::parse_from_str(user_date, "%Y-%m-%d");
-        match naive_date {
-            Ok(_) => {}
-            Err(_) => return VerifyingOutput::NotAccepted(NAType::NonExistingDate),
+        if naive_date.is_err() {
+            let last_day = (1..int_day)
+                .rev()
+                .find_map(|day| NaiveDate::from_ymd_opt(int_year, int_month, day));
+
+            if let Some(last_day) = last_day {
+                *user_date = last_day.format("%Y-%m-%d").to_string();
+            }
+            return VerifyingOutput::NotAccepted(NAType::NonExistingDate);
         }
 
         VerifyingOutput::Accepted(AType::Date)
     }
 
+    /// Checks if:
+    ///
+    /// - Details is empty
+    /// - contains newlines, tabs or other control characters
+    /// - is longer than the default max length
+    ///
+    /// Newlines and tabs become a single space and longer text gets cut to the max length
+    fn verify_details(&self, user_details: &mut String) -> VerifyingOutput {
+        let max_length = get_default_setting("details_max_length").parse().unwrap();
+        self.verify_details_length(user_details, max_length)
+    }
+
+    /// Same as `verify_details` but the text can be at most `max_length` characters
+    fn verify_details_length(
+        &self,
+        user_details: &mut String,
+        max_length: usize,
+    ) -> VerifyingOutput {
+        *user_details = clean_details(user_details);
+
+        if user_details.is_empty() {
+            return VerifyingOutput::Nothing(AType::Details);
+        }
+
+        if user_details.chars().count() > max_length {
+            *user_details = user_details.chars().take(max_length).collect::<String>();
+            *user_details = user_details.trim_end().to_string();
+            return VerifyingOutput::NotAccepted(NAType::DetailsTooLong(max_length));
+        }
+
+        VerifyingOutput::Accepted(AType::Details)
+    }
+
     /// Checks if:
     ///
     /// - Amount is empty
@@ -147,21 +226,41 @@ pub trait DataVerifier {
     /// - Amount text contains a calculation symbol
     /// - contains any extra spaces
     /// - removes any extra spaces and non-numeric characters
+    /// - removes currency symbols and group separators in valid positions
     ///
-    /// if the value is not float, tries to make it float ending with double zero
+    /// if the value is not float, tries to make it float ending with double zero.
+    /// Extra decimals are dropped with the rounding mode of the amount format
 
     fn verify_amount(&self, user_amount: &mut String) -> VerifyingOutput {
+        self.verify_amount_units(user_amount, 2, &AmountFormat::default())
+    }
+
+    /// Same as `verify_amount` but the final value keeps `minor_units` numbers after the dot
+    /// and the amount is typed in the given format. With 0 minor units the value has no dot at all
+    fn verify_amount_units(
+        &self,
+        user_amount: &mut String,
+        minor_units: usize,
+        amount_format: &AmountFormat,
+    ) -> VerifyingOutput {
         // cancel all verification if the amount is empty
         if user_amount.is_empty() {
             return VerifyingOutput::Nothing(AType::Amount);
         }
 
-        let calc_symbols = vec!['*', '/', '+', '-'];
+        // example: $1,250.50 => 1250.50. A misplaced group separator such as 1,25 is not guessed
+        match amount_format.normalize(user_amount) {
+            Some(normalized) => *user_amount = normalized,
+            None => return VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        }
 
-        *user_amount = user_amount
-            .chars()
-            .filter(|c| c.is_numeric() || *c == '.' || calc_symbols.contains(c))
-            .collect();
+        // example: 1.5k => 1500. Done before the whole part gets limited to 10 characters
+        match expand_amount_multipliers(user_amount) {
+            Some(expanded) => *user_amount = expanded,
+            None => return VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        }
+
+        *user_amount = filter_amount_expression(user_amount);
 
         // Already checked if the initial amount is empty.
         // if it becomes empty after the filtering was done, there no number inside so return error
@@ -169,149 +268,38 @@ pub trait DataVerifier {
             return VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount));
         }
 
-        // check if any of the symbols are present
-        if calc_symbols.iter().any(|s| user_amount.contains(*s)) {
-            // how it works:
-            // the calc_symbol intentionally starts with * and / so these calculations are done first
-            // start a main loop which will only run for the amount of times any one of them from calc_symbols is present
-            // loop over the symbols and check if the symbol is present in the string
-            // find the index of where the symbol is then take the number values from both side of the symbol
-            // example: 1+5*10. We start with *, we initially, we will work with 5*10
-            // isolate the numbers => do the calculation => replace the part of the string we are working with, with the result which is 50
-            // result: 1+50 => break the symbol checking loop and continue the main loop again so we start working with 1+50.
-
-            // get the amount of time the symbols were found in the amount string
-            let count = user_amount
-                .chars()
-                .filter(|c| calc_symbols.contains(c))
-                .count();
-
-            // remove all spaces for easier indexing
-            let mut working_value = user_amount.to_owned();
-
-            for _i in 0..count {
-                for symbol in &calc_symbols {
-                    if let Some(location) = working_value.find(*symbol) {
-                        // if a symbol is found, we want to store the values to its side to these variables.
-                        // example: 1+5 first_value = 1 last_value = 5
-                        let mut first_value = String::new();
-                        let mut last_value = String::new();
-
-                        // skip to symbol location + 1 index value and start taking chars from here until the end
-                        // of the string or until another cal symbol is encountered
-                        for char in working_value.chars().skip(location + 1) {
-                            if !calc_symbols.contains(&char) {
-                                last_value.push(char)
-                            } else {
-                                break;
-                            }
-                        }
-
-                        // do the same thing as before but this time, reverse the string
-                        for char in working_value
-                            .chars()
-                            .rev()
-                            .skip(working_value.len() - location)
-                        {
-                            if !calc_symbols.contains(&char) {
-                                first_value.push(char)
-                            } else {
-                                break;
-                            }
-                        }
-                        // un-reverse the string
-                        first_value = first_value.chars().rev().collect();
-
-                        // if either of them is empty, the one that is not empty is the value we want to use for using in replacement
-                        let final_value = if first_value.is_empty() || last_value.is_empty() {
-                            if first_value.is_empty() {
-                                last_value.to_string()
-                            } else {
-                                first_value.to_string()
-                            }
-                        } else {
-                            // if both value is intact, do the calculation and the result is for replacement
-                            let first_num: f64 = match first_value.parse() {
-                                Ok(v) => v,
-                                Err(_) => {
-                                    return VerifyingOutput::NotAccepted(NAType::ParsingError(
-                                        AType::Amount,
-                                    ))
-                                }
-                            };
-
-                            let last_num: f64 = match last_value.parse() {
-                                Ok(v) => v,
-                                Err(_) => {
-                                    return VerifyingOutput::NotAccepted(NAType::ParsingError(
-                                        AType::Amount,
-                                    ))
-                                }
-                            };
-
-                            match *symbol {
-                                '*' => format!("{:.2}", (first_num * last_num)),
-                                '/' => format!("{:.2}", (first_num / last_num)),
-                                '+' => format!("{:.2}", (first_num + last_num)),
-                                '-' => format!("{:.2}", (first_num - last_num)),
-                                _ => String::new(),
-                            }
-                        };
-
-                        // example: 1+5*10
-                        // if everything goes alright, first_value is 5, last_value is 10 and the symbol is *
-                        // replace 5*10 with the earlier result we got which is 50. Continue with 1+50 in the next loop
-                        working_value = working_value
-                            .replace(&format!("{first_value}{symbol}{last_value}"), &final_value);
-
-                        break;
-                    }
-                }
+        // example: 10-2*3+5. 2*3 is calculated first, then 10-6+5 from left to right.
+        // The result keeps extra decimals so it is rounded the same way as a typed number
+        if is_amount_expression(user_amount) {
+            match evaluate_amount_expression(user_amount) {
+                Ok(v) => *user_amount = format!("{:.*}", CALCULATION_DIGITS, v),
+                Err(e) => return VerifyingOutput::NotAccepted(e),
             }
-            *user_amount = working_value;
         }
 
-        // if dot is present but nothing after that, add 2 zero
-        // if no dot, add dot + 2 zero
-        if user_amount.contains('.') {
-            let state = user_amount.split('.').collect::<Vec<&str>>();
-            if state[1].is_empty() {
-                *user_amount += "00"
-            }
-        } else {
-            *user_amount = format!("{user_amount}.00");
-        }
-
-        let float_amount: f64 = match user_amount.parse() {
-            Ok(v) => v,
-            Err(_) => return VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
-        };
+        // limit max character of the whole part to 10
+        let (whole, fraction) = user_amount.split_once('.').unwrap_or((user_amount, ""));
+        let whole = whole.chars().take(10).collect::<String>();
 
-        if float_amount <= 0.0 {
-            *user_amount = format!("{:.2}", (float_amount - (float_amount * 2.0)));
-            return VerifyingOutput::NotAccepted(NAType::AmountBelowZero);
-        }
+        let rounding = amount_format.get_rounding();
+        let rounded = fraction.trim_end_matches('0').len() > minor_units;
 
-        // checks if there is 2 number after the dot else add zero/s
-        if user_amount.contains('.') {
-            let splitted = user_amount.split('.').collect::<Vec<&str>>();
+        // keep exactly minor_units numbers after the dot by adding zeros or rounding the extra ones
+        let amount =
+            match Money::parse_rounded(&format!("{whole}.{fraction}"), minor_units, rounding) {
+                Some(v) => v,
+                None => return VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+            };
 
-            match splitted[1].len().cmp(&2) {
-                Ordering::Less => *user_amount = format!("{user_amount}0"),
-                Ordering::Greater => {
-                    *user_amount = format!("{}.{}", splitted[0], &splitted[1][..2])
-                }
-                Ordering::Equal => (),
-            }
+        if amount <= Money::ZERO {
+            *user_amount = (-amount).format(minor_units);
+            return VerifyingOutput::NotAccepted(NAType::AmountBelowZero);
         }
 
-        // we can safely split now as previously we just added a dot + 2 numbers with the amount
-        // and create the final value for the amount
-        let splitted_amount = user_amount.split('.').collect::<Vec<&str>>();
+        *user_amount = amount.format(minor_units);
 
-        // limit max character to 10
-        if splitted_amount[0].len() > 10 {
-            *user_amount = format!("{}.{}", &splitted_amount[0][..10], splitted_amount[1]);
+        if rounded {
+            return VerifyingOutput::Accepted(AType::RoundedAmount(rounding));
         }
 
         VerifyingOutput::Accepted(AType::Amount)
@@ -322,12 +310,19 @@ pub trait DataVerifier {
     /// - The Transaction method exists on the database.
     /// - The Transaction method is empty
     /// - contains any extra spaces
-    /// if the Transaction is not found, matches each character with the available
-    /// Transaction Methods and corrects to the best matching one.
-
-    fn verify_tx_method(&self, user_method: &mut String, conn: &Connection) -> VerifyingOutput {
+    /// if the Transaction is not found, ranks the available Transaction Methods by how close
+    /// they are and corrects to the best matching one only if it is a confident match
+    /// and no other method is about as close.
+    /// Hidden Transaction Methods are only accepted when typed in full and never corrected to.
+
+    fn verify_tx_method(
+        &self,
+        user_method: &mut String,
+        names: &NameCache,
+        conn: &Connection,
+    ) -> VerifyingOutput {
         // get all currently added tx methods
-        let all_tx_methods = get_all_tx_methods(conn);
+        let all_tx_methods = names.get_tx_methods(conn);
 
         *user_method = user_method.trim().to_string();
 
@@ -343,17 +338,21 @@ pub trait DataVerifier {
             }
         }
 
-        let best_match = get_best_match(user_method, all_tx_methods);
+        // only a clearly closest match replaces the typed method. If several methods are
+        // equally close it is left for the user to pick one of them
+        let active_tx_methods = names.get_active_tx_methods(conn);
+        if let [best_match] = get_close_matches(user_method, &active_tx_methods).as_slice() {
+            *user_method = best_match.to_string();
+        }
 
-        *user_method = best_match;
         VerifyingOutput::NotAccepted(NAType::InvalidTxMethod)
     }
 
     /// Checks if:
     ///
-    /// - The transaction method starts with E or I
+    /// - The transaction method starts with E, I, T or R
     ///
-    /// Auto expands E to Expense and I to Income.
+    /// Auto expands E to Expense, I to Income, T to Transfer and R to Refund.
     fn verify_tx_type(&self, user_type: &mut String) -> VerifyingOutput {
         *user_type = user_type.replace(' ', "");
 
@@ -369,57 +368,139 @@ pub trait DataVerifier {
         } else if user_type.to_lowercase().starts_with('t') {
             *user_type = "Transfer".to_string();
             VerifyingOutput::Accepted(AType::TxType)
+        } else if user_type.to_lowercase().starts_with('r') {
+            *user_type = "Refund".to_string();
+            VerifyingOutput::Accepted(AType::TxType)
         } else {
             *user_type = String::new();
             VerifyingOutput::NotAccepted(NAType::InvalidTxType)
         }
     }
 
-    fn verify_tags(&self, user_tag: &mut String) {
-        let mut splitted = user_tag.split(',').map(|s| s.trim()).collect::<Vec<&str>>();
+    /// Checks if:
+    ///
+    /// - the tags are separated by a comma
+    /// - any tag is repeated
+    /// - any tag is an existing tag written with a different case or spacing
+    ///
+    /// Removes the repeated tags and replaces the colliding ones with the existing tag.
+    /// The default tag limits are applied
+    fn verify_tags(&self, user_tag: &mut String, existing_tags: &[String]) -> VerifyingOutput {
+        self.verify_tags_limits(user_tag, existing_tags, &TagLimits::default())
+    }
+
+    /// Same as `verify_tags` but extra tags and characters over the given limits are cut off
+    fn verify_tags_limits(
+        &self,
+        user_tag: &mut String,
+        existing_tags: &[String],
+        limits: &TagLimits,
+    ) -> VerifyingOutput {
+        let mut splitted = user_tag
+            .split(',')
+            .map(normalize_name)
+            .collect::<Vec<String>>();
         splitted.retain(|s| !s.is_empty());
 
         let mut seen = HashSet::new();
         let mut unique = Vec::new();
 
         for item in splitted {
-            if seen.insert(item) {
+            let item = get_colliding_name(&item, existing_tags).unwrap_or(item);
+
+            if seen.insert(item.to_lowercase()) {
                 unique.push(item);
             }
         }
 
+        let status = limit_tags(&mut unique, limits);
         *user_tag = unique.join(", ");
+
+        match status {
+            Some(value) => VerifyingOutput::NotAccepted(value),
+            None if user_tag.is_empty() => VerifyingOutput::Nothing(AType::Tags),
+            None => VerifyingOutput::Accepted(AType::Tags),
+        }
     }
 
-    fn verify_tags_forced(&self, user_tag: &mut String, conn: &Connection) -> VerifyingOutput {
+    fn verify_tags_forced(
+        &self,
+        user_tag: &mut String,
+        names: &NameCache,
+        conn: &Connection,
+    ) -> VerifyingOutput {
+        self.verify_tags_known(user_tag, &names.get_tags(conn), &names.get_tag_limits(conn))
+    }
+
+    /// Keeps only the tags that are in the known tags, written the way they are saved.
+    /// The tags that are not known are removed and returned in the status so they
+    /// can be created if needed
+    fn verify_tags_known(
+        &self,
+        user_tag: &mut String,
+        known_tags: &[String],
+        limits: &TagLimits,
+    ) -> VerifyingOutput {
         if user_tag.is_empty() {
             return VerifyingOutput::Nothing(AType::Tags);
         }
-        let all_tags = get_all_tags(conn);
         let mut splitted = user_tag.split(',').map(|s| s.trim()).collect::<Vec<&str>>();
         splitted.retain(|s| !s.is_empty());
 
         let mut seen = HashSet::new();
         let mut unique = Vec::new();
+        let mut unknown: Vec<String> = Vec::new();
 
+        // tags are written the way they are saved. food becomes Food if Food exists
         for item in splitted {
-            if seen.insert(item) {
-                unique.push(item);
+            match get_colliding_name(item, known_tags) {
+                Some(tag) => {
+                    if seen.insert(tag.to_lowercase()) {
+                        unique.push(tag);
+                    }
+                }
+                None => {
+                    if get_colliding_name(item, &unknown).is_none() {
+                        unknown.push(normalize_name(item));
+                    }
+                }
             }
         }
 
-        let old_tags_len = unique.len();
+        let status = limit_tags(&mut unique, limits);
+        *user_tag = unique.join(", ");
 
-        unique.retain(|&tag| all_tags.contains(&tag.to_owned()));
+        if !unknown.is_empty() {
+            VerifyingOutput::NotAccepted(NAType::UnknownTags(unknown))
+        } else if let Some(value) = status {
+            VerifyingOutput::NotAccepted(value)
+        } else {
+            VerifyingOutput::Accepted(AType::Tags)
+        }
+    }
+}
 
-        let new_tags_len = unique.len();
+/// Keeps the tags that fit in the limits and cuts the longer ones.
+/// Returns the reason if anything had to be removed
+fn limit_tags(tags: &mut Vec<String>, limits: &TagLimits) -> Option<NAType> {
+    let mut status = None;
 
-        *user_tag = unique.join(", ");
+    if tags.len() > limits.max_tags {
+        tags.truncate(limits.max_tags);
+        status = Some(NAType::TooManyTags(limits.max_tags));
+    }
 
-        if old_tags_len == new_tags_len {
-            VerifyingOutput::Accepted(AType::Tags)
-        } else {
-            VerifyingOutput::NotAccepted(NAType::NonExistingTag)
+    for tag in tags.iter_mut() {
+        if tag.chars().count() > limits.max_tag_length {
+            *tag = tag
+                .chars()
+                .take(limits.max_tag_length)
+                .collect::<String>()
+                .trim_end()
+                .to_string();
+            status = status.or(Some(NAType::TagTooLong(limits.max_tag_length)));
         }
     }
+
+    status
 }
@@ -1,26 +1,47 @@
-use crate::db::{add_tags_column, create_db, update_balance_type, YEARS};
-use crate::outputs::ComparisonType;
+use crate::db::{
+    add_date_range_table, add_details_templates_table, add_excluded_tags_table,
+    add_external_ids_table, add_hidden_methods_table, add_minor_units_table,
+    add_refund_links_table, add_reviewed_txs_table, add_settings_table, add_tag_bounds_table,
+    add_tag_groups_table, add_tags_column, add_templates_table, add_tx_links_table, create_db,
+    get_bool_setting, get_date_range, merge_tag_case_duplicates, normalize_stored_amounts,
+    set_date_range, update_balance_type, DateRange, DETAILS_LENGTH_LIMIT,
+};
+use crate::outputs::{AType, ComparisonType, NAType};
 use crate::page_handler::{
-    IndexedData, SortingType, UserInputType, BACKGROUND, BOX, HIGHLIGHTED, TEXT,
+    IndexedData, SearchGrouping, SortOrder, SortingType, UserInputType, BACKGROUND, BOX, GRAY,
+    HIGHLIGHTED, TEXT,
+};
+use crate::utility::{
+    get_amount_format, get_date_format, get_user_tx_methods, AmountFormat, Clock, DateFormat,
+    Money, WeekRange,
 };
-use crate::utility::get_user_tx_methods;
+use chrono::{Datelike, Local, NaiveDate, Weekday};
+use crossterm::event::{poll, read, DisableBracketedPaste, EnableBracketedPaste};
 use crossterm::execute;
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen,
 };
-use ratatui::backend::CrosstermBackend;
+use ratatui::backend::{Backend, CrosstermBackend};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, BorderType, Borders, Tabs};
 use ratatui::Terminal;
 use rusqlite::{Connection, Result as sqlResult};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fs;
 use std::io::{stdout, Stdout, Write};
-use std::time::Duration;
+use std::iter::Peekable;
+use std::str::Chars;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use std::{process, thread};
-use strsim::normalized_levenshtein;
+use strsim::{jaro_winkler, normalized_damerau_levenshtein};
+use thousands::Separable;
+
+/// Usage count and the last used date of a tag or tx method
+pub type UsageData = HashMap<String, (usize, String)>;
 
 const RESTRICTED: [&str; 6] = ["Total", "Balance", "Changes", "Income", "Expense", "Cancel"];
 
@@ -42,6 +63,81 @@ pub fn get_all_tx_methods(conn: &Connection) -> Vec<String> {
     data
 }
 
+/// Returns the tx methods that were hidden by the user
+pub fn get_hidden_tx_methods(conn: &Connection) -> Vec<String> {
+    let mut query = match conn.prepare("SELECT tx_method FROM hidden_methods") {
+        Ok(query) => query,
+        Err(_) => return Vec::new(),
+    };
+
+    let hidden_methods = query.query_map([], |row| row.get::<_, String>(0));
+
+    match hidden_methods {
+        Ok(rows) => rows.filter_map(|row| row.ok()).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Returns the tags that were excluded from the Summary page calculations by the user
+pub fn get_excluded_tags(conn: &Connection) -> HashSet<String> {
+    let mut query = match conn.prepare("SELECT tag FROM excluded_tags") {
+        Ok(query) => query,
+        Err(_) => return HashSet::new(),
+    };
+
+    let excluded_tags = query.query_map([], |row| row.get::<_, String>(0));
+
+    match excluded_tags {
+        Ok(rows) => rows.filter_map(|row| row.ok()).collect(),
+        Err(_) => HashSet::new(),
+    }
+}
+
+/// Returns the tx methods that are not hidden in their position order. Returns every
+/// tx method if all of them are hidden
+pub fn get_active_tx_methods(conn: &Connection) -> Vec<String> {
+    filter_active_tx_methods(get_all_tx_methods(conn), &get_hidden_tx_methods(conn))
+}
+
+/// Leaves out the hidden tx methods. Returns every tx method if all of them are hidden
+pub fn filter_active_tx_methods(
+    all_methods: Vec<String>,
+    hidden_methods: &[String],
+) -> Vec<String> {
+    let active_methods = all_methods
+        .iter()
+        .filter(|method| !hidden_methods.contains(method))
+        .cloned()
+        .collect::<Vec<String>>();
+
+    if active_methods.is_empty() {
+        all_methods
+    } else {
+        active_methods
+    }
+}
+
+/// Removes the columns of the hidden tx methods from the rows of the Home balance table.
+/// Each row starts with the row name followed by a column for every tx method
+pub fn remove_hidden_method_columns(rows: &mut [Vec<String>], conn: &Connection) {
+    let active_methods = get_active_tx_methods(conn);
+
+    let hidden_columns = get_all_tx_methods(conn)
+        .iter()
+        .enumerate()
+        .filter(|(_, method)| !active_methods.contains(method))
+        .map(|(index, _)| index + 1)
+        .collect::<Vec<usize>>();
+
+    for row in rows.iter_mut() {
+        for column in hidden_columns.iter().rev() {
+            if *column < row.len() {
+                row.remove(*column);
+            }
+        }
+    }
+}
+
 /// Returns all unique tags from the db
 pub fn get_all_tags(conn: &Connection) -> Vec<String> {
     let mut query = conn
@@ -72,6 +168,139 @@ pub fn get_all_tags(conn: &Connection) -> Vec<String> {
     sorted_tags
 }
 
+/// Returns all tag and group pairs from the db.
+/// return example: `{"Coffee": "Food", "Groceries": "Food"}`
+pub fn get_tag_groups(conn: &Connection) -> HashMap<String, String> {
+    let mut query = conn
+        .prepare("SELECT tag, group_name FROM tag_groups")
+        .expect("could not prepare statement");
+
+    let mut tag_groups = HashMap::new();
+
+    if let Ok(rows) = query.query_map([], |row| {
+        let tag: String = row.get(0)?;
+        let group_name: String = row.get(1)?;
+        Ok((tag, group_name))
+    }) {
+        for (tag, group_name) in rows.flatten() {
+            tag_groups.insert(tag, group_name);
+        }
+    }
+
+    tag_groups
+}
+
+/// Returns the expected amount range of all tags with a bound from the db.
+/// return example: `{"Coffee": (None, Some(10.0))}`
+pub fn get_tag_bounds(conn: &Connection) -> HashMap<String, (Option<f64>, Option<f64>)> {
+    let mut query = conn
+        .prepare("SELECT tag, min_amount, max_amount FROM tag_bounds")
+        .expect("could not prepare statement");
+
+    let mut tag_bounds = HashMap::new();
+
+    if let Ok(rows) = query.query_map([], |row| {
+        let tag: String = row.get(0)?;
+        let min_amount: Option<f64> = row.get(1)?;
+        let max_amount: Option<f64> = row.get(2)?;
+        Ok((tag, (min_amount, max_amount)))
+    }) {
+        for (tag, bounds) in rows.flatten() {
+            tag_bounds.insert(tag, bounds);
+        }
+    }
+
+    tag_bounds
+}
+
+/// Returns the first and the last year with a transaction. None if there are no transactions
+pub fn get_tx_year_bounds(conn: &Connection) -> Option<(i32, i32)> {
+    conn.query_row(
+        "SELECT CAST(strftime('%Y', MIN(date)) AS INTEGER), CAST(strftime('%Y', MAX(date)) AS INTEGER) FROM tx_all",
+        [],
+        |row| Ok((row.get::<_, Option<i32>>(0)?, row.get::<_, Option<i32>>(1)?)),
+    )
+    .ok()
+    .and_then(|bounds| match bounds {
+        (Some(first), Some(last)) => Some((first, last)),
+        _ => None,
+    })
+}
+
+/// Returns how many times each tag and tx method was used along with the last date it was used
+/// from a single query. Transfer tx methods are counted for both the From and To method.
+/// return example: `({"Food": (3, "2022-05-01")}, {"Cash": (5, "2022-05-03")})`
+pub fn get_usage_data(conn: &Connection) -> (UsageData, UsageData) {
+    let mut query = conn
+        .prepare("SELECT tags, tx_method, COUNT(*), MAX(date) FROM tx_all GROUP BY tags, tx_method")
+        .expect("could not prepare statement");
+
+    let mut tag_usage: UsageData = HashMap::new();
+    let mut method_usage: UsageData = HashMap::new();
+
+    // adds the count and keeps the latest date of the value
+    let add_usage = |usage: &mut UsageData, value: &str, count: usize, date: &str| {
+        let entry = usage.entry(value.to_string()).or_insert((0, String::new()));
+        entry.0 += count;
+        if date > entry.1.as_str() {
+            entry.1 = date.to_string();
+        }
+    };
+
+    if let Ok(rows) = query.query_map([], |row| {
+        let tags: String = row.get(0)?;
+        let tx_method: String = row.get(1)?;
+        let count: usize = row.get(2)?;
+        let date: String = row.get(3)?;
+        Ok((tags, tx_method, count, date))
+    }) {
+        for (tags, tx_method, count, date) in rows.flatten() {
+            for tag in tags.split(',').map(|s| s.trim()) {
+                add_usage(&mut tag_usage, tag, count, &date);
+            }
+            for method in tx_method.split(" to ") {
+                add_usage(&mut method_usage, method, count, &date);
+            }
+        }
+    }
+
+    (tag_usage, method_usage)
+}
+
+/// Orders the values by how often and how recently they were used. The score of a value is its
+/// usage count divided by the highest usage count. The 5 most recently used values get
+/// `recency_weight` added on top of it. Values with the same score keep their original order.
+pub fn rank_by_usage(
+    mut values: Vec<String>,
+    usage: &UsageData,
+    recency_weight: f64,
+) -> Vec<String> {
+    let max_count = usage.values().map(|(count, _)| *count).max().unwrap_or(0);
+
+    let mut by_recency = usage.iter().collect::<Vec<(&String, &(usize, String))>>();
+    by_recency.sort_by(|a, b| b.1 .1.cmp(&a.1 .1).then(a.0.cmp(b.0)));
+
+    let recent = by_recency
+        .into_iter()
+        .take(5)
+        .map(|(value, _)| value)
+        .collect::<Vec<&String>>();
+
+    let get_score = |value: &String| {
+        let mut score = match usage.get(value) {
+            Some((count, _)) if max_count > 0 => *count as f64 / max_count as f64,
+            _ => 0.0,
+        };
+        if recent.contains(&value) {
+            score += recency_weight;
+        }
+        score
+    };
+
+    values.sort_by(|a, b| get_score(b).total_cmp(&get_score(a)));
+    values
+}
+
 /// Returns all unique details from the db
 pub fn get_all_details(conn: &Connection) -> Vec<String> {
     let mut query = conn
@@ -101,6 +330,279 @@ pub fn get_all_details(conn: &Connection) -> Vec<String> {
     sorted_details
 }
 
+/// Returns all transactions of the given YYYY-MM-DD date in the order they were added.
+/// return example: `[["2022-05-01", "Bus", "Cash", "1.50", "Expense", "Travel"]]`
+pub fn get_txs_by_date(date: &str, conn: &Connection) -> Vec<Vec<String>> {
+    let mut query = conn
+        .prepare(
+            "SELECT date, details, tx_method, amount, tx_type, tags FROM tx_all WHERE date = ? ORDER BY id_num",
+        )
+        .expect("could not prepare statement");
+
+    let mut txs = Vec::new();
+
+    if let Ok(rows) = query.query_map([date], |row| {
+        let mut tx = Vec::new();
+        for i in 0..6 {
+            tx.push(row.get::<_, String>(i)?);
+        }
+        Ok(tx)
+    }) {
+        txs.extend(rows.flatten());
+    }
+
+    txs
+}
+
+/// Returns the id_num of every transaction that was marked as reviewed
+pub fn get_reviewed_txs(conn: &Connection) -> HashSet<String> {
+    let mut query = conn
+        .prepare("SELECT id_num FROM reviewed_txs")
+        .expect("could not prepare statement");
+
+    let mut reviewed_txs = HashSet::new();
+
+    if let Ok(rows) = query.query_map([], |row| row.get::<_, i64>(0)) {
+        for id_num in rows.flatten() {
+            reviewed_txs.insert(id_num.to_string());
+        }
+    }
+
+    reviewed_txs
+}
+
+/// Returns the transaction with the id_num. The date is in YYYY-MM-DD format
+/// return example: `["2022-05-01", "Bus", "Cash", "1.50", "Expense", "Travel"]`
+pub fn get_tx_by_id(id_num: i32, conn: &Connection) -> Option<Vec<String>> {
+    conn.query_row(
+        "SELECT date, details, tx_method, amount, tx_type, tags FROM tx_all WHERE id_num = ?",
+        [id_num],
+        |row| {
+            let mut tx = Vec::new();
+            for i in 0..6 {
+                tx.push(row.get::<_, String>(i)?);
+            }
+            Ok(tx)
+        },
+    )
+    .ok()
+}
+
+/// Returns the id_num of the expense the refund is linked to. The expense may have been deleted since
+pub fn get_refund_original(id_num: i32, conn: &Connection) -> Option<i32> {
+    conn.query_row(
+        "SELECT original_id FROM refund_links WHERE id_num = ?",
+        [id_num],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+/// Returns the id_num of every refund linked to the expense in the order they were added
+pub fn get_refund_ids(original_id: i32, conn: &Connection) -> Vec<i32> {
+    let mut query = conn
+        .prepare("SELECT id_num FROM refund_links WHERE original_id = ? ORDER BY id_num")
+        .expect("could not prepare statement");
+
+    let mut refund_ids = Vec::new();
+
+    if let Ok(rows) = query.query_map([original_id], |row| row.get::<_, i32>(0)) {
+        refund_ids.extend(rows.flatten());
+    }
+
+    refund_ids
+}
+
+/// Returns how much of the expense can still be refunded after the refunds linked to it.
+/// The refund being edited is not counted. None if the transaction is not an expense
+pub fn get_refundable_amount(
+    original_id: i32,
+    editing_id: Option<i32>,
+    conn: &Connection,
+) -> Option<Money> {
+    let original = get_tx_by_id(original_id, conn)?;
+
+    if original[4] != "Expense" {
+        return None;
+    }
+
+    let mut refundable = Money::parse(&original[3]).unwrap_or_default();
+
+    for refund_id in get_refund_ids(original_id, conn) {
+        if Some(refund_id) == editing_id {
+            continue;
+        }
+
+        if let Some(refund) = get_tx_by_id(refund_id, conn) {
+            refundable -= Money::parse(&refund[3]).unwrap_or_default();
+        }
+    }
+
+    Some(refundable)
+}
+
+/// Returns the lines shown about the refund links of the transaction. A refund gets the expense
+/// it refunds and an expense gets every refund of it with the amount left to refund
+///
+/// return example: `["Refunded by: 2022-05-03 · Refund: Shoes · 20.00", "Left to refund: 30.00"]`
+pub fn get_refund_lines(id_num: i32, conn: &Connection) -> Vec<String> {
+    let date_format = get_date_format(conn);
+    let amount_format = get_amount_format(conn);
+
+    let describe = |tx: &[String]| {
+        format!(
+            "{} · {} · {}",
+            date_format.localize(&tx[0]),
+            tx[1],
+            amount_format.localize(&tx[3])
+        )
+    };
+
+    if let Some(original_id) = get_refund_original(id_num, conn) {
+        let original = match get_tx_by_id(original_id, conn) {
+            Some(tx) => describe(&tx),
+            None => "The expense was deleted".to_string(),
+        };
+        return vec![format!("Refund of: {original}")];
+    }
+
+    let mut lines = get_refund_ids(id_num, conn)
+        .into_iter()
+        .filter_map(|refund_id| get_tx_by_id(refund_id, conn))
+        .map(|refund| format!("Refunded by: {}", describe(&refund)))
+        .collect::<Vec<String>>();
+
+    if !lines.is_empty() {
+        if let Some(refundable) = get_refundable_amount(id_num, None, conn) {
+            lines.push(format!(
+                "Left to refund: {}",
+                amount_format.localize(&refundable.to_string())
+            ));
+        }
+    }
+
+    lines
+}
+
+/// Returns the id_num and the saved data of every transaction linked to the transaction with the
+/// link type, oldest first. Links to transactions that are not in the database are skipped
+///
+/// return example: `[(5, ["2022-05-01", "Deposit", "Cash", "100.00", "Expense", "Rent"])]`
+pub fn get_linked_txs(id_num: i32, link_type: &str, conn: &Connection) -> Vec<(i32, Vec<String>)> {
+    let mut query = conn
+        .prepare(
+            "SELECT tx_all.id_num, date, details, tx_method, amount, tx_type, tags FROM tx_links
+            INNER JOIN tx_all ON tx_all.id_num = tx_links.linked_id
+            WHERE tx_links.id_num = ? AND link_type = ? ORDER BY date, tx_all.id_num",
+        )
+        .expect("could not prepare statement");
+
+    let mut linked_txs = Vec::new();
+
+    if let Ok(rows) = query.query_map((id_num, link_type), |row| {
+        let mut tx = Vec::new();
+        for i in 1..7 {
+            tx.push(row.get::<_, String>(i)?);
+        }
+        Ok((row.get::<_, i32>(0)?, tx))
+    }) {
+        linked_txs.extend(rows.flatten());
+    }
+
+    linked_txs
+}
+
+/// Returns the transaction count and the highest id_num of every month of the given year.
+/// Months without any transactions return 0 for both.
+/// return example: `[(3, 25), (0, 0), (1, 31),]`
+pub fn get_month_tx_counts(year: i32, conn: &Connection) -> Vec<(usize, i64)> {
+    let mut query = conn
+        .prepare(
+            "SELECT CAST(strftime('%m', date) AS INTEGER), COUNT(*), MAX(id_num) FROM tx_all
+            WHERE date BETWEEN ? AND ? GROUP BY strftime('%m', date)",
+        )
+        .expect("could not prepare statement");
+
+    let mut counts = vec![(0, 0); 12];
+
+    if let Ok(rows) = query.query_map([format!("{year}-01-01"), format!("{year}-12-31")], |row| {
+        Ok((
+            row.get::<_, usize>(0)?,
+            row.get::<_, usize>(1)?,
+            row.get::<_, i64>(2)?,
+        ))
+    }) {
+        for (month, count, last_id) in rows.flatten() {
+            counts[month - 1] = (count, last_id);
+        }
+    }
+
+    counts
+}
+
+/// Returns every month that has at least one transaction
+///
+/// return example: `{"2022-05", "2023-01"}`
+pub fn get_tx_periods(conn: &Connection) -> HashSet<String> {
+    let mut query = conn
+        .prepare("SELECT DISTINCT strftime('%Y-%m', date) FROM tx_all")
+        .expect("could not prepare statement");
+
+    let mut periods = HashSet::new();
+
+    if let Ok(rows) = query.query_map([], |row| row.get::<_, String>(0)) {
+        periods.extend(rows.flatten());
+    }
+
+    periods
+}
+
+/// Checks whether a transaction with the exact same date, details, tx method, amount, tx type and tags
+/// already exists in the db
+pub fn check_duplicate_tx(
+    date: &str,
+    details: &str,
+    tx_method: &str,
+    amount: &str,
+    tx_type: &str,
+    tags: &str,
+    conn: &Connection,
+) -> bool {
+    let count: sqlResult<i32> = conn.query_row(
+        "SELECT COUNT(*) FROM tx_all WHERE date = ? AND details = ? AND tx_method = ?
+        AND CAST(amount AS REAL) = CAST(? AS REAL) AND tx_type = ? AND tags = ?",
+        [date, details, tx_method, amount, tx_type, tags],
+        |row| row.get(0),
+    );
+
+    matches!(count, Ok(count) if count > 0)
+}
+
+/// Checks whether a transaction with the same tx method and tags and an amount within 10% of the
+/// given amount exists between the two YYYY-MM-DD dates, both included
+pub fn check_similar_tx(
+    start_date: &str,
+    end_date: &str,
+    tx_method: &str,
+    amount: &str,
+    tags: &str,
+    conn: &Connection,
+) -> bool {
+    let amount: f64 = match amount.parse() {
+        Ok(amount) => amount,
+        Err(_) => return false,
+    };
+
+    let count: sqlResult<i32> = conn.query_row(
+        "SELECT COUNT(*) FROM tx_all WHERE date BETWEEN ? AND ? AND tx_method = ? AND tags = ?
+        AND ABS(CAST(amount AS REAL) - ?) <= ? * 0.1",
+        (start_date, end_date, tx_method, tags, amount, amount),
+        |row| row.get(0),
+    );
+
+    matches!(count, Ok(count) if count > 0)
+}
+
 /// Gets all columns inside the tx_all table. Used to determine if the database needs to be migrated
 pub fn get_all_tx_columns(conn: &Connection) -> Vec<String> {
     let column_names = conn
@@ -149,9 +651,10 @@ pub fn get_last_balance_id(conn: &Connection) -> sqlResult<i32> {
 /// based on the month and year index that has been passed to it. Will return two dates to use in the
 /// WHERE statement. Will return the 1st and the 31st date of the given month and year.
 /// return example: `(2022-01-01, 2022-01-31)`
-pub fn get_sql_dates(month: usize, year: usize) -> (String, String) {
-    let datetime_1 = format!("{}-{:02}-01", YEARS[year], month + 1);
-    let datetime_2 = format!("{}-{:02}-31", YEARS[year], month + 1);
+pub fn get_sql_dates(month: usize, year: usize, date_range: &DateRange) -> (String, String) {
+    let year = date_range.year_at(year);
+    let datetime_1 = format!("{}-{:02}-01", year, month + 1);
+    let datetime_2 = format!("{}-{:02}-31", year, month + 1);
     (datetime_1, datetime_2)
 }
 
@@ -186,6 +689,209 @@ pub fn check_old_sql(conn: &mut Connection) {
             }
         }
     }
+
+    // earlier version of the database didn't had the tag_groups table
+    if !check_table_exists("tag_groups", conn) {
+        let status = add_tag_groups_table(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // earlier version of the database didn't had the tag_bounds table
+    if !check_table_exists("tag_bounds", conn) {
+        let status = add_tag_bounds_table(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // earlier version of the database didn't had the reviewed_txs table
+    if !check_table_exists("reviewed_txs", conn) {
+        let status = add_reviewed_txs_table(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // earlier version of the database didn't had the minor_units table
+    if !check_table_exists("minor_units", conn) {
+        let status = add_minor_units_table(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // earlier version of the database didn't had the hidden_methods table
+    if !check_table_exists("hidden_methods", conn) {
+        let status = add_hidden_methods_table(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // earlier version of the database didn't had the excluded_tags table
+    if !check_table_exists("excluded_tags", conn) {
+        let status = add_excluded_tags_table(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // earlier version of the database didn't had the refund_links table
+    if !check_table_exists("refund_links", conn) {
+        let status = add_refund_links_table(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // earlier version of the database didn't had the tx_links table
+    if !check_table_exists("tx_links", conn) {
+        let status = add_tx_links_table(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // earlier version of the database didn't had the external_ids table
+    if !check_table_exists("external_ids", conn) {
+        let status = add_external_ids_table(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // earlier version of the database didn't had the settings table
+    if !check_table_exists("settings", conn) {
+        let status = add_settings_table(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // earlier version of the database didn't had the template tables
+    if !check_table_exists("tx_templates", conn) {
+        let status = add_templates_table(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // earlier version of the database didn't had the details_templates table
+    if !check_table_exists("details_templates", conn) {
+        let status = add_details_templates_table(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // earlier version of the database had the year range fixed to the default range
+    if !check_table_exists("date_range", conn) {
+        let status = add_date_range_table(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // extend the year range once the current year goes past it so new transactions can still be added
+    let date_range = get_date_range(conn);
+    let current_year = Local::now().year();
+
+    if current_year > date_range.end_year {
+        println!("Extending the year range to {current_year}...");
+        let status = set_date_range(&date_range.including(current_year), conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // earlier version of the database calculated the amounts with f64 which could save
+    // balances with tiny errors. Round them once so the exact calculations start from them
+    if !get_bool_setting("amounts_normalized", conn) {
+        let status = normalize_stored_amounts(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+
+    // earlier version of the database could save the same tag with different cases
+    // which splits the tag into multiple rows on the summary
+    if !get_bool_setting("tags_merged", conn) {
+        let status = merge_tag_case_duplicates(conn);
+        if let Err(e) = status {
+            println!("Database updating failed. Try again. Error: {}", e);
+            println!("Commits reversed. Exiting...");
+            process::exit(1);
+        }
+    }
+}
+
+/// The tables that older versions of the database did not have. `check_old_sql` adds them
+pub const MIGRATED_TABLES: [&str; 13] = [
+    "tag_groups",
+    "tag_bounds",
+    "reviewed_txs",
+    "minor_units",
+    "hidden_methods",
+    "excluded_tags",
+    "refund_links",
+    "tx_links",
+    "external_ids",
+    "settings",
+    "tx_templates",
+    "details_templates",
+    "date_range",
+];
+
+/// Returns whether the structure of the database is older than what the app reads.
+/// Only reads so it also works on a read-only connection, which `check_old_sql` can not update
+pub fn needs_migration(conn: &Connection) -> bool {
+    !get_all_tx_columns(conn).contains(&"tags".to_string())
+        || check_old_balance_sql(conn)
+        || MIGRATED_TABLES
+            .iter()
+            .any(|table| !check_table_exists(table, conn))
+}
+
+/// Checks if a table with the given name exists in the database
+pub fn check_table_exists(table_name: &str, conn: &Connection) -> bool {
+    conn.query_row(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = ?",
+        [table_name],
+        |row| row.get::<_, String>(0),
+    )
+    .is_ok()
 }
 
 /// Checks if the balance_all table is outdated
@@ -213,7 +919,8 @@ pub fn check_old_balance_sql(conn: &Connection) -> bool {
 pub fn enter_tui_interface() -> Result<Terminal<CrosstermBackend<Stdout>>, Box<dyn Error>> {
     enable_raw_mode()?;
     let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    // pasted text comes as one event instead of a key press for every character
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
@@ -226,12 +933,71 @@ pub fn exit_tui_interface() -> Result<(), Box<dyn Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableBracketedPaste
+    )?;
     terminal.show_cursor()?;
     disable_raw_mode()?;
     Ok(())
 }
 
+/// Registers flags that get set when the app receives SIGTSTP or SIGCONT from outside.
+/// Returns `(suspend_flag, resume_flag)`. The flags never get set on non-unix systems.
+#[cfg(not(tarpaulin_include))]
+pub fn register_suspend_signals() -> (Arc<AtomicBool>, Arc<AtomicBool>) {
+    let suspend_flag = Arc::new(AtomicBool::new(false));
+    let resume_flag = Arc::new(AtomicBool::new(false));
+
+    #[cfg(unix)]
+    {
+        use signal_hook::consts::{SIGCONT, SIGTSTP};
+        use signal_hook::flag::register;
+
+        // failing to register only means suspending from outside will not restore the terminal
+        register(SIGTSTP, Arc::clone(&suspend_flag)).ok();
+        register(SIGCONT, Arc::clone(&resume_flag)).ok();
+    }
+
+    (suspend_flag, resume_flag)
+}
+
+/// Restores the terminal and stops the process until it is resumed. Once resumed, raw mode and
+/// the alternate screen are turned on again, the whole screen is redrawn on the next draw and
+/// key presses made while suspended are discarded. Does nothing on non-unix systems.
+#[cfg(not(tarpaulin_include))]
+pub fn suspend_tui_interface<B: Backend>(terminal: &mut Terminal<B>) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        execute!(stdout(), LeaveAlternateScreen, DisableBracketedPaste)?;
+        terminal.show_cursor()?;
+        disable_raw_mode()?;
+
+        // SIGTSTP is handled by the app so SIGSTOP is used to actually stop the process
+        signal_hook::low_level::raise(signal_hook::consts::SIGSTOP)?;
+
+        enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen, EnableBracketedPaste)?;
+        terminal.clear()?;
+
+        while poll(Duration::from_millis(0))? {
+            read()?;
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = terminal;
+
+    Ok(())
+}
+
+/// Returns the last modified time of the db file the connection is using
+pub fn get_db_modified_time(conn: &Connection) -> Option<SystemTime> {
+    let path = conn.path()?;
+    fs::metadata(path).ok()?.modified().ok()
+}
+
 /// Checks if a db already exists or prompts to create a new one
 #[cfg(not(tarpaulin_include))]
 pub fn check_n_create_db(verifying_path: &str) -> Result<(), Box<dyn Error>> {
@@ -281,6 +1047,27 @@ pub fn styled_block(title: &str) -> Block {
         ))
 }
 
+/// Returns the position of the selected row among all the rows of a table to be shown in its
+/// title. Without a selection the first shown row is used. None if every row fits in the area.
+/// The area height includes the borders and the 1 line header of the table
+///
+/// return example: `42/317`
+pub fn get_table_position(
+    selected: Option<usize>,
+    offset: usize,
+    total_rows: usize,
+    area_height: u16,
+) -> Option<String> {
+    let shown_rows = area_height.saturating_sub(3) as usize;
+
+    if total_rows <= shown_rows {
+        return None;
+    }
+
+    let position = (selected.unwrap_or(offset) + 1).min(total_rows);
+    Some(format!("{position}/{total_rows}"))
+}
+
 #[cfg(not(tarpaulin_include))]
 pub fn main_block<'a>() -> Block<'a> {
     Block::default().style(Style::default().bg(BACKGROUND).fg(BOX))
@@ -309,11 +1096,16 @@ pub fn create_bolded_text(text: &str) -> Vec<Line> {
 
 /// Tabs from some given data for the UI
 #[cfg(not(tarpaulin_include))]
-pub fn create_tab<'a>(data: &'a IndexedData, name: &'a str) -> Tabs<'a> {
+pub fn create_tab<'a>(data: &'a IndexedData, name: &'a str, width: u16) -> Tabs<'a> {
+    // months and years without any transactions are greyed out
     let titles = data
-        .titles
-        .iter()
-        .map(|t| Line::from(vec![Span::styled(t, Style::default().fg(TEXT))]))
+        .get_titles(width)
+        .into_iter()
+        .enumerate()
+        .map(|(index, t)| {
+            let color = if data.is_empty_at(index) { GRAY } else { TEXT };
+            Line::from(vec![Span::styled(t, Style::default().fg(color))])
+        })
         .collect();
 
     Tabs::new(titles)
@@ -379,6 +1171,43 @@ pub fn check_restricted(item: &str, restricted: Option<&Vec<String>>) -> bool {
     false
 }
 
+/// Trims the name and collapses every run of spaces inside it to a single space.
+/// Used for tx method and tag names before they are saved
+pub fn normalize_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Returns the existing name that is the same as the given name once both are normalized
+/// and compared case-insensitively
+pub fn get_colliding_name(name: &str, existing: &[String]) -> Option<String> {
+    let name = normalize_name(name).to_lowercase();
+
+    existing
+        .iter()
+        .find(|existing_name| normalize_name(existing_name).to_lowercase() == name)
+        .map(|existing_name| existing_name.to_string())
+}
+
+/// Returns every group of names that collide with each other once normalized and compared
+/// case-insensitively. Each group keeps the order of the given names.
+/// return example: `[["Cash", "cash "]]`
+pub fn get_name_collisions(names: &[String]) -> Vec<Vec<String>> {
+    let mut collisions: Vec<Vec<String>> = Vec::new();
+
+    for name in names {
+        match collisions
+            .iter_mut()
+            .find(|group| get_colliding_name(name, group).is_some())
+        {
+            Some(group) => group.push(name.to_string()),
+            None => collisions.push(vec![name.to_string()]),
+        }
+    }
+
+    collisions.retain(|group| group.len() > 1);
+    collisions
+}
+
 /// Parse github release information for popup menu
 pub fn parse_github_body(body: String) -> String {
     let body = body.replace("## Updates", "");
@@ -388,41 +1217,445 @@ pub fn parse_github_body(body: String) -> String {
     format!("\n{}\n", &body[..end_point].trim())
 }
 
-/// Uses Levenshtein algorithm to get the best match of a string in a vec of strings
+/// The lowest match score that is close enough to replace a typed value on its own
+pub const CONFIDENT_MATCH_SCORE: f64 = 0.8;
+
+/// Scores how close the typed text is to a candidate between 0.0 and 1.0, ignoring the case.
+/// Takes the higher of the Jaro-Winkler similarity, which favors a typed prefix, and the
+/// Damerau-Levenshtein similarity, which counts swapped letters as a single typo
+pub fn get_match_score(data: &str, candidate: &str) -> f64 {
+    let data = data.trim().to_lowercase();
+    let candidate = candidate.trim().to_lowercase();
+
+    jaro_winkler(&data, &candidate).max(normalized_damerau_levenshtein(&data, &candidate))
+}
+
+/// Returns every candidate with its match score, the closest first. Candidates with
+/// the same score keep their given order
+///
+/// return example: `[("Bank", 0.93), ("Card", 0.5), ("Cash", 0.5)]`
+pub fn get_ranked_matches(data: &str, candidates: &[String]) -> Vec<(String, f64)> {
+    let mut ranked = candidates
+        .iter()
+        .map(|candidate| (candidate.to_string(), get_match_score(data, candidate)))
+        .collect::<Vec<(String, f64)>>();
+
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked
+}
+
+/// The most the score of another candidate can be below the best match for the two to be
+/// too close to pick one of them on its own
+pub const AMBIGUOUS_MATCH_MARGIN: f64 = 0.05;
+
+/// Returns the candidates that are too close to the best match to tell apart, the closest first.
+/// Contains only the best match if it is clearly the closest one and nothing if the best
+/// match is not a confident one
+///
+/// return example: `["Visa Card", "Virtual Card"]`
+pub fn get_close_matches(data: &str, candidates: &[String]) -> Vec<String> {
+    let ranked = get_ranked_matches(data, candidates);
+
+    let best_score = match ranked.first() {
+        Some((_, score)) if *score >= CONFIDENT_MATCH_SCORE => *score,
+        _ => return Vec::new(),
+    };
+
+    ranked
+        .into_iter()
+        .take_while(|(_, score)| best_score - score <= AMBIGUOUS_MATCH_MARGIN)
+        .map(|(candidate, _)| candidate)
+        .collect()
+}
+
+/// Returns the closest match of a string in a vec of strings
 pub fn get_best_match(data: &str, matching_set: Vec<String>) -> String {
-    let mut best_match = &matching_set[0];
-    let mut best_score = -1.0;
+    get_ranked_matches(data, &matching_set)
+        .into_iter()
+        .next()
+        .map(|(best_match, _)| best_match)
+        .unwrap_or_default()
+}
 
-    for x in matching_set.iter() {
-        let new_score = normalized_levenshtein(&x.to_lowercase(), &data.to_lowercase());
+/// Checks whether every whitespace separated token of the query is a case-insensitive prefix
+/// of some token of the candidate, in any order. Returns the sum of the positions of the
+/// matched candidate tokens so that a lower value means the tokens matched earlier
+pub fn match_tokens(query: &str, candidate: &str) -> Option<usize> {
+    let candidate_tokens = candidate
+        .split_whitespace()
+        .map(|token| token.to_lowercase())
+        .collect::<Vec<String>>();
 
-        if new_score > best_score {
-            best_match = x;
-            best_score = new_score;
-        }
+    let mut score = 0;
+    let mut matched = false;
+
+    for query_token in query.split_whitespace() {
+        let query_token = query_token.to_lowercase();
+        let position = candidate_tokens
+            .iter()
+            .position(|token| token.starts_with(&query_token))?;
+
+        score += position;
+        matched = true;
     }
-    best_match.to_string()
+
+    matched.then_some(score)
+}
+
+/// Returns the candidate that matches all tokens of the query the earliest.
+/// On equal scores the candidate that comes first is kept
+pub fn get_best_token_match(query: &str, matching_set: &[String]) -> Option<String> {
+    matching_set
+        .iter()
+        .filter_map(|candidate| match_tokens(query, candidate).map(|score| (score, candidate)))
+        .min_by_key(|(score, _)| *score)
+        .map(|(_, candidate)| candidate.to_string())
 }
 
 /// Used for sorting summary table data
-pub fn sort_table_data(mut data: Vec<Vec<String>>, sort_type: &SortingType) -> Vec<Vec<String>> {
-    match sort_type {
-        SortingType::ByTags => data.sort(),
-        SortingType::ByIncome => data.sort_by(|a, b| {
-            let val_a: f64 = a[1].parse().unwrap();
-            let val_b: f64 = b[1].parse().unwrap();
-            val_b.partial_cmp(&val_a).unwrap()
-        }),
-        SortingType::ByExpense => {
-            data.sort_by(|a, b| {
-                let val_a: f64 = a[2].parse().unwrap();
-                let val_b: f64 = b[2].parse().unwrap();
-                val_b.partial_cmp(&val_a).unwrap()
-            });
+pub fn sort_table_data(data: Vec<Vec<String>>, sort_type: &SortingType) -> Vec<Vec<String>> {
+    sort_table_data_by(data, sort_type, sort_type.default_order())
+}
+
+/// Sorts the summary tag table by the column of the sort type in the given order.
+/// Amounts and percentages are compared as numbers and rows with the same value
+/// are kept in the tag order
+pub fn sort_table_data_by(
+    mut data: Vec<Vec<String>>,
+    sort_type: &SortingType,
+    order: SortOrder,
+) -> Vec<Vec<String>> {
+    let column = sort_type.column();
+
+    // example: 1,250.00 => 1250.0, 12.50% => 12.5
+    let to_number = |value: &str| {
+        value
+            .replace(',', "")
+            .trim_end_matches('%')
+            .parse::<f64>()
+            .unwrap_or(0.0)
+    };
+
+    data.sort_by(|a, b| {
+        let ordering = if column == 0 {
+            a[0].cmp(&b[0])
+        } else {
+            to_number(&a[column]).total_cmp(&to_number(&b[column]))
+        };
+
+        let ordering = match order {
+            SortOrder::Ascending => ordering,
+            SortOrder::Descending => ordering.reverse(),
+        };
+
+        ordering.then_with(|| a[0].cmp(&b[0]))
+    });
+
+    data
+}
+
+/// Selects the month and year of the clock's date if the month and year of the previous day
+/// were selected. Returns whether the selection changed. Used when the day changes while the app is running
+pub fn roll_over_month(
+    months: &mut IndexedData,
+    years: &mut IndexedData,
+    previous_day: NaiveDate,
+    clock: &impl Clock,
+) -> bool {
+    let today = clock.today();
+    let year_position = |year: i32| years.titles.iter().position(|y| *y == year.to_string());
+
+    let is_previous_selected = months.index == previous_day.month0() as usize
+        && year_position(previous_day.year()) == Some(years.index);
+
+    let year_index = match year_position(today.year()) {
+        Some(index) if is_previous_selected => index,
+        _ => return false,
+    };
+
+    let changed = months.index != today.month0() as usize || years.index != year_index;
+
+    months.index = today.month0() as usize;
+    years.index = year_index;
+    changed
+}
+
+/// Selects the month and year of the date. Returns false without changing the selection
+/// if the year is not on the year tab
+pub fn select_month_of(months: &mut IndexedData, years: &mut IndexedData, date: NaiveDate) -> bool {
+    match years
+        .titles
+        .iter()
+        .position(|y| *y == date.year().to_string())
+    {
+        Some(year_index) => {
+            months.index = date.month0() as usize;
+            years.index = year_index;
+            true
         }
+        None => false,
     }
+}
 
-    data
+/// Moves the week one week forward or back. The month and year tabs follow the week once it
+/// no longer has a day in the selected month. Returns false without moving if none of the
+/// days of the new week is in a year on the year tab
+pub fn step_week(
+    week: &mut WeekRange,
+    forward: bool,
+    months: &mut IndexedData,
+    years: &mut IndexedData,
+) -> bool {
+    let new_week = if forward {
+        week.next()
+    } else {
+        week.previous()
+    };
+
+    let selected_year = years.titles[years.index].parse::<i32>().unwrap_or_default();
+
+    if new_week.overlaps_month(months.index, selected_year)
+        || select_month_of(months, years, new_week.get_start())
+        || select_month_of(months, years, new_week.get_end())
+    {
+        *week = new_week;
+        true
+    } else {
+        false
+    }
+}
+
+/// Moves the week to the first week of the selected month if it has no day in it.
+/// Used after the month or year tab got changed while the weekly view is on
+pub fn align_week(
+    week: &mut WeekRange,
+    months: &IndexedData,
+    years: &IndexedData,
+    week_start: Weekday,
+) {
+    let selected_year = years.titles[years.index].parse::<i32>().unwrap_or_default();
+
+    if !week.overlaps_month(months.index, selected_year) {
+        if let Some(first_week) =
+            WeekRange::in_month(months.index, selected_year, week_start).first()
+        {
+            *week = *first_week;
+        }
+    }
+}
+
+/// Groups the search result rows by month or year of their date while keeping the order the
+/// groups first appear in. A subtotal row is added after the rows of each group with the group name,
+/// the number of transactions, the net amount and Subtotal as the tx type. Transfers are not counted in the net amount
+pub fn group_search_txs(txs: &[Vec<String>], grouping: SearchGrouping) -> Vec<Vec<String>> {
+    if let SearchGrouping::None = grouping {
+        return txs.to_vec();
+    }
+
+    // search dates are in DD-MM-YYYY format
+    let group_key = |date: &str| -> String {
+        let (_, month_year) = date.split_once('-').unwrap_or(("", date));
+        match grouping {
+            SearchGrouping::Month => month_year.to_string(),
+            _ => month_year.split('-').last().unwrap_or("").to_string(),
+        }
+    };
+
+    let mut group_order: Vec<String> = Vec::new();
+    let mut groups: HashMap<String, Vec<&Vec<String>>> = HashMap::new();
+
+    for tx in txs {
+        let key = group_key(&tx[0]);
+        if !groups.contains_key(&key) {
+            group_order.push(key.clone());
+        }
+        groups.entry(key).or_default().push(tx);
+    }
+
+    let mut grouped_txs = Vec::new();
+
+    for key in group_order {
+        let group_txs = &groups[&key];
+        let mut net_amount = Money::ZERO;
+
+        for tx in group_txs {
+            let amount = Money::parse(&tx[3]).unwrap_or_default();
+            match tx[4].as_str() {
+                "Income" | "Refund" => net_amount += amount,
+                "Expense" => net_amount -= amount,
+                _ => {}
+            }
+            grouped_txs.push(tx.to_vec());
+        }
+
+        grouped_txs.push(vec![
+            key,
+            format!("Subtotal of {} transactions", group_txs.len()),
+            String::new(),
+            net_amount.to_string(),
+            "Subtotal".to_string(),
+            String::new(),
+        ]);
+    }
+
+    grouped_txs
+}
+
+/// Trims the text, turns every whitespace run that contains a newline or a tab into a single
+/// space and removes other control characters
+///
+/// example: `" Lunch\n\twith team "` => `"Lunch with team"`
+pub fn clean_details(text: &str) -> String {
+    let mut cleaned = String::new();
+    let mut whitespace = String::new();
+
+    for c in text.trim().chars() {
+        if c.is_whitespace() {
+            whitespace.push(c);
+            continue;
+        }
+
+        // plain spaces are kept as typed, any other whitespace run becomes one space
+        if !whitespace.is_empty() {
+            if whitespace.chars().all(|c| c == ' ') {
+                cleaned.push_str(&whitespace);
+            } else {
+                cleaned.push(' ');
+            }
+            whitespace.clear();
+        }
+
+        if !c.is_control() {
+            cleaned.push(c);
+        }
+    }
+
+    cleaned
+}
+
+/// Returns the details the way they are shown in a table. Details saved before they were
+/// verified can have any length and newlines so they are cleaned and cut
+pub fn get_shown_details(details: &str) -> String {
+    let cleaned = clean_details(details);
+
+    if cleaned.chars().count() > DETAILS_LENGTH_LIMIT {
+        let shortened = cleaned
+            .chars()
+            .take(DETAILS_LENGTH_LIMIT - 1)
+            .collect::<String>();
+        format!("{shortened}…")
+    } else {
+        cleaned
+    }
+}
+
+/// The column names of the Home and the Search transaction tables
+pub const TX_TABLE_HEADER: [&str; 6] = ["Date", "Details", "TX Method", "Amount", "Type", "Tags"];
+
+/// Joins the fields into a line of a separated text such as CSV or TSV. Fields with the separator,
+/// a quote or a line break are quoted with their quotes doubled
+///
+/// return example: `2023-05-01\t"Rent\tMay"\tBank\t800.00\tExpense\tHome`
+pub fn to_separated_line(fields: &[String], separator: char) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            if field.contains(separator)
+                || field.contains('"')
+                || field.contains('\n')
+                || field.contains('\r')
+            {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(&separator.to_string())
+}
+
+/// Returns the rows of a transaction table as tab separated lines with the dates
+/// in the date format. The column names are the 1st line if with_header is true
+pub fn get_tx_table_tsv(
+    rows: &[Vec<String>],
+    date_format: &DateFormat,
+    with_header: bool,
+) -> String {
+    let mut lines = Vec::new();
+
+    if with_header {
+        let header = TX_TABLE_HEADER.map(|name| name.to_string());
+        lines.push(to_separated_line(&header, '\t'));
+    }
+
+    for row in rows {
+        let mut fields = row.clone();
+        if let Some(date) = fields.first_mut() {
+            *date = date_format.format_table_date(date);
+        }
+        lines.push(to_separated_line(&fields, '\t'));
+    }
+
+    lines.join("\n")
+}
+
+/// Returns the rows of a Summary table as tab separated lines with the column names as the
+/// 1st line. The numbers are localized and the excluded tags are marked as shown on the page
+///
+/// return example: `Tags\tTotal Income\nFood (excluded)\t1,250.00`
+pub fn get_summary_table_tsv(
+    header: &[&str],
+    rows: &[Vec<String>],
+    amount_format: &AmountFormat,
+    excluded_tags: &HashSet<String>,
+) -> String {
+    let header = header
+        .iter()
+        .map(|name| name.to_string())
+        .collect::<Vec<String>>();
+    let mut lines = vec![to_separated_line(&header, '\t')];
+
+    for row in rows {
+        let excluded = row
+            .first()
+            .map_or(false, |name| excluded_tags.contains(name));
+
+        let fields = row
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                if excluded && index == 0 {
+                    format!("{field} (excluded)")
+                } else {
+                    amount_format.localize(field)
+                }
+            })
+            .collect::<Vec<String>>();
+        lines.push(to_separated_line(&fields, '\t'));
+    }
+
+    lines.join("\n")
+}
+
+/// Turns pasted text into a single line. The spaces around the line breaks are removed and
+/// the lines are joined with a space. Tabs become spaces as well
+///
+/// return example: `Rent May 2023` for `"Rent\r\nMay\t2023\n"`
+pub fn get_single_line(text: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| {
+            // the start of the 1st line is kept as it may continue the text before the index point
+            if index == 0 {
+                line.trim_end()
+            } else {
+                line.trim()
+            }
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .replace('\t', " ")
 }
 
 /// Adds a char to the given index on the given string
@@ -461,3 +1694,376 @@ pub fn check_comparison(input: &str) -> ComparisonType {
         ComparisonType::Equal
     }
 }
+
+/// Expands the k and m multipliers that follow a number into the full number.
+/// Returns None if a multiplier is not right after a number or another digit follows it
+///
+/// example: `1.5k+2M` => `1500+2000000`
+pub fn expand_amount_multipliers(amount: &str) -> Option<String> {
+    let mut expanded = String::new();
+    let mut number = String::new();
+    let mut chars = amount.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+
+        let places = match c {
+            'k' | 'K' => 3,
+            'm' | 'M' => 6,
+            _ => {
+                expanded.push_str(&number);
+                expanded.push(c);
+                number.clear();
+                continue;
+            }
+        };
+
+        let followed_by_number = chars.peek().map_or(false, |next| {
+            next.is_ascii_digit() || matches!(next, '.' | 'k' | 'K' | 'm' | 'M')
+        });
+
+        if !number.chars().any(|c| c.is_ascii_digit()) || followed_by_number {
+            return None;
+        }
+
+        expanded.push_str(&shift_decimal(&number, places));
+        number.clear();
+    }
+    expanded.push_str(&number);
+
+    Some(expanded)
+}
+
+/// Moves the decimal point of a number to the right by the given places without going through f64
+fn shift_decimal(number: &str, places: usize) -> String {
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+
+    let mut fraction = fraction.to_string();
+    while fraction.len() < places {
+        fraction.push('0');
+    }
+
+    let whole = format!("{whole}{}", &fraction[..places]);
+    let whole = match whole.trim_start_matches('0') {
+        "" => "0",
+        trimmed => trimmed,
+    };
+
+    if fraction.len() > places {
+        format!("{whole}.{}", &fraction[places..])
+    } else {
+        whole.to_string()
+    }
+}
+
+/// The symbols that make an amount a calculation
+const AMOUNT_CALC_SYMBOLS: [char; 7] = ['*', '/', '+', '-', '%', '(', ')'];
+
+/// Keeps only the numbers, dots, parentheses and calculation symbols of the amount.
+/// A % not right after a number is not a percentage so it gets removed like any other character
+///
+/// example: `100+5x0%` => `100+50%`
+pub fn filter_amount_expression(amount: &str) -> String {
+    let mut filtered_amount = String::new();
+
+    for c in amount.chars() {
+        let after_number = filtered_amount
+            .chars()
+            .last()
+            .map_or(false, |last| last.is_numeric() || last == '.');
+
+        if c.is_numeric()
+            || c == '.'
+            || c == '('
+            || c == ')'
+            || ['*', '/', '+', '-'].contains(&c)
+            || (c == '%' && after_number)
+        {
+            filtered_amount.push(c);
+        }
+    }
+
+    filtered_amount
+}
+
+/// Returns whether the amount is a calculation rather than a plain number
+pub fn is_amount_expression(amount: &str) -> bool {
+    amount.contains(AMOUNT_CALC_SYMBOLS)
+}
+
+/// Calculates a filtered amount expression without changing it. Grouped expressions need
+/// the parentheses aware calculation, the rest are flat
+///
+/// example: `1450/3+20` => `503.33`
+pub fn evaluate_amount_expression(expression: &str) -> Result<f64, NAType> {
+    let result = if expression.contains(['(', ')']) {
+        calculate_grouped(expression)
+    } else {
+        calculate_flat(expression)
+    };
+
+    result.ok_or(NAType::ParsingError(AType::Amount))
+}
+
+/// The decimals a calculated amount keeps before it is rounded to the minor units of the tx method
+pub const CALCULATION_DIGITS: usize = 6;
+
+/// Returns the result of the amount calculation while it is being typed. None if the amount is
+/// not a calculation and `…` if it can not be calculated yet, such as with a trailing symbol
+///
+/// return example: `= 503.33`
+pub fn get_amount_preview(
+    amount: &str,
+    minor_units: usize,
+    amount_format: &AmountFormat,
+) -> Option<String> {
+    if !is_amount_expression(amount) {
+        return None;
+    }
+
+    let result = amount_format
+        .normalize(amount)
+        .and_then(|normalized| expand_amount_multipliers(&normalized))
+        .map(|expanded| filter_amount_expression(&expanded))
+        // a symbol at the end is still being typed so the number after it is not known yet
+        .filter(|filtered| !filtered.ends_with(['*', '/', '+', '-', '(']))
+        .and_then(|filtered| evaluate_amount_expression(&filtered).ok());
+
+    let rounded = result.and_then(|value| {
+        Money::parse_rounded(
+            &format!("{value:.CALCULATION_DIGITS$}"),
+            minor_units,
+            amount_format.get_rounding(),
+        )
+    });
+
+    match rounded {
+        Some(value) => Some(format!(
+            "= {}",
+            amount_format.localize(&value.format(minor_units))
+        )),
+        None => Some("…".to_string()),
+    }
+}
+
+/// Calculates a flat amount expression without parentheses. Multiplications and divisions are
+/// applied first, then additions and subtractions from left to right.
+/// `X+Y%` and `X-Y%` add or remove Y percent of everything calculated before the percentage.
+/// Returns None if there is no number, a number can not be parsed, a step divides by zero
+/// or a percentage has nothing to apply on
+pub fn calculate_flat(expression: &str) -> Option<f64> {
+    let (numbers, symbols, percents) = tokenize_amount(expression)?;
+
+    // a percentage of nothing has no value
+    if percents[0] {
+        return None;
+    }
+
+    // example: 100+50-10%. 100+50 is calculated first, then 10% of 150 is removed from it
+    let mut current_numbers = vec![numbers[0]];
+    let mut current_symbols = Vec::new();
+
+    for (index, symbol) in symbols.iter().enumerate() {
+        let number = numbers[index + 1];
+
+        if !percents[index + 1] {
+            current_symbols.push(*symbol);
+            current_numbers.push(number);
+            continue;
+        }
+
+        let base = fold_amount(&current_numbers, &current_symbols)?;
+        let value = match symbol {
+            '+' => base + base * number / 100.0,
+            '-' => base - base * number / 100.0,
+            _ => return None,
+        };
+
+        current_numbers = vec![value];
+        current_symbols.clear();
+    }
+
+    fold_amount(&current_numbers, &current_symbols)
+}
+
+/// Calculates the numbers with the symbols between them. `*` and `/` are applied before `+` and `-`
+fn fold_amount(numbers: &[f64], symbols: &[char]) -> Option<f64> {
+    // first pass, * and / are applied on the number before them
+    let mut values = vec![numbers[0]];
+    let mut sum_symbols = Vec::new();
+
+    for (symbol, number) in symbols.iter().zip(numbers.iter().skip(1)) {
+        let last_value = values.last_mut()?;
+        match symbol {
+            '*' => *last_value *= number,
+            // a zero divisor has no usable result
+            '/' if *number == 0.0 => return None,
+            '/' => *last_value /= number,
+            _ => {
+                sum_symbols.push(*symbol);
+                values.push(*number);
+            }
+        }
+    }
+
+    // second pass, + and - are folded left to right
+    let mut result = values[0];
+    for (symbol, value) in sum_symbols.iter().zip(values.iter().skip(1)) {
+        if *symbol == '+' {
+            result += value;
+        } else {
+            result -= value;
+        }
+    }
+
+    if result.is_finite() {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// The numbers, symbols and percentage markers of a flat amount expression
+type AmountTokens = (Vec<f64>, Vec<char>, Vec<bool>);
+
+/// Splits a flat amount expression into its numbers, the symbols between them and whether
+/// each number is a percentage. Symbols without a number on both sides are dropped
+/// and a run of symbols keeps only the last one. A `%` not right after a number is rejected.
+///
+/// return example: `5*-3+10%` => `([5.0, 3.0, 10.0], ['-', '+'], [false, false, true])`
+fn tokenize_amount(expression: &str) -> Option<AmountTokens> {
+    let mut numbers: Vec<f64> = Vec::new();
+    let mut symbols = Vec::new();
+    let mut percents = Vec::new();
+    let mut pending_symbol = None;
+    let mut current_number = String::new();
+
+    for c in expression.chars() {
+        if c.is_numeric() || c == '.' {
+            if current_number.is_empty() && !numbers.is_empty() {
+                symbols.push(pending_symbol.take()?);
+            }
+            current_number.push(c);
+        } else if ['*', '/', '+', '-'].contains(&c) {
+            if !current_number.is_empty() {
+                numbers.push(current_number.parse().ok()?);
+                percents.push(false);
+                current_number.clear();
+            }
+            if !numbers.is_empty() {
+                pending_symbol = Some(c);
+            }
+        } else if c == '%' && !current_number.is_empty() {
+            numbers.push(current_number.parse().ok()?);
+            percents.push(true);
+            current_number.clear();
+        } else {
+            return None;
+        }
+    }
+
+    if !current_number.is_empty() {
+        numbers.push(current_number.parse().ok()?);
+        percents.push(false);
+    }
+
+    if numbers.is_empty() {
+        return None;
+    }
+
+    Some((numbers, symbols, percents))
+}
+
+/// Calculates an amount expression that may contain parentheses with the usual precedence.
+/// Returns None if the parentheses are unbalanced, the expression can not be parsed
+/// or a step divides by zero
+pub fn calculate_grouped(expression: &str) -> Option<f64> {
+    let mut chars = expression.chars().peekable();
+    let result = parse_sum(&mut chars)?;
+
+    // anything left means a closing parenthesis without an opening one or a broken expression
+    if chars.next().is_some() || !result.is_finite() {
+        return None;
+    }
+
+    Some(result)
+}
+
+/// Parses additions and subtractions of the expression
+fn parse_sum(chars: &mut Peekable<Chars>) -> Option<f64> {
+    let mut value = parse_product(chars)?;
+
+    while let Some(symbol) = chars.next_if(|c| *c == '+' || *c == '-') {
+        let next_value = parse_product(chars)?;
+        if symbol == '+' {
+            value += next_value;
+        } else {
+            value -= next_value;
+        }
+    }
+
+    Some(value)
+}
+
+/// Parses multiplications and divisions of the expression
+fn parse_product(chars: &mut Peekable<Chars>) -> Option<f64> {
+    let mut value = parse_factor(chars)?;
+
+    while let Some(symbol) = chars.next_if(|c| *c == '*' || *c == '/') {
+        let next_value = parse_factor(chars)?;
+        if symbol == '*' {
+            value *= next_value;
+        } else if next_value == 0.0 {
+            return None;
+        } else {
+            value /= next_value;
+        }
+    }
+
+    Some(value)
+}
+
+/// Parses a number, a signed value or a group inside parentheses
+fn parse_factor(chars: &mut Peekable<Chars>) -> Option<f64> {
+    match chars.peek()? {
+        '-' => {
+            chars.next();
+            Some(-parse_factor(chars)?)
+        }
+        '+' => {
+            chars.next();
+            parse_factor(chars)
+        }
+        '(' => {
+            chars.next();
+            let value = parse_sum(chars)?;
+            chars.next_if_eq(&')')?;
+            Some(value)
+        }
+        _ => {
+            let mut number = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_numeric() || *c == '.') {
+                number.push(c);
+            }
+            number.parse().ok()
+        }
+    }
+}
+
+/// Formats an amount with 2 decimal points and comma separators
+pub fn format_amount(amount: f64, minor_units: usize) -> String {
+    format!("{:.*}", minor_units, amount).separate_with_commas()
+}
+
+/// Returns the number of minor units the amounts of the tx method use. Defaults to 2
+pub fn get_minor_units(tx_method: &str, conn: &Connection) -> usize {
+    conn.query_row(
+        "SELECT units FROM minor_units WHERE tx_method = ?",
+        [tx_method],
+        |row| row.get(0),
+    )
+    .unwrap_or(2)
+}
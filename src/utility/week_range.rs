@@ -0,0 +1,92 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+/// A week of 7 days starting on the day of the week_start setting. Used by the weekly
+/// Home view and the weekly chart bars. A week can reach into the next month or year
+///
+/// start: `2024-06-10`, end: `2024-06-16`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct WeekRange {
+    start: NaiveDate,
+}
+
+impl WeekRange {
+    /// Returns the week the date is in with weeks starting on the given day
+    pub fn containing(date: NaiveDate, week_start: Weekday) -> Self {
+        let offset =
+            (7 + date.weekday().num_days_from_monday() - week_start.num_days_from_monday()) % 7;
+        WeekRange {
+            start: date - Duration::days(i64::from(offset)),
+        }
+    }
+
+    /// Returns every week that has at least one day in the month of the given year.
+    /// `month` is the 0 based month index
+    pub fn in_month(month: usize, year: i32, week_start: Weekday) -> Vec<Self> {
+        let first_day = NaiveDate::from_ymd_opt(year, month as u32 + 1, 1).unwrap();
+
+        let mut weeks = Vec::new();
+        let mut week = WeekRange::containing(first_day, week_start);
+
+        while week.overlaps_month(month, year) {
+            weeks.push(week);
+            week = week.next();
+        }
+        weeks
+    }
+
+    pub fn get_start(&self) -> NaiveDate {
+        self.start
+    }
+
+    pub fn get_end(&self) -> NaiveDate {
+        self.start + Duration::days(6)
+    }
+
+    pub fn next(&self) -> Self {
+        WeekRange {
+            start: self.start + Duration::days(7),
+        }
+    }
+
+    pub fn previous(&self) -> Self {
+        WeekRange {
+            start: self.start - Duration::days(7),
+        }
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        date >= self.start && date <= self.get_end()
+    }
+
+    /// Returns whether any day of the week is in the month of the given year.
+    /// `month` is the 0 based month index
+    pub fn overlaps_month(&self, month: usize, year: i32) -> bool {
+        [self.start, self.get_end()]
+            .iter()
+            .any(|date| date.month0() as usize == month && date.year() == year)
+    }
+
+    /// Returns the 0 based month index and the year of every month the week has a day in
+    ///
+    /// return example: `[(4, 2024), (5, 2024)]`
+    pub fn get_months(&self) -> Vec<(usize, i32)> {
+        let mut months = vec![(self.start.month0() as usize, self.start.year())];
+        let end = self.get_end();
+
+        if end.month() != self.start.month() {
+            months.push((end.month0() as usize, end.year()));
+        }
+        months
+    }
+
+    /// Returns the first and last day of the week
+    ///
+    /// return example: `"Jun 10 – Jun 16"`
+    pub fn get_label(&self) -> String {
+        format!(
+            "{} – {}",
+            self.start.format("%b %-d"),
+            self.get_end().format("%b %-d")
+        )
+    }
+}
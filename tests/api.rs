@@ -0,0 +1,357 @@
+extern crate rex_tui;
+use chrono::NaiveDate;
+use rex_tui::api::{Database, NewTx};
+use rex_tui::db::create_db;
+use rex_tui::outputs::{ApiError, CheckingError};
+use rex_tui::page_handler::TxTab;
+use rex_tui::tx_handler::{delete_tx, TxPickerData};
+use rex_tui::utility::{
+    get_last_balances, get_refund_ids, get_refund_lines, get_refund_original,
+    get_refundable_amount, Money,
+};
+use rusqlite::Connection;
+use std::fs;
+
+fn create_test_db(file_name: &str) {
+    if let Ok(metadata) = fs::metadata(file_name) {
+        if metadata.is_file() {
+            fs::remove_file(file_name).expect("Failed to delete existing file");
+        }
+    }
+
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(vec!["test1".to_string(), "test 2".to_string()], &mut conn).unwrap();
+    conn.close().unwrap();
+}
+
+#[test]
+fn check_api_end_to_end() {
+    let file_name = "check_api_end_to_end.sqlite";
+    create_test_db(file_name);
+
+    let mut db = Database::open(file_name).unwrap();
+
+    assert_eq!(db.methods(), vec!["test1", "test 2"]);
+
+    let salary_id = db
+        .add_transaction(NewTx {
+            date: "2023-05-01".to_string(),
+            details: "Salary".to_string(),
+            from_method: "test1".to_string(),
+            amount: "1000".to_string(),
+            tx_type: "income".to_string(),
+            tags: "Salary".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+    let grocery_id = db
+        .add_transaction(NewTx {
+            date: "2023-05-12".to_string(),
+            details: "Groceries".to_string(),
+            from_method: "TEST1".to_string(),
+            amount: "100 + 25.5".to_string(),
+            tx_type: "e".to_string(),
+            tags: "Food, Home".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+    let transfer_id = db
+        .add_transaction(NewTx {
+            date: "2023-05-20".to_string(),
+            details: "Savings".to_string(),
+            from_method: "test1".to_string(),
+            to_method: "test 2".to_string(),
+            amount: "200".to_string(),
+            tx_type: "t".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+    db.add_transaction(NewTx {
+        date: "2023-06-02".to_string(),
+        details: "Rent".to_string(),
+        from_method: "test 2".to_string(),
+        amount: "50".to_string(),
+        tx_type: "Expense".to_string(),
+        tags: "Home".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    assert_eq!((salary_id, grocery_id, transfer_id), (1, 2, 3));
+
+    let invalid = db.add_transaction(NewTx {
+        date: "2023-05-40".to_string(),
+        from_method: "unknown".to_string(),
+        amount: "0".to_string(),
+        tx_type: "Expense".to_string(),
+        ..Default::default()
+    });
+
+    let invalid_fields = match invalid {
+        Err(ApiError::InvalidTx(errors)) => errors.get_fields(),
+        _ => panic!("invalid transaction was accepted"),
+    };
+    assert_eq!(
+        invalid_fields,
+        vec![TxTab::Date, TxTab::FromMethod, TxTab::Amount]
+    );
+
+    let start = NaiveDate::from_ymd_opt(2023, 5, 1).unwrap();
+    let end = NaiveDate::from_ymd_opt(2023, 5, 31).unwrap();
+
+    let txs = db.transactions_in(start..=end).unwrap();
+    let summary = db.summary_for(start..=end).unwrap();
+    let tags = db.tags();
+
+    drop(db);
+
+    let conn = Connection::open(file_name).unwrap();
+    let balances = get_last_balances(&conn);
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(txs.len(), 3);
+    assert_eq!(txs[1].tx_method, "test1");
+    assert_eq!(txs[1].amount, "125.50");
+    assert_eq!(txs[1].tx_type, "Expense");
+    assert_eq!(txs[1].tags, vec!["Food", "Home"]);
+    assert_eq!(txs[2].tx_method, "test1 to test 2");
+    assert_eq!(txs[2].tags, vec!["Unknown"]);
+
+    assert_eq!(summary.total_income, 1000.0);
+    assert_eq!(summary.total_expense, 125.5);
+    assert_eq!(summary.net, 874.5);
+    assert_eq!(summary.tx_count, 3);
+    assert_eq!(summary.tags.len(), 3);
+    assert_eq!(summary.tags[0].tag, "Food");
+    assert_eq!(summary.tags[0].expense, 125.5);
+
+    assert_eq!(tags, vec!["Food", "Home", "Salary", "Unknown"]);
+    assert_eq!(balances, vec!["674.5", "150"]);
+}
+
+#[test]
+fn check_api_open_errors() {
+    let file_name = "check_api_open_errors.sqlite";
+
+    let conn = Connection::open(file_name).unwrap();
+    conn.close().unwrap();
+
+    let status = Database::open(file_name);
+    fs::remove_file(file_name).unwrap();
+
+    assert!(matches!(status, Err(ApiError::NotRexDatabase)));
+}
+
+#[test]
+fn check_api_tag_normalization() {
+    let file_name = "check_api_tag_normalization.sqlite";
+    create_test_db(file_name);
+
+    let mut db = Database::open(file_name).unwrap();
+
+    for tags in ["Food Court", "food  court, Car", " CAR "] {
+        db.add_transaction(NewTx {
+            date: "2023-05-01".to_string(),
+            details: "Lunch".to_string(),
+            from_method: "test1".to_string(),
+            amount: "10".to_string(),
+            tx_type: "Expense".to_string(),
+            tags: tags.to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+    }
+
+    let start = NaiveDate::from_ymd_opt(2023, 5, 1).unwrap();
+    let txs = db.transactions_in(start..=start).unwrap();
+
+    drop(db);
+    fs::remove_file(file_name).unwrap();
+
+    let tags = txs.iter().map(|tx| tx.tags.clone()).collect::<Vec<_>>();
+
+    assert_eq!(
+        tags,
+        vec![
+            vec!["Food Court".to_string()],
+            vec!["Food Court".to_string(), "Car".to_string()],
+            vec!["Car".to_string()],
+        ]
+    );
+}
+
+#[test]
+fn check_api_refund() {
+    let file_name = "check_api_refund.sqlite";
+    create_test_db(file_name);
+
+    let mut db = Database::open(file_name).unwrap();
+
+    let shoes_id = db
+        .add_transaction(NewTx {
+            date: "2023-05-01".to_string(),
+            details: "Shoes".to_string(),
+            from_method: "test1".to_string(),
+            amount: "50".to_string(),
+            tx_type: "Expense".to_string(),
+            tags: "Clothes".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+    db.add_transaction(NewTx {
+        date: "2023-05-02".to_string(),
+        details: "Lunch".to_string(),
+        from_method: "test1".to_string(),
+        amount: "10".to_string(),
+        tx_type: "Expense".to_string(),
+        tags: "Food".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let refund = NewTx {
+        date: "2023-05-03".to_string(),
+        details: "Refund: Shoes".to_string(),
+        from_method: "test1".to_string(),
+        amount: "20".to_string(),
+        tx_type: "r".to_string(),
+        tags: "Clothes".to_string(),
+        refund_of: Some(shoes_id),
+        ..Default::default()
+    };
+
+    let refund_id = db.add_transaction(refund.clone()).unwrap();
+
+    let unlinked = db.add_transaction(NewTx {
+        refund_of: None,
+        ..refund.clone()
+    });
+    let too_big = db.add_transaction(NewTx {
+        amount: "40".to_string(),
+        ..refund
+    });
+
+    let start = NaiveDate::from_ymd_opt(2023, 5, 1).unwrap();
+    let end = NaiveDate::from_ymd_opt(2023, 5, 31).unwrap();
+    let summary = db.summary_for(start..=end).unwrap();
+
+    drop(db);
+
+    let conn = Connection::open(file_name).unwrap();
+    let balances = get_last_balances(&conn);
+    let refund_ids = get_refund_ids(shoes_id, &conn);
+    let refund_original = get_refund_original(refund_id, &conn);
+    let refundable = get_refundable_amount(shoes_id, None, &conn);
+    let refundable_editing = get_refundable_amount(shoes_id, Some(refund_id), &conn);
+    let expense_lines = get_refund_lines(shoes_id, &conn);
+    let refund_lines = get_refund_lines(refund_id, &conn);
+
+    let mut picker = TxPickerData::new_expenses(&conn);
+    let all_expenses = picker.get_matches().len();
+    picker.edit_query(Some('s'));
+    picker.edit_query(Some('H'));
+    let picked = picker.get_selected();
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    let unlinked_errors = match unlinked {
+        Err(ApiError::InvalidTx(errors)) => errors,
+        _ => panic!("refund without an expense was accepted"),
+    };
+    let too_big_errors = match too_big {
+        Err(ApiError::InvalidTx(errors)) => errors,
+        _ => panic!("refund bigger than the expense was accepted"),
+    };
+
+    assert_eq!(
+        unlinked_errors.get_errors(),
+        &vec![(TxTab::TxType, CheckingError::NoRefundOriginal)]
+    );
+    assert_eq!(
+        too_big_errors.get_errors(),
+        &vec![(
+            TxTab::Amount,
+            CheckingError::RefundTooBig("30.00".to_string())
+        )]
+    );
+
+    assert_eq!(balances, vec!["-40", "0"]);
+    assert_eq!(refund_ids, vec![refund_id]);
+    assert_eq!(refund_original, Some(shoes_id));
+    assert_eq!(refundable, Money::parse("30"));
+    assert_eq!(refundable_editing, Money::parse("50"));
+
+    assert_eq!(
+        expense_lines,
+        vec![
+            "Refunded by: 2023-05-03 · Refund: Shoes · 20.00",
+            "Left to refund: 30.00"
+        ]
+    );
+    assert_eq!(refund_lines, vec!["Refund of: 2023-05-01 · Shoes · 50.00"]);
+
+    assert_eq!(summary.total_expense, 40.0);
+    assert_eq!(summary.tags[0].tag, "Clothes");
+    assert_eq!(summary.tags[0].expense, 30.0);
+
+    assert_eq!(all_expenses, 2);
+    assert_eq!(picked, Some(shoes_id));
+}
+
+#[test]
+fn check_refund_deletion() {
+    let file_name = "check_refund_deletion.sqlite";
+    create_test_db(file_name);
+
+    let mut db = Database::open(file_name).unwrap();
+
+    let shoes_id = db
+        .add_transaction(NewTx {
+            date: "2023-05-01".to_string(),
+            details: "Shoes".to_string(),
+            from_method: "test1".to_string(),
+            amount: "50".to_string(),
+            tx_type: "Expense".to_string(),
+            tags: "Clothes".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+    let refund_id = db
+        .add_transaction(NewTx {
+            date: "2023-05-03".to_string(),
+            details: "Refund: Shoes".to_string(),
+            from_method: "test1".to_string(),
+            amount: "20".to_string(),
+            tx_type: "Refund".to_string(),
+            tags: "Clothes".to_string(),
+            refund_of: Some(shoes_id),
+            ..Default::default()
+        })
+        .unwrap();
+
+    drop(db);
+
+    let mut conn = Connection::open(file_name).unwrap();
+    let refunds_before = get_refund_ids(shoes_id, &conn).len();
+
+    delete_tx(refund_id as usize, &mut conn).unwrap();
+
+    let refunds_after = get_refund_ids(shoes_id, &conn).len();
+    let refund_original = get_refund_original(refund_id, &conn);
+    let refundable = get_refundable_amount(shoes_id, None, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(refunds_before, 1);
+    assert_eq!(refunds_after, 0);
+    assert_eq!(refund_original, None);
+    assert_eq!(refundable, Money::parse("50"));
+}
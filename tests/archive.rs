@@ -0,0 +1,199 @@
+extern crate rex_tui;
+use rex_tui::db::*;
+use rex_tui::tx_handler::add_tx;
+use rex_tui::utility::*;
+use rusqlite::Connection;
+use std::fs;
+
+fn create_test_db(file_name: &str) -> Connection {
+    if let Ok(metadata) = fs::metadata(file_name) {
+        if metadata.is_file() {
+            fs::remove_file(file_name).expect("Failed to delete existing file");
+        }
+    }
+
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(vec!["test1".to_string(), "test 2".to_string()], &mut conn).unwrap();
+    conn
+}
+
+fn remove_file(file_name: &str) {
+    if fs::metadata(file_name).is_ok() {
+        fs::remove_file(file_name).unwrap();
+    }
+}
+
+#[test]
+fn check_archiving_txs() {
+    let file_name = "archive_txs.sqlite";
+    let archive_name = "archive_txs_archive.sqlite";
+    remove_file(archive_name);
+    let mut conn = create_test_db(file_name);
+
+    add_tx(
+        "2022-07-19",
+        "Testing transaction",
+        "test1",
+        "100.00",
+        "Income",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-08-19",
+        "Testing transaction",
+        "test 2",
+        "50.00",
+        "Income",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-09-10",
+        "Testing transaction",
+        "test1",
+        "20.00",
+        "Expense",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    let last_balances = get_last_balances(&conn);
+    let september_txs = get_all_txs(&conn, 8, 0);
+
+    let archived = archive_txs("2022-09-15", archive_name, &mut conn).unwrap();
+
+    let archived_last_balances = get_last_balances(&conn);
+    let archived_july_txs = get_all_txs(&conn, 6, 0);
+    let archived_september_txs = get_all_txs(&conn, 8, 0);
+    let archive_attached = is_archive_attached(&conn);
+
+    let restored = unarchive_txs(archive_name, &mut conn).unwrap();
+    let restored_july_txs = get_all_txs(&conn, 6, 0);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+    fs::remove_file(archive_name).unwrap();
+
+    assert_eq!(archived, 2);
+    assert!(!archive_attached);
+    assert_eq!(last_balances, archived_last_balances);
+    assert!(archived_july_txs.0.is_empty());
+    assert_eq!(september_txs, archived_september_txs);
+    assert_eq!(restored, 2);
+    assert_eq!(restored_july_txs.0.len(), 1);
+}
+
+#[test]
+fn check_archive_search() {
+    let file_name = "archive_search.sqlite";
+    let archive_name = "archive_search_archive.sqlite";
+    remove_file(archive_name);
+    let mut conn = create_test_db(file_name);
+
+    add_tx(
+        "2022-07-19",
+        "Old transaction",
+        "test1",
+        "100.00",
+        "Income",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-09-19",
+        "New transaction",
+        "test1",
+        "100.00",
+        "Income",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    archive_txs("2022-08-01", archive_name, &mut conn).unwrap();
+
+    let without_archive = get_search_data("", "transaction", "", "", "", "", "", &conn);
+
+    attach_archive(archive_name, &conn).unwrap();
+    let with_archive = get_search_data("", "transaction", "", "", "", "", "", &conn);
+    detach_archive(&conn).unwrap();
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+    fs::remove_file(archive_name).unwrap();
+
+    assert_eq!(without_archive.0.len(), 1);
+    assert_eq!(without_archive.2, vec![false]);
+    assert_eq!(with_archive.0.len(), 2);
+    assert_eq!(with_archive.1, vec!["1".to_string(), "2".to_string()]);
+    assert_eq!(with_archive.2, vec![true, false]);
+}
+
+#[test]
+fn check_archive_method_rename() {
+    let file_name = "archive_rename.sqlite";
+    let archive_name = "archive_rename_archive.sqlite";
+    remove_file(archive_name);
+    let mut conn = create_test_db(file_name);
+
+    add_tx(
+        "2022-07-19",
+        "Old transaction",
+        "test1",
+        "100.00",
+        "Income",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-09-19",
+        "New transaction",
+        "test1",
+        "50.00",
+        "Income",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    let archived_july_txs = get_all_txs(&conn, 6, 0);
+
+    archive_txs("2022-08-01", archive_name, &mut conn).unwrap();
+
+    let archive_years = get_archive_year_bounds(archive_name, &conn);
+    let missing_archive_years = get_archive_year_bounds("archive_rename_missing.sqlite", &conn);
+
+    rename_column("test1", "test one", archive_name, &mut conn).unwrap();
+    let archive_attached = is_archive_attached(&conn);
+
+    unarchive_txs(archive_name, &mut conn).unwrap();
+    let restored_july_txs = get_all_txs(&conn, 6, 0);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+    fs::remove_file(archive_name).unwrap();
+
+    assert_eq!(archive_years, Some((2022, 2022)));
+    assert_eq!(missing_archive_years, None);
+    assert!(!archive_attached);
+    assert_eq!(restored_july_txs.0.len(), 1);
+    assert_eq!(restored_july_txs.0[0][2], "test one");
+    assert_eq!(archived_july_txs.1, restored_july_txs.1);
+}
@@ -1,6 +1,7 @@
 extern crate rex_tui;
 use chrono::{naive::NaiveDate, Duration};
-use rex_tui::db::create_db;
+use rex_tui::db::{create_db, get_date_range, set_date_range, DateRange};
+use rex_tui::outputs::{NAType, VerifyingOutput};
 use rex_tui::tx_handler::*;
 use rex_tui::utility::*;
 use rusqlite::{Connection, Result as sqlResult};
@@ -123,7 +124,7 @@ fn check_last_balances_3() {
 #[test]
 fn check_last_month_balance_1() {
     let file_name = "last_month_balance_1.sqlite";
-    let conn = create_test_db(&file_name);
+    let conn = create_test_db(file_name);
     let tx_methods = get_all_tx_methods(&conn);
 
     let data = get_last_time_balance(6, 1, &tx_methods, &conn);
@@ -138,7 +139,7 @@ fn check_last_month_balance_1() {
 #[test]
 fn check_last_balance_id() {
     let file_name = "last_balance_id.sqlite";
-    let conn = create_test_db(&file_name);
+    let conn = create_test_db(file_name);
 
     let data = get_last_balance_id(&conn);
     let expected_data: sqlResult<i32> = Ok(193);
@@ -152,7 +153,7 @@ fn check_last_balance_id() {
 #[test]
 fn check_last_month_balance_2() {
     let file_name = "last_month_balance_2.sqlite";
-    let mut conn = create_test_db(&file_name);
+    let mut conn = create_test_db(file_name);
     let tx_methods = get_all_tx_methods(&conn);
 
     add_tx(
@@ -278,7 +279,7 @@ fn check_last_month_balance_2() {
 #[ignore]
 fn check_balance_all_day() {
     let file_name = "check_balance_all_day.sqlite";
-    let mut conn = create_test_db(&file_name);
+    let mut conn = create_test_db(file_name);
     let tx_methods = get_all_tx_methods(&conn);
 
     let mut current_date = NaiveDate::parse_from_str("2022-01-01", "%Y-%m-%d").unwrap();
@@ -331,7 +332,7 @@ fn check_balance_all_day() {
 
     let expected_data_1 = vec!["0".to_string(), "0".to_string()];
     let mut expected_data_2 = HashMap::new();
-    for (i, _x) in &data_2 {
+    for i in data_2.keys() {
         expected_data_2.insert(i.to_string(), 0.0);
     }
 
@@ -341,3 +342,281 @@ fn check_balance_all_day() {
     assert_eq!(data_1, expected_data_1);
     assert_eq!(data_2, expected_data_2);
 }
+
+#[test]
+fn check_date_range() {
+    let file_name = "date_range.sqlite";
+    let mut conn = create_test_db(file_name);
+    let tx_methods = get_all_tx_methods(&conn);
+
+    let default_range = get_date_range(&conn);
+
+    add_tx(
+        "2023-03-10",
+        "Testing transaction",
+        "test1",
+        "100.00",
+        "Income",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    set_date_range(&DateRange::new(2015, 2040), &mut conn).unwrap();
+
+    let date_range = get_date_range(&conn);
+    let last_balance_id = get_last_balance_id(&conn).unwrap();
+
+    // 2023 is now the 9th year of the range. Month index 3 gives the balance at the end of March
+    let moved_balance = get_last_time_balance(3, 8, &tx_methods, &conn);
+
+    add_tx(
+        "2016-01-05",
+        "Testing transaction",
+        "test1",
+        "20.00",
+        "Expense",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    let old_balance = get_last_time_balance(1, 1, &tx_methods, &conn);
+    let last_balances = get_last_balances(&conn);
+
+    let mut old_tx = TxData::custom(
+        "05-05-2014",
+        "Testing transaction",
+        "test1",
+        "",
+        "10.00",
+        "Expense",
+        "Unknown",
+        0,
+    );
+    let old_tx_status = old_tx.check_date(&conn);
+    let old_tx_date = old_tx.get_all_texts()[0].to_string();
+
+    delete_tx(2, &mut conn).unwrap();
+    let deleted_balances = get_last_balances(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(default_range.start_year, 2022);
+    assert!(default_range.end_year >= 2037);
+    assert_eq!(date_range, DateRange::new(2015, 2040));
+    assert_eq!(date_range.years().len(), 26);
+    assert_eq!(last_balance_id, 26 * 12 + 1);
+
+    assert_eq!(
+        moved_balance,
+        HashMap::from([("test 2".to_string(), 0.0), ("test1".to_string(), 100.0)])
+    );
+    assert_eq!(
+        old_balance,
+        HashMap::from([("test 2".to_string(), 0.0), ("test1".to_string(), -20.0)])
+    );
+    assert_eq!(last_balances[0].parse::<f64>().unwrap(), 80.0);
+
+    assert_eq!(
+        old_tx_status,
+        VerifyingOutput::NotAccepted(NAType::YearTooBig(2015, 2040))
+    );
+    assert_eq!(old_tx_date, "2015-05-05");
+    assert_eq!(deleted_balances[0].parse::<f64>().unwrap(), 100.0);
+}
+
+#[test]
+fn check_balance_on_date() {
+    let file_name = "check_balance_on_date.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    let txs = [
+        ("2022-03-10", "test1", "100.00", "Income"),
+        ("2022-05-02", "test1", "20.00", "Expense"),
+        ("2022-05-15", "test 2", "50.00", "Income"),
+        ("2022-05-20", "test1 to test 2", "30.00", "Transfer"),
+    ];
+
+    for (date, tx_method, amount, tx_type) in txs {
+        add_tx(
+            date,
+            "Testing transaction",
+            tx_method,
+            amount,
+            tx_type,
+            "Unknown",
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let before_first = get_all_balances_on_date("2022-01-05", &conn);
+    let first_day = get_all_balances_on_date("2022-03-10", &conn);
+    let mid_month = get_all_balances_on_date("2022-05-15", &conn);
+    let month_end = get_all_balances_on_date("2022-05-31", &conn);
+
+    let test1_mid_month = get_balance_on_date("test1", "2022-05-15", &conn);
+    let unknown_method = get_balance_on_date("test3", "2022-05-15", &conn);
+
+    // the chart balances after the last transaction of a date are the balances on that date
+    let (_, chart_balances, _) = get_all_txs(&conn, 4, 0);
+
+    let clock = FixedClock::new(NaiveDate::from_ymd_opt(2022, 6, 1).unwrap());
+    let mut balance_data = BalanceDateData::new();
+    for c in "2022-05-15".chars() {
+        balance_data.edit_date(Some(c));
+    }
+    balance_data.load_balances_with_clock(&clock, &conn);
+
+    let mut invalid_data = BalanceDateData::new();
+    for c in "2022-13-01".chars() {
+        invalid_data.edit_date(Some(c));
+    }
+    invalid_data.load_balances(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(before_first["test1"], 0.0);
+    assert_eq!(before_first["test 2"], 0.0);
+
+    assert_eq!(first_day["test1"], 100.0);
+    assert_eq!(first_day["test 2"], 0.0);
+
+    assert_eq!(mid_month["test1"], 80.0);
+    assert_eq!(mid_month["test 2"], 50.0);
+
+    assert_eq!(month_end["test1"], 50.0);
+    assert_eq!(month_end["test 2"], 80.0);
+
+    assert_eq!(test1_mid_month, 80.0);
+    assert_eq!(unknown_method, 0.0);
+
+    assert_eq!(chart_balances[1], vec!["80.00", "50.00"]);
+    assert_eq!(chart_balances[2], vec!["50.00", "80.00"]);
+
+    assert_eq!(
+        balance_data.get_balances(),
+        &vec![
+            vec![
+                "test1".to_string(),
+                "80.00".to_string(),
+                "-30.00".to_string()
+            ],
+            vec![
+                "test 2".to_string(),
+                "50.00".to_string(),
+                "+30.00".to_string()
+            ],
+        ]
+    );
+    assert_eq!(balance_data.get_total(), "130.00");
+    assert_eq!(balance_data.get_total_change(), "0.00");
+    assert_eq!(
+        balance_data.get_summary_line(),
+        "Balance on 2022-05-15: test1 80.00, test 2 50.00, Total 130.00"
+    );
+
+    assert!(invalid_data.get_balances().is_empty());
+    assert!(!invalid_data.get_status().is_empty());
+    assert_eq!(invalid_data.get_summary_line(), "");
+}
+
+#[test]
+fn check_money_arithmetic() {
+    let amount = Money::parse("0.1").unwrap() + Money::parse("0.2").unwrap();
+
+    assert_eq!(amount, Money::parse("0.3").unwrap());
+    assert_eq!(amount.to_string(), "0.30");
+    assert_eq!(
+        Money::parse("12.3456").unwrap().to_stored_string(),
+        "12.345"
+    );
+    assert_eq!(Money::parse("-5").unwrap().format(0), "-5");
+    assert_eq!(Money::parse("1.005").unwrap().format(2), "1.01");
+    assert_eq!(Money::parse("-0.001").unwrap().format(2), "0.00");
+    assert_eq!(
+        Money::parse("1.239").unwrap().truncate(2),
+        Money::from_minor(1230)
+    );
+    assert_eq!(
+        Money::from_whole(3) * 2 - Money::parse("0.5").unwrap(),
+        Money::from_minor(5500)
+    );
+    assert_eq!(Money::from_f64(0.1 + 0.2), Money::from_minor(300));
+
+    assert_eq!(Money::parse("abc"), None);
+    assert_eq!(Money::parse("1.2.3"), None);
+    assert_eq!(Money::parse("."), None);
+    assert_eq!(Money::parse("99999999999999999999"), None);
+
+    assert_eq!(Money::parse_rounded("", 2, RoundingMode::HalfUp), None);
+    assert_eq!(Money::parse_rounded("-", 2, RoundingMode::HalfUp), None);
+    assert_eq!(Money::parse_rounded(".", 2, RoundingMode::HalfUp), None);
+    assert_eq!(
+        Money::parse_rounded(".125", 2, RoundingMode::HalfUp),
+        Some(Money::from_minor(130))
+    );
+    assert_eq!(
+        Money::parse_rounded("-5.", 2, RoundingMode::HalfUp),
+        Some(Money::from_minor(-5000))
+    );
+}
+
+#[test]
+fn check_repeated_tx_balance() {
+    let file_name = "repeated_tx_balance.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    add_tx(
+        "2022-07-19",
+        "Testing transaction",
+        "test1",
+        "100.10",
+        "Income",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    let tx_methods = get_all_tx_methods(&conn);
+    let last_balances = get_last_balances(&conn);
+    let month_balances = get_all_balances_on_date("2022-07-31", &conn);
+
+    for _ in 0..1000 {
+        add_tx(
+            "2022-07-20",
+            "Testing transaction",
+            "test1 to test 2",
+            "0.10",
+            "Transfer",
+            "Unknown",
+            None,
+            &mut conn,
+        )
+        .unwrap();
+
+        let tx_id = get_last_tx_id(&conn).unwrap();
+        delete_tx(tx_id as usize, &mut conn).unwrap();
+    }
+
+    let new_last_balances = get_last_balances(&conn);
+    let new_month_balances = get_all_balances_on_date("2022-07-31", &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(new_last_balances, last_balances);
+    for method in &tx_methods {
+        assert_eq!(
+            new_month_balances[method].to_bits(),
+            month_balances[method].to_bits()
+        );
+    }
+}
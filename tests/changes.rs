@@ -52,7 +52,7 @@ fn check_getting_all_changes() {
 #[test]
 fn check_getting_all_changes_2() {
     let file_name = "getting_changes_2.sqlite";
-    let mut conn = create_test_db(&file_name);
+    let mut conn = create_test_db(file_name);
 
     add_tx(
         "2022-07-19",
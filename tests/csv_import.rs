@@ -0,0 +1,237 @@
+extern crate rex_tui;
+use rex_tui::db::*;
+use rex_tui::page_handler::BackgroundTask;
+use rex_tui::tx_handler::*;
+use rex_tui::utility::*;
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::fs;
+
+const FIREFLY_EXPORT: &str = "tests/fixtures/firefly_export.csv";
+
+fn create_test_db(file_name: &str) -> Connection {
+    if let Ok(metadata) = fs::metadata(file_name) {
+        if metadata.is_file() {
+            fs::remove_file(file_name).expect("Failed to delete existing file");
+        }
+    }
+
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(vec!["Cash".to_string(), "test 2".to_string()], &mut conn).unwrap();
+    conn
+}
+
+fn run_import_task(
+    file_name: &str,
+    new_methods: Vec<String>,
+    txs: Vec<(usize, rex_tui::api::NewTx)>,
+    problems: Vec<CsvProblem>,
+) -> Result<String, String> {
+    let mut task = BackgroundTask::start("CSV import", file_name, move |conn, progress| {
+        import_csv_task(
+            ImportProfile::FireflyIII,
+            new_methods,
+            &txs,
+            problems,
+            progress,
+            conn,
+        )
+    });
+
+    loop {
+        if let Some(result) = task.poll() {
+            break result;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn check_csv_parsing() {
+    let records = parse_csv(
+        "\u{feff}a,b,c\r\n1,\"two, three\",\"say \"\"hi\"\"\"\r\n\n\"multi\nline\",,x\n4,5,6",
+    )
+    .unwrap();
+
+    assert_eq!(
+        records,
+        vec![
+            (1, vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+            (
+                2,
+                vec![
+                    "1".to_string(),
+                    "two, three".to_string(),
+                    "say \"hi\"".to_string()
+                ]
+            ),
+            (
+                4,
+                vec!["multi\nline".to_string(), "".to_string(), "x".to_string()]
+            ),
+            (6, vec!["4".to_string(), "5".to_string(), "6".to_string()]),
+        ]
+    );
+
+    let unclosed = parse_csv("a,b\n1,\"open\n2,3\n");
+    assert_eq!(
+        unclosed,
+        Err(CsvProblem::new(2, "The quoted field is never closed"))
+    );
+}
+
+#[test]
+fn check_firefly_export_reading() {
+    let text = fs::read_to_string(FIREFLY_EXPORT).unwrap();
+    let (txs, problems) = read_csv_export(&text, ImportProfile::FireflyIII).unwrap();
+
+    assert_eq!(txs.len(), 4);
+    assert_eq!(
+        txs[1],
+        CsvTx {
+            line: 4,
+            external_id: "firefly-203".to_string(),
+            date: "2023-01-05".to_string(),
+            details: "Weekly groceries".to_string(),
+            from_account: "Checking Account".to_string(),
+            to_account: String::new(),
+            amount: Money::parse("45.20").unwrap(),
+            tx_type: "Expense".to_string(),
+            tags: "Groceries, food, Weekly".to_string(),
+        }
+    );
+
+    assert_eq!(txs[0].tx_type, "Income");
+    assert_eq!(txs[0].from_account, "Checking Account");
+    assert_eq!(txs[2].line, 5);
+    assert_eq!(txs[2].details, "Dinner, \"Luigi's\"");
+    assert_eq!(txs[3].tx_type, "Transfer");
+    assert_eq!(txs[3].to_account, "Savings Account");
+
+    assert_eq!(
+        problems,
+        vec![
+            CsvProblem::new(2, "Transaction type Opening balance is not imported"),
+            CsvProblem::new(8, "Could not read the amount twelve"),
+        ]
+    );
+
+    assert_eq!(
+        get_csv_accounts(&txs),
+        vec![
+            "Checking Account".to_string(),
+            "cash".to_string(),
+            "Savings Account".to_string()
+        ]
+    );
+
+    assert_eq!(
+        merge_csv_tags(&["", " Food ", "food", "Travel"]),
+        "Food, Travel"
+    );
+    assert_eq!(
+        parse_csv_date("2023-01-05T00:00:00+01:00"),
+        Some("2023-01-05".to_string())
+    );
+    assert_eq!(parse_csv_date("05/01/2023"), None);
+    assert!(is_valid_new_method("Savings Account"));
+    assert!(!is_valid_new_method("Stock portfolio"));
+
+    let missing = read_csv_export("date,amount\n2023-01-05,10", ImportProfile::FireflyIII);
+    assert_eq!(
+        missing,
+        Err("The file has no journal_id column. Is it a Firefly III export?".to_string())
+    );
+}
+
+#[test]
+fn check_firefly_import() {
+    let file_name = "check_firefly_import.sqlite";
+    let conn = create_test_db(file_name);
+
+    let text = fs::read_to_string(FIREFLY_EXPORT).unwrap();
+    let (txs, problems) = read_csv_export(&text, ImportProfile::FireflyIII).unwrap();
+
+    let new_methods = vec![
+        "Checking Account".to_string(),
+        "Savings Account".to_string(),
+    ];
+
+    let mut method_map = HashMap::new();
+    method_map.insert(
+        "Checking Account".to_string(),
+        "Checking Account".to_string(),
+    );
+    method_map.insert("Savings Account".to_string(), "Savings Account".to_string());
+
+    // cash is left unmapped first so its row is reported
+    let (new_txs, tx_problems) = csv_to_txs(&txs, &method_map);
+    assert_eq!(new_txs.len(), 3);
+    assert_eq!(
+        tx_problems,
+        vec![CsvProblem::new(
+            5,
+            "Account cash is not mapped to a tx method"
+        )]
+    );
+
+    method_map.insert("cash".to_string(), "Cash".to_string());
+    let (new_txs, _) = csv_to_txs(&txs, &method_map);
+
+    let first_report = run_import_task(file_name, new_methods, new_txs.clone(), problems);
+    let groceries = get_tx_by_id(2, &conn);
+    let dinner = get_tx_by_id(3, &conn);
+    let transfer = get_tx_by_id(4, &conn);
+    // read after a query so the connection has loaded the new columns, as the app does on reload
+    let tx_methods = get_all_tx_methods(&conn);
+
+    // importing the same export again adds nothing
+    let second_report = run_import_task(file_name, Vec::new(), new_txs, Vec::new());
+    let last_tx_id = get_last_tx_id(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(
+        first_report,
+        Ok("Imported 4 transactions from the Firefly III export.
+0 transactions were already imported and skipped.
+Created tx methods: Checking Account, Savings Account
+
+2 lines or transactions were not imported:
+
+Line 2: Transaction type Opening balance is not imported
+Line 8: Could not read the amount twelve"
+            .to_string())
+    );
+    assert_eq!(
+        second_report,
+        Ok("Imported 0 transactions from the Firefly III export.
+4 transactions were already imported and skipped."
+            .to_string())
+    );
+    assert_eq!(last_tx_id, Ok(4));
+
+    assert_eq!(
+        tx_methods,
+        vec![
+            "Cash".to_string(),
+            "test 2".to_string(),
+            "Checking Account".to_string(),
+            "Savings Account".to_string()
+        ]
+    );
+    assert_eq!(
+        groceries,
+        Some(vec![
+            "2023-01-05".to_string(),
+            "Weekly groceries".to_string(),
+            "Checking Account".to_string(),
+            "45.20".to_string(),
+            "Expense".to_string(),
+            "Groceries, food, Weekly".to_string(),
+        ])
+    );
+    assert_eq!(dinner.unwrap()[2], "Cash");
+    assert_eq!(transfer.unwrap()[2], "Checking Account to Savings Account");
+}
@@ -1,9 +1,17 @@
 extern crate rex_tui;
-use rex_tui::db::{add_new_tx_methods, create_db, rename_column, reposition_column};
+use chrono::NaiveDate;
+use rex_tui::db::{
+    add_new_tx_methods, check_db_health, create_db, find_newest_backup, get_bool_setting,
+    get_f64_setting, get_list_setting, get_moved_aside_path, get_recovery_actions, get_setting,
+    get_usize_setting, is_valid_setting, rename_column, reposition_column, set_bool_setting,
+    set_list_setting, set_setting, set_usize_setting, DbHealth, RecoveryAction,
+};
+use rex_tui::page_handler::{IndexedData, SortingType};
 use rex_tui::tx_handler::add_tx;
 use rex_tui::utility::{get_all_tx_methods, get_last_balances};
 use rusqlite::Connection;
 use std::fs;
+use std::path::{Path, PathBuf};
 
 fn create_test_db(file_name: &str) -> Connection {
     if let Ok(metadata) = fs::metadata(file_name) {
@@ -62,7 +70,7 @@ fn check_renaming_columns() {
     let file_name = "test_db_3.sqlite";
     let mut conn = create_test_db(file_name);
 
-    let status = rename_column("test 2", "testing", &mut conn);
+    let status = rename_column("test 2", "testing", "test_db_3_archive.sqlite", &mut conn);
     let tx_methods = get_all_tx_methods(&conn);
     let expected_tx_methods = vec!["test1".to_string(), "testing".to_string()];
 
@@ -106,3 +114,237 @@ fn check_repositioning_columns() {
     assert_eq!(old_last_balances, vec!["159", "0"]);
     assert_eq!(last_balances, vec!["0", "159"]);
 }
+
+#[test]
+fn check_settings() {
+    let file_name = "settings_db.sqlite";
+    let conn = create_test_db(file_name);
+
+    let default_ratio = get_f64_setting("gauge_warning_ratio", &conn);
+    let default_hidden = get_bool_setting("gauge_hidden", &conn);
+
+    set_setting("gauge_warning_ratio", "0.5", &conn).unwrap();
+    set_setting("gauge_hidden", "true", &conn).unwrap();
+
+    let new_ratio = get_f64_setting("gauge_warning_ratio", &conn);
+    let new_hidden = get_bool_setting("gauge_hidden", &conn);
+
+    set_setting("gauge_warning_ratio", "invalid", &conn).unwrap();
+    let invalid_ratio = get_f64_setting("gauge_warning_ratio", &conn);
+    let unknown_setting = get_setting("unknown_setting", &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(default_ratio, 0.75);
+    assert!(!default_hidden);
+    assert_eq!(new_ratio, 0.5);
+    assert!(new_hidden);
+    assert_eq!(invalid_ratio, 0.75);
+    assert_eq!(unknown_setting, "");
+}
+
+#[test]
+fn check_ui_preferences() {
+    let file_name = "ui_preferences_db.sqlite";
+    let conn = create_test_db(file_name);
+
+    let missing_index = get_usize_setting("summary_mode", &conn);
+    let missing_hidden = get_bool_setting("summary_hidden_mode", &conn);
+
+    set_usize_setting("summary_mode", 2, &conn).unwrap();
+    set_bool_setting("summary_hidden_mode", true, &conn).unwrap();
+
+    let saved_index = get_usize_setting("summary_mode", &conn);
+    let saved_hidden = get_bool_setting("summary_hidden_mode", &conn);
+
+    let mut modes = IndexedData::new_summary_modes();
+    modes.restore_index("summary_mode", &conn);
+    let restored_index = modes.index;
+
+    modes.next();
+    modes.save_index("summary_mode", &conn);
+    let next_index = get_usize_setting("summary_mode", &conn);
+
+    // corrupt and out of range values keep the default selection
+    set_setting("summary_mode", "invalid", &conn).unwrap();
+    let mut corrupt_modes = IndexedData::new_summary_modes();
+    corrupt_modes.restore_index("summary_mode", &conn);
+
+    set_setting("summary_mode", "50", &conn).unwrap();
+    let mut out_of_range_modes = IndexedData::new_summary_modes();
+    out_of_range_modes.restore_index("summary_mode", &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(missing_index, None);
+    assert!(!missing_hidden);
+    assert_eq!(saved_index, Some(2));
+    assert!(saved_hidden);
+    assert_eq!(restored_index, 2);
+    assert_eq!(next_index, Some(3));
+    assert_eq!(corrupt_modes.index, 0);
+    assert_eq!(out_of_range_modes.index, 0);
+
+    for column in 0..5 {
+        assert_eq!(SortingType::from_column(column).unwrap().column(), column);
+    }
+    assert!(SortingType::from_column(5).is_none());
+}
+
+#[test]
+fn check_list_setting() {
+    let file_name = "list_setting_db.sqlite";
+    let conn = create_test_db(file_name);
+
+    let missing_list = get_list_setting("chart_hidden_methods", &conn);
+
+    let methods = vec!["test1".to_string(), "test 2".to_string()];
+    set_list_setting("chart_hidden_methods", &methods, &conn).unwrap();
+    let saved_list = get_list_setting("chart_hidden_methods", &conn);
+
+    set_list_setting("chart_hidden_methods", &[], &conn).unwrap();
+    let cleared_list = get_list_setting("chart_hidden_methods", &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert!(missing_list.is_empty());
+    assert_eq!(saved_list, methods);
+    assert!(cleared_list.is_empty());
+}
+
+#[test]
+fn check_valid_settings() {
+    assert!(is_valid_setting("tx_confirmation", "always"));
+    assert!(is_valid_setting("tx_confirmation", "threshold"));
+    assert!(!is_valid_setting("tx_confirmation", "sometimes"));
+    assert!(is_valid_setting("tx_confirmation_threshold", "500"));
+    assert!(!is_valid_setting("tx_confirmation_threshold", "abc"));
+    assert!(is_valid_setting("gauge_hidden", "true"));
+    assert!(!is_valid_setting("gauge_hidden", "yes"));
+    assert!(is_valid_setting("amount_step", "0.05"));
+    assert!(!is_valid_setting("amount_step", "0"));
+    assert!(!is_valid_setting("amount_step", "-1"));
+    assert!(is_valid_setting("week_start", "sunday"));
+    assert!(!is_valid_setting("week_start", "friday"));
+    assert!(is_valid_setting("amount_rounding", "half_even"));
+    assert!(!is_valid_setting("amount_rounding", "round"));
+    assert!(is_valid_setting("autofill_recency_weight", "0.5"));
+    assert!(!is_valid_setting("autofill_recency_weight", "nan"));
+    assert!(!is_valid_setting("autofill_recency_weight", "inf"));
+}
+
+#[test]
+fn check_db_health_status() {
+    let file_name = "db_health.sqlite";
+    let conn = create_test_db(file_name);
+    let healthy = check_db_health(&conn);
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    let file_name = "db_health_foreign.sqlite";
+    fs::remove_file(file_name).ok();
+    let conn = Connection::open(file_name).unwrap();
+    conn.execute("CREATE TABLE tx_all (date TEXT, amount TEXT)", [])
+        .unwrap();
+    let foreign = check_db_health(&conn);
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    let file_name = "db_health_random.sqlite";
+    fs::write(
+        file_name,
+        "this is not a database file at all, only some text. ".repeat(100),
+    )
+    .unwrap();
+    let conn = Connection::open(file_name).unwrap();
+    let unreadable = check_db_health(&conn);
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(healthy, DbHealth::Healthy);
+    assert_eq!(
+        foreign,
+        DbHealth::Foreign(vec![
+            "column tx_all.details".to_string(),
+            "column tx_all.tx_method".to_string(),
+            "column tx_all.tx_type".to_string(),
+            "column tx_all.id_num".to_string(),
+            "table balance_all".to_string(),
+            "table changes_all".to_string(),
+        ])
+    );
+    assert!(matches!(unreadable, DbHealth::Unreadable(_)));
+    assert!(unreadable.get_message().contains("not a database"));
+}
+
+#[test]
+fn check_db_recovery_actions() {
+    let backup = PathBuf::from("data_backup-20230101-120000.sqlite");
+    let corrupt = DbHealth::Corrupt(
+        (1..=12)
+            .map(|page| format!("Page {page} is never used"))
+            .collect(),
+    );
+
+    assert!(get_recovery_actions(&DbHealth::Healthy, Some(&backup), false).is_empty());
+    assert_eq!(
+        get_recovery_actions(&corrupt, Some(&backup), false),
+        vec![
+            RecoveryAction::RestoreBackup(backup.clone()),
+            RecoveryAction::StartFresh,
+            RecoveryAction::Exit,
+        ]
+    );
+    assert_eq!(
+        get_recovery_actions(&DbHealth::Unreadable(String::new()), None, false),
+        vec![RecoveryAction::StartFresh, RecoveryAction::Exit]
+    );
+    assert_eq!(
+        get_recovery_actions(&corrupt, Some(&backup), true),
+        vec![RecoveryAction::Exit]
+    );
+
+    // the reported pages are shown, up to 10 of them
+    let message = corrupt.get_message();
+    assert!(message.contains("Page 1 is never used"));
+    assert!(message.contains("Page 10 is never used"));
+    assert!(!message.contains("Page 11 is never used"));
+    assert!(message.ends_with("and 2 more"));
+
+    let now = NaiveDate::from_ymd_opt(2023, 7, 14)
+        .unwrap()
+        .and_hms_opt(9, 30, 0)
+        .unwrap();
+    assert_eq!(
+        get_moved_aside_path("data.sqlite", now),
+        "data.sqlite.corrupt-20230714-093000"
+    );
+}
+
+#[test]
+fn check_newest_backup() {
+    let dir = Path::new("newest_backup_test");
+    fs::remove_dir_all(dir).ok();
+    fs::create_dir(dir).unwrap();
+
+    let empty = find_newest_backup(dir);
+
+    for name in [
+        "data_backup-20230101-120000.sqlite",
+        "data_backup-20230315-080000.sqlite",
+        "data_backup-20221231-235959.sqlite",
+        "data_backup-20991231-000000.txt",
+        "data.sqlite",
+    ] {
+        fs::write(dir.join(name), "").unwrap();
+    }
+
+    let newest = find_newest_backup(dir);
+    fs::remove_dir_all(dir).unwrap();
+
+    assert_eq!(empty, None);
+    assert_eq!(newest, Some(dir.join("data_backup-20230315-080000.sqlite")));
+}
@@ -1,5 +1,9 @@
 extern crate rex_tui;
-use rex_tui::db::{add_tags_column, update_balance_type};
+use rex_tui::db::{
+    add_tags_column, create_db, get_bool_setting, normalize_stored_amounts, set_minor_units,
+    update_balance_type,
+};
+use rex_tui::tx_handler::add_tx;
 use rex_tui::utility::{check_old_balance_sql, get_all_tx_columns, get_last_balance_id};
 use rusqlite::Connection;
 use std::fs;
@@ -48,7 +52,7 @@ fn check_tags_migration() {
     conn.close().unwrap();
     fs::remove_file(file_name).unwrap();
 
-    assert_eq!(old_columns.contains(&"tags".to_string()), false);
+    assert!(!old_columns.contains(&"tags".to_string()));
     assert_eq!(new_columns, expected_columns);
 }
 
@@ -68,7 +72,8 @@ fn check_balance_migration() {
     )
     .unwrap();
 
-    let query = format!(r#"INSERT INTO balance_all ("test1", "test 2") VALUES ("0.00", "0.00")"#);
+    let query =
+        r#"INSERT INTO balance_all ("test1", "test 2") VALUES ("0.00", "0.00")"#.to_string();
     for _i in 0..49 {
         conn.execute(&query, []).unwrap();
     }
@@ -88,7 +93,7 @@ fn check_balance_migration() {
         })
         .unwrap();
 
-    let old_db_status = check_old_balance_sql(&mut conn);
+    let old_db_status = check_old_balance_sql(&conn);
     let old_last_balance_id = get_last_balance_id(&conn).unwrap();
 
     update_balance_type(&mut conn).unwrap();
@@ -101,23 +106,97 @@ fn check_balance_migration() {
         })
         .unwrap();
 
-    let db_status = check_old_balance_sql(&mut conn);
+    let db_status = check_old_balance_sql(&conn);
     let last_balance_id = get_last_balance_id(&conn).unwrap();
 
     conn.close().unwrap();
     fs::remove_file(file_name).unwrap();
 
-    assert_eq!(old_db_status, true);
+    assert!(old_db_status);
     assert_eq!(old_last_balance_id, 49);
     assert_eq!(
         old_last_balances,
         vec!["200.19".to_string(), "159.19".to_string()]
     );
 
-    assert_eq!(db_status, false);
+    assert!(!db_status);
     assert_eq!(last_balance_id, 193);
     assert_eq!(
         last_balances,
         vec!["200.19".to_string(), "159.19".to_string()]
     );
 }
+
+#[test]
+fn check_amount_normalization() {
+    let file_name = "db_update_3.sqlite";
+    check_test_db(file_name);
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(vec!["test1".to_string(), "test 2".to_string()], &mut conn).unwrap();
+    set_minor_units("test 2", 0, &mut conn).unwrap();
+
+    add_tx(
+        "2022-07-19",
+        "Testing transaction",
+        "test1",
+        "10.10",
+        "Income",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-07-19",
+        "Testing transaction",
+        "test 2",
+        "5",
+        "Income",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    // old versions could save amounts and balances with f64 errors
+    conn.execute(r#"UPDATE tx_all SET amount = "10.1" WHERE id_num = 1"#, [])
+        .unwrap();
+    conn.execute(r#"UPDATE tx_all SET amount = "5.00" WHERE id_num = 2"#, [])
+        .unwrap();
+    conn.execute(
+        r#"UPDATE balance_all SET "test1" = 10.100000000000001 WHERE id_num = 7"#,
+        [],
+    )
+    .unwrap();
+
+    let old_status = get_bool_setting("amounts_normalized", &conn);
+
+    normalize_stored_amounts(&mut conn).unwrap();
+
+    let amounts = conn
+        .prepare("SELECT amount FROM tx_all ORDER BY id_num")
+        .unwrap()
+        .query_map([], |row| row.get::<_, String>(0))
+        .unwrap()
+        .collect::<Result<Vec<String>, _>>()
+        .unwrap();
+
+    let month_balance: String = conn
+        .query_row(
+            r#"SELECT CAST("test1" AS TEXT) FROM balance_all WHERE id_num = 7"#,
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+
+    let status = get_bool_setting("amounts_normalized", &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert!(!old_status);
+    assert!(status);
+    assert_eq!(amounts, vec!["10.10".to_string(), "5".to_string()]);
+    assert_eq!(month_balance, "10.1");
+}
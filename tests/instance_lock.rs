@@ -0,0 +1,114 @@
+extern crate rex_tui;
+use rex_tui::db::create_db;
+use rex_tui::tx_handler::add_tx;
+use rex_tui::utility::{get_all_txs, needs_migration, InstanceLock, LockStatus};
+use rusqlite::{Connection, OpenFlags};
+use std::fs;
+
+#[test]
+fn check_instance_lock() {
+    let file_name = "instance_lock.lock";
+    fs::remove_file(file_name).ok();
+
+    let lock = match InstanceLock::acquire_with(file_name, 100, |_| true).unwrap() {
+        LockStatus::Acquired(lock) => lock,
+        LockStatus::HeldBy(_) => panic!("the lock should be free"),
+    };
+    assert_eq!(fs::read_to_string(file_name).unwrap(), "100");
+
+    // another running instance can not take it
+    let status = InstanceLock::acquire_with(file_name, 200, |pid| pid == 100).unwrap();
+    assert!(matches!(status, LockStatus::HeldBy(Some(100))));
+
+    drop(lock);
+    assert!(fs::metadata(file_name).is_err());
+
+    // a lock of a process that is no longer running gets taken over
+    fs::write(file_name, "300").unwrap();
+    let lock = match InstanceLock::acquire_with(file_name, 400, |pid| pid != 300).unwrap() {
+        LockStatus::Acquired(lock) => lock,
+        LockStatus::HeldBy(_) => panic!("the stale lock should be taken over"),
+    };
+    assert_eq!(fs::read_to_string(file_name).unwrap(), "400");
+
+    // the lock file is not removed if it belongs to someone else
+    fs::write(file_name, "500").unwrap();
+    lock.release();
+    assert_eq!(fs::read_to_string(file_name).unwrap(), "500");
+    drop(lock);
+
+    // a lock file without a PID may be one that is still being written so it is kept
+    fs::write(file_name, "not a pid").unwrap();
+    let status = InstanceLock::acquire_with(file_name, 600, |_| false).unwrap();
+    assert!(matches!(status, LockStatus::HeldBy(None)));
+
+    fs::write(file_name, "").unwrap();
+    let status = InstanceLock::acquire_with(file_name, 600, |_| false).unwrap();
+    assert!(matches!(status, LockStatus::HeldBy(None)));
+    assert_eq!(fs::read_to_string(file_name).unwrap(), "");
+
+    fs::remove_file(file_name).unwrap();
+    let lock = InstanceLock::acquire_with(file_name, 700, |_| true).unwrap();
+    assert!(matches!(lock, LockStatus::Acquired(_)));
+    assert_eq!(fs::read_to_string(file_name).unwrap(), "700");
+    // the temporary file the PID was written to is gone
+    assert!(fs::metadata("instance_lock.700.tmp").is_err());
+
+    drop(lock);
+    assert!(fs::metadata(file_name).is_err());
+}
+
+#[test]
+fn check_read_only_connection() {
+    let file_name = "read_only_connection.sqlite";
+    fs::remove_file(file_name).ok();
+
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(vec!["test1".to_string(), "test 2".to_string()], &mut conn).unwrap();
+    conn.close().unwrap();
+
+    // read-only mode opens the database so nothing can be written even if an action is missed
+    let mut conn =
+        Connection::open_with_flags(file_name, OpenFlags::SQLITE_OPEN_READ_ONLY).unwrap();
+    let status = add_tx(
+        "2022-08-19",
+        "Testing transaction",
+        "test1",
+        "100.00",
+        "Expense",
+        "Car",
+        None,
+        &mut conn,
+    );
+    let (txs, ..) = get_all_txs(&conn, 7, 0);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert!(status.is_err());
+    assert!(txs.is_empty());
+}
+
+#[test]
+fn check_read_only_migration() {
+    let file_name = "read_only_migration.sqlite";
+    fs::remove_file(file_name).ok();
+
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(vec!["test1".to_string(), "test 2".to_string()], &mut conn).unwrap();
+    let new_db = needs_migration(&conn);
+
+    // a database from before the reviewed transactions were added
+    conn.execute("DROP TABLE reviewed_txs", []).unwrap();
+    conn.close().unwrap();
+
+    // read-only mode can not add the table so it has to refuse the database
+    let conn = Connection::open_with_flags(file_name, OpenFlags::SQLITE_OPEN_READ_ONLY).unwrap();
+    let old_db = needs_migration(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert!(!new_db);
+    assert!(old_db);
+}
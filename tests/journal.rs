@@ -0,0 +1,306 @@
+extern crate rex_tui;
+use rex_tui::db::*;
+use rex_tui::page_handler::BackgroundTask;
+use rex_tui::tx_handler::*;
+use rex_tui::utility::*;
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::fs;
+
+const SAMPLE_JOURNAL: &str = "; exported from hledger
+commodity $1,000.00
+account Assets:Checking
+    ; type: Asset
+
+2022/05/01 * (101) Salary
+    Assets:Checking          $2,500.00
+    Income:Salary
+
+2022-05-03 ! Supermarket  ; weekly groceries
+    Expenses:Food:Groceries   $45.20
+    ; a comment line within the transaction
+    Expenses:Household        12.30 USD
+    Assets:Checking
+
+2022-05-04 Cash withdrawal
+    Assets:Cash               100
+    Assets:Checking          -100
+
+2022-05-05 Gift
+    Assets:Checking           $50
+    Equity:Opening
+
+05/06 Lunch
+    Expenses:Food             10
+    Assets:Cash
+
+2022-05-07 Bus
+    Expenses:Travel           $1.50 @ 1.1 EUR
+    Assets:Cash              $-1.50 = $98.50
+
+2022-05-08 Broken
+    Expenses:Food             ten dollars
+    Assets:Cash
+
+not a journal line
+
+comment
+2022-05-09 Hidden
+    Expenses:Food    5
+end comment
+";
+
+#[test]
+fn check_journal_parsing() {
+    assert_eq!(parse_journal_amount("$-1,250.50"), Money::parse("-1250.50"));
+    assert_eq!(parse_journal_amount("12 EUR"), Money::parse("12"));
+    assert_eq!(parse_journal_amount("-$3"), Money::parse("-3"));
+    assert_eq!(parse_journal_amount("USD"), None);
+
+    assert_eq!(get_account_kind("Assets:Bank"), AccountKind::Method);
+    assert_eq!(get_account_kind("liabilities:card"), AccountKind::Method);
+    assert_eq!(get_account_kind("Expenses:Food"), AccountKind::Expense);
+    assert_eq!(get_account_kind("Revenue:Sales"), AccountKind::Income);
+    assert_eq!(get_account_kind("Equity:Opening"), AccountKind::Other);
+
+    let (entries, problems) = parse_journal(SAMPLE_JOURNAL);
+
+    let headers = entries
+        .iter()
+        .map(|entry| (entry.line, entry.date.as_str(), entry.payee.as_str()))
+        .collect::<Vec<(usize, &str, &str)>>();
+
+    assert_eq!(
+        headers,
+        vec![
+            (6, "2022-05-01", "Salary"),
+            (10, "2022-05-03", "Supermarket"),
+            (16, "2022-05-04", "Cash withdrawal"),
+            (20, "2022-05-05", "Gift"),
+            (28, "2022-05-07", "Bus"),
+        ]
+    );
+
+    assert_eq!(
+        entries[1].postings,
+        vec![
+            JournalPosting {
+                account: "Expenses:Food:Groceries".to_string(),
+                amount: Money::parse("45.20"),
+            },
+            JournalPosting {
+                account: "Expenses:Household".to_string(),
+                amount: Money::parse("12.30"),
+            },
+            JournalPosting {
+                account: "Assets:Checking".to_string(),
+                amount: None,
+            },
+        ]
+    );
+    assert_eq!(entries[4].postings[1].amount, Money::parse("-1.50"));
+
+    let problem_lines = problems
+        .iter()
+        .map(|problem| problem.line)
+        .collect::<Vec<usize>>();
+
+    // the lines of the dateless header, the bad amount and the unknown line
+    assert_eq!(problem_lines, vec![24, 33, 36]);
+
+    let tx_methods = vec!["Cash".to_string(), "Bank".to_string()];
+
+    assert_eq!(
+        get_journal_accounts(&entries),
+        vec!["Assets:Checking".to_string(), "Assets:Cash".to_string()]
+    );
+    assert_eq!(
+        get_default_method("Assets:Cash", &tx_methods),
+        Some("Cash".to_string())
+    );
+    assert_eq!(get_default_method("Assets:Checking", &tx_methods), None);
+}
+
+#[test]
+fn check_journal_to_txs() {
+    let (entries, _) = parse_journal(SAMPLE_JOURNAL);
+
+    let mut method_map = HashMap::new();
+    method_map.insert("Assets:Checking".to_string(), "test1".to_string());
+    method_map.insert("Assets:Cash".to_string(), "test 2".to_string());
+
+    let (txs, problems) = journal_to_txs(&entries, &method_map);
+
+    let rows = txs
+        .iter()
+        .map(|(line, tx)| {
+            (
+                *line,
+                tx.from_method.as_str(),
+                tx.to_method.as_str(),
+                tx.amount.as_str(),
+                tx.tx_type.as_str(),
+                tx.tags.as_str(),
+            )
+        })
+        .collect::<Vec<(usize, &str, &str, &str, &str, &str)>>();
+
+    // the entry with 3 postings is split into one transaction per expense
+    assert_eq!(
+        rows,
+        vec![
+            (6, "test1", "", "2500.000", "Income", "Salary"),
+            (10, "test1", "", "45.200", "Expense", "Food, Groceries"),
+            (10, "test1", "", "12.300", "Expense", "Household"),
+            (16, "test1", "test 2", "100.000", "Transfer", ""),
+            (28, "test 2", "", "1.500", "Expense", "Travel"),
+        ]
+    );
+
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].line, 20);
+    assert_eq!(
+        problems[0].to_string(),
+        "Line 20: Account Equity:Opening is not an asset, liability, expense or income account"
+    );
+
+    method_map.remove("Assets:Cash");
+    let (txs, problems) = journal_to_txs(&entries, &method_map);
+
+    assert_eq!(txs.len(), 3);
+    assert_eq!(
+        problems
+            .iter()
+            .map(|problem| problem.line)
+            .collect::<Vec<usize>>(),
+        vec![16, 20, 28]
+    );
+
+    let (unbalanced, _) = parse_journal(
+        "2022-05-01 Off
+    Expenses:Food   10
+    Assets:Cash    -9
+",
+    );
+    let (_, problems) = journal_to_txs(&unbalanced, &method_map);
+
+    assert_eq!(problems[0].reason, "The postings are off balance by 1.00");
+}
+
+#[test]
+fn check_journal_import() {
+    let file_name = "check_journal_import.sqlite";
+    if fs::metadata(file_name).is_ok() {
+        fs::remove_file(file_name).unwrap();
+    }
+
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(vec!["test1".to_string(), "test 2".to_string()], &mut conn).unwrap();
+
+    let (entries, _) = parse_journal(
+        "2022-05-01 Salary
+    Assets:Checking   $2,500.00
+    Income:Salary
+
+2022-05-02 Same account
+    Assets:Checking   5
+    Assets:Checking  -5
+
+2022-05-03 Groceries
+    Expenses:Food     45.20
+    Assets:Checking
+",
+    );
+
+    let mut method_map = HashMap::new();
+    method_map.insert("Assets:Checking".to_string(), "test1".to_string());
+
+    let (txs, _) = journal_to_txs(&entries, &method_map);
+    let (added, problems) = import_journal_txs(&txs, &mut conn);
+
+    let salary = get_tx_by_id(1, &conn);
+    let groceries = get_tx_by_id(2, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(added, 2);
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].line, 5);
+    assert_eq!(
+        salary,
+        Some(vec![
+            "2022-05-01".to_string(),
+            "Salary".to_string(),
+            "test1".to_string(),
+            "2500.00".to_string(),
+            "Income".to_string(),
+            "Salary".to_string(),
+        ])
+    );
+    assert_eq!(groceries.unwrap()[3], "45.20");
+}
+
+#[test]
+fn check_journal_import_task() {
+    let file_name = "check_journal_import_task.sqlite";
+    if fs::metadata(file_name).is_ok() {
+        fs::remove_file(file_name).unwrap();
+    }
+
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(vec!["test1".to_string(), "test 2".to_string()], &mut conn).unwrap();
+
+    // more transactions than a single batch so the progress is sent more than once
+    let journal = (1..=60)
+        .map(|day| {
+            format!(
+                "2022-05-{:02} Lunch\n    Expenses:Food   {day}\n    Assets:Checking\n\n",
+                day % 28 + 1
+            )
+        })
+        .collect::<String>();
+
+    let (entries, _) = parse_journal(&journal);
+
+    let mut method_map = HashMap::new();
+    method_map.insert("Assets:Checking".to_string(), "test1".to_string());
+
+    let (txs, _) = journal_to_txs(&entries, &method_map);
+    let problems = vec![JournalProblem::new(70, "Not a transaction")];
+
+    let mut task = BackgroundTask::start("Journal import", file_name, move |conn, progress| {
+        Ok(import_journal_task(&txs, problems, progress, conn))
+    });
+
+    assert_eq!(task.get_name(), "Journal import");
+
+    let result = loop {
+        if let Some(result) = task.poll() {
+            break result;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    };
+
+    let last_tx = get_tx_by_id(60, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(
+        result,
+        Ok("Imported 60 transactions from the journal.\n\n1 lines or transactions were not imported:\n\nLine 70: Not a transaction".to_string())
+    );
+    assert_eq!(task.get_progress(), (60, 60));
+    assert_eq!(task.get_percentage(), Some(100));
+    assert_eq!(last_tx.unwrap()[3], "60.00");
+
+    let problems = (1..=20)
+        .map(|line| JournalProblem::new(line, "Bad line"))
+        .collect::<Vec<JournalProblem>>();
+    let report = get_journal_report(5, true, &problems);
+
+    assert!(report.starts_with("Stopped after importing 5 transactions from the journal."));
+    assert!(report.contains("Line 15: Bad line\n...and 5 more"));
+    assert!(!report.contains("Line 16"));
+}
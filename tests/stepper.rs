@@ -0,0 +1,731 @@
+extern crate rex_tui;
+use chrono::NaiveDate;
+use rex_tui::db::{create_db, get_amount_step, set_setting, DateRange};
+use rex_tui::outputs::{AmountStepSize, DateStepSize, StepType, SteppingError};
+use rex_tui::page_handler::{NameCache, TxTab};
+use rex_tui::tx_handler::{add_tx, TxData};
+use rex_tui::utility::traits::{DataVerifier, FieldStepper};
+use rex_tui::utility::{AmountFormat, DateFormat, FixedClock, Money};
+use rusqlite::Connection;
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct Testing {}
+impl DataVerifier for Testing {}
+impl FieldStepper for Testing {}
+
+fn create_test_db(file_name: &str) -> Connection {
+    if let Ok(metadata) = fs::metadata(file_name) {
+        if metadata.is_file() {
+            fs::remove_file(file_name).expect("Failed to delete existing file");
+        }
+    }
+
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(vec!["test1".to_string(), "test 2".to_string()], &mut conn).unwrap();
+    conn
+}
+
+#[test]
+fn check_empty_date_step() {
+    let stepper = Testing {};
+    let date_range = DateRange::new(2020, 2040);
+
+    let mut up_date = String::new();
+    let mut down_date = String::new();
+
+    assert!(stepper
+        .step_date(
+            &mut up_date,
+            StepType::StepUp,
+            &date_range,
+            &DateFormat::Ymd
+        )
+        .is_ok());
+    assert!(stepper
+        .step_date(
+            &mut down_date,
+            StepType::StepDown,
+            &date_range,
+            &DateFormat::Ymd
+        )
+        .is_ok());
+
+    assert_eq!(up_date, "2020-01-01");
+    assert_eq!(down_date, "2040-12-31");
+
+    // the last date does not go past the range
+    assert!(stepper
+        .step_date(
+            &mut down_date,
+            StepType::StepUp,
+            &date_range,
+            &DateFormat::Ymd
+        )
+        .is_ok());
+    assert_eq!(down_date, "2040-12-31");
+}
+
+#[test]
+fn check_empty_tx_method_step() {
+    let file_name = "check_empty_tx_method_step.sqlite";
+    let conn = create_test_db(file_name);
+    let stepper = Testing {};
+
+    let mut up_method = String::new();
+    let mut down_method = String::new();
+
+    let up_status =
+        stepper.step_tx_method(&mut up_method, StepType::StepUp, &NameCache::new(), &conn);
+    let down_status = stepper.step_tx_method(
+        &mut down_method,
+        StepType::StepDown,
+        &NameCache::new(),
+        &conn,
+    );
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert!(up_status.is_ok());
+    assert!(down_status.is_ok());
+    assert_eq!(up_method, "test1");
+    assert_eq!(down_method, "test 2");
+}
+
+#[test]
+fn check_division_by_zero_amount_step() {
+    let stepper = Testing {};
+
+    let mut amount = "50/0".to_string();
+    let status = stepper.step_amount(&mut amount, StepType::StepUp, AmountStepSize::Single);
+
+    assert!(matches!(status, Err(SteppingError::InvalidAmount)));
+    assert_eq!(amount, "50/0");
+}
+
+#[test]
+fn check_empty_tx_type_step() {
+    let stepper = Testing {};
+
+    let mut up_type = String::new();
+    let mut down_type = String::new();
+
+    assert!(stepper.step_tx_type(&mut up_type, StepType::StepUp).is_ok());
+    assert!(stepper
+        .step_tx_type(&mut down_type, StepType::StepDown)
+        .is_ok());
+
+    assert_eq!(up_type, "Income");
+    assert_eq!(down_type, "Refund");
+
+    assert!(stepper
+        .step_tx_type(&mut down_type, StepType::StepDown)
+        .is_ok());
+    assert_eq!(down_type, "Transfer");
+}
+
+#[test]
+fn check_empty_tags_step() {
+    let file_name = "check_empty_tags_step.sqlite";
+    let mut conn = create_test_db(file_name);
+    let stepper = Testing {};
+
+    // no tags in the db yet
+    let mut no_tag = String::new();
+    let no_tag_up = stepper.step_tags(&mut no_tag, "", StepType::StepUp, &NameCache::new(), &conn);
+    let no_tag_down = stepper.step_tags(
+        &mut no_tag,
+        "",
+        StepType::StepDown,
+        &NameCache::new(),
+        &conn,
+    );
+
+    for tag in ["Food", "Car", "Rent"] {
+        add_tx(
+            "2023-05-01",
+            "Testing transaction",
+            "test1",
+            "10.00",
+            "Expense",
+            tag,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let mut up_tag = String::new();
+    let mut down_tag = String::new();
+    let mut next_down_tag = "Car, ".to_string();
+
+    assert!(stepper
+        .step_tags(&mut up_tag, "", StepType::StepUp, &NameCache::new(), &conn)
+        .is_ok());
+    assert!(stepper
+        .step_tags(
+            &mut down_tag,
+            "",
+            StepType::StepDown,
+            &NameCache::new(),
+            &conn
+        )
+        .is_ok());
+    assert!(stepper
+        .step_tags(
+            &mut next_down_tag,
+            "",
+            StepType::StepDown,
+            &NameCache::new(),
+            &conn
+        )
+        .is_ok());
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert!(matches!(no_tag_up, Err(SteppingError::InvalidTags)));
+    assert!(matches!(no_tag_down, Err(SteppingError::InvalidTags)));
+    assert_eq!(no_tag, "");
+
+    assert_eq!(up_tag, "Car");
+    assert_eq!(down_tag, "Rent");
+    assert_eq!(next_down_tag, "Car, Rent");
+}
+
+#[test]
+fn check_date_format_step() {
+    let stepper = Testing {};
+    let date_range = DateRange::default();
+
+    let mut dmy_date = "31/12/2023".to_string();
+    let mut mdy_date = "03-01-2024".to_string();
+
+    assert!(stepper
+        .step_date(
+            &mut dmy_date,
+            StepType::StepUp,
+            &date_range,
+            &DateFormat::Dmy
+        )
+        .is_ok());
+    assert!(stepper
+        .step_date(
+            &mut mdy_date,
+            StepType::StepDown,
+            &date_range,
+            &DateFormat::Mdy
+        )
+        .is_ok());
+
+    assert_eq!(dmy_date, "01-01-2024");
+    assert_eq!(mdy_date, "02-29-2024");
+}
+
+#[test]
+fn check_relative_date_step() {
+    let stepper = Testing {};
+    let date_range = DateRange::default();
+    let clock = FixedClock::new(NaiveDate::from_ymd_opt(2024, 6, 8).unwrap());
+
+    let mut date = "t".to_string();
+
+    stepper.verify_date_with_clock(&mut date, &date_range, &DateFormat::Ymd, &clock);
+
+    assert!(stepper
+        .step_date(&mut date, StepType::StepUp, &date_range, &DateFormat::Ymd)
+        .is_ok());
+    assert_eq!(date, "2024-06-09");
+}
+
+#[test]
+fn check_month_year_date_step() {
+    let stepper = Testing {};
+    let date_range = DateRange::new(2020, 2025);
+
+    let step = |date: &str, step_type: StepType, step_size: DateStepSize| {
+        let mut date = date.to_string();
+        let status = stepper.step_date_by(
+            &mut date,
+            step_type,
+            step_size,
+            &date_range,
+            &DateFormat::Ymd,
+        );
+        assert!(status.is_ok());
+        date
+    };
+
+    // the day is clamped to the end of a shorter month
+    assert_eq!(
+        step("2023-01-31", StepType::StepUp, DateStepSize::Month),
+        "2023-02-28"
+    );
+    assert_eq!(
+        step("2024-01-31", StepType::StepUp, DateStepSize::Month),
+        "2024-02-29"
+    );
+    assert_eq!(
+        step("2024-03-31", StepType::StepDown, DateStepSize::Month),
+        "2024-02-29"
+    );
+    assert_eq!(
+        step("2024-02-29", StepType::StepUp, DateStepSize::Year),
+        "2025-02-28"
+    );
+
+    // stepping across a year boundary
+    assert_eq!(
+        step("2023-12-15", StepType::StepUp, DateStepSize::Month),
+        "2024-01-15"
+    );
+    assert_eq!(
+        step("2024-01-15", StepType::StepDown, DateStepSize::Month),
+        "2023-12-15"
+    );
+    assert_eq!(
+        step("2023-12-29", StepType::StepUp, DateStepSize::Week),
+        "2024-01-05"
+    );
+
+    // the range still limits the date
+    assert_eq!(
+        step("2025-12-15", StepType::StepUp, DateStepSize::Month),
+        "2025-12-31"
+    );
+    assert_eq!(
+        step("2025-06-15", StepType::StepUp, DateStepSize::Year),
+        "2025-12-31"
+    );
+    assert_eq!(
+        step("2020-01-15", StepType::StepDown, DateStepSize::Month),
+        "2020-01-01"
+    );
+    assert_eq!(
+        step("2020-05-01", StepType::StepDown, DateStepSize::Year),
+        "2020-01-01"
+    );
+}
+
+#[test]
+fn check_set_today() {
+    let stepper = Testing {};
+    let date_range = DateRange::new(2020, 2025);
+
+    let set_today = |date: &str, today: NaiveDate, date_format: &DateFormat| {
+        let mut date = date.to_string();
+        let clock = FixedClock::new(today);
+        let in_range = stepper.set_today_with_clock(&mut date, &date_range, date_format, &clock);
+        (date, in_range)
+    };
+
+    let today = NaiveDate::from_ymd_opt(2024, 6, 8).unwrap();
+
+    assert_eq!(
+        set_today("2021-01-01", today, &DateFormat::Ymd),
+        ("2024-06-08".to_string(), true)
+    );
+    assert_eq!(
+        set_today("", today, &DateFormat::Dmy),
+        ("08-06-2024".to_string(), true)
+    );
+
+    // an invalid partial date is replaced
+    assert_eq!(
+        set_today("2024-1", today, &DateFormat::Ymd),
+        ("2024-06-08".to_string(), true)
+    );
+
+    // today outside the range is clamped to the closest date of the range
+    let after_range = NaiveDate::from_ymd_opt(2027, 3, 10).unwrap();
+    let before_range = NaiveDate::from_ymd_opt(2019, 3, 10).unwrap();
+
+    assert_eq!(
+        set_today("2024-1", after_range, &DateFormat::Ymd),
+        ("2025-12-31".to_string(), false)
+    );
+    assert_eq!(
+        set_today("abc", before_range, &DateFormat::Mdy),
+        ("01-01-2020".to_string(), false)
+    );
+}
+
+#[test]
+fn check_amount_step_sizes() {
+    let stepper = Testing {};
+
+    let step = |amount: &str, step_type: StepType, step_size: AmountStepSize| {
+        let mut amount = amount.to_string();
+        let status = stepper.step_amount(&mut amount, step_type, step_size);
+        assert!(status.is_ok());
+        amount
+    };
+
+    assert_eq!(
+        step("4500.00", StepType::StepUp, AmountStepSize::Single),
+        "4501.00"
+    );
+    assert_eq!(
+        step("4500.00", StepType::StepUp, AmountStepSize::Ten),
+        "4510.00"
+    );
+    assert_eq!(
+        step("4500.00", StepType::StepDown, AmountStepSize::Hundred),
+        "4400.00"
+    );
+    assert_eq!(step("", StepType::StepUp, AmountStepSize::Ten), "10.00");
+
+    // large steps stop at the bounds
+    assert_eq!(
+        step("9999999950.00", StepType::StepUp, AmountStepSize::Hundred),
+        "9999999999.99"
+    );
+    assert_eq!(
+        step("9999999999.99", StepType::StepUp, AmountStepSize::Single),
+        "9999999999.99"
+    );
+    assert_eq!(
+        step("50.00", StepType::StepDown, AmountStepSize::Hundred),
+        "0.00"
+    );
+    assert_eq!(
+        step("5.00", StepType::StepDown, AmountStepSize::Ten),
+        "0.00"
+    );
+}
+
+#[test]
+fn check_expression_amount_step() {
+    let stepper = Testing {};
+
+    let step = |amount: &str, step_type: StepType, step_size: AmountStepSize| {
+        let mut amount = amount.to_string();
+        let status = stepper.step_amount(&mut amount, step_type, step_size);
+        assert!(status.is_ok());
+        amount
+    };
+
+    // the expression is kept and a trailing number is added
+    assert_eq!(
+        step("1200+350", StepType::StepUp, AmountStepSize::Single),
+        "1200+350+1"
+    );
+    assert_eq!(
+        step("1200+350", StepType::StepDown, AmountStepSize::Ten),
+        "1200+350-10"
+    );
+    assert_eq!(
+        step("100*2", StepType::StepUp, AmountStepSize::Single),
+        "100*2+1"
+    );
+    assert_eq!(
+        step("(10+5)*2", StepType::StepUp, AmountStepSize::Single),
+        "(10+5)*2+1"
+    );
+
+    // an existing trailing number is adjusted
+    assert_eq!(
+        step("1200+350+1", StepType::StepUp, AmountStepSize::Single),
+        "1200+350+2"
+    );
+    assert_eq!(
+        step("1200+350-2.5", StepType::StepUp, AmountStepSize::Ten),
+        "1200+350+7.5"
+    );
+
+    // a trailing number that becomes 0 is removed
+    assert_eq!(
+        step("1200+350+1", StepType::StepDown, AmountStepSize::Single),
+        "1200+350"
+    );
+    assert_eq!(
+        step("1200+350-1", StepType::StepUp, AmountStepSize::Single),
+        "1200+350"
+    );
+
+    // stepping down below 0 keeps the expression
+    assert_eq!(
+        step("10-5-4.5", StepType::StepDown, AmountStepSize::Single),
+        "10-5-4.5"
+    );
+
+    // a single number is still stepped and formatted
+    assert_eq!(
+        step("1200", StepType::StepUp, AmountStepSize::Single),
+        "1201.00"
+    );
+
+    let mut amount = "1,25+3".to_string();
+    let status = stepper.step_amount(&mut amount, StepType::StepUp, AmountStepSize::Single);
+    assert!(status.is_err());
+    assert_eq!(amount, "1,25+3");
+
+    let mut amount = "1.200+350".to_string();
+    let status = stepper.step_amount_units(
+        &mut amount,
+        StepType::StepUp,
+        Money::from_minor(1500),
+        2,
+        &AmountFormat::new(',', ""),
+    );
+    assert!(status.is_ok());
+    assert_eq!(amount, "1.200+350+1,5");
+}
+
+#[test]
+fn check_custom_amount_step() {
+    let file_name = "check_custom_amount_step.sqlite";
+    let conn = create_test_db(file_name);
+    let stepper = Testing {};
+
+    let default_step = get_amount_step(&conn);
+
+    set_setting("amount_step", "0.05", &conn).unwrap();
+    let custom_step = get_amount_step(&conn);
+
+    set_setting("amount_step", "-2", &conn).unwrap();
+    let invalid_step = get_amount_step(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    let mut up_amount = "1.00".to_string();
+    let mut down_amount = "0.10".to_string();
+    let mut big_amount = "9999999999.90".to_string();
+
+    for (amount, step_type, step_size) in [
+        (&mut up_amount, StepType::StepUp, AmountStepSize::Single),
+        (
+            &mut down_amount,
+            StepType::StepDown,
+            AmountStepSize::Hundred,
+        ),
+        (&mut big_amount, StepType::StepUp, AmountStepSize::Hundred),
+    ] {
+        let status = stepper.step_amount_units(
+            amount,
+            step_type,
+            custom_step * step_size.multiplier(),
+            2,
+            &AmountFormat::default(),
+        );
+        assert!(status.is_ok());
+    }
+
+    assert_eq!(default_step.to_string(), "1.00");
+    assert_eq!(custom_step.to_string(), "0.05");
+    assert_eq!(invalid_step, default_step);
+
+    assert_eq!(up_amount, "1.05");
+    assert_eq!(down_amount, "0.00");
+    assert_eq!(big_amount, "9999999999.99");
+}
+
+#[test]
+fn check_prefix_tags_step() {
+    let file_name = "check_prefix_tags_step.sqlite";
+    let mut conn = create_test_db(file_name);
+    let stepper = Testing {};
+
+    for tag in ["Groceries", "Grooming", "Rent"] {
+        add_tx(
+            "2023-05-01",
+            "Testing transaction",
+            "test1",
+            "10.00",
+            "Expense",
+            tag,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let step = |user_tag: &mut String, prefix: &str, step_type: StepType| {
+        let status =
+            stepper.step_tags_matching(user_tag, prefix, "", step_type, &NameCache::new(), &conn);
+        assert!(status.is_ok());
+        user_tag.to_string()
+    };
+
+    // cycles only through the tags starting with the prefix in both directions
+    let mut up_tags = "Rent, gro".to_string();
+    let up_steps = (0..3)
+        .map(|_| step(&mut up_tags, "gro", StepType::StepUp))
+        .collect::<Vec<String>>();
+
+    let mut down_tags = "gro".to_string();
+    let down_steps = (0..3)
+        .map(|_| step(&mut down_tags, "gro", StepType::StepDown))
+        .collect::<Vec<String>>();
+
+    // tags containing the text are used if none start with it
+    let mut contained_tags = "min".to_string();
+    let contained = step(&mut contained_tags, "min", StepType::StepUp);
+
+    // all tags are used when nothing matches or the prefix is a complete tag
+    let mut full_tags = "Rent".to_string();
+    let full = step(&mut full_tags, "Rent", StepType::StepUp);
+
+    // the typed prefix is kept between the steps of the add tx page until the tags are edited
+    let mut tx_data = TxData::new();
+    for c in "gro".chars() {
+        tx_data.edit_tags(Some(c));
+    }
+    let mut tx_steps = Vec::new();
+    for _ in 0..3 {
+        tx_data
+            .do_tags_up(&NameCache::new(), &conn)
+            .unwrap_or_default();
+        tx_steps.push(tx_data.get_all_texts()[6].to_string());
+    }
+    for _ in 0.."Groceries".len() {
+        tx_data.edit_tags(None);
+    }
+    tx_data.edit_tags(Some('r'));
+    tx_data
+        .do_tags_up(&NameCache::new(), &conn)
+        .unwrap_or_default();
+    let edited_step = tx_data.get_all_texts()[6].to_string();
+
+    let mut stateless_tags = "gro".to_string();
+    let stateless = stepper.step_tags(
+        &mut stateless_tags,
+        "",
+        StepType::StepUp,
+        &NameCache::new(),
+        &conn,
+    );
+
+    let mut unmatched_tags = "xyz".to_string();
+    let unmatched = stepper.step_tags(
+        &mut unmatched_tags,
+        "Rent",
+        StepType::StepUp,
+        &NameCache::new(),
+        &conn,
+    );
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(
+        up_steps,
+        vec![
+            "Rent, Groceries".to_string(),
+            "Rent, Grooming".to_string(),
+            "Rent, Groceries".to_string(),
+        ]
+    );
+    assert_eq!(
+        down_steps,
+        vec![
+            "Grooming".to_string(),
+            "Groceries".to_string(),
+            "Grooming".to_string(),
+        ]
+    );
+    assert_eq!(tx_steps, vec!["Groceries", "Grooming", "Groceries"]);
+    assert_eq!(edited_step, "Rent");
+    assert_eq!(contained, "Grooming");
+    assert_eq!(full, "Groceries");
+
+    assert!(stateless.is_ok());
+    assert_eq!(stateless_tags, "Groceries");
+
+    assert!(matches!(unmatched, Err(SteppingError::InvalidTags)));
+    assert_eq!(unmatched_tags, "Rent");
+}
+
+#[test]
+fn check_name_cache() {
+    let file_name = "check_name_cache.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    for tag in ["Food", "Rent"] {
+        add_tx(
+            "2023-05-01",
+            "Testing transaction",
+            "test1",
+            "10.00",
+            "Expense",
+            tag,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    // every statement the connection runs is counted from here on
+    static STATEMENTS: AtomicUsize = AtomicUsize::new(0);
+    fn count_statement(_: &str) {
+        STATEMENTS.fetch_add(1, Ordering::Relaxed);
+    }
+    conn.trace(Some(count_statement));
+
+    let names = NameCache::new();
+    let mut tx_data = TxData::new();
+
+    // a burst of key presses on the method and tag fields of the add tx page
+    let burst = |tx_data: &mut TxData, key_events: usize| {
+        for event in 0..key_events {
+            match event % 5 {
+                0 => assert!(tx_data.do_from_method_up(&names, &conn).is_ok()),
+                1 => assert!(tx_data.do_tags_up(&names, &conn).is_ok()),
+                2 => tx_data.check_autofill(&TxTab::Tags, &names, &conn),
+                3 => {
+                    tx_data.check_tags(&names, &conn);
+                }
+                _ => {
+                    tx_data.check_from_method(&names, &conn);
+                }
+            }
+        }
+    };
+
+    burst(&mut tx_data, 10);
+    let loads_after_ten = names.get_load_count();
+    let statements_after_ten = STATEMENTS.load(Ordering::Relaxed);
+
+    burst(&mut tx_data, 100);
+    let loads_after_burst = names.get_load_count();
+    let statements_after_burst = STATEMENTS.load(Ordering::Relaxed);
+
+    conn.trace(None);
+
+    add_tx(
+        "2023-05-02",
+        "Testing transaction",
+        "test 2",
+        "10.00",
+        "Expense",
+        "Travel",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    // nothing is queried again until the cache is invalidated
+    let stale_tags = names.get_tags(&conn);
+    names.invalidate();
+    let fresh_tags = names.get_tags(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    // the cached data is only queried once for the whole burst and the key presses
+    // after the first ones do not run any statement
+    assert_eq!(loads_after_ten, 4);
+    assert_eq!(loads_after_burst, 4);
+    assert!(statements_after_ten > 0);
+    assert_eq!(statements_after_burst, statements_after_ten);
+    assert_eq!(names.get_load_count(), 5);
+    assert_eq!(names.get_generation(), 1);
+
+    assert_eq!(stale_tags, vec!["Food".to_string(), "Rent".to_string()]);
+    assert_eq!(
+        fresh_tags,
+        vec!["Food".to_string(), "Rent".to_string(), "Travel".to_string()]
+    );
+}
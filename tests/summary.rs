@@ -1,10 +1,18 @@
 extern crate rex_tui;
+use chrono::NaiveDate;
 use rex_tui::db::*;
-use rex_tui::page_handler::{IndexedData, SortingType};
-use rex_tui::summary_page::SummaryData;
-use rex_tui::tx_handler::add_tx;
-use rex_tui::utility::sort_table_data;
+use rex_tui::outputs::{AType, DateStepSize, StepType, VerifyingOutput};
+use rex_tui::page_handler::{IndexedData, NameCache, SortOrder, SortingType};
+use rex_tui::summary_page::{
+    get_method_table_header, get_tag_table_header, SummaryData, SummaryRangeData, RANGE_MODE,
+};
+use rex_tui::tx_handler::{add_tx, TxData};
+use rex_tui::utility::traits::DataVerifier;
+use rex_tui::utility::{
+    get_excluded_tags, get_tag_groups, sort_table_data, sort_table_data_by, FixedClock,
+};
 use rusqlite::Connection;
+use std::collections::HashMap;
 use std::fs;
 
 fn create_test_db(file_name: &str) -> Connection {
@@ -22,7 +30,7 @@ fn create_test_db(file_name: &str) -> Connection {
 #[test]
 fn check_summary_data_1() {
     let file_name = "summary_data_1.sqlite";
-    let mut conn = create_test_db(&file_name);
+    let mut conn = create_test_db(file_name);
 
     add_tx(
         "2022-08-19",
@@ -63,10 +71,12 @@ fn check_summary_data_1() {
     let summary_modes = IndexedData::new_modes();
 
     let my_summary = SummaryData::new(&conn);
-    let my_summary_text = my_summary.get_table_data(&summary_modes, 6, 1);
-    let my_summary_text_2 = my_summary.get_tx_data(&summary_modes, 6, 1, &conn);
+    let my_summary_text = my_summary.get_table_data(&summary_modes, 6, 1, None, None);
+    let my_summary_text_2 = my_summary.get_tx_data(&summary_modes, 6, 1, None, None, &conn);
 
-    let expected_data_1 = vec![vec!["Food", "200.00", "100.00", "100.00", "100.00"]];
+    let expected_data_1 = vec![vec![
+        "Food", "200.00", "100.00", "100.00", "100.00", "100.00", "2", "200.00",
+    ]];
 
     let expected_data_2 = (
         vec![
@@ -93,6 +103,21 @@ fn check_summary_data_1() {
                 "100.00".to_string(),
                 "-".to_string(),
             ],
+            vec![
+                "Daily Income".to_string(),
+                "6.45".to_string(),
+                "-".to_string(),
+            ],
+            vec![
+                "Daily Expense".to_string(),
+                "3.23".to_string(),
+                "-".to_string(),
+            ],
+            vec![
+                "Projected Expense".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+            ],
         ],
         vec![
             vec![
@@ -137,6 +162,13 @@ fn check_summary_data_1() {
                 "0.00".to_string(),
                 "200.00".to_string(),
                 "0.00".to_string(),
+                "0.00".to_string(),
+                "0.00".to_string(),
+                "200.00".to_string(),
+                "1".to_string(),
+                "-100.00".to_string(),
+                "100.00".to_string(),
+                "200.00".to_string(),
             ],
             vec![
                 "test 2".to_string(),
@@ -146,6 +178,13 @@ fn check_summary_data_1() {
                 "100.00%".to_string(),
                 "0.00".to_string(),
                 "100.00".to_string(),
+                "0.00".to_string(),
+                "0.00".to_string(),
+                "-100.00".to_string(),
+                "1".to_string(),
+                "0.00".to_string(),
+                "-100.00".to_string(),
+                "-100.00".to_string(),
             ],
         ],
     );
@@ -160,7 +199,7 @@ fn check_summary_data_1() {
 #[test]
 fn check_summary_data_2() {
     let file_name = "summary_data_2.sqlite";
-    let mut conn = create_test_db(&file_name);
+    let mut conn = create_test_db(file_name);
 
     add_tx(
         "2022-08-19",
@@ -214,8 +253,8 @@ fn check_summary_data_2() {
     summary_modes.next();
 
     let my_summary = SummaryData::new(&conn);
-    let my_summary_text = my_summary.get_table_data(&summary_modes, 0, 0);
-    let my_summary_text_2 = my_summary.get_tx_data(&summary_modes, 0, 0, &conn);
+    let my_summary_text = my_summary.get_table_data(&summary_modes, 0, 0, None, None);
+    let my_summary_text_2 = my_summary.get_tx_data(&summary_modes, 0, 0, None, None, &conn);
 
     let expected_data_1 = vec![
         vec![
@@ -224,6 +263,9 @@ fn check_summary_data_2() {
             "1000.00".to_string(),
             "0.00".to_string(),
             "100.00".to_string(),
+            "-1000.00".to_string(),
+            "2".to_string(),
+            "500.00".to_string(),
         ],
         vec![
             "Food".to_string(),
@@ -231,6 +273,9 @@ fn check_summary_data_2() {
             "0.00".to_string(),
             "100.00".to_string(),
             "0.00".to_string(),
+            "1700.00".to_string(),
+            "2".to_string(),
+            "1000.00".to_string(),
         ],
     ];
 
@@ -259,6 +304,16 @@ fn check_summary_data_2() {
                 "250.00".to_string(),
                 "-".to_string(),
             ],
+            vec![
+                "Monthly Income".to_string(),
+                "141.67".to_string(),
+                "-".to_string(),
+            ],
+            vec![
+                "Monthly Expense".to_string(),
+                "83.33".to_string(),
+                "-".to_string(),
+            ],
         ],
         vec![
             vec![
@@ -303,6 +358,13 @@ fn check_summary_data_2() {
                 "100.00%".to_string(),
                 "250.00".to_string(),
                 "250.00".to_string(),
+                "0.00".to_string(),
+                "0.00".to_string(),
+                "0.00".to_string(),
+                "3".to_string(),
+                "0.00".to_string(),
+                "500.00".to_string(),
+                "500.00".to_string(),
             ],
             vec![
                 "test 2".to_string(),
@@ -312,6 +374,13 @@ fn check_summary_data_2() {
                 "0.00".to_string(),
                 "175.00".to_string(),
                 "0.00".to_string(),
+                "0.00".to_string(),
+                "0.00".to_string(),
+                "700.00".to_string(),
+                "1".to_string(),
+                "0.00".to_string(),
+                "700.00".to_string(),
+                "700.00".to_string(),
             ],
         ],
     );
@@ -326,7 +395,7 @@ fn check_summary_data_2() {
 #[test]
 fn check_summary_data_3() {
     let file_name = "summary_data_3.sqlite";
-    let mut conn = create_test_db(&file_name);
+    let mut conn = create_test_db(file_name);
 
     add_tx(
         "2022-08-19",
@@ -369,8 +438,11 @@ fn check_summary_data_3() {
     summary_modes.next();
 
     let my_summary = SummaryData::new(&conn);
-    let my_summary_text = my_summary.get_table_data(&summary_modes, 0, 1);
-    let my_summary_text_2 = my_summary.get_tx_data(&summary_modes, 0, 1, &conn);
+    let my_summary_text = my_summary.get_table_data(&summary_modes, 0, 1, None, None);
+    // the all time monthly averages count the months until the clock's date
+    let clock = FixedClock::new(NaiveDate::from_ymd_opt(2024, 6, 15).unwrap());
+    let my_summary_text_2 =
+        my_summary.get_tx_data_with_clock(&clock, &summary_modes, 0, 1, None, None, &conn);
 
     let expected_data_1 = vec![
         vec![
@@ -379,6 +451,9 @@ fn check_summary_data_3() {
             "100.00".to_string(),
             "0.00".to_string(),
             "100.00".to_string(),
+            "-100.00".to_string(),
+            "1".to_string(),
+            "100.00".to_string(),
         ],
         vec![
             "Food".to_string(),
@@ -386,6 +461,9 @@ fn check_summary_data_3() {
             "0.00".to_string(),
             "100.00".to_string(),
             "0.00".to_string(),
+            "200.00".to_string(),
+            "2".to_string(),
+            "100.00".to_string(),
         ],
     ];
 
@@ -414,6 +492,16 @@ fn check_summary_data_3() {
                 "33.33".to_string(),
                 "-".to_string(),
             ],
+            vec![
+                "Monthly Income".to_string(),
+                "6.67".to_string(),
+                "-".to_string(),
+            ],
+            vec![
+                "Monthly Expense".to_string(),
+                "3.33".to_string(),
+                "-".to_string(),
+            ],
         ],
         vec![
             vec![
@@ -458,6 +546,13 @@ fn check_summary_data_3() {
                 "100.00%".to_string(),
                 "33.33".to_string(),
                 "33.33".to_string(),
+                "0.00".to_string(),
+                "0.00".to_string(),
+                "0.00".to_string(),
+                "2".to_string(),
+                "0.00".to_string(),
+                "0.00".to_string(),
+                "0.00".to_string(),
             ],
             vec![
                 "test 2".to_string(),
@@ -467,6 +562,13 @@ fn check_summary_data_3() {
                 "0.00".to_string(),
                 "33.33".to_string(),
                 "0.00".to_string(),
+                "0.00".to_string(),
+                "0.00".to_string(),
+                "100.00".to_string(),
+                "1".to_string(),
+                "0.00".to_string(),
+                "100.00".to_string(),
+                "100.00".to_string(),
             ],
         ],
     );
@@ -481,7 +583,7 @@ fn check_summary_data_3() {
 #[test]
 fn check_summary_sorting() {
     let file_name = "summary_sorting.sqlite";
-    let mut conn = create_test_db(&file_name);
+    let mut conn = create_test_db(file_name);
 
     add_tx(
         "2022-08-19",
@@ -524,55 +626,73 @@ fn check_summary_sorting() {
     summary_modes.next();
 
     let my_summary = SummaryData::new(&conn);
-    let table_data = my_summary.get_table_data(&summary_modes, 0, 0);
+    let table_data = my_summary.get_table_data(&summary_modes, 0, 0, None, None);
 
     let sorted_data_1 = sort_table_data(table_data.clone(), &SortingType::ByTags);
     let sorted_data_2 = sort_table_data(table_data.clone(), &SortingType::ByIncome);
-    let sorted_data_3 = sort_table_data(table_data.clone(), &SortingType::ByExpense);
+    let sorted_data_3 = sort_table_data(table_data, &SortingType::ByExpense);
 
     let expected_data_1 = vec![
-        vec!["Bank", "2000.00", "0.00", "80.00", "0.00"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>(),
-        vec!["Car", "0.00", "1000.00", "0.00", "100.00"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>(),
-        vec!["Food", "500.00", "0.00", "20.00", "0.00"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>(),
+        vec![
+            "Bank", "2000.00", "0.00", "80.00", "0.00", "2000.00", "1", "2000.00",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>(),
+        vec![
+            "Car", "0.00", "1000.00", "0.00", "100.00", "-1000.00", "1", "1000.00",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>(),
+        vec![
+            "Food", "500.00", "0.00", "20.00", "0.00", "500.00", "1", "500.00",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>(),
     ];
 
     let expected_data_2 = vec![
-        vec!["Bank", "2000.00", "0.00", "80.00", "0.00"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>(),
-        vec!["Food", "500.00", "0.00", "20.00", "0.00"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>(),
-        vec!["Car", "0.00", "1000.00", "0.00", "100.00"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>(),
+        vec![
+            "Bank", "2000.00", "0.00", "80.00", "0.00", "2000.00", "1", "2000.00",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>(),
+        vec![
+            "Food", "500.00", "0.00", "20.00", "0.00", "500.00", "1", "500.00",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>(),
+        vec![
+            "Car", "0.00", "1000.00", "0.00", "100.00", "-1000.00", "1", "1000.00",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>(),
     ];
 
     let expected_data_3 = vec![
-        vec!["Car", "0.00", "1000.00", "0.00", "100.00"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>(),
-        vec!["Bank", "2000.00", "0.00", "80.00", "0.00"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>(),
-        vec!["Food", "500.00", "0.00", "20.00", "0.00"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>(),
+        vec![
+            "Car", "0.00", "1000.00", "0.00", "100.00", "-1000.00", "1", "1000.00",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>(),
+        vec![
+            "Bank", "2000.00", "0.00", "80.00", "0.00", "2000.00", "1", "2000.00",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>(),
+        vec![
+            "Food", "500.00", "0.00", "20.00", "0.00", "500.00", "1", "500.00",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>(),
     ];
 
     conn.close().unwrap();
@@ -582,3 +702,826 @@ fn check_summary_sorting() {
     assert_eq!(sorted_data_2, expected_data_2);
     assert_eq!(sorted_data_3, expected_data_3);
 }
+
+#[test]
+fn check_summary_grouped_data() {
+    let file_name = "summary_grouped_data.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    add_tx(
+        "2022-08-19",
+        "Testing transaction",
+        "test1",
+        "100.00",
+        "Expense",
+        "Coffee",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-08-20",
+        "Testing transaction",
+        "test1",
+        "300.00",
+        "Expense",
+        "Groceries, Coffee",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-08-21",
+        "Testing transaction",
+        "test1",
+        "100.00",
+        "Expense",
+        "Car",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    set_tag_group("Coffee", "Food", &mut conn).unwrap();
+    set_tag_group("Groceries", "Food", &mut conn).unwrap();
+    set_tag_group("Car", "Transport", &mut conn).unwrap();
+    set_tag_group("Car", "", &mut conn).unwrap();
+
+    let tag_groups = get_tag_groups(&conn);
+    let summary_modes = IndexedData::new_modes();
+    let my_summary = SummaryData::new(&conn);
+    let grouped_data =
+        my_summary.get_grouped_table_data(&summary_modes, 7, 0, None, None, &tag_groups);
+
+    let expected_data = vec![
+        vec![
+            "Car", "0.00", "100.00", "0.00", "20.00", "-100.00", "1", "100.00",
+        ],
+        vec![
+            "Food", "0.00", "400.00", "0.00", "80.00", "-400.00", "2", "300.00",
+        ],
+    ];
+
+    let biggest_group_tx =
+        my_summary.get_tag_biggest_tx(&summary_modes, 7, 0, None, None, &tag_groups, "Food");
+    let biggest_tag_tx =
+        my_summary.get_tag_biggest_tx(&summary_modes, 7, 0, None, None, &HashMap::new(), "Coffee");
+    let missing_tx =
+        my_summary.get_tag_biggest_tx(&summary_modes, 7, 0, None, None, &tag_groups, "Coffee");
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(tag_groups.len(), 2);
+    assert_eq!(grouped_data, expected_data);
+
+    let biggest_group_tx = biggest_group_tx.unwrap();
+    assert_eq!(biggest_group_tx[3], "300.00");
+    assert_eq!(biggest_group_tx[5], "Groceries, Coffee");
+    assert_eq!(biggest_tag_tx.unwrap()[3], "300.00");
+    assert_eq!(missing_tx, None);
+}
+
+#[test]
+fn check_summary_transfers() {
+    let file_name = "summary_transfers.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    add_tx(
+        "2022-08-19",
+        "Salary",
+        "test1",
+        "1000.00",
+        "Income",
+        "Salary",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-08-20",
+        "Move to savings",
+        "test1 to test 2",
+        "300.00",
+        "Transfer",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-08-25",
+        "Move back",
+        "test 2 to test1",
+        "50.00",
+        "Transfer",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    let summary_modes = IndexedData::new_modes();
+    let my_summary = SummaryData::new(&conn);
+
+    let (summary_data_1, .., method_data) =
+        my_summary.get_tx_data(&summary_modes, 7, 0, None, None, &conn);
+
+    let expected_method_data = vec![
+        vec![
+            "test1", "1000.00", "0.00", "100.00%", "0.00", "1000.00", "0.00", "50.00", "300.00",
+            "1000.00", "3", "0.00", "750.00", "750.00",
+        ],
+        vec![
+            "test 2", "0.00", "0.00", "0.00", "0.00", "0.00", "0.00", "300.00", "50.00", "0.00",
+            "2", "0.00", "250.00", "250.00",
+        ],
+    ];
+
+    assert_eq!(method_data, expected_method_data);
+    assert_eq!(summary_data_1[0][1], "1000.00");
+    assert_eq!(summary_data_1[1][1], "0.00");
+
+    let expected_table_data = vec![vec![
+        "Salary", "1000.00", "0.00", "100.00", "0.00", "1000.00", "1", "1000.00",
+    ]];
+
+    assert_eq!(
+        my_summary.get_table_data(&summary_modes, 7, 0, None, None),
+        expected_table_data
+    );
+
+    set_setting("summary_include_transfers", "true", &conn).unwrap();
+    let (summary_data_1, .., method_data) =
+        my_summary.get_tx_data(&summary_modes, 7, 0, None, None, &conn);
+    // the tag table never counts transfers
+    let table_data = my_summary.get_table_data(&summary_modes, 7, 0, None, None);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    let expected_method_data = vec![
+        vec![
+            "test1", "1050.00", "300.00", "105.00%", "0.00", "1050.00", "300.00", "50.00",
+            "300.00", "1000.00", "3", "0.00", "750.00", "750.00",
+        ],
+        vec![
+            "test 2", "300.00", "50.00", "30.00%", "0.00", "300.00", "50.00", "300.00", "50.00",
+            "0.00", "2", "0.00", "250.00", "250.00",
+        ],
+    ];
+
+    assert_eq!(method_data, expected_method_data);
+    assert_eq!(summary_data_1[0][1], "1000.00");
+    assert_eq!(summary_data_1[1][1], "0.00");
+    assert_eq!(table_data, expected_table_data);
+}
+
+#[test]
+fn check_tag_case_merge() {
+    let file_name = "tag_case_merge.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    for (tags, amount) in [
+        ("food", "10.00"),
+        ("Food", "20.00"),
+        ("Food, Rent", "30.00"),
+    ] {
+        add_tx(
+            "2022-08-19",
+            "Testing transaction",
+            "test1",
+            amount,
+            "Expense",
+            tags,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let summary_modes = IndexedData::new_modes();
+
+    let old_table = SummaryData::new(&conn).get_table_data(&summary_modes, 7, 0, None, None);
+    let old_status = get_bool_setting("tags_merged", &conn);
+
+    merge_tag_case_duplicates(&mut conn).unwrap();
+
+    let table = SummaryData::new(&conn).get_table_data(&summary_modes, 7, 0, None, None);
+    let status = get_bool_setting("tags_merged", &conn);
+
+    // typed tags are rewritten to the saved case
+    let mut typed_tags = "food, FOOD, rent".to_string();
+    let typed_status = TxData::new().verify_tags_forced(&mut typed_tags, &NameCache::new(), &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(old_table.len(), 3);
+    assert!(!old_status);
+
+    assert!(status);
+    assert_eq!(typed_status, VerifyingOutput::Accepted(AType::Tags));
+    assert_eq!(typed_tags, "Food, Rent");
+    assert_eq!(
+        table,
+        vec![
+            vec!["Food", "0.00", "60.00", "0.00", "66.67", "-60.00", "3", "30.00"],
+            vec!["Rent", "0.00", "30.00", "0.00", "33.33", "-30.00", "1", "30.00"],
+        ]
+    );
+}
+
+#[test]
+fn check_summary_range_mode() {
+    let file_name = "summary_range_mode.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    for (date, method, amount, tx_type, tags) in [
+        ("2022-08-19", "test1", "100.00", "Expense", "Car"),
+        ("2023-07-19", "test 2", "100.00", "Expense", "Food"),
+        ("2023-07-25", "test1", "200.00", "Income", "Food"),
+        ("2023-08-02", "test 2", "50.00", "Expense", "Car"),
+        ("2023-09-01", "test1", "75.00", "Expense", "Rent"),
+    ] {
+        add_tx(
+            date,
+            "Testing transaction",
+            method,
+            amount,
+            tx_type,
+            tags,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let mut summary_modes = IndexedData::new_summary_modes();
+    summary_modes.index = RANGE_MODE;
+
+    let clock = FixedClock::new(NaiveDate::from_ymd_opt(2023, 8, 31).unwrap());
+    let mut range_data = SummaryRangeData::new_with_clock(&clock, &conn);
+
+    // the last 90 days until the clock's date
+    let default_start = range_data.get_start_date().to_string();
+    let default_end = range_data.get_end_date().to_string();
+
+    // move the start date to 2023-07-20
+    for _ in 0..default_start.len() {
+        range_data.edit_start_date(None);
+    }
+    for c in "2023-07-20".chars() {
+        range_data.edit_start_date(Some(c));
+    }
+    range_data.verify_dates(&conn);
+
+    let custom_range = range_data.get_active_range(&summary_modes, &conn);
+
+    let my_summary = SummaryData::new(&conn);
+    let table_data = my_summary.get_table_data(&summary_modes, 0, 0, custom_range, None);
+    let (summary_data_1, _, summary_data_3, _, method_data) =
+        my_summary.get_tx_data(&summary_modes, 0, 0, custom_range, None, &conn);
+
+    // the range is ignored by the other modes
+    let monthly_range = range_data.get_active_range(&IndexedData::new_summary_modes(), &conn);
+
+    // an end date before the start date can not be used
+    range_data.step_end_date(StepType::StepDown, DateStepSize::Year, &conn);
+    let inverted_range = range_data.get_range(&conn);
+    let inverted_active = range_data.get_active_range(&summary_modes, &conn);
+    let no_range_table = my_summary.get_table_data(&summary_modes, 0, 0, inverted_active, None);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(default_start, "2023-06-03");
+    assert_eq!(default_end, "2023-08-31");
+
+    assert_eq!(
+        custom_range,
+        Some((
+            NaiveDate::from_ymd_opt(2023, 7, 20).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 8, 31).unwrap()
+        ))
+    );
+
+    let expected_table = vec![
+        vec![
+            "Car", "0.00", "50.00", "0.00", "100.00", "-50.00", "1", "50.00",
+        ],
+        vec![
+            "Food", "200.00", "0.00", "100.00", "0.00", "200.00", "1", "200.00",
+        ],
+    ];
+    assert_eq!(table_data, expected_table);
+
+    assert_eq!(summary_data_1[0][1], "200.00");
+    assert_eq!(summary_data_1[1][1], "50.00");
+    assert_eq!(summary_data_3[0][1], "25-07-2023");
+    assert_eq!(summary_data_3[1][1], "02-08-2023");
+    assert_eq!(summary_data_3[2][1], "2");
+
+    assert_eq!(method_data[0][1], "200.00");
+    assert_eq!(method_data[1][2], "50.00");
+
+    assert_eq!(monthly_range, None);
+
+    assert_eq!(
+        inverted_range,
+        Err("Range: End date cannot be before the start date".to_string())
+    );
+    assert_eq!(inverted_active, None);
+    assert!(no_range_table.is_empty());
+}
+
+#[test]
+fn check_summary_sort_columns() {
+    let to_rows = |rows: Vec<[&str; 5]>| {
+        rows.iter()
+            .map(|row| row.iter().map(|s| s.to_string()).collect::<Vec<String>>())
+            .collect::<Vec<Vec<String>>>()
+    };
+
+    let table_data = to_rows(vec![
+        ["Bank", "2000.00", "0.00", "80.00", "0.00"],
+        ["Car", "0.00", "1000.00", "0.00", "9.00"],
+        ["Food", "500.00", "0.00", "20.00", "0.00"],
+        ["Rent", "0.00", "10,100.00", "0.00", "91.00"],
+    ]);
+
+    let tags = |data: Vec<Vec<String>>| {
+        data.into_iter()
+            .map(|row| row[0].to_string())
+            .collect::<Vec<String>>()
+    };
+
+    // amounts are compared as numbers, 10,100.00 is the highest expense and 9.00 not above 91.00
+    let by_expense = sort_table_data(table_data.clone(), &SortingType::ByExpense);
+    let by_expense_percentage =
+        sort_table_data(table_data.clone(), &SortingType::ByExpensePercentage);
+    let by_income_percentage =
+        sort_table_data(table_data.clone(), &SortingType::ByIncomePercentage);
+
+    // flipped directions. Rows with the same value stay in the tag order
+    let by_expense_ascending = sort_table_data_by(
+        table_data.clone(),
+        &SortingType::ByExpense,
+        SortOrder::Ascending,
+    );
+    let by_tags_descending =
+        sort_table_data_by(table_data, &SortingType::ByTags, SortOrder::Descending);
+
+    assert_eq!(tags(by_expense), vec!["Rent", "Car", "Bank", "Food"]);
+    assert_eq!(
+        tags(by_expense_percentage),
+        vec!["Rent", "Car", "Bank", "Food"]
+    );
+    assert_eq!(
+        tags(by_income_percentage),
+        vec!["Bank", "Food", "Car", "Rent"]
+    );
+    assert_eq!(
+        tags(by_expense_ascending),
+        vec!["Bank", "Food", "Car", "Rent"]
+    );
+    assert_eq!(
+        tags(by_tags_descending),
+        vec!["Rent", "Food", "Car", "Bank"]
+    );
+
+    assert_eq!(SortingType::ByTags.default_order(), SortOrder::Ascending);
+    assert_eq!(SortingType::ByIncome.default_order(), SortOrder::Descending);
+}
+
+#[test]
+fn check_summary_excluded_tags() {
+    let file_name = "summary_excluded_tags.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    add_tx(
+        "2022-08-19",
+        "Salary",
+        "test1",
+        "1000.00",
+        "Income",
+        "Salary",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-08-20",
+        "Work trip",
+        "test1",
+        "100.00",
+        "Expense",
+        "Reimbursable",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-08-21",
+        "Team dinner",
+        "test1",
+        "300.00",
+        "Expense",
+        "Food, Reimbursable",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-08-22",
+        "Lunch",
+        "test1",
+        "100.00",
+        "Expense",
+        "Food",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    set_tag_excluded("Reimbursable", true, &mut conn).unwrap();
+    set_tag_excluded("Salary", true, &mut conn).unwrap();
+    set_tag_excluded("Salary", false, &mut conn).unwrap();
+
+    let excluded_tags = get_excluded_tags(&conn);
+
+    let summary_modes = IndexedData::new_modes();
+    let mut yearly_modes = IndexedData::new_modes();
+    yearly_modes.next();
+
+    let my_summary = SummaryData::new(&conn);
+    let table_data = my_summary.get_table_data(&summary_modes, 7, 0, None, None);
+    let (summary_data_1, .., method_data) =
+        my_summary.get_tx_data(&summary_modes, 7, 0, None, None, &conn);
+    let (yearly_data_1, ..) = my_summary.get_tx_data(&yearly_modes, 7, 0, None, None, &conn);
+
+    let expected_table = vec![
+        vec![
+            "Food", "0.00", "400.00", "0.00", "100.00", "-400.00", "2", "300.00",
+        ],
+        vec![
+            "Reimbursable",
+            "0.00",
+            "400.00",
+            "-",
+            "-",
+            "-400.00",
+            "2",
+            "300.00",
+        ],
+        vec![
+            "Salary", "1000.00", "0.00", "100.00", "0.00", "1000.00", "1", "1000.00",
+        ],
+    ];
+
+    set_setting("summary_raw_view", "true", &conn).unwrap();
+
+    let raw_summary = SummaryData::new(&conn);
+    let raw_table = raw_summary.get_table_data(&summary_modes, 7, 0, None, None);
+    let (raw_data_1, ..) = raw_summary.get_tx_data(&summary_modes, 7, 0, None, None, &conn);
+
+    let expected_raw_table = vec![
+        vec![
+            "Food", "0.00", "400.00", "0.00", "50.00", "-400.00", "2", "300.00",
+        ],
+        vec![
+            "Reimbursable",
+            "0.00",
+            "400.00",
+            "0.00",
+            "50.00",
+            "-400.00",
+            "2",
+            "300.00",
+        ],
+        vec![
+            "Salary", "1000.00", "0.00", "100.00", "0.00", "1000.00", "1", "1000.00",
+        ],
+    ];
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(excluded_tags.len(), 1);
+    assert!(excluded_tags.contains("Reimbursable"));
+    assert!(my_summary.get_excluded_tags().contains("Reimbursable"));
+    assert!(raw_summary.get_excluded_tags().is_empty());
+
+    assert_eq!(table_data, expected_table);
+    assert_eq!(summary_data_1[1][1], "400.00");
+    assert_eq!(summary_data_1[2][1], "600.00");
+    assert_eq!(method_data[0][2], "400.00");
+    assert_eq!(yearly_data_1[1][1], "400.00");
+
+    assert_eq!(raw_table, expected_raw_table);
+    assert_eq!(raw_data_1[1][1], "500.00");
+}
+
+#[test]
+fn check_summary_method_filter() {
+    let file_name = "summary_method_filter.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    let txs = [
+        ("2022-08-19", "test1", "1000.00", "Income", "Salary"),
+        ("2022-08-20", "test1", "200.00", "Expense", "Food"),
+        ("2022-08-21", "test 2", "50.00", "Expense", "Food"),
+        ("2022-08-22", "test 2", "100.00", "Expense", "Car"),
+        (
+            "2022-08-23",
+            "test1 to test 2",
+            "300.00",
+            "Transfer",
+            "Unknown",
+        ),
+    ];
+
+    for (date, method, amount, tx_type, tags) in txs {
+        add_tx(
+            date,
+            "Testing transaction",
+            method,
+            amount,
+            tx_type,
+            tags,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let summary_modes = IndexedData::new_summary_modes();
+    let mut summary_methods = IndexedData::new_summary_methods(&conn);
+    let all_methods = summary_methods.get_summary_method().map(str::to_string);
+
+    let my_summary = SummaryData::new(&conn);
+
+    let (all_data_1, .., all_method_data) =
+        my_summary.get_tx_data(&summary_modes, 7, 0, None, all_methods.as_deref(), &conn);
+
+    summary_methods.next();
+    let first_method = summary_methods.get_summary_method().map(str::to_string);
+
+    let first_table =
+        my_summary.get_table_data(&summary_modes, 7, 0, None, first_method.as_deref());
+    let (first_data_1, .., first_method_data) =
+        my_summary.get_tx_data(&summary_modes, 7, 0, None, first_method.as_deref(), &conn);
+
+    summary_methods.next();
+    let second_method = summary_methods.get_summary_method().map(str::to_string);
+
+    let second_table =
+        my_summary.get_table_data(&summary_modes, 7, 0, None, second_method.as_deref());
+    let (second_data_1, .., second_method_data) =
+        my_summary.get_tx_data(&summary_modes, 7, 0, None, second_method.as_deref(), &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(summary_methods.titles, vec!["All", "test1", "test 2"]);
+    assert_eq!(all_methods, None);
+    assert_eq!(all_data_1[1][1], "350.00");
+    assert_eq!(all_method_data.len(), 2);
+
+    assert_eq!(first_method.as_deref(), Some("test1"));
+    assert_eq!(
+        first_table,
+        vec![
+            vec!["Food", "0.00", "200.00", "0.00", "100.00", "-200.00", "1", "200.00"],
+            vec!["Salary", "1000.00", "0.00", "100.00", "0.00", "1000.00", "1", "1000.00"],
+        ]
+    );
+    assert_eq!(first_data_1[0][1], "1000.00");
+    assert_eq!(first_data_1[1][1], "200.00");
+    assert_eq!(first_method_data.len(), 1);
+    assert_eq!(first_method_data[0][0], "test1");
+    assert_eq!(first_method_data[0][8], "300.00");
+
+    assert_eq!(second_method.as_deref(), Some("test 2"));
+    assert_eq!(
+        second_table,
+        vec![
+            vec!["Car", "0.00", "100.00", "0.00", "66.67", "-100.00", "1", "100.00"],
+            vec!["Food", "0.00", "50.00", "0.00", "33.33", "-50.00", "1", "50.00"],
+        ]
+    );
+    assert_eq!(second_data_1[0][1], "0.00");
+    assert_eq!(second_data_1[1][1], "150.00");
+    assert_eq!(second_method_data.len(), 1);
+    assert_eq!(second_method_data[0][0], "test 2");
+    assert_eq!(second_method_data[0][7], "300.00");
+}
+
+#[test]
+fn check_summary_comparison() {
+    let file_name = "summary_comparison.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    let txs = [
+        ("2022-12-10", "test1", "100.00", "Expense", "Food"),
+        ("2022-12-12", "test1", "40.00", "Expense", "Car"),
+        ("2022-12-15", "test 2", "500.00", "Income", "Salary"),
+        ("2023-01-05", "test1", "150.00", "Expense", "Food"),
+        ("2023-01-06", "test 2", "30.00", "Expense", "Rent"),
+        ("2023-01-07", "test1", "200.00", "Income", "Salary"),
+    ];
+
+    for (date, method, amount, tx_type, tags) in txs {
+        add_tx(
+            date,
+            "Testing transaction",
+            method,
+            amount,
+            tx_type,
+            tags,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let summary_modes = IndexedData::new_summary_modes();
+    let my_summary = SummaryData::new(&conn);
+
+    // January 2023 is compared with December 2022
+    let table_data =
+        my_summary.get_page_table_data(&summary_modes, 0, 1, None, None, &HashMap::new(), true);
+    let method_data =
+        my_summary.get_page_method_data(&summary_modes, 0, 1, None, None, true, &conn);
+
+    // the first month of the date range has nothing to compare with
+    let first_month_data = my_summary.get_comparison_table_data(0, 0, None, &HashMap::new());
+
+    // the comparison is only used by the Monthly mode
+    let mut yearly_mode = IndexedData::new_summary_modes();
+    yearly_mode.next();
+    let yearly_data =
+        my_summary.get_page_table_data(&yearly_mode, 0, 1, None, None, &HashMap::new(), true);
+    let yearly_method_data =
+        my_summary.get_page_method_data(&yearly_mode, 0, 1, None, None, true, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(
+        table_data,
+        vec![
+            vec!["Car", "0.00", "40.00", "-40.00", "-100.00"],
+            vec!["Food", "150.00", "100.00", "50.00", "50.00"],
+            vec!["Rent", "30.00", "0.00", "30.00", "-"],
+            vec!["Salary", "0.00", "0.00", "0.00", "-"],
+        ]
+    );
+    assert_eq!(
+        method_data,
+        vec![
+            vec!["test1", "200.00", "0.00", "200.00", "150.00", "140.00", "10.00"],
+            vec!["test 2", "0.00", "500.00", "-500.00", "30.00", "0.00", "30.00"],
+        ]
+    );
+    assert_eq!(first_month_data, Vec::<Vec<String>>::new());
+    assert_eq!(yearly_data[0].len(), 8);
+    assert_eq!(yearly_method_data[0].len(), 14);
+
+    assert_eq!(get_tag_table_header(false, true)[0], "Tags");
+    assert_eq!(
+        get_tag_table_header(true, false).len(),
+        yearly_data[0].len()
+    );
+    assert_eq!(get_method_table_header(true).len(), method_data[0].len());
+    assert_eq!(
+        get_method_table_header(false).len(),
+        yearly_method_data[0].len()
+    );
+}
+
+#[test]
+fn check_summary_time_averages() {
+    let file_name = "summary_time_averages.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    let txs = [
+        ("2022-08-05", "test1", "300.00", "Expense", "Food"),
+        ("2022-08-08", "test1", "900.00", "Income", "Salary"),
+        ("2022-09-10", "test 2", "60.00", "Expense", "Car"),
+    ];
+
+    for (date, method, amount, tx_type, tags) in txs {
+        add_tx(
+            date,
+            "Testing transaction",
+            method,
+            amount,
+            tx_type,
+            tags,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let summary_modes = IndexedData::new_summary_modes();
+    let mut yearly_modes = IndexedData::new_summary_modes();
+    yearly_modes.next();
+
+    let my_summary = SummaryData::new(&conn);
+
+    // August 2022 is the current month and 10 days of it have passed
+    let clock = FixedClock::new(NaiveDate::from_ymd_opt(2022, 8, 10).unwrap());
+    let (_, current_data, ..) =
+        my_summary.get_tx_data_with_clock(&clock, &summary_modes, 7, 0, None, None, &conn);
+
+    // a past month is averaged over all of its days
+    let clock = FixedClock::new(NaiveDate::from_ymd_opt(2022, 10, 1).unwrap());
+    let (_, past_data, ..) =
+        my_summary.get_tx_data_with_clock(&clock, &summary_modes, 8, 0, None, None, &conn);
+
+    // the current year only counts the months until the clock's date
+    let (_, yearly_data, ..) =
+        my_summary.get_tx_data_with_clock(&clock, &yearly_modes, 0, 0, None, None, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(
+        current_data[2..],
+        vec![
+            vec!["Daily Income", "90.00", "-"],
+            vec!["Daily Expense", "30.00", "-"],
+            vec!["Projected Expense", "930.00", "-"],
+        ]
+    );
+    assert_eq!(
+        past_data[2..],
+        vec![
+            vec!["Daily Income", "0.00", "-"],
+            vec!["Daily Expense", "2.00", "-"],
+            vec!["Projected Expense", "-", "-"],
+        ]
+    );
+    assert_eq!(
+        yearly_data[2..],
+        vec![
+            vec!["Monthly Income", "90.00", "-"],
+            vec!["Monthly Expense", "36.00", "-"],
+        ]
+    );
+}
+
+#[test]
+fn check_summary_tx_data_cache() {
+    let file_name = "summary_tx_data_cache.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    add_tx(
+        "2022-08-19",
+        "Testing transaction",
+        "test1",
+        "100.00",
+        "Expense",
+        "Food",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    let summary_modes = IndexedData::new_summary_modes();
+    let my_summary = SummaryData::new(&conn);
+
+    // every draw of the page with the same selection uses the first result
+    let first_data = my_summary.get_tx_data(&summary_modes, 7, 0, None, None, &conn);
+    for _ in 0..10 {
+        let tx_data = my_summary.get_tx_data(&summary_modes, 7, 0, None, None, &conn);
+        assert_eq!(tx_data, first_data);
+    }
+    let unchanged_count = my_summary.get_aggregation_count();
+
+    let other_month = my_summary.get_tx_data(&summary_modes, 6, 0, None, None, &conn);
+    my_summary.get_tx_data(&summary_modes, 7, 0, None, None, &conn);
+    let month_changed_count = my_summary.get_aggregation_count();
+
+    my_summary.get_tx_data(&summary_modes, 7, 0, None, Some("test1"), &conn);
+    set_setting("summary_include_transfers", "true", &conn).unwrap();
+    my_summary.get_tx_data(&summary_modes, 7, 0, None, None, &conn);
+    let inputs_changed_count = my_summary.get_aggregation_count();
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(unchanged_count, 1);
+    assert_eq!(other_month.0[1][1], "0.00");
+    assert_eq!(month_changed_count, 2);
+    assert_eq!(inputs_changed_count, 4);
+}
@@ -0,0 +1,250 @@
+extern crate rex_tui;
+use chrono::NaiveDate;
+use rex_tui::db::*;
+use rex_tui::tx_handler::{add_tx, parse_details_template, DetailsTemplateData, TemplatePart};
+use rex_tui::utility::*;
+use rusqlite::Connection;
+use std::fs;
+
+fn create_test_db(file_name: &str) -> Connection {
+    if let Ok(metadata) = fs::metadata(file_name) {
+        if metadata.is_file() {
+            fs::remove_file(file_name).expect("Failed to delete existing file");
+        }
+    }
+
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(vec!["test1".to_string(), "test 2".to_string()], &mut conn).unwrap();
+    conn
+}
+
+#[test]
+fn check_pending_reminders() {
+    let file_name = "pending_reminders.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    add_template(
+        "Rent",
+        "test1",
+        "1000.00",
+        "Expense",
+        "Home",
+        Some(5),
+        &conn,
+    )
+    .unwrap();
+    add_template(
+        "Gym",
+        "test 2",
+        "30.00",
+        "Expense",
+        "Health",
+        Some(20),
+        &conn,
+    )
+    .unwrap();
+    add_template("Coffee", "test1", "3.00", "Expense", "Food", None, &conn).unwrap();
+
+    let templates = get_templates(&conn);
+
+    let day_1 = NaiveDate::from_ymd_opt(2022, 7, 1).unwrap();
+    let day_10 = NaiveDate::from_ymd_opt(2022, 7, 10).unwrap();
+    let day_25 = NaiveDate::from_ymd_opt(2022, 7, 25).unwrap();
+
+    let before_remind_day = get_pending_reminders(day_1, &conn);
+    let after_first_day = get_pending_reminders(day_10, &conn);
+    let after_both_days = get_pending_reminders(day_25, &conn);
+
+    // a similar amount with the same method and tag counts as already added
+    add_tx(
+        "2022-07-03",
+        "Rent",
+        "test1",
+        "1020.00",
+        "Expense",
+        "Home",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    let after_adding = get_pending_reminders(day_25, &conn);
+
+    dismiss_reminder(templates[1].id_num, "2022-07", &conn).unwrap();
+
+    let after_dismissing = get_pending_reminders(day_25, &conn);
+    let next_month = get_pending_reminders(NaiveDate::from_ymd_opt(2022, 8, 25).unwrap(), &conn);
+
+    delete_template(templates[0].id_num, &mut conn).unwrap();
+    let remaining_templates = get_templates(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(templates.len(), 3);
+    assert_eq!(templates[0].remind_day, Some(5));
+    assert_eq!(templates[2].remind_day, None);
+
+    assert!(before_remind_day.is_empty());
+    assert_eq!(after_first_day, vec![templates[0].clone()]);
+    assert_eq!(
+        after_both_days,
+        vec![templates[0].clone(), templates[1].clone()]
+    );
+    assert_eq!(after_adding, vec![templates[1].clone()]);
+    assert!(after_dismissing.is_empty());
+    assert_eq!(next_month, vec![templates[0].clone(), templates[1].clone()]);
+    assert_eq!(remaining_templates.len(), 2);
+}
+
+#[test]
+fn check_month_end_reminders() {
+    let file_name = "month_end_reminders.sqlite";
+    let conn = create_test_db(file_name);
+
+    add_template(
+        "Savings",
+        "test1",
+        "200.00",
+        "Transfer",
+        "Savings",
+        Some(31),
+        &conn,
+    )
+    .unwrap();
+
+    let templates = get_templates(&conn);
+
+    let february_27 = get_pending_reminders(NaiveDate::from_ymd_opt(2023, 2, 27).unwrap(), &conn);
+    let february_28 = get_pending_reminders(NaiveDate::from_ymd_opt(2023, 2, 28).unwrap(), &conn);
+    let leap_february_28 =
+        get_pending_reminders(NaiveDate::from_ymd_opt(2024, 2, 28).unwrap(), &conn);
+    let leap_february_29 =
+        get_pending_reminders(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(), &conn);
+    let april_30 = get_pending_reminders(NaiveDate::from_ymd_opt(2023, 4, 30).unwrap(), &conn);
+    let december_30 = get_pending_reminders(NaiveDate::from_ymd_opt(2023, 12, 30).unwrap(), &conn);
+    let december_31 = get_pending_reminders(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(), &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert!(february_27.is_empty());
+    assert_eq!(february_28, templates);
+    assert!(leap_february_28.is_empty());
+    assert_eq!(leap_february_29, templates);
+    assert_eq!(april_30, templates);
+    assert!(december_30.is_empty());
+    assert_eq!(december_31, templates);
+}
+
+#[test]
+fn check_similar_txs() {
+    let file_name = "similar_txs.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    add_tx(
+        "2022-07-03",
+        "Rent",
+        "test1",
+        "1000.00",
+        "Expense",
+        "Home",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    let same = check_similar_tx("2022-07-01", "2022-07-31", "test1", "1000", "Home", &conn);
+    let close = check_similar_tx("2022-07-01", "2022-07-31", "test1", "950", "Home", &conn);
+    let far = check_similar_tx("2022-07-01", "2022-07-31", "test1", "500", "Home", &conn);
+    let other_method =
+        check_similar_tx("2022-07-01", "2022-07-31", "test 2", "1000", "Home", &conn);
+    let other_tag = check_similar_tx("2022-07-01", "2022-07-31", "test1", "1000", "Food", &conn);
+    let other_month = check_similar_tx("2022-08-01", "2022-08-31", "test1", "1000", "Home", &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert!(same);
+    assert!(close);
+    assert!(!far);
+    assert!(!other_method);
+    assert!(!other_tag);
+    assert!(!other_month);
+}
+
+#[test]
+fn check_details_templates() {
+    let file_name = "details_templates.sqlite";
+    let conn = create_test_db(file_name);
+
+    add_details_template("Lunch at {place}", &conn).unwrap();
+    add_details_template("Fuel {liters}L @ {price} {{full}}", &conn).unwrap();
+    add_details_template("Rent", &conn).unwrap();
+    delete_details_template(3, &conn).unwrap();
+
+    let templates = get_details_templates(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(templates.len(), 2);
+    assert_eq!(templates[0].text, "Lunch at {place}");
+
+    assert_eq!(
+        parse_details_template(&templates[1].text),
+        vec![
+            TemplatePart::Text("Fuel ".to_string()),
+            TemplatePart::Placeholder("liters".to_string()),
+            TemplatePart::Text("L @ ".to_string()),
+            TemplatePart::Placeholder("price".to_string()),
+            TemplatePart::Text(" {full}".to_string()),
+        ]
+    );
+    // braces that do not make a marker are kept
+    assert_eq!(
+        parse_details_template("a { b {c} d}"),
+        vec![
+            TemplatePart::Text("a { b ".to_string()),
+            TemplatePart::Placeholder("c".to_string()),
+            TemplatePart::Text(" d}".to_string()),
+        ]
+    );
+
+    let mut template_data = DetailsTemplateData::new(templates);
+    template_data.next();
+    assert!(!template_data.is_filling());
+    assert_eq!(template_data.select(), None);
+    assert_eq!(template_data.get_current_placeholder(), Some("liters"));
+
+    for c in "40x".chars() {
+        template_data.edit_input(Some(c));
+    }
+    template_data.edit_input(None);
+    assert_eq!(template_data.confirm_value(), None);
+    assert_eq!(template_data.get_preview(), "Fuel 40L @ {price} {full}");
+    assert_eq!(template_data.get_progress(), (2, 2));
+
+    for c in "1.85".chars() {
+        template_data.edit_input(Some(c));
+    }
+    assert_eq!(
+        template_data.confirm_value(),
+        Some("Fuel 40L @ 1.85 {full}".to_string())
+    );
+
+    // a repeated placeholder is asked once and a template without one is inserted right away
+    let mut repeated = DetailsTemplateData::new(vec![DetailsTemplate {
+        id_num: 1,
+        text: "{a} and {a}".to_string(),
+    }]);
+    repeated.select();
+    repeated.edit_input(Some('x'));
+    assert_eq!(repeated.confirm_value(), Some("x and x".to_string()));
+
+    let mut snippet = DetailsTemplateData::new(vec![DetailsTemplate {
+        id_num: 1,
+        text: "Groceries {{weekly}}".to_string(),
+    }]);
+    assert_eq!(snippet.select(), Some("Groceries {weekly}".to_string()));
+}
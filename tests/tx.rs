@@ -1,7 +1,9 @@
 extern crate rex_tui;
-use rex_tui::tx_handler::add_tx;
+use rex_tui::db::{add_tx_link, create_db, remove_tx_link, RELATED_LINK};
+use rex_tui::home_page::TransactionData;
+use rex_tui::page_handler::HomeFilter;
+use rex_tui::tx_handler::{add_tx, add_txs, delete_tx, TxDetailsData, TxPickerData};
 use rex_tui::utility::*;
-use rex_tui::{db::create_db, tx_handler::delete_tx};
 use rusqlite::{Connection, Result as sqlResult};
 use std::fs;
 
@@ -20,7 +22,7 @@ fn create_test_db(file_name: &str) -> Connection {
 #[test]
 fn check_last_tx_id_1() {
     let file_name = "last_tx_id_1.sqlite";
-    let conn = create_test_db(&file_name);
+    let conn = create_test_db(file_name);
 
     let data = get_last_tx_id(&conn);
     let expected_data: sqlResult<i32> = Err(rusqlite::Error::QueryReturnedNoRows);
@@ -34,7 +36,7 @@ fn check_last_tx_id_1() {
 #[test]
 fn check_last_tx_id_2() {
     let file_name = "last_tx_id_2.sqlite";
-    let mut conn = create_test_db(&file_name);
+    let mut conn = create_test_db(file_name);
 
     add_tx(
         "2022-09-19",
@@ -57,10 +59,88 @@ fn check_last_tx_id_2() {
     assert_eq!(data, expected_data);
 }
 
+#[test]
+fn check_added_tx_id() {
+    let file_name = "added_tx_id.sqlite";
+    let mut conn = create_test_db(file_name);
+    let mut other_conn = Connection::open(file_name).unwrap();
+
+    let first_id = add_tx(
+        "2022-09-19",
+        "First",
+        "test1",
+        "100.00",
+        "Income",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    // another process adding a transaction does not change the id returned to this one
+    let other_id = add_tx(
+        "2022-09-20",
+        "Other",
+        "test 2",
+        "50.00",
+        "Income",
+        "Unknown",
+        None,
+        &mut other_conn,
+    )
+    .unwrap();
+
+    let second_id = add_tx(
+        "2022-09-21",
+        "Second",
+        "test1",
+        "20.00",
+        "Expense",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    delete_tx(second_id as usize, &mut conn).unwrap();
+    let edited_id = add_tx(
+        "2022-09-21",
+        "Second edited",
+        "test1",
+        "25.00",
+        "Expense",
+        "Unknown",
+        Some(&second_id.to_string()),
+        &mut conn,
+    )
+    .unwrap();
+
+    let get_details = |id: i32| -> String {
+        conn.query_row("SELECT details FROM tx_all WHERE id_num = ?", [id], |row| {
+            row.get(0)
+        })
+        .unwrap()
+    };
+
+    let details = vec![
+        get_details(first_id),
+        get_details(other_id),
+        get_details(edited_id),
+    ];
+
+    other_conn.close().unwrap();
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!((first_id, other_id, second_id), (1, 2, 3));
+    assert_eq!(edited_id, second_id);
+    assert_eq!(details, vec!["First", "Other", "Second edited"]);
+}
+
 #[test]
 fn check_getting_all_tx_1() {
     let file_name = "getting_tx_1.sqlite";
-    let conn = create_test_db(&file_name);
+    let conn = create_test_db(file_name);
 
     let data = get_all_txs(&conn, 6, 0);
     let expected_data = (Vec::new(), Vec::new(), Vec::new());
@@ -74,7 +154,7 @@ fn check_getting_all_tx_1() {
 #[test]
 fn check_getting_all_tx_2() {
     let file_name = "getting_tx_2.sqlite";
-    let mut conn = create_test_db(&file_name);
+    let mut conn = create_test_db(file_name);
 
     add_tx(
         "2022-07-19",
@@ -263,7 +343,7 @@ fn check_getting_all_tx_2() {
 
 fn check_tx_columns() {
     let file_name = "tx_columns.sqlite";
-    let conn = create_test_db(&file_name);
+    let conn = create_test_db(file_name);
 
     let columns = get_all_tx_columns(&conn);
     let expected_data = vec![
@@ -282,3 +362,166 @@ fn check_tx_columns() {
     assert_eq!(columns, expected_data);
     assert_eq!(columns.len(), 7);
 }
+
+#[test]
+fn check_repeat_txs() {
+    let file_name = "repeat_txs.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    add_tx(
+        "2022-09-19",
+        "Bus",
+        "test1",
+        "1.50",
+        "Expense",
+        "Travel",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-09-19",
+        "Salary",
+        "test 2",
+        "500.00",
+        "Income",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    let txs = get_txs_by_date("2022-09-19", &conn);
+    let expected_txs = vec![
+        vec![
+            "2022-09-19".to_string(),
+            "Bus".to_string(),
+            "test1".to_string(),
+            "1.50".to_string(),
+            "Expense".to_string(),
+            "Travel".to_string(),
+        ],
+        vec![
+            "2022-09-19".to_string(),
+            "Salary".to_string(),
+            "test 2".to_string(),
+            "500.00".to_string(),
+            "Income".to_string(),
+            "Unknown".to_string(),
+        ],
+    ];
+
+    let before_repeat = check_duplicate_tx(
+        "2022-09-20",
+        "Bus",
+        "test1",
+        "1.5",
+        "Expense",
+        "Travel",
+        &conn,
+    );
+
+    let to_add = txs
+        .iter()
+        .map(|tx| {
+            let mut tx = tx.to_owned();
+            tx[0] = "2022-09-20".to_string();
+            tx
+        })
+        .collect::<Vec<Vec<String>>>();
+
+    add_txs(&to_add, &mut conn).unwrap();
+
+    let after_repeat = check_duplicate_tx(
+        "2022-09-20",
+        "Bus",
+        "test1",
+        "1.5",
+        "Expense",
+        "Travel",
+        &conn,
+    );
+    let repeated_txs = get_txs_by_date("2022-09-20", &conn);
+    let balance = get_last_balances(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(txs, expected_txs);
+    assert!(!before_repeat);
+    assert!(after_repeat);
+    assert_eq!(repeated_txs, to_add);
+    assert_eq!(balance, vec!["-3".to_string(), "1000".to_string()]);
+}
+
+#[test]
+fn check_tx_links() {
+    let file_name = "check_tx_links.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    for details in ["Deposit", "Deposit return", "Repair"] {
+        add_tx(
+            "2023-05-01",
+            details,
+            "test1",
+            "100.00",
+            "Expense",
+            "Unknown",
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    add_tx_link(1, 2, RELATED_LINK, &mut conn).unwrap();
+    add_tx_link(1, 3, RELATED_LINK, &mut conn).unwrap();
+    add_tx_link(2, 1, RELATED_LINK, &mut conn).unwrap();
+
+    let linked_ids = |id_num: i32, conn: &Connection| {
+        get_linked_txs(id_num, RELATED_LINK, conn)
+            .into_iter()
+            .map(|(id_num, _)| id_num)
+            .collect::<Vec<i32>>()
+    };
+
+    let first_links = linked_ids(1, &conn);
+    let second_links = linked_ids(2, &conn);
+    let linked_details = get_linked_txs(3, RELATED_LINK, &conn)[0].1[1].clone();
+
+    let mut picker = TxPickerData::new_link_targets(2, RELATED_LINK, &conn);
+    let link_targets = picker.get_matches().len();
+    picker.edit_query(Some('r'));
+    picker.edit_query(Some('e'));
+    picker.edit_query(Some('p'));
+    let picked = picker.get_selected();
+
+    let mut details_data = TxDetailsData::new(1, &conn).unwrap();
+    details_data.next();
+    let selected_link = details_data.get_selected_link();
+
+    remove_tx_link(3, 1, RELATED_LINK, &mut conn).unwrap();
+    details_data.reload_links(&conn);
+    let links_after_removal = details_data.get_links().len();
+    let selected_after_removal = details_data.get_selected_link();
+
+    let home_data = TransactionData::new(4, 1, &HomeFilter::All, &conn);
+    let first_index = (0..3).find(|index| home_data.get_id_num(*index) == 1);
+    home_data.del_tx(first_index.unwrap(), &mut conn).unwrap();
+    let links_after_deletion = linked_ids(2, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(first_links, vec![2, 3]);
+    assert_eq!(second_links, vec![1]);
+    assert_eq!(linked_details, "Deposit");
+
+    assert_eq!(link_targets, 1);
+    assert_eq!(picked, Some(3));
+
+    assert_eq!(selected_link, Some(3));
+    assert_eq!(links_after_removal, 1);
+    assert_eq!(selected_after_removal, Some(2));
+    assert!(links_after_deletion.is_empty());
+}
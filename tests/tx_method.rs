@@ -1,6 +1,13 @@
 extern crate rex_tui;
 use rex_tui::db::*;
-use rex_tui::utility::get_all_tx_methods;
+use rex_tui::outputs::{AType, NAType, StepType, VerifyingOutput};
+use rex_tui::page_handler::NameCache;
+use rex_tui::tx_handler::{add_tx, TxData};
+use rex_tui::utility::traits::{AutoFiller, DataVerifier, FieldStepper};
+use rex_tui::utility::{
+    get_active_tx_methods, get_all_tags, get_all_tx_methods, get_hidden_tx_methods,
+    get_last_balances, remove_hidden_method_columns,
+};
 use rusqlite::Connection;
 use std::fs;
 
@@ -54,3 +61,238 @@ fn check_getting_tx_methods_2() {
         ]
     );
 }
+
+#[test]
+fn check_tx_method_normalization() {
+    let file_name = "check_tx_method_normalization.sqlite";
+
+    if let Ok(metadata) = fs::metadata(file_name) {
+        if metadata.is_file() {
+            fs::remove_file(file_name).expect("Failed to delete existing file");
+        }
+    }
+
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(
+        vec!["  Cash ".to_string(), "Super   Bank".to_string()],
+        &mut conn,
+    )
+    .unwrap();
+
+    add_new_tx_methods(vec![" Pay  Pal ".to_string()], &mut conn).unwrap();
+
+    let data = get_all_tx_methods(&conn);
+    conn.close().unwrap();
+
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(data, vec!["Cash", "Super Bank", "Pay Pal"]);
+}
+
+#[test]
+fn check_merge_tx_methods() {
+    let file_name = "check_merge_tx_methods.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    // a method that only differs by spacing, as created before names were normalized
+    conn.execute(
+        r#"ALTER TABLE balance_all ADD COLUMN "test1 " REAL DEFAULT 0.00"#,
+        [],
+    )
+    .unwrap();
+    conn.execute(
+        r#"ALTER TABLE changes_all ADD COLUMN "test1 " TEXT DEFAULT 0.00"#,
+        [],
+    )
+    .unwrap();
+
+    add_tx(
+        "2023-05-01",
+        "Salary",
+        "test1",
+        "100.00",
+        "Income",
+        "Salary",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+    add_tx(
+        "2023-05-02",
+        "Lunch",
+        "test1 ",
+        "30.00",
+        "Income",
+        "Food",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+    add_tx(
+        "2023-05-03",
+        "Savings",
+        "test1  to test 2",
+        "10.00",
+        "Transfer",
+        "Savings",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    let collisions = rex_tui::utility::get_name_collisions(&get_all_tx_methods(&conn));
+
+    merge_tx_methods("test1 ", "test1", &mut conn).unwrap();
+
+    let methods = get_all_tx_methods(&conn);
+    let balances = get_last_balances(&conn);
+    let tx_methods = conn
+        .prepare("SELECT tx_method FROM tx_all ORDER BY id_num")
+        .unwrap()
+        .query_map([], |row| row.get::<_, String>(0))
+        .unwrap()
+        .flatten()
+        .collect::<Vec<String>>();
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(collisions, vec![vec!["test1", "test1 "]]);
+    assert_eq!(methods, vec!["test1", "test 2"]);
+    assert_eq!(balances, vec!["120", "10"]);
+    assert_eq!(tx_methods, vec!["test1", "test1", "test1 to test 2"]);
+}
+
+#[test]
+fn check_merge_tags() {
+    let file_name = "check_merge_tags.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    for tags in ["Food", "food, Car", "Food, food"] {
+        add_tx(
+            "2023-05-01",
+            "Testing transaction",
+            "test1",
+            "10.00",
+            "Expense",
+            tags,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    set_tag_group("food", "Daily", &mut conn).unwrap();
+
+    merge_tags("food", "Food", &mut conn).unwrap();
+
+    let all_tags = get_all_tags(&conn);
+    let row_tags = conn
+        .prepare("SELECT tags FROM tx_all ORDER BY id_num")
+        .unwrap()
+        .query_map([], |row| row.get::<_, String>(0))
+        .unwrap()
+        .flatten()
+        .collect::<Vec<String>>();
+    let group = conn
+        .query_row("SELECT tag FROM tag_groups", [], |row| {
+            row.get::<_, String>(0)
+        })
+        .unwrap();
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(all_tags, vec!["Car", "Food"]);
+    assert_eq!(row_tags, vec!["Food", "Food, Car", "Food"]);
+    assert_eq!(group, "Food");
+}
+
+#[test]
+fn check_hidden_tx_methods() {
+    let file_name = "check_hidden_tx_methods.sqlite";
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(
+        vec![
+            "Old Bank".to_string(),
+            "Cash".to_string(),
+            "Card".to_string(),
+            "Closed".to_string(),
+        ],
+        &mut conn,
+    )
+    .unwrap();
+
+    set_method_hidden("Old Bank", true, &mut conn).unwrap();
+    set_method_hidden("Closed", true, &mut conn).unwrap();
+
+    let tx_data = TxData::new();
+    let step = |method: &str, step_type: StepType| {
+        let mut method = method.to_string();
+        assert!(tx_data
+            .step_tx_method(&mut method, step_type, &NameCache::new(), &conn)
+            .is_ok());
+        method
+    };
+
+    // the first and the last methods are hidden so stepping wraps between the middle ones
+    let stepped = vec![
+        step("", StepType::StepUp),
+        step("", StepType::StepDown),
+        step("Card", StepType::StepUp),
+        step("Cash", StepType::StepDown),
+        step("Old Bank", StepType::StepUp),
+        step("Closed", StepType::StepDown),
+    ];
+
+    // a hidden method typed in full is still accepted but typos are never corrected to it
+    let mut full_method = "old bank".to_string();
+    let full_status = tx_data.verify_tx_method(&mut full_method, &NameCache::new(), &conn);
+    let mut typo_method = "Closde".to_string();
+    let typo_status = tx_data.verify_tx_method(&mut typo_method, &NameCache::new(), &conn);
+    let autofill = tx_data.autofill_tx_method("Clos", &NameCache::new(), &conn);
+
+    let active_methods = get_active_tx_methods(&conn);
+
+    let mut balance = vec![
+        vec!["", "Old Bank", "Cash", "Card", "Closed", "Total"]
+            .into_iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<String>>(),
+        vec!["Balance", "1", "2", "3", "4", "10"]
+            .into_iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<String>>(),
+    ];
+    remove_hidden_method_columns(&mut balance, &conn);
+
+    rename_column("Closed", "Closed Card", "no_archive.sqlite", &mut conn).unwrap();
+    let renamed_hidden = get_hidden_tx_methods(&conn);
+
+    set_method_hidden("Old Bank", false, &mut conn).unwrap();
+    let shown_methods = get_active_tx_methods(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(
+        stepped,
+        vec!["Cash", "Card", "Cash", "Card", "Cash", "Card"]
+    );
+
+    assert_eq!(full_status, VerifyingOutput::Accepted(AType::TxMethod));
+    assert_eq!(full_method, "Old Bank");
+    assert_eq!(
+        typo_status,
+        VerifyingOutput::NotAccepted(NAType::InvalidTxMethod)
+    );
+    assert_eq!(typo_method, "Closde");
+    assert_ne!(autofill, "Closed");
+
+    assert_eq!(active_methods, vec!["Cash", "Card"]);
+    assert_eq!(balance[0], vec!["", "Cash", "Card", "Total"]);
+    assert_eq!(balance[1], vec!["Balance", "2", "3", "10"]);
+
+    assert_eq!(renamed_hidden, vec!["Old Bank", "Closed Card"]);
+    assert_eq!(shown_methods, vec!["Old Bank", "Cash", "Card"]);
+}
@@ -1,7 +1,15 @@
 extern crate rex_tui;
-use chrono::{Datelike, Local};
-use rex_tui::db::{MODES, MONTHS, YEARS};
+use chrono::{Datelike, Local, NaiveDate, Weekday};
+use rex_tui::db::{create_db, set_setting, DateRange, MODES, MONTHS, YEARS};
 use rex_tui::page_handler::*;
+use rex_tui::tx_handler::{get_month_weeks, CalendarData, RepeatTxData, TxData, TxDeletionData};
+use rex_tui::utility::{
+    align_week, roll_over_month, select_month_of, step_week, AmountFormat, Clock, DateFormat,
+    FixedClock, WeekRange,
+};
+use rusqlite::Connection;
+use std::fs;
+use std::time::{Duration, Instant};
 
 #[test]
 fn test_table_data() {
@@ -59,7 +67,7 @@ fn test_indexed_data() {
     let local_year_index = Local::now().year() as usize - 2022;
 
     let mut index_data_monthly = IndexedData::new_monthly();
-    let mut index_data_yearly = IndexedData::new_yearly();
+    let mut index_data_yearly = IndexedData::new_yearly(&DateRange::default());
     let index_data_modes = IndexedData::new_modes();
 
     assert_eq!(index_data_monthly.titles, MONTHS);
@@ -83,3 +91,646 @@ fn test_indexed_data() {
     index_data_yearly.previous();
     assert_eq!(index_data_yearly.index, YEARS.len() - 2);
 }
+
+#[test]
+fn test_indexed_data_suffixes() {
+    let mut index_data_modes = IndexedData::new_modes();
+
+    // no suffixes set
+    assert_eq!(index_data_modes.get_titles(10), MODES);
+
+    index_data_modes.set_suffixes(vec![" (1)".to_string(), String::new(), " (3)*".to_string()]);
+
+    // "Monthly (1)", "Yearly", "All Time (3)*", "Range" padded, divided and bordered
+    let required_width = 11 + 6 + 13 + 5 + 4 * 3 + 1;
+
+    assert_eq!(
+        index_data_modes.get_titles(required_width),
+        vec!["Monthly (1)", "Yearly", "All Time (3)*", "Range"]
+    );
+    assert_eq!(index_data_modes.get_titles(required_width - 1), MODES);
+}
+
+#[test]
+fn test_home_filter() {
+    assert!(HomeFilter::All.includes("Food", true));
+    assert!(HomeFilter::All.includes("Unknown", false));
+
+    assert!(HomeFilter::Untagged.includes("Unknown", true));
+    assert!(HomeFilter::Untagged.includes("", true));
+    assert!(!HomeFilter::Untagged.includes("Food", false));
+
+    assert!(HomeFilter::Cleanup.includes("Unknown", true));
+    assert!(HomeFilter::Cleanup.includes("Food", false));
+    assert!(!HomeFilter::Cleanup.includes("Food", true));
+}
+
+#[test]
+fn test_navigation_stack() {
+    let mut navigation = NavigationStack::new();
+
+    // Home table row -> Summary tag -> Search result -> Home transaction
+    let home_state = PageState {
+        page: CurrentUi::Home,
+        mode_index: 0,
+        month_index: 4,
+        year_index: 1,
+        selected: Some(2),
+    };
+    let summary_state = PageState {
+        page: CurrentUi::Summary,
+        mode_index: 1,
+        month_index: 0,
+        year_index: 2,
+        selected: Some(3),
+    };
+    let search_state = PageState {
+        selected: Some(5),
+        ..PageState::new(CurrentUi::Search)
+    };
+
+    navigation.push(home_state);
+    navigation.push(summary_state);
+    navigation.push(search_state);
+
+    assert_eq!(navigation.len(), 3);
+
+    assert_eq!(navigation.pop(), Some(search_state));
+    assert_eq!(navigation.len(), 2);
+
+    let restored_summary = navigation.pop().unwrap();
+    assert_eq!(restored_summary.page, CurrentUi::Summary);
+    assert_eq!(restored_summary.mode_index, 1);
+    assert_eq!(restored_summary.year_index, 2);
+    assert_eq!(restored_summary.selected, Some(3));
+
+    let restored_home = navigation.pop().unwrap();
+    assert_eq!(restored_home.page, CurrentUi::Home);
+    assert_eq!(restored_home.month_index, 4);
+    assert_eq!(restored_home.year_index, 1);
+    assert_eq!(restored_home.selected, Some(2));
+
+    // nothing left, going back falls to the Home page
+    assert!(navigation.is_empty());
+    assert_eq!(navigation.pop(), None);
+
+    // opening a page directly forgets the path
+    navigation.push(home_state);
+    navigation.clear();
+    assert!(navigation.is_empty());
+}
+
+#[test]
+fn test_type_ahead() {
+    let rows = vec![
+        vec!["Bills".to_string(), "0.00".to_string()],
+        vec!["Car".to_string(), "0.00".to_string()],
+        vec!["Food".to_string(), "0.00".to_string()],
+        vec!["Fuel".to_string(), "0.00".to_string()],
+    ];
+
+    let start = Instant::now();
+    let mut type_ahead = TypeAhead::new();
+
+    assert!(type_ahead.is_empty());
+    assert_eq!(type_ahead.find_match(&rows), None);
+
+    type_ahead.push_at('f', start);
+    assert_eq!(type_ahead.find_match(&rows), Some(2));
+
+    type_ahead.push_at('U', start + Duration::from_millis(300));
+    assert_eq!(type_ahead.get_prefix(), "fU");
+    assert_eq!(type_ahead.find_match(&rows), Some(3));
+    assert!(type_ahead.is_match(&rows[3]));
+    assert!(!type_ahead.is_match(&rows[2]));
+
+    // nothing starts with the prefix so the selection stays where it is
+    type_ahead.push_at('x', start + Duration::from_millis(600));
+    assert_eq!(type_ahead.find_match(&rows), None);
+
+    type_ahead.pop();
+    assert_eq!(type_ahead.get_prefix(), "fU");
+
+    // a key typed after the timeout starts a new prefix
+    let later = start + Duration::from_millis(600) + TYPE_AHEAD_TIMEOUT;
+    assert!(type_ahead.is_expired_at(later + TYPE_AHEAD_TIMEOUT));
+    type_ahead.push_at('c', later + TYPE_AHEAD_TIMEOUT);
+    assert_eq!(type_ahead.get_prefix(), "c");
+    assert_eq!(type_ahead.find_match(&rows), Some(1));
+
+    type_ahead.clear();
+    assert!(type_ahead.is_empty());
+    assert!(!type_ahead.is_expired());
+}
+
+#[test]
+fn test_month_jump() {
+    let start = Instant::now();
+    let mut month_jump = MonthJump::new();
+
+    assert_eq!(month_jump.push_at('3', start), Some(2));
+    assert_eq!(month_jump.push_at('9', start), Some(8));
+    assert_eq!(month_jump.push_at('0', start), Some(9));
+    assert_eq!(month_jump.push_at('x', start), None);
+
+    // a 1 jumps to January right away and to a later month with the next number
+    assert_eq!(month_jump.push_at('1', start), Some(0));
+    assert_eq!(
+        month_jump.push_at('2', start + Duration::from_millis(300)),
+        Some(11)
+    );
+    assert_eq!(month_jump.push_at('1', start), Some(0));
+    assert_eq!(month_jump.push_at('1', start), Some(10));
+    assert_eq!(month_jump.push_at('1', start), Some(0));
+    assert_eq!(month_jump.push_at('0', start), Some(9));
+
+    // 1 and 3 can not be a month so the 3 is a new jump
+    assert_eq!(month_jump.push_at('1', start), Some(0));
+    assert_eq!(month_jump.push_at('3', start), Some(2));
+
+    // a number typed after the timeout is a new jump
+    assert_eq!(month_jump.push_at('1', start), Some(0));
+    assert_eq!(month_jump.push_at('2', start + TYPE_AHEAD_TIMEOUT), Some(1));
+
+    let date = |year: i32, month: u32, day: u32| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    let clock = FixedClock::new(date(2022, 3, 15));
+
+    let mut months = IndexedData::new_monthly_with_clock(&clock);
+    let mut years = IndexedData::new_yearly_with_clock(&clock, &DateRange::default());
+
+    assert!(select_month_of(&mut months, &mut years, date(2023, 11, 2)));
+    assert_eq!(months.index, 10);
+    assert_eq!(years.index, 1);
+
+    // a year outside of the range keeps the selection
+    assert!(!select_month_of(&mut months, &mut years, date(1990, 5, 2)));
+    assert_eq!(months.index, 10);
+    assert_eq!(years.index, 1);
+}
+
+#[test]
+fn test_period_skipping() {
+    let mut months = IndexedData::new_monthly();
+    months.index = 1;
+
+    // without the flags nothing is skipped
+    months.next_period(true);
+    assert_eq!(months.index, 2);
+
+    let mut empty = vec![true; 12];
+    empty[2] = false;
+    empty[7] = false;
+    months.set_empty(empty);
+
+    assert!(months.is_empty_at(0));
+    assert!(!months.is_empty_at(2));
+
+    months.next_period(true);
+    assert_eq!(months.index, 7);
+    months.next_period(true);
+    assert_eq!(months.index, 2);
+    months.previous_period(true);
+    assert_eq!(months.index, 7);
+    months.previous_period(false);
+    assert_eq!(months.index, 6);
+    months.next_period(false);
+    assert_eq!(months.index, 7);
+
+    // the only month with transactions stays selected
+    let mut empty = vec![true; 12];
+    empty[7] = false;
+    months.set_empty(empty);
+    months.next_period(true);
+    assert_eq!(months.index, 7);
+
+    // every month is empty so the arrows work as usual
+    months.set_empty(vec![true; 12]);
+    months.next_period(true);
+    assert_eq!(months.index, 8);
+    months.previous_period(true);
+    assert_eq!(months.index, 7);
+}
+
+#[test]
+fn test_day_rollover() {
+    let date = |year: i32, month: u32, day: u32| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+    let mut clock = FixedClock::new(date(2023, 1, 31));
+
+    let mut months = IndexedData::new_monthly_with_clock(&clock);
+    let mut years = IndexedData::new_yearly_with_clock(&clock, &DateRange::default());
+    let mut other_months = IndexedData::new_monthly_with_clock(&clock);
+    let mut other_years = IndexedData::new_yearly_with_clock(&clock, &DateRange::default());
+    let mut tx_data = TxData::new_with_clock(&clock);
+
+    assert_eq!(months.index, 0);
+    assert_eq!(years.index, 1);
+    assert_eq!(tx_data.get_all_texts()[0], "2023-01-31");
+
+    // the user moved away from the current month so it should not follow the clock
+    other_months.next();
+
+    let previous_day = clock.today();
+    clock.set(date(2023, 2, 1));
+
+    assert!(roll_over_month(
+        &mut months,
+        &mut years,
+        previous_day,
+        &clock
+    ));
+    assert!(!roll_over_month(
+        &mut other_months,
+        &mut other_years,
+        previous_day,
+        &clock
+    ));
+    tx_data.roll_date(previous_day, &clock, &DateFormat::Ymd);
+
+    assert_eq!(months.index, 1);
+    assert_eq!(years.index, 1);
+    assert_eq!(other_months.index, 1);
+    assert_eq!(tx_data.get_all_texts()[0], "2023-02-01");
+
+    // a date the user changed is kept
+    let mut tx_data = TxData::new_with_clock(&clock);
+    tx_data.edit_date(Some('1'));
+    let previous_day = clock.today();
+    clock.set(date(2023, 2, 2));
+    tx_data.roll_date(previous_day, &clock, &DateFormat::Ymd);
+
+    assert_eq!(tx_data.get_all_texts()[0], "12023-02-01");
+
+    // same month, nothing to move
+    assert!(!roll_over_month(
+        &mut months,
+        &mut years,
+        previous_day,
+        &clock
+    ));
+
+    let previous_day = clock.today();
+    clock.set(date(2024, 1, 1));
+
+    assert!(roll_over_month(
+        &mut months,
+        &mut years,
+        previous_day,
+        &clock
+    ));
+    assert_eq!(months.index, 0);
+    assert_eq!(years.index, 2);
+
+    let repeat_data = RepeatTxData::new_with_clock(&clock);
+    assert_eq!(repeat_data.get_date(), "2023-12-31");
+}
+
+#[test]
+fn check_home_highlight() {
+    let start = Instant::now();
+    let mut highlight = HomeHighlight::new();
+
+    assert!(!highlight.is_highlighted_at(1, start));
+    assert!(!highlight.is_expired_at(start));
+
+    highlight.start_at(1, start);
+
+    assert!(highlight.is_highlighted_at(1, start + Duration::from_millis(500)));
+    assert!(!highlight.is_highlighted_at(2, start + Duration::from_millis(500)));
+    assert!(!highlight.is_expired_at(start + Duration::from_millis(500)));
+
+    let later = start + HOME_HIGHLIGHT_TIMEOUT;
+    assert!(!highlight.is_highlighted_at(1, later));
+    assert!(highlight.is_expired_at(later));
+
+    highlight.clear();
+    assert!(!highlight.is_expired_at(later));
+}
+
+#[test]
+fn check_quit_status() {
+    let quit_status = QuitStatus::default();
+    assert_eq!(quit_status, QuitStatus::Wait);
+
+    let quit_status = quit_status.toggle();
+    assert_eq!(quit_status, QuitStatus::QuitAnyway);
+    assert_eq!(quit_status.toggle(), QuitStatus::Wait);
+}
+
+#[test]
+fn check_toasts() {
+    let start = Instant::now();
+    let toasts = Toasts::new();
+
+    assert!(toasts.is_empty());
+    assert!(!toasts.dismiss_newest());
+
+    // a cloned handle pushes to the same queue from another thread
+    let handle = toasts.clone();
+    std::thread::spawn(move || handle.push_at(StatusMessage::info("First"), start))
+        .join()
+        .unwrap();
+    toasts.push_at(
+        StatusMessage::warn("Second"),
+        start + Duration::from_secs(1),
+    );
+    toasts.push_at(
+        StatusMessage::error("Third"),
+        start + Duration::from_secs(2),
+    );
+    toasts.push_at(
+        StatusMessage::info("Fourth"),
+        start + Duration::from_secs(2),
+    );
+
+    // only the newest 3 are kept
+    let messages = toasts.get_messages();
+    assert_eq!(messages.len(), MAX_TOASTS);
+    assert_eq!(messages[0], StatusMessage::new("Second", Severity::Warn));
+    assert_eq!(messages[1].get_severity(), Severity::Error);
+    assert_eq!(messages[2].get_text(), "Fourth");
+
+    assert!(!toasts.is_expired_at(start + Duration::from_secs(2)));
+    let later = start + Duration::from_secs(1) + TOAST_TIMEOUT;
+    assert!(toasts.is_expired_at(later));
+
+    toasts.remove_expired_at(later);
+    assert_eq!(
+        toasts.get_messages(),
+        vec![StatusMessage::error("Third"), StatusMessage::info("Fourth")]
+    );
+
+    assert!(toasts.dismiss_newest());
+    assert_eq!(toasts.get_messages(), vec![StatusMessage::error("Third")]);
+    assert!(!toasts.is_expired_at(later));
+}
+
+#[test]
+fn check_calendar_grid() {
+    // May 2022 starts on a Sunday and has 31 days
+    let monday_weeks = get_month_weeks(2022, 5, Weekday::Mon);
+    let sunday_weeks = get_month_weeks(2022, 5, Weekday::Sun);
+
+    assert_eq!(monday_weeks.len(), 6);
+    assert_eq!(
+        monday_weeks[0],
+        [None, None, None, None, None, None, Some(1)]
+    );
+    assert_eq!(
+        monday_weeks[5],
+        [Some(30), Some(31), None, None, None, None, None]
+    );
+
+    assert_eq!(sunday_weeks.len(), 5);
+    assert_eq!(sunday_weeks[0][0], Some(1));
+    assert_eq!(
+        sunday_weeks[4],
+        [Some(29), Some(30), Some(31), None, None, None, None]
+    );
+
+    // February 2021 starts on a Monday and fills exactly 4 weeks
+    let february_weeks = get_month_weeks(2021, 2, Weekday::Mon);
+    assert_eq!(february_weeks.len(), 4);
+    assert_eq!(february_weeks[3][6], Some(28));
+
+    assert_eq!(
+        get_month_weeks(2024, 2, Weekday::Mon)
+            .concat()
+            .iter()
+            .flatten()
+            .count(),
+        29
+    );
+    assert!(get_month_weeks(2022, 13, Weekday::Mon).is_empty());
+}
+
+#[test]
+fn check_calendar_data() {
+    let date_range = DateRange::new(2022, 2023);
+    let today = NaiveDate::from_ymd_opt(2023, 3, 10).unwrap();
+
+    // opens on the date of the field in the date format
+    let mut calendar = CalendarData::new(
+        "31-01-2022",
+        &DateFormat::Dmy,
+        &date_range,
+        Weekday::Sun,
+        today,
+    );
+    assert_eq!(calendar.get_iso_date(), "2022-01-31");
+    assert_eq!(calendar.get_title(), "January 2022");
+    assert_eq!(
+        calendar.get_day_names(),
+        vec!["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"]
+    );
+
+    // the day is cut to the final day of a shorter month
+    calendar.next_month();
+    assert_eq!(calendar.get_iso_date(), "2022-02-28");
+    calendar.move_days(7);
+    assert_eq!(calendar.get_iso_date(), "2022-03-07");
+    calendar.move_days(-1);
+    assert_eq!(calendar.get_iso_date(), "2022-03-06");
+
+    // stops at the start of the year range
+    calendar.previous_month();
+    calendar.previous_month();
+    calendar.previous_month();
+    assert_eq!(calendar.get_iso_date(), "2022-01-01");
+    calendar.move_days(-1);
+    assert_eq!(calendar.get_iso_date(), "2022-01-01");
+
+    // invalid dates and dates outside of the range open on today
+    for field_date in ["", "2022-02-30", "2021-05-01", "abc"] {
+        let calendar = CalendarData::new(
+            field_date,
+            &DateFormat::Ymd,
+            &date_range,
+            Weekday::Mon,
+            today,
+        );
+        assert_eq!(calendar.get_selected(), today);
+    }
+
+    // stops at the end of the year range
+    let mut calendar = CalendarData::new(
+        "2023-12-20",
+        &DateFormat::Ymd,
+        &date_range,
+        Weekday::Mon,
+        today,
+    );
+    calendar.next_month();
+    assert_eq!(calendar.get_iso_date(), "2023-12-31");
+    assert_eq!(calendar.get_weeks()[0][4], Some(1));
+}
+
+#[test]
+fn check_tx_deletion_data() {
+    let tx = vec![
+        "12-05-2023".to_string(),
+        "Rent".repeat(30),
+        "Cash".to_string(),
+        "1450.00".to_string(),
+        "Expense".to_string(),
+        "Home".to_string(),
+    ];
+    let comma_format = AmountFormat::new(',', "$");
+
+    let deletion_data =
+        TxDeletionData::new(&tx, String::new(), 0.0, &DateFormat::Ymd, &comma_format);
+
+    let lines = deletion_data.get_lines();
+    assert_eq!(lines[0], "Date: 2023-05-12");
+    assert_eq!(lines[1].chars().count(), "Details: ".len() + 80);
+    assert!(lines[1].ends_with('…'));
+    assert_eq!(lines[3], "Amount: 1.450,00");
+    assert_eq!(lines[4], "Tx Type: Expense");
+    assert!(!deletion_data.needs_typing());
+    assert!(deletion_data.is_confirmed(&comma_format));
+
+    let mut deletion_data =
+        TxDeletionData::new(&tx, String::new(), 1000.0, &DateFormat::Ymd, &comma_format);
+
+    assert!(deletion_data.needs_typing());
+    assert!(!deletion_data.is_confirmed(&comma_format));
+
+    for c in "$1.450,5".chars() {
+        deletion_data.push(c);
+    }
+    assert!(!deletion_data.is_confirmed(&comma_format));
+
+    deletion_data.push('0');
+    assert_eq!(deletion_data.get_typed(), "$1.450,50");
+    assert!(!deletion_data.is_confirmed(&comma_format));
+
+    deletion_data.pop();
+    deletion_data.pop();
+    deletion_data.push('0');
+    assert!(deletion_data.is_confirmed(&comma_format));
+
+    for _ in 0..10 {
+        deletion_data.pop();
+    }
+    for c in "YES".chars() {
+        deletion_data.push(c);
+    }
+    assert!(deletion_data.is_confirmed(&comma_format));
+}
+
+#[test]
+fn check_week_range() {
+    let date = |year: i32, month: u32, day: u32| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+    // 2024-06-12 is a Wednesday
+    let week = WeekRange::containing(date(2024, 6, 12), Weekday::Mon);
+    let sunday_week = WeekRange::containing(date(2024, 6, 12), Weekday::Sun);
+
+    assert_eq!(week.get_start(), date(2024, 6, 10));
+    assert_eq!(week.get_end(), date(2024, 6, 16));
+    assert_eq!(week.get_label(), "Jun 10 – Jun 16");
+    assert_eq!(sunday_week.get_label(), "Jun 9 – Jun 15");
+    assert_eq!(WeekRange::containing(date(2024, 6, 10), Weekday::Mon), week);
+    assert_eq!(week.next().get_start(), date(2024, 6, 17));
+    assert_eq!(week.previous().get_start(), date(2024, 6, 3));
+    assert!(week.contains(date(2024, 6, 16)));
+    assert!(!week.contains(date(2024, 6, 17)));
+
+    let june_weeks = WeekRange::in_month(5, 2024, Weekday::Mon);
+    assert_eq!(june_weeks.len(), 5);
+    assert_eq!(june_weeks[0].get_label(), "May 27 – Jun 2");
+    assert_eq!(june_weeks[4].get_label(), "Jun 24 – Jun 30");
+    assert_eq!(june_weeks[0].get_months(), vec![(4, 2024), (5, 2024)]);
+    assert_eq!(week.get_months(), vec![(5, 2024)]);
+
+    let clock = FixedClock::new(date(2022, 3, 15));
+    let mut months = IndexedData::new_monthly_with_clock(&clock);
+    let mut years = IndexedData::new_yearly_with_clock(&clock, &DateRange::default());
+    select_month_of(&mut months, &mut years, date(2023, 12, 20));
+
+    // the week reaching into the next year keeps December selected
+    let mut week = WeekRange::containing(date(2023, 12, 20), Weekday::Mon);
+    assert!(step_week(&mut week, true, &mut months, &mut years));
+    assert_eq!(week.get_label(), "Dec 25 – Dec 31");
+    assert!(step_week(&mut week, true, &mut months, &mut years));
+    assert_eq!(week.get_label(), "Jan 1 – Jan 7");
+    assert_eq!((months.index, years.index), (0, 2));
+
+    assert!(step_week(&mut week, false, &mut months, &mut years));
+    assert_eq!((months.index, years.index), (11, 1));
+
+    // the first week of the range can not go further back
+    select_month_of(&mut months, &mut years, date(2022, 1, 10));
+    let mut first_week = WeekRange::containing(date(2022, 1, 1), Weekday::Mon);
+    assert!(step_week(&mut first_week, true, &mut months, &mut years));
+    assert!(step_week(&mut first_week, false, &mut months, &mut years));
+    assert!(!step_week(&mut first_week, false, &mut months, &mut years));
+    assert_eq!(first_week.get_label(), "Dec 27 – Jan 2");
+
+    // a changed month moves the week to the first week of it
+    months.index = 2;
+    align_week(&mut first_week, &months, &years, Weekday::Mon);
+    assert_eq!(first_week.get_label(), "Feb 28 – Mar 6");
+    align_week(&mut first_week, &months, &years, Weekday::Mon);
+    assert_eq!(first_week.get_label(), "Feb 28 – Mar 6");
+}
+
+#[test]
+fn check_display_settings() {
+    let file_name = "display_settings.sqlite";
+    fs::remove_file(file_name).ok();
+
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(vec!["test1".to_string(), "test 2".to_string()], &mut conn).unwrap();
+    set_setting("date_format", "dmy", &conn).unwrap();
+
+    let mut settings = DisplaySettings::new(&conn);
+    let default_settings = (
+        settings.is_day_grouped(),
+        settings.is_showing_hidden_methods(),
+        settings.is_summary_raw_view(),
+        settings.is_summary_including_transfers(),
+    );
+
+    // changing the saved value does not change the loaded settings until they are loaded again
+    set_setting("home_day_groups", &(!default_settings.0).to_string(), &conn).unwrap();
+    let grouped_before_reload = settings.is_day_grouped();
+    set_setting("home_day_groups", &default_settings.0.to_string(), &conn).unwrap();
+
+    settings.toggle_day_groups(&conn);
+    settings.toggle_hidden_methods(&conn);
+    settings.toggle_summary_raw_view(&conn);
+    settings.toggle_summary_transfers(&conn);
+
+    let toggled_settings = (
+        settings.is_day_grouped(),
+        settings.is_showing_hidden_methods(),
+        settings.is_summary_raw_view(),
+        settings.is_summary_including_transfers(),
+    );
+
+    let reloaded = DisplaySettings::new(&conn);
+    let reloaded_settings = (
+        reloaded.is_day_grouped(),
+        reloaded.is_showing_hidden_methods(),
+        reloaded.is_summary_raw_view(),
+        reloaded.is_summary_including_transfers(),
+    );
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(settings.get_date_format(), DateFormat::Dmy);
+    assert_eq!(settings.get_amount_format(), &AmountFormat::default());
+    assert_eq!(grouped_before_reload, default_settings.0);
+    assert_eq!(
+        toggled_settings,
+        (
+            !default_settings.0,
+            !default_settings.1,
+            !default_settings.2,
+            !default_settings.3
+        )
+    );
+    assert_eq!(reloaded_settings, toggled_settings);
+}
@@ -1,7 +1,23 @@
 extern crate rex_tui;
+use chrono::NaiveDate;
+use rex_tui::chart_page::{
+    format_axis_date, get_monthly_expense_points, get_monthly_income_expense,
+    get_monthly_stacked_expense, get_monthly_tag_income_expense, get_total_points,
+    get_weekly_income_expense, ChartData,
+};
+use rex_tui::db::{
+    add_template, get_month_viewed, set_tag_bounds, set_tag_group, set_tx_reviewed, update_tx_tags,
+    DateRange,
+};
+use rex_tui::home_page::{DashboardData, HomeRow, MonthBadges, TransactionData};
+use rex_tui::page_handler::{HomeFilter, IndexedData, SearchGrouping, TxPeriods};
+use rex_tui::summary_page::SummaryRangeData;
+use rex_tui::tx_handler::TagEditData;
 use rex_tui::{db::create_db, tx_handler::add_tx, utility::*};
 use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::time::Duration;
 
 fn create_test_db(file_name: &str) -> Connection {
     if let Ok(metadata) = fs::metadata(file_name) {
@@ -78,14 +94,14 @@ fn check_restricted_test() {
     let is_restricted = check_restricted("cancel", None);
     let not_restricted = check_restricted("some word", None);
 
-    assert_eq!(is_restricted, true);
-    assert_eq!(not_restricted, false);
+    assert!(is_restricted);
+    assert!(!not_restricted);
 
     let is_restricted = check_restricted("unknown", Some(&word_list));
     let not_restricted = check_restricted("some word", Some(&word_list));
 
-    assert_eq!(is_restricted, true);
-    assert_eq!(not_restricted, false);
+    assert!(is_restricted);
+    assert!(!not_restricted);
 }
 
 #[test]
@@ -110,3 +126,1365 @@ more stuff"
 
     assert_eq!(parsed, expected_data);
 }
+
+#[test]
+fn check_amount_formatting() {
+    assert_eq!(format_amount(0.0, 2), "0.00");
+    assert_eq!(format_amount(1204.0, 2), "1,204.00");
+    assert_eq!(format_amount(-1204.0, 2), "-1,204.00");
+    assert_eq!(format_amount(-32.5405, 2), "-32.54");
+    assert_eq!(format_amount(1234567.891, 2), "1,234,567.89");
+    assert_eq!(format_amount(1234567.891, 0), "1,234,568");
+    assert_eq!(format_amount(1204.5, 3), "1,204.500");
+}
+
+#[test]
+fn check_separated_lines() {
+    let fields = vec![
+        "2023-05-01".to_string(),
+        "Rent, \"May\"".to_string(),
+        "Bank\tCard".to_string(),
+    ];
+
+    assert_eq!(
+        to_separated_line(&fields, ','),
+        "2023-05-01,\"Rent, \"\"May\"\"\",Bank\tCard"
+    );
+    assert_eq!(
+        to_separated_line(&fields, '\t'),
+        "2023-05-01\t\"Rent, \"\"May\"\"\"\t\"Bank\tCard\""
+    );
+
+    let rows = vec![
+        vec![
+            "15-05-2022".to_string(),
+            "Groceries".to_string(),
+            "Cash".to_string(),
+            "12.50".to_string(),
+            "Expense".to_string(),
+            "Food".to_string(),
+        ],
+        vec![
+            "05-2022".to_string(),
+            "1 txs".to_string(),
+            "".to_string(),
+            "-12.50".to_string(),
+            "Subtotal".to_string(),
+            "".to_string(),
+        ],
+    ];
+
+    assert_eq!(
+        get_tx_table_tsv(&rows[..1], &DateFormat::Dmy, false),
+        "15-05-2022\tGroceries\tCash\t12.50\tExpense\tFood"
+    );
+    assert_eq!(
+        get_tx_table_tsv(&rows, &DateFormat::Ymd, true),
+        "Date\tDetails\tTX Method\tAmount\tType\tTags\n\
+         2022-05-15\tGroceries\tCash\t12.50\tExpense\tFood\n\
+         05-2022\t1 txs\t\t-12.50\tSubtotal\t"
+    );
+}
+
+#[test]
+fn check_summary_table_tsv() {
+    let rows = vec![
+        vec![
+            "Food".to_string(),
+            "0.00".to_string(),
+            "1250.50".to_string(),
+            "0.00%".to_string(),
+        ],
+        vec![
+            "Salary".to_string(),
+            "3000.00".to_string(),
+            "0.00".to_string(),
+            "100.00%".to_string(),
+        ],
+    ];
+    let header = ["Tags", "Total Income", "Total Expense", "Income %"];
+    let excluded_tags = HashSet::from(["Salary".to_string()]);
+
+    assert_eq!(
+        get_summary_table_tsv(&header, &rows, &AmountFormat::default(), &HashSet::new()),
+        "Tags\tTotal Income\tTotal Expense\tIncome %\n\
+         Food\t0.00\t1,250.50\t0.00%\n\
+         Salary\t3,000.00\t0.00\t100.00%"
+    );
+    assert_eq!(
+        get_summary_table_tsv(&header, &rows, &AmountFormat::new(',', ""), &excluded_tags),
+        "Tags\tTotal Income\tTotal Expense\tIncome %\n\
+         Food\t0,00\t1.250,50\t0,00%\n\
+         Salary (excluded)\t3.000,00\t0,00\t100,00%"
+    );
+    assert_eq!(
+        get_summary_table_tsv(&header, &[], &AmountFormat::default(), &HashSet::new()),
+        "Tags\tTotal Income\tTotal Expense\tIncome %"
+    );
+}
+
+#[test]
+fn check_table_position() {
+    // 20 lines leave 17 for the rows after the borders and the header
+    assert_eq!(get_table_position(None, 0, 17, 20), None);
+    assert_eq!(get_table_position(Some(16), 0, 17, 20), None);
+    assert_eq!(get_table_position(Some(0), 0, 0, 0), None);
+
+    assert_eq!(
+        get_table_position(Some(5), 0, 100, 20),
+        Some("6/100".to_string())
+    );
+    assert_eq!(
+        get_table_position(Some(99), 83, 100, 20),
+        Some("100/100".to_string())
+    );
+    assert_eq!(
+        get_table_position(None, 41, 100, 20),
+        Some("42/100".to_string())
+    );
+    assert_eq!(
+        get_table_position(Some(150), 0, 100, 20),
+        Some("100/100".to_string())
+    );
+}
+
+#[test]
+fn check_rank_by_usage() {
+    let values = vec![
+        "Car".to_string(),
+        "Food".to_string(),
+        "Gym".to_string(),
+        "Rent".to_string(),
+    ];
+
+    let mut usage: UsageData = HashMap::new();
+    usage.insert("Food".to_string(), (10, "2022-01-10".to_string()));
+    usage.insert("Rent".to_string(), (5, "2022-01-01".to_string()));
+    usage.insert("Gym".to_string(), (2, "2022-03-01".to_string()));
+
+    // without any recency boost only the frequency matters
+    let ranked = rank_by_usage(values.clone(), &usage, 0.0);
+    let expected = vec![
+        "Food".to_string(),
+        "Rent".to_string(),
+        "Gym".to_string(),
+        "Car".to_string(),
+    ];
+    assert_eq!(ranked, expected);
+
+    // all used values are within the 5 most recent, the boost keeps them above unused ones
+    let ranked = rank_by_usage(values, &usage, 0.5);
+    assert_eq!(ranked, expected);
+
+    // with more than 5 used values, only the 5 most recent get the boost
+    for (index, tag) in ["A", "B", "C", "D"].iter().enumerate() {
+        usage.insert(tag.to_string(), (1, format!("2022-02-0{}", index + 1)));
+    }
+
+    let values = vec![
+        "A".to_string(),
+        "B".to_string(),
+        "C".to_string(),
+        "D".to_string(),
+        "Food".to_string(),
+        "Gym".to_string(),
+        "Rent".to_string(),
+    ];
+
+    // Food is used the most but is no longer among the 5 most recent values
+    let ranked = rank_by_usage(values, &usage, 1.0);
+    let expected = vec![
+        "Gym".to_string(),
+        "A".to_string(),
+        "B".to_string(),
+        "C".to_string(),
+        "D".to_string(),
+        "Food".to_string(),
+        "Rent".to_string(),
+    ];
+    assert_eq!(ranked, expected);
+
+    let ranked = rank_by_usage(vec!["Car".to_string()], &HashMap::new(), 0.5);
+    assert_eq!(ranked, vec!["Car".to_string()]);
+
+    let ranked = rank_by_usage(vec!["Car".to_string(), "Gym".to_string()], &usage, f64::NAN);
+    assert_eq!(ranked.len(), 2);
+}
+
+#[test]
+fn check_usage_data() {
+    let file_name = "usage_data.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    add_tx(
+        "2022-07-19",
+        "Testing transaction",
+        "test1",
+        "100.00",
+        "Income",
+        "Food, Car",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-08-19",
+        "Testing transaction",
+        "test1",
+        "100.00",
+        "Expense",
+        "Food",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    add_tx(
+        "2022-09-19",
+        "Testing transaction",
+        "test1 to test 2",
+        "100.00",
+        "Transfer",
+        "Car",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    let (tag_usage, method_usage) = get_usage_data(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(tag_usage["Food"], (2, "2022-08-19".to_string()));
+    assert_eq!(tag_usage["Car"], (2, "2022-09-19".to_string()));
+    assert_eq!(method_usage["test1"], (3, "2022-09-19".to_string()));
+    assert_eq!(method_usage["test 2"], (1, "2022-09-19".to_string()));
+}
+
+#[test]
+fn check_search_grouping() {
+    let tx = |date: &str, amount: &str, tx_type: &str| {
+        vec![
+            date.to_string(),
+            "Details".to_string(),
+            "test1".to_string(),
+            amount.to_string(),
+            tx_type.to_string(),
+            "Food".to_string(),
+        ]
+    };
+
+    let subtotal = |group: &str, total: usize, amount: &str| {
+        vec![
+            group.to_string(),
+            format!("Subtotal of {total} transactions"),
+            String::new(),
+            amount.to_string(),
+            "Subtotal".to_string(),
+            String::new(),
+        ]
+    };
+
+    let search_txs = vec![
+        tx("19-08-2022", "100.00", "Expense"),
+        tx("25-08-2022", "500.00", "Income"),
+        tx("19-09-2022", "50.00", "Expense"),
+        tx("20-09-2022", "10.00", "Transfer"),
+        tx("01-01-2023", "20.50", "Expense"),
+    ];
+
+    assert_eq!(
+        group_search_txs(&search_txs, SearchGrouping::None),
+        search_txs
+    );
+
+    let expected_month = vec![
+        search_txs[0].clone(),
+        search_txs[1].clone(),
+        subtotal("08-2022", 2, "400.00"),
+        search_txs[2].clone(),
+        search_txs[3].clone(),
+        subtotal("09-2022", 2, "-50.00"),
+        search_txs[4].clone(),
+        subtotal("01-2023", 1, "-20.50"),
+    ];
+
+    let expected_year = vec![
+        search_txs[0].clone(),
+        search_txs[1].clone(),
+        search_txs[2].clone(),
+        search_txs[3].clone(),
+        subtotal("2022", 4, "350.00"),
+        search_txs[4].clone(),
+        subtotal("2023", 1, "-20.50"),
+    ];
+
+    assert_eq!(
+        group_search_txs(&search_txs, SearchGrouping::Month),
+        expected_month
+    );
+    assert_eq!(
+        group_search_txs(&search_txs, SearchGrouping::Year),
+        expected_year
+    );
+
+    // groups follow the order of the results
+    let reversed_txs = search_txs
+        .iter()
+        .rev()
+        .cloned()
+        .collect::<Vec<Vec<String>>>();
+    let grouped = group_search_txs(&reversed_txs, SearchGrouping::Year);
+
+    assert_eq!(grouped[1], subtotal("2023", 1, "-20.50"));
+    assert_eq!(grouped[6], subtotal("2022", 4, "350.00"));
+}
+
+#[test]
+fn check_token_matching() {
+    let details = "REWE Markt Koeln 1234";
+
+    assert_eq!(match_tokens("rewe koeln", details), Some(2));
+    assert_eq!(match_tokens("koeln rewe", details), Some(2));
+    assert_eq!(match_tokens("re ko", details), Some(2));
+    assert_eq!(match_tokens("MAR 12", details), Some(4));
+    assert_eq!(match_tokens("rewe berlin", details), None);
+    assert_eq!(match_tokens("arkt", details), None);
+    assert_eq!(match_tokens("   ", details), None);
+
+    let candidates = vec![
+        "Lunch at Koeln Station".to_string(),
+        "REWE Markt Koeln 1234".to_string(),
+        "Koeln REWE".to_string(),
+        "Salary".to_string(),
+    ];
+
+    assert_eq!(
+        get_best_token_match("rewe koeln", &candidates),
+        Some("Koeln REWE".to_string())
+    );
+    assert_eq!(
+        get_best_token_match("koeln", &candidates),
+        Some("Koeln REWE".to_string())
+    );
+    assert_eq!(
+        get_best_token_match("sal", &candidates),
+        Some("Salary".to_string())
+    );
+    assert_eq!(get_best_token_match("berlin", &candidates), None);
+
+    let file_name = "check_token_matching.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    for details in ["REWE Markt Koeln 1234", "Koeln Market", "REWE Berlin"] {
+        add_tx(
+            "2022-08-19",
+            details,
+            "test1",
+            "100.00",
+            "Expense",
+            "Food",
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let (search_txs, ..) = get_search_data("", "koeln rewe", "", "", "", "", "", &conn);
+    let (partial_txs, ..) = get_search_data("", "mark", "", "", "", "", "", &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(search_txs.len(), 1);
+    assert_eq!(search_txs[0][1], "REWE Markt Koeln 1234");
+    assert_eq!(partial_txs.len(), 2);
+}
+
+#[test]
+fn check_search_by_id() {
+    assert_eq!(get_searched_id("#12"), Some(12));
+    assert_eq!(get_searched_id(" # 7 "), Some(7));
+    assert_eq!(get_searched_id("12"), None);
+    assert_eq!(get_searched_id("#rent"), None);
+
+    let file_name = "check_search_by_id.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    for details in ["Rent", "Groceries", "Rent"] {
+        add_tx(
+            "2022-08-19",
+            details,
+            "test1",
+            "100.00",
+            "Expense",
+            "Home",
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    // other fields are ignored once an id is searched
+    let (found_txs, found_ids, _) =
+        get_search_data("", "#2", "test2", "", "5", "Income", "", &conn);
+    let (missing_txs, ..) = get_search_data("", "#99", "", "", "", "", "", &conn);
+    let full_tx = get_tx_by_id(2, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(found_txs.len(), 1);
+    assert_eq!(found_txs[0][1], "Groceries");
+    assert_eq!(found_ids, vec!["2".to_string()]);
+    assert!(missing_txs.is_empty());
+    assert_eq!(
+        full_tx,
+        Some(vec![
+            "2022-08-19".to_string(),
+            "Groceries".to_string(),
+            "test1".to_string(),
+            "100.00".to_string(),
+            "Expense".to_string(),
+            "Home".to_string(),
+        ])
+    );
+}
+
+#[test]
+fn check_tx_periods() {
+    let file_name = "check_tx_periods.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    for date in ["2022-05-01", "2022-05-20", "2023-01-10"] {
+        add_tx(
+            date,
+            "Testing transaction",
+            "test1",
+            "10.00",
+            "Expense",
+            "Food",
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let periods = get_tx_periods(&conn);
+
+    let mut tx_periods = TxPeriods::new();
+    tx_periods.update(&conn);
+
+    let mut months = IndexedData::new_monthly();
+    let mut years = IndexedData::new_yearly(&DateRange::default());
+    years.index = 0;
+
+    tx_periods.mark_tabs(&mut months, &mut years);
+    let empty_2022_months = months.empty.clone();
+    let empty_years = years.empty.clone();
+
+    add_tx(
+        "2024-03-02",
+        "Testing transaction",
+        "test1",
+        "10.00",
+        "Expense",
+        "Food",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    // the new transaction is picked up once the db changed
+    tx_periods.update(&conn);
+    tx_periods.mark_tabs(&mut months, &mut years);
+    let added_years = years.empty.clone();
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(
+        periods,
+        HashSet::from(["2022-05".to_string(), "2023-01".to_string()])
+    );
+
+    assert!(tx_periods.has_month("2022", 4));
+    assert!(!tx_periods.has_month("2022", 5));
+    assert!(tx_periods.has_year("2024"));
+    assert!(!tx_periods.has_year("2025"));
+
+    let mut expected_months = vec![true; 12];
+    expected_months[4] = false;
+    assert_eq!(empty_2022_months, expected_months);
+
+    assert_eq!(&empty_years[..3], &[false, false, true]);
+    assert_eq!(&added_years[..3], &[false, false, false]);
+}
+
+#[test]
+fn check_month_badges() {
+    let file_name = "check_month_badges.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    for date in ["2023-05-01", "2023-05-20", "2023-07-10"] {
+        add_tx(
+            date,
+            "Testing transaction",
+            "test1",
+            "10.00",
+            "Expense",
+            "Food",
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let counts = get_month_tx_counts(2023, &conn);
+
+    let mut month_badges = MonthBadges::new();
+
+    // months that were never viewed are not marked
+    month_badges.update(4, 1, &conn);
+    let first_suffixes = month_badges.get_suffixes();
+
+    add_tx(
+        "2023-07-11",
+        "Testing transaction",
+        "test1",
+        "10.00",
+        "Expense",
+        "Food",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    month_badges.update(4, 1, &conn);
+    let added_suffixes = month_badges.get_suffixes();
+
+    // viewing July clears the marker
+    month_badges.update(6, 1, &conn);
+    month_badges.update(4, 1, &conn);
+    let viewed_suffixes = month_badges.get_suffixes();
+
+    let july_viewed = get_month_viewed(7, 2023, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(counts[4], (2, 2));
+    assert_eq!(counts[6], (1, 3));
+    assert_eq!(counts[0], (0, 0));
+
+    assert_eq!(first_suffixes[4], " (2)");
+    assert_eq!(first_suffixes[6], " (1)");
+    assert_eq!(first_suffixes[0], "");
+
+    assert_eq!(added_suffixes[6], " (2)*");
+    assert_eq!(viewed_suffixes[6], " (2)");
+    assert_eq!(july_viewed, Some(4));
+}
+
+#[test]
+fn check_name_normalization() {
+    let existing = vec!["Cash".to_string(), "Super Bank".to_string()];
+
+    assert_eq!(normalize_name("  Super    Bank  "), "Super Bank");
+    assert_eq!(normalize_name("Cash"), "Cash");
+
+    assert_eq!(
+        get_colliding_name("cash ", &existing),
+        Some("Cash".to_string())
+    );
+    assert_eq!(
+        get_colliding_name("super  BANK", &existing),
+        Some("Super Bank".to_string())
+    );
+    assert_eq!(get_colliding_name("Cash Card", &existing), None);
+
+    let names = vec![
+        "Cash".to_string(),
+        "Bank".to_string(),
+        "cash ".to_string(),
+        "CASH".to_string(),
+    ];
+
+    assert_eq!(
+        get_name_collisions(&names),
+        vec![vec!["Cash", "cash ", "CASH"]]
+    );
+    assert!(get_name_collisions(&existing).is_empty());
+}
+
+#[test]
+fn check_home_filter() {
+    let file_name = "check_home_filter.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    for (details, tags) in [("Lunch", "Food"), ("Bus", ""), ("Gift", "Unknown")] {
+        add_tx(
+            "2023-05-01",
+            details,
+            "test1",
+            "10.00",
+            "Expense",
+            tags,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let all_data = TransactionData::new(4, 1, &HomeFilter::All, &conn);
+    let untagged_data = TransactionData::new(4, 1, &HomeFilter::Untagged, &conn);
+    let cleanup_data = TransactionData::new(4, 1, &HomeFilter::Cleanup, &conn);
+
+    set_tx_reviewed(1, true, &mut conn).unwrap();
+    set_tx_reviewed(2, true, &mut conn).unwrap();
+    let reviewed_data = TransactionData::new(4, 1, &HomeFilter::Cleanup, &conn);
+
+    update_tx_tags(2, "Food", &mut conn).unwrap();
+    let tagged_data = TransactionData::new(4, 1, &HomeFilter::Cleanup, &conn);
+
+    set_tx_reviewed(1, false, &mut conn).unwrap();
+    let unreviewed_data = TransactionData::new(4, 1, &HomeFilter::Cleanup, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(all_data.all_tx.len(), 3);
+    assert_eq!(all_data.get_reviewed(), &vec![false, false, false]);
+
+    assert_eq!(untagged_data.all_tx.len(), 2);
+    assert_eq!(untagged_data.get_id_num(0), 2);
+    assert_eq!(untagged_data.get_id_num(1), 3);
+
+    assert_eq!(cleanup_data.all_tx.len(), 3);
+
+    // reviewed but untagged transactions are still shown
+    assert_eq!(reviewed_data.all_tx.len(), 2);
+    assert_eq!(reviewed_data.get_reviewed(), &vec![true, false]);
+
+    assert_eq!(tagged_data.all_tx.len(), 1);
+    assert_eq!(tagged_data.get_id_num(0), 3);
+
+    assert_eq!(unreviewed_data.all_tx.len(), 2);
+    assert_eq!(unreviewed_data.get_id_num(0), 1);
+}
+
+#[test]
+fn check_tag_edit() {
+    let file_name = "check_tag_edit.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    for tags in ["Food, Car", ""] {
+        add_tx(
+            "2023-05-01",
+            "Testing transaction",
+            "test1",
+            "10.00",
+            "Expense",
+            tags,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let mut tag_data = TagEditData::new(2, "Testing transaction", "Unknown");
+    let starting_tags = tag_data.get_tags().to_string();
+
+    for c in "Food, Rent".chars() {
+        tag_data.edit_tags(Some(c));
+    }
+
+    // non-existing tags are dropped and nothing gets saved
+    let rejected = tag_data.save_tags(&mut conn);
+    let rejected_data = TransactionData::new(4, 1, &HomeFilter::Untagged, &conn);
+    let rejected_tags = tag_data.get_tags().to_string();
+
+    let saved = tag_data.save_tags(&mut conn);
+    let saved_data = TransactionData::new(4, 1, &HomeFilter::Untagged, &conn);
+    let all_data = TransactionData::new(4, 1, &HomeFilter::All, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(starting_tags, "");
+
+    assert!(rejected.is_err());
+    assert_eq!(rejected_tags, "Food");
+    assert_eq!(rejected_data.all_tx.len(), 1);
+
+    assert!(saved.is_ok());
+    assert!(tag_data.get_status().is_empty());
+    assert!(saved_data.all_tx.is_empty());
+    assert_eq!(all_data.all_tx[1][5], "Food");
+}
+
+#[test]
+fn check_tag_edit_new_tags() {
+    let file_name = "check_tag_edit_new_tags.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    for tag in ["Food", "Unknown"] {
+        add_tx(
+            "2022-07-19",
+            "Testing transaction",
+            "test1",
+            "100.00",
+            "Expense",
+            tag,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let mut tag_data = TagEditData::new(2, "Testing transaction", "Unknown");
+
+    for c in "food, Vacation, Hotel, vacation".chars() {
+        tag_data.edit_tags(Some(c));
+    }
+
+    // every unknown tag is listed once and declining brings back the typed tags
+    let declined = tag_data.save_tags(&mut conn);
+    let declined_unknown = tag_data.get_unknown_tags().clone();
+    let declined_prompt = tag_data.get_unknown_tags_prompt();
+    tag_data.discard_unknown_tags();
+    let restored_tags = tag_data.get_tags().to_string();
+    let declined_data = TransactionData::new(6, 0, &HomeFilter::Untagged, &conn);
+
+    let asked_again = tag_data.save_tags(&mut conn);
+    let created = tag_data.create_unknown_tags(&mut conn);
+    let all_tags = get_all_tags(&conn);
+    let created_data = TransactionData::new(6, 0, &HomeFilter::All, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert!(declined.is_err());
+    assert_eq!(declined_unknown, vec!["Vacation", "Hotel"]);
+    assert_eq!(
+        declined_prompt,
+        "Tags 'Vacation', 'Hotel' don't exist. Create them? [Y/N]"
+    );
+    assert_eq!(restored_tags, "food, Vacation, Hotel, vacation");
+    assert!(!tag_data.has_unknown_tags());
+    assert_eq!(declined_data.all_tx.len(), 1);
+
+    assert!(asked_again.is_err());
+    assert!(created.is_ok());
+    assert_eq!(tag_data.get_tags(), "Food, Vacation, Hotel");
+    assert!(tag_data.get_status().is_empty());
+    assert!(all_tags.contains(&"Vacation".to_string()));
+    assert!(all_tags.contains(&"Hotel".to_string()));
+    assert_eq!(created_data.all_tx[1][5], "Food, Vacation, Hotel");
+}
+
+#[test]
+fn check_dashboard_data() {
+    let file_name = "check_dashboard_data.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    let txs = [
+        ("Salary", "100.00", "Income", "Job"),
+        ("Lunch", "30.00", "Expense", "Food"),
+        ("Bus", "5.00", "Expense", "Car"),
+        ("Groceries", "10.00", "Expense", "Food, Home"),
+        ("Gift", "2.00", "Expense", "Other"),
+    ];
+
+    for (details, amount, tx_type, tags) in txs {
+        add_tx(
+            "2023-05-01",
+            details,
+            "test1",
+            amount,
+            tx_type,
+            tags,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    set_tag_bounds("Food", None, Some(20.0), &mut conn).unwrap();
+    add_template("Rent", "test1", "50.00", "Expense", "Home", Some(5), &conn).unwrap();
+
+    let clock = FixedClock::new(NaiveDate::from_ymd_opt(2023, 5, 20).unwrap());
+    let tx_data = TransactionData::new(4, 1, &HomeFilter::All, &conn);
+    let dashboard = DashboardData::new(&tx_data, &clock, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(dashboard.get_month(), "May 2023");
+    assert_eq!(dashboard.get_total_balance(), 53.0);
+    assert_eq!(dashboard.get_income(), 100.0);
+    assert_eq!(dashboard.get_expense(), 47.0);
+    assert_eq!(dashboard.get_net(), 53.0);
+
+    assert_eq!(
+        dashboard.get_top_tags(),
+        &vec![
+            ("Food".to_string(), 40.0),
+            ("Home".to_string(), 10.0),
+            ("Car".to_string(), 5.0)
+        ]
+    );
+    assert_eq!(
+        dashboard.get_warnings(),
+        &vec!["Lunch: Food expected - to 20.00".to_string()]
+    );
+    assert_eq!(dashboard.get_reminders(), &vec!["Day 5: Rent".to_string()]);
+
+    assert!(!dashboard.is_auto_closing());
+    assert!(!dashboard.is_expired());
+
+    let auto_closing = dashboard.with_auto_close(Duration::ZERO);
+    assert!(auto_closing.is_auto_closing());
+    assert!(auto_closing.is_expired());
+}
+
+#[test]
+fn check_monthly_income_expense() {
+    let file_name = "monthly_income_expense.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    let txs = [
+        ("2023-01-05", "test1", "500.00", "Income", "Salary"),
+        ("2023-01-09", "test1", "120.50", "Expense", "Food"),
+        ("2023-01-20", "test 2", "30.25", "Expense", "Car"),
+        (
+            "2023-03-02",
+            "test1 to test 2",
+            "50.00",
+            "Transfer",
+            "Unknown",
+        ),
+        ("2023-12-31", "test 2", "10.00", "Income", "Gift"),
+        ("2022-12-31", "test1", "99.00", "Expense", "Food"),
+    ];
+
+    for (date, method, amount, tx_type, tags) in txs {
+        add_tx(
+            date,
+            "Testing transaction",
+            method,
+            amount,
+            tx_type,
+            tags,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let monthly_data = get_monthly_income_expense(2023, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(monthly_data.len(), 12);
+    assert_eq!(monthly_data[0], ("January".to_string(), 500.0, 150.75));
+    // transfers and months without transactions are zero
+    assert_eq!(monthly_data[1], ("February".to_string(), 0.0, 0.0));
+    assert_eq!(monthly_data[2], ("March".to_string(), 0.0, 0.0));
+    assert_eq!(monthly_data[11], ("December".to_string(), 10.0, 0.0));
+}
+
+#[test]
+fn check_monthly_tag_income_expense() {
+    let file_name = "monthly_tag_income_expense.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    let txs = [
+        ("2023-01-05", "500.00", "Income", "Salary"),
+        ("2023-01-09", "120.50", "Expense", "Food"),
+        ("2023-01-20", "30.25", "Expense", "Food, Car"),
+        ("2023-04-11", "15.00", "Income", "Food"),
+        ("2023-07-02", "40.00", "Expense", "Seafood"),
+        ("2022-12-31", "99.00", "Expense", "Food"),
+    ];
+
+    for (date, amount, tx_type, tags) in txs {
+        add_tx(
+            date,
+            "Testing transaction",
+            "test1",
+            amount,
+            tx_type,
+            tags,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let food_data = get_monthly_tag_income_expense(2023, "Food", &conn);
+    let missing_data = get_monthly_tag_income_expense(2023, "Travel", &conn);
+    let chart_tags = IndexedData::new_chart_tags(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(food_data.len(), 12);
+    assert_eq!(food_data[0], ("January".to_string(), 0.0, 150.75));
+    assert_eq!(food_data[3], ("April".to_string(), 15.0, 0.0));
+    // a tag is matched exactly, not as a part of another tag
+    assert_eq!(food_data[6], ("July".to_string(), 0.0, 0.0));
+    assert_eq!(food_data[11], ("December".to_string(), 0.0, 0.0));
+
+    assert_eq!(missing_data.len(), 12);
+    assert!(missing_data
+        .iter()
+        .all(|(_, income, expense)| *income == 0.0 && *expense == 0.0));
+
+    assert_eq!(chart_tags.titles[0], "All");
+    assert_eq!(chart_tags.get_chart_tag(), None);
+    assert!(chart_tags.titles.contains(&"Food".to_string()));
+}
+
+#[test]
+fn check_chart_range_data() {
+    let file_name = "chart_range_data.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    let txs = [
+        ("2022-12-31", "100.00", "Income"),
+        ("2023-06-01", "50.00", "Income"),
+        ("2023-06-15", "20.00", "Expense"),
+        ("2023-09-10", "10.00", "Expense"),
+        ("2024-02-15", "5.00", "Expense"),
+        ("2024-02-16", "1.00", "Expense"),
+    ];
+
+    for (date, amount, tx_type) in txs {
+        add_tx(
+            date,
+            "Testing transaction",
+            "test1",
+            amount,
+            tx_type,
+            "Food",
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let chart_data = ChartData::new(&conn);
+
+    let start_date = NaiveDate::from_ymd_opt(2023, 6, 1).unwrap();
+    let end_date = NaiveDate::from_ymd_opt(2024, 2, 15).unwrap();
+    let (range_txs, range_balances) = chart_data.get_range_data(start_date, end_date);
+    let range_dates = chart_data.get_range_dates(start_date, end_date);
+
+    let empty_start = NaiveDate::from_ymd_opt(2023, 3, 1).unwrap();
+    let empty_end = NaiveDate::from_ymd_opt(2023, 5, 31).unwrap();
+    let (empty_txs, _) = chart_data.get_range_data(empty_start, empty_end);
+
+    let mut range_boxes = SummaryRangeData::new(&conn);
+    range_boxes.set_month(2024, 1, &conn);
+    let month_range = range_boxes.get_range(&conn);
+    range_boxes.set_year(2023, &conn);
+    let year_range = range_boxes.get_range(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(range_txs.len(), 4);
+    assert_eq!(range_txs[0][0], "01-06-2023");
+    assert_eq!(range_txs[3][0], "15-02-2024");
+    // balances carry the transactions before the range
+    assert_eq!(range_balances[0][0], "150.00");
+    assert_eq!(range_balances[3][0], "115.00");
+    assert_eq!(range_dates.len(), 4);
+    assert_eq!(
+        range_dates[2],
+        NaiveDate::from_ymd_opt(2023, 9, 10).unwrap()
+    );
+    assert!(empty_txs.is_empty());
+
+    assert_eq!(
+        month_range,
+        Ok((
+            NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        ))
+    );
+    assert_eq!(
+        year_range,
+        Ok((
+            NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()
+        ))
+    );
+
+    assert_eq!(format_axis_date(start_date, 30), "2023-06-01");
+    assert_eq!(format_axis_date(start_date, 259), "Jun 2023");
+}
+
+#[test]
+fn check_total_points() {
+    let tx_methods = vec!["Cash".to_string(), "Bank".to_string(), "Card".to_string()];
+
+    // two transactions on day 1 were merged into the x = 1.0 point of every method
+    let datasets = vec![
+        vec![(0.0, 100.0), (1.0, 40.0), (2.0, 40.0)],
+        vec![(0.0, 50.0), (1.0, 80.0), (2.0, -200.0)],
+        vec![(0.0, -10.0), (1.0, -30.0), (2.0, 500.0)],
+    ];
+
+    let mut hidden_methods = HashSet::new();
+
+    let expected_data = (vec![(0.0, 140.0), (1.0, 90.0), (2.0, 340.0)], 0.0, 340.0);
+    assert_eq!(
+        get_total_points(&datasets, &tx_methods, &hidden_methods),
+        expected_data
+    );
+
+    hidden_methods.insert("Card".to_string());
+
+    let expected_data = (
+        vec![(0.0, 150.0), (1.0, 120.0), (2.0, -160.0)],
+        -160.0,
+        150.0,
+    );
+    assert_eq!(
+        get_total_points(&datasets, &tx_methods, &hidden_methods),
+        expected_data
+    );
+
+    hidden_methods.insert("Cash".to_string());
+    hidden_methods.insert("Bank".to_string());
+
+    let expected_data = (vec![(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)], 0.0, 0.0);
+    assert_eq!(
+        get_total_points(&datasets, &tx_methods, &hidden_methods),
+        expected_data
+    );
+
+    assert_eq!(
+        get_total_points(&[], &[], &HashSet::new()),
+        (Vec::new(), 0.0, 0.0)
+    );
+}
+
+#[test]
+fn check_monthly_expense_points() {
+    let file_name = "monthly_expense_points.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    let txs = [
+        ("2022-02-10", "40.00", "Expense"),
+        ("2022-11-05", "15.00", "Expense"),
+        ("2023-01-09", "120.50", "Expense"),
+        ("2023-03-02", "10.00", "Income"),
+    ];
+
+    for (date, amount, tx_type) in txs {
+        add_tx(
+            date,
+            "Testing transaction",
+            "test1",
+            amount,
+            tx_type,
+            "Food",
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let full_year = get_monthly_expense_points(2022, &conn);
+    let partial_year = get_monthly_expense_points(2023, &conn);
+    let empty_year = get_monthly_expense_points(2024, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(full_year.len(), 11);
+    assert_eq!(full_year[0], (0.0, 0.0));
+    assert_eq!(full_year[1], (1.0, 40.0));
+    assert_eq!(full_year[10], (10.0, 15.0));
+
+    // the points stop at the last month with a transaction
+    assert_eq!(partial_year, vec![(0.0, 120.5), (1.0, 0.0), (2.0, 0.0)]);
+    assert!(empty_year.is_empty());
+}
+
+#[test]
+fn check_home_day_groups() {
+    let file_name = "check_home_day_groups.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    for (date, amount, tx_type) in [
+        ("2023-05-01", "50.00", "Expense"),
+        ("2023-05-01", "6.20", "Expense"),
+        ("2023-05-01", "100.00", "Income"),
+        ("2023-05-03", "20.00", "Income"),
+    ] {
+        add_tx(
+            date, "Test", "test1", amount, tx_type, "Food", None, &mut conn,
+        )
+        .unwrap();
+    }
+
+    let tx_data = TransactionData::new(4, 1, &HomeFilter::All, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(
+        tx_data.get_grouped_rows(),
+        &vec![
+            HomeRow::Separator("01-05-2023".to_string(), Money::parse("56.20").unwrap()),
+            HomeRow::Tx(0),
+            HomeRow::Tx(1),
+            HomeRow::Tx(2),
+            HomeRow::Separator("03-05-2023".to_string(), Money::ZERO),
+            HomeRow::Tx(3),
+        ]
+    );
+
+    let grouped_indexes = (0..4)
+        .map(|index| tx_data.get_grouped_index(index))
+        .collect::<Vec<usize>>();
+    assert_eq!(grouped_indexes, vec![1, 2, 3, 5]);
+}
+
+#[test]
+fn check_monthly_stacked_expense() {
+    let file_name = "monthly_stacked_expense.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    let txs = [
+        ("2022-01-10", "test1", "40.00", "Expense", "Coffee"),
+        ("2022-01-12", "test 2", "60.00", "Expense", "Groceries, Car"),
+        ("2022-03-05", "test1", "15.00", "Expense", "Car"),
+        ("2022-03-06", "test1", "5.00", "Refund", "Car"),
+        ("2022-04-01", "test 2", "500.00", "Income", "Salary"),
+    ];
+
+    for (date, method, amount, tx_type, tags) in txs {
+        add_tx(
+            date,
+            "Testing transaction",
+            method,
+            amount,
+            tx_type,
+            tags,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    set_tag_group("Coffee", "Food", &mut conn).unwrap();
+    set_tag_group("Groceries", "Food", &mut conn).unwrap();
+
+    let mut hidden_methods = HashSet::new();
+    let by_method = get_monthly_stacked_expense(2022, false, &hidden_methods, &conn);
+    let by_group = get_monthly_stacked_expense(2022, true, &hidden_methods, &conn);
+
+    hidden_methods.insert("test 2".to_string());
+    let hidden_by_method = get_monthly_stacked_expense(2022, false, &hidden_methods, &conn);
+    let hidden_by_group = get_monthly_stacked_expense(2022, true, &hidden_methods, &conn);
+
+    let empty_year = get_monthly_stacked_expense(2023, false, &HashSet::new(), &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    let months = |values: &[(usize, f64)]| {
+        let mut monthly_expense = vec![0.0; 12];
+        for (month, value) in values {
+            monthly_expense[*month] = *value;
+        }
+        monthly_expense
+    };
+
+    assert_eq!(
+        by_method,
+        vec![
+            ("test1".to_string(), months(&[(0, 40.0), (2, 10.0)])),
+            ("test 2".to_string(), months(&[(0, 60.0)])),
+        ]
+    );
+
+    // a tx belongs to the group of its first tag, tags without a group are their own group
+    assert_eq!(
+        by_group,
+        vec![
+            ("Car".to_string(), months(&[(2, 10.0)])),
+            ("Food".to_string(), months(&[(0, 100.0)])),
+        ]
+    );
+
+    // hidden methods are not counted and are not returned
+    assert_eq!(
+        hidden_by_method,
+        vec![("test1".to_string(), months(&[(0, 40.0), (2, 10.0)]))]
+    );
+    assert_eq!(
+        hidden_by_group,
+        vec![
+            ("Car".to_string(), months(&[(2, 10.0)])),
+            ("Food".to_string(), months(&[(0, 40.0)])),
+        ]
+    );
+    assert!(empty_year.is_empty());
+}
+
+#[test]
+fn check_suggestion_index() {
+    let ranked = vec![
+        "Groceries".to_string(),
+        "Rent".to_string(),
+        "Gro".to_string(),
+        "Grooming".to_string(),
+        "Travel".to_string(),
+    ];
+    let index = SuggestionIndex::new(ranked);
+
+    // the shortest value starting with the text wins, then the more used one
+    assert_eq!(index.get_suggestion("gr"), Some("Gro".to_string()));
+    assert_eq!(index.get_suggestion("groo"), Some("Grooming".to_string()));
+    assert_eq!(index.get_suggestion(" RE "), Some("Rent".to_string()));
+    // the typo tolerant matching is only used when nothing starts with the text
+    assert_eq!(index.get_suggestion("Trvael"), Some("Travel".to_string()));
+    assert_eq!(index.get_suggestion(""), None);
+    assert_eq!(SuggestionIndex::new(Vec::new()).get_suggestion("a"), None);
+
+    // the same values with equal length are picked by their usage
+    let index = SuggestionIndex::new(vec!["Cash".to_string(), "Card".to_string()]);
+    assert_eq!(index.get_suggestion("ca"), Some("Cash".to_string()));
+}
+
+#[test]
+fn check_suggestion_scans() {
+    let words = [
+        "Food", "Travel", "Rent", "Health", "Gift", "Salary", "Books",
+    ];
+    let tags = (0..5000)
+        .map(|num| format!("{} {num}", words[num % words.len()]))
+        .collect::<Vec<String>>();
+
+    let index = SuggestionIndex::new(tags);
+
+    // every prefix of the typed tags as they are typed, one of them with a typo
+    let typed_tags = ["Travel 4215", "hea", "books 13", "Slaary", "Gift 4995"];
+    let keystrokes = typed_tags
+        .iter()
+        .flat_map(|tag| (1..=tag.len()).map(|end| &tag[..end]))
+        .collect::<Vec<&str>>();
+
+    let mut scan_counts = Vec::new();
+    let suggestions = keystrokes
+        .iter()
+        .map(|typed| {
+            let suggestion = index.get_suggestion(typed);
+            scan_counts.push(index.get_scan_count());
+            suggestion
+        })
+        .collect::<Vec<Option<String>>>();
+
+    assert_eq!(suggestions[10], Some("Travel 4215".to_string()));
+    assert_eq!(suggestions.last().unwrap(), &Some("Gift 4995".to_string()));
+
+    // a full tag only goes through the tags that start with it
+    assert_eq!(scan_counts[10], 1);
+    assert_eq!(scan_counts.last(), Some(&1));
+
+    // "hea" is shared by every 7th tag and the typo only goes through the most used tags
+    assert_eq!(index.get_suggestion("hea"), Some("Health 3".to_string()));
+    assert_eq!(index.get_scan_count(), 5000 / words.len());
+    assert_eq!(index.get_suggestion("Slaary"), Some("Salary 5".to_string()));
+    assert_eq!(index.get_scan_count(), FUZZY_CANDIDATES);
+
+    assert!(scan_counts
+        .iter()
+        .all(|count| *count <= 5000 / words.len() + 1));
+}
+
+#[test]
+fn check_weekly_home_data() {
+    let file_name = "check_weekly_home_data.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    let txs = [
+        ("2023-05-28", "100.00", "Income", "Salary"),
+        ("2023-05-30", "20.00", "Expense", "Food"),
+        ("2023-06-01", "5.00", "Expense", "Food"),
+        ("2023-06-02", "2.00", "Refund", "Food"),
+        ("2023-06-05", "40.00", "Expense", "Car"),
+    ];
+
+    for (date, amount, tx_type, tags) in txs {
+        add_tx(
+            date,
+            "Testing transaction",
+            "test1",
+            amount,
+            tx_type,
+            tags,
+            None,
+            &mut conn,
+        )
+        .unwrap();
+    }
+
+    let date = |year: i32, month: u32, day: u32| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+    // May 29 to June 4 reaches into both months
+    let week = WeekRange::containing(date(2023, 6, 1), chrono::Weekday::Mon);
+    let week_data = TransactionData::new_weekly(&week, &HomeFilter::All, &conn);
+    let month_data = TransactionData::new_period(5, 1, &None, &HomeFilter::All, &conn);
+    let food_data = TransactionData::new_weekly(&week, &HomeFilter::Untagged, &conn);
+
+    let weekly_bars = get_weekly_income_expense(5, 2023, chrono::Weekday::Mon, None, &conn);
+    let weekly_food_bars =
+        get_weekly_income_expense(5, 2023, chrono::Weekday::Mon, Some("Food"), &conn);
+    let week_expense = week_data.get_total_expense(None, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    let week_ids = (0..week_data.all_tx.len())
+        .map(|index| week_data.get_id_num(index))
+        .collect::<Vec<i32>>();
+
+    assert_eq!(week_ids, vec![2, 3, 4]);
+    assert_eq!(month_data.all_tx.len(), 3);
+    assert_eq!(food_data.all_tx.len(), 0);
+
+    // the balance keeps counting from the start of the month
+    assert_eq!(week_data.get_balance(0)[1], "80.00");
+    assert_eq!(week_data.get_balance(2)[1], "77.00");
+    assert_eq!(week_expense[3], "23.00");
+    assert_eq!(week_data.get_month_totals(), (0.0, 23.0));
+
+    let clock = FixedClock::new(date(2023, 5, 31));
+    assert_eq!(
+        week_data.get_weekly_net(&week, &clock),
+        (3, -23.0, -23.0 / 3.0)
+    );
+
+    assert_eq!(weekly_bars.len(), 5);
+    assert_eq!(weekly_bars[0], ("May 29 – Jun 4".to_string(), 0.0, 23.0));
+    assert_eq!(weekly_bars[1], ("Jun 5 – Jun 11".to_string(), 0.0, 40.0));
+    assert_eq!(weekly_bars[4].0, "Jun 26 – Jul 2");
+    assert_eq!(
+        weekly_food_bars[0],
+        ("May 29 – Jun 4".to_string(), 0.0, 23.0)
+    );
+    assert_eq!(
+        weekly_food_bars[1],
+        ("Jun 5 – Jun 11".to_string(), 0.0, 0.0)
+    );
+}
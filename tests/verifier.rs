@@ -1,6 +1,11 @@
 extern crate rex_tui;
-use rex_tui::db::create_db;
-use rex_tui::outputs::{AType, NAType, VerifyingOutput};
+use chrono::NaiveDate;
+use rex_tui::db::{create_db, set_minor_units, set_setting, set_tag_bounds, DateRange, TagLimits};
+use rex_tui::outputs::{
+    AType, AmountStepSize, CheckingError, NAType, SteppingError, VerifyingOutput,
+};
+use rex_tui::page_handler::{NameCache, TxTab};
+use rex_tui::tx_handler::{add_tx, TxData};
 use rex_tui::utility::traits::DataVerifier;
 use rex_tui::utility::*;
 use rusqlite::Connection;
@@ -27,7 +32,7 @@ fn create_test_db(file_name: &str) -> Connection {
 
 #[test]
 fn check_sql_dates() {
-    let data = get_sql_dates(11, 2);
+    let data = get_sql_dates(11, 2, &DateRange::default());
     let expected_data = ("2024-12-01".to_string(), "2024-12-31".to_string());
     assert_eq!(data, expected_data);
 }
@@ -61,7 +66,7 @@ fn check_verifier_date() {
             "2022-00-01".to_string(),
             "2022-01-2022".to_string(),
             "2022-01-31".to_string(),
-            "2022-02-31".to_string(),
+            "2022-02-28".to_string(),
             "2022-12-31".to_string(),
             "2037-01-31".to_string(),
             "2022-01-".to_string(),
@@ -80,7 +85,7 @@ fn check_verifier_date() {
             VerifyingOutput::NotAccepted(NAType::DayTooBig),
             VerifyingOutput::NotAccepted(NAType::NonExistingDate),
             VerifyingOutput::NotAccepted(NAType::MonthTooBig),
-            VerifyingOutput::NotAccepted(NAType::YearTooBig),
+            VerifyingOutput::NotAccepted(NAType::YearTooBig(2022, 2037)),
             VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Date)),
             VerifyingOutput::NotAccepted(NAType::InvalidYear),
             VerifyingOutput::NotAccepted(NAType::InvalidMonth),
@@ -90,7 +95,7 @@ fn check_verifier_date() {
 
     for i in 0..test_data.data.len() {
         let mut to_verify = test_data.data[i].clone();
-        let result = test_data.verify_date(&mut to_verify);
+        let result = test_data.verify_date(&mut to_verify, &DateRange::default(), &DateFormat::Ymd);
         assert_eq!(result, test_data.result[i]);
         assert_eq!(to_verify, test_data.expected[i]);
     }
@@ -151,7 +156,8 @@ fn check_verifier_amount() {
             VerifyingOutput::Accepted(AType::Amount),
             VerifyingOutput::Accepted(AType::Amount),
             VerifyingOutput::Accepted(AType::Amount),
-            VerifyingOutput::Accepted(AType::Amount),
+            // 0.2857 has more decimals than the tx method keeps
+            VerifyingOutput::Accepted(AType::RoundedAmount(RoundingMode::HalfUp)),
             VerifyingOutput::Accepted(AType::Amount),
             VerifyingOutput::Accepted(AType::Amount),
             VerifyingOutput::Accepted(AType::Amount),
@@ -166,6 +172,206 @@ fn check_verifier_amount() {
     }
 }
 
+#[test]
+fn check_verifier_amount_units() {
+    // extra decimals used to be cut off and are now rounded half up by default
+    let test_data = vec![
+        ("1", ("1", false), ("1.000", false)),
+        ("1.5", ("2", true), ("1.500", false)),
+        ("100/3", ("33", true), ("33.333", true)),
+        ("1.23456", ("1", true), ("1.235", true)),
+    ];
+
+    let verifier = Testing {
+        data: Vec::new(),
+        expected: Vec::new(),
+        result: Vec::new(),
+    };
+
+    let get_status = |rounded| {
+        if rounded {
+            VerifyingOutput::Accepted(AType::RoundedAmount(RoundingMode::HalfUp))
+        } else {
+            VerifyingOutput::Accepted(AType::Amount)
+        }
+    };
+
+    for (data, (expected_zero, rounded_zero), (expected_three, rounded_three)) in test_data {
+        let mut to_verify = data.to_string();
+        let result = verifier.verify_amount_units(&mut to_verify, 0, &AmountFormat::default());
+        assert_eq!(result, get_status(rounded_zero));
+        assert_eq!(to_verify, expected_zero);
+
+        let mut to_verify = data.to_string();
+        let result = verifier.verify_amount_units(&mut to_verify, 3, &AmountFormat::default());
+        assert_eq!(result, get_status(rounded_three));
+        assert_eq!(to_verify, expected_three);
+    }
+
+    let file_name = "check_verifier_amount_units.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    set_minor_units("test1", 0, &mut conn).unwrap();
+    set_minor_units("test 2", 3, &mut conn).unwrap();
+
+    let mut yen_tx = TxData::custom("", "", "test1", "", "10.7", "Expense", "", 0);
+    let mut dinar_tx = TxData::custom("", "", "test 2", "", "10.7", "Expense", "", 0);
+
+    yen_tx.check_amount(false, &conn);
+    dinar_tx.check_amount(false, &conn);
+
+    assert_eq!(yen_tx.get_all_texts()[4], "11");
+    assert_eq!(dinar_tx.get_all_texts()[4], "10.700");
+
+    assert!(yen_tx
+        .do_amount_up(false, AmountStepSize::Single, &conn)
+        .is_ok());
+    assert!(dinar_tx
+        .do_amount_down(false, AmountStepSize::Single, &conn)
+        .is_ok());
+
+    assert_eq!(yen_tx.get_all_texts()[4], "12");
+    assert_eq!(dinar_tx.get_all_texts()[4], "9.700");
+
+    set_minor_units("test1", 2, &mut conn).unwrap();
+    yen_tx.check_amount(false, &conn);
+    let units = get_minor_units("test1", &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(yen_tx.get_all_texts()[4], "12.00");
+    assert_eq!(units, 2);
+}
+
+#[test]
+fn check_verifier_amount_separators() {
+    let verifier = Testing {
+        data: Vec::new(),
+        expected: Vec::new(),
+        result: Vec::new(),
+    };
+
+    let test_data = vec![
+        (
+            "1,000,000",
+            "1000000.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        ("$45.00", "45.00", VerifyingOutput::Accepted(AType::Amount)),
+        ("€ 12.5", "12.50", VerifyingOutput::Accepted(AType::Amount)),
+        (
+            "1,250.50",
+            "1250.50",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        (
+            "1 000 + 12£",
+            "1012.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        (
+            "1,2,3",
+            "1,2,3",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+        (
+            "1,25",
+            "1,25",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+    ];
+
+    for (data, expected, result) in test_data {
+        let mut to_verify = data.to_string();
+        assert_eq!(verifier.verify_amount(&mut to_verify), result);
+        assert_eq!(to_verify, expected);
+    }
+
+    let comma_format = AmountFormat::new(',', "kr");
+    let comma_data = vec![
+        ("1,25", "1.25", VerifyingOutput::Accepted(AType::Amount)),
+        (
+            "1.250,5 kr",
+            "1250.50",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        (
+            "1.25",
+            "1.25",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+    ];
+
+    for (data, expected, result) in comma_data {
+        let mut to_verify = data.to_string();
+        assert_eq!(
+            verifier.verify_amount_units(&mut to_verify, 2, &comma_format),
+            result
+        );
+        assert_eq!(to_verify, expected);
+    }
+
+    let file_name = "check_verifier_amount_separators.sqlite";
+    let conn = create_test_db(file_name);
+
+    set_setting("decimal_mark", ",", &conn).unwrap();
+    let mut tx_data = TxData::custom("", "", "test1", "", "2,5", "Expense", "", 0);
+    tx_data.check_amount(false, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(tx_data.get_all_texts()[4], "2.50");
+}
+
+#[test]
+fn check_amount_locale_round_trip() {
+    let verifier = Testing {
+        data: Vec::new(),
+        expected: Vec::new(),
+        result: Vec::new(),
+    };
+
+    let comma_format = AmountFormat::new(',', "");
+    let dot_format = AmountFormat::new('.', "");
+
+    let mut comma_amount = "1.234,56".to_string();
+    let comma_status = verifier.verify_amount_units(&mut comma_amount, 2, &comma_format);
+
+    let mut dot_amount = "1,234.56".to_string();
+    let dot_status = verifier.verify_amount_units(&mut dot_amount, 2, &dot_format);
+
+    assert_eq!(comma_status, VerifyingOutput::Accepted(AType::Amount));
+    assert_eq!(dot_status, VerifyingOutput::Accepted(AType::Amount));
+
+    // both are saved in the same form and shown back as they were typed
+    assert_eq!(comma_amount, "1234.56");
+    assert_eq!(dot_amount, "1234.56");
+    assert_eq!(comma_format.localize(&comma_amount), "1.234,56");
+    assert_eq!(dot_format.localize(&dot_amount), "1,234.56");
+
+    assert_eq!(comma_format.format_amount(-1234567.5, 2), "-1.234.567,50");
+    assert_eq!(comma_format.localize_decimal("1234.56"), "1234,56");
+    assert_eq!(
+        comma_format.localize("Coffee, tea. 12.50"),
+        "Coffee, tea. 12,50"
+    );
+
+    let file_name = "check_amount_locale_round_trip.sqlite";
+    let conn = create_test_db(file_name);
+
+    let default_format = get_amount_format(&conn);
+    set_setting("decimal_mark", ",", &conn).unwrap();
+    let changed_format = get_amount_format(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(default_format, AmountFormat::default());
+    assert_eq!(changed_format.localize("1234.56"), "1.234,56");
+}
+
 #[test]
 fn check_verifier_tx_method() {
     let test_data = Testing {
@@ -179,9 +385,10 @@ fn check_verifier_tx_method() {
         expected: vec![
             "".to_string(),
             "test 2".to_string(),
-            "test1".to_string(),
+            "random".to_string(),
             "test 2".to_string(),
-            "test1".to_string(),
+            // about as close to both methods so it is left for the user to pick one
+            "te".to_string(),
         ],
         result: vec![
             VerifyingOutput::Nothing(AType::TxMethod),
@@ -196,7 +403,7 @@ fn check_verifier_tx_method() {
 
     for i in 0..test_data.data.len() {
         let mut to_verify = test_data.data[i].clone();
-        let result = test_data.verify_tx_method(&mut to_verify, &conn);
+        let result = test_data.verify_tx_method(&mut to_verify, &NameCache::new(), &conn);
         assert_eq!(result, test_data.result[i]);
         assert_eq!(to_verify, test_data.expected[i]);
     }
@@ -204,6 +411,115 @@ fn check_verifier_tx_method() {
     fs::remove_file(file_name).unwrap();
 }
 
+#[test]
+fn check_verifier_tx_method_ranking() {
+    let file_name = "check_verifier_tx_method_ranking.sqlite";
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(
+        vec!["Bank".to_string(), "Cash".to_string(), "Card".to_string()],
+        &mut conn,
+    )
+    .unwrap();
+    let verifier = TxData::new();
+
+    let mut results = Vec::new();
+
+    // swapped letters used to tie with another method and get corrected to the first one
+    for typed in ["Cadr", "Bnak", "cahs", "Cxyz"] {
+        let mut to_verify = typed.to_string();
+        let status = verifier.verify_tx_method(&mut to_verify, &NameCache::new(), &conn);
+        results.push((status, to_verify));
+    }
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    for (status, _) in &results {
+        assert_eq!(
+            *status,
+            VerifyingOutput::NotAccepted(NAType::InvalidTxMethod)
+        );
+    }
+    assert_eq!(
+        results
+            .into_iter()
+            .map(|(_, method)| method)
+            .collect::<Vec<String>>(),
+        vec!["Card", "Bank", "Cash", "Cxyz"]
+    );
+
+    let methods = vec!["Bank".to_string(), "Cash".to_string(), "Card".to_string()];
+    let ranked = get_ranked_matches("Cadr", &methods);
+
+    assert_eq!(ranked[0].0, "Card");
+    assert!(ranked[0].1 >= CONFIDENT_MATCH_SCORE);
+    assert!(ranked[1].1 < CONFIDENT_MATCH_SCORE);
+    assert_eq!(ranked[2].0, "Bank");
+    assert_eq!(get_match_score("bank", "Bank"), 1.0);
+    assert_eq!(get_best_match("Ca", methods), "Cash");
+}
+
+#[test]
+fn check_verifier_tx_method_close_matches() {
+    let file_name = "check_verifier_tx_method_close_matches.sqlite";
+    let mut conn = Connection::open(file_name).unwrap();
+    create_db(
+        vec![
+            "Visa Card".to_string(),
+            "Virtual Card".to_string(),
+            "Cash".to_string(),
+        ],
+        &mut conn,
+    )
+    .unwrap();
+    let verifier = TxData::new();
+
+    let mut results = Vec::new();
+
+    for typed in ["Visual Card", "Vir Card", "Virtual", "Cxyz"] {
+        let mut to_verify = typed.to_string();
+        let status = verifier.verify_tx_method(&mut to_verify, &NameCache::new(), &conn);
+        results.push((status, to_verify));
+    }
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    for (status, _) in &results {
+        assert_eq!(
+            *status,
+            VerifyingOutput::NotAccepted(NAType::InvalidTxMethod)
+        );
+    }
+
+    // methods that are about as close are left for the user to pick
+    assert_eq!(
+        results
+            .into_iter()
+            .map(|(_, method)| method)
+            .collect::<Vec<String>>(),
+        vec!["Visual Card", "Vir Card", "Virtual Card", "Cxyz"]
+    );
+
+    let methods = vec![
+        "Visa Card".to_string(),
+        "Virtual Card".to_string(),
+        "Cash".to_string(),
+    ];
+
+    assert_eq!(
+        get_close_matches("Visual Card", &methods),
+        vec!["Visa Card", "Virtual Card"]
+    );
+    assert_eq!(
+        get_close_matches("Vir Card", &methods),
+        vec!["Virtual Card", "Visa Card"]
+    );
+    assert_eq!(get_close_matches("Virtual", &methods), vec!["Virtual Card"]);
+    assert!(get_close_matches("Cxyz", &methods).is_empty());
+    assert!(get_close_matches("Cash", &[]).is_empty());
+}
+
 #[test]
 fn check_verifier_tx_type() {
     let test_data = Testing {
@@ -214,6 +530,7 @@ fn check_verifier_tx_type() {
             "w".to_string(),
             "   i".to_string(),
             "  i   ".to_string(),
+            "r".to_string(),
         ],
         expected: vec![
             "".to_string(),
@@ -222,6 +539,7 @@ fn check_verifier_tx_type() {
             "".to_string(),
             "Income".to_string(),
             "Income".to_string(),
+            "Refund".to_string(),
         ],
         result: vec![
             VerifyingOutput::Nothing(AType::TxType),
@@ -230,6 +548,7 @@ fn check_verifier_tx_type() {
             VerifyingOutput::NotAccepted(NAType::InvalidTxType),
             VerifyingOutput::Accepted(AType::TxType),
             VerifyingOutput::Accepted(AType::TxType),
+            VerifyingOutput::Accepted(AType::TxType),
         ],
     };
 
@@ -251,6 +570,7 @@ fn check_verifier_tags() {
             "tag1,tag2,tag3".to_string(),
             "tag1,tag1,tag1".to_string(),
             "tag1, Tag1, tAg1".to_string(),
+            "  Food   Court , food court".to_string(),
         ],
         expected: vec![
             "".to_string(),
@@ -258,14 +578,1139 @@ fn check_verifier_tags() {
             "tag1".to_string(),
             "tag1, tag2, tag3".to_string(),
             "tag1".to_string(),
-            "tag1, Tag1, tAg1".to_string(),
+            "tag1".to_string(),
+            "Food Court".to_string(),
         ],
         result: Vec::new(),
     };
 
     for i in 0..test_data.data.len() {
         let mut to_verify = test_data.data[i].clone();
-        test_data.verify_tags(&mut to_verify);
+        test_data.verify_tags(&mut to_verify, &[]);
+        assert_eq!(to_verify, test_data.expected[i]);
+    }
+
+    // tags colliding with an existing tag are replaced by it
+    let existing_tags = vec!["Food Court".to_string(), "Car".to_string()];
+    let mut to_verify = "food  court, CAR , Rent".to_string();
+    test_data.verify_tags(&mut to_verify, &existing_tags);
+    assert_eq!(to_verify, "Food Court, Car, Rent");
+}
+
+#[test]
+fn check_all_fields_errors() {
+    let file_name = "check_all_fields_errors.sqlite";
+    let conn = create_test_db(file_name);
+
+    let mut tx_data = TxData::custom("", "", "", "", "0", "Income", "", 0);
+    let errors = tx_data.check_all_fields(&NameCache::new(), &conn);
+
+    let error_list = errors
+        .get_errors()
+        .iter()
+        .map(|(field, error)| (*field, error))
+        .collect::<Vec<(TxTab, &CheckingError)>>();
+
+    let expected_list = vec![
+        (TxTab::Date, &CheckingError::EmptyDate),
+        (TxTab::FromMethod, &CheckingError::EmptyMethod),
+        (
+            TxTab::Amount,
+            &CheckingError::NotAccepted(NAType::AmountBelowZero),
+        ),
+    ];
+
+    assert_eq!(error_list, expected_list);
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors.first_field(), Some(TxTab::Date));
+    assert!(tx_data.is_invalid_field(&TxTab::Date));
+    assert!(tx_data.is_invalid_field(&TxTab::FromMethod));
+    assert!(tx_data.is_invalid_field(&TxTab::Amount));
+    assert!(!tx_data.is_invalid_field(&TxTab::TxType));
+    assert_eq!(
+        errors.to_string(),
+        "Date: Date cannot be empty\nTx Method: TX Method cannot be empty\nAmount: Value must be bigger than zero"
+    );
+
+    tx_data.edit_date(Some('2'));
+    assert!(!tx_data.is_invalid_field(&TxTab::Date));
+
+    let mut tx_data = TxData::custom("01-05-2022", "", "test1", "test1", "100", "t", "", 0);
+    let errors = tx_data.check_all_fields(&NameCache::new(), &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors.first_field(), Some(TxTab::ToMethod));
+    assert_eq!(errors.get_errors()[0].1, CheckingError::SameTxMethod);
+}
+
+#[test]
+fn check_submit_errors_report() {
+    let file_name = "check_submit_errors_report.sqlite";
+    let conn = create_test_db(file_name);
+
+    let mut tx_data = TxData::custom("", "", "", "", "", "", "", 0);
+    let errors = tx_data.check_all_fields(&NameCache::new(), &conn);
+
+    let mut tx_data = TxData::custom("01-05-2022", "", "test1", "test1", "100", "t", "", 0);
+    let single_error = tx_data.check_all_fields(&NameCache::new(), &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    let report = errors.get_report();
+    let report_lines = report.lines().skip(2).collect::<Vec<&str>>();
+
+    // every field is reported once and in the order of the page
+    assert_eq!(
+        report.lines().next(),
+        Some("4 fields need fixing before the transaction can be saved")
+    );
+    assert_eq!(
+        report_lines,
+        vec![
+            "Date: Date cannot be empty",
+            "Tx Type: Transaction Type cannot be empty",
+            "Tx Method: TX Method cannot be empty",
+            "Amount: Amount cannot be empty",
+        ]
+    );
+    assert_eq!(report_lines.len(), errors.get_fields().len());
+
+    assert_eq!(
+        single_error.get_report(),
+        "1 field needs fixing before the transaction can be saved\n\n\
+         Tx Method: From and To methods cannot be the same for Transfer"
+    );
+}
+
+#[test]
+fn check_required_tags() {
+    let file_name = "check_required_tags.sqlite";
+    let conn = create_test_db(file_name);
+
+    let mut tx_data = TxData::custom("01-05-2022", "", "test1", "", "100", "Expense", "", 0);
+    let errors = tx_data.check_all_fields(&NameCache::new(), &conn);
+
+    assert!(errors.is_empty());
+    assert_eq!(tx_data.get_all_texts()[6], "Unknown");
+
+    set_setting("require_tags", "true", &conn).unwrap();
+
+    let mut tx_data = TxData::custom("01-05-2022", "", "test1", "", "", "Expense", " , ", 0);
+    let errors = tx_data.check_all_fields(&NameCache::new(), &conn);
+
+    let error_list = errors
+        .get_errors()
+        .iter()
+        .map(|(field, error)| (*field, error))
+        .collect::<Vec<(TxTab, &CheckingError)>>();
+
+    assert_eq!(
+        error_list,
+        vec![
+            (TxTab::Amount, &CheckingError::EmptyAmount),
+            (TxTab::Tags, &CheckingError::EmptyTags),
+        ]
+    );
+    assert!(tx_data.is_invalid_field(&TxTab::Tags));
+
+    let mut tx_data = TxData::custom("01-05-2022", "", "test1", "", "100", "Expense", "Food", 0);
+    let errors = tx_data.check_all_fields(&NameCache::new(), &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn check_tx_confirmation() {
+    let tx_data = TxData::custom(
+        "12-05-2023",
+        "Rent",
+        "test1",
+        "",
+        "1450.00",
+        "Expense",
+        "Home",
+        0,
+    );
+
+    assert!(tx_data.needs_confirmation("always", 2000.0));
+    assert!(tx_data.needs_confirmation("threshold", 1000.0));
+    assert!(!tx_data.needs_confirmation("threshold", 2000.0));
+    assert!(!tx_data.needs_confirmation("never", 0.0));
+    assert_eq!(
+        tx_data.get_summary(),
+        "Updating transaction\n2023-05-12 · Expense · 1,450.00 · Rent · from test1\nTags: Home"
+    );
+
+    let tx_data = TxData::custom(
+        "12-05-2023",
+        "",
+        "test1",
+        "test 2",
+        "100.00",
+        "Transfer",
+        "Unknown",
+        0,
+    );
+    let mut new_tx_data = TxData::new();
+    new_tx_data.edit_amount(Some('5'));
+
+    assert!(!tx_data.needs_confirmation("threshold", 1000.0));
+    assert_eq!(
+        tx_data.get_summary(),
+        "Updating transaction\n2023-05-12 · Transfer · 100.00 · from test1 to test 2\nTags: Unknown"
+    );
+    assert!(new_tx_data.get_summary().starts_with("Adding transaction"));
+}
+
+#[test]
+fn check_edit_changes() {
+    let mut tx_data = TxData::custom(
+        "12-05-2023",
+        "Lunch",
+        "test1",
+        "",
+        "45.00",
+        "Expense",
+        "Food",
+        0,
+    );
+
+    assert!(tx_data.get_changes().is_empty());
+    assert_eq!(tx_data.get_original_value(&TxTab::Amount), None);
+
+    tx_data.go_current_index(&TxTab::Amount);
+    for _ in 0..5 {
+        tx_data.edit_amount(None);
+    }
+    for c in "54.00".chars() {
+        tx_data.edit_amount(Some(c));
+    }
+
+    tx_data.go_current_index(&TxTab::Tags);
+    for c in ", Work".chars() {
+        tx_data.edit_tags(Some(c));
+    }
+
+    assert_eq!(
+        tx_data.get_changes(),
+        vec!["Amount: 45.00 → 54.00", "Tags: Food → Food, Work"]
+    );
+    assert_eq!(tx_data.get_original_value(&TxTab::Amount), Some("45.00"));
+    assert_eq!(tx_data.get_original_value(&TxTab::Details), None);
+    assert_eq!(
+        tx_data.get_summary(),
+        "Updating transaction\n2023-05-12 · Expense · 54.00 · Lunch · from test1\nTags: Food, Work\n\nChanges:\nAmount: 45.00 → 54.00\nTags: Food → Food, Work"
+    );
+
+    // changing a field back to the original value removes it from the diff
+    tx_data.go_current_index(&TxTab::Tags);
+    for _ in 0..6 {
+        tx_data.edit_tags(None);
+    }
+
+    assert_eq!(tx_data.get_changes(), vec!["Amount: 45.00 → 54.00"]);
+
+    let mut new_tx_data = TxData::new();
+    new_tx_data.edit_amount(Some('5'));
+
+    assert!(new_tx_data.get_changes().is_empty());
+}
+
+#[test]
+fn check_tag_bounds() {
+    let file_name = "check_tag_bounds.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    set_tag_bounds("Coffee", None, Some(10.0), &mut conn).unwrap();
+    set_tag_bounds("Rent", Some(500.0), Some(1500.0), &mut conn).unwrap();
+    set_tag_bounds("Car", Some(1.0), None, &mut conn).unwrap();
+    set_tag_bounds("Car", None, None, &mut conn).unwrap();
+
+    let tag_bounds = get_tag_bounds(&conn);
+
+    let normal_tx = TxData::custom("", "", "test1", "", "5.50", "Expense", "Coffee", 0);
+    let typo_tx = TxData::custom("", "", "test1", "", "100.00", "Expense", "Coffee, Rent", 0);
+    let unbounded_tx = TxData::custom("", "", "test1", "", "0.50", "Expense", "Car, Food", 0);
+
+    let normal_warnings = normal_tx.get_bound_warnings(&conn);
+    let typo_warnings = typo_tx.get_bound_warnings(&conn);
+    let unbounded_warnings = unbounded_tx.get_bound_warnings(&conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(tag_bounds.len(), 2);
+    assert_eq!(tag_bounds.get("Coffee"), Some(&(None, Some(10.0))));
+    assert_eq!(tag_bounds.get("Car"), None);
+
+    assert!(normal_warnings.is_empty());
+    assert_eq!(
+        typo_warnings,
+        vec![
+            "Coffee: expected - to 10.00",
+            "Rent: expected 500.00 to 1,500.00"
+        ]
+    );
+    assert!(unbounded_warnings.is_empty());
+}
+
+#[test]
+fn check_date_format() {
+    let test_data = Testing {
+        data: vec![],
+        expected: vec![],
+        result: vec![],
+    };
+    let date_range = DateRange::default();
+
+    let mut dmy_date = "15/03/2024".to_string();
+    let mut mdy_date = "03.15.2024".to_string();
+    let mut ymd_date = "2024.03.15".to_string();
+    let mut iso_dmy_date = "2024-03-15".to_string();
+    let mut wrong_dmy_date = "03-15-2024".to_string();
+
+    let dmy_result = test_data.verify_date(&mut dmy_date, &date_range, &DateFormat::Dmy);
+    let mdy_result = test_data.verify_date(&mut mdy_date, &date_range, &DateFormat::Mdy);
+    let ymd_result = test_data.verify_date(&mut ymd_date, &date_range, &DateFormat::Ymd);
+    let iso_dmy_result = test_data.verify_date(&mut iso_dmy_date, &date_range, &DateFormat::Dmy);
+    let wrong_dmy_result =
+        test_data.verify_date(&mut wrong_dmy_date, &date_range, &DateFormat::Dmy);
+
+    assert_eq!(dmy_result, VerifyingOutput::Accepted(AType::Date));
+    assert_eq!(dmy_date, "15-03-2024");
+    assert_eq!(mdy_result, VerifyingOutput::Accepted(AType::Date));
+    assert_eq!(mdy_date, "03-15-2024");
+    assert_eq!(ymd_result, VerifyingOutput::Accepted(AType::Date));
+    assert_eq!(ymd_date, "2024-03-15");
+
+    // a date typed as YYYY-MM-DD is accepted in every format
+    assert_eq!(iso_dmy_result, VerifyingOutput::Accepted(AType::Date));
+    assert_eq!(iso_dmy_date, "15-03-2024");
+
+    assert_eq!(
+        wrong_dmy_result,
+        VerifyingOutput::NotAccepted(NAType::MonthTooBig)
+    );
+    assert_eq!(wrong_dmy_date, "03-12-2024");
+
+    assert_eq!(DateFormat::Dmy.to_iso("15-03-2024"), "2024-03-15");
+    assert_eq!(DateFormat::Mdy.to_iso("03/15/2024"), "2024-03-15");
+    assert_eq!(DateFormat::Mdy.from_iso("2024-03-15"), "03-15-2024");
+    assert_eq!(
+        DateFormat::Dmy.format_table_date("15-03-2024"),
+        "15-03-2024"
+    );
+    assert_eq!(
+        DateFormat::Ymd.format_table_date("15-03-2024"),
+        "2024-03-15"
+    );
+    assert_eq!(DateFormat::Ymd.format_table_date("Subtotal"), "Subtotal");
+}
+
+#[test]
+fn check_relative_dates() {
+    let test_data = Testing {
+        data: vec![
+            "t".to_string(),
+            "Today".to_string(),
+            " y ".to_string(),
+            "yesterday".to_string(),
+            "+3".to_string(),
+            "-10".to_string(),
+            "+5000".to_string(),
+            "+".to_string(),
+            "tomorrow".to_string(),
+        ],
+        expected: vec![
+            "2024-06-08".to_string(),
+            "2024-06-08".to_string(),
+            "2024-06-07".to_string(),
+            "2024-06-07".to_string(),
+            "2024-06-11".to_string(),
+            "2024-05-29".to_string(),
+            "2037-02-15".to_string(),
+            "2022-01-01".to_string(),
+            "2022-01-01".to_string(),
+        ],
+        result: vec![
+            VerifyingOutput::Accepted(AType::Date),
+            VerifyingOutput::Accepted(AType::Date),
+            VerifyingOutput::Accepted(AType::Date),
+            VerifyingOutput::Accepted(AType::Date),
+            VerifyingOutput::Accepted(AType::Date),
+            VerifyingOutput::Accepted(AType::Date),
+            VerifyingOutput::NotAccepted(NAType::YearTooBig(2022, 2037)),
+            VerifyingOutput::NotAccepted(NAType::InvalidDate),
+            VerifyingOutput::NotAccepted(NAType::InvalidDate),
+        ],
+    };
+    let clock = FixedClock::new(NaiveDate::from_ymd_opt(2024, 6, 8).unwrap());
+    let date_range = DateRange::default();
+
+    for i in 0..test_data.data.len() {
+        let mut to_verify = test_data.data[i].clone();
+        let result =
+            test_data.verify_date_with_clock(&mut to_verify, &date_range, &DateFormat::Ymd, &clock);
+        assert_eq!(result, test_data.result[i]);
+        assert_eq!(to_verify, test_data.expected[i]);
+    }
+
+    // the expanded date is written in the date format
+    let mut dmy_date = "y".to_string();
+    let dmy_result =
+        test_data.verify_date_with_clock(&mut dmy_date, &date_range, &DateFormat::Dmy, &clock);
+    assert_eq!(dmy_result, VerifyingOutput::Accepted(AType::Date));
+    assert_eq!(dmy_date, "07-06-2024");
+
+    assert_eq!(
+        expand_relative_date("-0", &clock),
+        Some("2024-06-08".to_string())
+    );
+    assert_eq!(expand_relative_date("+1d", &clock), None);
+    assert_eq!(expand_relative_date("2024-06-08", &clock), None);
+}
+
+#[test]
+fn check_non_existing_date_correction() {
+    let test_data = Testing {
+        data: vec![
+            "2024-02-30".to_string(),
+            "2024-02-31".to_string(),
+            "2023-02-29".to_string(),
+            "2023-02-30".to_string(),
+            "2023-04-31".to_string(),
+            "2023-06-31".to_string(),
+            "2023-09-31".to_string(),
+            "2023-11-31".to_string(),
+            "2024-02-29".to_string(),
+            "2023-04-30".to_string(),
+        ],
+        expected: vec![
+            "2024-02-29".to_string(),
+            "2024-02-29".to_string(),
+            "2023-02-28".to_string(),
+            "2023-02-28".to_string(),
+            "2023-04-30".to_string(),
+            "2023-06-30".to_string(),
+            "2023-09-30".to_string(),
+            "2023-11-30".to_string(),
+            "2024-02-29".to_string(),
+            "2023-04-30".to_string(),
+        ],
+        result: vec![
+            VerifyingOutput::NotAccepted(NAType::NonExistingDate),
+            VerifyingOutput::NotAccepted(NAType::NonExistingDate),
+            VerifyingOutput::NotAccepted(NAType::NonExistingDate),
+            VerifyingOutput::NotAccepted(NAType::NonExistingDate),
+            VerifyingOutput::NotAccepted(NAType::NonExistingDate),
+            VerifyingOutput::NotAccepted(NAType::NonExistingDate),
+            VerifyingOutput::NotAccepted(NAType::NonExistingDate),
+            VerifyingOutput::NotAccepted(NAType::NonExistingDate),
+            VerifyingOutput::Accepted(AType::Date),
+            VerifyingOutput::Accepted(AType::Date),
+        ],
+    };
+
+    for i in 0..test_data.data.len() {
+        let mut to_verify = test_data.data[i].clone();
+        let result = test_data.verify_date(&mut to_verify, &DateRange::default(), &DateFormat::Ymd);
+        assert_eq!(result, test_data.result[i]);
         assert_eq!(to_verify, test_data.expected[i]);
+
+        // the corrected date is accepted on the next try
+        let result = test_data.verify_date(&mut to_verify, &DateRange::default(), &DateFormat::Ymd);
+        assert_eq!(result, VerifyingOutput::Accepted(AType::Date));
     }
 }
+
+#[test]
+fn check_verifier_amount_grouped() {
+    let verifier = Testing {
+        data: Vec::new(),
+        expected: Vec::new(),
+        result: Vec::new(),
+    };
+
+    let test_data = vec![
+        (
+            "(20+5)*3",
+            "75.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        (
+            "2*((1+2)*(3+1))",
+            "24.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        ("(10)", "10.00", VerifyingOutput::Accepted(AType::Amount)),
+        (
+            "100/(2+2)-5",
+            "20.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        (
+            "(5 - 10) * 2",
+            "10.00",
+            VerifyingOutput::NotAccepted(NAType::AmountBelowZero),
+        ),
+        (
+            "(1+2)*99999999999",
+            "2999999999.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        (
+            "(20+5*3",
+            "(20+5*3",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+        (
+            "20+5)*3",
+            "20+5)*3",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+        (
+            ")(",
+            ")(",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+        (
+            "()",
+            "()",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+    ];
+
+    for (data, expected, result) in test_data {
+        let mut to_verify = data.to_string();
+        assert_eq!(verifier.verify_amount(&mut to_verify), result);
+        assert_eq!(to_verify, expected);
+    }
+}
+
+#[test]
+fn check_verifier_amount_order() {
+    let verifier = Testing {
+        data: Vec::new(),
+        expected: Vec::new(),
+        result: Vec::new(),
+    };
+
+    let test_data = vec![
+        ("10-2+5", "13.00", VerifyingOutput::Accepted(AType::Amount)),
+        (
+            "2-5*3",
+            "13.00",
+            VerifyingOutput::NotAccepted(NAType::AmountBelowZero),
+        ),
+        ("5*5+5*5", "50.00", VerifyingOutput::Accepted(AType::Amount)),
+        (
+            "100/3*3",
+            "100.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        ("10-2*3+5", "9.00", VerifyingOutput::Accepted(AType::Amount)),
+        ("5*-3", "2.00", VerifyingOutput::Accepted(AType::Amount)),
+        (
+            "1.2.3+1",
+            "1.2.3+1",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+        (
+            "+",
+            "+",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+    ];
+
+    for (data, expected, result) in test_data {
+        let mut to_verify = data.to_string();
+        assert_eq!(verifier.verify_amount(&mut to_verify), result);
+        assert_eq!(to_verify, expected);
+    }
+}
+
+#[test]
+fn check_verifier_amount_percentage() {
+    let verifier = Testing {
+        data: Vec::new(),
+        expected: Vec::new(),
+        result: Vec::new(),
+    };
+
+    let test_data = vec![
+        (
+            "200-25%",
+            "150.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        (
+            "200+25%",
+            "250.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        (
+            "10%",
+            "10%",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+        (
+            "100*2-50%",
+            "100.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        (
+            "100+50-10%",
+            "135.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        (
+            "1500 - 10%",
+            "1350.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        (
+            "200-25%+10",
+            "160.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        (
+            "50*10%",
+            "50*10%",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+        ("100+%", "100.00", VerifyingOutput::Accepted(AType::Amount)),
+    ];
+
+    for (data, expected, result) in test_data {
+        let mut to_verify = data.to_string();
+        assert_eq!(verifier.verify_amount(&mut to_verify), result);
+        assert_eq!(to_verify, expected);
+    }
+}
+
+#[test]
+fn check_verifier_amount_division_by_zero() {
+    let verifier = Testing {
+        data: Vec::new(),
+        expected: Vec::new(),
+        result: Vec::new(),
+    };
+
+    let test_data = vec![
+        ("50/0", "50/0"),
+        ("10+5/0*2", "10+5/0*2"),
+        ("0/0", "0/0"),
+        ("5 / 0.00", "5/0.00"),
+        ("(5/0)", "(5/0)"),
+        ("1/(1/0)", "1/(1/0)"),
+        ("(2-2)/0", "(2-2)/0"),
+    ];
+
+    for (data, expected) in test_data {
+        let mut to_verify = data.to_string();
+        let result = verifier.verify_amount(&mut to_verify);
+
+        assert_eq!(
+            result,
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount))
+        );
+        assert_eq!(to_verify, expected);
+        assert!(!to_verify.contains("inf"));
+        assert!(!to_verify.contains("NaN"));
+    }
+}
+
+#[test]
+fn check_verifier_amount_balance() {
+    let file_name = "check_verifier_amount_balance.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    add_tx(
+        "2022-07-19",
+        "Testing transaction",
+        "test1",
+        "500.50",
+        "Income",
+        "Unknown",
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    let mut full_tx = TxData::custom("", "", "test1", "", "b", "Expense", "", 0);
+    let mut half_tx = TxData::custom("", "", "test1", "", "b/2", "Expense", "", 0);
+    let mut transfer_tx = TxData::custom("", "", "test1", "test 2", "B-100", "Transfer", "", 0);
+    let mut empty_tx = TxData::custom("", "", "test 2", "", "b", "Expense", "", 0);
+    let mut no_method_tx = TxData::custom("", "", "", "", "b-100", "Expense", "", 0);
+    let mut step_tx = TxData::custom("", "", "", "", "b", "Expense", "", 0);
+
+    let full_status = full_tx.check_amount(false, &conn);
+    let half_status = half_tx.check_amount(false, &conn);
+    let transfer_status = transfer_tx.check_amount(false, &conn);
+    let empty_status = empty_tx.check_amount(false, &conn);
+    let no_method_status = no_method_tx.check_amount(false, &conn);
+    let step_status = step_tx.do_amount_up(false, AmountStepSize::Single, &conn);
+
+    // the balance gets written with the decimal mark the user types amounts with
+    set_setting("decimal_mark", ",", &conn).unwrap();
+    let mut comma_tx = TxData::custom("", "", "test1", "", "b-0,5", "Expense", "", 0);
+    let comma_status = comma_tx.check_amount(false, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(full_status, VerifyingOutput::Accepted(AType::Amount));
+    assert_eq!(full_tx.get_all_texts()[4], "500.50");
+
+    assert_eq!(half_status, VerifyingOutput::Accepted(AType::Amount));
+    assert_eq!(half_tx.get_all_texts()[4], "250.25");
+
+    assert_eq!(transfer_status, VerifyingOutput::Accepted(AType::Amount));
+    assert_eq!(transfer_tx.get_all_texts()[4], "400.50");
+
+    assert_eq!(
+        empty_status,
+        VerifyingOutput::NotAccepted(NAType::AmountBelowZero)
+    );
+
+    assert_eq!(
+        no_method_status,
+        VerifyingOutput::NotAccepted(NAType::InvalidBValue)
+    );
+    assert_eq!(no_method_tx.get_all_texts()[4], "b-100");
+
+    assert!(matches!(step_status, Err(SteppingError::UnknownBValue)));
+
+    assert_eq!(comma_status, VerifyingOutput::Accepted(AType::Amount));
+    assert_eq!(comma_tx.get_all_texts()[4], "500.00");
+}
+
+#[test]
+fn check_verifier_amount_multipliers() {
+    let verifier = Testing {
+        data: Vec::new(),
+        expected: Vec::new(),
+        result: Vec::new(),
+    };
+
+    let test_data = vec![
+        ("1.5k", "1500.00", VerifyingOutput::Accepted(AType::Amount)),
+        ("2m", "2000000.00", VerifyingOutput::Accepted(AType::Amount)),
+        ("1.5K", "1500.00", VerifyingOutput::Accepted(AType::Amount)),
+        ("2M", "2000000.00", VerifyingOutput::Accepted(AType::Amount)),
+        (
+            "1k+250",
+            "1250.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        ("2k*3", "6000.00", VerifyingOutput::Accepted(AType::Amount)),
+        (
+            "(1k-500)/2",
+            "250.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        ("0.0015k", "1.50", VerifyingOutput::Accepted(AType::Amount)),
+        (
+            "1m-10%",
+            "900000.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        ("$1.5k", "1500.00", VerifyingOutput::Accepted(AType::Amount)),
+        (
+            "99999m",
+            "9999900000.00",
+            VerifyingOutput::Accepted(AType::Amount),
+        ),
+        (
+            "k5",
+            "k5",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+        (
+            "1kk",
+            "1kk",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+        (
+            "1k5",
+            "1k5",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+        (
+            "10+m",
+            "10+m",
+            VerifyingOutput::NotAccepted(NAType::ParsingError(AType::Amount)),
+        ),
+    ];
+
+    for (data, expected, result) in test_data {
+        let mut to_verify = data.to_string();
+        assert_eq!(verifier.verify_amount(&mut to_verify), result, "{data}");
+        assert_eq!(to_verify, expected, "{data}");
+    }
+}
+
+#[test]
+fn check_verifier_details() {
+    let verifier = Testing {
+        data: Vec::new(),
+        expected: Vec::new(),
+        result: Vec::new(),
+    };
+
+    let long_details = "a".repeat(150);
+    let expected_long = "a".repeat(100);
+
+    let test_data = vec![
+        (
+            "  Lunch  ",
+            "Lunch",
+            VerifyingOutput::Accepted(AType::Details),
+        ),
+        (
+            "Lunch\nwith\t\tteam",
+            "Lunch with team",
+            VerifyingOutput::Accepted(AType::Details),
+        ),
+        (
+            "Lunch \r\n with  team",
+            "Lunch with  team",
+            VerifyingOutput::Accepted(AType::Details),
+        ),
+        (
+            "Bus\u{7} ticket",
+            "Bus ticket",
+            VerifyingOutput::Accepted(AType::Details),
+        ),
+        (" \n\t ", "", VerifyingOutput::Nothing(AType::Details)),
+        (
+            &long_details,
+            &expected_long,
+            VerifyingOutput::NotAccepted(NAType::DetailsTooLong(100)),
+        ),
+    ];
+
+    for (data, expected, result) in test_data {
+        let mut to_verify = data.to_string();
+        assert_eq!(verifier.verify_details(&mut to_verify), result);
+        assert_eq!(to_verify, expected);
+    }
+
+    let file_name = "check_verifier_details.sqlite";
+    let conn = create_test_db(file_name);
+    set_setting("details_max_length", "5", &conn).unwrap();
+
+    let mut tx_data = TxData::custom("", "Groceries", "test1", "", "10", "Expense", "", 0);
+    let status = tx_data.check_details(&conn);
+    let errors = tx_data.check_all_fields(&NameCache::new(), &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(
+        status,
+        VerifyingOutput::NotAccepted(NAType::DetailsTooLong(5))
+    );
+    assert_eq!(tx_data.get_all_texts()[1], "Groce");
+    assert!(!errors.get_fields().contains(&TxTab::Details));
+
+    let shown = get_shown_details(&format!("Rent\n{}", "b".repeat(600)));
+    assert_eq!(shown.chars().count(), 500);
+    assert!(shown.starts_with("Rent b"));
+    assert!(shown.ends_with('…'));
+}
+
+#[test]
+fn check_pasted_text() {
+    assert_eq!(get_single_line("Rent\r\nMay\t2023\n"), "Rent May 2023");
+    assert_eq!(
+        get_single_line("  Lunch \n\n  with team  "),
+        "  Lunch with team"
+    );
+    assert_eq!(get_single_line("\n \n"), "");
+
+    let mut tx_data = TxData::custom("", "Bus", "test1", "", "10", "Expense", "", 0);
+
+    // pasted at the index point of the field
+    tx_data.go_current_index(&TxTab::Details);
+    tx_data.paste_text(&TxTab::Details, " ticket\nto\nwork\n");
+    assert_eq!(tx_data.get_all_texts()[1], "Bus ticket to work");
+    assert_eq!(tx_data.get_current_index(), 18);
+
+    tx_data.go_current_index(&TxTab::Amount);
+    tx_data.move_index_left(&TxTab::Amount);
+    tx_data.move_index_left(&TxTab::Amount);
+    tx_data.paste_text(&TxTab::Amount, "1,25");
+    assert_eq!(tx_data.get_all_texts()[4], "1,2510");
+    assert_eq!(tx_data.get_current_index(), 4);
+
+    // an index past the end of a shorter field pastes at its end
+    tx_data.paste_text(&TxTab::Date, "2023-05-01");
+    assert_eq!(tx_data.get_all_texts()[0], "2023-05-01");
+
+    tx_data.paste_text(&TxTab::Nothing, "ignored");
+    assert!(!tx_data.get_all_texts().contains(&"ignored"));
+
+    let file_name = "check_pasted_text.sqlite";
+    let conn = create_test_db(file_name);
+
+    let mut tx_data = TxData::new();
+    tx_data.paste_text(&TxTab::Amount, "1,234.50\n");
+    let status = tx_data.check_amount(false, &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert_eq!(status, VerifyingOutput::Accepted(AType::Amount));
+    assert_eq!(tx_data.get_all_texts()[4], "1234.50");
+}
+
+#[test]
+fn check_verifier_tag_limits() {
+    let verifier = Testing {
+        data: Vec::new(),
+        expected: Vec::new(),
+        result: Vec::new(),
+    };
+
+    let many_tags = (1..=12)
+        .map(|i| format!("tag{i}"))
+        .collect::<Vec<String>>()
+        .join(",");
+    let mut to_verify = many_tags;
+    let many_status = verifier.verify_tags(&mut to_verify, &[]);
+    assert_eq!(
+        many_status,
+        VerifyingOutput::NotAccepted(NAType::TooManyTags(10))
+    );
+    assert_eq!(
+        to_verify,
+        "tag1, tag2, tag3, tag4, tag5, tag6, tag7, tag8, tag9, tag10"
+    );
+
+    let mut to_verify = format!("Food, {}", "a".repeat(45));
+    let long_status = verifier.verify_tags(&mut to_verify, &[]);
+    assert_eq!(
+        long_status,
+        VerifyingOutput::NotAccepted(NAType::TagTooLong(40))
+    );
+    assert_eq!(to_verify, format!("Food, {}", "a".repeat(40)));
+
+    let limits = TagLimits {
+        max_tags: 2,
+        max_tag_length: 3,
+    };
+    let mut to_verify = "Food, Car, Rent".to_string();
+    let custom_status = verifier.verify_tags_limits(&mut to_verify, &[], &limits);
+    assert_eq!(
+        custom_status,
+        VerifyingOutput::NotAccepted(NAType::TooManyTags(2))
+    );
+    assert_eq!(to_verify, "Foo, Car");
+
+    let file_name = "check_verifier_tag_limits.sqlite";
+    let mut conn = create_test_db(file_name);
+
+    // a tx exactly at the limits is saved without any change
+    let tags_at_limit = (1..=10)
+        .map(|i| format!("{i:0>40}"))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let mut tx_data = TxData::custom(
+        "19-07-2022",
+        "Testing transaction",
+        "test1",
+        "",
+        "10",
+        "Expense",
+        &tags_at_limit,
+        0,
+    );
+    let errors = tx_data.check_all_fields(&NameCache::new(), &conn);
+    let data = tx_data.get_all_texts();
+    add_tx(
+        "2022-07-19",
+        data[1],
+        data[2],
+        data[4],
+        data[5],
+        data[6],
+        None,
+        &mut conn,
+    )
+    .unwrap();
+
+    let (saved_txs, ..) = get_all_txs(&conn, 6, 0);
+
+    set_setting("max_tags", "3", &conn).unwrap();
+    let mut forced_tags = tags_at_limit
+        .split(", ")
+        .take(4)
+        .collect::<Vec<&str>>()
+        .join(", ");
+    let forced_status = verifier.verify_tags_forced(&mut forced_tags, &NameCache::new(), &conn);
+
+    let mut edited_tx = TxData::custom(
+        "19-07-2022",
+        "Testing transaction",
+        "test1",
+        "",
+        "10",
+        "Expense",
+        &tags_at_limit,
+        1,
+    );
+    let edited_errors = edited_tx.check_all_fields(&NameCache::new(), &conn);
+
+    conn.close().unwrap();
+    fs::remove_file(file_name).unwrap();
+
+    assert!(errors.is_empty());
+    assert_eq!(saved_txs[0][5], tags_at_limit);
+
+    assert_eq!(
+        forced_status,
+        VerifyingOutput::NotAccepted(NAType::TooManyTags(3))
+    );
+    assert_eq!(forced_tags.split(", ").count(), 3);
+
+    assert!(edited_errors.get_fields().contains(&TxTab::Tags));
+    assert_eq!(edited_tx.get_all_texts()[6].split(", ").count(), 3);
+}
+
+#[test]
+fn check_amount_preview() {
+    assert_eq!(
+        evaluate_amount_expression("1450/3+20"),
+        Ok(1450.0 / 3.0 + 20.0)
+    );
+    assert_eq!(evaluate_amount_expression("(20+5)*3"), Ok(75.0));
+    assert_eq!(
+        evaluate_amount_expression("+"),
+        Err(NAType::ParsingError(AType::Amount))
+    );
+
+    // evaluating does not collapse the typed text
+    let typed = "1450/3+20".to_string();
+    let _ = evaluate_amount_expression(&typed);
+    assert_eq!(typed, "1450/3+20");
+
+    let dot_format = AmountFormat::default();
+    let comma_format = AmountFormat::new(',', "kr");
+
+    let test_data = vec![
+        ("1450/3+20", 2, &dot_format, Some("= 503.33")),
+        ("1450/3+20", 0, &dot_format, Some("= 503")),
+        ("1450/", 2, &dot_format, Some("…")),
+        ("1450/3+", 2, &dot_format, Some("…")),
+        ("(20+5", 2, &dot_format, Some("…")),
+        ("1.5k*2", 2, &dot_format, Some("= 3,000.00")),
+        ("200-25%", 2, &dot_format, Some("= 150.00")),
+        ("1000,5+1", 2, &comma_format, Some("= 1.001,50")),
+        ("1450", 2, &dot_format, None),
+        ("", 2, &dot_format, None),
+    ];
+
+    for (amount, minor_units, amount_format, expected) in test_data {
+        assert_eq!(
+            get_amount_preview(amount, minor_units, amount_format).as_deref(),
+            expected
+        );
+    }
+}
+
+#[test]
+fn check_amount_rounding_modes() {
+    let verifier = Testing {
+        data: Vec::new(),
+        expected: Vec::new(),
+        result: Vec::new(),
+    };
+
+    // (typed, half up, half even, truncate) with 2 minor units
+    let test_data = vec![
+        ("1.005", "1.01", "1.00", "1.00"),
+        ("1.015", "1.02", "1.02", "1.01"),
+        ("1.0049", "1.00", "1.00", "1.00"),
+        ("9.999", "10.00", "10.00", "9.99"),
+        ("9.995", "10.00", "10.00", "9.99"),
+        ("1.23456", "1.23", "1.23", "1.23"),
+        ("0.5+0.505", "1.01", "1.00", "1.00"),
+        ("2.01/2", "1.01", "1.00", "1.00"),
+    ];
+
+    for (typed, half_up, half_even, truncate) in test_data {
+        for (mode, expected) in [
+            (RoundingMode::HalfUp, half_up),
+            (RoundingMode::HalfEven, half_even),
+            (RoundingMode::Truncate, truncate),
+        ] {
+            let amount_format = AmountFormat::default().with_rounding(mode);
+            let mut to_verify = typed.to_string();
+            let status = verifier.verify_amount_units(&mut to_verify, 2, &amount_format);
+
+            assert_eq!(
+                status,
+                VerifyingOutput::Accepted(AType::RoundedAmount(mode))
+            );
+            assert_eq!(to_verify, expected, "{typed} {mode}");
+            assert_eq!(
+                get_amount_preview(&format!("{typed}*1"), 2, &amount_format).as_deref(),
+                Some(format!("= {expected}").as_str())
+            );
+        }
+    }
+
+    // any decimal after a half decides half even
+    let amount_format = AmountFormat::default().with_rounding(RoundingMode::HalfEven);
+    let mut to_verify = "1.0050001".to_string();
+    verifier.verify_amount_units(&mut to_verify, 2, &amount_format);
+    assert_eq!(to_verify, "1.01");
+
+    // the 4th decimal is rounded with 3 minor units even though Money keeps only 3
+    let amount_format = AmountFormat::default().with_rounding(RoundingMode::HalfEven);
+    let mut to_verify = "1.0025".to_string();
+    verifier.verify_amount_units(&mut to_verify, 3, &amount_format);
+    assert_eq!(to_verify, "1.002");
+
+    let mut to_verify = "1.50".to_string();
+    assert_eq!(
+        verifier.verify_amount(&mut to_verify),
+        VerifyingOutput::Accepted(AType::Amount)
+    );
+    assert_eq!(
+        VerifyingOutput::Accepted(AType::RoundedAmount(RoundingMode::HalfEven)).to_string(),
+        "Amount: Accepted. Extra decimals were rounded half to even"
+    );
+
+    let amount = Money::from_minor(1005);
+    assert_eq!(
+        amount.round(2, RoundingMode::HalfUp),
+        Money::from_minor(1010)
+    );
+    assert_eq!(
+        amount.round(2, RoundingMode::HalfEven),
+        Money::from_minor(1000)
+    );
+    assert_eq!(
+        amount.round(2, RoundingMode::Truncate),
+        Money::from_minor(1000)
+    );
+    assert_eq!(
+        Money::from_minor(-1005).round(2, RoundingMode::HalfUp),
+        Money::from_minor(-1010)
+    );
+    assert_eq!(
+        RoundingMode::from_setting("half_even"),
+        RoundingMode::HalfEven
+    );
+    assert_eq!(RoundingMode::from_setting("unknown"), RoundingMode::HalfUp);
+}